@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+fn main() {
+    if std::env::var_os("CARGO_FEATURE_BUNDLED_PDFIUM").is_some() {
+        let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+        bundle_pdfium(&target_os);
+    }
+}
+
+/// Copies the Pdfium library into `OUT_DIR` so the `bundled-pdfium` feature can embed it.
+fn bundle_pdfium(target_os: &str) {
+    println!("cargo:rerun-if-env-changed=KPDF_BUNDLED_PDFIUM_DIR");
+    let lib_name = match target_os {
+        "macos" => "libpdfium.dylib",
+        "windows" => "pdfium.dll",
+        _ => "libpdfium.so",
+    };
+    let source_dir = std::env::var_os("KPDF_BUNDLED_PDFIUM_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../lib"));
+    let source = source_dir.join(lib_name);
+    let version_source = source_dir.join("pdfium.version");
+    println!("cargo:rerun-if-changed={}", source.display());
+    println!("cargo:rerun-if-changed={}", version_source.display());
+
+    let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").expect("OUT_DIR is set by cargo"));
+    std::fs::copy(&source, out_dir.join("bundled_pdfium")).unwrap_or_else(|err| {
+        panic!(
+            "bundled-pdfium: cannot read {} ({err}); run scripts/fetch_pdfium_lib first or set KPDF_BUNDLED_PDFIUM_DIR",
+            source.display()
+        )
+    });
+    let version = std::fs::read_to_string(&version_source).unwrap_or_default();
+    std::fs::write(out_dir.join("bundled_pdfium.version"), version)
+        .expect("failed to write bundled Pdfium version");
+}
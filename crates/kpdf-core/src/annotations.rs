@@ -0,0 +1,284 @@
+//! Notes, text markups, stamps and shapes attached to documents, and how they are stored.
+//!
+//! Annotations live outside the PDF, keyed by document path, with positions given as
+//! ratios of the page size in page space (origin bottom-left). Each kind is kept in its own
+//! sled tree as JSON values under big-endian `u32` keys that preserve the list order.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Stroke width of new shapes, in points.
+pub const DEFAULT_SHAPE_STROKE_WIDTH_PT: f32 = 2.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkdownNoteEntry {
+    pub id: u64,
+    pub path: PathBuf,
+    pub page_index: usize,
+    pub x_ratio: f32,
+    pub y_ratio: f32,
+    pub markdown: String,
+    pub created_at_unix_secs: u64,
+    pub updated_at_unix_secs: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// 如果是从文本选区创建的，存储选中的文本和矩形区域
+    #[serde(default)]
+    pub selected_text: String,
+    #[serde(default)]
+    pub selection_rects: Vec<TextMarkupRect>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextMarkupKind {
+    Highlight,
+    Underline,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextMarkupColor {
+    #[default]
+    Yellow,
+    Green,
+    Blue,
+    Pink,
+}
+
+impl TextMarkupColor {
+    pub const ALL: [TextMarkupColor; 4] = [
+        TextMarkupColor::Yellow,
+        TextMarkupColor::Green,
+        TextMarkupColor::Blue,
+        TextMarkupColor::Pink,
+    ];
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextMarkupRect {
+    pub left_ratio: f32,
+    pub top_ratio: f32,
+    pub right_ratio: f32,
+    pub bottom_ratio: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextMarkupEntry {
+    pub id: u64,
+    pub path: PathBuf,
+    pub page_index: usize,
+    pub kind: TextMarkupKind,
+    #[serde(default)]
+    pub color: TextMarkupColor,
+    pub selected_text: String,
+    pub rects: Vec<TextMarkupRect>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub created_at_unix_secs: u64,
+    pub updated_at_unix_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StampPreset {
+    Approved,
+    Draft,
+    Confidential,
+    Rejected,
+}
+
+impl StampPreset {
+    pub const ALL: [StampPreset; 4] = [
+        StampPreset::Approved,
+        StampPreset::Draft,
+        StampPreset::Confidential,
+        StampPreset::Rejected,
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StampContent {
+    Preset(StampPreset),
+    Text(String),
+    Image(PathBuf),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StampEntry {
+    pub id: u64,
+    pub path: PathBuf,
+    pub page_index: usize,
+    pub content: StampContent,
+    /// Center of the stamp in page space.
+    pub x_ratio: f32,
+    pub y_ratio: f32,
+    pub width_ratio: f32,
+    pub height_ratio: f32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub created_at_unix_secs: u64,
+    pub updated_at_unix_secs: u64,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShapeKind {
+    Rectangle,
+    Ellipse,
+    Line,
+    Arrow,
+    Ink,
+}
+
+impl ShapeKind {
+    pub const ALL: [ShapeKind; 5] = [
+        ShapeKind::Rectangle,
+        ShapeKind::Ellipse,
+        ShapeKind::Line,
+        ShapeKind::Arrow,
+        ShapeKind::Ink,
+    ];
+
+    pub fn is_closed(self) -> bool {
+        matches!(self, ShapeKind::Rectangle | ShapeKind::Ellipse)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShapeColor {
+    #[default]
+    Red,
+    Blue,
+    Green,
+    Black,
+}
+
+impl ShapeColor {
+    pub const ALL: [ShapeColor; 4] = [
+        ShapeColor::Red,
+        ShapeColor::Blue,
+        ShapeColor::Green,
+        ShapeColor::Black,
+    ];
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ShapeStyle {
+    pub stroke: ShapeColor,
+    pub stroke_width_pt: f32,
+    /// Closed shapes are filled with a translucent tint of the stroke color.
+    pub filled: bool,
+}
+
+impl Default for ShapeStyle {
+    fn default() -> Self {
+        Self {
+            stroke: ShapeColor::default(),
+            stroke_width_pt: DEFAULT_SHAPE_STROKE_WIDTH_PT,
+            filled: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShapeEntry {
+    pub id: u64,
+    pub path: PathBuf,
+    pub page_index: usize,
+    pub kind: ShapeKind,
+    /// Endpoints in page space. Rectangles and ellipses use them as opposite corners of their
+    /// bounding box, ink as its bounds.
+    pub start_x_ratio: f32,
+    pub start_y_ratio: f32,
+    pub end_x_ratio: f32,
+    pub end_y_ratio: f32,
+    /// Freehand samples in the same page space; only ink strokes have any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub points: Vec<[f32; 2]>,
+    pub style: ShapeStyle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    pub created_at_unix_secs: u64,
+    pub updated_at_unix_secs: u64,
+}
+
+/// Reads every entry of `store` in list order. Entries that no longer parse are skipped.
+pub fn load_entries<T: DeserializeOwned>(store: &sled::Tree) -> Vec<T> {
+    let mut indexed_entries = store
+        .iter()
+        .filter_map(Result::ok)
+        .filter_map(|(key, value)| {
+            let index = u32::from_be_bytes(key.as_ref().try_into().ok()?);
+            let entry = serde_json::from_slice::<T>(&value).ok()?;
+            Some((index, entry))
+        })
+        .collect::<Vec<_>>();
+    indexed_entries.sort_by_key(|(index, _)| *index);
+    indexed_entries
+        .into_iter()
+        .map(|(_, entry)| entry)
+        .collect()
+}
+
+/// Replaces the contents of `store` with `entries`, in order.
+pub fn store_entries<T: Serialize>(store: &sled::Tree, entries: &[T]) {
+    if store.clear().is_err() {
+        return;
+    }
+
+    for (index, entry) in entries.iter().enumerate() {
+        let key = (index as u32).to_be_bytes();
+        let Ok(value) = serde_json::to_vec(entry) else {
+            continue;
+        };
+        if store.insert(key, value).is_err() {
+            return;
+        }
+    }
+
+    let _ = store.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_round_trip_in_order() {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        let store = db.open_tree("shapes").unwrap();
+        let shape = |id| ShapeEntry {
+            id,
+            path: PathBuf::from("a.pdf"),
+            page_index: 0,
+            kind: ShapeKind::Line,
+            start_x_ratio: 0.1,
+            start_y_ratio: 0.2,
+            end_x_ratio: 0.3,
+            end_y_ratio: 0.4,
+            points: Vec::new(),
+            style: ShapeStyle::default(),
+            author: None,
+            created_at_unix_secs: 0,
+            updated_at_unix_secs: 0,
+        };
+
+        store_entries(&store, &(0..300).map(shape).collect::<Vec<_>>());
+        store.insert(b"bad", b"not json".as_slice()).unwrap();
+        let loaded = load_entries::<ShapeEntry>(&store);
+        assert_eq!(loaded.len(), 300);
+        assert!(
+            loaded
+                .iter()
+                .enumerate()
+                .all(|(ix, shape)| shape.id == ix as u64)
+        );
+
+        store_entries(&store, &[shape(7)]);
+        assert_eq!(load_entries::<ShapeEntry>(&store), vec![shape(7)]);
+    }
+}
@@ -0,0 +1,261 @@
+use anyhow::{Result, anyhow, bail};
+use flate2::read::DeflateDecoder;
+use std::io::Read;
+
+const END_OF_CENTRAL_DIRECTORY: &[u8] = b"PK\x05\x06";
+const CENTRAL_DIRECTORY_HEADER: &[u8] = b"PK\x01\x02";
+const LOCAL_FILE_HEADER: &[u8] = b"PK\x03\x04";
+const END_OF_CENTRAL_DIRECTORY_LEN: usize = 22;
+const MAX_ARCHIVE_COMMENT_LEN: usize = u16::MAX as usize;
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATED: u16 = 8;
+/// Sizes in the directory are untrusted, so output buffers start at most this large and
+/// grow as data is actually inflated.
+const MAX_PREALLOCATED_BYTES: usize = 4 * 1024 * 1024;
+
+/// A file in a ZIP archive, as listed by its central directory.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZipEntry {
+    pub name: String,
+    method: u16,
+    compressed_size: usize,
+    uncompressed_size: usize,
+    local_header_offset: usize,
+}
+
+impl ZipEntry {
+    pub fn is_dir(&self) -> bool {
+        self.name.ends_with('/')
+    }
+}
+
+/// Whether `bytes` start like a ZIP archive (comic `.cbr` files are often ZIPs renamed).
+pub fn is_zip(bytes: &[u8]) -> bool {
+    bytes.starts_with(LOCAL_FILE_HEADER) || bytes.starts_with(END_OF_CENTRAL_DIRECTORY)
+}
+
+/// Lists the entries of a ZIP archive in central directory order.
+///
+/// Only what comic archives use is supported: stored and deflated entries without
+/// encryption or ZIP64 extensions.
+pub fn zip_entries(bytes: &[u8]) -> Result<Vec<ZipEntry>> {
+    let search_start = bytes
+        .len()
+        .saturating_sub(END_OF_CENTRAL_DIRECTORY_LEN + MAX_ARCHIVE_COMMENT_LEN);
+    let end = bytes[search_start..]
+        .windows(END_OF_CENTRAL_DIRECTORY.len())
+        .rposition(|window| window == END_OF_CENTRAL_DIRECTORY)
+        .map(|position| search_start + position)
+        .ok_or_else(|| anyhow!("not a ZIP archive: end of central directory not found"))?;
+    let record = bytes
+        .get(end..end + END_OF_CENTRAL_DIRECTORY_LEN)
+        .ok_or_else(|| anyhow!("truncated end of central directory"))?;
+    let entry_count = u16_at(record, 10);
+    let directory_offset = u32_at(record, 16);
+    if entry_count == u16::MAX || directory_offset == u32::MAX {
+        bail!("ZIP64 archives are not supported");
+    }
+
+    let mut entries = Vec::with_capacity(usize::from(entry_count));
+    let mut offset = directory_offset as usize;
+    for _ in 0..entry_count {
+        let header = bytes
+            .get(offset..offset + 46)
+            .filter(|header| header.starts_with(CENTRAL_DIRECTORY_HEADER))
+            .ok_or_else(|| anyhow!("corrupt central directory at offset {offset}"))?;
+        let flags = u16_at(header, 8);
+        let name_len = usize::from(u16_at(header, 28));
+        let extra_len = usize::from(u16_at(header, 30));
+        let comment_len = usize::from(u16_at(header, 32));
+        let name = bytes
+            .get(offset + 46..offset + 46 + name_len)
+            .ok_or_else(|| anyhow!("truncated entry name at offset {offset}"))?;
+        if flags & 1 != 0 {
+            bail!(
+                "encrypted ZIP entries are not supported: {}",
+                String::from_utf8_lossy(name)
+            );
+        }
+        entries.push(ZipEntry {
+            name: String::from_utf8_lossy(name).into_owned(),
+            method: u16_at(header, 10),
+            compressed_size: u32_at(header, 20) as usize,
+            uncompressed_size: u32_at(header, 24) as usize,
+            local_header_offset: u32_at(header, 42) as usize,
+        });
+        offset += 46 + name_len + extra_len + comment_len;
+    }
+    Ok(entries)
+}
+
+/// Reads and inflates one entry; output is capped at the size the directory declares.
+pub fn read_zip_entry(bytes: &[u8], entry: &ZipEntry) -> Result<Vec<u8>> {
+    let offset = entry.local_header_offset;
+    let header = bytes
+        .get(offset..offset + 30)
+        .filter(|header| header.starts_with(LOCAL_FILE_HEADER))
+        .ok_or_else(|| anyhow!("corrupt local header for {}", entry.name))?;
+    let data_start =
+        offset + 30 + usize::from(u16_at(header, 26)) + usize::from(u16_at(header, 28));
+    let data = bytes
+        .get(data_start..data_start + entry.compressed_size)
+        .ok_or_else(|| anyhow!("truncated data for {}", entry.name))?;
+
+    match entry.method {
+        METHOD_STORED => Ok(data.to_vec()),
+        METHOD_DEFLATED => {
+            let mut content =
+                Vec::with_capacity(entry.uncompressed_size.min(MAX_PREALLOCATED_BYTES));
+            DeflateDecoder::new(data)
+                .take(entry.uncompressed_size as u64)
+                .read_to_end(&mut content)?;
+            Ok(content)
+        }
+        method => bail!("unsupported compression method {method} for {}", entry.name),
+    }
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::DeflateEncoder;
+    use std::io::Write;
+
+    /// A minimal archive with the given `(name, method, content)` entries.
+    fn build_zip(files: &[(&str, u16, &[u8])]) -> Vec<u8> {
+        let mut archive = Vec::new();
+        let mut directory = Vec::new();
+        for (name, method, content) in files {
+            let data = if *method == METHOD_DEFLATED {
+                let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(content).unwrap();
+                encoder.finish().unwrap()
+            } else {
+                content.to_vec()
+            };
+            let local_offset = archive.len() as u32;
+            archive.extend_from_slice(LOCAL_FILE_HEADER);
+            archive.extend_from_slice(&[20, 0, 0, 0]);
+            archive.extend_from_slice(&method.to_le_bytes());
+            archive.extend_from_slice(&[0; 8]);
+            archive.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            archive.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            archive.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            archive.extend_from_slice(&[0, 0]);
+            archive.extend_from_slice(name.as_bytes());
+            archive.extend_from_slice(&data);
+
+            directory.extend_from_slice(CENTRAL_DIRECTORY_HEADER);
+            directory.extend_from_slice(&[20, 0, 20, 0, 0, 0]);
+            directory.extend_from_slice(&method.to_le_bytes());
+            directory.extend_from_slice(&[0; 8]);
+            directory.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            directory.extend_from_slice(&(content.len() as u32).to_le_bytes());
+            directory.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            directory.extend_from_slice(&[0; 12]);
+            directory.extend_from_slice(&local_offset.to_le_bytes());
+            directory.extend_from_slice(name.as_bytes());
+        }
+        let directory_offset = archive.len() as u32;
+        archive.extend_from_slice(&directory);
+        archive.extend_from_slice(END_OF_CENTRAL_DIRECTORY);
+        archive.extend_from_slice(&[0; 4]);
+        archive.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        archive.extend_from_slice(&(files.len() as u16).to_le_bytes());
+        archive.extend_from_slice(&(directory.len() as u32).to_le_bytes());
+        archive.extend_from_slice(&directory_offset.to_le_bytes());
+        archive.extend_from_slice(&[0, 0]);
+        archive
+    }
+
+    #[test]
+    fn reads_stored_and_deflated_entries() {
+        let text = b"page two page two page two page two";
+        let archive = build_zip(&[
+            ("pages/", METHOD_STORED, b""),
+            ("pages/01.jpg", METHOD_STORED, b"first"),
+            ("pages/02.jpg", METHOD_DEFLATED, text),
+        ]);
+        assert!(is_zip(&archive));
+
+        let entries = zip_entries(&archive).unwrap();
+        let names = entries
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["pages/", "pages/01.jpg", "pages/02.jpg"]);
+        assert!(entries[0].is_dir());
+        assert_eq!(read_zip_entry(&archive, &entries[1]).unwrap(), b"first");
+        assert_eq!(read_zip_entry(&archive, &entries[2]).unwrap(), text);
+    }
+
+    #[test]
+    fn declared_sizes_do_not_decide_allocation_or_bounds() {
+        let text = b"page two page two page two page two";
+        let archive = build_zip(&[("02.jpg", METHOD_DEFLATED, text)]);
+        let entry = zip_entries(&archive).unwrap().remove(0);
+
+        let oversized = ZipEntry {
+            uncompressed_size: u32::MAX as usize,
+            ..entry.clone()
+        };
+        assert_eq!(read_zip_entry(&archive, &oversized).unwrap(), text);
+        let undersized = ZipEntry {
+            uncompressed_size: 4,
+            ..entry.clone()
+        };
+        assert_eq!(read_zip_entry(&archive, &undersized).unwrap(), b"page");
+
+        let past_end = ZipEntry {
+            compressed_size: archive.len(),
+            ..entry.clone()
+        };
+        assert!(read_zip_entry(&archive, &past_end).is_err());
+        let bad_offset = ZipEntry {
+            local_header_offset: archive.len() - 10,
+            ..entry
+        };
+        assert!(read_zip_entry(&archive, &bad_offset).is_err());
+    }
+
+    #[test]
+    fn rejects_corrupt_central_directories() {
+        let archive = build_zip(&[("01.jpg", METHOD_STORED, b"first")]);
+        let record = archive.len() - END_OF_CENTRAL_DIRECTORY_LEN;
+
+        let mut past_end = archive.clone();
+        past_end[record + 16..record + 20].copy_from_slice(&(archive.len() as u32).to_le_bytes());
+        assert!(zip_entries(&past_end).is_err());
+
+        let mut too_many = archive.clone();
+        too_many[record + 10..record + 12].copy_from_slice(&2u16.to_le_bytes());
+        assert!(zip_entries(&too_many).is_err());
+
+        let mut long_name = archive.clone();
+        let directory = u32_at(&archive[record..], 16) as usize;
+        long_name[directory + 28..directory + 30].copy_from_slice(&u16::MAX.to_le_bytes());
+        assert!(zip_entries(&long_name).is_err());
+
+        assert!(zip_entries(&archive[..archive.len() - 4]).is_err());
+    }
+
+    #[test]
+    fn rejects_data_without_central_directory() {
+        assert!(!is_zip(b"Rar!\x1a\x07\x00"));
+        assert!(zip_entries(b"Rar!\x1a\x07\x00").is_err());
+    }
+}
@@ -0,0 +1,549 @@
+//! Reading and rasterizing PDF documents through Pdfium.
+//!
+//! The most recently used document stays open between calls, so a viewer asking for page
+//! after page of the same file pays for parsing it once. Every function takes the path of a
+//! PDF on disk; converting other formats to PDF is left to the caller.
+
+use crate::pdfium::{pdfium_access_guard, shared_pdfium};
+use crate::render::{RenderOptions, clamp_render_width};
+use anyhow::{Context as _, Result, anyhow};
+use pdfium_render::prelude::*;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::SystemTime;
+
+const OUTLINE_MAX_DEPTH: usize = 32;
+const OUTLINE_MAX_ENTRIES: usize = 10_000;
+const TEXT_SEARCH_MAX_HITS: usize = 1_000;
+const TEXT_SEARCH_SNIPPET_BEFORE: usize = 24;
+const TEXT_SEARCH_SNIPPET_AFTER: usize = 48;
+
+static DOCUMENT_CACHE: OnceLock<Mutex<Option<CachedDocument>>> = OnceLock::new();
+
+/// Size and label of one page.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PageInfo {
+    pub index: usize,
+    pub width_pt: f32,
+    pub height_pt: f32,
+    /// Page label from the document (e.g. "iv"), when it differs from the page number.
+    pub label: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct OutlineEntry {
+    pub title: String,
+    pub page_index: Option<usize>,
+    pub depth: usize,
+}
+
+/// One character of a page's text layer, in page space (origin bottom-left).
+#[derive(Clone, Debug)]
+pub struct TextChar {
+    pub text: String,
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+/// The text layer of one page, in reading order.
+#[derive(Clone, Debug)]
+pub struct PageText {
+    pub page_index: usize,
+    pub width_pt: f32,
+    pub height_pt: f32,
+    pub chars: Vec<TextChar>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TextSearchHit {
+    pub page_index: usize,
+    /// Index of the first matched char in [`PageText::chars`].
+    pub char_start: usize,
+    pub char_len: usize,
+    pub snippet: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct PageLink {
+    pub page_index: usize,
+    /// Link area as `[left, bottom, right, top]` ratios of the page size, origin bottom-left.
+    pub rect: [f32; 4],
+    pub target: PageLinkTarget,
+}
+
+#[derive(Clone, Debug)]
+pub enum PageLinkTarget {
+    Page(usize),
+    Uri(String),
+}
+
+/// Pdfium could not open the file at `path`; the [`PdfiumError`] says why.
+#[derive(Debug)]
+pub struct OpenError {
+    pub path: PathBuf,
+    pub source: PdfiumError,
+}
+
+impl fmt::Display for OpenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot open {}", self.path.display())
+    }
+}
+
+impl std::error::Error for OpenError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CachedDocumentKey {
+    canonical_path: PathBuf,
+    file_len: Option<u64>,
+    modified: Option<SystemTime>,
+}
+
+struct CachedDocument {
+    key: CachedDocumentKey,
+    document: PdfDocument<'static>,
+}
+
+impl CachedDocumentKey {
+    fn for_path(path: &Path) -> Self {
+        let canonical_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let metadata = std::fs::metadata(&canonical_path).ok();
+
+        Self {
+            canonical_path,
+            file_len: metadata.as_ref().map(|meta| meta.len()),
+            modified: metadata.and_then(|meta| meta.modified().ok()),
+        }
+    }
+}
+
+/// Runs `f` on the open document for `path` while holding the Pdfium lock, opening the file
+/// first unless it is the one already cached. A changed file on disk is opened again.
+pub fn with_document<T>(
+    path: &Path,
+    f: impl FnOnce(&PdfDocument<'static>) -> Result<T>,
+) -> Result<T> {
+    let _access_guard = pdfium_access_guard()?;
+    let mut cache = document_cache()?;
+    let key = CachedDocumentKey::for_path(path);
+    if cache.as_ref().is_none_or(|cached| cached.key != key) {
+        let document = shared_pdfium()?
+            .load_pdf_from_file(&key.canonical_path, None)
+            .map_err(|source| OpenError {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        *cache = Some(CachedDocument { key, document });
+    }
+
+    let document = cache
+        .as_ref()
+        .map(|cached| &cached.document)
+        .ok_or_else(|| anyhow!("Pdfium document cache should be initialized"))?;
+    f(document)
+}
+
+/// Opens `path` outside the document cache, for callers that change the document. The caller
+/// must hold the Pdfium lock for as long as the document is alive.
+pub(crate) fn open_uncached(path: &Path) -> Result<PdfDocument<'static>> {
+    let document = shared_pdfium()?
+        .load_pdf_from_file(path, None)
+        .map_err(|source| OpenError {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    Ok(document)
+}
+
+fn document_cache() -> Result<MutexGuard<'static, Option<CachedDocument>>> {
+    DOCUMENT_CACHE
+        .get_or_init(|| Mutex::new(None))
+        .lock()
+        .map_err(|_| anyhow!("Pdfium document cache lock is poisoned"))
+}
+
+/// Whether a load failed because the file is malformed, as opposed to missing or locked.
+pub fn is_damaged_document_error(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<PdfiumError>(),
+            Some(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::FormatError
+            ))
+        )
+    })
+}
+
+/// Reads up to `max_pages` pages starting at `start` and returns them with the document's
+/// total page count. `on_progress` receives the number of pages read from the start of the
+/// document and the total after each page.
+pub fn page_infos(
+    path: &Path,
+    start: usize,
+    max_pages: usize,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<(usize, Vec<PageInfo>)> {
+    with_document(path, |document| {
+        let total_pages = document.pages().len() as usize;
+        let end = start.saturating_add(max_pages).min(total_pages);
+        let mut pages = Vec::with_capacity(end.saturating_sub(start));
+
+        for ix in start..end {
+            let page = document.pages().get(ix as u16)?;
+            let label = page
+                .label()
+                .map(|label| label.trim().to_string())
+                .filter(|label| !label.is_empty() && *label != (ix + 1).to_string());
+            pages.push(PageInfo {
+                index: ix,
+                width_pt: page.width().value,
+                height_pt: page.height().value,
+                label,
+            });
+            on_progress(ix + 1, total_pages);
+        }
+        Ok((total_pages, pages))
+    })
+}
+
+/// Renders one page as BGRA pixels at (at most) `target_width`.
+pub fn render_page(
+    path: &Path,
+    page_index: usize,
+    target_width: u32,
+    options: RenderOptions,
+) -> Result<(u32, u32, Vec<u8>)> {
+    with_document(path, |document| {
+        let total_pages = document.pages().len() as usize;
+        if page_index >= total_pages || page_index > u16::MAX as usize {
+            return Err(anyhow!("page out of range (total_pages={})", total_pages));
+        }
+
+        let page = document
+            .pages()
+            .get(page_index as u16)
+            .context("get_page error")?;
+        // Posters and maps can ask for bitmaps far beyond what Pdfium can allocate.
+        let render_width =
+            clamp_render_width(page.width().value, page.height().value, target_width);
+        let render_config =
+            options.apply(PdfRenderConfig::new().set_target_width(render_width as i32));
+        let bitmap = page
+            .render_with_config(&render_config)
+            .context("render error")?;
+        let (width, height, mut bytes) = bitmap_to_bgra_bytes(&bitmap)?;
+        options.post_process(&mut bytes);
+        Ok((width, height, bytes))
+    })
+}
+
+/// Renders horizontal bands of one page at `target_width` so huge pages never need a full
+/// bitmap. Each band is `tile_height` device pixels tall (the last one may be shorter).
+/// `on_tile` receives each band's row and BGRA pixels, or the error that band hit, and
+/// returns whether to keep going.
+pub fn render_page_tiles(
+    path: &Path,
+    page_index: usize,
+    target_width: u32,
+    tile_rows: &[u32],
+    tile_height: u32,
+    options: RenderOptions,
+    mut on_tile: impl FnMut(u32, Result<(u32, u32, Vec<u8>)>) -> bool,
+) -> Result<()> {
+    if tile_rows.is_empty() || target_width == 0 || tile_height == 0 {
+        return Ok(());
+    }
+    with_document(path, |document| {
+        if page_index > u16::MAX as usize {
+            return Ok(());
+        }
+        let page = document.pages().get(page_index as u16)?;
+        let page_width_pt = page.width().value.max(1.0);
+        let scale = target_width as f32 / page_width_pt;
+        let page_height_px = (page.height().value * scale).round().max(1.0) as u32;
+
+        for &row in tile_rows {
+            let top = row.saturating_mul(tile_height);
+            if top >= page_height_px {
+                continue;
+            }
+            let height = tile_height.min(page_height_px - top);
+
+            // Shift the page up by the band offset (in points, before scaling) and clip to
+            // the band.
+            let render_config = options
+                .apply(PdfRenderConfig::new())
+                .set_target_width(target_width as i32)
+                .translate(PdfPoints::ZERO, PdfPoints::new(-(top as f32) / scale))
+                .map(|config| config.clip(0, 0, target_width as i32, height as i32));
+            let rendered = render_config.and_then(|config| {
+                let mut bitmap = PdfBitmap::empty(
+                    target_width as i32,
+                    height as i32,
+                    PdfBitmapFormat::BGRA,
+                    page.bindings(),
+                )?;
+                page.render_into_bitmap_with_config(&mut bitmap, &config)?;
+                Ok(bitmap)
+            });
+            let tile = rendered
+                .map_err(anyhow::Error::from)
+                .and_then(|bitmap| bitmap_to_bgra_bytes(&bitmap))
+                .map(|(width, height, mut bytes)| {
+                    options.post_process(&mut bytes);
+                    (width, height, bytes)
+                });
+            if !on_tile(row, tile) {
+                break;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Copies a Pdfium bitmap out as opaque BGRA pixels; this must run under the Pdfium lock.
+#[allow(deprecated)]
+pub fn bitmap_to_bgra_bytes(bitmap: &PdfBitmap) -> Result<(u32, u32, Vec<u8>)> {
+    let width = bitmap.width() as u32;
+    let height = bitmap.height() as u32;
+    if width == 0 || height == 0 {
+        return Err(anyhow!("invalid bitmap size {width}x{height}"));
+    }
+
+    let format = bitmap.format().unwrap_or(PdfBitmapFormat::BGRA);
+    let mut bytes = match format {
+        PdfBitmapFormat::BGRA | PdfBitmapFormat::BGRx | PdfBitmapFormat::BRGx => {
+            bitmap.as_raw_bytes()
+        }
+        _ => rgba_to_bgra(bitmap.as_rgba_bytes()),
+    };
+
+    let expected_len = width as usize * height as usize * 4;
+    if bytes.len() != expected_len {
+        bytes = rgba_to_bgra(bitmap.as_rgba_bytes());
+        if bytes.len() != expected_len {
+            return Err(anyhow!(
+                "bitmap has {} bytes, expected {expected_len}",
+                bytes.len()
+            ));
+        }
+    }
+
+    if matches!(format, PdfBitmapFormat::BGRx | PdfBitmapFormat::BRGx) {
+        for pixel in bytes.chunks_exact_mut(4) {
+            pixel[3] = 255;
+        }
+    }
+
+    Ok((width, height, bytes))
+}
+
+fn rgba_to_bgra(mut rgba: Vec<u8>) -> Vec<u8> {
+    for pixel in rgba.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    rgba
+}
+
+/// The characters on one page, skipping those Pdfium reports no bounds for. `None` when the
+/// page does not exist.
+pub fn page_text(path: &Path, page_index: usize) -> Result<Option<PageText>> {
+    with_document(path, |document| {
+        let total_pages = document.pages().len() as usize;
+        if page_index >= total_pages {
+            return Ok(None);
+        }
+
+        let page = document.pages().get(page_index as u16)?;
+        let page_text = page.text()?;
+        let chars = page_text
+            .chars()
+            .iter()
+            .filter_map(|char| {
+                let bounds = char.tight_bounds().ok()?;
+                Some(TextChar {
+                    text: char
+                        .unicode_char()
+                        .map(|c| c.to_string())
+                        .unwrap_or_default(),
+                    left: bounds.left().value,
+                    top: bounds.top().value,
+                    right: bounds.right().value,
+                    bottom: bounds.bottom().value,
+                })
+            })
+            .collect();
+
+        Ok(Some(PageText {
+            page_index,
+            width_pt: page.width().value,
+            height_pt: page.height().value,
+            chars,
+        }))
+    })
+}
+
+/// The document outline flattened depth-first, capped at [`OUTLINE_MAX_ENTRIES`] entries
+/// and [`OUTLINE_MAX_DEPTH`] levels.
+pub fn outline(path: &Path) -> Result<Vec<OutlineEntry>> {
+    with_document(path, |document| {
+        let mut entries = Vec::new();
+        let mut stack = Vec::new();
+        if let Some(root) = document.bookmarks().root() {
+            stack.push((root, 0usize));
+        }
+
+        // Depth-first walk: push the next sibling first so the child is visited before it.
+        while let Some((bookmark, depth)) = stack.pop() {
+            if entries.len() >= OUTLINE_MAX_ENTRIES {
+                crate::debug_log!(
+                    "[pdf][outline] {} truncated at {} entries",
+                    path.display(),
+                    OUTLINE_MAX_ENTRIES
+                );
+                break;
+            }
+
+            if let Some(sibling) = bookmark.next_sibling() {
+                stack.push((sibling, depth));
+            }
+            if depth + 1 < OUTLINE_MAX_DEPTH
+                && let Some(child) = bookmark.first_child()
+            {
+                stack.push((child, depth + 1));
+            }
+
+            let title = bookmark
+                .title()
+                .map(|title| title.trim().to_string())
+                .unwrap_or_default();
+            let page_index = bookmark
+                .destination()
+                .and_then(|destination| destination.page_index().ok())
+                .map(|page_index| page_index as usize);
+            entries.push(OutlineEntry {
+                title,
+                page_index,
+                depth,
+            });
+        }
+        Ok(entries)
+    })
+}
+
+/// Reads the internal and web links on `page_indices`; links to other files or launch actions
+/// are skipped.
+pub fn page_links(path: &Path, page_indices: &[usize]) -> Result<Vec<PageLink>> {
+    with_document(path, |document| {
+        let mut links = Vec::new();
+        for &page_index in page_indices {
+            let Ok(page) = document.pages().get(page_index as u16) else {
+                continue;
+            };
+            let width_pt = page.width().value.max(1.0);
+            let height_pt = page.height().value.max(1.0);
+            for link in page.links().iter() {
+                let target = match link.action() {
+                    Some(PdfAction::Uri(action)) => action.uri().ok().map(PageLinkTarget::Uri),
+                    Some(PdfAction::LocalDestination(action)) => action
+                        .destination()
+                        .and_then(|destination| destination.page_index())
+                        .ok()
+                        .map(|index| PageLinkTarget::Page(index as usize)),
+                    Some(_) => None,
+                    None => link
+                        .destination()
+                        .and_then(|destination| destination.page_index().ok())
+                        .map(|index| PageLinkTarget::Page(index as usize)),
+                };
+                let (Some(target), Ok(rect)) = (target, link.rect()) else {
+                    continue;
+                };
+                links.push(PageLink {
+                    page_index,
+                    rect: [
+                        (rect.left().value / width_pt).clamp(0.0, 1.0),
+                        (rect.bottom().value / height_pt).clamp(0.0, 1.0),
+                        (rect.right().value / width_pt).clamp(0.0, 1.0),
+                        (rect.top().value / height_pt).clamp(0.0, 1.0),
+                    ],
+                    target,
+                });
+            }
+        }
+        Ok(links)
+    })
+}
+
+/// Case-insensitive search through every page's text, capped at [`TEXT_SEARCH_MAX_HITS`].
+pub fn search_text(path: &Path, query: &str) -> Result<Vec<TextSearchHit>> {
+    let needle = query
+        .trim()
+        .chars()
+        .map(|c| c.to_lowercase().next().unwrap_or(c))
+        .collect::<Vec<_>>();
+    if needle.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    with_document(path, |document| {
+        let mut hits = Vec::new();
+        for (page_index, page) in document.pages().iter().enumerate() {
+            let Ok(page_text) = page.text() else {
+                continue;
+            };
+
+            // Keep the same filtering as `page_text` so char indices line up.
+            let chars = page_text
+                .chars()
+                .iter()
+                .filter(|char| char.tight_bounds().is_ok())
+                .map(|char| char.unicode_char().unwrap_or(' '))
+                .collect::<Vec<_>>();
+            if chars.len() < needle.len() {
+                continue;
+            }
+            let folded = chars
+                .iter()
+                .map(|c| c.to_lowercase().next().unwrap_or(*c))
+                .collect::<Vec<_>>();
+
+            let mut start = 0;
+            while start + needle.len() <= folded.len() {
+                if folded[start..start + needle.len()] != needle[..] {
+                    start += 1;
+                    continue;
+                }
+
+                let snippet_start = start.saturating_sub(TEXT_SEARCH_SNIPPET_BEFORE);
+                let snippet_end =
+                    (start + needle.len() + TEXT_SEARCH_SNIPPET_AFTER).min(chars.len());
+                let snippet = chars[snippet_start..snippet_end]
+                    .iter()
+                    .map(|c| if c.is_whitespace() { ' ' } else { *c })
+                    .collect::<String>()
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                hits.push(TextSearchHit {
+                    page_index,
+                    char_start: start,
+                    char_len: needle.len(),
+                    snippet,
+                });
+                if hits.len() >= TEXT_SEARCH_MAX_HITS {
+                    return Ok(hits);
+                }
+                start += needle.len();
+            }
+        }
+        Ok(hits)
+    })
+}
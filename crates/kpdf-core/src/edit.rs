@@ -0,0 +1,1487 @@
+//! Whole-document edits that write a new file: merging, splitting, optimizing, stamping
+//! watermarks, headers and footers, encrypting, and saving bookmarks as an outline.
+//!
+//! Errors are in English; a file Pdfium cannot open fails with
+//! [`OpenError`](crate::document::OpenError).
+
+use crate::document::{bitmap_to_bgra_bytes, open_uncached};
+use crate::pdfium::{pdfium_access_guard, shared_pdfium};
+use anyhow::{Context as _, Result, anyhow, bail};
+use image::imageops::FilterType;
+use pdfium_render::prelude::*;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Concatenates every page of `inputs`, in order, into a new document at `output`.
+/// `on_progress` receives the number of inputs appended so far and the total.
+pub fn merge_documents(
+    inputs: &[PathBuf],
+    output: &Path,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<()> {
+    let _access_guard = pdfium_access_guard()?;
+    let pdfium = shared_pdfium()?;
+
+    let mut merged = pdfium.create_new_pdf()?;
+    on_progress(0, inputs.len());
+    for (done, input) in inputs.iter().enumerate() {
+        let source = open_uncached(input)?;
+        merged
+            .pages_mut()
+            .append(&source)
+            .with_context(|| format!("append {}", input.display()))?;
+        on_progress(done + 1, inputs.len());
+    }
+    merged
+        .save_to_file(output)
+        .with_context(|| format!("write {}", output.display()))?;
+
+    crate::debug_log!(
+        "[pdf][edit] merged {} files into {}",
+        inputs.len(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Writes `input` as consecutive documents of `every` pages into `output_dir`, named
+/// `<stem>-<first>-<last>.pdf` with 1-based page numbers. Returns the written paths. Unless
+/// `overwrite` is set, nothing is written when one of the parts already exists.
+pub fn split_document(
+    input: &Path,
+    every: usize,
+    output_dir: &Path,
+    overwrite: bool,
+) -> Result<Vec<PathBuf>> {
+    if every == 0 {
+        bail!("page count per part must be at least 1");
+    }
+    let _access_guard = pdfium_access_guard()?;
+    let pdfium = shared_pdfium()?;
+
+    let source = open_uncached(input)?;
+    let total_pages = source.pages().len() as usize;
+    let stem = input
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "part".to_string());
+    let width = total_pages.to_string().len();
+    let parts = (0..total_pages)
+        .step_by(every)
+        .map(|start| {
+            let end = (start + every).min(total_pages) - 1;
+            let path = output_dir.join(format!(
+                "{stem}-{:0width$}-{:0width$}.pdf",
+                start + 1,
+                end + 1
+            ));
+            (start, end, path)
+        })
+        .collect::<Vec<_>>();
+    if !overwrite && let Some((_, _, path)) = parts.iter().find(|(_, _, path)| path.exists()) {
+        bail!("{} already exists", path.display());
+    }
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("create {}", output_dir.display()))?;
+
+    let mut written = Vec::new();
+    for (start, end, path) in parts {
+        let range = PdfPageIndex::try_from(start)
+            .ok()
+            .zip(PdfPageIndex::try_from(end).ok())
+            .ok_or_else(|| anyhow!("page out of range (total_pages={})", total_pages))?;
+
+        let mut part = pdfium.create_new_pdf()?;
+        part.pages_mut()
+            .copy_page_range_from_document(&source, range.0..=range.1, 0)?;
+        part.save_to_file(&path)
+            .with_context(|| format!("write {}", path.display()))?;
+        written.push(path);
+    }
+
+    crate::debug_log!(
+        "[pdf][edit] split {} into {} parts",
+        input.display(),
+        written.len()
+    );
+    Ok(written)
+}
+
+/// Sizes before and after [`optimize_document`].
+#[derive(Clone, Copy, Debug)]
+pub struct OptimizeReport {
+    pub original_bytes: u64,
+    pub optimized_bytes: u64,
+    pub images_downsampled: usize,
+}
+
+/// Writes a copy of `input` to `output` without the objects nothing refers to, with every
+/// stream compressed. The document itself is copied, so its outline, metadata, names and forms
+/// are kept. With `max_image_dpi`, images placed at a higher resolution are downsampled, and
+/// downsampled or losslessly stored 8-bit images are stored as JPEG where that is smaller.
+/// `on_progress` receives the number of pages processed so far and the total.
+pub fn optimize_document(
+    input: &Path,
+    output: &Path,
+    max_image_dpi: Option<u32>,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<OptimizeReport> {
+    let original_bytes = std::fs::metadata(input)
+        .with_context(|| format!("stat {}", input.display()))?
+        .len();
+    // Round-trip through Pdfium first so damaged files are repaired the same way the viewer
+    // sees them; the optimizing itself is left to lopdf.
+    let bytes = {
+        let _access_guard = pdfium_access_guard()?;
+        let document = open_uncached(input)?;
+        document
+            .save_to_bytes()
+            .with_context(|| format!("serialize {}", input.display()))?
+    };
+    let mut document =
+        lopdf::Document::load_mem(&bytes).with_context(|| format!("parse {}", input.display()))?;
+
+    let pages = document.get_pages();
+    let total_pages = pages.len();
+    let mut images_downsampled = 0;
+    let mut recoded = std::collections::HashSet::new();
+    on_progress(0, total_pages);
+    for (index, page_id) in pages.into_values().enumerate() {
+        if let Some(max_dpi) = max_image_dpi {
+            for (image_id, width_pt) in image_placements(&document, page_id) {
+                // Shared images are handled where they are first drawn.
+                if !recoded.insert(image_id) {
+                    continue;
+                }
+                let Ok(stream) = document
+                    .get_object(image_id)
+                    .and_then(lopdf::Object::as_stream)
+                else {
+                    continue;
+                };
+                if let Some((recoded_stream, downsampled)) = recode_image(stream, width_pt, max_dpi)
+                {
+                    document
+                        .objects
+                        .insert(image_id, lopdf::Object::Stream(recoded_stream));
+                    images_downsampled += usize::from(downsampled);
+                }
+            }
+        }
+        on_progress(index + 1, total_pages);
+    }
+
+    document.prune_objects();
+    document.compress();
+    document
+        .save(output)
+        .with_context(|| format!("write {}", output.display()))?;
+    let optimized_bytes = std::fs::metadata(output)
+        .with_context(|| format!("stat {}", output.display()))?
+        .len();
+
+    crate::debug_log!(
+        "[pdf][edit] optimized {}: {} -> {} bytes, {} images downsampled",
+        input.display(),
+        original_bytes,
+        optimized_bytes,
+        images_downsampled
+    );
+    Ok(OptimizeReport {
+        original_bytes,
+        optimized_bytes,
+        images_downsampled,
+    })
+}
+
+/// JPEG quality used for images the optimizer re-encodes.
+const OPTIMIZE_JPEG_QUALITY: u8 = 80;
+
+/// Whether an image color space is plain gray or RGB, possibly behind an ICC profile, so
+/// the samples can be handed to a JPEG encoder as they are. Indexed, CMYK and special color
+/// spaces are not.
+fn is_gray_or_rgb(color_space: &lopdf::Object) -> bool {
+    use lopdf::Object;
+
+    match color_space {
+        Object::Name(name) => matches!(name.as_slice(), b"DeviceGray" | b"DeviceRGB"),
+        // The component count is checked against the decoded samples afterwards.
+        Object::Array(entries) => entries
+            .first()
+            .and_then(|family| family.as_name().ok())
+            .is_some_and(|family| family == b"ICCBased"),
+        _ => false,
+    }
+}
+
+/// Image XObjects the page draws directly, each with the widest width in points it is
+/// placed at. Images inside form XObjects are not followed.
+fn image_placements(
+    document: &lopdf::Document,
+    page_id: lopdf::ObjectId,
+) -> Vec<(lopdf::ObjectId, f32)> {
+    use lopdf::Object;
+
+    let Ok((resources, inherited)) = document.get_page_resources(page_id) else {
+        return Vec::new();
+    };
+    let xobjects = resources
+        .into_iter()
+        .chain(
+            inherited
+                .into_iter()
+                .filter_map(|id| document.get_dictionary(id).ok()),
+        )
+        .filter_map(|resources| {
+            let (_, xobjects) = document.dereference(resources.get(b"XObject").ok()?).ok()?;
+            xobjects.as_dict().ok()
+        })
+        .collect::<Vec<_>>();
+    let Ok(content) = lopdf::content::Content::decode(&document.get_page_content(page_id)) else {
+        return Vec::new();
+    };
+
+    // Only the horizontal scale matters, so the CTM is tracked as its first two entries.
+    let mut ctm = [1.0f32, 0.0, 0.0, 1.0];
+    let mut saved = Vec::new();
+    let mut placements: Vec<(lopdf::ObjectId, f32)> = Vec::new();
+    for operation in content.operations {
+        match operation.operator.as_str() {
+            "q" => saved.push(ctm),
+            "Q" => ctm = saved.pop().unwrap_or(ctm),
+            "cm" => {
+                let m = operation
+                    .operands
+                    .iter()
+                    .take(4)
+                    .filter_map(|operand| operand.as_float().ok())
+                    .collect::<Vec<_>>();
+                if let [a, b, c, d] = m[..] {
+                    ctm = [
+                        a * ctm[0] + b * ctm[2],
+                        a * ctm[1] + b * ctm[3],
+                        c * ctm[0] + d * ctm[2],
+                        c * ctm[1] + d * ctm[3],
+                    ];
+                }
+            }
+            "Do" => {
+                let Some(name) = operation
+                    .operands
+                    .first()
+                    .and_then(|operand| operand.as_name().ok())
+                else {
+                    continue;
+                };
+                let Some(image_id) = xobjects.iter().find_map(|xobjects| {
+                    let id = xobjects.get(name).and_then(Object::as_reference).ok()?;
+                    let stream = document.get_object(id).and_then(Object::as_stream).ok()?;
+                    (stream.dict.get(b"Subtype").and_then(Object::as_name).ok()? == b"Image")
+                        .then_some(id)
+                }) else {
+                    continue;
+                };
+                let width_pt = ctm[0].hypot(ctm[1]);
+                match placements.iter_mut().find(|(id, _)| *id == image_id) {
+                    Some((_, widest)) => *widest = widest.max(width_pt),
+                    None => placements.push((image_id, width_pt)),
+                }
+            }
+            _ => {}
+        }
+    }
+    placements
+}
+
+/// Re-encodes an 8-bit gray or RGB image as JPEG, downsampled first when its pixels are
+/// denser than `max_dpi` at the placed `width_pt`. Returns the new stream and whether it was
+/// downsampled, or `None` when the image is left as it is: it is masked, in another color
+/// format, already a JPEG at an acceptable resolution, or would not get smaller.
+fn recode_image(
+    stream: &lopdf::Stream,
+    width_pt: f32,
+    max_dpi: u32,
+) -> Option<(lopdf::Stream, bool)> {
+    use image::{DynamicImage, GrayImage, RgbImage};
+    use lopdf::Object;
+
+    let dict = &stream.dict;
+    if width_pt <= 0.0
+        || max_dpi == 0
+        || dict
+            .get(b"ImageMask")
+            .and_then(Object::as_bool)
+            .unwrap_or(false)
+        || dict.has(b"Decode")
+        || dict.has(b"Mask")
+        || !is_gray_or_rgb(dict.get(b"ColorSpace").ok()?)
+        || dict
+            .get(b"BitsPerComponent")
+            .and_then(Object::as_i64)
+            .ok()?
+            != 8
+    {
+        return None;
+    }
+    let width = u32::try_from(dict.get(b"Width").and_then(Object::as_i64).ok()?).ok()?;
+    let height = u32::try_from(dict.get(b"Height").and_then(Object::as_i64).ok()?).ok()?;
+    let filters = stream.filters().unwrap_or_default();
+    let is_jpeg = filters == [b"DCTDecode".as_slice()];
+
+    let decoded = if is_jpeg {
+        image::load_from_memory_with_format(&stream.content, image::ImageFormat::Jpeg).ok()?
+    } else if filters.iter().all(|filter| *filter == b"FlateDecode") {
+        let area = width as usize * height as usize;
+        let pixels = stream.decompressed_content_with_limit(area * 3).ok()?;
+        if pixels.len() == area {
+            DynamicImage::ImageLuma8(GrayImage::from_raw(width, height, pixels)?)
+        } else if pixels.len() == area * 3 {
+            DynamicImage::ImageRgb8(RgbImage::from_raw(width, height, pixels)?)
+        } else {
+            return None;
+        }
+    } else {
+        return None;
+    };
+    // CMYK and 16-bit images would need a different color space entry.
+    if !matches!(
+        decoded,
+        DynamicImage::ImageLuma8(_) | DynamicImage::ImageRgb8(_)
+    ) {
+        return None;
+    }
+
+    let dpi = decoded.width() as f32 / (width_pt / 72.0);
+    let downsampled = dpi > max_dpi as f32;
+    if is_jpeg && !downsampled {
+        // Re-encoding a JPEG at the same size only loses quality.
+        return None;
+    }
+    let image = if downsampled {
+        let scale = max_dpi as f32 / dpi;
+        let target_width = ((decoded.width() as f32 * scale).round() as u32).max(1);
+        let target_height = ((decoded.height() as f32 * scale).round() as u32).max(1);
+        decoded.resize_exact(target_width, target_height, FilterType::Triangle)
+    } else {
+        decoded
+    };
+
+    let mut jpeg = Vec::new();
+    image::codecs::jpeg::JpegEncoder::new_with_quality(&mut jpeg, OPTIMIZE_JPEG_QUALITY)
+        .encode_image(&image)
+        .ok()?;
+    if jpeg.len() >= stream.content.len() {
+        return None;
+    }
+
+    let mut dict = dict.clone();
+    dict.set("Width", i64::from(image.width()));
+    dict.set("Height", i64::from(image.height()));
+    dict.set("Filter", "DCTDecode");
+    dict.remove(b"DecodeParms");
+    Some((
+        lopdf::Stream::new(dict, jpeg).with_compression(false),
+        downsampled,
+    ))
+}
+
+/// Distance kept between a corner-placed stamp and the page edges.
+const STAMP_MARGIN_PT: f32 = 36.0;
+/// Text is laid out at this size and then scaled to the requested width.
+const WATERMARK_BASE_FONT_SIZE: f32 = 48.0;
+
+#[derive(Clone, Debug)]
+pub enum WatermarkContent {
+    Text { text: String, color: [u8; 3] },
+    Image(PathBuf),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WatermarkPosition {
+    #[default]
+    Center,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl WatermarkPosition {
+    pub const ALL: [Self; 5] = [
+        Self::Center,
+        Self::TopLeft,
+        Self::TopRight,
+        Self::BottomLeft,
+        Self::BottomRight,
+    ];
+}
+
+#[derive(Clone, Debug)]
+pub struct Watermark {
+    pub content: WatermarkContent,
+    pub position: WatermarkPosition,
+    /// 0.0 (invisible) to 1.0 (opaque).
+    pub opacity: f32,
+    /// Counter-clockwise, in degrees.
+    pub rotation_degrees: f32,
+    /// Width of the stamp as a fraction of the page width, before rotation.
+    pub relative_width: f32,
+    /// Zero-based page indices; empty stamps every page.
+    pub pages: Vec<usize>,
+}
+
+/// One part of a page selection: 1-based and inclusive, where an open end runs to the last
+/// page.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PageSpan {
+    pub first: usize,
+    pub last: Option<usize>,
+}
+
+impl PageSpan {
+    /// Zero-based indices of the span in a document of `page_count` pages, or the page
+    /// number that lies past the end.
+    pub fn indices(self, page_count: usize) -> Result<std::ops::Range<usize>, String> {
+        let last = self.last.unwrap_or(page_count);
+        if self.first > page_count {
+            return Err(self.first.to_string());
+        }
+        if last > page_count {
+            return Err(last.to_string());
+        }
+        Ok(self.first - 1..last)
+    }
+}
+
+/// Parses a page selection like `1-3, 5, 8-` without knowing the page count. Returns the
+/// offending part on error; a blank selection has no spans.
+pub fn parse_page_spans(spec: &str) -> Result<Vec<PageSpan>, String> {
+    let parse_page = |raw: &str| -> Result<usize, String> {
+        raw.trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|page| *page > 0)
+            .ok_or_else(|| raw.trim().to_string())
+    };
+
+    spec.split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let span = match part.split_once('-') {
+                Some((first, last)) => PageSpan {
+                    first: if first.trim().is_empty() {
+                        1
+                    } else {
+                        parse_page(first)?
+                    },
+                    last: if last.trim().is_empty() {
+                        None
+                    } else {
+                        Some(parse_page(last)?)
+                    },
+                },
+                None => {
+                    let page = parse_page(part)?;
+                    PageSpan {
+                        first: page,
+                        last: Some(page),
+                    }
+                }
+            };
+            if span.last.is_some_and(|last| span.first > last) {
+                return Err(part.to_string());
+            }
+            Ok(span)
+        })
+        .collect()
+}
+
+/// Parses a page selection like `1-3, 5, 8-` into sorted zero-based indices.
+/// A blank selection means every page.
+pub fn parse_page_ranges(spec: &str, page_count: usize) -> Result<Vec<usize>, String> {
+    let spans = parse_page_spans(spec)?;
+    if spans.is_empty() {
+        return Ok((0..page_count).collect());
+    }
+
+    let mut pages = Vec::new();
+    for span in spans {
+        pages.extend(span.indices(page_count)?);
+    }
+    pages.sort_unstable();
+    pages.dedup();
+    Ok(pages)
+}
+
+enum PreparedStamp<'a> {
+    Text {
+        text: &'a str,
+        color: [u8; 3],
+        font: PdfFontToken,
+    },
+    Image(image::DynamicImage),
+}
+
+/// Writes `input` to `output` with `watermark` stamped on the selected pages. `on_progress`
+/// receives the number of pages stamped so far and the total. Returns the stamped page count.
+pub fn stamp_watermark(
+    input: &Path,
+    output: &Path,
+    watermark: &Watermark,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<usize> {
+    let _access_guard = pdfium_access_guard()?;
+
+    let mut document = open_uncached(input)?;
+    let page_count = document.pages().len() as usize;
+    let pages = if watermark.pages.is_empty() {
+        (0..page_count).collect()
+    } else {
+        watermark
+            .pages
+            .iter()
+            .copied()
+            .filter(|index| *index < page_count)
+            .collect::<Vec<_>>()
+    };
+
+    let opacity = watermark.opacity.clamp(0.0, 1.0);
+    let alpha = (opacity * 255.0).round() as u8;
+    // Decode the image once; every page gets its own object built from it.
+    let stamp = match &watermark.content {
+        WatermarkContent::Text { text, color } => PreparedStamp::Text {
+            text,
+            color: *color,
+            font: text_font(&mut document, text, true),
+        },
+        WatermarkContent::Image(path) => {
+            let mut image = image::open(path)
+                .with_context(|| format!("read {}", path.display()))?
+                .into_rgba8();
+            for pixel in image.pixels_mut() {
+                pixel.0[3] = (pixel.0[3] as f32 * opacity).round() as u8;
+            }
+            PreparedStamp::Image(image::DynamicImage::ImageRgba8(image))
+        }
+    };
+
+    on_progress(0, pages.len());
+    for (done, &index) in pages.iter().enumerate() {
+        let page_index = PdfPageIndex::try_from(index)
+            .map_err(|_| anyhow!("page out of range (index={index})"))?;
+        let mut page = document.pages().get(page_index)?;
+        let space = StampSpace::of(&page);
+        let page_size = space.size;
+        let mut object = match &stamp {
+            PreparedStamp::Text { text, color, font } => {
+                let mut object = page.objects_mut().create_text_object(
+                    PdfPoints::ZERO,
+                    PdfPoints::ZERO,
+                    text,
+                    *font,
+                    PdfPoints::new(WATERMARK_BASE_FONT_SIZE),
+                )?;
+                object.set_fill_color(PdfColor::new(color[0], color[1], color[2], alpha))?;
+                object
+            }
+            PreparedStamp::Image(image) => page.objects_mut().create_image_object(
+                PdfPoints::ZERO,
+                PdfPoints::ZERO,
+                image,
+                Some(PdfPoints::new(page_size.0 * watermark.relative_width)),
+                None,
+            )?,
+        };
+        place_stamp(&mut object, page_size, watermark)?;
+        space.place(&mut object)?;
+        page.regenerate_content()
+            .with_context(|| format!("rewrite page {}", index + 1))?;
+        on_progress(done + 1, pages.len());
+    }
+
+    document
+        .save_to_file(output)
+        .with_context(|| format!("write {}", output.display()))?;
+
+    crate::debug_log!(
+        "[pdf][edit] stamped watermark on {} pages of {} into {}",
+        pages.len(),
+        input.display(),
+        output.display()
+    );
+    Ok(pages.len())
+}
+
+/// A font that can draw `text`. ASCII text uses a built-in font, which adds nothing to the
+/// file; anything else needs a TrueType font from the system, embedded as a CID font so every
+/// script it covers survives. Falls back to the built-in font when none is installed.
+fn text_font(document: &mut PdfDocument, text: &str, bold: bool) -> PdfFontToken {
+    let builtin = |document: &mut PdfDocument| {
+        if bold {
+            document.fonts_mut().helvetica_bold()
+        } else {
+            document.fonts_mut().helvetica()
+        }
+    };
+    if text.is_ascii() {
+        return builtin(document);
+    }
+
+    for path in unicode_font_candidates(bold) {
+        let Ok(bytes) = std::fs::read(&path) else {
+            continue;
+        };
+        match document.fonts_mut().load_true_type_from_bytes(&bytes, true) {
+            Ok(font) => {
+                crate::debug_log!("[pdf][edit] using font {}", path.display());
+                return font;
+            }
+            Err(err) => {
+                crate::debug_log!("[pdf][edit] cannot load font {}: {}", path.display(), err)
+            }
+        }
+    }
+    crate::debug_log!("[pdf][edit] no Unicode font found; non-Latin text may not show");
+    builtin(document)
+}
+
+/// System TrueType fonts with wide script coverage, best first. Bold faces are tried first
+/// when `bold` is set.
+fn unicode_font_candidates(bold: bool) -> Vec<PathBuf> {
+    let (bold_files, regular_files): (&[&str], &[&str]) = if cfg!(target_os = "windows") {
+        (
+            &["arialbd.ttf"],
+            &["arialuni.ttf", "simhei.ttf", "arial.ttf"],
+        )
+    } else if cfg!(target_os = "macos") {
+        (&[], &["Arial Unicode.ttf"])
+    } else {
+        (
+            &["DejaVuSans-Bold.ttf", "NotoSans-Bold.ttf"],
+            &["DejaVuSans.ttf", "NotoSans-Regular.ttf"],
+        )
+    };
+    let dirs: Vec<PathBuf> = if cfg!(target_os = "windows") {
+        std::env::var_os("WINDIR")
+            .map(|windir| vec![PathBuf::from(windir).join("Fonts")])
+            .unwrap_or_default()
+    } else if cfg!(target_os = "macos") {
+        vec![
+            PathBuf::from("/Library/Fonts"),
+            PathBuf::from("/System/Library/Fonts/Supplemental"),
+        ]
+    } else {
+        [
+            "/usr/share/fonts/truetype/dejavu",
+            "/usr/share/fonts/dejavu",
+            "/usr/share/fonts/TTF",
+            "/usr/share/fonts/truetype/noto",
+            "/usr/share/fonts/noto",
+        ]
+        .into_iter()
+        .map(PathBuf::from)
+        .collect()
+    };
+
+    let bold_files = if bold { bold_files } else { &[] };
+    bold_files
+        .iter()
+        .chain(regular_files)
+        .flat_map(|file| dirs.iter().map(move |dir| dir.join(file)))
+        .collect()
+}
+
+/// A page as the reader sees it. Stamps are laid out upright in this space, from the
+/// bottom-left corner of the visible box, then mapped into the page's own coordinates, which
+/// `/Rotate` turns and a crop or media box may offset from the origin.
+struct StampSpace {
+    /// Width and height of the visible box after rotation.
+    size: (f32, f32),
+    /// The `a b c d e f` matrix from the upright view to page coordinates.
+    to_page: [f32; 6],
+}
+
+impl StampSpace {
+    fn of(page: &PdfPage) -> Self {
+        let (width, height) = (page.width().value, page.height().value);
+        let (left, bottom) = page
+            .boundaries()
+            .crop()
+            .or_else(|_| page.boundaries().media())
+            .map(|boundary| (boundary.bounds.left().value, boundary.bounds.bottom().value))
+            .unwrap_or((0.0, 0.0));
+        // `width` and `height` are already rotated, so a quarter turn swaps them back.
+        let to_page = match page.rotation().unwrap_or(PdfPageRenderRotation::None) {
+            PdfPageRenderRotation::None => [1.0, 0.0, 0.0, 1.0, left, bottom],
+            PdfPageRenderRotation::Degrees90 => [0.0, 1.0, -1.0, 0.0, left + height, bottom],
+            PdfPageRenderRotation::Degrees180 => {
+                [-1.0, 0.0, 0.0, -1.0, left + width, bottom + height]
+            }
+            PdfPageRenderRotation::Degrees270 => [0.0, -1.0, 1.0, 0.0, left, bottom + width],
+        };
+        Self {
+            size: (width, height),
+            to_page,
+        }
+    }
+
+    /// Moves an object laid out in the upright view onto the page.
+    fn place(&self, object: &mut PdfPageObject) -> Result<()> {
+        let [a, b, c, d, e, f] = self.to_page;
+        object.transform(a, b, c, d, e, f)?;
+        Ok(())
+    }
+}
+
+/// Scales, rotates and moves a freshly created object from the page origin to its slot.
+fn place_stamp(
+    object: &mut PdfPageObject,
+    (page_width, page_height): (f32, f32),
+    watermark: &Watermark,
+) -> Result<()> {
+    let width = object.width()?.value;
+    let height = object.height()?.value;
+    if width <= 0.0 || height <= 0.0 {
+        return Ok(());
+    }
+
+    let scale = watermark.relative_width * page_width / width;
+    object.translate(PdfPoints::new(-width / 2.0), PdfPoints::new(-height / 2.0))?;
+    object.scale(scale, scale)?;
+    object.rotate_counter_clockwise_degrees(watermark.rotation_degrees)?;
+
+    let (center_x, center_y) = stamp_center(
+        (width * scale, height * scale),
+        watermark.rotation_degrees,
+        watermark.position,
+        (page_width, page_height),
+    );
+    object.translate(PdfPoints::new(center_x), PdfPoints::new(center_y))?;
+    Ok(())
+}
+
+/// Where the center of a `size` stamp rotated by `rotation_degrees` lands on the page.
+fn stamp_center(
+    (width, height): (f32, f32),
+    rotation_degrees: f32,
+    position: WatermarkPosition,
+    (page_width, page_height): (f32, f32),
+) -> (f32, f32) {
+    let (sin, cos) = rotation_degrees.to_radians().sin_cos();
+    let half_x = (width * cos.abs() + height * sin.abs()) / 2.0;
+    let half_y = (width * sin.abs() + height * cos.abs()) / 2.0;
+    let left = STAMP_MARGIN_PT + half_x;
+    let right = page_width - STAMP_MARGIN_PT - half_x;
+    let bottom = STAMP_MARGIN_PT + half_y;
+    let top = page_height - STAMP_MARGIN_PT - half_y;
+
+    match position {
+        WatermarkPosition::Center => (page_width / 2.0, page_height / 2.0),
+        WatermarkPosition::TopLeft => (left, top),
+        WatermarkPosition::TopRight => (right, top),
+        WatermarkPosition::BottomLeft => (left, bottom),
+        WatermarkPosition::BottomRight => (right, bottom),
+    }
+}
+
+/// Distance between a header baseline (or the bottom of a footer) and the page edge.
+const HEADER_FOOTER_EDGE_PT: f32 = 24.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HeaderFooterAlignment {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+impl HeaderFooterAlignment {
+    pub const ALL: [Self; 3] = [Self::Left, Self::Center, Self::Right];
+}
+
+#[derive(Clone, Debug)]
+pub struct HeaderFooter {
+    /// Header line template; `{page}`, `{pages}`, `{date}` and `{file}` are filled in per page.
+    pub header: String,
+    /// Footer line template, with the same placeholders as `header`.
+    pub footer: String,
+    pub alignment: HeaderFooterAlignment,
+    pub font_size: f32,
+    /// Zero-based page indices; empty stamps every page.
+    pub pages: Vec<usize>,
+}
+
+/// Fills in the placeholders of a header or footer template for one page.
+pub fn expand_header_footer(
+    template: &str,
+    page_number: usize,
+    page_count: usize,
+    date: &str,
+    file_name: &str,
+) -> String {
+    template
+        .replace("{page}", &page_number.to_string())
+        .replace("{pages}", &page_count.to_string())
+        .replace("{date}", date)
+        .replace("{file}", file_name)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+/// Today's date in the local time zone, as `YYYY-MM-DD`.
+pub fn today_iso_date() -> String {
+    let now = chrono::Local::now();
+    let local_secs = now.timestamp() + i64::from(now.offset().local_minus_utc());
+    iso_date_from_unix_days(local_secs.div_euclid(86_400))
+}
+
+/// Converts days since 1970-01-01 to a proleptic Gregorian `YYYY-MM-DD`.
+fn iso_date_from_unix_days(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Writes `input` to `output` with the header and footer added to the selected pages.
+/// `on_progress` receives the number of pages stamped so far and the total.
+pub fn stamp_header_footer(
+    input: &Path,
+    output: &Path,
+    header_footer: &HeaderFooter,
+    mut on_progress: impl FnMut(usize, usize),
+) -> Result<usize> {
+    let _access_guard = pdfium_access_guard()?;
+
+    let mut document = open_uncached(input)?;
+    let page_count = document.pages().len() as usize;
+    let pages = if header_footer.pages.is_empty() {
+        (0..page_count).collect()
+    } else {
+        header_footer
+            .pages
+            .iter()
+            .copied()
+            .filter(|index| *index < page_count)
+            .collect::<Vec<_>>()
+    };
+    let file_name = file_name(input);
+    let date = today_iso_date();
+    let font = header_footer_font(&mut document, header_footer, &file_name);
+
+    on_progress(0, pages.len());
+    for (done, &index) in pages.iter().enumerate() {
+        add_header_footer_to_page(&mut document, index, header_footer, &date, &file_name, font)?;
+        on_progress(done + 1, pages.len());
+    }
+
+    document
+        .save_to_file(output)
+        .with_context(|| format!("write {}", output.display()))?;
+
+    crate::debug_log!(
+        "[pdf][edit] added header/footer to {} pages of {} into {}",
+        pages.len(),
+        input.display(),
+        output.display()
+    );
+    Ok(pages.len())
+}
+
+/// Renders the first selected page of `input` with the header and footer applied, without
+/// writing anything to disk.
+pub fn render_header_footer_preview(
+    input: &Path,
+    header_footer: &HeaderFooter,
+    target_width: u32,
+) -> Result<(u32, u32, Vec<u8>)> {
+    let _access_guard = pdfium_access_guard()?;
+
+    let mut document = open_uncached(input)?;
+    let page_count = document.pages().len() as usize;
+    let index = header_footer
+        .pages
+        .iter()
+        .copied()
+        .find(|index| *index < page_count)
+        .unwrap_or(0);
+    if index >= page_count {
+        bail!("page out of range (total_pages={page_count})");
+    }
+    let file_name = file_name(input);
+    let font = header_footer_font(&mut document, header_footer, &file_name);
+    add_header_footer_to_page(
+        &mut document,
+        index,
+        header_footer,
+        &today_iso_date(),
+        &file_name,
+        font,
+    )?;
+
+    let page_index =
+        PdfPageIndex::try_from(index).map_err(|_| anyhow!("page out of range (index={index})"))?;
+    let page = document.pages().get(page_index)?;
+    let bitmap = page
+        .render_with_config(&PdfRenderConfig::new().set_target_width(target_width as i32))
+        .context("render error")?;
+    bitmap_to_bgra_bytes(&bitmap)
+}
+
+/// A font for every line `header_footer` can produce; page numbers and dates are ASCII, so
+/// only the templates and the file name decide.
+fn header_footer_font(
+    document: &mut PdfDocument,
+    header_footer: &HeaderFooter,
+    file_name: &str,
+) -> PdfFontToken {
+    let text = format!(
+        "{}{}{}",
+        header_footer.header, header_footer.footer, file_name
+    );
+    text_font(document, &text, false)
+}
+
+fn add_header_footer_to_page(
+    document: &mut PdfDocument,
+    index: usize,
+    header_footer: &HeaderFooter,
+    date: &str,
+    file_name: &str,
+    font: PdfFontToken,
+) -> Result<()> {
+    let page_count = document.pages().len() as usize;
+    let page_index =
+        PdfPageIndex::try_from(index).map_err(|_| anyhow!("page out of range (index={index})"))?;
+    let mut page = document.pages().get(page_index)?;
+    let space = StampSpace::of(&page);
+
+    for (template, is_header) in [
+        (&header_footer.header, true),
+        (&header_footer.footer, false),
+    ] {
+        let text = expand_header_footer(template, index + 1, page_count, date, file_name);
+        if text.trim().is_empty() {
+            continue;
+        }
+        let mut object = page.objects_mut().create_text_object(
+            PdfPoints::ZERO,
+            PdfPoints::ZERO,
+            &text,
+            font,
+            PdfPoints::new(header_footer.font_size),
+        )?;
+        object.set_fill_color(PdfColor::new(0, 0, 0, 255))?;
+        let (x, y) = header_footer_origin(
+            object.width()?.value,
+            header_footer.font_size,
+            header_footer.alignment,
+            is_header,
+            space.size,
+        );
+        object.translate(PdfPoints::new(x), PdfPoints::new(y))?;
+        space.place(&mut object)?;
+    }
+
+    page.regenerate_content()
+        .with_context(|| format!("rewrite page {}", index + 1))?;
+    Ok(())
+}
+
+/// Baseline origin of a header or footer line that is `text_width` wide.
+fn header_footer_origin(
+    text_width: f32,
+    font_size: f32,
+    alignment: HeaderFooterAlignment,
+    is_header: bool,
+    (page_width, page_height): (f32, f32),
+) -> (f32, f32) {
+    let x = match alignment {
+        HeaderFooterAlignment::Left => STAMP_MARGIN_PT,
+        HeaderFooterAlignment::Center => (page_width - text_width) / 2.0,
+        HeaderFooterAlignment::Right => page_width - STAMP_MARGIN_PT - text_width,
+    };
+    let y = if is_header {
+        page_height - HEADER_FOOTER_EDGE_PT - font_size
+    } else {
+        HEADER_FOOTER_EDGE_PT
+    };
+    (x, y)
+}
+
+/// What a reader may do with an encrypted copy without the owner password.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncryptPermissions {
+    pub print: bool,
+    pub copy: bool,
+    pub modify: bool,
+    pub annotate: bool,
+}
+
+impl Default for EncryptPermissions {
+    fn default() -> Self {
+        Self {
+            print: true,
+            copy: true,
+            modify: false,
+            annotate: true,
+        }
+    }
+}
+
+impl EncryptPermissions {
+    fn to_lopdf(self) -> lopdf::Permissions {
+        // Accessibility extraction must stay allowed for backward compatibility.
+        let mut permissions = lopdf::Permissions::COPYABLE_FOR_ACCESSIBILITY;
+        if self.print {
+            permissions |=
+                lopdf::Permissions::PRINTABLE | lopdf::Permissions::PRINTABLE_IN_HIGH_QUALITY;
+        }
+        if self.copy {
+            permissions |= lopdf::Permissions::COPYABLE;
+        }
+        if self.modify {
+            permissions |= lopdf::Permissions::MODIFIABLE | lopdf::Permissions::ASSEMBLABLE;
+        }
+        if self.annotate {
+            permissions |= lopdf::Permissions::ANNOTABLE | lopdf::Permissions::FILLABLE;
+        }
+        permissions
+    }
+}
+
+/// Writes an AES-256 encrypted copy of `input` to `output`. An empty `owner_password` is
+/// replaced by a random one nobody knows, so the permissions hold even for readers who
+/// have the user password.
+pub fn save_encrypted_copy(
+    input: &Path,
+    output: &Path,
+    user_password: &str,
+    owner_password: &str,
+    permissions: EncryptPermissions,
+) -> Result<()> {
+    if user_password.is_empty() && owner_password.is_empty() {
+        bail!("a user or owner password is required");
+    }
+    let owner_password = if owner_password.is_empty() {
+        random_password()?
+    } else {
+        owner_password.to_string()
+    };
+
+    // Round-trip through Pdfium first so damaged files are repaired the same way the viewer
+    // sees them; only the encryption is left to lopdf.
+    let bytes = {
+        let _access_guard = pdfium_access_guard()?;
+        let document = open_uncached(input)?;
+        document
+            .save_to_bytes()
+            .with_context(|| format!("serialize {}", input.display()))?
+    };
+
+    let mut document =
+        lopdf::Document::load_mem(&bytes).with_context(|| format!("parse {}", input.display()))?;
+    let mut file_encryption_key = [0u8; 32];
+    getrandom::fill(&mut file_encryption_key)
+        .map_err(|err| anyhow!("generate encryption key: {err}"))?;
+    let crypt_filter: Arc<dyn lopdf::encryption::crypt_filters::CryptFilter> =
+        Arc::new(lopdf::encryption::crypt_filters::Aes256CryptFilter);
+    let state = lopdf::EncryptionState::try_from(lopdf::EncryptionVersion::V5 {
+        encrypt_metadata: true,
+        crypt_filters: BTreeMap::from([(b"StdCF".to_vec(), crypt_filter)]),
+        file_encryption_key: &file_encryption_key,
+        stream_filter: b"StdCF".to_vec(),
+        string_filter: b"StdCF".to_vec(),
+        owner_password: &owner_password,
+        user_password,
+        permissions: permissions.to_lopdf(),
+    })
+    .context("prepare encryption")?;
+    document.encrypt(&state).context("encrypt document")?;
+    document
+        .save(output)
+        .with_context(|| format!("write {}", output.display()))?;
+
+    crate::debug_log!(
+        "[pdf][edit] saved encrypted copy of {} to {}",
+        input.display(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// 32 random hex digits, for an owner password that is never shown.
+fn random_password() -> Result<String> {
+    let mut bytes = [0u8; 16];
+    getrandom::fill(&mut bytes).map_err(|err| anyhow!("generate owner password: {err}"))?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// Writes a copy of `input` to `output` whose outline gains one top-level entry per
+/// `(page index, title)` bookmark, after any outline the document already has. Returns the
+/// number of entries added.
+pub fn save_copy_with_bookmark_outline(
+    input: &Path,
+    output: &Path,
+    bookmarks: &[(usize, String)],
+) -> Result<usize> {
+    let bytes = {
+        let _access_guard = pdfium_access_guard()?;
+        let document = open_uncached(input)?;
+        document
+            .save_to_bytes()
+            .with_context(|| format!("serialize {}", input.display()))?
+    };
+
+    let mut document =
+        lopdf::Document::load_mem(&bytes).with_context(|| format!("parse {}", input.display()))?;
+    let added = append_outline_entries(&mut document, bookmarks).context("write outline")?;
+    document
+        .save(output)
+        .with_context(|| format!("write {}", output.display()))?;
+
+    crate::debug_log!(
+        "[pdf][edit] saved {} bookmarks of {} as outline entries in {}",
+        added,
+        input.display(),
+        output.display()
+    );
+    Ok(added)
+}
+
+fn append_outline_entries(
+    document: &mut lopdf::Document,
+    entries: &[(usize, String)],
+) -> Result<usize> {
+    use lopdf::{Object, dictionary};
+
+    let pages = document.get_pages();
+    let existing_root = document
+        .catalog()?
+        .get(b"Outlines")
+        .and_then(Object::as_reference)
+        .ok()
+        .filter(|id| document.get_dictionary(*id).is_ok());
+    let root = match existing_root {
+        Some(id) => id,
+        None => {
+            let id = document.add_object(dictionary! { "Type" => "Outlines", "Count" => 0 });
+            document.catalog_mut()?.set("Outlines", id);
+            id
+        }
+    };
+
+    let mut previous = document
+        .get_dictionary(root)?
+        .get(b"Last")
+        .and_then(Object::as_reference)
+        .ok();
+    let mut added = 0;
+    for (page_index, title) in entries {
+        let Some(page_id) = u32::try_from(*page_index + 1)
+            .ok()
+            .and_then(|page_number| pages.get(&page_number))
+        else {
+            continue;
+        };
+        let title = if title.is_ascii() {
+            title.as_bytes().to_vec()
+        } else {
+            let mut encoded = Vec::new();
+            lopdf::encode_utf16_be(title, &mut encoded);
+            encoded
+        };
+        let mut entry = dictionary! {
+            "Title" => Object::string_literal(title),
+            "Parent" => root,
+            "Dest" => vec![Object::Reference(*page_id), Object::Name(b"Fit".to_vec())],
+        };
+        if let Some(previous) = previous {
+            entry.set("Prev", previous);
+        }
+        let entry_id = document.add_object(entry);
+        match previous {
+            Some(previous) => document.get_dictionary_mut(previous)?.set("Next", entry_id),
+            None => document.get_dictionary_mut(root)?.set("First", entry_id),
+        }
+        previous = Some(entry_id);
+        added += 1;
+    }
+
+    if let Some(last) = previous {
+        let root = document.get_dictionary_mut(root)?;
+        let open_count = root.get(b"Count").and_then(Object::as_i64).unwrap_or(0);
+        root.set("Last", last);
+        root.set("Count", open_count.max(0) + added as i64);
+    }
+    // Ask other viewers to open with the outline showing, unless the file says otherwise.
+    let catalog = document.catalog_mut()?;
+    if added > 0 && !catalog.has(b"PageMode") {
+        catalog.set("PageMode", "UseOutlines");
+    }
+    Ok(added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_ranges_parse_lists_and_open_ends() {
+        assert_eq!(parse_page_ranges("", 3), Ok(vec![0, 1, 2]));
+        assert_eq!(parse_page_ranges("1-3, 5", 6), Ok(vec![0, 1, 2, 4]));
+        assert_eq!(parse_page_ranges("4-, 2", 5), Ok(vec![1, 3, 4]));
+        assert_eq!(parse_page_ranges("-2,2", 5), Ok(vec![0, 1]));
+    }
+
+    #[test]
+    fn page_spans_parse_without_a_page_count() {
+        assert_eq!(
+            parse_page_spans("1-3, 5, 8-"),
+            Ok(vec![
+                PageSpan {
+                    first: 1,
+                    last: Some(3)
+                },
+                PageSpan {
+                    first: 5,
+                    last: Some(5)
+                },
+                PageSpan {
+                    first: 8,
+                    last: None
+                },
+            ])
+        );
+        assert_eq!(parse_page_spans(" "), Ok(Vec::new()));
+        assert_eq!(parse_page_spans("3-1"), Err("3-1".to_string()));
+        assert_eq!(
+            PageSpan {
+                first: 2,
+                last: None
+            }
+            .indices(4),
+            Ok(1..4)
+        );
+    }
+
+    #[test]
+    fn page_ranges_reject_bad_parts() {
+        assert_eq!(parse_page_ranges("0", 3), Err("0".to_string()));
+        assert_eq!(parse_page_ranges("2-9", 3), Err("9".to_string()));
+        assert_eq!(parse_page_ranges("3-1", 3), Err("3-1".to_string()));
+        assert_eq!(parse_page_ranges("a", 3), Err("a".to_string()));
+    }
+
+    #[test]
+    fn stamp_center_keeps_corners_inside_margins() {
+        let page = (600.0, 800.0);
+        assert_eq!(
+            stamp_center((100.0, 20.0), 0.0, WatermarkPosition::Center, page),
+            (300.0, 400.0)
+        );
+        assert_eq!(
+            stamp_center((100.0, 20.0), 0.0, WatermarkPosition::TopLeft, page),
+            (86.0, 754.0)
+        );
+        let (x, y) = stamp_center((100.0, 20.0), 90.0, WatermarkPosition::BottomRight, page);
+        assert!((x - 554.0).abs() < 0.01 && (y - 86.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn header_footer_templates_fill_placeholders() {
+        assert_eq!(
+            expand_header_footer("Page {page} of {pages}", 2, 9, "2026-10-16", "a.pdf"),
+            "Page 2 of 9"
+        );
+        assert_eq!(
+            expand_header_footer("{file} · {date}", 1, 1, "2026-10-16", "a.pdf"),
+            "a.pdf · 2026-10-16"
+        );
+    }
+
+    #[test]
+    fn random_owner_passwords_differ() {
+        let first = random_password().unwrap();
+        assert_eq!(first.len(), 32);
+        assert!(first.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(first, random_password().unwrap());
+    }
+
+    #[test]
+    fn iso_dates_from_unix_days() {
+        assert_eq!(iso_date_from_unix_days(0), "1970-01-01");
+        assert_eq!(iso_date_from_unix_days(11_016), "2000-02-29");
+        assert_eq!(iso_date_from_unix_days(20_742), "2026-10-16");
+    }
+
+    #[test]
+    fn header_footer_origin_respects_alignment_and_edges() {
+        let page = (600.0, 800.0);
+        assert_eq!(
+            header_footer_origin(100.0, 10.0, HeaderFooterAlignment::Center, true, page),
+            (250.0, 766.0)
+        );
+        assert_eq!(
+            header_footer_origin(100.0, 10.0, HeaderFooterAlignment::Right, false, page),
+            (464.0, 24.0)
+        );
+        assert_eq!(
+            header_footer_origin(100.0, 10.0, HeaderFooterAlignment::Left, false, page),
+            (36.0, 24.0)
+        );
+    }
+
+    #[test]
+    fn encrypt_permissions_map_to_pdf_flags() {
+        let none = EncryptPermissions {
+            print: false,
+            copy: false,
+            modify: false,
+            annotate: false,
+        };
+        assert_eq!(
+            none.to_lopdf(),
+            lopdf::Permissions::COPYABLE_FOR_ACCESSIBILITY
+        );
+
+        let print_only = EncryptPermissions {
+            print: true,
+            ..none
+        };
+        assert!(print_only.to_lopdf().contains(
+            lopdf::Permissions::PRINTABLE | lopdf::Permissions::PRINTABLE_IN_HIGH_QUALITY
+        ));
+        assert!(!print_only.to_lopdf().contains(lopdf::Permissions::COPYABLE));
+    }
+
+    #[test]
+    fn optimize_recodes_dense_images_as_jpeg() {
+        use lopdf::{Object, Stream, dictionary};
+
+        let image = |color_space: Object| {
+            Stream::new(
+                dictionary! {
+                    "Type" => "XObject",
+                    "Subtype" => "Image",
+                    "Width" => 200,
+                    "Height" => 100,
+                    "BitsPerComponent" => 8,
+                    "ColorSpace" => color_space,
+                },
+                vec![0x80; 200 * 100 * 3],
+            )
+        };
+
+        // 200 pixels across one inch is 200 dpi.
+        let (recoded, downsampled) = recode_image(&image("DeviceRGB".into()), 72.0, 100).unwrap();
+        assert!(downsampled);
+        assert_eq!(recoded.dict.get(b"Width").unwrap().as_i64().unwrap(), 100);
+        assert_eq!(recoded.dict.get(b"Height").unwrap().as_i64().unwrap(), 50);
+        assert_eq!(recoded.filters().unwrap(), [b"DCTDecode".as_slice()]);
+
+        let (_, downsampled) = recode_image(&image("DeviceRGB".into()), 72.0, 300).unwrap();
+        assert!(!downsampled);
+
+        let indexed = Object::Array(vec!["Indexed".into(), "DeviceRGB".into(), 255.into()]);
+        assert!(recode_image(&image(indexed), 72.0, 100).is_none());
+    }
+
+    #[test]
+    fn bookmark_outline_appends_after_existing_entries() {
+        use lopdf::{Document, Object, dictionary};
+
+        let mut document = Document::with_version("1.7");
+        let pages_id = document.new_object_id();
+        let page_ids = (0..3)
+            .map(|_| document.add_object(dictionary! { "Type" => "Page", "Parent" => pages_id }))
+            .collect::<Vec<_>>();
+        document.objects.insert(
+            pages_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Pages",
+                "Kids" => page_ids.iter().copied().map(Object::Reference).collect::<Vec<_>>(),
+                "Count" => 3,
+            }),
+        );
+        let outlines_id = document.new_object_id();
+        let chapter_id = document.add_object(dictionary! {
+            "Title" => Object::string_literal("Chapter"),
+            "Parent" => outlines_id,
+        });
+        document.objects.insert(
+            outlines_id,
+            Object::Dictionary(dictionary! {
+                "Type" => "Outlines",
+                "First" => chapter_id,
+                "Last" => chapter_id,
+                "Count" => 1,
+            }),
+        );
+        let catalog_id = document.add_object(dictionary! {
+            "Type" => "Catalog",
+            "Pages" => pages_id,
+            "Outlines" => outlines_id,
+        });
+        document.trailer.set("Root", catalog_id);
+
+        let bookmarks = [
+            (2, "Page 3".to_string()),
+            (7, "Missing".to_string()),
+            (0, "第 1 页".to_string()),
+        ];
+        assert_eq!(
+            append_outline_entries(&mut document, &bookmarks).unwrap(),
+            2
+        );
+
+        let root = document.get_dictionary(outlines_id).unwrap();
+        assert_eq!(root.get(b"Count").unwrap().as_i64().unwrap(), 3);
+        let first_added = document
+            .get_dictionary(chapter_id)
+            .unwrap()
+            .get(b"Next")
+            .unwrap()
+            .as_reference()
+            .unwrap();
+        let entry = document.get_dictionary(first_added).unwrap();
+        assert_eq!(entry.get(b"Title").unwrap().as_str().unwrap(), b"Page 3");
+        let dest = entry.get(b"Dest").unwrap().as_array().unwrap();
+        assert_eq!(dest[0].as_reference().unwrap(), page_ids[2]);
+
+        let last = root.get(b"Last").unwrap().as_reference().unwrap();
+        let title = document
+            .get_dictionary(last)
+            .unwrap()
+            .get(b"Title")
+            .unwrap();
+        assert!(title.as_str().unwrap().starts_with(&[0xFE, 0xFF]));
+        assert_eq!(
+            document
+                .catalog()
+                .unwrap()
+                .get(b"PageMode")
+                .unwrap()
+                .as_name()
+                .unwrap(),
+            b"UseOutlines"
+        );
+    }
+}
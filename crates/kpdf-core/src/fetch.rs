@@ -0,0 +1,350 @@
+use anyhow::{Context, Result, anyhow, bail};
+use flate2::read::GzDecoder;
+use pdfium_render::prelude::Pdfium;
+use reqwest::StatusCode;
+use reqwest::blocking::Client;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+const DEFAULT_DOWNLOAD_BASE_URL: &str =
+    "https://github.com/bblanchon/pdfium-binaries/releases/download";
+const HTTP_USER_AGENT: &str = concat!("kPDF-PdfiumFetch/", env!("CARGO_PKG_VERSION"));
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const DOWNLOAD_CHUNK_BYTES: usize = 64 * 1024;
+const VERSION_FILE_NAME: &str = "pdfium.version";
+
+/// Release tag and per-asset SHA-256 sums, regenerated by `scripts/pin_pdfium_checksums.sh`.
+const PINNED_CHECKSUMS: &str = include_str!("../pdfium_checksums.sha256");
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FetchOptions {
+    /// Replaces the GitHub download prefix; the release tag and asset name are appended.
+    pub mirror: Option<String>,
+    pub proxy: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+/// Release tag of pdfium-binaries that the pinned checksums belong to.
+pub fn pinned_release() -> Option<&'static str> {
+    pinned_release_in(PINNED_CHECKSUMS)
+}
+
+/// Whether this build carries a pinned release and a checksum for its platform's archive.
+/// Without both, `download_pdfium` refuses to run and callers should link to the
+/// releases page instead.
+pub fn pinned_download_available() -> bool {
+    pinned_download_available_in(PINNED_CHECKSUMS)
+}
+
+/// pdfium-binaries archive for the platform kPDF was built for.
+pub fn platform_asset_name() -> Option<&'static str> {
+    let os = if cfg!(target_os = "macos") {
+        "mac"
+    } else if cfg!(all(target_os = "linux", target_env = "musl")) {
+        "linux-musl"
+    } else if cfg!(target_os = "linux") {
+        "linux"
+    } else if cfg!(target_os = "windows") {
+        "win"
+    } else {
+        return None;
+    };
+    let arch = if cfg!(target_arch = "x86_64") {
+        "x64"
+    } else if cfg!(target_arch = "aarch64") {
+        "arm64"
+    } else if cfg!(target_arch = "x86") {
+        "x86"
+    } else {
+        return None;
+    };
+
+    Some(match (os, arch) {
+        ("mac", "x64") => "pdfium-mac-x64.tgz",
+        ("mac", "arm64") => "pdfium-mac-arm64.tgz",
+        ("linux", "x64") => "pdfium-linux-x64.tgz",
+        ("linux", "arm64") => "pdfium-linux-arm64.tgz",
+        ("linux", "x86") => "pdfium-linux-x86.tgz",
+        ("linux-musl", "x64") => "pdfium-linux-musl-x64.tgz",
+        ("linux-musl", "arm64") => "pdfium-linux-musl-arm64.tgz",
+        ("linux-musl", "x86") => "pdfium-linux-musl-x86.tgz",
+        ("win", "x64") => "pdfium-win-x64.tgz",
+        ("win", "arm64") => "pdfium-win-arm64.tgz",
+        ("win", "x86") => "pdfium-win-x86.tgz",
+        _ => return None,
+    })
+}
+
+/// Downloads the pinned Pdfium release into `target_dir`, verifying it against its SHA-256.
+///
+/// The archive is kept as a `.part` file in `staging_dir` until verified, so an interrupted
+/// download resumes with a range request. Returns the installed library path.
+pub fn download_pdfium(
+    options: &FetchOptions,
+    target_dir: &Path,
+    staging_dir: &Path,
+    mut on_progress: impl FnMut(DownloadProgress),
+    is_cancelled: impl Fn() -> bool,
+) -> Result<PathBuf> {
+    let asset =
+        platform_asset_name().ok_or_else(|| anyhow!("no Pdfium build for this platform"))?;
+    let release = pinned_release().ok_or_else(|| anyhow!("no pinned Pdfium release"))?;
+    let expected = checksum_in(PINNED_CHECKSUMS, asset)
+        .ok_or_else(|| anyhow!("no pinned checksum for {asset}"))?;
+    let url = asset_url(options.mirror.as_deref(), release, asset);
+
+    std::fs::create_dir_all(staging_dir)
+        .with_context(|| format!("create {}", staging_dir.display()))?;
+    let part_path = staging_dir.join(format!("{asset}.part"));
+    fetch_resumable(options, &url, &part_path, &mut on_progress, &is_cancelled)?;
+
+    let actual = sha256_file(&part_path)?;
+    if !actual.eq_ignore_ascii_case(expected) {
+        // A corrupt partial file would otherwise be resumed forever.
+        let _ = std::fs::remove_file(&part_path);
+        bail!("checksum mismatch for {asset}: expected {expected}, got {actual}");
+    }
+
+    let installed = install_from_archive(&part_path, target_dir)?;
+    let _ = std::fs::remove_file(&part_path);
+    crate::debug_log!(
+        "[pdfium] downloaded {} {} -> {}",
+        release,
+        asset,
+        installed.display()
+    );
+    Ok(installed)
+}
+
+/// Streams `url` into `part_path`, resuming from what an earlier attempt left there.
+pub fn fetch_resumable(
+    options: &FetchOptions,
+    url: &str,
+    part_path: &Path,
+    on_progress: &mut impl FnMut(DownloadProgress),
+    is_cancelled: &impl Fn() -> bool,
+) -> Result<()> {
+    let mut client = Client::builder().connect_timeout(HTTP_CONNECT_TIMEOUT);
+    if let Some(proxy) = options.proxy.as_deref() {
+        client = client.proxy(reqwest::Proxy::all(proxy).context("invalid proxy")?);
+    }
+    let client = client
+        .build()
+        .context("failed to create download http client")?;
+
+    let resume_from = std::fs::metadata(part_path).map_or(0, |metadata| metadata.len());
+    let mut request = client
+        .get(url)
+        .header(reqwest::header::USER_AGENT, HTTP_USER_AGENT);
+    if resume_from > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={resume_from}-"));
+    }
+    let mut response = request
+        .send()
+        .with_context(|| format!("failed to request {url}"))?;
+
+    // The partial file already holds the whole archive.
+    if resume_from > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok(());
+    }
+    let response_status = response.status();
+    response
+        .error_for_status_ref()
+        .with_context(|| format!("download failed: {url}"))?;
+
+    let resumed = response_status == StatusCode::PARTIAL_CONTENT;
+    let mut downloaded = if resumed { resume_from } else { 0 };
+    let total = response.content_length().map(|len| len + downloaded);
+    let mut file = File::options()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part_path)
+        .with_context(|| format!("open {}", part_path.display()))?;
+
+    let mut buffer = vec![0; DOWNLOAD_CHUNK_BYTES];
+    on_progress(DownloadProgress { downloaded, total });
+    loop {
+        if is_cancelled() {
+            bail!("download cancelled");
+        }
+        let read = response.read(&mut buffer).context("download interrupted")?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read])?;
+        downloaded += read as u64;
+        on_progress(DownloadProgress { downloaded, total });
+    }
+    file.flush()?;
+    Ok(())
+}
+
+/// Lowercase hex SHA-256 of the file at `path`.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; DOWNLOAD_CHUNK_BYTES];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Lowercase hex SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    hex(&Sha256::digest(bytes))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Extracts the platform library (and `VERSION`, as `pdfium.version`) from the archive.
+fn install_from_archive(archive_path: &Path, target_dir: &Path) -> Result<PathBuf> {
+    let target = Pdfium::pdfium_platform_library_name_at_path(target_dir);
+    let lib_name = target
+        .file_name()
+        .ok_or_else(|| anyhow!("invalid library path"))?
+        .to_os_string();
+    std::fs::create_dir_all(target_dir)
+        .with_context(|| format!("create {}", target_dir.display()))?;
+
+    let mut archive = tar::Archive::new(GzDecoder::new(File::open(archive_path)?));
+    let mut installed = false;
+    for entry in archive.entries().context("invalid Pdfium archive")? {
+        let mut entry = entry.context("invalid Pdfium archive")?;
+        let entry_path = entry.path()?.into_owned();
+        let destination = if entry_path.file_name() == Some(lib_name.as_os_str()) {
+            installed = true;
+            target.clone()
+        } else if entry_path.file_name().is_some_and(|name| name == "VERSION") {
+            target_dir.join(VERSION_FILE_NAME)
+        } else {
+            continue;
+        };
+
+        // Never overwrite a library that may be loaded in place.
+        let temp_path = destination.with_extension("download");
+        entry
+            .unpack(&temp_path)
+            .with_context(|| format!("extract {}", entry_path.display()))?;
+        std::fs::rename(&temp_path, &destination)
+            .with_context(|| format!("{} -> {}", temp_path.display(), destination.display()))?;
+    }
+
+    if !installed {
+        bail!("{} not found in Pdfium archive", lib_name.to_string_lossy());
+    }
+    Ok(target)
+}
+
+fn asset_url(mirror: Option<&str>, release: &str, asset: &str) -> String {
+    let base = mirror
+        .map(str::trim)
+        .filter(|mirror| !mirror.is_empty())
+        .unwrap_or(DEFAULT_DOWNLOAD_BASE_URL)
+        .trim_end_matches('/');
+    format!("{base}/{}/{asset}", release.replace('/', "%2F"))
+}
+
+fn pinned_release_in(checksums: &str) -> Option<&str> {
+    checksums.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix("release ")
+            .map(str::trim)
+            .filter(|release| !release.is_empty())
+    })
+}
+
+fn pinned_download_available_in(checksums: &str) -> bool {
+    pinned_release_in(checksums).is_some()
+        && platform_asset_name().is_some_and(|asset| checksum_in(checksums, asset).is_some())
+}
+
+fn checksum_in<'a>(checksums: &'a str, asset: &str) -> Option<&'a str> {
+    checksums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let checksum = parts.next()?;
+        let name = parts.next()?.trim_start_matches('*');
+        (name == asset && checksum.len() == 64 && checksum.chars().all(|ch| ch.is_ascii_hexdigit()))
+            .then_some(checksum)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHECKSUMS: &str = "# comment\n\
+release chromium/7350\n\
+3f0a9e1b6c2d4e5f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7  pdfium-linux-x64.tgz\n\
+not-a-checksum  pdfium-win-x64.tgz\n";
+
+    #[test]
+    fn parse_pinned_checksums() {
+        assert_eq!(pinned_release_in(CHECKSUMS), Some("chromium/7350"));
+        assert_eq!(
+            checksum_in(CHECKSUMS, "pdfium-linux-x64.tgz"),
+            Some("3f0a9e1b6c2d4e5f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7")
+        );
+        assert_eq!(checksum_in(CHECKSUMS, "pdfium-win-x64.tgz"), None);
+        assert_eq!(checksum_in(CHECKSUMS, "pdfium-mac-x64.tgz"), None);
+    }
+
+    #[test]
+    fn comment_only_pin_file_disables_download() {
+        assert!(!pinned_download_available_in(
+            "# SHA-256 sums of the pdfium-binaries archives\n# Archives without an entry here are refused.\n"
+        ));
+        assert!(!pinned_download_available_in(
+            "3f0a9e1b6c2d4e5f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7  pdfium-linux-x64.tgz\n"
+        ));
+    }
+
+    #[test]
+    fn shipped_pin_file_names_a_release_with_checksums() {
+        assert!(
+            pinned_release().is_some(),
+            "pdfium_checksums.sha256 has no release line; run scripts/pin_pdfium_checksums.sh"
+        );
+        let pinned = ["mac", "linux", "linux-musl", "win"]
+            .iter()
+            .flat_map(|os| ["x64", "arm64", "x86"].map(|arch| format!("pdfium-{os}-{arch}.tgz")))
+            .filter(|asset| checksum_in(PINNED_CHECKSUMS, asset).is_some())
+            .count();
+        assert!(
+            pinned > 0,
+            "pdfium_checksums.sha256 has no archive checksums"
+        );
+    }
+
+    #[test]
+    fn asset_url_uses_mirror_when_set() {
+        assert_eq!(
+            asset_url(None, "chromium/7350", "pdfium-mac-arm64.tgz"),
+            "https://github.com/bblanchon/pdfium-binaries/releases/download/chromium%2F7350/pdfium-mac-arm64.tgz"
+        );
+        assert_eq!(
+            asset_url(
+                Some("https://mirror.example.com/pdfium/ "),
+                "chromium/7350",
+                "pdfium-mac-arm64.tgz"
+            ),
+            "https://mirror.example.com/pdfium/chromium%2F7350/pdfium-mac-arm64.tgz"
+        );
+    }
+}
@@ -0,0 +1,406 @@
+use anyhow::{Context as _, Result, bail};
+use flate2::Compression;
+use flate2::write::ZlibEncoder;
+use image::{DynamicImage, GenericImageView as _, ImageBuffer};
+use std::io::{self, Cursor, Write};
+use tiff::ColorType as TiffColorType;
+use tiff::decoder::{Decoder as TiffDecoder, DecodingResult};
+
+const CATALOG_ID: usize = 1;
+const PAGES_ID: usize = 2;
+const FIRST_PAGE_OBJECT_ID: usize = 3;
+const OBJECTS_PER_PAGE: usize = 3;
+const JPEG_START_OF_IMAGE: &[u8] = &[0xFF, 0xD8];
+
+/// One image prepared for embedding: JPEG data is kept as is, anything else is stored as
+/// deflated samples (8-bit, or 1-bit for bilevel scans).
+pub struct ImagePage {
+    width: u32,
+    height: u32,
+    color_space: &'static str,
+    bits_per_component: u8,
+    filter: &'static str,
+    data: Vec<u8>,
+}
+
+impl ImagePage {
+    /// Prepares an encoded image file (JPEG, PNG, WebP, ...).
+    pub fn from_encoded(bytes: &[u8]) -> Result<Self> {
+        if let Some((width, height, components)) = jpeg_frame_header(bytes) {
+            let color_space = match components {
+                1 => Some("DeviceGray"),
+                3 => Some("DeviceRGB"),
+                _ => None,
+            };
+            if let Some(color_space) = color_space {
+                return Ok(Self {
+                    width,
+                    height,
+                    color_space,
+                    bits_per_component: 8,
+                    filter: "DCTDecode",
+                    data: bytes.to_vec(),
+                });
+            }
+        }
+        let image = image::load_from_memory(bytes).context("decode image")?;
+        Self::from_image(&image)
+    }
+
+    /// Prepares decoded pixels; transparency is flattened onto white as a page would show it.
+    pub fn from_image(image: &DynamicImage) -> Result<Self> {
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 {
+            bail!("image has no pixels");
+        }
+        let grayscale = !image.color().has_color();
+        let samples = if image.color().has_alpha() {
+            let rgba = image.to_rgba8();
+            let mut samples = Vec::with_capacity(rgba.len() / 4 * if grayscale { 1 } else { 3 });
+            for pixel in rgba.pixels() {
+                let alpha = u32::from(pixel[3]);
+                let channels = if grayscale {
+                    &pixel.0[..1]
+                } else {
+                    &pixel.0[..3]
+                };
+                samples.extend(channels.iter().map(|channel| {
+                    ((u32::from(*channel) * alpha + 255 * (255 - alpha)) / 255) as u8
+                }));
+            }
+            samples
+        } else if grayscale {
+            image.to_luma8().into_raw()
+        } else {
+            image.to_rgb8().into_raw()
+        };
+
+        Self::deflated(
+            width,
+            height,
+            if grayscale { "DeviceGray" } else { "DeviceRGB" },
+            8,
+            &samples,
+        )
+    }
+
+    fn deflated(
+        width: u32,
+        height: u32,
+        color_space: &'static str,
+        bits_per_component: u8,
+        samples: &[u8],
+    ) -> Result<Self> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(samples)?;
+        Ok(Self {
+            width,
+            height,
+            color_space,
+            bits_per_component,
+            filter: "FlateDecode",
+            data: encoder.finish()?,
+        })
+    }
+
+    pub fn pixel_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+}
+
+/// Writes a PDF with one full-bleed image per page, streaming pages as they are added.
+pub struct ImagePdfWriter<W: Write> {
+    output: W,
+    written: usize,
+    dpi: f32,
+    offsets: Vec<usize>,
+    page_count: usize,
+}
+
+impl<W: Write> ImagePdfWriter<W> {
+    /// Pages are sized so images show at `dpi` when the page is viewed at 100%.
+    pub fn new(output: W, dpi: f32) -> io::Result<Self> {
+        let mut writer = Self {
+            output,
+            written: 0,
+            dpi,
+            offsets: Vec::new(),
+            page_count: 0,
+        };
+        writer.write_bytes(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n")?;
+        Ok(writer)
+    }
+
+    pub fn add_page(&mut self, page: &ImagePage) -> io::Result<()> {
+        let image_id = FIRST_PAGE_OBJECT_ID + self.page_count * OBJECTS_PER_PAGE;
+        let (content_id, page_id) = (image_id + 1, image_id + 2);
+        let width_pt = page.width as f32 * 72.0 / self.dpi;
+        let height_pt = page.height as f32 * 72.0 / self.dpi;
+
+        self.begin_object(image_id)?;
+        self.write_bytes(
+            format!(
+                "<< /Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /{} \
+/BitsPerComponent {} /Filter /{} /Length {} >>\nstream\n",
+                page.width,
+                page.height,
+                page.color_space,
+                page.bits_per_component,
+                page.filter,
+                page.data.len()
+            )
+            .as_bytes(),
+        )?;
+        self.write_bytes(&page.data)?;
+        self.write_bytes(b"\nendstream\nendobj\n")?;
+
+        let content = format!("q {width_pt:.2} 0 0 {height_pt:.2} 0 0 cm /Im0 Do Q");
+        self.begin_object(content_id)?;
+        self.write_bytes(
+            format!(
+                "<< /Length {} >>\nstream\n{content}\nendstream\nendobj\n",
+                content.len()
+            )
+            .as_bytes(),
+        )?;
+
+        self.begin_object(page_id)?;
+        self.write_bytes(
+            format!(
+                "<< /Type /Page /Parent {PAGES_ID} 0 R /MediaBox [0 0 {width_pt:.2} {height_pt:.2}] \
+/Resources << /XObject << /Im0 {image_id} 0 R >> >> /Contents {content_id} 0 R >>\nendobj\n"
+            )
+            .as_bytes(),
+        )?;
+        self.page_count += 1;
+        Ok(())
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.page_count
+    }
+
+    /// Writes the page tree, cross-reference table and trailer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let kids = (0..self.page_count)
+            .map(|index| {
+                format!(
+                    "{} 0 R",
+                    FIRST_PAGE_OBJECT_ID + index * OBJECTS_PER_PAGE + 2
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.begin_object(PAGES_ID)?;
+        self.write_bytes(
+            format!(
+                "<< /Type /Pages /Kids [{kids}] /Count {} >>\nendobj\n",
+                self.page_count
+            )
+            .as_bytes(),
+        )?;
+        self.begin_object(CATALOG_ID)?;
+        self.write_bytes(
+            format!("<< /Type /Catalog /Pages {PAGES_ID} 0 R >>\nendobj\n").as_bytes(),
+        )?;
+
+        let xref_offset = self.written;
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", self.offsets.len() + 1);
+        for offset in &self.offsets {
+            xref.push_str(&format!("{offset:010} 00000 n \n"));
+        }
+        xref.push_str(&format!(
+            "trailer\n<< /Size {} /Root {CATALOG_ID} 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            self.offsets.len() + 1
+        ));
+        self.write_bytes(xref.as_bytes())?;
+        self.output.flush()?;
+        Ok(self.output)
+    }
+
+    fn begin_object(&mut self, id: usize) -> io::Result<()> {
+        if self.offsets.len() < id {
+            self.offsets.resize(id, 0);
+        }
+        self.offsets[id - 1] = self.written;
+        self.write_bytes(format!("{id} 0 obj\n").as_bytes())
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.output.write_all(bytes)?;
+        self.written += bytes.len();
+        Ok(())
+    }
+}
+
+/// Decodes the pages of a (possibly multi-page) TIFF one at a time.
+pub struct TiffPages<'a> {
+    decoder: TiffDecoder<Cursor<&'a [u8]>>,
+    done: bool,
+}
+
+impl<'a> TiffPages<'a> {
+    pub fn new(bytes: &'a [u8]) -> Result<Self> {
+        Ok(Self {
+            decoder: TiffDecoder::new(Cursor::new(bytes)).context("read TIFF header")?,
+            done: false,
+        })
+    }
+}
+
+impl Iterator for TiffPages<'_> {
+    type Item = Result<ImagePage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let page = tiff_page(&mut self.decoder);
+        self.done = !self.decoder.more_images() || self.decoder.next_image().is_err();
+        Some(page)
+    }
+}
+
+fn tiff_page(decoder: &mut TiffDecoder<Cursor<&[u8]>>) -> Result<ImagePage> {
+    let (width, height) = decoder.dimensions()?;
+    let color_type = decoder.colortype()?;
+    let pixels = decoder.read_image()?;
+    let image = match (color_type, pixels) {
+        // Bilevel scans are already packed the way PDF wants them: 1 is white, rows padded
+        // to whole bytes.
+        (TiffColorType::Gray(1), DecodingResult::U8(samples)) => {
+            return ImagePage::deflated(width, height, "DeviceGray", 1, &samples);
+        }
+        (TiffColorType::Gray(8), DecodingResult::U8(samples)) => {
+            ImageBuffer::from_raw(width, height, samples).map(DynamicImage::ImageLuma8)
+        }
+        (TiffColorType::GrayA(8), DecodingResult::U8(samples)) => {
+            ImageBuffer::from_raw(width, height, samples).map(DynamicImage::ImageLumaA8)
+        }
+        (TiffColorType::RGB(8), DecodingResult::U8(samples)) => {
+            ImageBuffer::from_raw(width, height, samples).map(DynamicImage::ImageRgb8)
+        }
+        (TiffColorType::RGBA(8), DecodingResult::U8(samples)) => {
+            ImageBuffer::from_raw(width, height, samples).map(DynamicImage::ImageRgba8)
+        }
+        (TiffColorType::CMYK(8), DecodingResult::U8(samples)) => {
+            let rgb = samples
+                .chunks_exact(4)
+                .flat_map(|cmyk| {
+                    let white = 255 - u32::from(cmyk[3]);
+                    [cmyk[0], cmyk[1], cmyk[2]]
+                        .map(|ink| ((255 - u32::from(ink)) * white / 255) as u8)
+                })
+                .collect();
+            ImageBuffer::from_raw(width, height, rgb).map(DynamicImage::ImageRgb8)
+        }
+        (TiffColorType::Gray(16), DecodingResult::U16(samples)) => {
+            ImageBuffer::from_raw(width, height, samples).map(DynamicImage::ImageLuma16)
+        }
+        (TiffColorType::RGB(16), DecodingResult::U16(samples)) => {
+            ImageBuffer::from_raw(width, height, samples).map(DynamicImage::ImageRgb16)
+        }
+        (TiffColorType::RGBA(16), DecodingResult::U16(samples)) => {
+            ImageBuffer::from_raw(width, height, samples).map(DynamicImage::ImageRgba16)
+        }
+        (color_type, _) => bail!("unsupported TIFF color type {color_type:?}"),
+    };
+    let image = image.context("TIFF page is smaller than its dimensions")?;
+    ImagePage::from_image(&image)
+}
+
+/// Size and component count from a baseline or progressive 8-bit JPEG frame header.
+fn jpeg_frame_header(bytes: &[u8]) -> Option<(u32, u32, u8)> {
+    if !bytes.starts_with(JPEG_START_OF_IMAGE) {
+        return None;
+    }
+    let mut offset = JPEG_START_OF_IMAGE.len();
+    while offset + 4 <= bytes.len() {
+        if bytes[offset] != 0xFF {
+            return None;
+        }
+        let marker = bytes[offset + 1];
+        if marker == 0xFF {
+            offset += 1;
+            continue;
+        }
+        let segment_len = usize::from(u16::from_be_bytes([bytes[offset + 2], bytes[offset + 3]]));
+        if matches!(marker, 0xC0..=0xC2) {
+            let header = bytes.get(offset + 4..offset + 10)?;
+            let precision = header[0];
+            let height = u16::from_be_bytes([header[1], header[2]]);
+            let width = u16::from_be_bytes([header[3], header[4]]);
+            return (precision == 8 && width > 0 && height > 0).then_some((
+                u32::from(width),
+                u32::from(height),
+                header[5],
+            ));
+        }
+        offset += 2 + segment_len;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skeleton::page_sizes;
+    use image::{GrayImage, RgbaImage};
+
+    #[test]
+    fn writes_one_page_per_image_at_the_requested_dpi() {
+        let wide =
+            ImagePage::from_image(&DynamicImage::ImageLuma8(GrayImage::new(300, 150))).unwrap();
+        let tall =
+            ImagePage::from_image(&DynamicImage::ImageRgba8(RgbaImage::new(150, 300))).unwrap();
+        let mut writer = ImagePdfWriter::new(Vec::new(), 150.0).unwrap();
+        writer.add_page(&wide).unwrap();
+        writer.add_page(&tall).unwrap();
+        assert_eq!(writer.page_count(), 2);
+        let pdf = writer.finish().unwrap();
+
+        assert_eq!(page_sizes(&pdf), Some(vec![(144.0, 72.0), (72.0, 144.0)]));
+        let startxref = String::from_utf8_lossy(&pdf)
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next()?.parse::<usize>().ok())
+            .unwrap();
+        assert!(pdf[startxref..].starts_with(b"xref\n0 9\n"));
+    }
+
+    #[test]
+    fn reads_every_page_of_a_multi_page_tiff() {
+        let mut tiff = Cursor::new(Vec::new());
+        let mut encoder = tiff::encoder::TiffEncoder::new(&mut tiff).unwrap();
+        encoder
+            .write_image::<tiff::encoder::colortype::Gray8>(4, 2, &[0; 8])
+            .unwrap();
+        encoder
+            .write_image::<tiff::encoder::colortype::RGB8>(2, 3, &[255; 18])
+            .unwrap();
+        let tiff = tiff.into_inner();
+
+        let pages = TiffPages::new(&tiff)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        let sizes = pages.iter().map(ImagePage::pixel_size).collect::<Vec<_>>();
+        assert_eq!(sizes, [(4, 2), (2, 3)]);
+        assert_eq!(pages[0].color_space, "DeviceGray");
+        assert_eq!(pages[1].color_space, "DeviceRGB");
+    }
+
+    #[test]
+    fn keeps_jpeg_data_and_reads_its_frame_header() {
+        let mut jpeg = Vec::new();
+        DynamicImage::ImageRgb8(image::RgbImage::new(40, 20))
+            .write_to(&mut io::Cursor::new(&mut jpeg), image::ImageFormat::Jpeg)
+            .unwrap();
+
+        assert_eq!(jpeg_frame_header(&jpeg), Some((40, 20, 3)));
+        let page = ImagePage::from_encoded(&jpeg).unwrap();
+        assert_eq!(page.filter, "DCTDecode");
+        assert_eq!(page.pixel_size(), (40, 20));
+        assert_eq!(page.data, jpeg);
+    }
+}
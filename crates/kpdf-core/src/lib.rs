@@ -0,0 +1,41 @@
+//! Document-side building blocks of kPDF that do not depend on the UI: locating and
+//! binding Pdfium, downloading pinned Pdfium releases, reading, rendering and searching
+//! documents, whole-document edits, the annotation store, repairing broken cross-reference
+//! tables, reading page sizes without Pdfium, the on-disk render cache, turning images and
+//! comic archives into PDFs, and the per-user data directory.
+
+use std::sync::OnceLock;
+
+pub mod annotations;
+pub mod archive;
+pub mod document;
+pub mod edit;
+pub mod fetch;
+pub mod image_pdf;
+pub mod paths;
+pub mod pdfium;
+pub mod render;
+pub mod render_cache;
+pub mod repair;
+pub mod skeleton;
+
+static LOG_SINK: OnceLock<fn(&str)> = OnceLock::new();
+
+/// Routes this crate's debug messages to `sink`; without one they are dropped.
+pub fn set_log_sink(sink: fn(&str)) {
+    let _ = LOG_SINK.set(sink);
+}
+
+fn log(message: &str) {
+    if let Some(sink) = LOG_SINK.get() {
+        sink(message);
+    }
+}
+
+macro_rules! debug_log {
+    ($($arg:tt)*) => {
+        $crate::log(&format!($($arg)*))
+    };
+}
+
+pub(crate) use debug_log;
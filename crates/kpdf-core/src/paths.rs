@@ -0,0 +1,12 @@
+//! The per-user directory every piece of kPDF state lives under.
+
+use std::path::PathBuf;
+
+/// `%APPDATA%\kpdf` on Windows and `~/.kpdf` elsewhere, or `None` when neither variable is
+/// set.
+pub fn data_dir() -> Option<PathBuf> {
+    if let Some(app_data) = std::env::var_os("APPDATA") {
+        return Some(PathBuf::from(app_data).join("kpdf"));
+    }
+    std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".kpdf"))
+}
@@ -0,0 +1,397 @@
+//! Locating, binding and diagnosing the Pdfium library.
+
+use anyhow::{Context as _, Result, anyhow};
+use pdfium_render::prelude::*;
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::SystemTime;
+
+const PDFIUM_RENDER_ERROR_HISTORY: usize = 20;
+const PDFIUM_VERSION_FILE_NAME: &str = "pdfium.version";
+
+static PDFIUM_INSTANCE: OnceLock<Pdfium> = OnceLock::new();
+static PDFIUM_INIT_LOCK: Mutex<()> = Mutex::new(());
+static PDFIUM_ACCESS_LOCK: Mutex<()> = Mutex::new(());
+/// Library file Pdfium was bound to; `None` means the system library.
+static PDFIUM_LOADED_LIBRARY: OnceLock<Option<PathBuf>> = OnceLock::new();
+static PDFIUM_RENDER_ERRORS: Mutex<VecDeque<PdfiumRenderError>> = Mutex::new(VecDeque::new());
+/// Library file chosen in settings, tried before every search directory.
+static PDFIUM_PREFERRED_LIBRARY: Mutex<Option<PathBuf>> = Mutex::new(None);
+/// Where the embedded library was unpacked; `None` when unavailable or not compiled in.
+static PDFIUM_BUNDLED_LIB_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+#[cfg(feature = "bundled-pdfium")]
+static BUNDLED_PDFIUM: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/bundled_pdfium"));
+#[cfg(feature = "bundled-pdfium")]
+static BUNDLED_PDFIUM_VERSION: &str =
+    include_str!(concat!(env!("OUT_DIR"), "/bundled_pdfium.version"));
+
+#[derive(Clone, Debug)]
+pub struct PdfiumRenderError {
+    pub at: SystemTime,
+    pub message: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct PdfiumDiagnostics {
+    pub library_path: Option<PathBuf>,
+    pub version: Option<String>,
+    pub load_error: Option<String>,
+    pub searched_dirs: Vec<PathBuf>,
+    pub user_library_dir: Option<PathBuf>,
+    pub recent_render_errors: Vec<PdfiumRenderError>,
+}
+
+/// The process-wide Pdfium binding, created on first use.
+pub fn shared_pdfium() -> Result<&'static Pdfium> {
+    if let Some(pdfium) = PDFIUM_INSTANCE.get() {
+        return Ok(pdfium);
+    }
+
+    let _init_guard = PDFIUM_INIT_LOCK
+        .lock()
+        .map_err(|_| anyhow!("Pdfium init lock is poisoned"))?;
+
+    if let Some(pdfium) = PDFIUM_INSTANCE.get() {
+        return Ok(pdfium);
+    }
+
+    let pdfium = init_pdfium()?;
+    let _ = PDFIUM_INSTANCE.set(pdfium);
+    PDFIUM_INSTANCE
+        .get()
+        .ok_or_else(|| anyhow!("Pdfium initialized but instance is unavailable"))
+}
+
+/// Serializes every Pdfium call; the bindings are not safe to use concurrently.
+pub fn pdfium_access_guard() -> Result<MutexGuard<'static, ()>> {
+    PDFIUM_ACCESS_LOCK
+        .lock()
+        .map_err(|_| anyhow!("Pdfium global access lock is poisoned"))
+}
+
+pub fn ensure_pdfium_ready() -> Result<()> {
+    let _access_guard = pdfium_access_guard()?;
+    shared_pdfium().map(|_| ())
+}
+
+pub fn record_pdfium_render_error(message: String) {
+    let Ok(mut errors) = PDFIUM_RENDER_ERRORS.lock() else {
+        return;
+    };
+    if errors.len() >= PDFIUM_RENDER_ERROR_HISTORY {
+        errors.pop_front();
+    }
+    errors.push_back(PdfiumRenderError {
+        at: SystemTime::now(),
+        message,
+    });
+}
+
+pub fn pdfium_diagnostics() -> PdfiumDiagnostics {
+    let load_error = ensure_pdfium_ready().err().map(|err| format!("{err:#}"));
+    let library_path = PDFIUM_LOADED_LIBRARY.get().cloned().flatten();
+    let version = library_path.as_deref().and_then(pdfium_library_version);
+    let recent_render_errors = PDFIUM_RENDER_ERRORS
+        .lock()
+        .map(|errors| errors.iter().rev().cloned().collect())
+        .unwrap_or_default();
+
+    PdfiumDiagnostics {
+        library_path,
+        version,
+        load_error,
+        searched_dirs: preferred_pdfium_library()
+            .into_iter()
+            .chain(collect_library_dirs())
+            .collect(),
+        user_library_dir: user_pdfium_lib_dir(),
+        recent_render_errors,
+    }
+}
+
+/// Reads the `VERSION` file shipped by pdfium-binaries, which the fetch scripts copy next
+/// to the library as `pdfium.version`.
+fn pdfium_library_version(lib_path: &Path) -> Option<String> {
+    let content =
+        std::fs::read_to_string(lib_path.parent()?.join(PDFIUM_VERSION_FILE_NAME)).ok()?;
+    let field = |key: &str| {
+        content.lines().find_map(|line| {
+            let (name, value) = line.split_once('=')?;
+            (name.trim() == key).then(|| value.trim().to_string())
+        })
+    };
+
+    let build = field("BUILD")?;
+    Some(match (field("MAJOR"), field("MINOR"), field("PATCH")) {
+        (Some(major), Some(minor), Some(patch)) => format!("{major}.{minor}.{build}.{patch}"),
+        _ => format!("chromium/{build}"),
+    })
+}
+
+/// Per-user directory for a relocated Pdfium library, searched before the bundled ones.
+pub fn user_pdfium_lib_dir() -> Option<PathBuf> {
+    crate::paths::data_dir().map(|dir| dir.join("lib"))
+}
+
+/// Downloaded releases live in one subdirectory each, named after the release tag.
+pub fn pdfium_versions_dir() -> Option<PathBuf> {
+    user_pdfium_lib_dir().map(|dir| dir.join("versions"))
+}
+
+/// Libraries under [`pdfium_versions_dir`] as `(label, library path)`, newest label first.
+pub fn downloaded_pdfium_libraries() -> Vec<(String, PathBuf)> {
+    let Some(Ok(entries)) = pdfium_versions_dir().map(std::fs::read_dir) else {
+        return Vec::new();
+    };
+    let mut libraries = entries
+        .flatten()
+        .filter_map(|entry| {
+            let lib_path = Pdfium::pdfium_platform_library_name_at_path(&entry.path());
+            if !lib_path.is_file() {
+                return None;
+            }
+            let label = pdfium_library_version(&lib_path)
+                .unwrap_or_else(|| entry.file_name().to_string_lossy().into_owned());
+            Some((label, lib_path))
+        })
+        .collect::<Vec<_>>();
+    libraries.sort_by(|a, b| b.0.cmp(&a.0));
+    libraries
+}
+
+/// Takes effect on the next Pdfium init, so normally only before the first document loads.
+pub fn set_preferred_pdfium_library(path: Option<PathBuf>) {
+    if let Ok(mut preferred) = PDFIUM_PREFERRED_LIBRARY.lock() {
+        *preferred = path;
+    }
+}
+
+fn preferred_pdfium_library() -> Option<PathBuf> {
+    PDFIUM_PREFERRED_LIBRARY.lock().ok()?.clone()
+}
+
+/// Copies a user-selected Pdfium library into [`user_pdfium_lib_dir`]. The new library is
+/// picked up on the next launch because the loaded bindings cannot be swapped at runtime.
+pub fn install_user_pdfium_library(source: &Path) -> Result<PathBuf> {
+    let lib_dir = user_pdfium_lib_dir().ok_or_else(|| anyhow!("No user library directory"))?;
+    std::fs::create_dir_all(&lib_dir)?;
+    let target = Pdfium::pdfium_platform_library_name_at_path(&lib_dir);
+    std::fs::copy(source, &target)
+        .with_context(|| format!("{} -> {}", source.display(), target.display()))?;
+
+    if let Some(source_dir) = source.parent() {
+        let version_file = source_dir.join(PDFIUM_VERSION_FILE_NAME);
+        if version_file.exists() {
+            let _ = std::fs::copy(version_file, lib_dir.join(PDFIUM_VERSION_FILE_NAME));
+        }
+    }
+
+    crate::debug_log!("[pdfium] installed user library: {}", target.display());
+    Ok(target)
+}
+
+/// Unpacks the library embedded by the `bundled-pdfium` feature next to the user library
+/// directory, once per build, so first run works without downloading Pdfium.
+fn bundled_pdfium_lib_dir() -> Option<PathBuf> {
+    PDFIUM_BUNDLED_LIB_DIR
+        .get_or_init(|| {
+            #[cfg(feature = "bundled-pdfium")]
+            {
+                match unpack_bundled_pdfium() {
+                    Ok(dir) => return Some(dir),
+                    Err(err) => crate::debug_log!("[pdfium] bundled unpack failed: {:#}", err),
+                }
+            }
+            None
+        })
+        .clone()
+}
+
+#[cfg(feature = "bundled-pdfium")]
+fn unpack_bundled_pdfium() -> Result<PathBuf> {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    BUNDLED_PDFIUM.len().hash(&mut hasher);
+    BUNDLED_PDFIUM_VERSION.hash(&mut hasher);
+    let lib_dir = user_pdfium_lib_dir()
+        .and_then(|dir| Some(dir.parent()?.join("bundled")))
+        .ok_or_else(|| anyhow!("No user library directory"))?
+        .join(format!("{:016x}", hasher.finish()));
+    let target = Pdfium::pdfium_platform_library_name_at_path(&lib_dir);
+    let up_to_date = std::fs::metadata(&target)
+        .is_ok_and(|metadata| metadata.len() == BUNDLED_PDFIUM.len() as u64);
+    if up_to_date {
+        return Ok(lib_dir);
+    }
+
+    std::fs::create_dir_all(&lib_dir)?;
+    // Another instance may be loading the existing file, so never write it in place.
+    let temp_path = target.with_extension("tmp");
+    std::fs::write(&temp_path, BUNDLED_PDFIUM)
+        .with_context(|| format!("write {}", temp_path.display()))?;
+    std::fs::rename(&temp_path, &target)
+        .with_context(|| format!("{} -> {}", temp_path.display(), target.display()))?;
+    if !BUNDLED_PDFIUM_VERSION.trim().is_empty() {
+        let _ = std::fs::write(
+            lib_dir.join(PDFIUM_VERSION_FILE_NAME),
+            BUNDLED_PDFIUM_VERSION,
+        );
+    }
+
+    crate::debug_log!("[pdfium] unpacked bundled library: {}", target.display());
+    Ok(lib_dir)
+}
+
+fn app_resources_lib_dir(current_exe: &Path) -> Option<PathBuf> {
+    let macos_dir = current_exe.parent()?;
+    if macos_dir.file_name()?.to_string_lossy() != "MacOS" {
+        return None;
+    }
+    let contents_dir = macos_dir.parent()?;
+    if contents_dir.file_name()?.to_string_lossy() != "Contents" {
+        return None;
+    }
+
+    Some(contents_dir.join("Resources").join("lib"))
+}
+
+#[cfg(target_os = "linux")]
+fn linux_packaged_lib_dir(current_exe: &Path) -> Option<PathBuf> {
+    let exe_dir = current_exe.parent()?;
+    if exe_dir.file_name()?.to_string_lossy() != "bin" {
+        return None;
+    }
+    let prefix_dir = exe_dir.parent()?;
+    Some(prefix_dir.join("lib").join("kpdf").join("lib"))
+}
+
+fn push_library_dir(
+    candidates: &mut Vec<PathBuf>,
+    seen: &mut HashSet<PathBuf>,
+    candidate: PathBuf,
+) {
+    if candidate.as_os_str().is_empty() {
+        return;
+    }
+
+    let normalized = if candidate.exists() {
+        candidate.canonicalize().unwrap_or(candidate)
+    } else if candidate.is_relative() {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(&candidate))
+            .unwrap_or(candidate)
+    } else {
+        candidate
+    };
+
+    if seen.insert(normalized.clone()) {
+        candidates.push(normalized);
+    }
+}
+
+fn collect_library_dirs() -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    let mut seen = HashSet::new();
+
+    if let Ok(override_dir) = std::env::var("KPDF_PDFIUM_LIB_DIR") {
+        let override_dir = override_dir.trim();
+        if !override_dir.is_empty() {
+            push_library_dir(&mut candidates, &mut seen, PathBuf::from(override_dir));
+        }
+    }
+
+    if let Some(bundled_lib_dir) = bundled_pdfium_lib_dir() {
+        push_library_dir(&mut candidates, &mut seen, bundled_lib_dir);
+    }
+
+    if let Some(user_lib_dir) = user_pdfium_lib_dir() {
+        push_library_dir(&mut candidates, &mut seen, user_lib_dir);
+    }
+
+    if let Ok(current_exe) = std::env::current_exe() {
+        if let Some(resources_lib_dir) = app_resources_lib_dir(&current_exe) {
+            push_library_dir(&mut candidates, &mut seen, resources_lib_dir);
+        }
+        #[cfg(target_os = "linux")]
+        if let Some(packaged_lib_dir) = linux_packaged_lib_dir(&current_exe) {
+            push_library_dir(&mut candidates, &mut seen, packaged_lib_dir);
+        }
+
+        if let Some(exe_dir) = current_exe.parent() {
+            push_library_dir(&mut candidates, &mut seen, exe_dir.join("lib"));
+            push_library_dir(&mut candidates, &mut seen, exe_dir.to_path_buf());
+
+            for ancestor in exe_dir.ancestors().take(6) {
+                push_library_dir(&mut candidates, &mut seen, ancestor.join("lib"));
+            }
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        push_library_dir(
+            &mut candidates,
+            &mut seen,
+            PathBuf::from("/usr/lib/kpdf/lib"),
+        );
+        push_library_dir(
+            &mut candidates,
+            &mut seen,
+            PathBuf::from("/usr/local/lib/kpdf/lib"),
+        );
+    }
+
+    if let Ok(current_dir) = std::env::current_dir() {
+        push_library_dir(&mut candidates, &mut seen, current_dir.join("lib"));
+        push_library_dir(&mut candidates, &mut seen, current_dir);
+    }
+
+    push_library_dir(&mut candidates, &mut seen, PathBuf::from("./lib"));
+    push_library_dir(&mut candidates, &mut seen, PathBuf::from("./"));
+
+    candidates
+}
+
+fn init_pdfium() -> Result<Pdfium> {
+    crate::debug_log!("[pdfium] starting init...");
+
+    let candidates = preferred_pdfium_library().into_iter().chain(
+        collect_library_dirs()
+            .into_iter()
+            .map(|lib_dir| Pdfium::pdfium_platform_library_name_at_path(&lib_dir)),
+    );
+    for lib_path in candidates {
+        let display = lib_path.to_string_lossy().into_owned();
+        crate::debug_log!("[pdfium] trying path: {}", display);
+
+        if !lib_path.exists() {
+            crate::debug_log!("[pdfium] {} skipped: not found", display);
+            continue;
+        }
+
+        match Pdfium::bind_to_library(&lib_path) {
+            Ok(bindings) => {
+                crate::debug_log!("[pdfium] loaded from {}", display);
+                crate::debug_log!("[pdfium] init success!");
+                let _ = PDFIUM_LOADED_LIBRARY.set(Some(lib_path));
+                return Ok(Pdfium::new(bindings));
+            }
+            Err(e) => crate::debug_log!("[pdfium] {} failed: {}", display, e),
+        }
+    }
+
+    crate::debug_log!("[pdfium] trying system library");
+    let bindings = Pdfium::bind_to_system_library();
+    match &bindings {
+        Ok(_) => crate::debug_log!("[pdfium] loaded from system"),
+        Err(e) => crate::debug_log!("[pdfium] system failed: {}", e),
+    }
+
+    let bindings = bindings.context("no usable Pdfium library found")?;
+    crate::debug_log!("[pdfium] init success!");
+    let _ = PDFIUM_LOADED_LIBRARY.set(None);
+    Ok(Pdfium::new(bindings))
+}
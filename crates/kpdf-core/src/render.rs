@@ -0,0 +1,185 @@
+//! Render settings shared by every rasterizer.
+
+use pdfium_render::prelude::PdfRenderConfig;
+
+/// Largest full-page bitmap, in pixels, that renderers are asked to allocate.
+pub const MAX_PAGE_PIXELS: u64 = 16384 * 16384;
+
+/// Luma below this turns black in high-contrast renders.
+const HIGH_CONTRAST_BLACK_POINT: i32 = 48;
+/// Luma above this turns white in high-contrast renders.
+const HIGH_CONTRAST_WHITE_POINT: i32 = 208;
+
+/// Engine used for full-page renders. MuPDF is only available in builds with the `mupdf`
+/// feature of the app; elsewhere it renders through Pdfium.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum RenderEngine {
+    #[default]
+    Pdfium,
+    MuPdf,
+}
+
+impl RenderEngine {
+    pub const ALL: [RenderEngine; 2] = [RenderEngine::Pdfium, RenderEngine::MuPdf];
+
+    pub fn as_stored(self) -> &'static [u8] {
+        match self {
+            RenderEngine::Pdfium => b"pdfium",
+            RenderEngine::MuPdf => b"mupdf",
+        }
+    }
+
+    pub fn from_stored(raw: &[u8]) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|engine| engine.as_stored() == raw)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RenderEngine::Pdfium => "PDFium",
+            RenderEngine::MuPdf => "MuPDF",
+        }
+    }
+}
+
+/// How Pdfium anti-aliases text.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum TextAntialiasing {
+    #[default]
+    Grayscale,
+    /// Subpixel rendering tuned for RGB-striped LCD panels.
+    Lcd,
+    Off,
+}
+
+impl TextAntialiasing {
+    pub const ALL: [TextAntialiasing; 3] = [
+        TextAntialiasing::Grayscale,
+        TextAntialiasing::Lcd,
+        TextAntialiasing::Off,
+    ];
+
+    pub fn as_stored(self) -> &'static [u8] {
+        match self {
+            TextAntialiasing::Grayscale => b"grayscale",
+            TextAntialiasing::Lcd => b"lcd",
+            TextAntialiasing::Off => b"off",
+        }
+    }
+
+    pub fn from_stored(raw: &[u8]) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.as_stored() == raw)
+    }
+}
+
+/// Pdfium rasterizer flags picked by the user. The default matches Pdfium's own defaults.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RenderOptions {
+    pub text_antialiasing: TextAntialiasing,
+    /// Let the platform font rasterizer draw text where Pdfium supports it.
+    pub native_text: bool,
+    /// Render without color, as a preview of black-and-white printing.
+    pub grayscale: bool,
+    /// Force near-black text on white paper; follows the high-contrast themes.
+    pub high_contrast: bool,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            text_antialiasing: TextAntialiasing::default(),
+            native_text: true,
+            grayscale: false,
+            high_contrast: false,
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn apply(self, config: PdfRenderConfig) -> PdfRenderConfig {
+        let config = match self.text_antialiasing {
+            TextAntialiasing::Grayscale => config,
+            TextAntialiasing::Lcd => config.use_lcd_text_rendering(true),
+            TextAntialiasing::Off => config.set_text_smoothing(false),
+        };
+        config
+            .disable_native_text_rendering(!self.native_text)
+            .use_grayscale_rendering(self.grayscale)
+    }
+
+    /// Distinguishes cached renders made with non-default options; empty for the defaults.
+    pub fn cache_suffix(self) -> String {
+        let mut suffix = String::new();
+        match self.text_antialiasing {
+            TextAntialiasing::Grayscale => {}
+            TextAntialiasing::Lcd => suffix.push_str("-lcd"),
+            TextAntialiasing::Off => suffix.push_str("-noaa"),
+        }
+        if !self.native_text {
+            suffix.push_str("-nonative");
+        }
+        if self.grayscale {
+            suffix.push_str("-gray");
+        }
+        if self.high_contrast {
+            suffix.push_str("-hc");
+        }
+        suffix
+    }
+
+    /// Adjusts rendered BGRA pixels for the options Pdfium has no flag for.
+    pub fn post_process(self, bgra: &mut [u8]) {
+        if !self.high_contrast {
+            return;
+        }
+        for pixel in bgra.chunks_exact_mut(4) {
+            // Stretching the mid-tones turns faint gray text black and tinted paper white.
+            let value = (luma(pixel[2], pixel[1], pixel[0]) as i32 - HIGH_CONTRAST_BLACK_POINT)
+                * 255
+                / (HIGH_CONTRAST_WHITE_POINT - HIGH_CONTRAST_BLACK_POINT);
+            pixel[..3].fill(value.clamp(0, 255) as u8);
+        }
+    }
+}
+
+/// Perceived brightness of an sRGB pixel (ITU-R BT.601 weights).
+pub fn luma(red: u8, green: u8, blue: u8) -> u8 {
+    ((red as u32 * 299 + green as u32 * 587 + blue as u32 * 114) / 1000) as u8
+}
+
+/// Largest width at or below `target_width` whose full-page bitmap fits in
+/// [`MAX_PAGE_PIXELS`].
+pub fn clamp_render_width(width_pt: f32, height_pt: f32, target_width: u32) -> u32 {
+    let aspect_ratio = if width_pt > 1.0 {
+        height_pt / width_pt
+    } else {
+        1.4
+    };
+    let max_width = (MAX_PAGE_PIXELS as f64 / aspect_ratio.max(f32::EPSILON) as f64)
+        .sqrt()
+        .floor() as u32;
+    target_width.min(max_width).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn high_contrast_pushes_pixels_to_black_and_white() {
+        let options = RenderOptions {
+            high_contrast: true,
+            ..RenderOptions::default()
+        };
+        let mut pixels = [40, 40, 40, 255, 230, 240, 250, 255, 128, 128, 128, 255];
+        options.post_process(&mut pixels);
+        assert_eq!(&pixels[..4], &[0, 0, 0, 255]);
+        assert_eq!(&pixels[4..8], &[255, 255, 255, 255]);
+        assert_eq!(&pixels[8..], &[127, 127, 127, 255]);
+
+        let mut untouched = [10, 20, 30, 255];
+        RenderOptions::default().post_process(&mut untouched);
+        assert_eq!(untouched, [10, 20, 30, 255]);
+    }
+}
@@ -0,0 +1,206 @@
+use crate::render::{RenderEngine, RenderOptions};
+use image::{ImageFormat, RgbaImage};
+use std::collections::HashMap;
+use std::fs::File;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+const RENDER_CACHE_DIR_NAME: &str = "render_cache";
+const FILE_HASH_SAMPLE_BYTES: u64 = 64 * 1024;
+
+/// (path, length, modification time) of a hashed file.
+type FileHashKey = (PathBuf, u64, Option<SystemTime>);
+
+static FILE_HASHES: OnceLock<Mutex<HashMap<FileHashKey, u64>>> = OnceLock::new();
+static EVICTION_LOCK: Mutex<()> = Mutex::new(());
+
+/// Identifies one cached full-page render on disk.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct RenderCacheKey {
+    pub file_hash: u64,
+    pub page_index: usize,
+    pub width: u32,
+    pub rotation: u16,
+    pub engine: RenderEngine,
+    pub options: RenderOptions,
+}
+
+impl RenderCacheKey {
+    fn file_name(&self) -> String {
+        // Pdfium entries keep the original name so existing caches stay valid.
+        let engine_suffix = match self.engine {
+            RenderEngine::Pdfium => "",
+            RenderEngine::MuPdf => "-mupdf",
+        };
+        format!(
+            "{:016x}-p{}-w{}-r{}{}{}.png",
+            self.file_hash,
+            self.page_index,
+            self.width,
+            self.rotation,
+            engine_suffix,
+            self.options.cache_suffix()
+        )
+    }
+}
+
+pub fn render_cache_dir() -> Option<PathBuf> {
+    crate::paths::data_dir().map(|dir| dir.join(RENDER_CACHE_DIR_NAME))
+}
+
+/// Content hash of a document, so renamed or copied files still hit the cache.
+///
+/// Only the length and the first and last 64 KiB are hashed; the result is memoized per
+/// path, length and modification time.
+pub fn file_hash(path: &Path) -> Option<u64> {
+    let metadata = std::fs::metadata(path).ok()?;
+    let memo_key = (path.to_path_buf(), metadata.len(), metadata.modified().ok());
+    let memo = FILE_HASHES.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(hash) = memo.lock().ok()?.get(&memo_key) {
+        return Some(*hash);
+    }
+
+    let mut file = File::open(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    metadata.len().hash(&mut hasher);
+    let mut sample = Vec::with_capacity(FILE_HASH_SAMPLE_BYTES as usize);
+    (&mut file)
+        .take(FILE_HASH_SAMPLE_BYTES)
+        .read_to_end(&mut sample)
+        .ok()?;
+    sample.hash(&mut hasher);
+    if metadata.len() > FILE_HASH_SAMPLE_BYTES {
+        sample.clear();
+        file.seek(SeekFrom::End(-(FILE_HASH_SAMPLE_BYTES as i64)))
+            .ok()?;
+        file.read_to_end(&mut sample).ok()?;
+        sample.hash(&mut hasher);
+    }
+
+    let hash = hasher.finish();
+    memo.lock().ok()?.insert(memo_key, hash);
+    Some(hash)
+}
+
+/// Returns the cached BGRA pixels for `key`, bumping the entry's recency.
+pub fn load(key: &RenderCacheKey) -> Option<(u32, u32, Vec<u8>)> {
+    let path = render_cache_dir()?.join(key.file_name());
+    let image = image::open(&path).ok()?.into_rgba8();
+    if let Ok(file) = File::options().write(true).open(&path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+
+    let (width, height) = image.dimensions();
+    Some((width, height, rgba_bgra_swap(image.into_raw())))
+}
+
+/// Whether an entry for `key` exists, without decoding it or touching its recency.
+pub fn contains(key: &RenderCacheKey) -> bool {
+    render_cache_dir().is_some_and(|dir| dir.join(key.file_name()).is_file())
+}
+
+/// Writes BGRA pixels as a PNG, then evicts least recently used entries over `limit_bytes`.
+pub fn store(key: &RenderCacheKey, width: u32, height: u32, bgra: &[u8], limit_bytes: u64) {
+    store_keeping_since(key, width, height, bgra, limit_bytes, None);
+}
+
+/// Like [`store`], but entries used at or after `keep_since` are never evicted, so a batch
+/// of writes cannot push out its own earlier entries. Returns the size of the new entry.
+pub fn store_keeping_since(
+    key: &RenderCacheKey,
+    width: u32,
+    height: u32,
+    bgra: &[u8],
+    limit_bytes: u64,
+    keep_since: Option<SystemTime>,
+) -> Option<u64> {
+    let dir = render_cache_dir()?;
+    std::fs::create_dir_all(&dir).ok()?;
+
+    // Write to a temporary name first so a concurrent reader never sees a partial file.
+    let path = dir.join(key.file_name());
+    let temp_path = path.with_extension("png.tmp");
+    if let Err(err) = save_png(&temp_path, width, height, bgra) {
+        crate::debug_log!("[render-cache] write failed: {} | {}", path.display(), err);
+        let _ = std::fs::remove_file(&temp_path);
+        return None;
+    }
+    if std::fs::rename(&temp_path, &path).is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+        return None;
+    }
+    let len = std::fs::metadata(&path).map(|metadata| metadata.len()).ok();
+
+    evict_to_limit(&dir, limit_bytes, keep_since);
+    len
+}
+
+/// Saves BGRA pixels as an RGBA PNG, the format cache entries are stored in.
+pub fn save_png(path: &Path, width: u32, height: u32, bgra: &[u8]) -> anyhow::Result<()> {
+    let image = RgbaImage::from_raw(width, height, rgba_bgra_swap(bgra.to_vec()))
+        .ok_or_else(|| anyhow::anyhow!("pixel buffer does not match {width}x{height}"))?;
+    image.save_with_format(path, ImageFormat::Png)?;
+    Ok(())
+}
+
+pub fn usage_bytes() -> u64 {
+    render_cache_dir()
+        .map(|dir| cache_entries(&dir).iter().map(|(_, len, _)| *len).sum())
+        .unwrap_or(0)
+}
+
+pub fn clear() {
+    if let Some(dir) = render_cache_dir() {
+        let _guard = EVICTION_LOCK.lock();
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
+
+fn cache_entries(dir: &Path) -> Vec<(PathBuf, u64, SystemTime)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "png" {
+                return None;
+            }
+            let metadata = entry.metadata().ok()?;
+            let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+            Some((path, metadata.len(), modified))
+        })
+        .collect()
+}
+
+fn evict_to_limit(dir: &Path, limit_bytes: u64, keep_since: Option<SystemTime>) {
+    let Ok(_guard) = EVICTION_LOCK.lock() else {
+        return;
+    };
+    let mut entries = cache_entries(dir);
+    let mut total: u64 = entries.iter().map(|(_, len, _)| *len).sum();
+    if total <= limit_bytes {
+        return;
+    }
+
+    entries.sort_by_key(|(_, _, modified)| *modified);
+    for (path, len, modified) in entries {
+        if total <= limit_bytes || keep_since.is_some_and(|since| modified >= since) {
+            break;
+        }
+        if std::fs::remove_file(&path).is_ok() {
+            total = total.saturating_sub(len);
+        }
+    }
+}
+
+fn rgba_bgra_swap(mut pixels: Vec<u8>) -> Vec<u8> {
+    for pixel in pixels.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    pixels
+}
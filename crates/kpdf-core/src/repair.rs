@@ -0,0 +1,182 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+/// Rebuilds the cross-reference table of a damaged PDF by scanning for `N G obj` headers.
+///
+/// A new xref section and trailer pointing at the last document catalog found are appended
+/// to `bytes`, so later incremental updates still win over earlier copies of an object.
+/// Returns `None` when no catalog can be located.
+pub fn rebuild_xref(bytes: &[u8]) -> Option<Vec<u8>> {
+    let objects = scan_objects(bytes);
+    let mut offsets: BTreeMap<u32, (u16, usize)> = BTreeMap::new();
+    let mut root = None;
+    for (ix, (id, generation, offset)) in objects.iter().copied().enumerate() {
+        offsets.insert(id, (generation, offset));
+        let body_end = objects
+            .get(ix + 1)
+            .map(|(_, _, next)| *next)
+            .unwrap_or(bytes.len());
+        if is_catalog(&bytes[offset..body_end]) {
+            root = Some((id, generation));
+        }
+    }
+    let (root_id, root_generation) = root?;
+    let size = offsets.keys().next_back().map_or(1, |id| id + 1);
+
+    let mut out = bytes.to_vec();
+    if !out.ends_with(b"\n") {
+        out.push(b'\n');
+    }
+    let xref_offset = out.len();
+    let mut table = format!("xref\n0 {size}\n0000000000 65535 f\r\n");
+    for id in 1..size {
+        match offsets.get(&id) {
+            Some((generation, offset)) => {
+                let _ = write!(table, "{offset:010} {generation:05} n\r\n");
+            }
+            None => table.push_str("0000000000 65535 f\r\n"),
+        }
+    }
+    let _ = write!(
+        table,
+        "trailer\n<< /Size {size} /Root {root_id} {root_generation} R >>\nstartxref\n{xref_offset}\n%%EOF\n"
+    );
+    out.extend_from_slice(table.as_bytes());
+    Some(out)
+}
+
+/// Finds every `N G obj` header, returning the object number, generation and byte offset.
+pub(crate) fn scan_objects(bytes: &[u8]) -> Vec<(u32, u16, usize)> {
+    let mut objects = Vec::new();
+    let mut search_from = 0;
+    while let Some(found) = find(&bytes[search_from..], b"obj") {
+        let keyword = search_from + found;
+        search_from = keyword + 3;
+        if keyword >= 3 && &bytes[keyword - 3..keyword] == b"end" {
+            continue;
+        }
+        if bytes
+            .get(keyword + 3)
+            .is_some_and(|next| !is_whitespace(*next) && !is_delimiter(*next))
+        {
+            continue;
+        }
+        if let Some((id, generation, offset)) = parse_object_header(bytes, keyword) {
+            objects.push((id, generation, offset));
+        }
+    }
+    objects
+}
+
+/// Walks backwards from an `obj` keyword over `<generation> <id>` and returns where the
+/// header starts.
+fn parse_object_header(bytes: &[u8], keyword: usize) -> Option<(u32, u16, usize)> {
+    let mut cursor = skip_whitespace_back(bytes, keyword);
+    if cursor == keyword {
+        return None;
+    }
+    let (generation, generation_start) = read_number_back(bytes, cursor)?;
+    cursor = skip_whitespace_back(bytes, generation_start);
+    if cursor == generation_start {
+        return None;
+    }
+    let (id, id_start) = read_number_back(bytes, cursor)?;
+    if id_start > 0 && !is_whitespace(bytes[id_start - 1]) && !is_delimiter(bytes[id_start - 1]) {
+        return None;
+    }
+    Some((
+        u32::try_from(id).ok().filter(|id| *id > 0)?,
+        u16::try_from(generation).ok()?,
+        id_start,
+    ))
+}
+
+fn read_number_back(bytes: &[u8], end: usize) -> Option<(u64, usize)> {
+    let start = bytes[..end]
+        .iter()
+        .rposition(|byte| !byte.is_ascii_digit())
+        .map_or(0, |ix| ix + 1);
+    if start == end || end - start > 10 {
+        return None;
+    }
+    let digits = std::str::from_utf8(&bytes[start..end]).ok()?;
+    Some((digits.parse().ok()?, start))
+}
+
+fn skip_whitespace_back(bytes: &[u8], end: usize) -> usize {
+    bytes[..end]
+        .iter()
+        .rposition(|byte| !is_whitespace(*byte))
+        .map_or(0, |ix| ix + 1)
+}
+
+fn is_catalog(body: &[u8]) -> bool {
+    let body_end = find(body, b"stream").unwrap_or(body.len());
+    let dictionary = &body[..body_end];
+    find(dictionary, b"/Type").is_some_and(|type_at| {
+        let value = &dictionary[type_at + b"/Type".len()..];
+        let value_start = value
+            .iter()
+            .position(|byte| !is_whitespace(*byte))
+            .unwrap_or(value.len());
+        value[value_start..].starts_with(b"/Catalog")
+    })
+}
+
+pub(crate) fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+pub(crate) fn is_whitespace(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\r' | b'\n' | b'\x0c' | b'\0')
+}
+
+pub(crate) fn is_delimiter(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BROKEN_PDF: &[u8] = b"%PDF-1.4\n\
+1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n\
+3 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] >>\nendobj\n\
+xref\n0 2\ngarbage\nstartxref\n99999\n%%EOF\n";
+
+    #[test]
+    fn rebuilds_offsets_and_root() {
+        let rebuilt = rebuild_xref(BROKEN_PDF).expect("catalog should be found");
+        let tail = std::str::from_utf8(&rebuilt[BROKEN_PDF.len()..]).unwrap();
+        let catalog_offset = find(BROKEN_PDF, b"1 0 obj").unwrap();
+        let page_offset = find(BROKEN_PDF, b"3 0 obj").unwrap();
+
+        assert!(tail.starts_with("xref\n0 4\n0000000000 65535 f\r\n"));
+        assert!(tail.contains(&format!("{catalog_offset:010} 00000 n\r\n")));
+        assert!(tail.contains(&format!("{page_offset:010} 00000 n\r\n")));
+        assert!(tail.contains("/Size 4 /Root 1 0 R"));
+        assert!(tail.ends_with(&format!("startxref\n{}\n%%EOF\n", BROKEN_PDF.len())));
+    }
+
+    #[test]
+    fn later_copies_of_an_object_win() {
+        let mut bytes = BROKEN_PDF.to_vec();
+        let update_offset = bytes.len();
+        bytes.extend_from_slice(b"3 0 obj\n<< /Type /Page /Parent 2 0 R >>\nendobj\n");
+
+        let rebuilt = rebuild_xref(&bytes).unwrap();
+        let tail = std::str::from_utf8(&rebuilt[bytes.len()..]).unwrap();
+        assert!(tail.contains(&format!("{update_offset:010} 00000 n\r\n")));
+    }
+
+    #[test]
+    fn missing_catalog_gives_up() {
+        assert!(rebuild_xref(b"%PDF-1.4\n1 0 obj\n<< /Type /Pages >>\nendobj\n").is_none());
+    }
+}
@@ -0,0 +1,195 @@
+use crate::repair::{find, is_delimiter, is_whitespace, scan_objects};
+use std::collections::{HashMap, HashSet};
+
+const DEFAULT_MEDIA_BOX: (f32, f32) = (612.0, 792.0);
+const MAX_PAGE_TREE_DEPTH: usize = 64;
+
+/// Page sizes in points, read without Pdfium by walking the page tree from raw objects.
+///
+/// Only uncompressed objects are visible, so documents that keep their page tree in object
+/// streams fall back to every `/Type /Page` object in file order.
+pub fn page_sizes(bytes: &[u8]) -> Option<Vec<(f32, f32)>> {
+    let objects = scan_objects(bytes);
+    let mut bodies = HashMap::new();
+    let mut root = None;
+    for (ix, (id, _, offset)) in objects.iter().copied().enumerate() {
+        let body_end = objects
+            .get(ix + 1)
+            .map(|(_, _, next)| *next)
+            .unwrap_or(bytes.len());
+        let body = dictionary_part(&bytes[offset..body_end]);
+        if name_value(body, b"/Type").is_some_and(|name| name == b"/Catalog") {
+            root = Some(id);
+        }
+        bodies.insert(id, body);
+    }
+
+    let mut sizes = Vec::new();
+    if let Some(pages_id) = root
+        .and_then(|root| bodies.get(&root))
+        .and_then(|catalog| ref_value(catalog, b"/Pages"))
+    {
+        let mut visited = HashSet::new();
+        walk_page_tree(&bodies, pages_id, None, 0, &mut visited, &mut sizes);
+    }
+    if sizes.is_empty() {
+        let mut seen = HashSet::new();
+        sizes = objects
+            .iter()
+            .filter(|(id, _, _)| seen.insert(*id))
+            .filter_map(|(id, _, _)| bodies.get(id))
+            .filter(|body| name_value(body, b"/Type").is_some_and(|name| name == b"/Page"))
+            .map(|body| media_box(body).unwrap_or(DEFAULT_MEDIA_BOX))
+            .collect();
+    }
+
+    (!sizes.is_empty()).then_some(sizes)
+}
+
+fn walk_page_tree(
+    bodies: &HashMap<u32, &[u8]>,
+    node_id: u32,
+    inherited_media_box: Option<(f32, f32)>,
+    depth: usize,
+    visited: &mut HashSet<u32>,
+    sizes: &mut Vec<(f32, f32)>,
+) {
+    if depth > MAX_PAGE_TREE_DEPTH || !visited.insert(node_id) {
+        return;
+    }
+    let Some(body) = bodies.get(&node_id) else {
+        return;
+    };
+    let media_box = media_box(body).or(inherited_media_box);
+    match name_value(body, b"/Type") {
+        Some(b"/Pages") => {
+            for kid in ref_array_value(body, b"/Kids") {
+                walk_page_tree(bodies, kid, media_box, depth + 1, visited, sizes);
+            }
+        }
+        Some(b"/Page") => sizes.push(media_box.unwrap_or(DEFAULT_MEDIA_BOX)),
+        _ => {}
+    }
+}
+
+/// Drops any stream payload so keys are only matched in the object's dictionary.
+fn dictionary_part(body: &[u8]) -> &[u8] {
+    &body[..find(body, b"stream").unwrap_or(body.len())]
+}
+
+/// Bytes following `key`, with leading whitespace skipped.
+fn value_after<'a>(body: &'a [u8], key: &[u8]) -> Option<&'a [u8]> {
+    let mut search_from = 0;
+    loop {
+        let at = search_from + find(&body[search_from..], key)?;
+        let end = at + key.len();
+        search_from = end;
+        // `/Type` must not match `/TypeX`.
+        if body
+            .get(end)
+            .is_none_or(|next| is_whitespace(*next) || is_delimiter(*next))
+        {
+            let rest = &body[end..];
+            let start = rest
+                .iter()
+                .position(|byte| !is_whitespace(*byte))
+                .unwrap_or(rest.len());
+            return Some(&rest[start..]);
+        }
+    }
+}
+
+fn name_value<'a>(body: &'a [u8], key: &[u8]) -> Option<&'a [u8]> {
+    let value = value_after(body, key)?;
+    if value.first() != Some(&b'/') {
+        return None;
+    }
+    let end = value[1..]
+        .iter()
+        .position(|byte| is_whitespace(*byte) || is_delimiter(*byte))
+        .map_or(value.len(), |ix| ix + 1);
+    Some(&value[..end])
+}
+
+fn ref_value(body: &[u8], key: &[u8]) -> Option<u32> {
+    let value = value_after(body, key)?;
+    let mut tokens = tokens(value);
+    let id = tokens.next()?.parse().ok()?;
+    tokens.next()?.parse::<u16>().ok()?;
+    (tokens.next()? == "R").then_some(id)
+}
+
+fn ref_array_value(body: &[u8], key: &[u8]) -> Vec<u32> {
+    let Some(array) = array_value(body, key) else {
+        return Vec::new();
+    };
+    let tokens = tokens(array).collect::<Vec<_>>();
+    tokens
+        .windows(3)
+        .filter(|window| window[2] == "R")
+        .filter_map(|window| window[0].parse().ok())
+        .collect()
+}
+
+fn media_box(body: &[u8]) -> Option<(f32, f32)> {
+    let numbers = tokens(array_value(body, b"/MediaBox")?)
+        .map(|token| token.parse::<f32>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let [left, bottom, right, top] = numbers[..] else {
+        return None;
+    };
+    let size = ((right - left).abs(), (top - bottom).abs());
+    (size.0 > 0.0 && size.1 > 0.0).then_some(size)
+}
+
+fn array_value<'a>(body: &'a [u8], key: &[u8]) -> Option<&'a [u8]> {
+    let value = value_after(body, key)?.strip_prefix(b"[")?;
+    let end = value.iter().position(|byte| *byte == b']')?;
+    Some(&value[..end])
+}
+
+/// Plain text of every page in page order, read without Pdfium. lopdf decodes content
+/// streams and object streams the raw scan above cannot; pages whose text cannot be decoded
+/// come back empty, and documents lopdf cannot load have no pages.
+pub fn page_texts(bytes: &[u8]) -> Vec<String> {
+    let Ok(document) = lopdf::Document::load_mem(bytes) else {
+        return Vec::new();
+    };
+    document
+        .get_pages()
+        .into_keys()
+        .map(|number| document.extract_text(&[number]).unwrap_or_default())
+        .collect()
+}
+
+fn tokens(value: &[u8]) -> impl Iterator<Item = &str> {
+    value
+        .split(|byte| is_whitespace(*byte) || is_delimiter(*byte))
+        .filter(|token| !token.is_empty())
+        .filter_map(|token| std::str::from_utf8(token).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walks_page_tree_with_inherited_media_box() {
+        let pdf = b"%PDF-1.4\n\
+1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+2 0 obj\n<< /Type /Pages /Kids [4 0 R 3 0 R] /Count 2 /MediaBox [0 0 595 842] >>\nendobj\n\
+3 0 obj\n<< /Type /Page /Parent 2 0 R >>\nendobj\n\
+4 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 842 595] >>\nendobj\n";
+
+        assert_eq!(page_sizes(pdf), Some(vec![(842.0, 595.0), (595.0, 842.0)]));
+    }
+
+    #[test]
+    fn falls_back_to_page_objects_without_a_catalog() {
+        let pdf = b"%PDF-1.4\n\
+5 0 obj\n<< /Type /Page /MediaBox [0 0 100 200] >>\nendobj\n\
+6 0 obj\n<< /Type /Pages /Kids [5 0 R] >>\nendobj\n";
+
+        assert_eq!(page_sizes(pdf), Some(vec![(100.0, 200.0)]));
+    }
+}
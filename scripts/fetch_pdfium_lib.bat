@@ -85,6 +85,7 @@ if errorlevel 1 (
   set "ERRMSG=Failed to copy pdfium.dll to %OUTPUT_DIR%."
   goto fail
 )
+if exist "%EXTRACT_DIR%\VERSION" copy /Y "%EXTRACT_DIR%\VERSION" "%OUTPUT_DIR%\pdfium.version" >nul
 
 if /I "%STRIP_LIB%"=="1" (
   set "SIZE_BEFORE="
@@ -331,6 +331,9 @@ main() {
   mkdir -p "${OUTPUT_DIR}"
   target_lib="${OUTPUT_DIR}/${lib_name}"
   cp -f "${src_lib}" "${target_lib}"
+  if [[ -f "${extract_dir}/VERSION" ]]; then
+    cp -f "${extract_dir}/VERSION" "${OUTPUT_DIR}/pdfium.version"
+  fi
 
   local original_size stripped_size
   original_size="$(file_size_bytes "${target_lib}")"
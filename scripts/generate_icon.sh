@@ -11,9 +11,10 @@ DEFAULT_ICNS_OUTPUT="${ROOT_DIR}/assets/app.icns"
 
 usage() {
   cat <<'EOF'
-Usage: generate_icon.sh [input_png] [output.ico|output.icns] [options]
+Usage: generate_icon.sh [input_png|input_svg] [output.ico|output.icns] [options]
 
-Generate icon files from a PNG via Rust crates.
+Generate icon files from a PNG or SVG via Rust crates.
+SVG input is rasterized at every icon size instead of resampling one bitmap.
 
 Defaults:
   input_png   ./assets/app.png
@@ -28,6 +29,10 @@ No-arg mode generates both defaults directly:
 Extra options are passed to the Rust tool, for example:
   --ico ./assets/app.ico --icns ./assets/app.icns
   --ico-sizes 16,32,48,256 --icns-sizes 16,32,128,256,512
+  --ico ./assets/app.ico --bmp-small
+  --icns ./assets/app.icns --padding 0.1 --background '#FFFFFF' --radius 0.225
+  --linux ./dist/usr/share --app-name kpdf --display-name kPDF
+  --template ./assets/icons --template-color '#FFFFFF'
 EOF
 }
 
@@ -51,7 +56,7 @@ if [[ $# -eq 0 ]]; then
 fi
 
 INPUT_PATH="$1"
-[[ -f "${INPUT_PATH}" ]] || die "Input PNG not found: ${INPUT_PATH}"
+[[ -f "${INPUT_PATH}" ]] || die "Input image not found: ${INPUT_PATH}"
 
 CARGO_TARGET_DIR="${ROOT_DIR}/target/icon_generator" \
   cargo run --quiet --manifest-path "${TOOL_MANIFEST}" -- "$@"
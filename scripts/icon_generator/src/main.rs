@@ -7,11 +7,76 @@ use anyhow::{Context, Result, bail};
 use icns::{IconFamily, Image as IcnsImage, PixelFormat};
 use image::codecs::ico::{IcoEncoder, IcoFrame};
 use image::imageops::{FilterType, overlay, resize};
-use image::{ExtendedColorType, Rgba, RgbaImage};
+use image::{ExtendedColorType, ImageFormat, Rgba, RgbaImage};
+use resvg::{tiny_skia, usvg};
 
 const DEFAULT_ICO_SIZES: [u32; 7] = [16, 24, 32, 48, 64, 128, 256];
 const DEFAULT_ICNS_SIZES: [u32; 8] = [16, 32, 48, 64, 128, 256, 512, 1024];
 const SUPPORTED_ICNS_SIZES: [u32; 8] = [16, 32, 48, 64, 128, 256, 512, 1024];
+/// Largest ICO frame written as BMP with `--bmp-small`; bigger frames stay PNG.
+const BMP_FRAME_MAX_SIZE: u32 = 32;
+const DEFAULT_LINUX_SIZES: [u32; 9] = [16, 22, 24, 32, 48, 64, 128, 256, 512];
+/// macOS menu-bar template sizes (18pt at 1x and 2x) and their file name suffixes.
+const MACOS_TEMPLATE_SIZES: [(u32, &str); 2] = [(18, ""), (36, "@2x")];
+/// Windows taskbar overlay icon sizes (100% and 200% scaling).
+const OVERLAY_ICO_SIZES: [u32; 2] = [16, 32];
+/// Frame size the template plate color is detected on.
+const TEMPLATE_ANALYSIS_SIZE: u32 = 256;
+/// Channel difference from the plate color at which a template pixel is fully inked.
+const TEMPLATE_CONTRAST_THRESHOLD: f32 = 64.0;
+const DEFAULT_APP_NAME: &str = "kpdf";
+const DEFAULT_DISPLAY_NAME: &str = "kPDF";
+const DESKTOP_ENTRY_TEMPLATE: &str = "\
+[Desktop Entry]
+Type=Application
+Name={display_name}
+GenericName=PDF Viewer
+Comment=Lightweight PDF viewer
+Exec={app_name} %F
+Icon={app_name}
+Terminal=false
+Categories=Office;Viewer;
+MimeType=application/pdf;
+StartupWMClass={app_name}
+";
+
+/// The icon artwork: a bitmap that is resampled per size, or an SVG that is rasterized
+/// at each size so large frames stay sharp.
+enum IconSource {
+    Raster(RgbaImage),
+    Vector(Box<usvg::Tree>),
+}
+
+/// How the artwork is placed in each frame: inset by `padding` (a fraction of the frame
+/// size per side) on a `background` square whose corners are rounded by `radius` (a
+/// fraction of the square's size), and inset again inside that square by
+/// `artwork_padding` (a fraction of the square's size per side).
+#[derive(Debug, Clone, Copy, Default)]
+struct FrameStyle {
+    padding: f32,
+    background: Option<Rgba<u8>>,
+    radius: f32,
+    artwork_padding: Option<f32>,
+}
+
+impl FrameStyle {
+    fn is_plain(&self) -> bool {
+        self.padding == 0.0
+            && self.background.is_none()
+            && self.radius == 0.0
+            && self.artwork_inset() == 0.0
+    }
+
+    /// The artwork padding inside the background square. Unless given, artwork on a rounded
+    /// background is inset just far enough that its corners stay clear of the rounding.
+    fn artwork_inset(&self) -> f32 {
+        match (self.artwork_padding, self.background) {
+            (Some(padding), _) => padding,
+            (None, Some(_)) => self.radius * (1.0 - std::f32::consts::FRAC_1_SQRT_2),
+            (None, None) => 0.0,
+        }
+    }
+}
 
 #[derive(Debug)]
 struct Config {
@@ -20,24 +85,58 @@ struct Config {
     icns_output: Option<PathBuf>,
     ico_sizes: Vec<u32>,
     icns_sizes: Vec<u32>,
+    style: FrameStyle,
+    bmp_small: bool,
+    template_output: Option<PathBuf>,
+    template_color: Rgba<u8>,
+    linux_output: Option<PathBuf>,
+    linux_sizes: Vec<u32>,
+    app_name: String,
+    display_name: String,
 }
 
 fn usage(bin: &str) -> String {
     format!(
         "\
 Usage:
-  {bin} <input_png> [output.ico|output.icns]
-  {bin} <input_png> [--ico <output.ico>] [--icns <output.icns>] [--ico-sizes <csv>] [--icns-sizes <csv>]
+  {bin} <input_png|input_svg> [output.ico|output.icns]
+  {bin} <input_png|input_svg> [--ico <output.ico>] [--icns <output.icns>] [--ico-sizes <csv>] [--icns-sizes <csv>] [--bmp-small]
+      [--padding <0..0.5>] [--background <#RRGGBB[AA]>] [--radius <0..0.5>] [--artwork-padding <0..0.5>]
+  {bin} <input_png|input_svg> --template <dir> [--template-color <#RRGGBB>] [--app-name <name>]
+  {bin} <input_png|input_svg> --linux <share_dir> [--linux-sizes <csv>] [--app-name <name>] [--display-name <name>]
 
 Examples:
   {bin} assets/app.png assets/app.ico
   {bin} assets/app.png --ico assets/app.ico --icns assets/app.icns
   {bin} assets/app.png --ico-sizes 16,32,48,256 --icns-sizes 16,32,128,256,512
+  {bin} assets/app.svg --ico assets/app.ico --icns assets/app.icns
+  {bin} assets/app.png --linux dist/usr/share
+  {bin} assets/app.svg --icns assets/app.icns --padding 0.1 --background '#FFFFFF' --radius 0.225
+  {bin} assets/app.svg --ico assets/app.ico --background '#1E293B'
+  {bin} assets/app.png --template assets/icons
 
 Notes:
   - If no output is specified, both files are generated next to input:
     <input_stem>.ico and <input_stem>.icns
+  - SVG (.svg, .svgz) input is rasterized separately for every size
+  - --linux writes <share_dir>/icons/hicolor/<size>x<size>/apps/<app_name>.png
+    (plus scalable/apps/<app_name>.svg for SVG input) and
+    <share_dir>/applications/<app_name>.desktop
   - Supported ICNS sizes: {}
+  - --bmp-small stores ICO frames up to {BMP_FRAME_MAX_SIZE}px as BMP with an AND mask
+    instead of PNG, for older Windows shell components
+  - --padding and --radius are fractions of the frame size; the background is a square
+    inset by the padding with its corners rounded by the radius, and the artwork is
+    drawn inside it
+  - --artwork-padding insets the artwork within the background square, as a fraction of
+    the square's size. With a background it defaults to just clearing the rounded
+    corners (0.29 x radius); without one it defaults to 0, so the radius rounds the
+    artwork itself
+  - --template writes monochrome variants: <app_name>Template.png and
+    <app_name>Template@2x.png for the macOS menu bar (black, only alpha matters) and
+    <app_name>-overlay.ico for Windows taskbar overlays (tinted with --template-color,
+    white by default). Sources drawn on an opaque plate (one dominant color) are inked
+    where they differ from the plate; other sources use their alpha
 ",
         join_sizes(&SUPPORTED_ICNS_SIZES),
     )
@@ -65,6 +164,31 @@ fn parse_sizes(csv: &str, kind: &str) -> Result<Vec<u32>> {
     Ok(sizes)
 }
 
+fn parse_fraction(value: &str, kind: &str) -> Result<f32> {
+    let fraction = value
+        .trim()
+        .parse::<f32>()
+        .with_context(|| format!("invalid {kind}: '{value}'"))?;
+    if !(0.0..=0.5).contains(&fraction) {
+        bail!("{kind} out of range (must be 0..=0.5): {value}");
+    }
+    Ok(fraction)
+}
+
+fn parse_color(value: &str) -> Result<Rgba<u8>> {
+    let hex = value.trim().trim_start_matches('#');
+    if !matches!(hex.len(), 6 | 8) || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!("invalid color (expected #RRGGBB or #RRGGBBAA): '{value}'");
+    }
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16);
+    Ok(Rgba([
+        channel(0)?,
+        channel(2)?,
+        channel(4)?,
+        if hex.len() == 8 { channel(6)? } else { 255 },
+    ]))
+}
+
 fn join_sizes(sizes: &[u32]) -> String {
     sizes
         .iter()
@@ -102,13 +226,21 @@ fn parse_args() -> Result<Config> {
         std::process::exit(0);
     }
 
-    let input = first.context("missing input PNG path")?;
+    let input = first.context("missing input image path")?;
 
     let mut positional_output: Option<PathBuf> = None;
     let mut ico_output: Option<PathBuf> = None;
     let mut icns_output: Option<PathBuf> = None;
     let mut ico_sizes = DEFAULT_ICO_SIZES.to_vec();
     let mut icns_sizes = DEFAULT_ICNS_SIZES.to_vec();
+    let mut bmp_small = false;
+    let mut style = FrameStyle::default();
+    let mut template_output: Option<PathBuf> = None;
+    let mut template_color = Rgba([255, 255, 255, 255]);
+    let mut linux_output: Option<PathBuf> = None;
+    let mut linux_sizes = DEFAULT_LINUX_SIZES.to_vec();
+    let mut app_name = DEFAULT_APP_NAME.to_string();
+    let mut display_name = DEFAULT_DISPLAY_NAME.to_string();
 
     let pending = args.collect::<Vec<OsString>>();
     let mut index = 0usize;
@@ -197,6 +329,188 @@ fn parse_args() -> Result<Config> {
             continue;
         }
 
+        if arg_str == "--padding" {
+            index += 1;
+            let value = pending
+                .get(index)
+                .context("missing value for --padding")?
+                .to_str()
+                .context("invalid non-utf8 value for --padding")?;
+            style.padding = parse_fraction(value, "padding")?;
+            index += 1;
+            continue;
+        }
+        if let Some(value) = arg_str.strip_prefix("--padding=") {
+            style.padding = parse_fraction(value, "padding")?;
+            index += 1;
+            continue;
+        }
+
+        if arg_str == "--background" {
+            index += 1;
+            let value = pending
+                .get(index)
+                .context("missing value for --background")?
+                .to_str()
+                .context("invalid non-utf8 value for --background")?;
+            style.background = Some(parse_color(value)?);
+            index += 1;
+            continue;
+        }
+        if let Some(value) = arg_str.strip_prefix("--background=") {
+            style.background = Some(parse_color(value)?);
+            index += 1;
+            continue;
+        }
+
+        if arg_str == "--radius" {
+            index += 1;
+            let value = pending
+                .get(index)
+                .context("missing value for --radius")?
+                .to_str()
+                .context("invalid non-utf8 value for --radius")?;
+            style.radius = parse_fraction(value, "radius")?;
+            index += 1;
+            continue;
+        }
+        if let Some(value) = arg_str.strip_prefix("--radius=") {
+            style.radius = parse_fraction(value, "radius")?;
+            index += 1;
+            continue;
+        }
+
+        if arg_str == "--artwork-padding" {
+            index += 1;
+            let value = pending
+                .get(index)
+                .context("missing value for --artwork-padding")?
+                .to_str()
+                .context("invalid non-utf8 value for --artwork-padding")?;
+            style.artwork_padding = Some(parse_fraction(value, "artwork padding")?);
+            index += 1;
+            continue;
+        }
+        if let Some(value) = arg_str.strip_prefix("--artwork-padding=") {
+            style.artwork_padding = Some(parse_fraction(value, "artwork padding")?);
+            index += 1;
+            continue;
+        }
+
+        if arg_str == "--template" {
+            index += 1;
+            let value = pending
+                .get(index)
+                .context("missing value for --template")?
+                .to_str()
+                .context("invalid non-utf8 value for --template")?;
+            template_output = Some(PathBuf::from(value));
+            index += 1;
+            continue;
+        }
+        if let Some(value) = arg_str.strip_prefix("--template=") {
+            if value.trim().is_empty() {
+                bail!("missing value for --template");
+            }
+            template_output = Some(PathBuf::from(value));
+            index += 1;
+            continue;
+        }
+
+        if arg_str == "--template-color" {
+            index += 1;
+            let value = pending
+                .get(index)
+                .context("missing value for --template-color")?
+                .to_str()
+                .context("invalid non-utf8 value for --template-color")?;
+            template_color = parse_color(value)?;
+            index += 1;
+            continue;
+        }
+        if let Some(value) = arg_str.strip_prefix("--template-color=") {
+            template_color = parse_color(value)?;
+            index += 1;
+            continue;
+        }
+
+        if arg_str == "--bmp-small" {
+            bmp_small = true;
+            index += 1;
+            continue;
+        }
+
+        if arg_str == "--linux" {
+            index += 1;
+            let value = pending
+                .get(index)
+                .context("missing value for --linux")?
+                .to_str()
+                .context("invalid non-utf8 value for --linux")?;
+            linux_output = Some(PathBuf::from(value));
+            index += 1;
+            continue;
+        }
+        if let Some(value) = arg_str.strip_prefix("--linux=") {
+            if value.trim().is_empty() {
+                bail!("missing value for --linux");
+            }
+            linux_output = Some(PathBuf::from(value));
+            index += 1;
+            continue;
+        }
+
+        if arg_str == "--linux-sizes" {
+            index += 1;
+            let value = pending
+                .get(index)
+                .context("missing value for --linux-sizes")?
+                .to_str()
+                .context("invalid non-utf8 value for --linux-sizes")?;
+            linux_sizes = parse_sizes(value, "Linux")?;
+            index += 1;
+            continue;
+        }
+        if let Some(value) = arg_str.strip_prefix("--linux-sizes=") {
+            linux_sizes = parse_sizes(value, "Linux")?;
+            index += 1;
+            continue;
+        }
+
+        if arg_str == "--app-name" {
+            index += 1;
+            app_name = pending
+                .get(index)
+                .context("missing value for --app-name")?
+                .to_str()
+                .context("invalid non-utf8 value for --app-name")?
+                .to_string();
+            index += 1;
+            continue;
+        }
+        if let Some(value) = arg_str.strip_prefix("--app-name=") {
+            app_name = value.to_string();
+            index += 1;
+            continue;
+        }
+
+        if arg_str == "--display-name" {
+            index += 1;
+            display_name = pending
+                .get(index)
+                .context("missing value for --display-name")?
+                .to_str()
+                .context("invalid non-utf8 value for --display-name")?
+                .to_string();
+            index += 1;
+            continue;
+        }
+        if let Some(value) = arg_str.strip_prefix("--display-name=") {
+            display_name = value.to_string();
+            index += 1;
+            continue;
+        }
+
         if arg_str.starts_with("--") {
             bail!("unknown option: {arg_str}");
         }
@@ -221,7 +535,11 @@ fn parse_args() -> Result<Config> {
         }
     }
 
-    if ico_output.is_none() && icns_output.is_none() {
+    if ico_output.is_none()
+        && icns_output.is_none()
+        && linux_output.is_none()
+        && template_output.is_none()
+    {
         let input_path = PathBuf::from(&input);
         ico_output = Some(input_path.with_extension("ico"));
         icns_output = Some(input_path.with_extension("icns"));
@@ -241,15 +559,162 @@ fn parse_args() -> Result<Config> {
         }
     }
 
+    for size in &linux_sizes {
+        if !(1..=1024).contains(size) {
+            bail!("Linux icon size out of range (must be 1..=1024): {size}");
+        }
+    }
+    // The name becomes a file name and the desktop entry's Exec/Icon keys.
+    if app_name.is_empty()
+        || !app_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    {
+        bail!("invalid --app-name (use letters, digits, '-', '_' or '.'): '{app_name}'");
+    }
+    if display_name.trim().is_empty() || display_name.contains('\n') {
+        bail!("invalid --display-name: '{display_name}'");
+    }
+
     Ok(Config {
         input: PathBuf::from(input),
         ico_output,
         icns_output,
         ico_sizes,
         icns_sizes,
+        style,
+        bmp_small,
+        template_output,
+        template_color,
+        linux_output,
+        linux_sizes,
+        app_name,
+        display_name,
     })
 }
 
+fn is_svg(path: &Path) -> bool {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .is_some_and(|x| x.eq_ignore_ascii_case("svg") || x.eq_ignore_ascii_case("svgz"))
+}
+
+fn load_source(path: &Path) -> Result<IconSource> {
+    if is_svg(path) {
+        let data = std::fs::read(path)
+            .with_context(|| format!("failed to read input SVG: {}", path.display()))?;
+        let mut options = usvg::Options {
+            resources_dir: path.parent().map(Path::to_path_buf),
+            ..Default::default()
+        };
+        options.fontdb_mut().load_system_fonts();
+        let tree = usvg::Tree::from_data(&data, &options)
+            .with_context(|| format!("failed to parse input SVG: {}", path.display()))?;
+        return Ok(IconSource::Vector(Box::new(tree)));
+    }
+
+    let source = image::open(path)
+        .with_context(|| format!("failed to open input image: {}", path.display()))?
+        .into_rgba8();
+    if source.width() == 0 || source.height() == 0 {
+        bail!(
+            "input image has invalid dimensions: {}x{}",
+            source.width(),
+            source.height()
+        );
+    }
+    Ok(IconSource::Raster(source))
+}
+
+fn build_frame(source: &IconSource, size: u32, style: &FrameStyle) -> Result<RgbaImage> {
+    let inset = (size as f32 * style.padding).round() as u32;
+    let body_size = size.saturating_sub(inset * 2).max(1);
+    let artwork_inset = (body_size as f32 * style.artwork_inset()).round() as u32;
+    let artwork_size = body_size.saturating_sub(artwork_inset * 2).max(1);
+    let artwork = match source {
+        IconSource::Raster(image) => build_icon_frame(image, artwork_size),
+        IconSource::Vector(tree) => render_svg_frame(tree, artwork_size)?,
+    };
+    if style.is_plain() {
+        return Ok(artwork);
+    }
+
+    let mut body = RgbaImage::from_pixel(
+        body_size,
+        body_size,
+        style.background.unwrap_or(Rgba([0, 0, 0, 0])),
+    );
+    overlay(
+        &mut body,
+        &artwork,
+        i64::from(artwork_inset),
+        i64::from(artwork_inset),
+    );
+    if style.radius > 0.0 {
+        round_corners(&mut body, style.radius * body_size as f32);
+    }
+
+    let mut canvas = RgbaImage::from_pixel(size, size, Rgba([0, 0, 0, 0]));
+    overlay(&mut canvas, &body, i64::from(inset), i64::from(inset));
+    Ok(canvas)
+}
+
+/// Scales alpha by how much of each pixel lies inside the rounded square, sampled on a
+/// 4x4 grid so the corners are anti-aliased.
+fn round_corners(image: &mut RgbaImage, radius: f32) {
+    const SAMPLES: u32 = 4;
+    let size = image.width() as f32;
+    let radius = radius.min(size / 2.0);
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        let mut inside = 0;
+        for sample_y in 0..SAMPLES {
+            for sample_x in 0..SAMPLES {
+                let px = x as f32 + (sample_x as f32 + 0.5) / SAMPLES as f32;
+                let py = y as f32 + (sample_y as f32 + 0.5) / SAMPLES as f32;
+                let dx = px - px.clamp(radius, size - radius);
+                let dy = py - py.clamp(radius, size - radius);
+                if dx * dx + dy * dy <= radius * radius {
+                    inside += 1;
+                }
+            }
+        }
+        if inside < SAMPLES * SAMPLES {
+            let coverage = inside as f32 / (SAMPLES * SAMPLES) as f32;
+            pixel.0[3] = (f32::from(pixel.0[3]) * coverage).round() as u8;
+        }
+    }
+}
+
+fn render_svg_frame(tree: &usvg::Tree, size: u32) -> Result<RgbaImage> {
+    let tree_size = tree.size();
+    let scale = f32::min(
+        size as f32 / tree_size.width(),
+        size as f32 / tree_size.height(),
+    );
+    let offset_x = (size as f32 - tree_size.width() * scale) / 2.0;
+    let offset_y = (size as f32 - tree_size.height() * scale) / 2.0;
+
+    let mut pixmap = tiny_skia::Pixmap::new(size, size)
+        .with_context(|| format!("failed to allocate SVG canvas {size}x{size}"))?;
+    resvg::render(
+        tree,
+        tiny_skia::Transform::from_row(scale, 0.0, 0.0, scale, offset_x, offset_y),
+        &mut pixmap.as_mut(),
+    );
+
+    // tiny-skia stores premultiplied alpha; icon encoders expect straight RGBA.
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|pixel| {
+            let color = pixel.demultiply();
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        })
+        .collect();
+    RgbaImage::from_raw(size, size, pixels)
+        .with_context(|| format!("failed to convert SVG frame {size}x{size}"))
+}
+
 fn build_icon_frame(source: &RgbaImage, size: u32) -> RgbaImage {
     let src_w = source.width().max(1);
     let src_h = source.height().max(1);
@@ -279,14 +744,69 @@ fn ensure_parent_dir(path: &Path) -> Result<()> {
     Ok(())
 }
 
-fn write_ico(source: &RgbaImage, output_path: &Path, sizes: &[u32]) -> Result<()> {
+/// Encodes a frame as an ICO bitmap: a 32-bit BGRA DIB (bottom-up, with the header
+/// height doubled) followed by the 1-bit AND mask that marks transparent pixels.
+/// Transparent pixels are written black, since shells that only honor the mask XOR the
+/// color into the background.
+fn encode_bmp_frame(frame: &RgbaImage) -> Vec<u8> {
+    let (width, height) = frame.dimensions();
+    let mask_stride = width.div_ceil(32) as usize * 4;
+    let pixel_bytes = (width * height * 4) as usize;
+    let mask_bytes = mask_stride * height as usize;
+
+    let mut data = Vec::with_capacity(40 + pixel_bytes + mask_bytes);
+    data.extend_from_slice(&40u32.to_le_bytes());
+    data.extend_from_slice(&(width as i32).to_le_bytes());
+    data.extend_from_slice(&(height as i32 * 2).to_le_bytes());
+    data.extend_from_slice(&1u16.to_le_bytes());
+    data.extend_from_slice(&32u16.to_le_bytes());
+    data.extend_from_slice(&0u32.to_le_bytes());
+    data.extend_from_slice(&((pixel_bytes + mask_bytes) as u32).to_le_bytes());
+    data.extend_from_slice(&[0; 16]);
+
+    for y in (0..height).rev() {
+        for x in 0..width {
+            match frame.get_pixel(x, y).0 {
+                [_, _, _, 0] => data.extend_from_slice(&[0; 4]),
+                [r, g, b, a] => data.extend_from_slice(&[b, g, r, a]),
+            }
+        }
+    }
+    for y in (0..height).rev() {
+        let mut row = vec![0u8; mask_stride];
+        for x in 0..width {
+            if frame.get_pixel(x, y).0[3] == 0 {
+                row[x as usize / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        data.extend_from_slice(&row);
+    }
+    data
+}
+
+fn write_ico(
+    source: &IconSource,
+    output_path: &Path,
+    sizes: &[u32],
+    style: &FrameStyle,
+    bmp_small: bool,
+) -> Result<()> {
     ensure_parent_dir(output_path)?;
 
     let mut frames: Vec<IcoFrame<'static>> = Vec::with_capacity(sizes.len());
     for &size in sizes {
-        let frame = build_icon_frame(source, size);
-        let ico_frame = IcoFrame::as_png(frame.as_raw(), size, size, ExtendedColorType::Rgba8)
-            .with_context(|| format!("failed to encode ICO frame {size}x{size}"))?;
+        let frame = build_frame(source, size, style)?;
+        let ico_frame = if bmp_small && size <= BMP_FRAME_MAX_SIZE {
+            IcoFrame::with_encoded(
+                encode_bmp_frame(&frame),
+                size,
+                size,
+                ExtendedColorType::Rgba8,
+            )
+        } else {
+            IcoFrame::as_png(frame.as_raw(), size, size, ExtendedColorType::Rgba8)
+        }
+        .with_context(|| format!("failed to encode ICO frame {size}x{size}"))?;
         frames.push(ico_frame);
     }
 
@@ -304,12 +824,17 @@ fn write_ico(source: &RgbaImage, output_path: &Path, sizes: &[u32]) -> Result<()
     Ok(())
 }
 
-fn write_icns(source: &RgbaImage, output_path: &Path, sizes: &[u32]) -> Result<()> {
+fn write_icns(
+    source: &IconSource,
+    output_path: &Path,
+    sizes: &[u32],
+    style: &FrameStyle,
+) -> Result<()> {
     ensure_parent_dir(output_path)?;
 
     let mut family = IconFamily::new();
     for &size in sizes {
-        let frame = build_icon_frame(source, size);
+        let frame = build_frame(source, size, style)?;
         let image = IcnsImage::from_data(PixelFormat::RGBA, size, size, frame.into_raw())
             .with_context(|| format!("failed to prepare ICNS frame {size}x{size}"))?;
         family
@@ -332,26 +857,180 @@ fn write_icns(source: &RgbaImage, output_path: &Path, sizes: &[u32]) -> Result<(
     Ok(())
 }
 
-fn run() -> Result<()> {
-    let cfg = parse_args()?;
+/// The color of the opaque plate the artwork is drawn on, if there is one: a color that
+/// covers most opaque pixels while other colors still cover a noticeable share.
+fn detect_template_plate(frame: &RgbaImage) -> Option<Rgba<u8>> {
+    let mut counts = std::collections::HashMap::<[u8; 3], (usize, Rgba<u8>)>::new();
+    let mut opaque = 0usize;
+    for pixel in frame.pixels().filter(|pixel| pixel.0[3] == 255) {
+        opaque += 1;
+        let [r, g, b, _] = pixel.0;
+        counts
+            .entry([r >> 4, g >> 4, b >> 4])
+            .or_insert((0, *pixel))
+            .0 += 1;
+    }
+    let (plate_count, plate) = counts.into_values().max_by_key(|(count, _)| *count)?;
+    let rest = opaque - plate_count;
+    (plate_count * 2 > opaque && rest * 10 >= opaque).then_some(plate)
+}
 
-    let source = image::open(&cfg.input)
-        .with_context(|| format!("failed to open input image: {}", cfg.input.display()))?
-        .into_rgba8();
+/// Recolors a frame to `tint`, keeping only how strongly each pixel is inked.
+fn build_template_frame(frame: &RgbaImage, plate: Option<Rgba<u8>>, tint: Rgba<u8>) -> RgbaImage {
+    RgbaImage::from_fn(frame.width(), frame.height(), |x, y| {
+        let [r, g, b, a] = frame.get_pixel(x, y).0;
+        let contrast = match plate {
+            Some(Rgba([plate_r, plate_g, plate_b, _])) => {
+                let difference = r
+                    .abs_diff(plate_r)
+                    .max(g.abs_diff(plate_g))
+                    .max(b.abs_diff(plate_b));
+                (f32::from(difference) / TEMPLATE_CONTRAST_THRESHOLD).min(1.0)
+            }
+            None => 1.0,
+        };
+        let alpha = f32::from(a) * contrast * f32::from(tint.0[3]) / 255.0;
+        Rgba([tint.0[0], tint.0[1], tint.0[2], alpha.round() as u8])
+    })
+}
 
-    if source.width() == 0 || source.height() == 0 {
-        bail!(
-            "input image has invalid dimensions: {}x{}",
-            source.width(),
-            source.height()
+fn write_templates(
+    source: &IconSource,
+    output_dir: &Path,
+    app_name: &str,
+    overlay_color: Rgba<u8>,
+) -> Result<()> {
+    let plain = FrameStyle::default();
+    let plate = detect_template_plate(&build_frame(source, TEMPLATE_ANALYSIS_SIZE, &plain)?);
+
+    for (size, suffix) in MACOS_TEMPLATE_SIZES {
+        let output_path = output_dir.join(format!("{app_name}Template{suffix}.png"));
+        ensure_parent_dir(&output_path)?;
+        let frame = build_frame(source, size, &plain)?;
+        build_template_frame(&frame, plate, Rgba([0, 0, 0, 255]))
+            .save_with_format(&output_path, ImageFormat::Png)
+            .with_context(|| format!("failed to write PNG file: {}", output_path.display()))?;
+        println!("Generated template: {}", output_path.display());
+    }
+
+    let output_path = output_dir.join(format!("{app_name}-overlay.ico"));
+    ensure_parent_dir(&output_path)?;
+    let mut frames: Vec<IcoFrame<'static>> = Vec::with_capacity(OVERLAY_ICO_SIZES.len());
+    for size in OVERLAY_ICO_SIZES {
+        let frame = build_template_frame(&build_frame(source, size, &plain)?, plate, overlay_color);
+        frames.push(
+            IcoFrame::with_encoded(
+                encode_bmp_frame(&frame),
+                size,
+                size,
+                ExtendedColorType::Rgba8,
+            )
+            .with_context(|| format!("failed to encode overlay frame {size}x{size}"))?,
         );
     }
+    let file = File::create(&output_path)
+        .with_context(|| format!("failed to create output file: {}", output_path.display()))?;
+    IcoEncoder::new(file)
+        .encode_images(&frames)
+        .with_context(|| format!("failed to write ICO file: {}", output_path.display()))?;
+    println!(
+        "Generated overlay ICO: {} (sizes: {})",
+        output_path.display(),
+        join_sizes(&OVERLAY_ICO_SIZES)
+    );
+    Ok(())
+}
+
+fn write_linux(
+    source: &IconSource,
+    input_path: &Path,
+    share_dir: &Path,
+    sizes: &[u32],
+    style: &FrameStyle,
+    app_name: &str,
+    display_name: &str,
+) -> Result<()> {
+    let hicolor_dir = share_dir.join("icons").join("hicolor");
+    for &size in sizes {
+        let output_path = hicolor_dir
+            .join(format!("{size}x{size}"))
+            .join("apps")
+            .join(format!("{app_name}.png"));
+        ensure_parent_dir(&output_path)?;
+        build_frame(source, size, style)?
+            .save_with_format(&output_path, ImageFormat::Png)
+            .with_context(|| format!("failed to write PNG file: {}", output_path.display()))?;
+    }
+    // The scalable copy is the untouched source, so it only matches the PNGs when no
+    // padding, background or radius is applied. Icon themes only look for `.svg`, so a
+    // gzipped `.svgz` source is decompressed.
+    if matches!(source, IconSource::Vector(_)) && style.is_plain() {
+        let output_path = hicolor_dir
+            .join("scalable")
+            .join("apps")
+            .join(format!("{app_name}.svg"));
+        ensure_parent_dir(&output_path)?;
+        let data = std::fs::read(input_path)
+            .with_context(|| format!("failed to read input SVG: {}", input_path.display()))?;
+        let svg = if data.starts_with(&[0x1f, 0x8b]) {
+            usvg::decompress_svgz(&data).with_context(|| {
+                format!("failed to decompress input SVG: {}", input_path.display())
+            })?
+        } else {
+            data
+        };
+        std::fs::write(&output_path, svg)
+            .with_context(|| format!("failed to write SVG file: {}", output_path.display()))?;
+    }
+    println!(
+        "Generated hicolor icons: {} (sizes: {})",
+        hicolor_dir.display(),
+        join_sizes(sizes)
+    );
+
+    let desktop_path = share_dir
+        .join("applications")
+        .join(format!("{app_name}.desktop"));
+    ensure_parent_dir(&desktop_path)?;
+    let desktop_entry = DESKTOP_ENTRY_TEMPLATE
+        .replace("{app_name}", app_name)
+        .replace("{display_name}", display_name);
+    std::fs::write(&desktop_path, desktop_entry)
+        .with_context(|| format!("failed to write desktop file: {}", desktop_path.display()))?;
+    println!("Generated desktop entry: {}", desktop_path.display());
+    Ok(())
+}
+
+fn run() -> Result<()> {
+    let cfg = parse_args()?;
+
+    let source = load_source(&cfg.input)?;
 
     if let Some(ico_output) = cfg.ico_output.as_deref() {
-        write_ico(&source, ico_output, &cfg.ico_sizes)?;
+        write_ico(
+            &source,
+            ico_output,
+            &cfg.ico_sizes,
+            &cfg.style,
+            cfg.bmp_small,
+        )?;
     }
     if let Some(icns_output) = cfg.icns_output.as_deref() {
-        write_icns(&source, icns_output, &cfg.icns_sizes)?;
+        write_icns(&source, icns_output, &cfg.icns_sizes, &cfg.style)?;
+    }
+    if let Some(template_output) = cfg.template_output.as_deref() {
+        write_templates(&source, template_output, &cfg.app_name, cfg.template_color)?;
+    }
+    if let Some(linux_output) = cfg.linux_output.as_deref() {
+        write_linux(
+            &source,
+            &cfg.input,
+            linux_output,
+            &cfg.linux_sizes,
+            &cfg.style,
+            &cfg.app_name,
+            &cfg.display_name,
+        )?;
     }
     Ok(())
 }
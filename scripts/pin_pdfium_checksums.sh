@@ -0,0 +1,60 @@
+#!/usr/bin/env bash
+set -euo pipefail
+
+REPO="bblanchon/pdfium-binaries"
+ASSETS=(
+  pdfium-mac-x64.tgz
+  pdfium-mac-arm64.tgz
+  pdfium-linux-x64.tgz
+  pdfium-linux-arm64.tgz
+  pdfium-linux-x86.tgz
+  pdfium-linux-musl-x64.tgz
+  pdfium-linux-musl-arm64.tgz
+  pdfium-linux-musl-x86.tgz
+  pdfium-win-x64.tgz
+  pdfium-win-arm64.tgz
+  pdfium-win-x86.tgz
+)
+
+SCRIPT_DIR="$(cd -- "$(dirname -- "${BASH_SOURCE[0]}")" && pwd)"
+ROOT_DIR="$(cd -- "${SCRIPT_DIR}/.." && pwd)"
+OUTPUT="${ROOT_DIR}/crates/kpdf-core/pdfium_checksums.sha256"
+
+die() {
+  printf 'Error: %s\n' "$*" >&2
+  exit 1
+}
+
+sha256_of() {
+  if command -v sha256sum >/dev/null 2>&1; then
+    sha256sum "$1" | cut -d' ' -f1
+  else
+    shasum -a 256 "$1" | cut -d' ' -f1
+  fi
+}
+
+[[ $# -eq 1 ]] || die "Usage: pin_pdfium_checksums.sh <release-tag, e.g. chromium/7350>"
+TAG="$1"
+TAG_ENCODED="${TAG//\//%2F}"
+
+TMP_DIR="$(mktemp -d)"
+trap 'rm -rf "${TMP_DIR}"' EXIT
+
+{
+  printf '# SHA-256 sums of the pdfium-binaries archives kPDF downloads in-app.\n'
+  printf '# Regenerate with: scripts/pin_pdfium_checksums.sh <release-tag>\n'
+  printf '# Archives without an entry here are refused.\n'
+  printf 'release %s\n' "${TAG}"
+  for asset in "${ASSETS[@]}"; do
+    url="https://github.com/${REPO}/releases/download/${TAG_ENCODED}/${asset}"
+    printf 'Fetching %s\n' "${asset}" >&2
+    if curl -fsSL --retry 3 --retry-delay 1 -A "kpdf-pdfium-pin/1.0" "${url}" -o "${TMP_DIR}/${asset}"; then
+      printf '%s  %s\n' "$(sha256_of "${TMP_DIR}/${asset}")" "${asset}"
+    else
+      printf 'Skipped (not published): %s\n' "${asset}" >&2
+    fi
+  done
+} > "${TMP_DIR}/checksums"
+
+mv "${TMP_DIR}/checksums" "${OUTPUT}"
+printf 'Wrote %s\n' "${OUTPUT}"
@@ -0,0 +1,375 @@
+use crate::i18n::Language;
+use crate::pdf_viewer::{export_thumbnails, merge_documents, print_document, split_document};
+use anyhow::{Result, anyhow, bail};
+use kpdf_core::edit::{PageSpan, parse_page_spans};
+use std::path::PathBuf;
+
+const USAGE: &str = "Usage:
+  kpdf merge <input.pdf>... -o <output.pdf> [--force]
+  kpdf split <input.pdf> --every <pages> [-o <output-dir>] [--force]
+  kpdf print <file> [--printer <name>] [--pages <1-3,5>]
+  kpdf thumbs <file-or-dir>... -o <output-dir> [--width <px>] [--all-pages]";
+
+const DEFAULT_THUMBNAIL_WIDTH: u32 = 256;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Merge {
+        inputs: Vec<PathBuf>,
+        output: PathBuf,
+        force: bool,
+    },
+    Split {
+        input: PathBuf,
+        every: usize,
+        output_dir: Option<PathBuf>,
+        force: bool,
+    },
+    Print {
+        input: PathBuf,
+        printer: Option<String>,
+        pages: Vec<PageSpan>,
+    },
+    Thumbs {
+        inputs: Vec<PathBuf>,
+        output_dir: PathBuf,
+        width: u32,
+        all_pages: bool,
+    },
+}
+
+/// Runs a subcommand when the first argument names one and returns the exit code;
+/// `None` means the GUI should start as usual.
+pub fn run_from_args() -> Option<i32> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if !matches!(
+        args.first().map(String::as_str),
+        Some("merge" | "split" | "print" | "thumbs")
+    ) {
+        return None;
+    }
+    attach_parent_console();
+
+    let result = parse(&args).and_then(|command| execute(command, Language::detect()));
+    Some(match result {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("kpdf: {err:#}");
+            eprintln!("{USAGE}");
+            1
+        }
+    })
+}
+
+/// A release build on Windows is a GUI program without a console, so its output would be lost.
+/// Writing to the console of the shell that started it makes `println!` reach the terminal.
+#[cfg(target_os = "windows")]
+fn attach_parent_console() {
+    use windows::Win32::System::Console::{ATTACH_PARENT_PROCESS, AttachConsole};
+
+    // Fails when there is no parent console or one is already attached; both are fine.
+    let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_parent_console() {}
+
+/// Files to open in the viewer, given as plain arguments (`kpdf a.pdf b.pdf`). This is how
+/// shell integrations such as Explorer's "Open with kPDF" launch the app.
+pub fn launch_paths() -> Vec<PathBuf> {
+    std::env::args_os()
+        .skip(1)
+        .map(PathBuf::from)
+        .filter(|path| path.is_file())
+        .map(|path| path.canonicalize().unwrap_or(path))
+        .collect()
+}
+
+fn parse(args: &[String]) -> Result<Command> {
+    let (subcommand, rest) = args
+        .split_first()
+        .ok_or_else(|| anyhow!("missing command"))?;
+    let mut positional = Vec::new();
+    let mut output = None;
+    let mut every = None;
+    let mut printer = None;
+    let mut pages = Vec::new();
+    let mut width = DEFAULT_THUMBNAIL_WIDTH;
+    let mut all_pages = false;
+    let mut force = false;
+    let mut rest = rest.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "-o" | "--output" => {
+                let value = rest
+                    .next()
+                    .ok_or_else(|| anyhow!("missing value for {arg}"))?;
+                output = Some(PathBuf::from(value));
+            }
+            "--every" if subcommand == "split" => {
+                let value = rest
+                    .next()
+                    .ok_or_else(|| anyhow!("missing value for {arg}"))?;
+                every = Some(
+                    value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|every| *every > 0)
+                        .ok_or_else(|| anyhow!("invalid page count: {value}"))?,
+                );
+            }
+            "--printer" if subcommand == "print" => {
+                let value = rest
+                    .next()
+                    .ok_or_else(|| anyhow!("missing value for {arg}"))?;
+                printer = Some(value.clone());
+            }
+            "--pages" if subcommand == "print" => {
+                let value = rest
+                    .next()
+                    .ok_or_else(|| anyhow!("missing value for {arg}"))?;
+                pages = parse_page_spans(value)
+                    .map_err(|part| anyhow!("invalid page range: {part}"))?;
+            }
+            "--width" if subcommand == "thumbs" => {
+                let value = rest
+                    .next()
+                    .ok_or_else(|| anyhow!("missing value for {arg}"))?;
+                width = value
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|width| *width > 0)
+                    .ok_or_else(|| anyhow!("invalid width: {value}"))?;
+            }
+            "--all-pages" if subcommand == "thumbs" => all_pages = true,
+            "--force" if matches!(subcommand.as_str(), "merge" | "split") => force = true,
+            flag if flag.starts_with('-') && flag.len() > 1 => bail!("unknown option: {flag}"),
+            path => positional.push(PathBuf::from(path)),
+        }
+    }
+
+    match subcommand.as_str() {
+        "merge" => {
+            if positional.len() < 2 {
+                bail!("merge needs at least two input files");
+            }
+            Ok(Command::Merge {
+                inputs: positional,
+                output: output.ok_or_else(|| anyhow!("merge needs -o <output.pdf>"))?,
+                force,
+            })
+        }
+        "split" => {
+            let [input] = <[PathBuf; 1]>::try_from(positional)
+                .map_err(|_| anyhow!("split takes exactly one input file"))?;
+            Ok(Command::Split {
+                input,
+                every: every.ok_or_else(|| anyhow!("split needs --every <pages>"))?,
+                output_dir: output,
+                force,
+            })
+        }
+        "print" => {
+            let [input] = <[PathBuf; 1]>::try_from(positional)
+                .map_err(|_| anyhow!("print takes exactly one input file"))?;
+            Ok(Command::Print {
+                input,
+                printer,
+                pages,
+            })
+        }
+        "thumbs" => {
+            if positional.is_empty() {
+                bail!("thumbs needs at least one file or directory");
+            }
+            Ok(Command::Thumbs {
+                inputs: positional,
+                output_dir: output.ok_or_else(|| anyhow!("thumbs needs -o <output-dir>"))?,
+                width,
+                all_pages,
+            })
+        }
+        other => bail!("unknown command: {other}"),
+    }
+}
+
+fn execute(command: Command, language: Language) -> Result<()> {
+    match command {
+        Command::Merge {
+            inputs,
+            output,
+            force,
+        } => {
+            if output.exists() && !force {
+                bail!("{} already exists", output.display());
+            }
+            merge_documents(&inputs, &output, language, |_, _| {})?;
+            println!("{}", output.display());
+        }
+        Command::Split {
+            input,
+            every,
+            output_dir,
+            force,
+        } => {
+            let output_dir = output_dir
+                .or_else(|| input.parent().map(PathBuf::from))
+                .unwrap_or_default();
+            for path in split_document(&input, every, &output_dir, force, language)? {
+                println!("{}", path.display());
+            }
+        }
+        Command::Print {
+            input,
+            printer,
+            pages,
+        } => print_document(&input, printer.as_deref(), &pages, language)?,
+        Command::Thumbs {
+            inputs,
+            output_dir,
+            width,
+            all_pages,
+        } => {
+            let results = export_thumbnails(&inputs, width, all_pages, &output_dir, language)?;
+            let mut failed = 0;
+            for result in &results {
+                match result {
+                    Ok(path) => println!("{}", path.display()),
+                    Err(err) => {
+                        eprintln!("kpdf: {err:#}");
+                        failed += 1;
+                    }
+                }
+            }
+            if failed > 0 {
+                bail!("{failed} of {} thumbnails failed", results.len());
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_merge() {
+        assert_eq!(
+            parse(&args(&["merge", "a.pdf", "b.pdf", "-o", "out.pdf"])).unwrap(),
+            Command::Merge {
+                inputs: vec!["a.pdf".into(), "b.pdf".into()],
+                output: "out.pdf".into(),
+                force: false,
+            }
+        );
+        assert_eq!(
+            parse(&args(&[
+                "merge", "a.pdf", "b.pdf", "-o", "out.pdf", "--force"
+            ]))
+            .unwrap(),
+            Command::Merge {
+                inputs: vec!["a.pdf".into(), "b.pdf".into()],
+                output: "out.pdf".into(),
+                force: true,
+            }
+        );
+        assert!(parse(&args(&["merge", "a.pdf", "b.pdf"])).is_err());
+        assert!(parse(&args(&["merge", "a.pdf", "-o", "out.pdf"])).is_err());
+    }
+
+    #[test]
+    fn parse_split() {
+        assert_eq!(
+            parse(&args(&["split", "in.pdf", "--every", "10"])).unwrap(),
+            Command::Split {
+                input: "in.pdf".into(),
+                every: 10,
+                output_dir: None,
+                force: false,
+            }
+        );
+        assert!(parse(&args(&["split", "in.pdf", "--every", "0"])).is_err());
+        assert!(parse(&args(&["split", "in.pdf"])).is_err());
+        assert!(parse(&args(&["split", "a.pdf", "b.pdf", "--every", "2"])).is_err());
+        assert!(parse(&args(&["print", "in.pdf", "--force"])).is_err());
+    }
+
+    #[test]
+    fn parse_print() {
+        assert_eq!(
+            parse(&args(&[
+                "print",
+                "in.pdf",
+                "--printer",
+                "Office",
+                "--pages",
+                "1-3, 5"
+            ]))
+            .unwrap(),
+            Command::Print {
+                input: "in.pdf".into(),
+                printer: Some("Office".into()),
+                pages: vec![
+                    PageSpan {
+                        first: 1,
+                        last: Some(3)
+                    },
+                    PageSpan {
+                        first: 5,
+                        last: Some(5)
+                    },
+                ],
+            }
+        );
+        assert_eq!(
+            parse(&args(&["print", "in.pdf"])).unwrap(),
+            Command::Print {
+                input: "in.pdf".into(),
+                printer: None,
+                pages: Vec::new(),
+            }
+        );
+        assert!(parse(&args(&["print", "in.pdf", "--pages", "3-1"])).is_err());
+        assert!(parse(&args(&["print", "in.pdf", "--pages", "0"])).is_err());
+        assert!(parse(&args(&["print"])).is_err());
+    }
+
+    #[test]
+    fn parse_thumbs() {
+        assert_eq!(
+            parse(&args(&["thumbs", "books", "a.pdf", "-o", "out"])).unwrap(),
+            Command::Thumbs {
+                inputs: vec!["books".into(), "a.pdf".into()],
+                output_dir: "out".into(),
+                width: DEFAULT_THUMBNAIL_WIDTH,
+                all_pages: false,
+            }
+        );
+        assert_eq!(
+            parse(&args(&[
+                "thumbs",
+                "a.pdf",
+                "--width",
+                "512",
+                "--all-pages",
+                "-o",
+                "out"
+            ]))
+            .unwrap(),
+            Command::Thumbs {
+                inputs: vec!["a.pdf".into()],
+                output_dir: "out".into(),
+                width: 512,
+                all_pages: true,
+            }
+        );
+        assert!(parse(&args(&["thumbs", "a.pdf"])).is_err());
+        assert!(parse(&args(&["thumbs", "-o", "out"])).is_err());
+        assert!(parse(&args(&["thumbs", "a.pdf", "-o", "out", "--width", "0"])).is_err());
+    }
+}
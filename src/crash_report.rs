@@ -0,0 +1,194 @@
+use std::backtrace::Backtrace;
+use std::fmt::Write as _;
+use std::fs::{create_dir_all, read_to_string, remove_file, write};
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Names the report the last run left behind; removed once it has been offered.
+const PENDING_MARKER_FILE_NAME: &str = "pending";
+/// GitHub rejects issue links much longer than this; counted after percent-encoding.
+const ISSUE_BODY_MAX_ENCODED_BYTES: usize = 6000;
+const ISSUE_BODY_FENCE: &str = "```\n";
+
+/// Hashed names of the open files, kept current so the panic hook never touches app state.
+static OPEN_FILE_HASHES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn crash_dir() -> Option<PathBuf> {
+    crate::logger::log_file_path()?
+        .parent()
+        .map(|dir| dir.join("crashes"))
+}
+
+/// Chains a hook that writes a crash report before the default one runs.
+pub fn install() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        match write_report(info) {
+            Some(path) => eprintln!("[crash] report written to {}", path.display()),
+            None => eprintln!("[crash] failed to write crash report"),
+        }
+        default_hook(info);
+    }));
+}
+
+/// Records the files open in the viewer; only a hash of each file name ends up in reports.
+pub fn set_open_files<'a>(paths: impl IntoIterator<Item = &'a Path>) {
+    let hashes = paths
+        .into_iter()
+        .filter_map(|path| path.file_name())
+        .map(|name| {
+            kpdf_core::fetch::sha256_hex(name.to_string_lossy().as_bytes())[..16].to_string()
+        })
+        .collect();
+    if let Ok(mut open_files) = OPEN_FILE_HASHES.lock() {
+        *open_files = hashes;
+    }
+}
+
+/// The report written by the previous run's crash, if it has not been offered yet.
+pub fn take_pending_report() -> Option<PathBuf> {
+    let marker = crash_dir()?.join(PENDING_MARKER_FILE_NAME);
+    let report = PathBuf::from(read_to_string(&marker).ok()?.trim());
+    let _ = remove_file(&marker);
+    report.is_file().then_some(report)
+}
+
+/// A new-issue link on the repository with the report as its body.
+pub fn issue_url(report: &Path) -> Option<String> {
+    let contents = read_to_string(report).ok()?;
+    let fences_len = 2 * encoded_len(ISSUE_BODY_FENCE);
+    let body = format!(
+        "{ISSUE_BODY_FENCE}{}\n```",
+        truncate_to_encoded_len(
+            &contents,
+            ISSUE_BODY_MAX_ENCODED_BYTES.saturating_sub(fences_len)
+        )
+    );
+    let title = format!("Crash report (kPDF {})", env!("CARGO_PKG_VERSION"));
+    Some(format!(
+        "{}/issues/new?title={}&body={}",
+        crate::APP_REPOSITORY_URL,
+        percent_encode(&title),
+        percent_encode(&body)
+    ))
+}
+
+fn write_report(info: &PanicHookInfo<'_>) -> Option<PathBuf> {
+    let dir = crash_dir()?;
+    create_dir_all(&dir).ok()?;
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let report_path = dir.join(format!("crash-{timestamp}.txt"));
+
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "<non-string panic payload>".to_string());
+    let location = info
+        .location()
+        .map(|location| {
+            format!(
+                "{}:{}:{}",
+                location.file(),
+                location.line(),
+                location.column()
+            )
+        })
+        .unwrap_or_else(|| "<unknown>".to_string());
+    let thread = std::thread::current();
+
+    let mut report = String::new();
+    let _ = writeln!(report, "kPDF crash report");
+    let _ = writeln!(report, "version: {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(
+        report,
+        "os: {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let _ = writeln!(report, "time: {timestamp}");
+    let _ = writeln!(report, "thread: {}", thread.name().unwrap_or("<unnamed>"));
+    let _ = writeln!(report, "message: {message}");
+    let _ = writeln!(report, "location: {location}");
+    // A panic while the list is locked must not stop the report from being written.
+    if let Ok(open_files) = OPEN_FILE_HASHES.try_lock() {
+        let _ = writeln!(report, "open files (hashed names): {}", open_files.len());
+        for hash in open_files.iter() {
+            let _ = writeln!(report, "  {hash}");
+        }
+    }
+    let _ = writeln!(report, "\nbacktrace:\n{}", Backtrace::force_capture());
+
+    write(&report_path, report).ok()?;
+    let _ = write(
+        dir.join(PENDING_MARKER_FILE_NAME),
+        report_path.to_string_lossy().as_bytes(),
+    );
+    Some(report_path)
+}
+
+/// The longest prefix of `text`, cut at a char boundary, that stays within
+/// `max_encoded_bytes` once percent-encoded.
+fn truncate_to_encoded_len(text: &str, max_encoded_bytes: usize) -> &str {
+    let mut total = 0;
+    for (index, ch) in text.char_indices() {
+        total += encoded_len(ch.encode_utf8(&mut [0; 4]));
+        if total > max_encoded_bytes {
+            return &text[..index];
+        }
+    }
+    text
+}
+
+fn is_unreserved(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~')
+}
+
+fn encoded_len(text: &str) -> usize {
+    text.bytes()
+        .map(|byte| if is_unreserved(byte) { 1 } else { 3 })
+        .sum()
+}
+
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        if is_unreserved(byte) {
+            encoded.push(byte as char);
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_encode_reserved_characters() {
+        assert_eq!(percent_encode("kPDF 0.13.0"), "kPDF%200.13.0");
+        assert_eq!(percent_encode("a&b=c\n"), "a%26b%3Dc%0A");
+        assert_eq!(percent_encode("页"), "%E9%A1%B5");
+    }
+
+    #[test]
+    fn truncate_counts_encoded_bytes_at_char_boundaries() {
+        assert_eq!(truncate_to_encoded_len("abc", 10), "abc");
+        assert_eq!(truncate_to_encoded_len("abc", 2), "ab");
+        assert_eq!(truncate_to_encoded_len("a b", 3), "a");
+        assert_eq!(truncate_to_encoded_len("a页", 9), "a");
+        assert_eq!(truncate_to_encoded_len("a页", 10), "a页");
+
+        let report = "页\n".repeat(2000);
+        let kept = truncate_to_encoded_len(&report, 6000);
+        assert!(percent_encode(kept).len() <= 6000);
+        assert_eq!(encoded_len(kept), percent_encode(kept).len());
+    }
+}
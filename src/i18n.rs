@@ -7,6 +7,11 @@ use std::sync::OnceLock;
 pub enum Language {
     ZhCn,
     EnUs,
+    JaJp,
+    KoKr,
+    DeDe,
+    EsEs,
+    ArSa,
 }
 
 impl Language {
@@ -34,13 +39,43 @@ impl Language {
             return Self::ZhCn;
         }
 
-        Self::EnUs
+        match tag.split(['-', '_', '.', '@']).next().unwrap_or_default() {
+            "ja" => Self::JaJp,
+            "ko" => Self::KoKr,
+            "de" => Self::DeDe,
+            "es" => Self::EsEs,
+            "ar" => Self::ArSa,
+            _ => Self::EnUs,
+        }
+    }
+
+    /// Whether the UI is laid out right to left.
+    pub fn is_rtl(self) -> bool {
+        match self {
+            Self::ArSa => true,
+            Self::ZhCn | Self::EnUs | Self::JaJp | Self::KoKr | Self::DeDe | Self::EsEs => false,
+        }
+    }
+
+    /// Whether `count` takes the singular form. Chinese, Japanese and Korean do not inflect
+    /// for number, so they always use the plural template. Arabic also has dual and few forms,
+    /// which fall back to the plural template.
+    fn is_singular(self, count: u64) -> bool {
+        match self {
+            Self::EnUs | Self::DeDe | Self::EsEs | Self::ArSa => count == 1,
+            Self::ZhCn | Self::JaJp | Self::KoKr => false,
+        }
     }
 
     fn file_name(self) -> &'static str {
         match self {
             Self::ZhCn => "zh_CN.json",
             Self::EnUs => "en_US.json",
+            Self::JaJp => "ja_JP.json",
+            Self::KoKr => "ko_KR.json",
+            Self::DeDe => "de_DE.json",
+            Self::EsEs => "es_ES.json",
+            Self::ArSa => "ar_SA.json",
         }
     }
 }
@@ -62,9 +97,13 @@ macro_rules! locale_message_fields {
             bookmark_added_unknown,
             bookmark_added_relative_just_now,
             bookmark_added_relative_minutes,
+            bookmark_added_relative_minutes_one,
             bookmark_added_relative_hours,
+            bookmark_added_relative_hours_one,
             bookmark_added_relative_days,
+            bookmark_added_relative_days_one,
             bookmark_notes_count_label,
+            bookmark_notes_count_label_one,
             open_logs_button,
             enable_logging_button,
             disable_logging_button,
@@ -91,6 +130,11 @@ macro_rules! locale_message_fields {
             settings_language_system,
             settings_language_zh_cn,
             settings_language_en_us,
+            settings_language_ja_jp,
+            settings_language_ko_kr,
+            settings_language_de_de,
+            settings_language_es_es,
+            settings_language_ar_sa,
             settings_db_section,
             settings_db_usage_label,
             settings_db_usage_hint,
@@ -182,10 +226,7 @@ macro_rules! locale_message_fields {
             action_toggle_recent_files,
             pdfium_not_found,
             cannot_open_file,
-            pdfium_cache_lock_poisoned,
             pdfium_cannot_open_file,
-            invalid_bitmap_size,
-            bitmap_len_mismatch,
             copy_button,
             text_markup_highlight_button,
             text_markup_underline_button,
@@ -209,6 +250,389 @@ macro_rules! locale_message_fields {
             reveal_in_file_manager_finder,
             reveal_in_file_manager_explorer,
             reveal_in_file_manager_default,
+            view_menu,
+            command_panel_toggle_thumbnail_panel_hint,
+            settings_thumbnail_panel_label,
+            settings_thumbnail_panel_hint,
+            sidebar_tab_thumbnails,
+            sidebar_tab_outline,
+            sidebar_tab_bookmarks,
+            sidebar_tab_annotations,
+            sidebar_tab_search,
+            sidebar_outline_loading,
+            sidebar_outline_empty,
+            sidebar_annotations_empty,
+            sidebar_search_placeholder,
+            sidebar_search_searching,
+            sidebar_search_no_results,
+            sidebar_search_result_count,
+            sidebar_search_result_count_one,
+            action_toggle_page_overview,
+            command_panel_toggle_page_overview_hint,
+            action_toggle_status_bar,
+            command_panel_toggle_status_bar_hint,
+            status_bar_page,
+            status_bar_page_with_label,
+            status_bar_selection_chars,
+            status_bar_renders_in_flight,
+            status_bar_idle,
+            load_error_dialog_title,
+            load_error_file_label,
+            load_error_details_label,
+            load_error_retry_button,
+            load_error_open_folder_button,
+            load_error_remove_recent_button,
+            load_error_repair_button,
+            load_error_repairing,
+            load_error_repair_failed,
+            load_error_summary,
+            load_error_show_details_button,
+            page_render_retry_button,
+            diagnostics_button,
+            diagnostics_dialog_title,
+            diagnostics_library_path_label,
+            diagnostics_library_system,
+            diagnostics_version_label,
+            diagnostics_version_unknown,
+            diagnostics_status_label,
+            diagnostics_status_loaded,
+            diagnostics_status_checking,
+            diagnostics_search_dirs_label,
+            diagnostics_render_errors_label,
+            diagnostics_render_errors_empty,
+            diagnostics_render_error_age,
+            diagnostics_redownload_button,
+            diagnostics_relocate_button,
+            diagnostics_relocate_prompt,
+            diagnostics_relocate_done,
+            diagnostics_relocate_failed,
+            diagnostics_refresh_button,
+            settings_rendering_section,
+            settings_render_workers_label,
+            settings_render_workers_hint,
+            settings_render_cache_label,
+            settings_render_cache_hint,
+            settings_render_cache_usage_label,
+            settings_render_cache_clear_button,
+            page_render_resolution_reduced,
+            status_bar_repaired,
+            page_preview_unavailable,
+            settings_render_engine_label,
+            settings_render_engine_hint,
+            diagnostics_mirror_label,
+            diagnostics_mirror_placeholder,
+            diagnostics_proxy_label,
+            diagnostics_proxy_placeholder,
+            diagnostics_download_cancel_button,
+            diagnostics_download_progress,
+            diagnostics_download_done,
+            diagnostics_download_failed,
+            settings_pdfium_library_label,
+            settings_pdfium_library_hint,
+            settings_pdfium_library_automatic,
+            settings_pdfium_library_automatic_hint,
+            settings_pdfium_library_choose_button,
+            settings_pdfium_library_prompt,
+            command_panel_plugin_badge,
+            settings_external_tools_section,
+            settings_external_tools_hint,
+            settings_external_tool_name_placeholder,
+            settings_external_tool_command_placeholder,
+            settings_external_tool_add_button,
+            settings_external_tool_remove_button,
+            open_with_tool,
+            settings_render_quality_label,
+            settings_render_quality_hint,
+            settings_text_antialiasing_label,
+            settings_text_antialiasing_hint,
+            settings_text_antialiasing_grayscale,
+            settings_text_antialiasing_lcd,
+            settings_text_antialiasing_off,
+            settings_native_text_label,
+            settings_native_text_hint,
+            action_toggle_grayscale,
+            command_panel_toggle_grayscale_hint,
+            settings_ui_font_size_label,
+            settings_ui_font_size_hint,
+            settings_ui_density_label,
+            settings_ui_density_hint,
+            settings_ui_density_comfortable,
+            settings_ui_density_compact,
+            settings_accent_color_label,
+            settings_accent_color_hint,
+            settings_accent_color_apply_button,
+            settings_accent_color_reset_button,
+            settings_theme_import_button,
+            settings_theme_import_prompt,
+            action_toggle_zen_mode,
+            command_panel_toggle_zen_mode_hint,
+            settings_toolbar_hint,
+            settings_toolbar_search_label,
+            settings_toolbar_search_hint,
+            settings_toolbar_bookmark_label,
+            settings_toolbar_bookmark_hint,
+            settings_window_opacity_label,
+            settings_window_opacity_hint,
+            action_toggle_fullscreen,
+            command_panel_toggle_fullscreen_hint,
+            action_toggle_presentation,
+            command_panel_toggle_presentation_hint,
+            settings_presentation_timer_label,
+            settings_presentation_timer_hint,
+            settings_presentation_auto_advance_label,
+            settings_presentation_auto_advance_hint,
+            settings_presentation_auto_advance_off,
+            settings_presentation_pointer_label,
+            settings_presentation_pointer_hint,
+            settings_presentation_pointer_off,
+            settings_presentation_pointer_laser,
+            settings_presentation_pointer_spotlight,
+            update_status_downloaded,
+            show_in_folder_button,
+            install_update_button,
+            update_channel_stable,
+            update_channel_beta,
+            settings_network_section,
+            settings_network_hint,
+            settings_proxy_label,
+            settings_update_endpoint_label,
+            settings_update_endpoint_placeholder,
+            settings_pdfium_mirror_label,
+            settings_network_save_button,
+            settings_updates_section,
+            settings_update_check_frequency_label,
+            settings_update_check_frequency_hint,
+            settings_update_check_never,
+            settings_update_check_daily,
+            settings_update_check_weekly,
+            update_available_menu_item,
+            update_available_badge,
+            settings_logging_section,
+            settings_log_level_label,
+            settings_log_level_hint,
+            settings_log_modules_label,
+            settings_log_modules_hint,
+            settings_log_modules_apply_button,
+            settings_log_filter_invalid,
+            crash_report_prompt_title,
+            crash_report_prompt_detail,
+            crash_report_show_button,
+            crash_report_submit_button,
+            crash_report_dismiss_button,
+            command_panel_toggle_debug_overlay,
+            command_panel_toggle_debug_overlay_hint,
+            debug_overlay_title,
+            debug_overlay_fps_label,
+            debug_overlay_display_queue_label,
+            debug_overlay_thumbnail_queue_label,
+            debug_overlay_image_memory_label,
+            debug_overlay_disk_cache_label,
+            debug_overlay_recent_renders_label,
+            debug_overlay_no_renders,
+            command_panel_merge_pdfs,
+            command_panel_merge_pdfs_hint,
+            merge_dialog_title,
+            merge_dialog_hint,
+            merge_dialog_empty,
+            merge_add_files_button,
+            merge_add_files_prompt,
+            merge_button,
+            merge_open_button,
+            merge_progress,
+            merge_done,
+            merge_failed,
+            command_panel_save_optimized_copy,
+            command_panel_save_optimized_copy_hint,
+            optimize_dialog_title,
+            optimize_dialog_hint,
+            optimize_image_dpi_label,
+            optimize_image_dpi_hint,
+            optimize_image_dpi_off,
+            optimize_save_button,
+            optimize_open_button,
+            optimize_progress,
+            optimize_done,
+            optimize_failed,
+            save_copy_same_file,
+            command_panel_add_watermark,
+            command_panel_add_watermark_hint,
+            watermark_dialog_title,
+            watermark_type_label,
+            watermark_type_text,
+            watermark_type_image,
+            watermark_text_placeholder,
+            watermark_choose_image_button,
+            watermark_image_prompt,
+            watermark_no_image,
+            watermark_color_label,
+            watermark_color_gray,
+            watermark_color_red,
+            watermark_position_label,
+            watermark_position_center,
+            watermark_position_top_left,
+            watermark_position_top_right,
+            watermark_position_bottom_left,
+            watermark_position_bottom_right,
+            watermark_opacity_label,
+            watermark_rotation_label,
+            watermark_size_label,
+            watermark_pages_label,
+            watermark_pages_placeholder,
+            watermark_invalid_pages,
+            watermark_missing_content,
+            watermark_save_button,
+            watermark_open_button,
+            watermark_progress,
+            watermark_done,
+            watermark_failed,
+            command_panel_add_header_footer,
+            command_panel_add_header_footer_hint,
+            header_footer_dialog_title,
+            header_footer_header_label,
+            header_footer_footer_label,
+            header_footer_tokens_hint,
+            header_footer_alignment_label,
+            header_footer_align_left,
+            header_footer_align_center,
+            header_footer_align_right,
+            header_footer_font_size_label,
+            header_footer_pages_label,
+            header_footer_pages_placeholder,
+            header_footer_preview_label,
+            header_footer_preview_loading,
+            header_footer_preview_failed,
+            header_footer_missing_content,
+            header_footer_invalid_pages,
+            header_footer_save_button,
+            header_footer_open_button,
+            header_footer_progress,
+            header_footer_done,
+            header_footer_failed,
+            command_panel_save_with_password,
+            command_panel_save_with_password_hint,
+            encrypt_dialog_title,
+            encrypt_user_password_label,
+            encrypt_user_password_placeholder,
+            encrypt_confirm_password_placeholder,
+            encrypt_owner_password_label,
+            encrypt_owner_password_placeholder,
+            encrypt_permissions_label,
+            encrypt_allow_print,
+            encrypt_allow_copy,
+            encrypt_allow_modify,
+            encrypt_allow_annotate,
+            encrypt_missing_password,
+            encrypt_password_mismatch,
+            encrypt_save_button,
+            encrypt_saving,
+            encrypt_done,
+            encrypt_failed,
+            add_stamp_here_button,
+            delete_stamp_button,
+            stamp_dialog_title,
+            stamp_presets_label,
+            stamp_preset_approved,
+            stamp_preset_draft,
+            stamp_preset_confidential,
+            stamp_preset_rejected,
+            stamp_custom_text_label,
+            stamp_custom_text_placeholder,
+            stamp_place_text_button,
+            stamp_choose_image_button,
+            stamp_image_prompt,
+            command_panel_draw_rectangle,
+            command_panel_draw_ellipse,
+            command_panel_draw_line,
+            command_panel_draw_arrow,
+            command_panel_draw_shape_hint,
+            shape_fill_button,
+            delete_shape_button,
+            command_panel_draw_ink,
+            command_panel_eraser,
+            command_panel_stroke_eraser,
+            command_panel_eraser_hint,
+            keymap_section_annotations,
+            action_undo_annotation,
+            action_redo_annotation,
+            action_delete_selected_shape,
+            action_cancel_annotation_tool,
+            settings_annotations_section,
+            settings_annotation_author_label,
+            settings_annotation_author_placeholder,
+            settings_annotation_author_save_button,
+            settings_annotation_highlight_color_label,
+            settings_annotation_shape_color_label,
+            settings_annotation_ink_color_label,
+            command_panel_export_annotations,
+            command_panel_export_annotations_hint,
+            command_panel_import_annotations,
+            command_panel_import_annotations_hint,
+            annotation_bundle_import_prompt,
+            sidebar_outline_filter_placeholder,
+            sidebar_outline_no_matches,
+            action_follow_link,
+            go_menu,
+            command_panel_first_page_hint,
+            command_panel_last_page_hint,
+            command_panel_recent_badge,
+            search_wrapped_to_first,
+            search_wrapped_to_last,
+            sidebar_search_result_position,
+            action_next_search_result,
+            action_previous_search_result,
+            sidebar_search_highlight_all,
+            comic_rar_unsupported,
+            comic_no_pages,
+            settings_comic_right_to_left_label,
+            settings_comic_right_to_left_hint,
+            open_recent_menu,
+            clear_recent_menu,
+            settings_shell_integration_section,
+            settings_shell_open_with_label,
+            settings_shell_open_with_hint,
+            shell_open_with_verb,
+            action_quick_switch_tab,
+            tab_switcher_placeholder,
+            tab_switcher_no_matches,
+            action_navigate_back,
+            action_navigate_forward,
+            action_toggle_page_order_right_to_left,
+            command_panel_toggle_page_order_right_to_left_hint,
+            command_panel_save_bookmark_outline,
+            command_panel_save_bookmark_outline_hint,
+            command_panel_session_badge,
+            session_tab_count,
+            session_tab_count_one,
+            session_default_name,
+            command_panel_save_session,
+            command_panel_save_session_hint,
+            command_panel_delete_session,
+            command_panel_delete_session_hint,
+            settings_startup_label,
+            settings_startup_hint,
+            settings_startup_restore_tabs,
+            settings_startup_empty,
+            settings_startup_home,
+            settings_page_double_click_label,
+            settings_page_double_click_hint,
+            settings_page_double_click_nothing,
+            settings_page_double_click_fit_width,
+            settings_page_double_click_actual_size,
+            settings_page_double_click_zen_mode,
+            status_bar_opening,
+            status_bar_loading_pages,
+            settings_idle_prerender_label,
+            settings_idle_prerender_hint,
+            annotation_bundle_exported,
+            annotation_bundle_export_failed,
+            annotation_bundle_imported,
+            annotation_bundle_nothing_new,
+            annotation_bundle_import_failed,
+            bookmark_outline_none,
+            bookmark_outline_saved,
+            bookmark_outline_failed,
+            status_bar_rendering_first_pages,
+            merge_output_is_input,
             cannot_create_image_buffer,
         }
     };
@@ -216,12 +640,35 @@ macro_rules! locale_message_fields {
 
 macro_rules! define_raw_locale_messages {
     ($($field:ident),+ $(,)?) => {
-        #[derive(Debug, Deserialize)]
-        #[serde(deny_unknown_fields)]
+        /// One locale file. Keys it leaves out are taken from the next file in the chain.
+        #[derive(Debug, Default, Deserialize)]
         struct RawLocaleMessages {
             $(
-                $field: String,
+                #[serde(default)]
+                $field: Option<String>,
             )+
+            #[serde(flatten)]
+            unknown: std::collections::BTreeMap<String, serde_json::Value>,
+        }
+
+        impl RawLocaleMessages {
+            fn fill_from(&mut self, fallback: RawLocaleMessages) {
+                $(
+                    if self.$field.is_none() {
+                        self.$field = fallback.$field;
+                    }
+                )+
+            }
+
+            fn missing_fields(&self) -> Vec<&'static str> {
+                let mut missing = Vec::new();
+                $(
+                    if self.$field.is_none() {
+                        missing.push(stringify!($field));
+                    }
+                )+
+                missing
+            }
         }
     };
 }
@@ -243,7 +690,7 @@ macro_rules! impl_from_raw_locale_messages {
             fn from(raw: RawLocaleMessages) -> Self {
                 Self {
                     $(
-                        $field: leak_str(raw.$field),
+                        $field: leak_str(raw.$field.unwrap_or_default()),
                     )+
                 }
             }
@@ -261,9 +708,15 @@ fn leak_str(value: String) -> &'static str {
 
 static ZH_CN_MESSAGES: OnceLock<LocaleMessages> = OnceLock::new();
 static EN_US_MESSAGES: OnceLock<LocaleMessages> = OnceLock::new();
+static JA_JP_MESSAGES: OnceLock<LocaleMessages> = OnceLock::new();
+static KO_KR_MESSAGES: OnceLock<LocaleMessages> = OnceLock::new();
+static DE_DE_MESSAGES: OnceLock<LocaleMessages> = OnceLock::new();
+static ES_ES_MESSAGES: OnceLock<LocaleMessages> = OnceLock::new();
+static AR_SA_MESSAGES: OnceLock<LocaleMessages> = OnceLock::new();
 
 #[derive(Clone, Copy, Debug)]
 pub struct I18n {
+    language: Language,
     messages: &'static LocaleMessages,
     pub reveal_in_file_manager_button: &'static str,
 }
@@ -274,11 +727,21 @@ impl I18n {
         let reveal_in_file_manager_button = reveal_button(messages);
 
         Self {
+            language: lang,
             messages,
             reveal_in_file_manager_button,
         }
     }
 
+    /// Picks the `_one` or plural template for `count`.
+    fn plural(self, count: u64, one: &'static str, other: &'static str) -> &'static str {
+        if self.language.is_singular(count) {
+            one
+        } else {
+            other
+        }
+    }
+
     pub fn last_seen_page(self, page_num: usize) -> String {
         format_template(self.last_seen_page, &[("page_num", page_num.to_string())])
     }
@@ -295,30 +758,192 @@ impl I18n {
             return self.bookmark_added_relative_just_now.to_string();
         }
         if seconds_ago < 3_600 {
+            let minutes = seconds_ago / 60;
             return format_template(
-                self.bookmark_added_relative_minutes,
-                &[("minutes", (seconds_ago / 60).to_string())],
+                self.plural(
+                    minutes,
+                    self.bookmark_added_relative_minutes_one,
+                    self.bookmark_added_relative_minutes,
+                ),
+                &[("minutes", minutes.to_string())],
             );
         }
         if seconds_ago < 86_400 {
+            let hours = seconds_ago / 3_600;
             return format_template(
-                self.bookmark_added_relative_hours,
-                &[("hours", (seconds_ago / 3_600).to_string())],
+                self.plural(
+                    hours,
+                    self.bookmark_added_relative_hours_one,
+                    self.bookmark_added_relative_hours,
+                ),
+                &[("hours", hours.to_string())],
             );
         }
+        let days = seconds_ago / 86_400;
         format_template(
-            self.bookmark_added_relative_days,
-            &[("days", (seconds_ago / 86_400).to_string())],
+            self.plural(
+                days,
+                self.bookmark_added_relative_days_one,
+                self.bookmark_added_relative_days,
+            ),
+            &[("days", days.to_string())],
         )
     }
 
     pub fn bookmark_notes_count_label(self, count: usize) -> String {
         format_template(
-            self.bookmark_notes_count_label,
+            self.plural(
+                count as u64,
+                self.bookmark_notes_count_label_one,
+                self.bookmark_notes_count_label,
+            ),
+            &[("count", count.to_string())],
+        )
+    }
+
+    pub fn session_tab_count(self, count: usize) -> String {
+        format_template(
+            self.plural(
+                count as u64,
+                self.session_tab_count_one,
+                self.session_tab_count,
+            ),
+            &[("count", count.to_string())],
+        )
+    }
+
+    pub fn session_default_name(self, number: usize) -> String {
+        format_template(self.session_default_name, &[("number", number.to_string())])
+    }
+
+    pub fn command_panel_save_session(self, name: &str) -> String {
+        format_template(
+            self.command_panel_save_session,
+            &[("name", name.to_string())],
+        )
+    }
+
+    pub fn command_panel_delete_session(self, name: &str) -> String {
+        format_template(
+            self.command_panel_delete_session,
+            &[("name", name.to_string())],
+        )
+    }
+
+    pub fn sidebar_search_result_count(self, count: usize) -> String {
+        format_template(
+            self.plural(
+                count as u64,
+                self.sidebar_search_result_count_one,
+                self.sidebar_search_result_count,
+            ),
+            &[("count", count.to_string())],
+        )
+    }
+
+    pub fn status_bar_page(self, page_num: usize, page_count: usize) -> String {
+        format_template(
+            self.status_bar_page,
+            &[
+                ("page_num", page_num.to_string()),
+                ("page_count", page_count.to_string()),
+            ],
+        )
+    }
+
+    pub fn status_bar_page_with_label(
+        self,
+        label: &str,
+        page_num: usize,
+        page_count: usize,
+    ) -> String {
+        format_template(
+            self.status_bar_page_with_label,
+            &[
+                ("label", label.to_string()),
+                ("page_num", page_num.to_string()),
+                ("page_count", page_count.to_string()),
+            ],
+        )
+    }
+
+    pub fn status_bar_selection_chars(self, count: usize) -> String {
+        format_template(
+            self.status_bar_selection_chars,
             &[("count", count.to_string())],
         )
     }
 
+    pub fn status_bar_loading_pages(self, done: usize, total: usize) -> String {
+        format_template(
+            self.status_bar_loading_pages,
+            &[("done", done.to_string()), ("total", total.to_string())],
+        )
+    }
+
+    pub fn status_bar_renders_in_flight(self, count: usize) -> String {
+        format_template(
+            self.status_bar_renders_in_flight,
+            &[("count", count.to_string())],
+        )
+    }
+
+    pub fn load_error_repair_failed(self, error: &str) -> String {
+        format_template(
+            self.load_error_repair_failed,
+            &[("error", error.to_string())],
+        )
+    }
+
+    pub fn diagnostics_render_error_age(self, seconds: u64) -> String {
+        format_template(
+            self.diagnostics_render_error_age,
+            &[("seconds", seconds.to_string())],
+        )
+    }
+
+    pub fn diagnostics_relocate_done(self, path: &Path) -> String {
+        format_template(
+            self.diagnostics_relocate_done,
+            &[("path", path.to_string_lossy().to_string())],
+        )
+    }
+
+    pub fn diagnostics_download_progress(self, downloaded: &str, total: &str) -> String {
+        format_template(
+            self.diagnostics_download_progress,
+            &[
+                ("downloaded", downloaded.to_string()),
+                ("total", total.to_string()),
+            ],
+        )
+    }
+
+    pub fn diagnostics_download_done(self, path: &Path) -> String {
+        format_template(
+            self.diagnostics_download_done,
+            &[("path", path.to_string_lossy().to_string())],
+        )
+    }
+
+    pub fn diagnostics_download_failed(self, error: &str) -> String {
+        format_template(
+            self.diagnostics_download_failed,
+            &[("error", error.to_string())],
+        )
+    }
+
+    pub fn open_with_tool(self, name: &str) -> String {
+        format_template(self.open_with_tool, &[("name", name.to_string())])
+    }
+
+    pub fn diagnostics_relocate_failed(self, error: &str) -> String {
+        format_template(
+            self.diagnostics_relocate_failed,
+            &[("error", error.to_string())],
+        )
+    }
+
     pub fn update_status_up_to_date(self, version: &str) -> String {
         format_template(
             self.update_status_up_to_date,
@@ -333,6 +958,30 @@ impl I18n {
         )
     }
 
+    pub fn settings_log_filter_invalid(self, rule: &str) -> String {
+        format_template(
+            self.settings_log_filter_invalid,
+            &[("rule", rule.to_string())],
+        )
+    }
+
+    pub fn update_available_menu_item(self, version: &str) -> String {
+        format_template(
+            self.update_available_menu_item,
+            &[("version", version.to_string())],
+        )
+    }
+
+    pub fn update_status_downloaded(self, version: &str, path: &Path) -> String {
+        format_template(
+            self.update_status_downloaded,
+            &[
+                ("version", version.to_string()),
+                ("path", path.to_string_lossy().to_string()),
+            ],
+        )
+    }
+
     pub fn update_status_failed(self, message: &str) -> String {
         format_template(
             self.update_status_failed,
@@ -340,6 +989,132 @@ impl I18n {
         )
     }
 
+    pub fn merge_progress(self, done: usize, total: usize) -> String {
+        format_template(
+            self.merge_progress,
+            &[("done", done.to_string()), ("total", total.to_string())],
+        )
+    }
+
+    pub fn merge_done(self, count: usize, path: &Path) -> String {
+        format_template(
+            self.merge_done,
+            &[
+                ("count", count.to_string()),
+                ("path", path.to_string_lossy().to_string()),
+            ],
+        )
+    }
+
+    pub fn merge_failed(self, error: &str) -> String {
+        format_template(self.merge_failed, &[("error", error.to_string())])
+    }
+
+    pub fn optimize_progress(self, done: usize, total: usize) -> String {
+        format_template(
+            self.optimize_progress,
+            &[("done", done.to_string()), ("total", total.to_string())],
+        )
+    }
+
+    pub fn optimize_done(
+        self,
+        path: &Path,
+        original: &str,
+        optimized: &str,
+        change: &str,
+        images: usize,
+    ) -> String {
+        format_template(
+            self.optimize_done,
+            &[
+                ("path", path.to_string_lossy().to_string()),
+                ("original", original.to_string()),
+                ("optimized", optimized.to_string()),
+                ("change", change.to_string()),
+                ("images", images.to_string()),
+            ],
+        )
+    }
+
+    pub fn optimize_failed(self, error: &str) -> String {
+        format_template(self.optimize_failed, &[("error", error.to_string())])
+    }
+
+    pub fn watermark_invalid_pages(self, part: &str) -> String {
+        format_template(self.watermark_invalid_pages, &[("part", part.to_string())])
+    }
+
+    pub fn watermark_progress(self, done: usize, total: usize) -> String {
+        format_template(
+            self.watermark_progress,
+            &[("done", done.to_string()), ("total", total.to_string())],
+        )
+    }
+
+    pub fn watermark_done(self, count: usize, path: &Path) -> String {
+        format_template(
+            self.watermark_done,
+            &[
+                ("count", count.to_string()),
+                ("path", path.to_string_lossy().to_string()),
+            ],
+        )
+    }
+
+    pub fn watermark_failed(self, error: &str) -> String {
+        format_template(self.watermark_failed, &[("error", error.to_string())])
+    }
+
+    pub fn header_footer_invalid_pages(self, part: &str) -> String {
+        format_template(
+            self.header_footer_invalid_pages,
+            &[("part", part.to_string())],
+        )
+    }
+
+    pub fn header_footer_progress(self, done: usize, total: usize) -> String {
+        format_template(
+            self.header_footer_progress,
+            &[("done", done.to_string()), ("total", total.to_string())],
+        )
+    }
+
+    pub fn header_footer_done(self, count: usize, path: &Path) -> String {
+        format_template(
+            self.header_footer_done,
+            &[
+                ("count", count.to_string()),
+                ("path", path.to_string_lossy().to_string()),
+            ],
+        )
+    }
+
+    pub fn header_footer_failed(self, error: &str) -> String {
+        format_template(self.header_footer_failed, &[("error", error.to_string())])
+    }
+
+    pub fn encrypt_done(self, path: &Path) -> String {
+        format_template(
+            self.encrypt_done,
+            &[("path", path.to_string_lossy().to_string())],
+        )
+    }
+
+    pub fn encrypt_failed(self, error: &str) -> String {
+        format_template(self.encrypt_failed, &[("error", error.to_string())])
+    }
+
+    pub fn sidebar_search_result_position(self, current: usize, count: usize) -> String {
+        format_template(
+            self.sidebar_search_result_position,
+            &[
+                ("current", current.to_string()),
+                ("count", count.to_string()),
+            ],
+        )
+    }
+
     pub fn cannot_open_file(self, path: &Path) -> String {
         format_template(
             self.cannot_open_file,
@@ -354,17 +1129,30 @@ impl I18n {
         )
     }
 
-    pub fn invalid_bitmap_size(self, width: u32, height: u32) -> String {
+    pub fn annotation_bundle_exported(self, count: usize, path: &Path) -> String {
         format_template(
-            self.invalid_bitmap_size,
-            &[("width", width.to_string()), ("height", height.to_string())],
+            self.annotation_bundle_exported,
+            &[
+                ("count", count.to_string()),
+                ("path", path.to_string_lossy().to_string()),
+            ],
+        )
+    }
+
+    pub fn bookmark_outline_saved(self, count: usize, path: &Path) -> String {
+        format_template(
+            self.bookmark_outline_saved,
+            &[
+                ("count", count.to_string()),
+                ("path", path.to_string_lossy().to_string()),
+            ],
         )
     }
 
-    pub fn bitmap_len_mismatch(self, got: usize, expected: usize) -> String {
+    pub fn annotation_bundle_imported(self, count: usize) -> String {
         format_template(
-            self.bitmap_len_mismatch,
-            &[("got", got.to_string()), ("expected", expected.to_string())],
+            self.annotation_bundle_imported,
+            &[("count", count.to_string())],
         )
     }
 
@@ -388,6 +1176,11 @@ fn messages_for(lang: Language) -> &'static LocaleMessages {
     match lang {
         Language::ZhCn => ZH_CN_MESSAGES.get_or_init(|| load_messages(Language::ZhCn)),
         Language::EnUs => EN_US_MESSAGES.get_or_init(|| load_messages(Language::EnUs)),
+        Language::JaJp => JA_JP_MESSAGES.get_or_init(|| load_messages(Language::JaJp)),
+        Language::KoKr => KO_KR_MESSAGES.get_or_init(|| load_messages(Language::KoKr)),
+        Language::DeDe => DE_DE_MESSAGES.get_or_init(|| load_messages(Language::DeDe)),
+        Language::EsEs => ES_ES_MESSAGES.get_or_init(|| load_messages(Language::EsEs)),
+        Language::ArSa => AR_SA_MESSAGES.get_or_init(|| load_messages(Language::ArSa)),
     }
 }
 
@@ -409,56 +1202,87 @@ fn reveal_button(messages: &LocaleMessages) -> &'static str {
     messages.reveal_in_file_manager_default
 }
 
+/// Resolves every key through the chain: the user's locale file, the bundled one, then the
+/// same two for English. Files that fail to load are logged and skipped.
 fn load_messages(lang: Language) -> LocaleMessages {
-    match try_load_messages(lang) {
-        Ok(messages) => messages,
-        Err(primary_err) => {
+    let mut chain = vec![lang];
+    if lang != Language::EnUs {
+        chain.push(Language::EnUs);
+    }
+
+    let mut merged = RawLocaleMessages::default();
+    for chain_lang in chain {
+        let user_file = user_i18n_dir()
+            .map(|dir| dir.join(chain_lang.file_name()))
+            .filter(|path| path.is_file());
+        if let Some(path) = user_file {
+            merge_locale_file(&mut merged, read_locale_file(&path));
+        }
+        merge_locale_file(
+            &mut merged,
+            load_locale_file(chain_lang.file_name())
+                .and_then(|(path, raw)| parse_locale_file(&path, &raw)),
+        );
+
+        let missing = merged.missing_fields();
+        if chain_lang != Language::EnUs && !missing.is_empty() {
             crate::debug_log!(
-                "[i18n] failed to load {}: {}",
-                lang.file_name(),
-                primary_err
+                "[i18n] {} lacks {} keys, using {}: {}",
+                chain_lang.file_name(),
+                missing.len(),
+                Language::EnUs.file_name(),
+                missing.join(", ")
             );
+        }
+    }
 
-            if lang == Language::EnUs {
-                panic!(
-                    "failed to load i18n file {}: {}",
-                    lang.file_name(),
-                    primary_err
-                );
-            }
+    let missing = merged.missing_fields();
+    if !missing.is_empty() {
+        panic!(
+            "i18n keys missing from every locale file for {}: {}",
+            lang.file_name(),
+            missing.join(", ")
+        );
+    }
+    LocaleMessages::from(merged)
+}
 
-            match try_load_messages(Language::EnUs) {
-                Ok(messages) => {
-                    crate::debug_log!(
-                        "[i18n] fallback to {} after {} failed",
-                        Language::EnUs.file_name(),
-                        lang.file_name()
-                    );
-                    messages
-                }
-                Err(fallback_err) => panic!(
-                    "failed to load i18n files {} ({}) and {} ({})",
-                    lang.file_name(),
-                    primary_err,
-                    Language::EnUs.file_name(),
-                    fallback_err
-                ),
-            }
-        }
+fn merge_locale_file(merged: &mut RawLocaleMessages, file: Result<RawLocaleMessages, String>) {
+    match file {
+        Ok(file) => merged.fill_from(file),
+        Err(err) => crate::debug_log!("[i18n] skipped locale file: {}", err),
     }
 }
 
-fn try_load_messages(lang: Language) -> Result<LocaleMessages, String> {
-    let (path, raw) = load_locale_file(lang.file_name())?;
-    crate::debug_log!(
-        "[i18n] loading locale {} from {}",
-        lang.file_name(),
-        path.display()
-    );
-
-    serde_json::from_str::<RawLocaleMessages>(&raw)
-        .map(LocaleMessages::from)
-        .map_err(|err| format!("{} parse failed: {}", path.display(), err))
+/// Translations dropped here override the bundled ones without rebuilding the app.
+fn user_i18n_dir() -> Option<PathBuf> {
+    kpdf_core::paths::data_dir().map(|dir| dir.join("i18n"))
+}
+
+fn read_locale_file(path: &Path) -> Result<RawLocaleMessages, String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|err| format!("{} read failed: {}", path.display(), err))?;
+    parse_locale_file(path, &raw)
+}
+
+fn parse_locale_file(path: &Path, raw: &str) -> Result<RawLocaleMessages, String> {
+    crate::debug_log!("[i18n] loading locale file {}", path.display());
+    let mut messages = serde_json::from_str::<RawLocaleMessages>(raw)
+        .map_err(|err| format!("{} parse failed: {}", path.display(), err))?;
+    if !messages.unknown.is_empty() {
+        crate::debug_log!(
+            "[i18n] {} has unknown keys: {}",
+            path.display(),
+            messages
+                .unknown
+                .keys()
+                .map(String::as_str)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        messages.unknown.clear();
+    }
+    Ok(messages)
 }
 
 fn load_locale_file(file_name: &str) -> Result<(PathBuf, String), String> {
@@ -601,3 +1425,45 @@ fn format_template(template: &str, vars: &[(&str, String)]) -> String {
     }
     output
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locale_files_fall_back_per_key() {
+        let mut merged = parse_locale_file(
+            Path::new("zh_CN.json"),
+            r#"{ "open_button": "打开", "not_a_key": "x" }"#,
+        )
+        .unwrap();
+        assert!(merged.unknown.is_empty());
+        merged.fill_from(
+            parse_locale_file(
+                Path::new("en_US.json"),
+                r#"{ "open_button": "Open", "close_button": "Close" }"#,
+            )
+            .unwrap(),
+        );
+        assert_eq!(merged.open_button.as_deref(), Some("打开"));
+        assert_eq!(merged.close_button.as_deref(), Some("Close"));
+        assert!(!merged.missing_fields().contains(&"close_button"));
+        assert!(merged.missing_fields().contains(&"no_pages"));
+    }
+
+    #[test]
+    fn locale_tags_map_to_languages() {
+        assert_eq!(Language::from_locale_tag("ja-JP"), Language::JaJp);
+        assert_eq!(Language::from_locale_tag("ko_KR.UTF-8"), Language::KoKr);
+        assert_eq!(Language::from_locale_tag("de"), Language::DeDe);
+        assert_eq!(Language::from_locale_tag("es-419"), Language::EsEs);
+        assert_eq!(Language::from_locale_tag("ar-EG"), Language::ArSa);
+        assert_eq!(Language::from_locale_tag("zh-Hant-TW"), Language::ZhCn);
+        assert_eq!(Language::from_locale_tag("fr-FR"), Language::EnUs);
+        assert!(Language::DeDe.is_singular(1));
+        assert!(!Language::DeDe.is_singular(2));
+        assert!(!Language::JaJp.is_singular(1));
+        assert!(Language::ArSa.is_rtl());
+        assert!(!Language::EnUs.is_rtl());
+    }
+}
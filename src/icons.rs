@@ -34,6 +34,8 @@ pub enum IconName {
     ChevronFirst,
     ChevronRight,
     ChevronLeft,
+    ChevronUp,
+    ChevronDown,
     File,
     FolderOpen,
     Minimize,
@@ -49,6 +51,10 @@ pub enum IconName {
     BookmarkCheck,
     PanelLeftDashed,
     ScanEye,
+    LayoutGrid,
+    ListTree,
+    MessageSquareText,
+    Search,
 }
 
 impl IconNamed for IconName {
@@ -58,6 +64,8 @@ impl IconNamed for IconName {
             Self::ChevronFirst => "icons/chevron-first.svg",
             Self::ChevronRight => "icons/chevron-right.svg",
             Self::ChevronLeft => "icons/chevron-left.svg",
+            Self::ChevronUp => "icons/chevron-up.svg",
+            Self::ChevronDown => "icons/chevron-down.svg",
             Self::File => "icons/file.svg",
             Self::FolderOpen => "icons/folder-open.svg",
             Self::Minimize => "icons/minimize.svg",
@@ -73,6 +81,10 @@ impl IconNamed for IconName {
             Self::BookmarkCheck => "icons/bookmark-check.svg",
             Self::PanelLeftDashed => "icons/panel-left-dashed.svg",
             Self::ScanEye => "icons/scan-eye.svg",
+            Self::LayoutGrid => "icons/layout-grid.svg",
+            Self::ListTree => "icons/list-tree.svg",
+            Self::MessageSquareText => "icons/message-square-text.svg",
+            Self::Search => "icons/search.svg",
         }
         .into()
     }
@@ -1,14 +1,127 @@
 use std::fs::{create_dir_all, read_to_string, write};
 use std::path::PathBuf;
-use std::sync::OnceLock;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{OnceLock, RwLock};
 use tklog::{Format, LEVEL, LOG};
 
 static LOG_PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
 static LOGGING_STATE_PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
+static LOG_FILTER: RwLock<Option<LogFilter>> = RwLock::new(None);
 static FILE_LOGGING_ENABLED: AtomicBool = AtomicBool::new(false);
 static FILE_HANDLER_INITIALIZED: AtomicBool = AtomicBool::new(false);
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 5] = [
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+
+    fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        Self::ALL
+            .into_iter()
+            .find(|level| level.as_str().eq_ignore_ascii_case(raw))
+    }
+}
+
+/// Which messages reach the log, written like `info,pdfium=debug,store=warn`.
+///
+/// Modules are the `[tag]` a message starts with; untagged messages and modules without
+/// a rule use the default level.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFilter {
+    pub level: LogLevel,
+    pub modules: Vec<(String, LogLevel)>,
+}
+
+impl Default for LogFilter {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::Debug,
+            modules: Vec::new(),
+        }
+    }
+}
+
+impl LogFilter {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut filter = Self::default();
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => {
+                    let module = module.trim().trim_matches(['[', ']']).to_ascii_lowercase();
+                    let level = LogLevel::parse(level).ok_or_else(|| directive.to_string())?;
+                    if module.is_empty() {
+                        return Err(directive.to_string());
+                    }
+                    filter.modules.retain(|(existing, _)| *existing != module);
+                    filter.modules.push((module, level));
+                }
+                None => {
+                    filter.level =
+                        LogLevel::parse(directive).ok_or_else(|| directive.to_string())?;
+                }
+            }
+        }
+        Ok(filter)
+    }
+
+    /// Module rules only, as shown in the settings field.
+    pub fn modules_spec(&self) -> String {
+        self.modules
+            .iter()
+            .map(|(module, level)| format!("{module}={}", level.as_str()))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    pub fn spec(&self) -> String {
+        let modules = self.modules_spec();
+        if modules.is_empty() {
+            self.level.as_str().to_string()
+        } else {
+            format!("{},{modules}", self.level.as_str())
+        }
+    }
+
+    pub fn allows(&self, level: LogLevel, message: &str) -> bool {
+        let module = message
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+            .map(|(module, _)| module);
+        let max_level = module
+            .and_then(|module| {
+                self.modules
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case(module))
+            })
+            .map_or(self.level, |(_, level)| *level);
+        level <= max_level
+    }
+}
+
 fn resolve_log_path() -> Option<PathBuf> {
     if let Some(path) = std::env::var_os("KPDF_LOG_FILE") {
         if !path.is_empty() {
@@ -16,16 +129,6 @@ fn resolve_log_path() -> Option<PathBuf> {
         }
     }
 
-    #[cfg(target_os = "windows")]
-    if let Some(app_data) = std::env::var_os("APPDATA") {
-        return Some(
-            PathBuf::from(app_data)
-                .join("kPDF")
-                .join("logs")
-                .join("debug.log"),
-        );
-    }
-
     #[cfg(target_os = "macos")]
     if let Some(home) = std::env::var_os("HOME") {
         return Some(
@@ -37,31 +140,25 @@ fn resolve_log_path() -> Option<PathBuf> {
         );
     }
 
-    if let Some(home) = std::env::var_os("HOME") {
-        return Some(
-            PathBuf::from(home)
-                .join(".kpdf")
-                .join("logs")
-                .join("debug.log"),
-        );
+    if let Some(dir) = kpdf_core::paths::data_dir() {
+        return Some(dir.join("logs").join("debug.log"));
     }
 
     Some(std::env::temp_dir().join("kpdf-debug.log"))
 }
 
 fn resolve_logging_state_path() -> Option<PathBuf> {
-    #[cfg(target_os = "windows")]
-    if let Some(app_data) = std::env::var_os("APPDATA") {
-        return Some(PathBuf::from(app_data).join("kpdf").join("logging_enabled"));
-    }
-
-    if let Some(home) = std::env::var_os("HOME") {
-        return Some(PathBuf::from(home).join(".kpdf").join("logging_enabled"));
+    if let Some(dir) = kpdf_core::paths::data_dir() {
+        return Some(dir.join("logging_enabled"));
     }
 
     Some(std::env::temp_dir().join("kpdf-logging-enabled"))
 }
 
+fn log_filter_path() -> Option<PathBuf> {
+    logging_state_path().map(|path| path.with_file_name("log_filter"))
+}
+
 fn logging_state_path() -> Option<PathBuf> {
     LOGGING_STATE_PATH
         .get_or_init(resolve_logging_state_path)
@@ -95,6 +192,36 @@ fn persist_logging_enabled(enabled: bool) {
     let _ = write(path, if enabled { "1" } else { "0" });
 }
 
+pub fn log_filter() -> LogFilter {
+    LOG_FILTER
+        .read()
+        .ok()
+        .and_then(|filter| filter.clone())
+        .unwrap_or_default()
+}
+
+/// Applies `filter` right away and keeps it for the next launch.
+pub fn set_log_filter(filter: LogFilter) {
+    if let Some(path) = log_filter_path() {
+        if let Some(parent) = path.parent() {
+            let _ = create_dir_all(parent);
+        }
+        let _ = write(path, filter.spec());
+    }
+    if let Ok(mut current) = LOG_FILTER.write() {
+        *current = Some(filter);
+    }
+}
+
+pub fn should_log(level: LogLevel, message: &str) -> bool {
+    match LOG_FILTER.read() {
+        Ok(filter) => filter
+            .as_ref()
+            .is_none_or(|filter| filter.allows(level, message)),
+        Err(_) => true,
+    }
+}
+
 pub fn log_file_path() -> Option<PathBuf> {
     LOG_PATH.get_or_init(resolve_log_path).clone()
 }
@@ -141,7 +268,23 @@ pub fn disable_file_logging() {
 }
 
 pub fn initialize() {
-    LOG.set_level(LEVEL::Debug)
+    // `KPDF_LOG` overrides the saved filter for this run without replacing it.
+    let spec = std::env::var("KPDF_LOG")
+        .ok()
+        .or_else(|| log_filter_path().and_then(|path| read_to_string(path).ok()));
+    if let Some(spec) = spec {
+        match LogFilter::parse(&spec) {
+            Ok(filter) => {
+                if let Ok(mut current) = LOG_FILTER.write() {
+                    *current = Some(filter);
+                }
+            }
+            Err(directive) => eprintln!("[log] ignoring invalid log filter: {directive}"),
+        }
+    }
+
+    // Filtering happens in `debug_log!`, so tklog itself lets everything through.
+    LOG.set_level(LEVEL::Trace)
         .set_console(true)
         .set_format(Format::LevelFlag | Format::Date | Format::Time | Format::ShortFileName)
         .set_formatter("{level}{time} {file}:{message}\n");
@@ -153,11 +296,65 @@ pub fn initialize() {
     }
 }
 
+/// Logs at debug level, or at another one with a leading `@error`, `@warn`, `@info` or
+/// `@trace`.
 #[macro_export]
 macro_rules! debug_log {
-    ($($arg:tt)*) => {{
+    (@error, $($arg:tt)*) => {
+        $crate::debug_log!(@emit Error, error, $($arg)*)
+    };
+    (@warn, $($arg:tt)*) => {
+        $crate::debug_log!(@emit Warn, warn, $($arg)*)
+    };
+    (@info, $($arg:tt)*) => {
+        $crate::debug_log!(@emit Info, info, $($arg)*)
+    };
+    (@trace, $($arg:tt)*) => {
+        $crate::debug_log!(@emit Trace, trace, $($arg)*)
+    };
+    (@emit $level:ident, $macro:ident, $($arg:tt)*) => {{
         if $crate::logger::file_logging_enabled() {
-            tklog::debug!(format!($($arg)*));
+            let message = format!($($arg)*);
+            if $crate::logger::should_log($crate::logger::LogLevel::$level, &message) {
+                tklog::$macro!(message);
+            }
         }
     }};
+    ($($arg:tt)*) => {
+        $crate::debug_log!(@emit Debug, debug, $($arg)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_log_filter_spec() {
+        let filter = LogFilter::parse("info, pdfium=trace,[store]=warn").unwrap();
+        assert_eq!(filter.level, LogLevel::Info);
+        assert_eq!(
+            filter.modules,
+            vec![
+                ("pdfium".to_string(), LogLevel::Trace),
+                ("store".to_string(), LogLevel::Warn),
+            ]
+        );
+        assert_eq!(filter.spec(), "info,pdfium=trace,store=warn");
+        assert_eq!(LogFilter::parse("").unwrap(), LogFilter::default());
+        assert_eq!(
+            LogFilter::parse("pdfium=loud"),
+            Err("pdfium=loud".to_string())
+        );
+    }
+
+    #[test]
+    fn log_filter_uses_message_module() {
+        let filter = LogFilter::parse("warn,pdfium=debug").unwrap();
+        assert!(filter.allows(LogLevel::Debug, "[pdfium] loaded"));
+        assert!(!filter.allows(LogLevel::Trace, "[pdfium] loaded"));
+        assert!(!filter.allows(LogLevel::Debug, "[store] open db failed"));
+        assert!(filter.allows(LogLevel::Error, "[store] open db failed"));
+        assert!(!filter.allows(LogLevel::Info, "untagged"));
+    }
 }
@@ -3,10 +3,13 @@
     windows_subsystem = "windows"
 )]
 
+mod cli;
+mod crash_report;
 pub mod i18n;
 pub mod icons;
 pub mod logger;
 mod pdf_viewer;
+mod single_instance;
 mod updater;
 
 use gpui::*;
@@ -22,7 +25,11 @@ use x11rb::wrapper::ConnectionExt as _;
 const WINDOW_SIZE_TREE: &str = "window_size";
 const WINDOW_SIZE_KEY_WIDTH: &str = "width";
 const WINDOW_SIZE_KEY_HEIGHT: &str = "height";
-const LOCAL_STATE_DB_DIR_NAME: &str = "kpdf_db";
+const WINDOW_SIZE_KEY_X: &str = "x";
+const WINDOW_SIZE_KEY_Y: &str = "y";
+const WINDOW_SIZE_KEY_DISPLAY: &str = "display_uuid";
+/// How much of a restored window must land on its display before the saved origin is trusted.
+const RESTORED_WINDOW_MIN_VISIBLE: f32 = 64.0;
 pub(crate) const APP_REPOSITORY_URL: &str = "https://github.com/KusStar/kpdf";
 #[cfg(target_os = "linux")]
 const KPDF_LINUX_BACKEND_ENV: &str = "KPDF_LINUX_BACKEND";
@@ -35,18 +42,73 @@ gpui::actions!(
         ShowSettingsMenu,
         EnableLoggingMenu,
         DisableLoggingMenu,
-        OpenLogsMenu
+        OpenLogsMenu,
+        ToggleThumbnailPanelMenu,
+        TogglePageOverviewMenu,
+        ToggleStatusBarMenu,
+        ToggleGrayscaleMenu,
+        ToggleZenModeMenu,
+        ToggleFullscreenMenu,
+        TogglePresentationMenu,
+        FirstPageMenu,
+        PreviousPageMenu,
+        NextPageMenu,
+        LastPageMenu,
+        ClearRecentFilesMenu
     ]
 );
 
-pub(crate) fn configure_app_menus(cx: &mut App, i18n: i18n::I18n) {
+/// Reopens a file picked from the Open Recent submenu.
+#[derive(Clone, PartialEq, Debug, gpui::Action)]
+#[action(namespace = kpdf, no_json)]
+pub(crate) struct OpenRecentFileMenu {
+    pub path: std::path::PathBuf,
+}
+
+/// `available_update` replaces the update item's label with a notice naming the version;
+/// `recent_files` fills the Open Recent submenu, most recent first.
+pub(crate) fn configure_app_menus(
+    cx: &mut App,
+    i18n: i18n::I18n,
+    available_update: Option<&str>,
+    recent_files: &[std::path::PathBuf],
+) {
+    let check_updates_label = match available_update {
+        Some(version) => i18n.update_available_menu_item(version),
+        None => i18n.check_updates_button.to_string(),
+    };
     let mut items = vec![
         MenuItem::action(i18n.about_button, ShowAboutMenu),
-        MenuItem::action(i18n.check_updates_button, CheckForUpdatesMenu),
+        MenuItem::action(check_updates_label, CheckForUpdatesMenu),
         MenuItem::action(i18n.settings_button, ShowSettingsMenu),
         MenuItem::separator(),
     ];
 
+    let mut recent_items = recent_files
+        .iter()
+        .map(|path| {
+            let name = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.to_string_lossy().into_owned());
+            MenuItem::action(name, OpenRecentFileMenu { path: path.clone() })
+        })
+        .collect::<Vec<_>>();
+    if !recent_items.is_empty() {
+        recent_items.push(MenuItem::separator());
+    }
+    recent_items.push(MenuItem::action(
+        i18n.clear_recent_menu,
+        ClearRecentFilesMenu,
+    ));
+    items.extend([
+        MenuItem::submenu(Menu {
+            name: i18n.open_recent_menu.into(),
+            items: recent_items,
+        }),
+        MenuItem::separator(),
+    ]);
+
     if logger::file_logging_enabled() {
         items.extend([
             MenuItem::action(i18n.open_logs_button, OpenLogsMenu),
@@ -60,24 +122,33 @@ pub(crate) fn configure_app_menus(cx: &mut App, i18n: i18n::I18n) {
         ));
     }
 
-    cx.set_menus(vec![Menu {
-        name: "kPDF".into(),
-        items,
-    }]);
-}
-
-fn window_size_db_path() -> std::path::PathBuf {
-    if let Some(app_data) = std::env::var_os("APPDATA") {
-        return std::path::PathBuf::from(app_data)
-            .join("kpdf")
-            .join(LOCAL_STATE_DB_DIR_NAME);
-    }
-    if let Some(home) = std::env::var_os("HOME") {
-        return std::path::PathBuf::from(home)
-            .join(".kpdf")
-            .join(LOCAL_STATE_DB_DIR_NAME);
-    }
-    std::path::PathBuf::from("kpdf_db")
+    cx.set_menus(vec![
+        Menu {
+            name: "kPDF".into(),
+            items,
+        },
+        Menu {
+            name: i18n.view_menu.into(),
+            items: vec![
+                MenuItem::action(i18n.action_toggle_thumbnail_panel, ToggleThumbnailPanelMenu),
+                MenuItem::action(i18n.action_toggle_page_overview, TogglePageOverviewMenu),
+                MenuItem::action(i18n.action_toggle_status_bar, ToggleStatusBarMenu),
+                MenuItem::action(i18n.action_toggle_grayscale, ToggleGrayscaleMenu),
+                MenuItem::action(i18n.action_toggle_zen_mode, ToggleZenModeMenu),
+                MenuItem::action(i18n.action_toggle_fullscreen, ToggleFullscreenMenu),
+                MenuItem::action(i18n.action_toggle_presentation, TogglePresentationMenu),
+            ],
+        },
+        Menu {
+            name: i18n.go_menu.into(),
+            items: vec![
+                MenuItem::action(i18n.action_first_page, FirstPageMenu),
+                MenuItem::action(i18n.action_previous_page, PreviousPageMenu),
+                MenuItem::action(i18n.action_next_page, NextPageMenu),
+                MenuItem::action(i18n.action_last_page, LastPageMenu),
+            ],
+        },
+    ]);
 }
 
 fn app_resources_themes_dir(current_exe: &std::path::Path) -> Option<std::path::PathBuf> {
@@ -234,8 +305,54 @@ fn themes_dir_path() -> std::path::PathBuf {
         .unwrap_or_else(|| std::path::PathBuf::from("assets").join("themes"))
 }
 
-fn load_saved_window_size() -> Option<(f32, f32)> {
-    let db_path = window_size_db_path();
+/// Directory the theme registry watches: bundled themes are copied here on startup so users
+/// can drop their own JSON files next to them.
+pub(crate) fn user_themes_dir() -> std::path::PathBuf {
+    kpdf_core::paths::data_dir()
+        .map(|dir| dir.join("themes"))
+        .unwrap_or_else(|| std::path::PathBuf::from("themes"))
+}
+
+/// Refreshes the bundled themes in [`user_themes_dir`]; a user file with the same name as a
+/// bundled one is overwritten, any other file is left alone.
+fn prepare_user_themes_dir(bundled_dir: &std::path::Path) -> std::io::Result<std::path::PathBuf> {
+    let user_dir = user_themes_dir();
+    std::fs::create_dir_all(&user_dir)?;
+    if user_dir.canonicalize()? == bundled_dir.canonicalize().unwrap_or_default() {
+        return Ok(user_dir);
+    }
+
+    for entry in std::fs::read_dir(bundled_dir)?.flatten() {
+        let path = entry.path();
+        let is_json = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let Some(file_name) = path.file_name().filter(|_| is_json && path.is_file()) else {
+            continue;
+        };
+        let target = user_dir.join(file_name);
+        if std::fs::read(&target).ok() != std::fs::read(&path).ok() {
+            std::fs::copy(&path, &target)?;
+        }
+    }
+    Ok(user_dir)
+}
+
+struct SavedWindowPlacement {
+    width: f32,
+    height: f32,
+    origin: Option<(f32, f32)>,
+    display_uuid: Option<[u8; 16]>,
+}
+
+fn decode_window_coordinate(raw: Option<sled::IVec>) -> Option<f32> {
+    let value = f32::from_be_bytes(raw?.as_ref().try_into().ok()?);
+    value.is_finite().then_some(value)
+}
+
+fn load_saved_window_placement() -> Option<SavedWindowPlacement> {
+    let db_path = PdfViewer::local_state_db_path();
     let db = match sled::open(&db_path) {
         Ok(db) => db,
         Err(_) => return None,
@@ -254,7 +371,44 @@ fn load_saved_window_size() -> Option<(f32, f32)> {
     if width <= 0.0 || height <= 0.0 {
         return None;
     }
-    Some((width, height))
+    let x = decode_window_coordinate(store.get(WINDOW_SIZE_KEY_X).ok().flatten());
+    let y = decode_window_coordinate(store.get(WINDOW_SIZE_KEY_Y).ok().flatten());
+    let display_uuid = store
+        .get(WINDOW_SIZE_KEY_DISPLAY)
+        .ok()
+        .flatten()
+        .and_then(|raw| <[u8; 16]>::try_from(raw.as_ref()).ok());
+    Some(SavedWindowPlacement {
+        width,
+        height,
+        origin: x.zip(y),
+        display_uuid,
+    })
+}
+
+/// Puts the window back on the display it was last on. Falls back to centering on the primary
+/// display when that display is gone or the saved frame would now be mostly off-screen.
+fn restored_window_bounds(
+    placement: &SavedWindowPlacement,
+    cx: &App,
+) -> (WindowBounds, Option<DisplayId>) {
+    let window_size = size(px(placement.width), px(placement.height));
+    if let (Some((x, y)), Some(display_uuid)) = (placement.origin, placement.display_uuid)
+        && let Some(display) = cx.displays().into_iter().find(|display| {
+            display
+                .uuid()
+                .is_ok_and(|uuid| *uuid.as_bytes() == display_uuid)
+        })
+    {
+        let bounds = Bounds::new(point(px(x), px(y)), window_size);
+        let visible = display.bounds().intersect(&bounds).size;
+        if visible.width >= px(RESTORED_WINDOW_MIN_VISIBLE)
+            && visible.height >= px(RESTORED_WINDOW_MIN_VISIBLE)
+        {
+            return (WindowBounds::Windowed(bounds), Some(display.id()));
+        }
+    }
+    (WindowBounds::centered(window_size, cx), None)
 }
 
 #[cfg(target_os = "linux")]
@@ -423,15 +577,25 @@ fn hide_linux_server_window_decorations(_window: &Window) {
 
     if changed > 0 {
         if conn.flush().is_err() {
-            crate::debug_log!("[linux] failed to flush X11 borderless hint update");
+            crate::debug_log!(@warn, "[linux] failed to flush X11 borderless hint update");
         }
     } else {
-        crate::debug_log!("[linux] failed to set X11 borderless hints on matching windows");
+        crate::debug_log!(@warn, "[linux] failed to set X11 borderless hints on matching windows");
     }
 }
 
 fn main() {
     logger::initialize();
+    kpdf_core::set_log_sink(|message| crate::debug_log!("{}", message));
+    if let Some(exit_code) = cli::run_from_args() {
+        std::process::exit(exit_code);
+    }
+    let launch_paths = cli::launch_paths();
+    let forwarded_launches = match single_instance::claim_or_forward(&launch_paths) {
+        single_instance::Instance::Primary(launches) => launches,
+        single_instance::Instance::Forwarded => return,
+    };
+    crash_report::install();
     #[cfg(target_os = "linux")]
     configure_linux_display_backend();
 
@@ -440,12 +604,25 @@ fn main() {
     let i18n = i18n::I18n::new(language);
 
     app.run(move |cx| {
-        configure_app_menus(cx, i18n);
+        configure_app_menus(cx, i18n, None, &[]);
 
         gpui_component::init(cx);
-        let themes_dir = themes_dir_path();
+        let bundled_themes_dir = themes_dir_path();
+        let themes_dir = match prepare_user_themes_dir(&bundled_themes_dir) {
+            Ok(dir) => dir,
+            Err(err) => {
+                crate::debug_log!(
+                    @warn,
+                    "[theme] failed to prepare user themes dir: {} | {}",
+                    user_themes_dir().display(),
+                    err
+                );
+                bundled_themes_dir
+            }
+        };
         if let Err(err) = ThemeRegistry::watch_dir(themes_dir.clone(), cx, |_| {}) {
             crate::debug_log!(
+                @warn,
                 "[theme] failed to watch themes dir: {} | {}",
                 themes_dir.display(),
                 err
@@ -461,17 +638,20 @@ fn main() {
         .detach();
 
         cx.spawn(async move |cx| {
-            let saved_size = load_saved_window_size();
-            let saved_window_bounds = if let Some((w, h)) = saved_size {
-                Some(cx.update(|app| WindowBounds::centered(size(px(w), px(h)), app))?)
-            } else {
-                None
+            let (saved_window_bounds, saved_display_id) = match load_saved_window_placement() {
+                Some(placement) => {
+                    let (bounds, display_id) =
+                        cx.update(|app| restored_window_bounds(&placement, app))?;
+                    (Some(bounds), display_id)
+                }
+                None => (None, None),
             };
 
             let window_options = WindowOptions {
                 titlebar: Some(TitleBar::title_bar_options()),
                 window_decorations: Some(WindowDecorations::Client),
                 window_bounds: saved_window_bounds,
+                display_id: saved_display_id,
                 ..WindowOptions::default()
             };
 
@@ -479,6 +659,12 @@ fn main() {
                 #[cfg(target_os = "linux")]
                 hide_linux_server_window_decorations(window);
                 let view = cx.new(|cx| PdfViewer::new(window, cx));
+                view.update(cx, |viewer, cx| {
+                    if !launch_paths.is_empty() {
+                        viewer.open_launch_paths(launch_paths, cx);
+                    }
+                    viewer.watch_forwarded_launches(forwarded_launches, window, cx);
+                });
                 cx.new(|cx| Root::new(view, window, cx))
             })?;
             Ok::<_, anyhow::Error>(())
@@ -60,7 +60,7 @@ impl PdfViewer {
                 cx.notify();
             }
             Err(err) => {
-                crate::debug_log!("[about] failed to open about window: {}", err);
+                crate::debug_log!(@warn, "[about] failed to open about window: {}", err);
                 self.on_about_dialog_window_closed(session_id, cx);
             }
         }
@@ -112,20 +112,41 @@ struct AboutDialogSnapshot {
     language: Language,
     updater_status: String,
     updater_download_url: Option<String>,
+    updater_download_progress: Option<DownloadProgress>,
+    updater_downloaded_path: Option<PathBuf>,
     updater_is_checking: bool,
+    update_channel: updater::UpdateChannel,
 }
 
 impl AboutDialogSnapshot {
     fn from_viewer(viewer: &PdfViewer) -> Self {
-        let updater_download_url = match &viewer.updater_state {
-            UpdaterUiState::Available { download_url, .. } => Some(download_url.clone()),
-            _ => None,
-        };
+        let (updater_download_url, updater_download_progress, updater_downloaded_path) =
+            match &viewer.updater_state {
+                UpdaterUiState::Available {
+                    download_url,
+                    download,
+                    ..
+                } => (
+                    Some(download_url.clone()),
+                    match download {
+                        UpdateDownload::InProgress(progress) => Some(*progress),
+                        _ => None,
+                    },
+                    match download {
+                        UpdateDownload::Done(path) => Some(path.clone()),
+                        _ => None,
+                    },
+                ),
+                _ => (None, None, None),
+            };
         Self {
             language: viewer.language,
             updater_status: viewer.updater_status_text(),
             updater_download_url,
+            updater_download_progress,
+            updater_downloaded_path,
             updater_is_checking: matches!(viewer.updater_state, UpdaterUiState::Checking),
+            update_channel: viewer.update_channel,
         }
     }
 }
@@ -161,6 +182,49 @@ impl AboutDialogWindow {
         });
         window.remove_window();
     }
+
+    fn render_download_progress(
+        progress: DownloadProgress,
+        i18n: I18n,
+        cx: &App,
+    ) -> impl IntoElement {
+        let fraction = progress
+            .total
+            .filter(|total| *total > 0)
+            .map(|total| (progress.downloaded as f32 / total as f32).clamp(0.0, 1.0));
+        let label = i18n.diagnostics_download_progress(
+            &PdfViewer::format_storage_size(progress.downloaded),
+            &progress
+                .total
+                .map(PdfViewer::format_storage_size)
+                .unwrap_or_else(|| "?".to_string()),
+        );
+
+        div()
+            .w_full()
+            .v_flex()
+            .gap_1()
+            .child(
+                div()
+                    .w_full()
+                    .h(px(6.))
+                    .rounded_full()
+                    .bg(cx.theme().secondary)
+                    .child(
+                        div()
+                            .h_full()
+                            .rounded_full()
+                            .bg(cx.theme().primary)
+                            .w(relative(fraction.unwrap_or(0.0))),
+                    ),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(label),
+            )
+    }
 }
 
 impl Render for AboutDialogWindow {
@@ -168,7 +232,10 @@ impl Render for AboutDialogWindow {
         let i18n = I18n::new(self.snapshot.language);
         let updater_status = self.snapshot.updater_status.clone();
         let updater_download_url = self.snapshot.updater_download_url.clone();
+        let updater_download_progress = self.snapshot.updater_download_progress;
+        let updater_downloaded_path = self.snapshot.updater_downloaded_path.clone();
         let updater_is_checking = self.snapshot.updater_is_checking;
+        let update_channel = self.snapshot.update_channel;
 
         let version = env!("CARGO_PKG_VERSION");
         window.set_window_title(&format!("{} kPDF", i18n.about_dialog_title));
@@ -231,9 +298,53 @@ impl Render for AboutDialogWindow {
                                     )
                                     .child(
                                         div()
-                                            .text_sm()
-                                            .text_color(cx.theme().foreground)
-                                            .child(version),
+                                            .flex()
+                                            .items_center()
+                                            .gap_2()
+                                            .child(
+                                                div()
+                                                    .text_sm()
+                                                    .text_color(cx.theme().foreground)
+                                                    .child(version),
+                                            )
+                                            .child(
+                                                updater::UpdateChannel::ALL
+                                                    .into_iter()
+                                                    .enumerate()
+                                                    .fold(
+                                                        ButtonGroup::new("about-update-channel-window")
+                                                            .xsmall()
+                                                            .outline(),
+                                                        |group, (ix, channel)| {
+                                                            group.child(
+                                                                Button::new(("about-update-channel", ix))
+                                                                    .label(match channel {
+                                                                        updater::UpdateChannel::Stable => {
+                                                                            i18n.update_channel_stable
+                                                                        }
+                                                                        updater::UpdateChannel::Beta => {
+                                                                            i18n.update_channel_beta
+                                                                        }
+                                                                    })
+                                                                    .selected(update_channel == channel),
+                                                            )
+                                                        },
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(channel) = selected
+                                                                .first()
+                                                                .and_then(|ix| updater::UpdateChannel::ALL.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_update_channel(channel, cx);
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
                                     ),
                             )
                             .child(
@@ -271,7 +382,12 @@ impl Render for AboutDialogWindow {
                                             .text_color(cx.theme().foreground)
                                             .whitespace_normal()
                                             .child(updater_status),
-                                    ),
+                                    )
+                                    .when_some(updater_download_progress, |this, progress| {
+                                        this.child(Self::render_download_progress(
+                                            progress, i18n, cx,
+                                        ))
+                                    }),
                             ),
                     )
                     .child(
@@ -301,16 +417,65 @@ impl Render for AboutDialogWindow {
                                         });
                                     })),
                             )
-                            .when_some(updater_download_url, |this, download_url| {
-                                this.child(
-                                    Button::new("about-download-update-window")
-                                        .small()
-                                        .label(i18n.download_update_button)
-                                        .on_click(move |_, _, cx| {
-                                            cx.open_url(download_url.as_str());
-                                        }),
-                                )
+                            .map(|this| {
+                                if let Some(path) = updater_downloaded_path {
+                                    let can_install = updater::can_install(&path);
+                                    this.child(
+                                        Button::new("about-show-update-window")
+                                            .small()
+                                            .label(i18n.show_in_folder_button)
+                                            .on_click(move |_, _, cx| {
+                                                cx.reveal_path(&path);
+                                            }),
+                                    )
+                                    .when(can_install, |this| {
+                                        this.child(
+                                            Button::new("about-install-update-window")
+                                                .small()
+                                                .primary()
+                                                .label(i18n.install_update_button)
+                                                .on_click(cx.listener(|this, _, _, cx| {
+                                                    let _ = this.viewer.update(cx, |viewer, cx| {
+                                                        viewer.install_update_and_relaunch(cx);
+                                                    });
+                                                })),
+                                        )
+                                    })
+                                } else if updater_download_progress.is_some() {
+                                    this.child(
+                                        Button::new("about-cancel-update-download-window")
+                                            .small()
+                                            .label(i18n.diagnostics_download_cancel_button)
+                                            .on_click(cx.listener(|this, _, _, cx| {
+                                                this.viewer.read(cx).cancel_update_download();
+                                            })),
+                                    )
+                                } else if updater_download_url.is_some() {
+                                    this.child(
+                                        Button::new("about-download-update-window")
+                                            .small()
+                                            .label(i18n.download_update_button)
+                                            .on_click(cx.listener(|this, _, _, cx| {
+                                                let _ = this.viewer.update(cx, |viewer, cx| {
+                                                    viewer.download_update(cx);
+                                                });
+                                            })),
+                                    )
+                                } else {
+                                    this
+                                }
                             })
+                            .child(
+                                Button::new("about-diagnostics-window")
+                                    .small()
+                                    .ghost()
+                                    .label(i18n.diagnostics_button)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        let _ = this.viewer.update(cx, |viewer, cx| {
+                                            viewer.open_diagnostics_dialog(cx);
+                                        });
+                                    })),
+                            )
                             .child(
                                 Button::new("about-close-window")
                                     .small()
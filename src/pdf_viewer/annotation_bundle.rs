@@ -0,0 +1,311 @@
+use super::{MarkdownNoteEntry, PdfViewer, ShapeEntry, StampContent, StampEntry, TextMarkupEntry};
+use crate::i18n::I18n;
+use anyhow::{Context as _, Result, bail};
+use gpui::*;
+use kpdf_core::fetch::sha256_file;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+const ANNOTATION_BUNDLE_VERSION: u32 = 1;
+const ANNOTATION_BUNDLE_EXTENSION: &str = "kpdfannot";
+
+/// Every note and annotation on one document. Bundles match other copies by content hash, so
+/// entry paths are blanked on export and replaced with the local path on import.
+#[derive(Debug, Serialize, Deserialize)]
+struct AnnotationBundle {
+    version: u32,
+    document_sha256: String,
+    #[serde(default)]
+    notes: Vec<MarkdownNoteEntry>,
+    #[serde(default)]
+    text_markups: Vec<TextMarkupEntry>,
+    #[serde(default)]
+    stamps: Vec<StampEntry>,
+    #[serde(default)]
+    shapes: Vec<ShapeEntry>,
+}
+
+impl AnnotationBundle {
+    fn len(&self) -> usize {
+        self.notes.len() + self.text_markups.len() + self.stamps.len() + self.shapes.len()
+    }
+}
+
+fn write_annotation_bundle(
+    document: &Path,
+    mut bundle: AnnotationBundle,
+    output: &Path,
+) -> Result<usize> {
+    bundle.document_sha256 = sha256_file(document)?;
+    let value = serde_json::to_vec(&bundle).context("serialize annotation bundle")?;
+    std::fs::write(output, value).with_context(|| format!("write {}", output.display()))?;
+    Ok(bundle.len())
+}
+
+fn read_annotation_bundle(bundle_path: &Path, document: &Path) -> Result<AnnotationBundle> {
+    let raw =
+        std::fs::read(bundle_path).with_context(|| format!("read {}", bundle_path.display()))?;
+    let bundle = serde_json::from_slice::<AnnotationBundle>(&raw)
+        .with_context(|| format!("parse {}", bundle_path.display()))?;
+    if bundle.version > ANNOTATION_BUNDLE_VERSION {
+        bail!(
+            "bundle version {} is newer than supported version {}",
+            bundle.version,
+            ANNOTATION_BUNDLE_VERSION
+        );
+    }
+    let document_sha256 = sha256_file(document)?;
+    if !bundle
+        .document_sha256
+        .eq_ignore_ascii_case(&document_sha256)
+    {
+        bail!("bundle was exported from a different document");
+    }
+    Ok(bundle)
+}
+
+/// Borrows an entry's id and document path, the only fields bundles rewrite.
+type EntryKey<T> = fn(&mut T) -> (&mut u64, &mut PathBuf);
+
+fn note_key(note: &mut MarkdownNoteEntry) -> (&mut u64, &mut PathBuf) {
+    (&mut note.id, &mut note.path)
+}
+
+fn text_markup_key(markup: &mut TextMarkupEntry) -> (&mut u64, &mut PathBuf) {
+    (&mut markup.id, &mut markup.path)
+}
+
+fn stamp_key(stamp: &mut StampEntry) -> (&mut u64, &mut PathBuf) {
+    (&mut stamp.id, &mut stamp.path)
+}
+
+fn shape_key(shape: &mut ShapeEntry) -> (&mut u64, &mut PathBuf) {
+    (&mut shape.id, &mut shape.path)
+}
+
+fn entries_for_export<T: Clone>(entries: &[T], path: &Path, key: EntryKey<T>) -> Vec<T> {
+    entries
+        .iter()
+        .cloned()
+        .filter_map(|mut entry| {
+            let (_, entry_path) = key(&mut entry);
+            if entry_path.as_path() != path {
+                return None;
+            }
+            *entry_path = PathBuf::new();
+            Some(entry)
+        })
+        .collect()
+}
+
+/// An entry's fields apart from its id and path, which imports rewrite.
+fn entry_content<T: Serialize>(entry: &T) -> Option<serde_json::Value> {
+    let mut value = serde_json::to_value(entry).ok()?;
+    let fields = value.as_object_mut()?;
+    fields.remove("id");
+    fields.remove("path");
+    Some(value)
+}
+
+/// Appends `imported` under `path`, skipping entries whose content is already there (an
+/// earlier import may have renumbered them) and renumbering ids that belong to another
+/// document. Returns how many were added.
+fn merge_imported<T: Serialize>(
+    existing: &mut Vec<T>,
+    imported: Vec<T>,
+    path: &Path,
+    key: EntryKey<T>,
+) -> usize {
+    let mut known = Vec::new();
+    for entry in existing.iter_mut() {
+        if key(entry).1.as_path() == path {
+            known.extend(entry_content(&*entry));
+        }
+    }
+
+    let mut added = 0;
+    for mut entry in imported {
+        if let Some(content) = entry_content(&entry) {
+            if known.contains(&content) {
+                continue;
+            }
+            known.push(content);
+        }
+        let (id, entry_path) = key(&mut entry);
+        while existing.iter_mut().any(|other| *key(other).0 == *id) {
+            *id = id.saturating_add(1);
+        }
+        *entry_path = path.to_path_buf();
+        existing.push(entry);
+        added += 1;
+    }
+    added
+}
+
+impl PdfViewer {
+    pub(super) fn export_annotation_bundle(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(path) = self.active_tab_path().cloned() else {
+            return;
+        };
+
+        let bundle = AnnotationBundle {
+            version: ANNOTATION_BUNDLE_VERSION,
+            document_sha256: String::new(),
+            notes: entries_for_export(&self.markdown_notes, &path, note_key),
+            text_markups: entries_for_export(&self.text_markups, &path, text_markup_key),
+            // Image stamps point at files on this machine, which the recipient won't have.
+            stamps: entries_for_export(&self.stamps, &path, stamp_key)
+                .into_iter()
+                .filter(|stamp| !matches!(stamp.content, StampContent::Image(_)))
+                .collect(),
+            shapes: entries_for_export(&self.shapes, &path, shape_key),
+        };
+
+        let directory = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let suggested_name = path
+            .file_stem()
+            .map(|stem| format!("{}.{ANNOTATION_BUNDLE_EXTENSION}", stem.to_string_lossy()))
+            .unwrap_or_else(|| format!("annotations.{ANNOTATION_BUNDLE_EXTENSION}"));
+        let picker = cx.prompt_for_new_path(&directory, Some(&suggested_name));
+        let i18n = I18n::new(self.language);
+
+        cx.spawn_in(window, async move |_, cx| {
+            let Ok(Ok(Some(output))) = picker.await else {
+                return;
+            };
+            let output_for_task = output.clone();
+            let result = cx
+                .background_executor()
+                .spawn(async move { write_annotation_bundle(&path, bundle, &output_for_task) })
+                .await;
+            let _ = match result {
+                Ok(count) => {
+                    crate::debug_log!(
+                        "[annotations] exported {} entries to {}",
+                        count,
+                        output.display()
+                    );
+                    cx.prompt(
+                        PromptLevel::Info,
+                        &i18n.annotation_bundle_exported(count, &output),
+                        None,
+                        &[i18n.close_button],
+                    )
+                }
+                Err(err) => {
+                    crate::debug_log!(@warn, "[annotations] failed to export bundle: {:#}", err);
+                    cx.prompt(
+                        PromptLevel::Warning,
+                        i18n.annotation_bundle_export_failed,
+                        Some(&format!("{err:#}")),
+                        &[i18n.close_button],
+                    )
+                }
+            };
+        })
+        .detach();
+    }
+
+    pub(super) fn import_annotation_bundle(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(path) = self.active_tab_path().cloned() else {
+            return;
+        };
+        let i18n = I18n::new(self.language);
+        let picker = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some(i18n.annotation_bundle_import_prompt.into()),
+        });
+
+        cx.spawn_in(window, async move |this, cx| {
+            let Ok(Ok(Some(paths))) = picker.await else {
+                return;
+            };
+            let Some(bundle_path) = paths.into_iter().next() else {
+                return;
+            };
+            let document = path.clone();
+            let result = cx
+                .background_executor()
+                .spawn(async move { read_annotation_bundle(&bundle_path, &document) })
+                .await;
+            let _ = match result {
+                Ok(bundle) => {
+                    let Ok(added) = this.update(cx, |this, cx| {
+                        this.merge_annotation_bundle(&path, bundle, cx)
+                    }) else {
+                        return;
+                    };
+                    let message = if added > 0 {
+                        i18n.annotation_bundle_imported(added)
+                    } else {
+                        i18n.annotation_bundle_nothing_new.to_string()
+                    };
+                    cx.prompt(PromptLevel::Info, &message, None, &[i18n.close_button])
+                }
+                Err(err) => {
+                    crate::debug_log!(@warn, "[annotations] failed to import bundle: {:#}", err);
+                    cx.prompt(
+                        PromptLevel::Warning,
+                        i18n.annotation_bundle_import_failed,
+                        Some(&format!("{err:#}")),
+                        &[i18n.close_button],
+                    )
+                }
+            };
+        })
+        .detach();
+    }
+
+    fn merge_annotation_bundle(
+        &mut self,
+        path: &Path,
+        bundle: AnnotationBundle,
+        cx: &mut Context<Self>,
+    ) -> usize {
+        let notes = merge_imported(&mut self.markdown_notes, bundle.notes, path, note_key);
+        let text_markups = merge_imported(
+            &mut self.text_markups,
+            bundle.text_markups,
+            path,
+            text_markup_key,
+        );
+        let stamps = merge_imported(&mut self.stamps, bundle.stamps, path, stamp_key);
+        // Imported shapes form one undo step, like any other shape edit.
+        self.begin_shape_edit(path);
+        let shapes = merge_imported(&mut self.shapes, bundle.shapes, path, shape_key);
+        self.commit_shape_edit();
+
+        if notes > 0 {
+            self.markdown_notes.sort_by(|a, b| {
+                b.updated_at_unix_secs
+                    .cmp(&a.updated_at_unix_secs)
+                    .then_with(|| b.id.cmp(&a.id))
+            });
+            self.persist_markdown_notes();
+        }
+        if text_markups > 0 {
+            self.text_markups.sort_by(|a, b| {
+                b.updated_at_unix_secs
+                    .cmp(&a.updated_at_unix_secs)
+                    .then_with(|| b.id.cmp(&a.id))
+            });
+            self.persist_text_markups();
+        }
+        if stamps > 0 {
+            self.persist_stamps();
+        }
+        if shapes > 0 {
+            self.persist_shapes();
+        }
+        let added = notes + text_markups + stamps + shapes;
+        crate::debug_log!(
+            "[annotations] imported {} entries into {}",
+            added,
+            path.display()
+        );
+        cx.notify();
+        added
+    }
+}
@@ -1,5 +1,8 @@
-use super::PdfViewer;
 use super::display_file_name;
+use super::{
+    COMMAND_PANEL_HISTORY_ITEMS, CommandHistoryEntry, EraserMode, MAX_COMMAND_HISTORY, PdfViewer,
+    ShapeKind,
+};
 use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 use gpui_component::input::Input;
@@ -15,6 +18,8 @@ enum CommandPanelItem {
         action: CommandPanelMenuAction,
         title: String,
         subtitle: String,
+        /// Pinned to the top of an empty query from the command history.
+        recent: bool,
     },
     OpenTab {
         tab_id: usize,
@@ -25,9 +30,23 @@ enum CommandPanelItem {
         path: PathBuf,
         last_seen_page: Option<usize>,
     },
+    Plugin {
+        index: usize,
+        recent: bool,
+    },
+    Session {
+        name: String,
+        tab_count: usize,
+    },
+    SaveSession {
+        name: String,
+    },
+    DeleteSession {
+        name: String,
+    },
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 enum CommandPanelMenuAction {
     ShowAbout,
     CheckForUpdates,
@@ -36,9 +55,30 @@ enum CommandPanelMenuAction {
     EnableLogging,
     DisableLogging,
     ToggleVerticalTabBar,
+    ToggleThumbnailPanel,
+    TogglePageOverview,
+    ToggleStatusBar,
+    ToggleGrayscale,
+    ToggleZenMode,
+    ToggleFullscreen,
+    TogglePresentation,
+    FirstPage,
+    LastPage,
+    TogglePageOrderRightToLeft,
     ShowBookmarks,
     ShowRecentFiles,
     ShowKeymap,
+    MergePdfs,
+    SaveOptimizedCopy,
+    AddWatermark,
+    AddHeaderFooter,
+    SaveWithPassword,
+    DrawShape(ShapeKind),
+    Erase(EraserMode),
+    ExportAnnotations,
+    ImportAnnotations,
+    SaveBookmarkOutline,
+    ToggleDebugOverlay,
 }
 
 const COMMAND_PANEL_WIDTH: f32 = 560.0;
@@ -48,6 +88,7 @@ const COMMAND_PANEL_SCROLLBAR_GUTTER: f32 = 20.0;
 impl PdfViewer {
     pub(super) fn open_command_panel(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let mut changed = false;
+        self.close_tab_switcher(cx);
         if !self.command_panel_open {
             self.command_panel_open = true;
             self.command_panel_needs_focus = true;
@@ -179,6 +220,7 @@ impl PdfViewer {
                         action,
                         title,
                         subtitle,
+                        recent: false,
                     });
                 }
             };
@@ -236,6 +278,55 @@ impl PdfViewer {
             &i18n_en.command_panel_toggle_vertical_tab_bar,
             &i18n_en.command_panel_toggle_vertical_tab_bar_hint,
         );
+        push_menu_item(
+            CommandPanelMenuAction::ToggleThumbnailPanel,
+            i18n.action_toggle_thumbnail_panel.to_string(),
+            i18n.command_panel_toggle_thumbnail_panel_hint.to_string(),
+            &i18n_en.action_toggle_thumbnail_panel,
+            &i18n_en.command_panel_toggle_thumbnail_panel_hint,
+        );
+        push_menu_item(
+            CommandPanelMenuAction::TogglePageOverview,
+            i18n.action_toggle_page_overview.to_string(),
+            i18n.command_panel_toggle_page_overview_hint.to_string(),
+            &i18n_en.action_toggle_page_overview,
+            &i18n_en.command_panel_toggle_page_overview_hint,
+        );
+        push_menu_item(
+            CommandPanelMenuAction::ToggleStatusBar,
+            i18n.action_toggle_status_bar.to_string(),
+            i18n.command_panel_toggle_status_bar_hint.to_string(),
+            &i18n_en.action_toggle_status_bar,
+            &i18n_en.command_panel_toggle_status_bar_hint,
+        );
+        push_menu_item(
+            CommandPanelMenuAction::ToggleGrayscale,
+            i18n.action_toggle_grayscale.to_string(),
+            i18n.command_panel_toggle_grayscale_hint.to_string(),
+            &i18n_en.action_toggle_grayscale,
+            &i18n_en.command_panel_toggle_grayscale_hint,
+        );
+        push_menu_item(
+            CommandPanelMenuAction::ToggleZenMode,
+            i18n.action_toggle_zen_mode.to_string(),
+            i18n.command_panel_toggle_zen_mode_hint.to_string(),
+            &i18n_en.action_toggle_zen_mode,
+            &i18n_en.command_panel_toggle_zen_mode_hint,
+        );
+        push_menu_item(
+            CommandPanelMenuAction::ToggleFullscreen,
+            i18n.action_toggle_fullscreen.to_string(),
+            i18n.command_panel_toggle_fullscreen_hint.to_string(),
+            &i18n_en.action_toggle_fullscreen,
+            &i18n_en.command_panel_toggle_fullscreen_hint,
+        );
+        push_menu_item(
+            CommandPanelMenuAction::TogglePresentation,
+            i18n.action_toggle_presentation.to_string(),
+            i18n.command_panel_toggle_presentation_hint.to_string(),
+            &i18n_en.action_toggle_presentation,
+            &i18n_en.command_panel_toggle_presentation_hint,
+        );
         push_menu_item(
             CommandPanelMenuAction::ShowBookmarks,
             i18n.add_bookmark_button.to_string(),
@@ -257,10 +348,235 @@ impl PdfViewer {
             &i18n_en.command_panel_show_keymap,
             &i18n_en.command_panel_show_keymap_hint,
         );
+        push_menu_item(
+            CommandPanelMenuAction::MergePdfs,
+            i18n.command_panel_merge_pdfs.to_string(),
+            i18n.command_panel_merge_pdfs_hint.to_string(),
+            &i18n_en.command_panel_merge_pdfs,
+            &i18n_en.command_panel_merge_pdfs_hint,
+        );
+        if self.active_tab_path().is_some() {
+            push_menu_item(
+                CommandPanelMenuAction::FirstPage,
+                i18n.action_first_page.to_string(),
+                i18n.command_panel_first_page_hint.to_string(),
+                &i18n_en.action_first_page,
+                &i18n_en.command_panel_first_page_hint,
+            );
+            push_menu_item(
+                CommandPanelMenuAction::LastPage,
+                i18n.action_last_page.to_string(),
+                i18n.command_panel_last_page_hint.to_string(),
+                &i18n_en.action_last_page,
+                &i18n_en.command_panel_last_page_hint,
+            );
+            push_menu_item(
+                CommandPanelMenuAction::TogglePageOrderRightToLeft,
+                i18n.action_toggle_page_order_right_to_left.to_string(),
+                i18n.command_panel_toggle_page_order_right_to_left_hint
+                    .to_string(),
+                &i18n_en.action_toggle_page_order_right_to_left,
+                &i18n_en.command_panel_toggle_page_order_right_to_left_hint,
+            );
+            push_menu_item(
+                CommandPanelMenuAction::SaveOptimizedCopy,
+                i18n.command_panel_save_optimized_copy.to_string(),
+                i18n.command_panel_save_optimized_copy_hint.to_string(),
+                &i18n_en.command_panel_save_optimized_copy,
+                &i18n_en.command_panel_save_optimized_copy_hint,
+            );
+            push_menu_item(
+                CommandPanelMenuAction::AddWatermark,
+                i18n.command_panel_add_watermark.to_string(),
+                i18n.command_panel_add_watermark_hint.to_string(),
+                &i18n_en.command_panel_add_watermark,
+                &i18n_en.command_panel_add_watermark_hint,
+            );
+            push_menu_item(
+                CommandPanelMenuAction::AddHeaderFooter,
+                i18n.command_panel_add_header_footer.to_string(),
+                i18n.command_panel_add_header_footer_hint.to_string(),
+                &i18n_en.command_panel_add_header_footer,
+                &i18n_en.command_panel_add_header_footer_hint,
+            );
+            push_menu_item(
+                CommandPanelMenuAction::SaveWithPassword,
+                i18n.command_panel_save_with_password.to_string(),
+                i18n.command_panel_save_with_password_hint.to_string(),
+                &i18n_en.command_panel_save_with_password,
+                &i18n_en.command_panel_save_with_password_hint,
+            );
+            for kind in ShapeKind::ALL {
+                push_menu_item(
+                    CommandPanelMenuAction::DrawShape(kind),
+                    Self::shape_tool_label(i18n, kind).to_string(),
+                    i18n.command_panel_draw_shape_hint.to_string(),
+                    Self::shape_tool_label(i18n_en, kind),
+                    &i18n_en.command_panel_draw_shape_hint,
+                );
+            }
+            for mode in EraserMode::ALL {
+                push_menu_item(
+                    CommandPanelMenuAction::Erase(mode),
+                    Self::eraser_label(i18n, mode).to_string(),
+                    i18n.command_panel_eraser_hint.to_string(),
+                    Self::eraser_label(i18n_en, mode),
+                    &i18n_en.command_panel_eraser_hint,
+                );
+            }
+            push_menu_item(
+                CommandPanelMenuAction::ExportAnnotations,
+                i18n.command_panel_export_annotations.to_string(),
+                i18n.command_panel_export_annotations_hint.to_string(),
+                &i18n_en.command_panel_export_annotations,
+                &i18n_en.command_panel_export_annotations_hint,
+            );
+            push_menu_item(
+                CommandPanelMenuAction::ImportAnnotations,
+                i18n.command_panel_import_annotations.to_string(),
+                i18n.command_panel_import_annotations_hint.to_string(),
+                &i18n_en.command_panel_import_annotations,
+                &i18n_en.command_panel_import_annotations_hint,
+            );
+            push_menu_item(
+                CommandPanelMenuAction::SaveBookmarkOutline,
+                i18n.command_panel_save_bookmark_outline.to_string(),
+                i18n.command_panel_save_bookmark_outline_hint.to_string(),
+                &i18n_en.command_panel_save_bookmark_outline,
+                &i18n_en.command_panel_save_bookmark_outline_hint,
+            );
+        }
+        push_menu_item(
+            CommandPanelMenuAction::ToggleDebugOverlay,
+            i18n.command_panel_toggle_debug_overlay.to_string(),
+            i18n.command_panel_toggle_debug_overlay_hint.to_string(),
+            &i18n_en.command_panel_toggle_debug_overlay,
+            &i18n_en.command_panel_toggle_debug_overlay_hint,
+        );
 
+        if self.active_tab().is_some_and(|tab| tab.path.is_some()) {
+            items.extend(
+                self.plugin_commands
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, command)| {
+                        query_matches_text(&command.title)
+                            || query_matches_text(&command.subtitle)
+                            || query_matches_text(&command.plugin_name)
+                    })
+                    .map(|(index, _)| CommandPanelItem::Plugin {
+                        index,
+                        recent: false,
+                    }),
+            );
+        }
+
+        items.extend(
+            self.sessions
+                .iter()
+                .filter(|session| {
+                    query_matches_text(&session.name)
+                        || query_matches_text_or_en(
+                            i18n.command_panel_session_badge,
+                            i18n_en.command_panel_session_badge,
+                        )
+                })
+                .map(|session| CommandPanelItem::Session {
+                    name: session.name.clone(),
+                    tab_count: session.tabs.len(),
+                }),
+        );
+        if !open_file_paths.is_empty() {
+            let typed_name = self.command_panel_query.trim();
+            let name = if typed_name.is_empty() {
+                self.default_session_name()
+            } else {
+                typed_name.to_string()
+            };
+            items.push(CommandPanelItem::SaveSession { name });
+        }
+        if let Some(session) = self.session_named(self.command_panel_query.trim()) {
+            items.push(CommandPanelItem::DeleteSession {
+                name: session.name.clone(),
+            });
+        }
+
+        if query.is_empty() {
+            self.pin_command_history(&mut items);
+        }
         items
     }
 
+    /// Identifies a command across runs. Menu commands use the variant name, so renaming one
+    /// only costs its history.
+    fn command_history_key(&self, item: &CommandPanelItem) -> Option<String> {
+        match item {
+            CommandPanelItem::MenuCommand { action, .. } => Some(format!("menu:{action:?}")),
+            CommandPanelItem::Plugin { index, .. } => {
+                let command = self.plugin_commands.get(*index)?;
+                Some(format!("plugin:{}:{}", command.plugin_name, command.title))
+            }
+            CommandPanelItem::OpenFile
+            | CommandPanelItem::OpenTab { .. }
+            | CommandPanelItem::RecentFile { .. }
+            | CommandPanelItem::Session { .. }
+            | CommandPanelItem::SaveSession { .. }
+            | CommandPanelItem::DeleteSession { .. } => None,
+        }
+    }
+
+    /// Moves the most used commands that are available right now to the front.
+    fn pin_command_history(&self, items: &mut Vec<CommandPanelItem>) {
+        let mut ranked = self.command_history.iter().collect::<Vec<_>>();
+        ranked.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| b.last_used_unix_secs.cmp(&a.last_used_unix_secs))
+        });
+
+        let mut pinned = Vec::new();
+        for entry in ranked {
+            if pinned.len() >= COMMAND_PANEL_HISTORY_ITEMS {
+                break;
+            }
+            let Some(position) = items.iter().position(|item| {
+                self.command_history_key(item).as_deref() == Some(entry.key.as_str())
+            }) else {
+                continue;
+            };
+            let mut item = items.remove(position);
+            if let CommandPanelItem::MenuCommand { recent, .. }
+            | CommandPanelItem::Plugin { recent, .. } = &mut item
+            {
+                *recent = true;
+            }
+            pinned.push(item);
+        }
+        items.splice(0..0, pinned);
+    }
+
+    fn record_command_history(&mut self, item: &CommandPanelItem) {
+        let Some(key) = self.command_history_key(item) else {
+            return;
+        };
+        let count = self
+            .command_history
+            .iter()
+            .position(|entry| entry.key == key)
+            .map(|position| self.command_history.remove(position).count)
+            .unwrap_or(0);
+        self.command_history.insert(
+            0,
+            CommandHistoryEntry {
+                key,
+                count: count.saturating_add(1),
+                last_used_unix_secs: Self::now_unix_secs(),
+            },
+        );
+        self.command_history.truncate(MAX_COMMAND_HISTORY);
+        self.persist_command_history();
+    }
+
     pub(super) fn move_command_panel_selection(&mut self, delta: isize, cx: &mut Context<Self>) {
         let items_len = self.command_panel_items().len();
         if items_len == 0 {
@@ -298,6 +614,7 @@ impl PdfViewer {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        self.record_command_history(&item);
         match item {
             CommandPanelItem::OpenFile => {
                 self.close_command_panel(cx);
@@ -321,16 +638,46 @@ impl PdfViewer {
                     }
                     CommandPanelMenuAction::EnableLogging => {
                         if crate::logger::enable_file_logging() {
-                            crate::configure_app_menus(cx, self.i18n());
+                            self.refresh_app_menus(cx);
                         }
                     }
                     CommandPanelMenuAction::DisableLogging => {
                         crate::logger::disable_file_logging();
-                        crate::configure_app_menus(cx, self.i18n());
+                        self.refresh_app_menus(cx);
                     }
                     CommandPanelMenuAction::ToggleVerticalTabBar => {
                         self.toggle_vertical_tab_bar(cx);
                     }
+                    CommandPanelMenuAction::ToggleThumbnailPanel => {
+                        self.toggle_thumbnail_panel(cx);
+                    }
+                    CommandPanelMenuAction::TogglePageOverview => {
+                        self.toggle_page_overview(cx);
+                    }
+                    CommandPanelMenuAction::ToggleStatusBar => {
+                        self.toggle_status_bar(cx);
+                    }
+                    CommandPanelMenuAction::ToggleGrayscale => {
+                        self.toggle_grayscale(cx);
+                    }
+                    CommandPanelMenuAction::ToggleZenMode => {
+                        self.toggle_zen_mode(cx);
+                    }
+                    CommandPanelMenuAction::ToggleFullscreen => {
+                        self.toggle_fullscreen(window);
+                    }
+                    CommandPanelMenuAction::TogglePresentation => {
+                        self.toggle_presentation(window, cx);
+                    }
+                    CommandPanelMenuAction::FirstPage => {
+                        self.first_page(cx);
+                    }
+                    CommandPanelMenuAction::LastPage => {
+                        self.last_page(cx);
+                    }
+                    CommandPanelMenuAction::TogglePageOrderRightToLeft => {
+                        self.toggle_page_order_right_to_left(cx);
+                    }
                     CommandPanelMenuAction::ShowBookmarks => {
                         self.toggle_bookmark_popup(cx);
                     }
@@ -340,6 +687,39 @@ impl PdfViewer {
                     CommandPanelMenuAction::ShowKeymap => {
                         self.open_keymap_dialog(cx);
                     }
+                    CommandPanelMenuAction::MergePdfs => {
+                        self.open_merge_dialog(cx);
+                    }
+                    CommandPanelMenuAction::SaveOptimizedCopy => {
+                        self.open_optimize_dialog(cx);
+                    }
+                    CommandPanelMenuAction::AddWatermark => {
+                        self.open_watermark_dialog(cx);
+                    }
+                    CommandPanelMenuAction::AddHeaderFooter => {
+                        self.open_header_footer_dialog(cx);
+                    }
+                    CommandPanelMenuAction::SaveWithPassword => {
+                        self.open_encrypt_dialog(cx);
+                    }
+                    CommandPanelMenuAction::DrawShape(kind) => {
+                        self.start_shape_tool(kind, cx);
+                    }
+                    CommandPanelMenuAction::Erase(mode) => {
+                        self.start_eraser(mode, cx);
+                    }
+                    CommandPanelMenuAction::ExportAnnotations => {
+                        self.export_annotation_bundle(window, cx);
+                    }
+                    CommandPanelMenuAction::ImportAnnotations => {
+                        self.import_annotation_bundle(window, cx);
+                    }
+                    CommandPanelMenuAction::SaveBookmarkOutline => {
+                        self.save_copy_with_bookmark_outline(window, cx);
+                    }
+                    CommandPanelMenuAction::ToggleDebugOverlay => {
+                        self.toggle_debug_overlay(cx);
+                    }
                 }
             }
             CommandPanelItem::OpenTab { tab_id, .. } => {
@@ -350,6 +730,22 @@ impl PdfViewer {
                 self.close_command_panel(cx);
                 self.open_recent_pdf(path, cx);
             }
+            CommandPanelItem::Plugin { index, .. } => {
+                self.close_command_panel(cx);
+                self.run_plugin_command(index, cx);
+            }
+            CommandPanelItem::Session { name, .. } => {
+                self.close_command_panel(cx);
+                self.restore_session(&name, cx);
+            }
+            CommandPanelItem::SaveSession { name } => {
+                self.close_command_panel(cx);
+                self.save_session(&name, cx);
+            }
+            CommandPanelItem::DeleteSession { name } => {
+                self.close_command_panel(cx);
+                self.delete_session(&name, cx);
+            }
         }
     }
 
@@ -357,6 +753,7 @@ impl PdfViewer {
         if !self.command_panel_open {
             return None;
         }
+        let density = self.ui_density;
 
         let i18n = self.i18n();
         let viewer = cx.entity();
@@ -402,11 +799,18 @@ impl PdfViewer {
                                     None,
                                 ),
                                 CommandPanelItem::MenuCommand {
-                                    title, subtitle, ..
+                                    title,
+                                    subtitle,
+                                    recent,
+                                    ..
                                 } => (
                                     title.clone(),
                                     subtitle.clone(),
-                                    Some(i18n.command_panel_menu_badge.to_string()),
+                                    Some(if *recent {
+                                        i18n.command_panel_recent_badge.to_string()
+                                    } else {
+                                        i18n.command_panel_menu_badge.to_string()
+                                    }),
                                     None,
                                 ),
                                 CommandPanelItem::OpenTab {
@@ -431,6 +835,37 @@ impl PdfViewer {
                                     last_seen_page
                                         .map(|page_index| i18n.last_seen_page(page_index + 1)),
                                 ),
+                                CommandPanelItem::Plugin { index, recent } => {
+                                    let command = &self.plugin_commands[*index];
+                                    (
+                                        command.title.clone(),
+                                        command.subtitle.clone(),
+                                        Some(if *recent {
+                                            i18n.command_panel_recent_badge.to_string()
+                                        } else {
+                                            i18n.command_panel_plugin_badge.to_string()
+                                        }),
+                                        Some(command.plugin_name.clone()),
+                                    )
+                                }
+                                CommandPanelItem::Session { name, tab_count } => (
+                                    name.clone(),
+                                    i18n.session_tab_count(*tab_count),
+                                    Some(i18n.command_panel_session_badge.to_string()),
+                                    None,
+                                ),
+                                CommandPanelItem::SaveSession { name } => (
+                                    i18n.command_panel_save_session(name),
+                                    i18n.command_panel_save_session_hint.to_string(),
+                                    Some(i18n.command_panel_menu_badge.to_string()),
+                                    None,
+                                ),
+                                CommandPanelItem::DeleteSession { name } => (
+                                    i18n.command_panel_delete_session(name),
+                                    i18n.command_panel_delete_session_hint.to_string(),
+                                    Some(i18n.command_panel_menu_badge.to_string()),
+                                    None,
+                                ),
                             };
 
                             div()
@@ -438,7 +873,7 @@ impl PdfViewer {
                                 .w_full()
                                 .rounded_md()
                                 .px_2()
-                                .py_1()
+                                .py(density.spacing(0.25))
                                 .cursor_pointer()
                                 .when(is_selected, |this| {
                                     this.border_1()
@@ -2,19 +2,70 @@ pub(super) const ZOOM_MIN: f32 = 0.6;
 pub(super) const ZOOM_MAX: f32 = 1.0;
 pub(super) const ZOOM_STEP: f32 = 0.1;
 pub(super) const SIDEBAR_WIDTH: f32 = 228.0;
+pub(super) const SIDEBAR_MIN_WIDTH: f32 = 140.0;
+pub(super) const SIDEBAR_MAX_WIDTH: f32 = 520.0;
+pub(super) const SIDEBAR_RESIZE_HANDLE_WIDTH: f32 = 6.0;
+pub(super) const SIDEBAR_TAB_BAR_HEIGHT: f32 = 32.0;
 pub(super) const THUMB_MIN_WIDTH: f32 = 96.0;
+pub(super) const PAGE_OVERVIEW_CELL_WIDTH: f32 = 180.0;
+pub(super) const PAGE_OVERVIEW_CELL_MIN_WIDTH: f32 = 96.0;
+pub(super) const PAGE_OVERVIEW_CELL_MAX_WIDTH: f32 = 480.0;
+pub(super) const PAGE_OVERVIEW_CELL_WIDTH_STEP: f32 = 40.0;
+pub(super) const PAGE_OVERVIEW_GAP: f32 = 16.0;
+pub(super) const PAGE_OVERVIEW_LABEL_HEIGHT: f32 = 20.0;
 pub(super) const THUMB_HORIZONTAL_PADDING: f32 = 16.0;
 pub(super) const THUMB_VERTICAL_PADDING: f32 = 8.0;
 pub(super) const THUMB_BATCH_SIZE: usize = 1;
-pub(super) const THUMB_MAX_PARALLEL_TASKS: usize = 1;
+pub(super) const THUMB_PREFETCH_PAGES: usize = 4;
 pub(super) const DISPLAY_MIN_WIDTH: f32 = 220.0;
 pub(super) const DISPLAY_BATCH_SIZE: usize = 1;
-pub(super) const DISPLAY_MAX_PARALLEL_TASKS: usize = 1;
+pub(super) const DISPLAY_PREFETCH_PAGES: usize = 2;
+pub(super) const DISPLAY_PREVIEW_SCALE: f32 = 0.25;
+pub(super) const DISPLAY_PREVIEW_MIN_WIDTH: u32 = 96;
+pub(super) const DISPLAY_TILE_PIXEL_THRESHOLD: u64 = 4096 * 4096;
+pub(super) const DISPLAY_TILE_HEIGHT: u32 = 1024;
+pub(super) const DISPLAY_TILE_BATCH_SIZE: usize = 2;
+/// Render jobs a tab keeps in flight. Pdfium still draws one page at a time behind its global
+/// lock, so with it the extra jobs only overlap disk cache reads and pixel conversion.
+pub(super) const RENDER_WORKERS_DEFAULT: usize = 2;
+pub(super) const RENDER_WORKERS_MAX: usize = 4;
+/// Supersampling factors, in percent of the layout width, offered in settings.
+pub(super) const RENDER_QUALITY_OPTIONS_PERCENT: [u8; 5] = [100, 125, 150, 175, 200];
+pub(super) const RENDER_QUALITY_DEFAULT_PERCENT: u8 = 100;
+pub(super) const WINDOW_OPACITY_OPTIONS_PERCENT: [u8; 5] = [100, 90, 80, 70, 50];
+pub(super) const WINDOW_OPACITY_DEFAULT_PERCENT: u8 = 100;
+pub(super) const UI_FONT_SIZE_OPTIONS: [u8; 5] = [12, 14, 16, 18, 20];
+pub(super) const UI_FONT_SIZE_DEFAULT: u8 = 16;
+pub(super) const RENDER_CACHE_LIMIT_OPTIONS_MB: [u64; 4] = [256, 512, 1024, 2048];
+pub(super) const RENDER_CACHE_LIMIT_DEFAULT_MB: u64 = 512;
+/// Quiet time after the last input before idle pre-rendering starts.
+pub(super) const IDLE_PRERENDER_DELAY_MS: u64 = 5000;
+/// Pause between idle pre-rendered pages, so the machine never stays busy for long.
+pub(super) const IDLE_PRERENDER_THROTTLE_MS: u64 = 400;
 pub(super) const DISPLAY_SCROLL_SYNC_DELAY_MS: u64 = 140;
+pub(super) const DISPLAY_ZOOM_RENDER_DELAY_MS: u64 = 180;
+pub(super) const SUMMARY_CHUNK_PAGES: usize = 500;
+pub(super) const SUMMARY_PROGRESS_POLL_MS: u64 = 100;
+/// Text shown per page when Pdfium is missing; the card clips anything longer anyway.
+pub(super) const SKELETON_PAGE_TEXT_MAX_CHARS: usize = 4000;
+/// How often files forwarded by later launches are picked up.
+pub(super) const FORWARDED_LAUNCH_POLL_MS: u64 = 200;
+pub(super) const TEXT_PREFETCH_PAGE_DELAY_MS: u64 = 15;
 pub(super) const MAX_RECENT_FILES: usize = 12;
+pub(super) const MAX_COMMAND_HISTORY: usize = 50;
+pub(super) const MAX_NAVIGATION_HISTORY: usize = 100;
+/// Horizontal trackpad travel, in pixels, that turns a scroll gesture into back/forward.
+pub(super) const SWIPE_NAVIGATION_THRESHOLD: f32 = 120.0;
+/// Gap between scroll events that ends a gesture on platforms without touch phases.
+pub(super) const SWIPE_GESTURE_IDLE_MS: u64 = 250;
+pub(super) const COMMAND_PANEL_HISTORY_ITEMS: usize = 5;
 pub(super) const RECENT_FILES_LIST_MAX_HEIGHT: f32 = 280.0;
 pub(super) const RECENT_POPUP_CLOSE_DELAY_MS: u64 = 120;
 pub(super) const BOOKMARK_POPUP_CLOSE_DELAY_MS: u64 = 120;
+pub(super) const PDFIUM_DOWNLOAD_PROGRESS_POLL_MS: u64 = 100;
+pub(super) const UPDATE_DOWNLOAD_PROGRESS_POLL_MS: u64 = 100;
+/// Keeps the startup update check from competing with restoring tabs.
+pub(super) const UPDATE_STARTUP_CHECK_DELAY_SECS: u64 = 5;
 pub(super) const RECENT_FILES_TREE: &str = "recent_files";
 pub(super) const FILE_POSITIONS_TREE: &str = "file_positions";
 pub(super) const WINDOW_SIZE_TREE: &str = "window_size";
@@ -24,29 +75,139 @@ pub(super) const THEME_PREFERENCES_TREE: &str = "theme_preferences";
 pub(super) const BOOKMARKS_TREE: &str = "bookmarks";
 pub(super) const NOTES_TREE: &str = "notes";
 pub(super) const TEXT_MARKUPS_TREE: &str = "text_markups";
+pub(super) const STAMPS_TREE: &str = "stamps";
+pub(super) const SHAPES_TREE: &str = "shapes";
 pub(super) const TAB_LAYOUT_MODE_TREE: &str = "tab_layout_mode";
 pub(super) const VERTICAL_TAB_BAR_VISIBLE_TREE: &str = "vertical_tab_bar_visible";
 pub(super) const THUMBNAIL_PANEL_VISIBLE_TREE: &str = "thumbnail_panel_visible";
+pub(super) const VIEW_PREFERENCES_TREE: &str = "view_preferences";
+pub(super) const SESSIONS_TREE: &str = "sessions";
 pub(super) const TAB_LAYOUT_MODE_KEY: &str = "tab_layout_mode";
 pub(super) const VERTICAL_TAB_BAR_VISIBLE_KEY: &str = "v_tab_bar_visible";
 pub(super) const THUMBNAIL_PANEL_VISIBLE_KEY: &str = "thumbnail_panel_visible";
+pub(super) const VIEW_PREFERENCES_KEY_SIDEBAR_WIDTH: &str = "sidebar_width";
+pub(super) const VIEW_PREFERENCES_KEY_SIDEBAR_TAB_PREFIX: &str = "sidebar_tab:";
+pub(super) const VIEW_PREFERENCES_KEY_STATUS_BAR_VISIBLE: &str = "status_bar_visible";
+pub(super) const VIEW_PREFERENCES_KEY_ZEN_MODE: &str = "zen_mode";
+pub(super) const VIEW_PREFERENCES_KEY_PRESENTATION_TIMER: &str = "presentation_timer_visible";
+pub(super) const VIEW_PREFERENCES_KEY_PRESENTATION_AUTO_ADVANCE: &str =
+    "presentation_auto_advance_secs";
+pub(super) const VIEW_PREFERENCES_KEY_PRESENTATION_POINTER: &str = "presentation_pointer";
+pub(super) const VIEW_PREFERENCES_KEY_RENDER_WORKERS: &str = "render_workers";
+pub(super) const VIEW_PREFERENCES_KEY_RENDER_QUALITY: &str = "render_quality_percent";
+pub(super) const VIEW_PREFERENCES_KEY_UI_FONT_SIZE: &str = "ui_font_size";
+pub(super) const VIEW_PREFERENCES_KEY_UI_DENSITY: &str = "ui_density";
+pub(super) const VIEW_PREFERENCES_KEY_RENDER_CACHE_ENABLED: &str = "render_cache_enabled";
+pub(super) const VIEW_PREFERENCES_KEY_RENDER_CACHE_LIMIT_MB: &str = "render_cache_limit_mb";
+pub(super) const VIEW_PREFERENCES_KEY_IDLE_PRERENDER: &str = "idle_prerender";
+pub(super) const VIEW_PREFERENCES_KEY_RENDER_ENGINE: &str = "render_engine";
+pub(super) const VIEW_PREFERENCES_KEY_TEXT_ANTIALIASING: &str = "text_antialiasing";
+pub(super) const VIEW_PREFERENCES_KEY_NATIVE_TEXT: &str = "native_text_rendering";
+pub(super) const VIEW_PREFERENCES_KEY_GRAYSCALE: &str = "grayscale_rendering";
+pub(super) const VIEW_PREFERENCES_KEY_PDFIUM_LIBRARY: &str = "pdfium_library_path";
+pub(super) const VIEW_PREFERENCES_KEY_PDFIUM_MIRROR: &str = "pdfium_download_mirror";
+/// Shared by pdfium and update downloads; the key predates the updater using it.
+pub(super) const VIEW_PREFERENCES_KEY_DOWNLOAD_PROXY: &str = "pdfium_download_proxy";
+pub(super) const VIEW_PREFERENCES_KEY_UPDATE_ENDPOINT: &str = "update_endpoint";
+pub(super) const VIEW_PREFERENCES_KEY_EXTERNAL_TOOLS: &str = "external_tools";
+pub(super) const VIEW_PREFERENCES_KEY_UPDATE_CHANNEL: &str = "update_channel";
+pub(super) const VIEW_PREFERENCES_KEY_UPDATE_CHECK_FREQUENCY: &str = "update_check_frequency";
+pub(super) const VIEW_PREFERENCES_KEY_LAST_UPDATE_CHECK: &str = "last_update_check_unix_secs";
+pub(super) const VIEW_PREFERENCES_KEY_ANNOTATION_DEFAULTS: &str = "annotation_defaults";
+pub(super) const VIEW_PREFERENCES_KEY_COMMAND_HISTORY: &str = "command_history";
+pub(super) const VIEW_PREFERENCES_KEY_SEARCH_HIGHLIGHT_ALL: &str = "search_highlight_all";
+pub(super) const VIEW_PREFERENCES_KEY_COMIC_RIGHT_TO_LEFT: &str = "comic_right_to_left";
+pub(super) const VIEW_PREFERENCES_KEY_STARTUP_BEHAVIOR: &str = "startup_behavior";
+pub(super) const VIEW_PREFERENCES_KEY_PAGE_DOUBLE_CLICK: &str = "page_double_click_action";
 pub(super) const WINDOW_SIZE_KEY_HEIGHT: &str = "height";
+pub(super) const WINDOW_SIZE_KEY_X: &str = "x";
+pub(super) const WINDOW_SIZE_KEY_Y: &str = "y";
+pub(super) const WINDOW_SIZE_KEY_DISPLAY: &str = "display_uuid";
+pub(super) const WINDOW_SIZE_KEY_OPACITY: &str = "opacity_percent";
 pub(super) const OPEN_TABS_KEY_ACTIVE_INDEX: &str = "active_index";
+pub(super) const TITLEBAR_PREFERENCES_KEY_TOOLBAR: &str = "toolbar";
+/// Written by versions before the toolbar became configurable; only read for migration.
 pub(super) const TITLEBAR_PREFERENCES_KEY_SHOW_NAVIGATION: &str = "show_navigation";
 pub(super) const TITLEBAR_PREFERENCES_KEY_SHOW_ZOOM: &str = "show_zoom";
 pub(super) const THEME_PREFERENCES_KEY_MODE: &str = "mode";
 pub(super) const THEME_PREFERENCES_KEY_LIGHT_NAME: &str = "light_name";
 pub(super) const THEME_PREFERENCES_KEY_DARK_NAME: &str = "dark_name";
+pub(super) const THEME_PREFERENCES_KEY_ACCENT_COLOR: &str = "accent_color";
 pub(super) const THEME_PREFERENCES_KEY_LANGUAGE: &str = "language";
+/// Themes shipped in `assets/themes/high-contrast.json`; they also force black-on-white pages.
+pub(super) const HIGH_CONTRAST_THEME_NAMES: [&str; 2] =
+    ["High Contrast Light", "High Contrast Dark"];
 pub(super) const WINDOW_SIZE_KEY_WIDTH: &str = "width";
 pub(super) const TITLE_BAR_HEIGHT: f32 = 34.0;
 pub(super) const TAB_BAR_HEIGHT: f32 = 36.0;
+pub(super) const STATUS_BAR_HEIGHT: f32 = 24.0;
+/// Distance from the window top that reveals the hidden chrome in zen mode.
+pub(super) const ZEN_MODE_REVEAL_EDGE: f32 = 6.0;
+pub(super) const FULLSCREEN_IDLE_HIDE_DELAY_MS: u64 = 3000;
+/// Auto-advance intervals offered in settings; 0 turns auto-advance off.
+pub(super) const PRESENTATION_AUTO_ADVANCE_OPTIONS_SECS: [u8; 6] = [0, 5, 10, 15, 30, 60];
+/// How long the button has to stay down before a click turns into the pointer overlay.
+pub(super) const PRESENTATION_POINTER_HOLD_MS: u64 = 200;
+pub(super) const PRESENTATION_LASER_DOT_SIZE: f32 = 14.0;
+pub(super) const PRESENTATION_SPOTLIGHT_RADIUS: f32 = 120.0;
+/// Vertical trackpad travel, in pixels, that flips a slide while presenting.
+pub(super) const PRESENTATION_WHEEL_THRESHOLD: f32 = 60.0;
+/// Redraw interval while the debug overlay is open, so FPS and queue depths stay live.
+pub(super) const DEBUG_OVERLAY_REFRESH_MS: u64 = 500;
+pub(super) const DEBUG_OVERLAY_RENDER_TIMING_LIMIT: usize = 8;
+pub(super) const DEBUG_OVERLAY_WIDTH: f32 = 260.0;
 pub(super) const VERTICAL_TAB_BAR_WIDTH: f32 = 200.0;
 pub(super) const TAB_DRAG_START_DISTANCE: f32 = 4.0;
 pub(super) const ABOUT_DIALOG_WIDTH: f32 = 460.0;
 pub(super) const ABOUT_DIALOG_WINDOW_HEIGHT: f32 = 420.0;
 pub(super) const SETTINGS_DIALOG_WIDTH: f32 = 520.0;
 pub(super) const SETTINGS_DIALOG_WINDOW_HEIGHT: f32 = 620.0;
+pub(super) const MERGE_DIALOG_WIDTH: f32 = 520.0;
+pub(super) const MERGE_DIALOG_WINDOW_HEIGHT: f32 = 520.0;
+pub(super) const MERGE_PROGRESS_POLL_MS: u64 = 100;
+pub(super) const OPTIMIZE_DIALOG_WIDTH: f32 = 480.0;
+pub(super) const OPTIMIZE_DIALOG_WINDOW_HEIGHT: f32 = 360.0;
+pub(super) const OPTIMIZE_PROGRESS_POLL_MS: u64 = 100;
+/// Image resolutions offered when saving an optimized copy; 0 leaves images untouched.
+pub(super) const OPTIMIZE_IMAGE_DPI_OPTIONS: [u32; 4] = [0, 72, 150, 300];
+pub(super) const OPTIMIZE_IMAGE_DPI_DEFAULT: u32 = 150;
+pub(super) const WATERMARK_DIALOG_WIDTH: f32 = 520.0;
+pub(super) const WATERMARK_DIALOG_WINDOW_HEIGHT: f32 = 560.0;
+pub(super) const WATERMARK_PROGRESS_POLL_MS: u64 = 100;
+pub(super) const WATERMARK_DEFAULT_TEXT: &str = "DRAFT";
+/// Text colors offered for watermarks, in the order of the gray/red choices.
+pub(super) const WATERMARK_TEXT_COLORS: [[u8; 3]; 2] = [[128, 128, 128], [200, 0, 0]];
+pub(super) const WATERMARK_OPACITY_OPTIONS: [f32; 4] = [0.15, 0.3, 0.5, 0.8];
+pub(super) const WATERMARK_ROTATION_OPTIONS: [f32; 4] = [0.0, 30.0, 45.0, 90.0];
+/// Watermark width as a fraction of the page width.
+pub(super) const WATERMARK_SIZE_OPTIONS: [f32; 3] = [0.25, 0.5, 0.75];
+pub(super) const HEADER_FOOTER_DIALOG_WIDTH: f32 = 520.0;
+pub(super) const HEADER_FOOTER_DIALOG_WINDOW_HEIGHT: f32 = 680.0;
+pub(super) const HEADER_FOOTER_PROGRESS_POLL_MS: u64 = 100;
+pub(super) const HEADER_FOOTER_PREVIEW_DEBOUNCE_MS: u64 = 250;
+pub(super) const HEADER_FOOTER_PREVIEW_RENDER_WIDTH: u32 = 480;
+pub(super) const HEADER_FOOTER_PREVIEW_HEIGHT: f32 = 260.0;
+pub(super) const HEADER_FOOTER_DEFAULT_FOOTER: &str = "{page} / {pages}";
+pub(super) const HEADER_FOOTER_FONT_SIZE_OPTIONS: [f32; 4] = [8.0, 10.0, 12.0, 14.0];
+pub(super) const HEADER_FOOTER_DEFAULT_FONT_SIZE_INDEX: usize = 1;
+pub(super) const ENCRYPT_DIALOG_WIDTH: f32 = 460.0;
+pub(super) const ENCRYPT_DIALOG_WINDOW_HEIGHT: f32 = 520.0;
+pub(super) const STAMP_DIALOG_WIDTH: f32 = 420.0;
+pub(super) const STAMP_DIALOG_WINDOW_HEIGHT: f32 = 420.0;
+/// New stamps span this fraction of the page width; height follows the stamp's aspect.
+pub(super) const STAMP_DEFAULT_WIDTH_RATIO: f32 = 0.28;
+/// Height-to-width ratio used for preset and custom text stamps.
+pub(super) const STAMP_TEXT_ASPECT: f32 = 0.3;
+pub(super) const SHAPE_STROKE_WIDTH_OPTIONS: [f32; 3] = [1.0, 2.0, 4.0];
+/// Pointer distance in logical pixels that still counts as touching a shape or its handles.
+pub(super) const SHAPE_HIT_TOLERANCE: f32 = 6.0;
+pub(super) const SHAPE_HANDLE_SIZE: f32 = 8.0;
+/// Shapes dragged out shorter than this many page points are discarded as stray clicks.
+pub(super) const SHAPE_MIN_DRAG_DISTANCE: f32 = 4.0;
+/// Ink samples closer than this many logical pixels to the previous one are dropped.
+pub(super) const INK_MIN_POINT_DISTANCE: f32 = 2.0;
+pub(super) const ERASER_RADIUS: f32 = 10.0;
+pub(super) const SHAPE_UNDO_LIMIT: usize = 50;
 pub(super) const MARKDOWN_NOTE_EDITOR_WIDTH: f32 = 640.0;
 pub(super) const MARKDOWN_NOTE_EDITOR_WINDOW_HEIGHT: f32 = 760.0;
 pub(super) const MARKDOWN_NOTE_MARKER_RADIUS: f32 = 9.0;
@@ -26,12 +26,19 @@ impl PdfViewer {
         Option<sled::Tree>,
         Option<sled::Tree>,
         Option<sled::Tree>,
+        Option<sled::Tree>,
+        Option<sled::Tree>,
+        Option<sled::Tree>,
+        Option<sled::Tree>,
     ) {
         let db_path = Self::local_state_db_path();
         if let Some(parent) = db_path.parent() {
             if std::fs::create_dir_all(parent).is_err() {
-                crate::debug_log!("[store] create dir failed: {}", parent.to_string_lossy());
-                return (None, None, None, None, None, None, None, None, None, None, None, None);
+                crate::debug_log!(@warn, "[store] create dir failed: {}", parent.to_string_lossy());
+                return (
+                    None, None, None, None, None, None, None, None, None, None, None, None, None,
+                    None, None, None,
+                );
             }
         }
 
@@ -39,18 +46,27 @@ impl PdfViewer {
             Ok(db) => db,
             Err(err) => {
                 crate::debug_log!(
+                    @warn,
                     "[store] open db failed: {} | {}",
                     db_path.to_string_lossy(),
                     err
                 );
-                return (None, None, None, None, None, None, None, None, None, None, None, None);
+                return (
+                    None, None, None, None, None, None, None, None, None, None, None, None, None,
+                    None, None, None,
+                );
             }
         };
 
         let recent_store = match db.open_tree(RECENT_FILES_TREE) {
             Ok(tree) => Some(tree),
             Err(err) => {
-                crate::debug_log!("[store] open tree failed: {} | {}", RECENT_FILES_TREE, err);
+                crate::debug_log!(
+                    @warn,
+                    "[store] open tree failed: {} | {}",
+                    RECENT_FILES_TREE,
+                    err,
+                );
                 None
             }
         };
@@ -58,6 +74,7 @@ impl PdfViewer {
             Ok(tree) => Some(tree),
             Err(err) => {
                 crate::debug_log!(
+                    @warn,
                     "[store] open tree failed: {} | {}",
                     FILE_POSITIONS_TREE,
                     err
@@ -68,14 +85,19 @@ impl PdfViewer {
         let window_size_store = match db.open_tree(WINDOW_SIZE_TREE) {
             Ok(tree) => Some(tree),
             Err(err) => {
-                crate::debug_log!("[store] open tree failed: {} | {}", WINDOW_SIZE_TREE, err);
+                crate::debug_log!(
+                    @warn,
+                    "[store] open tree failed: {} | {}",
+                    WINDOW_SIZE_TREE,
+                    err,
+                );
                 None
             }
         };
         let open_tabs_store = match db.open_tree(OPEN_TABS_TREE) {
             Ok(tree) => Some(tree),
             Err(err) => {
-                crate::debug_log!("[store] open tree failed: {} | {}", OPEN_TABS_TREE, err);
+                crate::debug_log!(@warn, "[store] open tree failed: {} | {}", OPEN_TABS_TREE, err);
                 None
             }
         };
@@ -83,6 +105,7 @@ impl PdfViewer {
             Ok(tree) => Some(tree),
             Err(err) => {
                 crate::debug_log!(
+                    @warn,
                     "[store] open tree failed: {} | {}",
                     TITLEBAR_PREFERENCES_TREE,
                     err
@@ -94,6 +117,7 @@ impl PdfViewer {
             Ok(tree) => Some(tree),
             Err(err) => {
                 crate::debug_log!(
+                    @warn,
                     "[store] open tree failed: {} | {}",
                     THEME_PREFERENCES_TREE,
                     err
@@ -104,21 +128,40 @@ impl PdfViewer {
         let bookmarks_store = match db.open_tree(BOOKMARKS_TREE) {
             Ok(tree) => Some(tree),
             Err(err) => {
-                crate::debug_log!("[store] open tree failed: {} | {}", BOOKMARKS_TREE, err);
+                crate::debug_log!(@warn, "[store] open tree failed: {} | {}", BOOKMARKS_TREE, err);
                 None
             }
         };
         let notes_store = match db.open_tree(NOTES_TREE) {
             Ok(tree) => Some(tree),
             Err(err) => {
-                crate::debug_log!("[store] open tree failed: {} | {}", NOTES_TREE, err);
+                crate::debug_log!(@warn, "[store] open tree failed: {} | {}", NOTES_TREE, err);
                 None
             }
         };
         let text_markups_store = match db.open_tree(TEXT_MARKUPS_TREE) {
             Ok(tree) => Some(tree),
             Err(err) => {
-                crate::debug_log!("[store] open tree failed: {} | {}", TEXT_MARKUPS_TREE, err);
+                crate::debug_log!(
+                    @warn,
+                    "[store] open tree failed: {} | {}",
+                    TEXT_MARKUPS_TREE,
+                    err,
+                );
+                None
+            }
+        };
+        let stamps_store = match db.open_tree(STAMPS_TREE) {
+            Ok(tree) => Some(tree),
+            Err(err) => {
+                crate::debug_log!(@warn, "[store] open tree failed: {} | {}", STAMPS_TREE, err);
+                None
+            }
+        };
+        let shapes_store = match db.open_tree(SHAPES_TREE) {
+            Ok(tree) => Some(tree),
+            Err(err) => {
+                crate::debug_log!(@warn, "[store] open tree failed: {} | {}", SHAPES_TREE, err);
                 None
             }
         };
@@ -126,6 +169,7 @@ impl PdfViewer {
             Ok(tree) => Some(tree),
             Err(err) => {
                 crate::debug_log!(
+                    @warn,
                     "[store] open tree failed: {} | {}",
                     TAB_LAYOUT_MODE_TREE,
                     err
@@ -137,6 +181,7 @@ impl PdfViewer {
             Ok(tree) => Some(tree),
             Err(err) => {
                 crate::debug_log!(
+                    @warn,
                     "[store] open tree failed: {} | {}",
                     VERTICAL_TAB_BAR_VISIBLE_TREE,
                     err
@@ -148,6 +193,7 @@ impl PdfViewer {
             Ok(tree) => Some(tree),
             Err(err) => {
                 crate::debug_log!(
+                    @warn,
                     "[store] open tree failed: {} | {}",
                     THUMBNAIL_PANEL_VISIBLE_TREE,
                     err
@@ -155,9 +201,28 @@ impl PdfViewer {
                 None
             }
         };
+        let view_preferences_store = match db.open_tree(VIEW_PREFERENCES_TREE) {
+            Ok(tree) => Some(tree),
+            Err(err) => {
+                crate::debug_log!(
+                    @warn,
+                    "[store] open tree failed: {} | {}",
+                    VIEW_PREFERENCES_TREE,
+                    err
+                );
+                None
+            }
+        };
+        let sessions_store = match db.open_tree(SESSIONS_TREE) {
+            Ok(tree) => Some(tree),
+            Err(err) => {
+                crate::debug_log!(@warn, "[store] open tree failed: {} | {}", SESSIONS_TREE, err);
+                None
+            }
+        };
 
         crate::debug_log!(
-            "[store] init recent={} positions={} window_size={} open_tabs={} titlebar_preferences={} theme_preferences={} bookmarks={} notes={} text_markups={} tab_layout_mode={} vertical_tab_bar_visible={} thumbnail_panel_visible={} path={}",
+            "[store] init recent={} positions={} window_size={} open_tabs={} titlebar_preferences={} theme_preferences={} bookmarks={} notes={} text_markups={} stamps={} shapes={} tab_layout_mode={} vertical_tab_bar_visible={} thumbnail_panel_visible={} view_preferences={} sessions={} path={}",
             recent_store.is_some(),
             position_store.is_some(),
             window_size_store.is_some(),
@@ -167,9 +232,13 @@ impl PdfViewer {
             bookmarks_store.is_some(),
             notes_store.is_some(),
             text_markups_store.is_some(),
+            stamps_store.is_some(),
+            shapes_store.is_some(),
             tab_layout_mode_store.is_some(),
             vertical_tab_bar_visible_store.is_some(),
             thumbnail_panel_visible_store.is_some(),
+            view_preferences_store.is_some(),
+            sessions_store.is_some(),
             db_path.to_string_lossy()
         );
 
@@ -183,26 +252,20 @@ impl PdfViewer {
             bookmarks_store,
             notes_store,
             text_markups_store,
+            stamps_store,
+            shapes_store,
             tab_layout_mode_store,
             vertical_tab_bar_visible_store,
             thumbnail_panel_visible_store,
+            view_preferences_store,
+            sessions_store,
         )
     }
 
-    fn local_state_db_path() -> PathBuf {
-        if let Some(app_data) = std::env::var_os("APPDATA") {
-            return PathBuf::from(app_data)
-                .join("kpdf")
-                .join(Self::LOCAL_STATE_DB_DIR_NAME);
-        }
-
-        if let Some(home) = std::env::var_os("HOME") {
-            return PathBuf::from(home)
-                .join(".kpdf")
-                .join(Self::LOCAL_STATE_DB_DIR_NAME);
-        }
-
-        PathBuf::from(".kpdf").join(Self::LOCAL_STATE_DB_DIR_NAME)
+    pub(crate) fn local_state_db_path() -> PathBuf {
+        kpdf_core::paths::data_dir()
+            .unwrap_or_else(|| PathBuf::from(".kpdf"))
+            .join(Self::LOCAL_STATE_DB_DIR_NAME)
     }
 
     fn directory_usage_bytes(path: &Path) -> u64 {
@@ -300,57 +363,15 @@ impl PdfViewer {
     }
 
     fn load_markdown_notes_from_store(store: &sled::Tree) -> Vec<MarkdownNoteEntry> {
-        let mut indexed_notes = Vec::new();
-        for entry in store.iter() {
-            let (key, value) = match entry {
-                Ok(entry) => entry,
-                Err(_) => continue,
-            };
-            if key.len() != 4 {
-                continue;
-            }
-            let note_index = u32::from_be_bytes([key[0], key[1], key[2], key[3]]) as usize;
-            let note = match serde_json::from_slice::<MarkdownNoteEntry>(&value) {
-                Ok(note) => note,
-                Err(_) => continue,
-            };
-            if note.markdown.trim().is_empty() {
-                continue;
-            }
-            indexed_notes.push((note_index, note));
-        }
-        indexed_notes.sort_by_key(|(index, _)| *index);
-        indexed_notes
-            .into_iter()
-            .map(|(_, note)| note)
-            .collect::<Vec<_>>()
+        let mut notes = annotations::load_entries::<MarkdownNoteEntry>(store);
+        notes.retain(|note| !note.markdown.trim().is_empty());
+        notes
     }
 
     fn load_text_markups_from_store(store: &sled::Tree) -> Vec<TextMarkupEntry> {
-        let mut indexed_markups = Vec::new();
-        for entry in store.iter() {
-            let (key, value) = match entry {
-                Ok(entry) => entry,
-                Err(_) => continue,
-            };
-            if key.len() != 4 {
-                continue;
-            }
-            let markup_index = u32::from_be_bytes([key[0], key[1], key[2], key[3]]) as usize;
-            let markup = match serde_json::from_slice::<TextMarkupEntry>(&value) {
-                Ok(markup) => markup,
-                Err(_) => continue,
-            };
-            if markup.rects.is_empty() {
-                continue;
-            }
-            indexed_markups.push((markup_index, markup));
-        }
-        indexed_markups.sort_by_key(|(index, _)| *index);
-        indexed_markups
-            .into_iter()
-            .map(|(_, markup)| markup)
-            .collect::<Vec<_>>()
+        let mut markups = annotations::load_entries::<TextMarkupEntry>(store);
+        markups.retain(|markup| !markup.rects.is_empty());
+        markups
     }
 
     fn load_open_tabs_from_store(store: &sled::Tree) -> (Vec<PathBuf>, Option<usize>) {
@@ -410,23 +431,161 @@ impl PdfViewer {
         Some(value)
     }
 
-    fn load_titlebar_preferences_from_store(store: &sled::Tree) -> TitleBarVisibilityPreferences {
-        let default = TitleBarVisibilityPreferences::default();
-        TitleBarVisibilityPreferences {
-            show_navigation: Self::decode_stored_bool(
-                store
-                    .get(TITLEBAR_PREFERENCES_KEY_SHOW_NAVIGATION)
-                    .ok()
-                    .flatten(),
-                default.show_navigation,
+    /// Parses `#RRGGBB`; the `#` is optional.
+    fn parse_hex_color(text: &str) -> Option<Hsla> {
+        let hex = text.trim().trim_start_matches('#');
+        if hex.len() != 6 || !hex.chars().all(|ch| ch.is_ascii_hexdigit()) {
+            return None;
+        }
+        u32::from_str_radix(hex, 16).ok().map(|value| rgb(value).into())
+    }
+
+    fn format_hex_color(color: Hsla) -> String {
+        let color = Rgba::from(color);
+        let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        format!(
+            "#{:02X}{:02X}{:02X}",
+            channel(color.r),
+            channel(color.g),
+            channel(color.b)
+        )
+    }
+
+    fn load_titlebar_preferences_from_store(store: &sled::Tree) -> TitleBarPreferences {
+        if let Some(entries) = store
+            .get(TITLEBAR_PREFERENCES_KEY_TOOLBAR)
+            .ok()
+            .flatten()
+            .and_then(|raw| serde_json::from_slice::<Vec<ToolbarEntry>>(&raw).ok())
+        {
+            return TitleBarPreferences::from_entries(&entries);
+        }
+
+        let mut preferences = TitleBarPreferences::default();
+        for (item, key) in [
+            (ToolbarItem::Navigation, TITLEBAR_PREFERENCES_KEY_SHOW_NAVIGATION),
+            (ToolbarItem::Zoom, TITLEBAR_PREFERENCES_KEY_SHOW_ZOOM),
+        ] {
+            let visible = Self::decode_stored_bool(
+                store.get(key).ok().flatten(),
+                preferences.is_visible(item),
+            );
+            preferences.set_visible(item, visible);
+        }
+        preferences
+    }
+
+    fn load_sidebar_width_from_store(store: &sled::Tree) -> f32 {
+        store
+            .get(VIEW_PREFERENCES_KEY_SIDEBAR_WIDTH)
+            .ok()
+            .flatten()
+            .and_then(|raw| raw.as_ref().try_into().ok().map(f32::from_be_bytes))
+            .filter(|width| width.is_finite())
+            .map(|width| width.clamp(SIDEBAR_MIN_WIDTH, SIDEBAR_MAX_WIDTH))
+            .unwrap_or(SIDEBAR_WIDTH)
+    }
+
+    fn load_presentation_auto_advance_from_store(store: &sled::Tree) -> u8 {
+        store
+            .get(VIEW_PREFERENCES_KEY_PRESENTATION_AUTO_ADVANCE)
+            .ok()
+            .flatten()
+            .and_then(|raw| raw.first().copied())
+            .filter(|secs| PRESENTATION_AUTO_ADVANCE_OPTIONS_SECS.contains(secs))
+            .unwrap_or(0)
+    }
+
+    fn load_render_worker_count_from_store(store: &sled::Tree) -> usize {
+        store
+            .get(VIEW_PREFERENCES_KEY_RENDER_WORKERS)
+            .ok()
+            .flatten()
+            .and_then(|raw| raw.first().copied())
+            .map(|count| (count as usize).clamp(1, RENDER_WORKERS_MAX))
+            .unwrap_or(RENDER_WORKERS_DEFAULT)
+    }
+
+    fn load_render_quality_from_store(store: &sled::Tree) -> u8 {
+        store
+            .get(VIEW_PREFERENCES_KEY_RENDER_QUALITY)
+            .ok()
+            .flatten()
+            .and_then(|raw| raw.first().copied())
+            .filter(|percent| RENDER_QUALITY_OPTIONS_PERCENT.contains(percent))
+            .unwrap_or(RENDER_QUALITY_DEFAULT_PERCENT)
+    }
+
+    fn load_window_opacity_from_store(store: &sled::Tree) -> u8 {
+        store
+            .get(WINDOW_SIZE_KEY_OPACITY)
+            .ok()
+            .flatten()
+            .and_then(|raw| raw.first().copied())
+            .filter(|percent| WINDOW_OPACITY_OPTIONS_PERCENT.contains(percent))
+            .unwrap_or(WINDOW_OPACITY_DEFAULT_PERCENT)
+    }
+
+    fn load_ui_preferences_from_store(store: &sled::Tree) -> (u8, UiDensity) {
+        let font_size = store
+            .get(VIEW_PREFERENCES_KEY_UI_FONT_SIZE)
+            .ok()
+            .flatten()
+            .and_then(|raw| raw.first().copied())
+            .filter(|size| UI_FONT_SIZE_OPTIONS.contains(size))
+            .unwrap_or(UI_FONT_SIZE_DEFAULT);
+        let density = store
+            .get(VIEW_PREFERENCES_KEY_UI_DENSITY)
+            .ok()
+            .flatten()
+            .and_then(|raw| UiDensity::from_stored(&raw))
+            .unwrap_or_default();
+        (font_size, density)
+    }
+
+    fn load_render_options_from_store(store: &sled::Tree) -> RenderOptions {
+        let defaults = RenderOptions::default();
+        RenderOptions {
+            text_antialiasing: store
+                .get(VIEW_PREFERENCES_KEY_TEXT_ANTIALIASING)
+                .ok()
+                .flatten()
+                .and_then(|raw| TextAntialiasing::from_stored(&raw))
+                .unwrap_or(defaults.text_antialiasing),
+            native_text: Self::decode_stored_bool(
+                store.get(VIEW_PREFERENCES_KEY_NATIVE_TEXT).ok().flatten(),
+                defaults.native_text,
             ),
-            show_zoom: Self::decode_stored_bool(
-                store.get(TITLEBAR_PREFERENCES_KEY_SHOW_ZOOM).ok().flatten(),
-                default.show_zoom,
+            grayscale: Self::decode_stored_bool(
+                store.get(VIEW_PREFERENCES_KEY_GRAYSCALE).ok().flatten(),
+                defaults.grayscale,
             ),
         }
     }
 
+    /// (disk cache enabled, cache limit in MB, idle pre-rendering enabled)
+    fn load_render_cache_preferences_from_store(store: &sled::Tree) -> (bool, u64, bool) {
+        let enabled = Self::decode_stored_bool(
+            store
+                .get(VIEW_PREFERENCES_KEY_RENDER_CACHE_ENABLED)
+                .ok()
+                .flatten(),
+            false,
+        );
+        let limit_mb = store
+            .get(VIEW_PREFERENCES_KEY_RENDER_CACHE_LIMIT_MB)
+            .ok()
+            .flatten()
+            .and_then(|raw| raw.as_ref().try_into().ok().map(u64::from_be_bytes))
+            .filter(|limit| RENDER_CACHE_LIMIT_OPTIONS_MB.contains(limit))
+            .unwrap_or(RENDER_CACHE_LIMIT_DEFAULT_MB);
+        let idle_prerender = Self::decode_stored_bool(
+            store.get(VIEW_PREFERENCES_KEY_IDLE_PRERENDER).ok().flatten(),
+            false,
+        );
+        (enabled, limit_mb, idle_prerender)
+    }
+
     fn load_theme_preferences_from_store(
         store: &sled::Tree,
         default_mode: ThemeMode,
@@ -446,11 +605,21 @@ impl PdfViewer {
         (mode, light_name, dark_name)
     }
 
+    fn load_accent_color_from_store(store: &sled::Tree) -> Option<Hsla> {
+        Self::decode_stored_string(store.get(THEME_PREFERENCES_KEY_ACCENT_COLOR).ok().flatten())
+            .and_then(|hex| Self::parse_hex_color(&hex))
+    }
+
     fn load_language_preference_from_store(store: &sled::Tree) -> LanguagePreference {
         match store.get(THEME_PREFERENCES_KEY_LANGUAGE).ok().flatten() {
             Some(raw) => match raw.as_ref() {
                 b"zh_cn" => LanguagePreference::ZhCn,
                 b"en_us" => LanguagePreference::EnUs,
+                b"ja_jp" => LanguagePreference::JaJp,
+                b"ko_kr" => LanguagePreference::KoKr,
+                b"de_de" => LanguagePreference::DeDe,
+                b"es_es" => LanguagePreference::EsEs,
+                b"ar_sa" => LanguagePreference::ArSa,
                 _ => LanguagePreference::System,
             },
             None => LanguagePreference::System,
@@ -473,20 +642,11 @@ impl PdfViewer {
             return;
         };
 
-        if store
-            .insert(
-                TITLEBAR_PREFERENCES_KEY_SHOW_NAVIGATION,
-                [u8::from(self.titlebar_preferences.show_navigation)].as_slice(),
-            )
-            .is_err()
-        {
+        let Ok(value) = serde_json::to_vec(&self.titlebar_preferences.toolbar) else {
             return;
-        }
+        };
         if store
-            .insert(
-                TITLEBAR_PREFERENCES_KEY_SHOW_ZOOM,
-                [u8::from(self.titlebar_preferences.show_zoom)].as_slice(),
-            )
+            .insert(TITLEBAR_PREFERENCES_KEY_TOOLBAR, value)
             .is_err()
         {
             return;
@@ -530,6 +690,19 @@ impl PdfViewer {
         } else if store.remove(THEME_PREFERENCES_KEY_DARK_NAME).is_err() {
             return;
         }
+        if let Some(accent_color) = self.accent_color {
+            if store
+                .insert(
+                    THEME_PREFERENCES_KEY_ACCENT_COLOR,
+                    Self::format_hex_color(accent_color).as_bytes(),
+                )
+                .is_err()
+            {
+                return;
+            }
+        } else if store.remove(THEME_PREFERENCES_KEY_ACCENT_COLOR).is_err() {
+            return;
+        }
 
         let _ = store.flush();
     }
@@ -543,6 +716,11 @@ impl PdfViewer {
             LanguagePreference::System => b"system".as_slice(),
             LanguagePreference::ZhCn => b"zh_cn".as_slice(),
             LanguagePreference::EnUs => b"en_us".as_slice(),
+            LanguagePreference::JaJp => b"ja_jp".as_slice(),
+            LanguagePreference::KoKr => b"ko_kr".as_slice(),
+            LanguagePreference::DeDe => b"de_de".as_slice(),
+            LanguagePreference::EsEs => b"es_es".as_slice(),
+            LanguagePreference::ArSa => b"ar_sa".as_slice(),
         };
         if store
             .insert(THEME_PREFERENCES_KEY_LANGUAGE, stored_value)
@@ -606,131 +784,540 @@ impl PdfViewer {
         let _ = store.flush();
     }
 
-    fn persist_open_tabs(&self) {
-        let Some(store) = self.open_tabs_store.as_ref() else {
+    fn persist_sidebar_width(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
             return;
         };
 
-        if store.clear().is_err() {
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_SIDEBAR_WIDTH,
+                self.sidebar_width.to_be_bytes().as_slice(),
+            )
+            .is_err()
+        {
             return;
         }
 
-        let active_tab_id = self.tab_bar.active_tab_id();
-        let mut active_index = None;
-        let mut open_paths = Vec::new();
-        for tab in self.tab_bar.tabs() {
-            let Some(path) = tab.path.as_ref() else {
-                continue;
-            };
-            if active_tab_id == Some(tab.id) {
-                active_index = Some(open_paths.len());
-            }
-            open_paths.push(path.clone());
-        }
+        let _ = store.flush();
+    }
 
-        for (index, path) in open_paths.iter().enumerate() {
-            let key = (index as u32).to_be_bytes();
-            if store
-                .insert(key, path.to_string_lossy().as_bytes())
-                .is_err()
-            {
-                return;
-            }
-        }
+    fn persist_render_engine(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
 
-        if let Some(index) = active_index {
-            let active_bytes = (index as u64).to_be_bytes();
-            if store
-                .insert(OPEN_TABS_KEY_ACTIVE_INDEX, active_bytes.as_slice())
-                .is_err()
-            {
-                return;
-            }
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_RENDER_ENGINE,
+                self.render_engine.as_stored(),
+            )
+            .is_err()
+        {
+            return;
         }
 
         let _ = store.flush();
     }
 
-    fn persist_bookmarks(&self) {
-        let Some(store) = self.bookmarks_store.as_ref() else {
+    fn persist_startup_behavior(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
             return;
         };
 
-        if store.clear().is_err() {
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_STARTUP_BEHAVIOR,
+                self.startup_behavior.as_stored(),
+            )
+            .is_err()
+        {
             return;
         }
 
-        for (index, bookmark) in self.bookmarks.iter().enumerate() {
-            let key = (index as u32).to_be_bytes();
-            let page_index = (bookmark.page_index as u64).to_be_bytes();
-            let created_at = bookmark.created_at_unix_secs.to_be_bytes();
-            let path = bookmark.path.to_string_lossy();
+        let _ = store.flush();
+    }
 
-            let mut value = Vec::with_capacity(16 + path.len());
-            value.extend_from_slice(&page_index);
-            value.extend_from_slice(&created_at);
-            value.extend_from_slice(path.as_bytes());
+    fn persist_update_check_frequency(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
 
-            if store.insert(key, value).is_err() {
-                return;
-            }
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_UPDATE_CHECK_FREQUENCY,
+                self.update_check_frequency.as_stored(),
+            )
+            .is_err()
+        {
+            return;
         }
 
         let _ = store.flush();
     }
 
-    fn persist_markdown_notes(&self) {
-        let Some(store) = self.notes_store.as_ref() else {
+    fn persist_last_update_check(&self, unix_secs: u64) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
             return;
         };
 
-        if store.clear().is_err() {
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_LAST_UPDATE_CHECK,
+                unix_secs.to_be_bytes().as_slice(),
+            )
+            .is_err()
+        {
             return;
         }
 
-        for (index, note) in self.markdown_notes.iter().enumerate() {
-            let key = (index as u32).to_be_bytes();
-            let Ok(value) = serde_json::to_vec(note) else {
-                continue;
-            };
-            if store.insert(key, value).is_err() {
-                return;
-            }
-        }
-
         let _ = store.flush();
     }
 
-    fn persist_text_markups(&self) {
-        let Some(store) = self.text_markups_store.as_ref() else {
+    fn persist_update_channel(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
             return;
         };
 
-        if store.clear().is_err() {
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_UPDATE_CHANNEL,
+                self.update_channel.as_stored(),
+            )
+            .is_err()
+        {
             return;
         }
 
-        for (index, markup) in self.text_markups.iter().enumerate() {
-            let key = (index as u32).to_be_bytes();
-            let Ok(value) = serde_json::to_vec(markup) else {
-                continue;
-            };
-            if store.insert(key, value).is_err() {
-                return;
-            }
-        }
-
         let _ = store.flush();
     }
 
-    fn restore_open_tabs(
-        &mut self,
-        tabs_to_restore: Vec<(usize, PathBuf)>,
-        cx: &mut Context<Self>,
-    ) {
-        let active_tab_id = self.tab_bar.active_tab_id();
-        for (tab_id, path) in tabs_to_restore {
-            if Some(tab_id) == active_tab_id {
+    fn persist_external_tools(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+        let Ok(value) = serde_json::to_vec(&self.external_tools) else {
+            return;
+        };
+        if store
+            .insert(VIEW_PREFERENCES_KEY_EXTERNAL_TOOLS, value)
+            .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_command_history(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+        let Ok(value) = serde_json::to_vec(&self.command_history) else {
+            return;
+        };
+        if store
+            .insert(VIEW_PREFERENCES_KEY_COMMAND_HISTORY, value)
+            .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_annotation_defaults(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+        let Ok(value) = serde_json::to_vec(&self.annotation_defaults) else {
+            return;
+        };
+        if store
+            .insert(VIEW_PREFERENCES_KEY_ANNOTATION_DEFAULTS, value)
+            .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_pdfium_library_path(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        let result = match self.pdfium_library_path.as_ref() {
+            Some(path) => store
+                .insert(
+                    VIEW_PREFERENCES_KEY_PDFIUM_LIBRARY,
+                    path.to_string_lossy().as_bytes(),
+                )
+                .map(|_| ()),
+            None => store.remove(VIEW_PREFERENCES_KEY_PDFIUM_LIBRARY).map(|_| ()),
+        };
+        if result.is_err() {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_render_worker_count(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_RENDER_WORKERS,
+                [self.render_worker_count as u8].as_slice(),
+            )
+            .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_render_quality(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_RENDER_QUALITY,
+                [self.render_quality_percent].as_slice(),
+            )
+            .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_window_opacity(&self) {
+        let Some(store) = self.window_size_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(
+                WINDOW_SIZE_KEY_OPACITY,
+                [self.window_opacity_percent].as_slice(),
+            )
+            .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_ui_preferences(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_UI_FONT_SIZE,
+                [self.ui_font_size].as_slice(),
+            )
+            .is_err()
+            || store
+                .insert(VIEW_PREFERENCES_KEY_UI_DENSITY, self.ui_density.as_stored())
+                .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_render_options(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_TEXT_ANTIALIASING,
+                self.render_options.text_antialiasing.as_stored(),
+            )
+            .is_err()
+            || store
+                .insert(
+                    VIEW_PREFERENCES_KEY_NATIVE_TEXT,
+                    [u8::from(self.render_options.native_text)].as_slice(),
+                )
+                .is_err()
+            || store
+                .insert(
+                    VIEW_PREFERENCES_KEY_GRAYSCALE,
+                    [u8::from(self.render_options.grayscale)].as_slice(),
+                )
+                .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_render_cache_preferences(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_RENDER_CACHE_ENABLED,
+                [u8::from(self.render_cache_enabled)].as_slice(),
+            )
+            .is_err()
+            || store
+                .insert(
+                    VIEW_PREFERENCES_KEY_RENDER_CACHE_LIMIT_MB,
+                    self.render_cache_limit_mb.to_be_bytes().as_slice(),
+                )
+                .is_err()
+            || store
+                .insert(
+                    VIEW_PREFERENCES_KEY_IDLE_PRERENDER,
+                    [u8::from(self.idle_prerender_enabled)].as_slice(),
+                )
+                .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_search_highlight_all(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_SEARCH_HIGHLIGHT_ALL,
+                [u8::from(self.search_highlight_all)].as_slice(),
+            )
+            .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_comic_right_to_left(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_COMIC_RIGHT_TO_LEFT,
+                [u8::from(self.comic_right_to_left)].as_slice(),
+            )
+            .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_page_double_click_action(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_PAGE_DOUBLE_CLICK,
+                self.page_double_click_action.as_stored(),
+            )
+            .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_status_bar_visible(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_STATUS_BAR_VISIBLE,
+                [u8::from(self.status_bar_visible)].as_slice(),
+            )
+            .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_zen_mode(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_ZEN_MODE,
+                [u8::from(self.zen_mode)].as_slice(),
+            )
+            .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_presentation_preferences(&self) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(
+                VIEW_PREFERENCES_KEY_PRESENTATION_TIMER,
+                [u8::from(self.presentation_timer_visible)].as_slice(),
+            )
+            .is_err()
+            || store
+                .insert(
+                    VIEW_PREFERENCES_KEY_PRESENTATION_AUTO_ADVANCE,
+                    [self.presentation_auto_advance_secs].as_slice(),
+                )
+                .is_err()
+            || store
+                .insert(
+                    VIEW_PREFERENCES_KEY_PRESENTATION_POINTER,
+                    self.presentation_pointer.as_stored(),
+                )
+                .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_open_tabs(&self) {
+        crate::crash_report::set_open_files(
+            self.tab_bar
+                .tabs()
+                .iter()
+                .filter_map(|tab| tab.path.as_deref()),
+        );
+        let Some(store) = self.open_tabs_store.as_ref() else {
+            return;
+        };
+
+        if store.clear().is_err() {
+            return;
+        }
+
+        let active_tab_id = self.tab_bar.active_tab_id();
+        let mut active_index = None;
+        let mut open_paths = Vec::new();
+        for tab in self.tab_bar.tabs() {
+            let Some(path) = tab.path.as_ref() else {
+                continue;
+            };
+            if active_tab_id == Some(tab.id) {
+                active_index = Some(open_paths.len());
+            }
+            open_paths.push(path.clone());
+        }
+
+        for (index, path) in open_paths.iter().enumerate() {
+            let key = (index as u32).to_be_bytes();
+            if store
+                .insert(key, path.to_string_lossy().as_bytes())
+                .is_err()
+            {
+                return;
+            }
+        }
+
+        if let Some(index) = active_index {
+            let active_bytes = (index as u64).to_be_bytes();
+            if store
+                .insert(OPEN_TABS_KEY_ACTIVE_INDEX, active_bytes.as_slice())
+                .is_err()
+            {
+                return;
+            }
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_bookmarks(&self) {
+        let Some(store) = self.bookmarks_store.as_ref() else {
+            return;
+        };
+
+        if store.clear().is_err() {
+            return;
+        }
+
+        for (index, bookmark) in self.bookmarks.iter().enumerate() {
+            let key = (index as u32).to_be_bytes();
+            let page_index = (bookmark.page_index as u64).to_be_bytes();
+            let created_at = bookmark.created_at_unix_secs.to_be_bytes();
+            let path = bookmark.path.to_string_lossy();
+
+            let mut value = Vec::with_capacity(16 + path.len());
+            value.extend_from_slice(&page_index);
+            value.extend_from_slice(&created_at);
+            value.extend_from_slice(path.as_bytes());
+
+            if store.insert(key, value).is_err() {
+                return;
+            }
+        }
+
+        let _ = store.flush();
+    }
+
+    fn persist_markdown_notes(&self) {
+        if let Some(store) = self.notes_store.as_ref() {
+            annotations::store_entries(store, &self.markdown_notes);
+        }
+    }
+
+    fn persist_text_markups(&self) {
+        if let Some(store) = self.text_markups_store.as_ref() {
+            annotations::store_entries(store, &self.text_markups);
+        }
+    }
+
+    fn restore_open_tabs(
+        &mut self,
+        tabs_to_restore: Vec<(usize, PathBuf)>,
+        cx: &mut Context<Self>,
+    ) {
+        let active_tab_id = self.tab_bar.active_tab_id();
+        for (tab_id, path) in tabs_to_restore {
+            if Some(tab_id) == active_tab_id {
                 self.load_pdf_path_into_tab(tab_id, path, false, cx);
                 break;
             }
@@ -753,6 +1340,138 @@ impl PdfViewer {
             })
     }
 
+    /// Copies the loader's page count into the tab until every summary is in, for the
+    /// progress shown on the tab and in the status bar.
+    fn poll_summary_progress(
+        &mut self,
+        tab_id: usize,
+        summary_epoch: u64,
+        progress: Arc<Mutex<(usize, usize)>>,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn(async move |view, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(Duration::from_millis(SUMMARY_PROGRESS_POLL_MS))
+                    .await;
+                let snapshot = progress.lock().map(|progress| *progress).ok();
+                let polling = view.update(cx, |this, cx| {
+                    let Some(tab) = this.tab_bar.get_tab_mut(tab_id) else {
+                        return false;
+                    };
+                    if tab.summary_epoch != summary_epoch || tab.summary_progress.is_none() {
+                        return false;
+                    }
+                    if let Some(snapshot) = snapshot
+                        && tab.summary_progress != Some(snapshot)
+                    {
+                        tab.summary_progress = Some(snapshot);
+                        cx.notify();
+                    }
+                    true
+                });
+                if !matches!(polling, Ok(true)) {
+                    return;
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Streams the page summaries after the first chunk into the tab, one chunk at a time.
+    fn load_remaining_page_summaries(
+        &mut self,
+        tab_id: usize,
+        path: PathBuf,
+        summary_epoch: u64,
+        progress: Arc<Mutex<(usize, usize)>>,
+        cx: &mut Context<Self>,
+    ) {
+        let language = self.language;
+
+        cx.spawn(async move |view, cx| {
+            loop {
+                let start = match view.update(cx, |this, _| {
+                    this.tab_bar
+                        .tabs()
+                        .iter()
+                        .find(|tab| tab.id == tab_id && tab.summary_epoch == summary_epoch)
+                        .filter(|tab| tab.summary_loaded_pages < tab.pages.len())
+                        .map(|tab| tab.summary_loaded_pages)
+                }) {
+                    Ok(Some(start)) => start,
+                    _ => return,
+                };
+
+                let chunk_path = path.clone();
+                let chunk_progress = progress.clone();
+                let parsed = cx
+                    .background_executor()
+                    .spawn(async move {
+                        load_document_summary(
+                            &chunk_path,
+                            language,
+                            start,
+                            SUMMARY_CHUNK_PAGES,
+                            |done, total| {
+                                if let Ok(mut progress) = chunk_progress.lock() {
+                                    *progress = (done, total);
+                                }
+                            },
+                        )
+                    })
+                    .await;
+
+                let updated = view.update(cx, |this, cx| {
+                    let Some(tab) = this.tab_bar.get_tab_mut(tab_id) else {
+                        return false;
+                    };
+                    if tab.summary_epoch != summary_epoch || tab.summary_loaded_pages != start {
+                        return false;
+                    }
+                    let pages = match parsed {
+                        Ok((_, pages)) if !pages.is_empty() => pages,
+                        Ok(_) => {
+                            // The page tree turned out shorter than reported; drop placeholders.
+                            tab.pages.truncate(start);
+                            tab.summary_loaded_pages = start;
+                            tab.summary_progress = None;
+                            cx.notify();
+                            return false;
+                        }
+                        Err(err) => {
+                            crate::debug_log!(
+                                @warn,
+                                "[pdf][load] summary chunk failed: {} | {:#}",
+                                path.display(),
+                                err
+                            );
+                            tab.summary_progress = None;
+                            cx.notify();
+                            return false;
+                        }
+                    };
+                    for summary in pages {
+                        if let Some(page) = tab.pages.get_mut(summary.index) {
+                            page.width_pt = summary.width_pt;
+                            page.height_pt = summary.height_pt;
+                            page.label = summary.label;
+                        }
+                        tab.summary_loaded_pages = summary.index + 1;
+                    }
+                    tab.summary_progress = (tab.summary_loaded_pages < tab.pages.len())
+                        .then_some((tab.summary_loaded_pages, tab.pages.len()));
+                    cx.notify();
+                    true
+                });
+                if !matches!(updated, Ok(true)) {
+                    return;
+                }
+            }
+        })
+        .detach();
+    }
+
     fn load_tab_if_needed(&mut self, tab_id: usize, cx: &mut Context<Self>) -> bool {
         if let Some(path) = self.pending_load_path_for_tab(tab_id) {
             self.load_pdf_path_into_tab(tab_id, path, false, cx);
@@ -770,20 +1489,32 @@ impl PdfViewer {
     ) {
         let language = self.language;
 
-        if let Some(tab) = self.tab_bar.get_tab_mut(tab_id) {
+        let summary_epoch = if let Some(tab) = self.tab_bar.get_tab_mut(tab_id) {
             tab.path = Some(path.clone());
             tab.pages.clear();
             tab.summary_loaded = false;
             tab.summary_loading = true;
             tab.summary_failed = false;
+            tab.summary_loaded_pages = 0;
+            tab.summary_progress = Some((0, 0));
+            tab.summary_epoch = tab.summary_epoch.wrapping_add(1);
+            tab.load_error = None;
+            tab.repairing = false;
+            tab.repaired = formats::repaired_copy(&path).is_some();
+            tab.skeleton_only = false;
             tab.selected_page = 0;
             tab.active_page = 0;
             tab.zoom = 1.0;
             tab.last_saved_position = None;
+            tab.right_to_left = None;
+            tab.back_history.clear();
+            tab.forward_history.clear();
             tab.reset_page_render_state();
+            tab.reset_document_side_state();
+            tab.summary_epoch
         } else {
             return;
-        }
+        };
 
         self.persist_open_tabs();
         if self.tab_bar.active_tab_id() == Some(tab_id) {
@@ -791,39 +1522,94 @@ impl PdfViewer {
         }
         cx.notify();
 
+        let progress = Arc::new(Mutex::new((0, 0)));
+        self.poll_summary_progress(tab_id, summary_epoch, progress.clone(), cx);
         cx.spawn(async move |view, cx| {
             let parsed = cx
                 .background_executor()
                 .spawn({
                     let path = path.clone();
-                    async move { load_document_summary(&path, language) }
+                    let progress = progress.clone();
+                    async move {
+                        let on_progress = |done, total| {
+                            if let Ok(mut progress) = progress.lock() {
+                                *progress = (done, total);
+                            }
+                        };
+                        match load_document_summary(
+                            &path,
+                            language,
+                            0,
+                            SUMMARY_CHUNK_PAGES,
+                            on_progress,
+                        ) {
+                            // Without Pdfium, still lay out the pages instead of an empty tab.
+                            Err(err) if ensure_pdfium_ready(language).is_err() => {
+                                load_document_skeleton(&path, language)
+                                    .map(|pages| (pages.len(), pages, true))
+                                    .map_err(|_| err)
+                            }
+                            parsed => {
+                                parsed.map(|(total_pages, pages)| (total_pages, pages, false))
+                            }
+                        }
+                    }
                 })
                 .await;
 
             let _ = view.update(cx, |this, cx| {
-                let restored_page = this.load_saved_file_position(&path);
+                let restored_view = this.load_saved_file_view(&path);
+                let restored_sidebar_tab = this.load_saved_sidebar_tab(&path);
                 let mut loaded_ok = false;
+                let mut summary_pending = false;
+                let mut auto_repair = false;
 
                 if let Some(tab) = this.tab_bar.get_tab_mut(tab_id) {
-                    if tab.path.as_ref() != Some(&path) {
+                    if tab.path.as_ref() != Some(&path) || tab.summary_epoch != summary_epoch {
                         return;
                     }
                     tab.path = Some(path.clone());
                     match parsed {
-                        Ok(mut pages) => {
+                        Ok((total_pages, mut pages, skeleton_only)) => {
                             pages.sort_by_key(|p| p.index);
+                            tab.skeleton_only = skeleton_only;
+                            tab.summary_loaded_pages = pages.len();
+                            // Pages beyond the first chunk borrow the last known size until
+                            // their real summaries stream in.
+                            if let Some(last) = pages.last().cloned() {
+                                pages.extend((pages.len()..total_pages).map(|index| PageSummary {
+                                    index,
+                                    label: None,
+                                    skeleton_text: None,
+                                    ..last.clone()
+                                }));
+                            }
+                            summary_pending = tab.summary_loaded_pages < pages.len();
+                            tab.summary_progress = summary_pending
+                                .then_some((tab.summary_loaded_pages, pages.len()));
                             tab.pages = pages;
                             tab.summary_loaded = true;
                             tab.summary_loading = false;
                             tab.summary_failed = false;
+                            tab.load_error = None;
 
-                            let initial_page = restored_page
+                            let initial_page = restored_view
+                                .map(|view| view.page_index)
                                 .unwrap_or(0)
                                 .min(tab.pages.len().saturating_sub(1));
                             tab.selected_page = initial_page;
                             tab.active_page = initial_page;
-                            tab.zoom = 1.0;
+                            tab.zoom = restored_view.and_then(|view| view.zoom).unwrap_or(1.0);
+                            tab.pending_page_fraction = restored_view
+                                .map(|view| view.page_fraction)
+                                .filter(|fraction| *fraction > 0.0);
+                            tab.right_to_left = restored_view.and_then(|view| view.right_to_left);
+                            tab.sidebar_tab = restored_sidebar_tab.unwrap_or_default();
+                            tab.file_size_bytes =
+                                std::fs::metadata(&path).ok().map(|meta| meta.len());
                             tab.reset_page_render_state();
+                            // Skeleton pages never render, so there is nothing to wait for.
+                            tab.first_render_pending = !skeleton_only && !tab.pages.is_empty();
 
                             if !tab.pages.is_empty() {
                                 let strategy = if initial_page == 0 {
@@ -838,21 +1624,67 @@ impl PdfViewer {
                             }
                             loaded_ok = true;
                         }
-                        Err(_) => {
+                        Err(err) => {
+                            crate::debug_log!(
+                                @warn,
+                                "[pdf][load] failed: {} | {:#}",
+                                path.display(),
+                                err
+                            );
+                            // Broken xref tables are common enough to repair without asking,
+                            // but never retry on an already repaired copy.
+                            auto_repair =
+                                is_damaged_document_error(&err) && !tab.repaired;
                             tab.pages.clear();
                             tab.summary_loaded = false;
                             tab.summary_loading = false;
                             tab.summary_failed = true;
+                            tab.summary_progress = None;
+                            tab.first_render_pending = false;
+                            tab.load_error = Some(format!("{err:#}"));
                             tab.selected_page = 0;
                             tab.active_page = 0;
                             tab.zoom = 1.0;
+                            tab.pending_page_fraction = None;
                             tab.reset_page_render_state();
                         }
                     }
                 }
 
                 if loaded_ok && remember_recent_file {
-                    this.remember_recent_file(&path);
+                    this.remember_recent_file(&path, cx);
+                }
+                if summary_pending {
+                    this.load_remaining_page_summaries(
+                        tab_id,
+                        path.clone(),
+                        summary_epoch,
+                        progress,
+                        cx,
+                    );
+                }
+                let skeleton_only = this
+                    .tab_bar
+                    .tabs()
+                    .iter()
+                    .any(|tab| tab.id == tab_id && tab.skeleton_only);
+                if loaded_ok && !skeleton_only {
+                    this.request_outline_load(tab_id, cx);
+                    this.request_text_layer_prefetch(tab_id, cx);
+                }
+                if loaded_ok {
+                    if this.load_error_dialog_tab_id == Some(tab_id) {
+                        this.load_error_dialog_tab_id = None;
+                    }
+                } else if auto_repair {
+                    this.repair_failed_load(tab_id, cx);
+                } else if this
+                    .tab_bar
+                    .tabs()
+                    .iter()
+                    .any(|tab| tab.id == tab_id && tab.summary_failed)
+                {
+                    this.open_load_error_dialog(tab_id, cx);
                 }
 
                 this.persist_open_tabs();
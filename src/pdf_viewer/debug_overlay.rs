@@ -0,0 +1,213 @@
+use super::{
+    DEBUG_OVERLAY_REFRESH_MS, DEBUG_OVERLAY_RENDER_TIMING_LIMIT, DEBUG_OVERLAY_WIDTH, PdfViewer,
+    TITLE_BAR_HEIGHT,
+};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::*;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+impl PdfViewer {
+    pub(super) fn toggle_debug_overlay(&mut self, cx: &mut Context<Self>) {
+        self.debug_overlay_visible = !self.debug_overlay_visible;
+        self.debug_overlay_epoch = self.debug_overlay_epoch.wrapping_add(1);
+        self.debug_frame_times.clear();
+        if self.debug_overlay_visible {
+            self.refresh_db_usage(cx);
+            self.schedule_debug_overlay_refresh(cx);
+        }
+        cx.notify();
+    }
+
+    fn schedule_debug_overlay_refresh(&mut self, cx: &mut Context<Self>) {
+        let epoch = self.debug_overlay_epoch;
+        cx.spawn(async move |view, cx| {
+            let mut ticks = 0u32;
+            loop {
+                cx.background_executor()
+                    .timer(Duration::from_millis(DEBUG_OVERLAY_REFRESH_MS))
+                    .await;
+                ticks = ticks.wrapping_add(1);
+
+                let keep_running = view
+                    .update(cx, |this, cx| {
+                        if !this.debug_overlay_visible || this.debug_overlay_epoch != epoch {
+                            return false;
+                        }
+                        // Sizing the disk cache walks its directory, so do it less often.
+                        if ticks % 10 == 0 {
+                            this.refresh_db_usage(cx);
+                        }
+                        cx.notify();
+                        true
+                    })
+                    .unwrap_or(false);
+                if !keep_running {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Called once per frame while the overlay is open; FPS is the frame count of the last second.
+    pub(super) fn record_debug_frame(&mut self) {
+        if !self.debug_overlay_visible {
+            return;
+        }
+        let now = Instant::now();
+        self.debug_frame_times.push_back(now);
+        while self
+            .debug_frame_times
+            .front()
+            .is_some_and(|time| now.duration_since(*time) > Duration::from_secs(1))
+        {
+            self.debug_frame_times.pop_front();
+        }
+    }
+
+    /// Records a finished display batch, splitting its wall time evenly across its pages.
+    pub(super) fn record_display_render_timing(
+        &mut self,
+        pages: &[usize],
+        elapsed: Duration,
+        render_width: u32,
+    ) {
+        if pages.is_empty() {
+            return;
+        }
+        let per_page = elapsed / pages.len() as u32;
+        for &page_index in pages {
+            if self.debug_render_timings.len() >= DEBUG_OVERLAY_RENDER_TIMING_LIMIT {
+                self.debug_render_timings.pop_front();
+            }
+            self.debug_render_timings
+                .push_back((page_index, per_page, render_width));
+        }
+    }
+
+    fn debug_image_memory_bytes(&self) -> u64 {
+        fn image_bytes(image: &Arc<RenderImage>) -> u64 {
+            image.as_bytes(0).map_or(0, |bytes| bytes.len() as u64)
+        }
+
+        let Some(tab) = self.active_tab() else {
+            return 0;
+        };
+        let page_bytes: u64 = tab
+            .pages
+            .iter()
+            .flat_map(|page| page.display_image.iter().chain(page.thumbnail_image.iter()))
+            .map(image_bytes)
+            .sum();
+        let tile_bytes: u64 = tab
+            .display_tiles
+            .values()
+            .flat_map(|tiles| {
+                tiles.tiles.values().chain(
+                    tiles
+                        .stale
+                        .iter()
+                        .flat_map(|(_, stale_tiles)| stale_tiles.values()),
+                )
+            })
+            .map(image_bytes)
+            .sum();
+        page_bytes + tile_bytes
+    }
+
+    pub(super) fn render_debug_overlay(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        if !self.debug_overlay_visible {
+            return None;
+        }
+        let i18n = self.i18n();
+        let tab = self.active_tab();
+        let display_queue = format!(
+            "{} · {} inflight · {} loading",
+            tab.map_or(0, |tab| tab.display_queue.pages().count()),
+            tab.map_or(0, |tab| tab.display_inflight_tasks),
+            tab.map_or(0, |tab| tab.display_loading.len()),
+        );
+        let thumbnail_queue = format!(
+            "{} · {} inflight · {} loading",
+            tab.map_or(0, |tab| tab.thumbnail_queue.pages().count()),
+            tab.map_or(0, |tab| tab.thumbnail_inflight_tasks),
+            tab.map_or(0, |tab| tab.thumbnail_loading.len()),
+        );
+
+        let row = |label: &str, value: String| {
+            div()
+                .h_flex()
+                .justify_between()
+                .gap_3()
+                .child(
+                    div()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(label.to_string()),
+                )
+                .child(div().child(value))
+        };
+
+        Some(
+            div()
+                .id("debug-overlay")
+                .absolute()
+                .top(px(TITLE_BAR_HEIGHT + 8.0))
+                .right(px(8.0))
+                .w(px(DEBUG_OVERLAY_WIDTH))
+                .p_2()
+                .v_flex()
+                .gap_1()
+                .text_xs()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().border)
+                .bg(cx.theme().background.opacity(0.88))
+                .shadow_md()
+                .occlude()
+                .child(div().font_semibold().child(i18n.debug_overlay_title))
+                .child(row(
+                    i18n.debug_overlay_fps_label,
+                    self.debug_frame_times.len().to_string(),
+                ))
+                .child(row(i18n.debug_overlay_display_queue_label, display_queue))
+                .child(row(
+                    i18n.debug_overlay_thumbnail_queue_label,
+                    thumbnail_queue,
+                ))
+                .child(row(
+                    i18n.debug_overlay_image_memory_label,
+                    Self::format_storage_size(self.debug_image_memory_bytes()),
+                ))
+                .child(row(
+                    i18n.debug_overlay_disk_cache_label,
+                    Self::format_storage_size(self.render_cache_usage_bytes),
+                ))
+                .child(
+                    div()
+                        .mt_1()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(i18n.debug_overlay_recent_renders_label),
+                )
+                .map(|this| {
+                    if self.debug_render_timings.is_empty() {
+                        return this.child(
+                            div()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(i18n.debug_overlay_no_renders),
+                        );
+                    }
+                    this.children(self.debug_render_timings.iter().rev().map(
+                        |(page_index, elapsed, render_width)| {
+                            row(
+                                &format!("#{} @ {render_width}px", page_index + 1),
+                                format!("{:.1} ms", elapsed.as_secs_f64() * 1000.0),
+                            )
+                        },
+                    ))
+                })
+                .into_any_element(),
+        )
+    }
+}
@@ -0,0 +1,662 @@
+use super::{
+    PDFIUM_DOWNLOAD_PROGRESS_POLL_MS, VIEW_PREFERENCES_KEY_DOWNLOAD_PROXY,
+    VIEW_PREFERENCES_KEY_PDFIUM_MIRROR,
+};
+use crate::i18n::{I18n, Language};
+use crate::pdf_viewer::PdfViewer;
+use crate::pdf_viewer::utils::{
+    PdfiumDiagnostics, install_user_pdfium_library, pdfium_diagnostics, pdfium_versions_dir,
+};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::input::{Input, InputState};
+use gpui_component::scroll::ScrollableElement;
+use gpui_component::{button::*, *};
+use kpdf_core::fetch::{
+    DownloadProgress, FetchOptions, download_pdfium, pinned_download_available, pinned_release,
+};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+const PDFIUM_BINARIES_RELEASES_URL: &str = "https://github.com/bblanchon/pdfium-binaries/releases";
+
+pub(super) struct DiagnosticsWindow {
+    viewer: Entity<PdfViewer>,
+    focus_handle: FocusHandle,
+    language: Language,
+    diagnostics: Option<PdfiumDiagnostics>,
+    status_message: Option<String>,
+    mirror_input: Entity<InputState>,
+    proxy_input: Entity<InputState>,
+    /// Latest progress of the running download; `None` when idle.
+    download_progress: Option<DownloadProgress>,
+    download_cancelled: Arc<AtomicBool>,
+}
+
+impl DiagnosticsWindow {
+    pub(super) fn new(
+        viewer: Entity<PdfViewer>,
+        language: Language,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let i18n = I18n::new(language);
+        let options = viewer.read(cx).pdfium_fetch_options();
+        let mirror_input = cx
+            .new(|cx| InputState::new(window, cx).placeholder(i18n.diagnostics_mirror_placeholder));
+        let proxy_input = cx
+            .new(|cx| InputState::new(window, cx).placeholder(i18n.diagnostics_proxy_placeholder));
+        mirror_input.update(cx, |input, cx| {
+            input.set_value(options.mirror.unwrap_or_default(), window, cx);
+        });
+        proxy_input.update(cx, |input, cx| {
+            input.set_value(options.proxy.unwrap_or_default(), window, cx);
+        });
+
+        let mut this = Self {
+            viewer,
+            focus_handle: cx.focus_handle(),
+            language,
+            diagnostics: None,
+            status_message: None,
+            mirror_input,
+            proxy_input,
+            download_progress: None,
+            download_cancelled: Arc::new(AtomicBool::new(false)),
+        };
+        this.refresh(cx);
+        this
+    }
+
+    fn i18n(&self) -> I18n {
+        I18n::new(self.language)
+    }
+
+    fn refresh(&mut self, cx: &mut Context<Self>) {
+        let language = self.language;
+        self.diagnostics = None;
+        cx.notify();
+
+        cx.spawn(async move |this, cx| {
+            let diagnostics = cx
+                .background_executor()
+                .spawn(async move { pdfium_diagnostics(language) })
+                .await;
+            let _ = this.update(cx, |this, cx| {
+                this.diagnostics = Some(diagnostics);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn relocate_library(&mut self, cx: &mut Context<Self>) {
+        let picker = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some(self.i18n().diagnostics_relocate_prompt.into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = picker.await else {
+                return;
+            };
+            let Some(source) = paths.into_iter().next() else {
+                return;
+            };
+            let installed = cx
+                .background_executor()
+                .spawn(async move { install_user_pdfium_library(&source) })
+                .await;
+            let _ = this.update(cx, |this, cx| {
+                let i18n = this.i18n();
+                this.status_message = Some(match installed {
+                    Ok(target) => i18n.diagnostics_relocate_done(&target),
+                    Err(err) => {
+                        crate::debug_log!(@warn, "[pdfium] relocate failed: {:#}", err);
+                        i18n.diagnostics_relocate_failed(&format!("{err:#}"))
+                    }
+                });
+                this.refresh(cx);
+            });
+        })
+        .detach();
+    }
+
+    fn start_download(&mut self, cx: &mut Context<Self>) {
+        if self.download_progress.is_some() {
+            return;
+        }
+        let Some(versions_dir) = pdfium_versions_dir() else {
+            return;
+        };
+        let staging_dir = versions_dir.join("downloads");
+        let target_dir = versions_dir.join(pinned_release().unwrap_or("latest").replace('/', "-"));
+        let input_value = |input: &Entity<InputState>| {
+            Some(input.read(cx).value().trim().to_string()).filter(|value| !value.is_empty())
+        };
+        let options = FetchOptions {
+            mirror: input_value(&self.mirror_input),
+            proxy: input_value(&self.proxy_input),
+        };
+        let _ = self.viewer.update(cx, |viewer, _| {
+            viewer.persist_pdfium_fetch_options(&options);
+        });
+
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.download_cancelled = cancelled.clone();
+        self.download_progress = Some(DownloadProgress::default());
+        self.status_message = None;
+        cx.notify();
+
+        let progress = Arc::new(Mutex::new(DownloadProgress::default()));
+        let progress_for_task = progress.clone();
+        let finished = Arc::new(AtomicBool::new(false));
+        let finished_for_task = finished.clone();
+        let download = cx.background_executor().spawn(async move {
+            let result = download_pdfium(
+                &options,
+                &target_dir,
+                &staging_dir,
+                |update| {
+                    if let Ok(mut progress) = progress_for_task.lock() {
+                        *progress = update;
+                    }
+                },
+                || cancelled.load(Ordering::Relaxed),
+            );
+            finished_for_task.store(true, Ordering::Release);
+            result
+        });
+
+        // Progress is polled rather than pushed per chunk to keep redraws cheap.
+        cx.spawn(async move |this, cx| {
+            while !finished.load(Ordering::Acquire) {
+                cx.background_executor()
+                    .timer(Duration::from_millis(PDFIUM_DOWNLOAD_PROGRESS_POLL_MS))
+                    .await;
+                let snapshot = progress.lock().map(|progress| *progress).ok();
+                let alive = this
+                    .update(cx, |this, cx| {
+                        if this.download_progress.is_some() {
+                            this.download_progress = snapshot.or(this.download_progress);
+                            cx.notify();
+                        }
+                    })
+                    .is_ok();
+                if !alive {
+                    return;
+                }
+            }
+        })
+        .detach();
+
+        cx.spawn(async move |this, cx| {
+            let result = download.await;
+            let _ = this.update(cx, |this, cx| {
+                let i18n = this.i18n();
+                this.download_progress = None;
+                this.status_message = Some(match result {
+                    Ok(target) => {
+                        let message = i18n.diagnostics_download_done(&target);
+                        let _ = this.viewer.update(cx, |viewer, cx| {
+                            viewer.set_pdfium_library_path(Some(target), cx);
+                        });
+                        message
+                    }
+                    Err(err) => {
+                        crate::debug_log!(@warn, "[pdfium] download failed: {:#}", err);
+                        i18n.diagnostics_download_failed(&format!("{err:#}"))
+                    }
+                });
+                this.refresh(cx);
+            });
+        })
+        .detach();
+    }
+
+    fn cancel_download(&mut self) {
+        self.download_cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn render_download_progress(&self, progress: DownloadProgress, cx: &App) -> impl IntoElement {
+        let i18n = self.i18n();
+        let fraction = progress
+            .total
+            .filter(|total| *total > 0)
+            .map(|total| (progress.downloaded as f32 / total as f32).clamp(0.0, 1.0));
+        let label = i18n.diagnostics_download_progress(
+            &PdfViewer::format_storage_size(progress.downloaded),
+            &progress
+                .total
+                .map(PdfViewer::format_storage_size)
+                .unwrap_or_else(|| "?".to_string()),
+        );
+
+        div()
+            .v_flex()
+            .gap_1()
+            .child(
+                div()
+                    .w_full()
+                    .h(px(6.))
+                    .rounded_full()
+                    .bg(cx.theme().secondary)
+                    .child(
+                        div()
+                            .h_full()
+                            .rounded_full()
+                            .bg(cx.theme().primary)
+                            .w(relative(fraction.unwrap_or(0.0))),
+                    ),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(label),
+            )
+    }
+
+    fn close_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.cancel_download();
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.close_diagnostics_dialog(cx);
+        });
+        window.remove_window();
+    }
+
+    fn render_field(
+        label: &'static str,
+        value: impl IntoElement,
+        cx: &App,
+    ) -> impl IntoElement {
+        div()
+            .v_flex()
+            .items_start()
+            .gap_1()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(label),
+            )
+            .child(
+                div()
+                    .w_full()
+                    .text_sm()
+                    .text_color(cx.theme().foreground)
+                    .whitespace_normal()
+                    .child(value),
+            )
+    }
+}
+
+impl Render for DiagnosticsWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let i18n = self.i18n();
+        let diagnostics = self.diagnostics.clone();
+        let is_checking = diagnostics.is_none();
+        let is_downloading = self.download_progress.is_some();
+
+        let library_path = match &diagnostics {
+            None => i18n.diagnostics_status_checking.to_string(),
+            Some(diagnostics) => diagnostics
+                .library_path
+                .as_ref()
+                .map(|path| path.to_string_lossy().into_owned())
+                .unwrap_or_else(|| {
+                    if diagnostics.load_error.is_some() {
+                        "-".to_string()
+                    } else {
+                        i18n.diagnostics_library_system.to_string()
+                    }
+                }),
+        };
+        let version = diagnostics
+            .as_ref()
+            .and_then(|diagnostics| diagnostics.version.clone())
+            .unwrap_or_else(|| i18n.diagnostics_version_unknown.to_string());
+        let (status_text, status_failed) = match &diagnostics {
+            None => (i18n.diagnostics_status_checking.to_string(), false),
+            Some(diagnostics) => match &diagnostics.load_error {
+                Some(error) => (error.clone(), true),
+                None => (i18n.diagnostics_status_loaded.to_string(), false),
+            },
+        };
+        let searched_dirs = diagnostics
+            .as_ref()
+            .map(|diagnostics| {
+                diagnostics
+                    .searched_dirs
+                    .iter()
+                    .map(|dir| dir.to_string_lossy().into_owned())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let now = SystemTime::now();
+        let render_errors = diagnostics
+            .as_ref()
+            .map(|diagnostics| {
+                diagnostics
+                    .recent_render_errors
+                    .iter()
+                    .map(|error| {
+                        let seconds_ago = now
+                            .duration_since(error.at)
+                            .map(|elapsed| elapsed.as_secs())
+                            .unwrap_or(0);
+                        (i18n.diagnostics_render_error_age(seconds_ago), error.message.clone())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        div()
+            .id("diagnostics-window")
+            .size_full()
+            .v_flex()
+            .bg(cx.theme().background)
+            .focusable()
+            .track_focus(&self.focus_handle)
+            .capture_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key.as_str() == "escape" {
+                    this.close_dialog(window, cx);
+                    cx.stop_propagation();
+                }
+            }))
+            .child(TitleBar::new())
+            .child(
+                div()
+                    .flex_1()
+                    .min_h(px(0.))
+                    .v_flex()
+                    .gap_3()
+                    .p_4()
+                    .child(
+                        div()
+                            .text_lg()
+                            .text_color(cx.theme().foreground)
+                            .child(i18n.diagnostics_dialog_title),
+                    )
+                    .child(div().h(px(1.)).bg(cx.theme().border))
+                    .child(
+                        div()
+                            .id("diagnostics-content")
+                            .flex_1()
+                            .min_h(px(0.))
+                            .overflow_y_scrollbar()
+                            .child(
+                                div()
+                                    .v_flex()
+                                    .gap_3()
+                                    .child(Self::render_field(
+                                        i18n.diagnostics_library_path_label,
+                                        library_path,
+                                        cx,
+                                    ))
+                                    .child(Self::render_field(
+                                        i18n.diagnostics_version_label,
+                                        version,
+                                        cx,
+                                    ))
+                                    .child(Self::render_field(
+                                        i18n.diagnostics_status_label,
+                                        div()
+                                            .when(status_failed, |this| {
+                                                this.text_color(cx.theme().danger)
+                                            })
+                                            .child(status_text),
+                                        cx,
+                                    ))
+                                    .child(Self::render_field(
+                                        i18n.diagnostics_search_dirs_label,
+                                        div().v_flex().gap_1().text_xs().children(searched_dirs),
+                                        cx,
+                                    ))
+                                    .child(Self::render_field(
+                                        i18n.diagnostics_mirror_label,
+                                        Input::new(&self.mirror_input).small(),
+                                        cx,
+                                    ))
+                                    .child(Self::render_field(
+                                        i18n.diagnostics_proxy_label,
+                                        Input::new(&self.proxy_input).small(),
+                                        cx,
+                                    ))
+                                    .child(Self::render_field(
+                                        i18n.diagnostics_render_errors_label,
+                                        div()
+                                            .v_flex()
+                                            .gap_1()
+                                            .text_xs()
+                                            .when(render_errors.is_empty(), |this| {
+                                                this.text_color(cx.theme().muted_foreground)
+                                                    .child(i18n.diagnostics_render_errors_empty)
+                                            })
+                                            .children(render_errors.into_iter().map(
+                                                |(age, message)| {
+                                                    div()
+                                                        .h_flex()
+                                                        .items_start()
+                                                        .gap_2()
+                                                        .child(
+                                                            div()
+                                                                .flex_none()
+                                                                .text_color(
+                                                                    cx.theme().muted_foreground,
+                                                                )
+                                                                .child(age),
+                                                        )
+                                                        .child(div().flex_1().child(message))
+                                                },
+                                            )),
+                                        cx,
+                                    )),
+                            ),
+                    )
+                    .when_some(self.download_progress, |this, progress| {
+                        this.child(self.render_download_progress(progress, cx))
+                    })
+                    .when_some(self.status_message.clone(), |this, message| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .whitespace_normal()
+                                .child(message),
+                        )
+                    })
+                    .child(
+                        div()
+                            .w_full()
+                            .flex()
+                            .items_center()
+                            .justify_end()
+                            .gap_2()
+                            .child(if is_downloading {
+                                Button::new("diagnostics-download-cancel")
+                                    .small()
+                                    .label(i18n.diagnostics_download_cancel_button)
+                                    .on_click(cx.listener(|this, _, _, _| {
+                                        this.cancel_download();
+                                    }))
+                            } else {
+                                Button::new("diagnostics-redownload")
+                                    .small()
+                                    .label(i18n.diagnostics_redownload_button)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        // Builds without pinned checksums cannot verify a
+                                        // download, so send the user to the releases instead.
+                                        if pinned_download_available() {
+                                            this.start_download(cx);
+                                        } else {
+                                            cx.open_url(PDFIUM_BINARIES_RELEASES_URL);
+                                        }
+                                    }))
+                            })
+                            .child(
+                                Button::new("diagnostics-relocate")
+                                    .small()
+                                    .label(i18n.diagnostics_relocate_button)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.relocate_library(cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("diagnostics-refresh")
+                                    .small()
+                                    .ghost()
+                                    .label(i18n.diagnostics_refresh_button)
+                                    .disabled(is_checking)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.refresh(cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("diagnostics-close")
+                                    .small()
+                                    .ghost()
+                                    .label(i18n.close_button)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.close_dialog(window, cx);
+                                    })),
+                            ),
+                    ),
+            )
+    }
+}
+
+impl PdfViewer {
+    pub(super) fn pdfium_fetch_options(&self) -> FetchOptions {
+        FetchOptions {
+            mirror: self.stored_preference_string(VIEW_PREFERENCES_KEY_PDFIUM_MIRROR),
+            proxy: self.stored_preference_string(VIEW_PREFERENCES_KEY_DOWNLOAD_PROXY),
+        }
+    }
+
+    pub(super) fn persist_pdfium_fetch_options(&self, options: &FetchOptions) {
+        self.persist_preference_strings(&[
+            (VIEW_PREFERENCES_KEY_PDFIUM_MIRROR, &options.mirror),
+            (VIEW_PREFERENCES_KEY_DOWNLOAD_PROXY, &options.proxy),
+        ]);
+    }
+
+    /// A non-blank string from the view preferences tree.
+    pub(super) fn stored_preference_string(&self, key: &str) -> Option<String> {
+        self.view_preferences_store
+            .as_ref()
+            .and_then(|store| store.get(key).ok().flatten())
+            .and_then(|raw| String::from_utf8(raw.to_vec()).ok())
+            .filter(|value| !value.trim().is_empty())
+    }
+
+    /// Writes each value, removing the key when it is `None`.
+    pub(super) fn persist_preference_strings(&self, entries: &[(&str, &Option<String>)]) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        for &(key, value) in entries {
+            let result = match value {
+                Some(value) => store.insert(key, value.as_bytes()).map(|_| ()),
+                None => store.remove(key).map(|_| ()),
+            };
+            if result.is_err() {
+                return;
+            }
+        }
+
+        let _ = store.flush();
+    }
+
+    pub(super) fn open_diagnostics_dialog(&mut self, cx: &mut Context<Self>) {
+        if self.command_panel_open {
+            self.close_command_panel(cx);
+        }
+        self.close_about_dialog(cx);
+
+        if self.diagnostics_dialog_open {
+            if let Some(handle) = self.diagnostics_dialog_window.as_ref() {
+                let _ = handle.update(cx, |_, window, _| {
+                    window.activate_window();
+                });
+            }
+            return;
+        }
+
+        self.diagnostics_dialog_open = true;
+        self.needs_root_refocus = false;
+        self.diagnostics_dialog_session = self.diagnostics_dialog_session.wrapping_add(1);
+        let session_id = self.diagnostics_dialog_session;
+
+        let language = self.language;
+        let viewer = cx.entity();
+        let viewer_for_close = viewer.clone();
+        let window_options = WindowOptions {
+            titlebar: Some(Self::dialog_titlebar_options()),
+            window_bounds: Some(WindowBounds::centered(size(px(560.), px(640.)), cx)),
+            window_decorations: Some(WindowDecorations::Client),
+            ..WindowOptions::default()
+        };
+
+        match cx.open_window(window_options, move |window, cx| {
+            window.on_window_should_close(cx, move |_, cx| {
+                let _ = viewer_for_close.update(cx, |this, cx| {
+                    this.on_diagnostics_dialog_window_closed(session_id, cx);
+                });
+                true
+            });
+            let dialog = cx.new(|cx| DiagnosticsWindow::new(viewer, language, window, cx));
+            let dialog_focus = dialog.read(cx).focus_handle.clone();
+            let root = cx.new(|cx| Root::new(dialog, window, cx));
+            window.focus(&dialog_focus);
+            root
+        }) {
+            Ok(handle) => {
+                self.diagnostics_dialog_window = Some(handle.into());
+                cx.notify();
+            }
+            Err(err) => {
+                crate::debug_log!(
+                    @warn,
+                    "[diagnostics] failed to open diagnostics window: {}",
+                    err,
+                );
+                self.on_diagnostics_dialog_window_closed(session_id, cx);
+            }
+        }
+    }
+
+    pub(super) fn close_diagnostics_dialog(&mut self, cx: &mut Context<Self>) {
+        let window_handle = self.diagnostics_dialog_window.take();
+        let mut changed = false;
+        if self.diagnostics_dialog_open {
+            self.diagnostics_dialog_open = false;
+            changed = true;
+        }
+        if changed || window_handle.is_some() {
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+        // Defer window removal to avoid borrow conflicts during event handling
+        if let Some(window_handle) = window_handle {
+            cx.defer(move |cx| {
+                let _ = window_handle.update(cx, |_, window, _| {
+                    window.remove_window();
+                });
+            });
+        }
+    }
+
+    fn on_diagnostics_dialog_window_closed(&mut self, session_id: u64, cx: &mut Context<Self>) {
+        if self.diagnostics_dialog_session == session_id {
+            self.diagnostics_dialog_window = None;
+            self.diagnostics_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+    }
+}
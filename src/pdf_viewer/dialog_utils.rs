@@ -10,4 +10,80 @@ impl PdfViewer {
         }
         options
     }
+
+    /// Thin bar with a caption underneath, as used by the long-running tool dialogs.
+    fn render_progress_bar(fraction: f32, label: String, cx: &App) -> impl IntoElement {
+        div()
+            .v_flex()
+            .gap_1()
+            .child(
+                div()
+                    .w_full()
+                    .h(px(6.))
+                    .rounded_full()
+                    .bg(cx.theme().secondary)
+                    .child(
+                        div()
+                            .h_full()
+                            .rounded_full()
+                            .bg(cx.theme().primary)
+                            .w(relative(fraction.clamp(0.0, 1.0))),
+                    ),
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(label),
+            )
+    }
+
+    fn render_dialog_field(
+        label: &'static str,
+        value: impl IntoElement,
+        cx: &App,
+    ) -> impl IntoElement {
+        div()
+            .v_flex()
+            .items_start()
+            .gap_1()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(label),
+            )
+            .child(value)
+    }
+
+    /// A row of mutually exclusive choices that writes the picked index through `select`.
+    fn render_choice_group<T: 'static>(
+        id: &'static str,
+        labels: Vec<String>,
+        selected: usize,
+        disabled: bool,
+        select: fn(&mut T, usize, &mut Context<T>),
+        cx: &mut Context<T>,
+    ) -> impl IntoElement {
+        labels
+            .into_iter()
+            .enumerate()
+            .fold(
+                ButtonGroup::new(id).small().outline(),
+                |group, (ix, label)| {
+                    group.child(
+                        Button::new((id, ix))
+                            .label(label)
+                            .selected(ix == selected)
+                            .disabled(disabled),
+                    )
+                },
+            )
+            .on_click(cx.listener(move |this, picked: &Vec<usize>, _, cx| {
+                if let Some(ix) = picked.first() {
+                    select(this, *ix, cx);
+                    cx.notify();
+                }
+            }))
+    }
 }
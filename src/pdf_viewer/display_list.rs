@@ -1,16 +1,32 @@
-use super::{PdfViewer, TabLayoutMode, TextMarkupColor, TextMarkupKind};
+use super::text_selection::{PageTextCache, TextSelection};
+use super::{
+    DISPLAY_TILE_HEIGHT, PdfViewer, StampContent, TEXT_PREFETCH_PAGE_DELAY_MS, TabLayoutMode,
+    TextMarkupColor, TextMarkupKind,
+};
 use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 use gpui_component::button::*;
 use gpui_component::scroll::{Scrollbar, ScrollbarShow};
 use gpui_component::text::TextView;
 use gpui_component::*;
+use std::collections::BTreeMap;
 use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 
 const MARKDOWN_NOTE_BUBBLE_OFFSET_X: f32 = 14.0;
 const MARKDOWN_NOTE_BUBBLE_PADDING: f32 = 8.0;
 const TEXT_SELECTION_HOVER_MENU_OFFSET_Y: f32 = 44.0;
 
+struct StampOverlay {
+    id: u64,
+    content: StampContent,
+    left: f32,
+    top: f32,
+    width: f32,
+    height: f32,
+}
+
 #[derive(Clone)]
 struct MarkdownNoteMarker {
     id: u64,
@@ -33,7 +49,7 @@ struct TextMarkupScreenRect {
 }
 
 impl PdfViewer {
-    fn text_markup_color_rgb(color: TextMarkupColor) -> gpui::Rgba {
+    pub(super) fn text_markup_color_rgb(color: TextMarkupColor) -> gpui::Rgba {
         match color {
             TextMarkupColor::Yellow => gpui::rgb(0xF2C94C),
             TextMarkupColor::Green => gpui::rgb(0x34D399),
@@ -52,6 +68,13 @@ impl PdfViewer {
         let i18n = self.i18n();
         let zoom = self.active_tab_zoom();
         let is_home_tab = self.active_tab_path().is_none();
+        let show_recent_files = self
+            .startup_blank_tab_id
+            .is_none_or(|tab_id| self.tab_bar.active_tab_id() != Some(tab_id));
+        let failed_tab_id = self
+            .active_tab()
+            .filter(|tab| tab.summary_failed)
+            .map(|tab| tab.id);
         let recent_files_with_positions = self.recent_files_with_positions(&self.recent_files);
 
         div()
@@ -60,6 +83,10 @@ impl PdfViewer {
             .flex_1()
             .v_flex()
             .overflow_hidden()
+            .on_scroll_wheel(cx.listener(|this, event: &ScrollWheelEvent, _, cx| {
+                this.note_user_activity(cx);
+                this.handle_display_swipe(event, cx);
+            }))
             .bg(cx.theme().muted)
             .child(
                 div()
@@ -92,15 +119,25 @@ impl PdfViewer {
                                                 .text_color(cx.theme().foreground)
                                                 .child(i18n.file_not_opened),
                                         )
-                                        .child(Self::render_recent_files_list_content(
-                                            2,
-                                            i18n,
-                                            cx.entity(),
-                                            recent_files_with_positions.clone(),
-                                            &self.recent_home_list_scroll,
-                                            true,
-                                            cx,
-                                        )),
+                                        .when(show_recent_files, |this| {
+                                            this.child(Self::render_recent_files_list_content(
+                                                2,
+                                                i18n,
+                                                cx.entity(),
+                                                recent_files_with_positions.clone(),
+                                                &self.recent_home_list_scroll,
+                                                true,
+                                                cx,
+                                            ))
+                                        })
+                                        .when(!show_recent_files, |this| {
+                                            this.child(
+                                                div()
+                                                    .text_xs()
+                                                    .text_color(cx.theme().muted_foreground)
+                                                    .child(i18n.no_document_hint),
+                                            )
+                                        }),
                                 ),
                         )
                     })
@@ -122,8 +159,22 @@ impl PdfViewer {
                                     div()
                                         .text_sm()
                                         .text_color(cx.theme().muted_foreground)
-                                        .child(i18n.no_pages),
-                                ),
+                                        .child(if failed_tab_id.is_some() {
+                                            i18n.load_error_summary
+                                        } else {
+                                            i18n.no_pages
+                                        }),
+                                )
+                                .when_some(failed_tab_id, |this, tab_id| {
+                                    this.child(
+                                        Button::new("load-error-show-details")
+                                            .small()
+                                            .label(i18n.load_error_show_details_button)
+                                            .on_click(cx.listener(move |this, _, _, cx| {
+                                                this.open_load_error_dialog(tab_id, cx);
+                                            })),
+                                    )
+                                }),
                         )
                     })
                     .when(page_count > 0, |this| {
@@ -139,12 +190,18 @@ impl PdfViewer {
                                         display_sizes.clone(),
                                         move |viewer, visible_range, window, cx| {
                                             let target_width =
-                                                viewer.display_target_width(window, zoom);
+                                                viewer.display_render_target_width(window, zoom);
                                             viewer.request_display_load_for_visible_range(
                                                 visible_range.clone(),
                                                 target_width,
                                                 cx,
                                             );
+                                            viewer.request_display_tiles_for_visible_range(
+                                                visible_range.clone(),
+                                                target_width,
+                                                window,
+                                                cx,
+                                            );
 
                                             // Note: Text is loaded on-demand when user interacts with the page
                                             // Pdfium is not thread-safe, so we cannot load text asynchronously
@@ -217,6 +274,8 @@ impl PdfViewer {
         let i18n = self.i18n();
         let selection_rects =
             self.get_selection_rects_for_page(page_index, page_width, page_height, scale);
+        let search_match_rects =
+            self.search_match_rects_for_page(page_index, page_width, page_height, scale);
         let text_markup_rects = self.text_markup_rects_for_page(
             page_index,
             page.width_pt,
@@ -225,6 +284,7 @@ impl PdfViewer {
             page_height,
             scale,
         );
+        let stamp_overlays = self.stamp_overlays_for_page(page_index, page_width, page_height);
         let markdown_note_markers =
             self.markdown_note_markers_for_page(page_index, page_width, page_height);
         let markdown_note_selection_rects =
@@ -232,6 +292,50 @@ impl PdfViewer {
 
         // Get page info for coordinate conversion
         let _page_height_pt = page.height_pt;
+        // Until the first display render lands, stretch the thumbnail so the card isn't blank.
+        let display_image = page
+            .display_image
+            .clone()
+            .or_else(|| page.thumbnail_image.clone())
+            .filter(|_| !page.display_failed);
+        // Oversized pages stitch their sharp band tiles over the low-resolution base image.
+        // Tiles from the previous zoom level go underneath, scaled, until replaced.
+        let tile_rects = |render_width: u32, tiles: &BTreeMap<u32, Arc<RenderImage>>| {
+            let logical_per_device = page_width / render_width.max(1) as f32;
+            let page_height_px = Self::display_render_height(page, render_width) as f32;
+            tiles
+                .iter()
+                .map(|(row, image)| {
+                    let top = (*row * DISPLAY_TILE_HEIGHT) as f32;
+                    let height = (DISPLAY_TILE_HEIGHT as f32).min(page_height_px - top);
+                    (
+                        top * logical_per_device,
+                        height * logical_per_device,
+                        image.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        let display_tiles = self
+            .active_tab()
+            .and_then(|tab| tab.display_tiles.get(&page_index))
+            .filter(|_| !page.display_failed)
+            .map(|tiles| {
+                let mut rects = tiles
+                    .stale
+                    .as_ref()
+                    .map(|(width, stale)| tile_rects(*width, stale))
+                    .unwrap_or_default();
+                rects.extend(tile_rects(tiles.render_width, &tiles.tiles));
+                rects
+            })
+            .unwrap_or_default();
+        let skeleton_only = self.active_tab().is_some_and(|tab| tab.skeleton_only);
+        // Gigantic pages (posters, maps) render below the zoom level; say so on the card.
+        let resolution_reduced = self.active_tab().is_some_and(|tab| {
+            tab.display_render_target_width > 0
+                && Self::display_render_clamped(page, tab.display_render_target_width)
+        });
 
         div()
             .id(("display-row", page_index))
@@ -244,14 +348,38 @@ impl PdfViewer {
                     .relative()
                     .overflow_hidden()
                     .bg(cx.theme().background)
-                    .when_some(page.display_image.clone(), |this, display_image| {
+                    .when_some(display_image.clone(), |this, display_image| {
                         this.child(
                             img(display_image)
                                 .size_full()
                                 .object_fit(ObjectFit::Contain),
                         )
                     })
-                    .when(page.display_image.is_none(), |this| {
+                    .children(display_tiles.into_iter().map(|(top, height, image)| {
+                        div()
+                            .absolute()
+                            .left_0()
+                            .top(px(top))
+                            .w_full()
+                            .h(px(height))
+                            .child(img(image).size_full().object_fit(ObjectFit::Fill))
+                    }))
+                    .when(resolution_reduced && display_image.is_some(), |this| {
+                        this.child(
+                            div()
+                                .absolute()
+                                .top_2()
+                                .right_2()
+                                .px_2()
+                                .py_0p5()
+                                .rounded_md()
+                                .bg(cx.theme().warning)
+                                .text_color(cx.theme().warning_foreground)
+                                .text_xs()
+                                .child(i18n.page_render_resolution_reduced),
+                        )
+                    })
+                    .when(display_image.is_none(), |this| {
                         this.child(
                             div()
                                 .size_full()
@@ -267,8 +395,36 @@ impl PdfViewer {
                                             .text_color(cx.theme().muted_foreground),
                                     )
                                     .child(div().text_xs().child(i18n.page_render_failed))
+                                    .child(
+                                        Button::new(("display-render-retry", page_index))
+                                            .small()
+                                            .label(i18n.page_render_retry_button)
+                                            .on_click(cx.listener(move |this, _, _, cx| {
+                                                cx.stop_propagation();
+                                                this.retry_display_render(page_index, cx);
+                                            })),
+                                    )
+                                })
+                                .when(skeleton_only, |this| match page.skeleton_text.clone() {
+                                    Some(text) => this.child(
+                                        div()
+                                            .size_full()
+                                            .p_6()
+                                            .overflow_hidden()
+                                            .text_xs()
+                                            .text_color(cx.theme().foreground)
+                                            .child(text),
+                                    ),
+                                    None => {
+                                        this.child(
+                                            Icon::new(crate::icons::IconName::File)
+                                                .size_8()
+                                                .text_color(cx.theme().muted_foreground),
+                                        )
+                                        .child(div().text_xs().child(i18n.page_preview_unavailable))
+                                    }
                                 })
-                                .when(!page.display_failed, |this| {
+                                .when(!page.display_failed && !skeleton_only, |this| {
                                     this.child(
                                         spinner::Spinner::new()
                                             .large()
@@ -307,6 +463,17 @@ impl PdfViewer {
                                             window,
                                         );
 
+                                        if this.handle_shape_mouse_down(
+                                            page_index,
+                                            local_x,
+                                            local_y,
+                                            page_width,
+                                            page_height,
+                                            cx,
+                                        ) {
+                                            return;
+                                        }
+
                                         if let Some(note_id) = this
                                             .hit_test_markdown_note_id_on_page(
                                                 page_index,
@@ -323,7 +490,7 @@ impl PdfViewer {
                                             return;
                                         }
 
-                                        this.handle_text_mouse_down(
+                                        let on_text = this.handle_text_mouse_down(
                                             page_index,
                                             local_x,
                                             local_y,
@@ -331,6 +498,9 @@ impl PdfViewer {
                                             page_height,
                                             cx,
                                         );
+                                        if event.click_count == 2 && !on_text {
+                                            this.run_page_double_click_action(window, cx);
+                                        }
                                     },
                                 ),
                             )
@@ -344,6 +514,20 @@ impl PdfViewer {
                                             page_width,
                                             window,
                                         );
+                                        if let Some((shape_id, _)) = this.hit_test_shape_on_page(
+                                            page_index,
+                                            local_x,
+                                            local_y,
+                                            page_width,
+                                            page_height,
+                                        ) {
+                                            this.open_shape_context_menu(
+                                                shape_id,
+                                                event.position,
+                                                cx,
+                                            );
+                                            return;
+                                        }
                                         let note_id = this.hit_test_markdown_note_id_on_page(
                                             page_index,
                                             local_x,
@@ -385,6 +569,17 @@ impl PdfViewer {
                                         window,
                                     );
 
+                                    if this.handle_shape_mouse_move(
+                                        page_index,
+                                        local_x,
+                                        local_y,
+                                        page_width,
+                                        page_height,
+                                        cx,
+                                    ) {
+                                        return;
+                                    }
+
                                     this.handle_text_mouse_move(
                                         page_index,
                                         local_x,
@@ -399,6 +594,9 @@ impl PdfViewer {
                                 gpui::MouseButton::Left,
                                 cx.listener(
                                     move |this, event: &gpui::MouseUpEvent, _window, cx| {
+                                        if this.handle_shape_mouse_up(cx) {
+                                            return;
+                                        }
                                         this.handle_text_mouse_up(page_index, event.position, cx);
                                     },
                                 ),
@@ -478,6 +676,22 @@ impl PdfViewer {
                             }
                         }
                     }))
+                    // Render "highlight all" search matches beneath the selection
+                    .children(
+                        search_match_rects
+                            .into_iter()
+                            .map(|(left, top, right, bottom)| {
+                                div()
+                                    .absolute()
+                                    .left(px(left))
+                                    .top(px(top))
+                                    .w(px(right - left))
+                                    .h(px(bottom - top))
+                                    .bg(gpui::rgb(0xFACC15)) // Yellow for search matches
+                                    .opacity(0.35)
+                                    .into_any_element()
+                            }),
+                    )
                     // Render selection highlights (rendered after overlay, so appear on top)
                     .children(
                         selection_rects
@@ -528,6 +742,50 @@ impl PdfViewer {
                                     .into_any_element()
                             }),
                     )
+                    .children(self.render_shape_overlay(page_index, page_width, page_height, cx))
+                    .children(stamp_overlays.into_iter().map(|stamp| {
+                        let color = Self::stamp_color(&stamp.content);
+                        let stamp_id = stamp.id;
+                        let label = match &stamp.content {
+                            StampContent::Preset(preset) => {
+                                Some(Self::stamp_preset_label(i18n, *preset).to_string())
+                            }
+                            StampContent::Text(text) => Some(text.clone()),
+                            StampContent::Image(_) => None,
+                        };
+                        div()
+                            .id(("page-stamp", stamp_id))
+                            .absolute()
+                            .left(px(stamp.left))
+                            .top(px(stamp.top))
+                            .w(px(stamp.width))
+                            .h(px(stamp.height))
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .overflow_hidden()
+                            .on_mouse_down(
+                                gpui::MouseButton::Right,
+                                cx.listener(move |this, event: &gpui::MouseDownEvent, _, cx| {
+                                    this.open_stamp_context_menu(stamp_id, event.position, cx);
+                                    cx.stop_propagation();
+                                }),
+                            )
+                            .map(|this| match (&stamp.content, label) {
+                                (StampContent::Image(path), _) => this.child(
+                                    img(path.clone()).size_full().object_fit(ObjectFit::Contain),
+                                ),
+                                (_, label) => this
+                                    .rounded_md()
+                                    .border_2()
+                                    .border_color(color)
+                                    .text_color(color)
+                                    .text_size(px((stamp.height * 0.45).max(6.0)))
+                                    .font_weight(FontWeight::BOLD)
+                                    .whitespace_nowrap()
+                                    .child(label.unwrap_or_default().to_uppercase()),
+                            })
+                    }))
                     .children(markdown_note_markers.iter().map(|marker| {
                         let is_hovered = self.hovered_markdown_note_id() == Some(marker.id);
                         let bubble_bg = if is_hovered {
@@ -597,7 +855,8 @@ impl PdfViewer {
                                 )
                             })
                             .into_any_element()
-                    })),
+                    }))
+                    .children(self.render_link_hints(page_index, page_width, page_height)),
             )
             .into_any_element()
     }
@@ -630,7 +889,7 @@ impl PdfViewer {
         Some((content_width, content_height, x_offset, y_offset))
     }
 
-    fn page_local_screen_to_note_anchor(
+    pub(super) fn page_local_screen_to_note_anchor(
         &self,
         page_index: usize,
         local_x: f32,
@@ -669,7 +928,7 @@ impl PdfViewer {
         })
     }
 
-    fn note_anchor_to_page_local_screen(
+    pub(super) fn note_anchor_to_page_local_screen(
         &self,
         page_index: usize,
         anchor: &super::MarkdownNoteAnchor,
@@ -695,6 +954,48 @@ impl PdfViewer {
         Some((local_x, local_y))
     }
 
+    fn stamp_overlays_for_page(
+        &self,
+        page_index: usize,
+        page_width_screen: f32,
+        page_height_screen: f32,
+    ) -> Vec<StampOverlay> {
+        let Some(page) = self
+            .active_tab_pages()
+            .and_then(|pages| pages.get(page_index))
+            .filter(|page| page.width_pt > 0.0)
+        else {
+            return Vec::new();
+        };
+        let scale = page_width_screen / page.width_pt;
+        self.active_tab_stamps_for_page(page_index)
+            .into_iter()
+            .filter_map(|stamp| {
+                let anchor = super::MarkdownNoteAnchor {
+                    page_index,
+                    x_ratio: stamp.x_ratio,
+                    y_ratio: stamp.y_ratio,
+                };
+                let (x, y) = self.note_anchor_to_page_local_screen(
+                    page_index,
+                    &anchor,
+                    page_width_screen,
+                    page_height_screen,
+                )?;
+                let width = stamp.width_ratio * page.width_pt * scale;
+                let height = stamp.height_ratio * page.height_pt * scale;
+                Some(StampOverlay {
+                    id: stamp.id,
+                    content: stamp.content,
+                    left: x - width / 2.0,
+                    top: y - height / 2.0,
+                    width,
+                    height,
+                })
+            })
+            .collect()
+    }
+
     fn markdown_note_markers_for_page(
         &self,
         page_index: usize,
@@ -816,9 +1117,78 @@ impl PdfViewer {
             return Vec::new();
         };
 
-        let page_width_pt = cache.page_width;
-        let page_height_pt = cache.page_height;
+        Self::text_rects_to_screen(
+            rects,
+            cache.page_width,
+            cache.page_height,
+            page_width_screen,
+            page_height_screen,
+            scale,
+        )
+    }
 
+    /// "Highlight all" match rectangles on `page_index`, in page-local screen space.
+    fn search_match_rects_for_page(
+        &self,
+        page_index: usize,
+        page_width_screen: f32,
+        page_height_screen: f32,
+        scale: f32,
+    ) -> Vec<(f32, f32, f32, f32)> {
+        if !self.search_highlights_shown() {
+            return Vec::new();
+        }
+        let Some(tab) = self.active_tab() else {
+            return Vec::new();
+        };
+        if !tab
+            .search_results
+            .iter()
+            .any(|hit| hit.page_index == page_index)
+        {
+            return Vec::new();
+        }
+        let Some(cache) = tab
+            .text_selection_manager
+            .borrow()
+            .get_page_cache(page_index)
+        else {
+            return Vec::new();
+        };
+
+        let mut match_rects = tab.search_match_rects.borrow_mut();
+        let rects = match_rects.entry(page_index).or_insert_with(|| {
+            tab.search_results
+                .iter()
+                .filter(|hit| hit.page_index == page_index)
+                .flat_map(|hit| {
+                    cache.get_selection_bounds(&TextSelection::new(
+                        page_index,
+                        hit.char_start,
+                        hit.char_start + hit.char_len,
+                    ))
+                })
+                .collect()
+        });
+        Self::text_rects_to_screen(
+            rects.clone(),
+            cache.page_width,
+            cache.page_height,
+            page_width_screen,
+            page_height_screen,
+            scale,
+        )
+    }
+
+    /// Converts rectangles in page points (origin bottom-left) to page-local screen space.
+    fn text_rects_to_screen(
+        rects: Vec<(f32, f32, f32, f32)>,
+        page_width_pt: f32,
+        page_height_pt: f32,
+        page_width_screen: f32,
+        page_height_screen: f32,
+        scale: f32,
+    ) -> Vec<(f32, f32, f32, f32)> {
         // Use the incoming scale parameter which should match the one used in mouse events
         // The incoming scale was calculated as: scale = page_width / page.width_pt (in render_page_with_text_selection)
         let effective_scale = scale;
@@ -978,18 +1348,26 @@ impl PdfViewer {
 
         // Calculate display panel offset based on current layout state
         // This mirrors the actual render layout structure in mod.rs
+        // With a right-to-left layout the side panels are on the right instead.
         let mut display_panel_left = 0.0;
-        if self.tab_layout_mode == TabLayoutMode::Vertical && self.vertical_tab_bar_visible {
-            display_panel_left += super::VERTICAL_TAB_BAR_WIDTH;
+        if !self.language.is_rtl() {
+            if self.tab_layout_mode == TabLayoutMode::Vertical
+                && self.vertical_tab_bar_visible
+                && !self.zen_mode
+            {
+                display_panel_left += super::VERTICAL_TAB_BAR_WIDTH;
+            }
+            if self.show_thumbnail_panel() {
+                display_panel_left += self.sidebar_width;
+            }
         }
-        if self.show_thumbnail_panel() {
-            display_panel_left += super::SIDEBAR_WIDTH;
+        let mut content_offset_y = 0.0;
+        if self.title_bar_in_layout() {
+            content_offset_y += super::TITLE_BAR_HEIGHT;
+        }
+        if self.tab_layout_mode == TabLayoutMode::Horizontal && !self.zen_mode {
+            content_offset_y += super::TAB_BAR_HEIGHT;
         }
-        let content_offset_y = if self.tab_layout_mode == TabLayoutMode::Vertical {
-            super::TITLE_BAR_HEIGHT
-        } else {
-            super::TITLE_BAR_HEIGHT + super::TAB_BAR_HEIGHT
-        };
 
         // Convert window coordinates to local page container coordinates
         let local_x = f32::from(window_pos.x) - display_panel_left - horizontal_offset;
@@ -1001,6 +1379,7 @@ impl PdfViewer {
         (local_x, local_y)
     }
 
+    /// Starts a text selection at the pointer; returns whether the pointer was on text.
     fn handle_text_mouse_down(
         &mut self,
         page_index: usize,
@@ -1009,7 +1388,7 @@ impl PdfViewer {
         page_width_screen: f32,
         page_height_screen: f32,
         cx: &mut Context<Self>,
-    ) {
+    ) -> bool {
         let _ = self.set_markdown_note_hover_id(None);
         let _ = self.set_text_markup_hover_id(None);
         self.clear_text_selection_hover_menu_state();
@@ -1018,7 +1397,7 @@ impl PdfViewer {
         self.ensure_page_text_loaded(page_index);
 
         let Some(manager_ref) = self.active_tab_text_selection_manager() else {
-            return;
+            return false;
         };
         let manager = manager_ref.borrow();
 
@@ -1030,7 +1409,7 @@ impl PdfViewer {
                 manager_ref.borrow_mut().clear_selection();
             }
             cx.notify();
-            return;
+            return false;
         };
 
         let page_width_pt = cache.page_width;
@@ -1062,6 +1441,7 @@ impl PdfViewer {
             }
         }
         cx.notify();
+        char_index.is_some()
     }
 
     fn handle_text_mouse_move(
@@ -1214,6 +1594,64 @@ impl PdfViewer {
         }
     }
 
+    /// Extracts the text layer of every page in the background after a document opens, so
+    /// the first selection or copy on a page doesn't stall on Pdfium.
+    pub(super) fn request_text_layer_prefetch(&mut self, tab_id: usize, cx: &mut Context<Self>) {
+        let Some(tab) = self.tab_bar.tabs().iter().find(|tab| tab.id == tab_id) else {
+            return;
+        };
+        let Some(path) = tab.path.clone() else {
+            return;
+        };
+        let page_count = tab.pages.len();
+        let text_caches = tab.text_selection_manager.borrow().text_cache_handle();
+
+        cx.spawn(async move |_, cx| {
+            for page_index in 0..page_count {
+                // The tab closed or loaded another document.
+                let Some(caches) = text_caches.upgrade() else {
+                    return;
+                };
+                let cached = caches
+                    .lock()
+                    .map(|caches| caches.contains_key(&page_index))
+                    .unwrap_or(true);
+                drop(caches);
+                if cached {
+                    continue;
+                }
+
+                let loaded = cx
+                    .background_executor()
+                    .spawn({
+                        let path = path.clone();
+                        async move {
+                            crate::pdf_viewer::utils::load_page_text_for_selection(
+                                &path, page_index,
+                            )
+                        }
+                    })
+                    .await;
+                if let Ok(Some((page_index, page_width, page_height, chars))) = loaded
+                    && let Some(caches) = text_caches.upgrade()
+                    && let Ok(mut caches) = caches.lock()
+                {
+                    caches.entry(page_index).or_insert(PageTextCache {
+                        chars,
+                        page_width,
+                        page_height,
+                    });
+                }
+
+                // Leave gaps between pages so renders waiting on Pdfium go first.
+                cx.background_executor()
+                    .timer(Duration::from_millis(TEXT_PREFETCH_PAGE_DELAY_MS))
+                    .await;
+            }
+        })
+        .detach();
+    }
+
     pub(super) fn has_markups_in_current_selection(&self) -> bool {
         if let Some((page_index, _, page_width_pt, page_height_pt, selection_rects)) =
             self.active_text_selection_snapshot()
@@ -1559,6 +1997,20 @@ impl PdfViewer {
                                 this.close_context_menu(cx);
                             })),
                     )
+                    .when(!self.external_tools.is_empty(), |this| {
+                        this.child(div().h(px(1.)).bg(cx.theme().border))
+                    })
+                    .children(self.external_tools.iter().enumerate().map(|(index, tool)| {
+                        Button::new(("tab-open-with", index))
+                            .small()
+                            .w_full()
+                            .disabled(!can_reveal)
+                            .label(i18n.open_with_tool(&tool.name))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.open_tab_with_external_tool(tab_id, index);
+                                this.close_context_menu(cx);
+                            }))
+                    }))
                     .into_any_element(),
             );
         }
@@ -1754,6 +2206,55 @@ impl PdfViewer {
             );
         }
 
+        if let Some(shape_id) = self.context_menu_shape_id {
+            return self.render_shape_context_menu(shape_id, x, y, cx);
+        }
+
+        if let Some(stamp_id) = self.context_menu_stamp_id {
+            return Some(
+                div()
+                    .id(("context-menu-stamp", stamp_id))
+                    .absolute()
+                    .left(px(x))
+                    .top(px(y))
+                    .h(px(38.))
+                    .rounded_lg()
+                    .border_1()
+                    .border_color(cx.theme().border.opacity(0.88))
+                    .bg(cx.theme().secondary.opacity(0.94))
+                    .shadow_md()
+                    .px_1()
+                    .flex()
+                    .items_center()
+                    .on_mouse_down(
+                        gpui::MouseButton::Left,
+                        cx.listener(|_, _: &gpui::MouseDownEvent, _, cx| {
+                            cx.stop_propagation();
+                        }),
+                    )
+                    .child(
+                        div()
+                            .id(("stamp-delete", stamp_id))
+                            .px_1()
+                            .py_0()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|this| this.bg(cx.theme().secondary))
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.delete_stamp_by_id(stamp_id, cx);
+                                this.close_context_menu(cx);
+                            }))
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(cx.theme().foreground)
+                                    .child(i18n.delete_stamp_button),
+                            ),
+                    )
+                    .into_any_element(),
+            );
+        }
+
         let note_anchor = self.context_menu_note_anchor;
         let has_text_selection = self.has_text_selection();
         Some(
@@ -1837,6 +2338,65 @@ impl PdfViewer {
                                         })
                                         .child(i18n.add_note_here_button),
                                 ),
+                        )
+                        .child(div().h(px(16.)).w_px().bg(cx.theme().border))
+                        .child(
+                            div()
+                                .id("stamp-add")
+                                .px_1()
+                                .py_0()
+                                .rounded_md()
+                                .cursor_pointer()
+                                .hover(|this| this.bg(cx.theme().secondary))
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    let Some(anchor) = note_anchor else {
+                                        return;
+                                    };
+                                    this.close_context_menu(cx);
+                                    this.open_stamp_dialog(anchor, cx);
+                                }))
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(if note_anchor.is_none() {
+                                            cx.theme().muted_foreground
+                                        } else {
+                                            cx.theme().foreground
+                                        })
+                                        .child(i18n.add_stamp_here_button),
+                                ),
+                        )
+                        .children(
+                            self.plugin_commands
+                                .iter()
+                                .enumerate()
+                                .filter(|(_, command)| command.in_context_menu)
+                                .map(|(index, command)| {
+                                    div()
+                                        .h_flex()
+                                        .items_center()
+                                        .gap_1()
+                                        .child(div().h(px(16.)).w_px().bg(cx.theme().border))
+                                        .child(
+                                            div()
+                                                .id(("plugin-command", index))
+                                                .px_1()
+                                                .py_0()
+                                                .rounded_md()
+                                                .cursor_pointer()
+                                                .hover(|this| this.bg(cx.theme().secondary))
+                                                .on_click(cx.listener(move |this, _, _, cx| {
+                                                    this.close_context_menu(cx);
+                                                    this.run_plugin_command(index, cx);
+                                                }))
+                                                .child(
+                                                    div()
+                                                        .text_xs()
+                                                        .text_color(cx.theme().foreground)
+                                                        .child(command.title.clone()),
+                                                ),
+                                        )
+                                }),
                         ),
                 )
                 .into_any_element(),
@@ -0,0 +1,405 @@
+use super::pdf_edit::{EncryptPermissions, save_encrypted_copy};
+use super::{ENCRYPT_DIALOG_WIDTH, ENCRYPT_DIALOG_WINDOW_HEIGHT};
+use crate::i18n::{I18n, Language};
+use crate::pdf_viewer::PdfViewer;
+use crate::pdf_viewer::utils::{display_file_name, is_same_file};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::checkbox::Checkbox;
+use gpui_component::input::{Input, InputState};
+use gpui_component::{button::*, *};
+use std::path::{Path, PathBuf};
+
+pub(super) struct EncryptWindow {
+    viewer: Entity<PdfViewer>,
+    focus_handle: FocusHandle,
+    language: Language,
+    input: PathBuf,
+    user_password_input: Entity<InputState>,
+    confirm_password_input: Entity<InputState>,
+    owner_password_input: Entity<InputState>,
+    permissions: EncryptPermissions,
+    saving: bool,
+    status_message: Option<String>,
+}
+
+impl EncryptWindow {
+    pub(super) fn new(
+        viewer: Entity<PdfViewer>,
+        language: Language,
+        input: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let i18n = I18n::new(language);
+        let mut password_input = |placeholder: &'static str| {
+            cx.new(|cx| {
+                InputState::new(window, cx)
+                    .masked(true)
+                    .placeholder(placeholder)
+            })
+        };
+        let user_password_input = password_input(i18n.encrypt_user_password_placeholder);
+        let confirm_password_input = password_input(i18n.encrypt_confirm_password_placeholder);
+        let owner_password_input = password_input(i18n.encrypt_owner_password_placeholder);
+
+        Self {
+            viewer,
+            focus_handle: cx.focus_handle(),
+            language,
+            input,
+            user_password_input,
+            confirm_password_input,
+            owner_password_input,
+            permissions: EncryptPermissions::default(),
+            saving: false,
+            status_message: None,
+        }
+    }
+
+    fn i18n(&self) -> I18n {
+        I18n::new(self.language)
+    }
+
+    fn start_save(&mut self, cx: &mut Context<Self>) {
+        if self.saving {
+            return;
+        }
+        let i18n = self.i18n();
+        let user_password = self.user_password_input.read(cx).value().to_string();
+        let confirm_password = self.confirm_password_input.read(cx).value().to_string();
+        let owner_password = self.owner_password_input.read(cx).value().to_string();
+        let problem = if user_password.is_empty() && owner_password.is_empty() {
+            Some(i18n.encrypt_missing_password)
+        } else if user_password != confirm_password {
+            Some(i18n.encrypt_password_mismatch)
+        } else {
+            None
+        };
+        if let Some(problem) = problem {
+            self.status_message = Some(problem.to_string());
+            cx.notify();
+            return;
+        }
+
+        let directory = self
+            .input
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let suggested_name = self
+            .input
+            .file_stem()
+            .map(|stem| format!("{}-protected.pdf", stem.to_string_lossy()))
+            .unwrap_or_else(|| "protected.pdf".to_string());
+        let picker = cx.prompt_for_new_path(&directory, Some(&suggested_name));
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(output))) = picker.await else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                this.run_encrypt(user_password, owner_password, output, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn run_encrypt(
+        &mut self,
+        user_password: String,
+        owner_password: String,
+        output: PathBuf,
+        cx: &mut Context<Self>,
+    ) {
+        if self.saving {
+            return;
+        }
+        // The protected file is always a copy; the unprotected original stays as it is.
+        if is_same_file(&self.input, &output) {
+            self.status_message = Some(self.i18n().save_copy_same_file.to_string());
+            cx.notify();
+            return;
+        }
+        let input = self.input.clone();
+        let language = self.language;
+        let permissions = self.permissions;
+        self.saving = true;
+        self.status_message = Some(self.i18n().encrypt_saving.to_string());
+        cx.notify();
+
+        let output_for_task = output.clone();
+        let encrypt = cx.background_executor().spawn(async move {
+            save_encrypted_copy(
+                &input,
+                &output_for_task,
+                &user_password,
+                &owner_password,
+                permissions,
+                language,
+            )
+        });
+
+        cx.spawn(async move |this, cx| {
+            let result = encrypt.await;
+            let _ = this.update(cx, |this, cx| {
+                let i18n = this.i18n();
+                this.saving = false;
+                this.status_message = Some(match result {
+                    Ok(()) => i18n.encrypt_done(&output),
+                    Err(err) => {
+                        crate::debug_log!(@warn, "[pdf][edit] encryption failed: {:#}", err);
+                        i18n.encrypt_failed(&format!("{err:#}"))
+                    }
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn close_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.close_encrypt_dialog(cx);
+        });
+        window.remove_window();
+    }
+
+    fn render_permission(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        checked: bool,
+        set: fn(&mut EncryptPermissions, bool),
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        Checkbox::new(id)
+            .label(label)
+            .checked(checked)
+            .disabled(self.saving)
+            .on_click(cx.listener(move |this, checked: &bool, _, cx| {
+                set(&mut this.permissions, *checked);
+                cx.notify();
+            }))
+    }
+}
+
+impl Render for EncryptWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let i18n = self.i18n();
+        let permissions = self.permissions;
+
+        div()
+            .id("encrypt-window")
+            .size_full()
+            .v_flex()
+            .bg(cx.theme().background)
+            .focusable()
+            .track_focus(&self.focus_handle)
+            .capture_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key.as_str() == "escape" {
+                    this.close_dialog(window, cx);
+                    cx.stop_propagation();
+                }
+            }))
+            .child(TitleBar::new())
+            .child(
+                div()
+                    .flex_1()
+                    .min_h(px(0.))
+                    .v_flex()
+                    .gap_3()
+                    .p_4()
+                    .child(
+                        div()
+                            .text_lg()
+                            .text_color(cx.theme().foreground)
+                            .child(i18n.encrypt_dialog_title),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().foreground)
+                            .truncate()
+                            .child(display_file_name(&self.input)),
+                    )
+                    .child(div().h(px(1.)).bg(cx.theme().border))
+                    .child(PdfViewer::render_dialog_field(
+                        i18n.encrypt_user_password_label,
+                        div()
+                            .w_full()
+                            .v_flex()
+                            .gap_2()
+                            .child(Input::new(&self.user_password_input).small())
+                            .child(Input::new(&self.confirm_password_input).small()),
+                        cx,
+                    ))
+                    .child(PdfViewer::render_dialog_field(
+                        i18n.encrypt_owner_password_label,
+                        div()
+                            .w_full()
+                            .child(Input::new(&self.owner_password_input).small()),
+                        cx,
+                    ))
+                    .child(PdfViewer::render_dialog_field(
+                        i18n.encrypt_permissions_label,
+                        div()
+                            .v_flex()
+                            .gap_2()
+                            .child(self.render_permission(
+                                "encrypt-allow-print",
+                                i18n.encrypt_allow_print,
+                                permissions.print,
+                                |permissions, allowed| permissions.print = allowed,
+                                cx,
+                            ))
+                            .child(self.render_permission(
+                                "encrypt-allow-copy",
+                                i18n.encrypt_allow_copy,
+                                permissions.copy,
+                                |permissions, allowed| permissions.copy = allowed,
+                                cx,
+                            ))
+                            .child(self.render_permission(
+                                "encrypt-allow-modify",
+                                i18n.encrypt_allow_modify,
+                                permissions.modify,
+                                |permissions, allowed| permissions.modify = allowed,
+                                cx,
+                            ))
+                            .child(self.render_permission(
+                                "encrypt-allow-annotate",
+                                i18n.encrypt_allow_annotate,
+                                permissions.annotate,
+                                |permissions, allowed| permissions.annotate = allowed,
+                                cx,
+                            )),
+                        cx,
+                    ))
+                    .child(div().flex_1())
+                    .when_some(self.status_message.clone(), |this, message| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .whitespace_normal()
+                                .child(message),
+                        )
+                    })
+                    .child(
+                        div()
+                            .w_full()
+                            .flex()
+                            .items_center()
+                            .justify_end()
+                            .gap_2()
+                            .child(
+                                Button::new("encrypt-save")
+                                    .small()
+                                    .primary()
+                                    .label(i18n.encrypt_save_button)
+                                    .disabled(self.saving)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.start_save(cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("encrypt-close")
+                                    .small()
+                                    .ghost()
+                                    .label(i18n.close_button)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.close_dialog(window, cx);
+                                    })),
+                            ),
+                    ),
+            )
+    }
+}
+
+impl PdfViewer {
+    pub(super) fn open_encrypt_dialog(&mut self, cx: &mut Context<Self>) {
+        if self.command_panel_open {
+            self.close_command_panel(cx);
+        }
+
+        if self.encrypt_dialog_open {
+            if let Some(handle) = self.encrypt_dialog_window.as_ref() {
+                let _ = handle.update(cx, |_, window, _| {
+                    window.activate_window();
+                });
+            }
+            return;
+        }
+        let Some(input) = self.active_tab_path().cloned() else {
+            return;
+        };
+
+        self.encrypt_dialog_open = true;
+        self.needs_root_refocus = false;
+        self.encrypt_dialog_session = self.encrypt_dialog_session.wrapping_add(1);
+        let session_id = self.encrypt_dialog_session;
+
+        let language = self.language;
+        let viewer = cx.entity();
+        let viewer_for_close = viewer.clone();
+        let window_options = WindowOptions {
+            titlebar: Some(Self::dialog_titlebar_options()),
+            window_bounds: Some(WindowBounds::centered(
+                size(px(ENCRYPT_DIALOG_WIDTH), px(ENCRYPT_DIALOG_WINDOW_HEIGHT)),
+                cx,
+            )),
+            window_decorations: Some(WindowDecorations::Client),
+            ..WindowOptions::default()
+        };
+
+        match cx.open_window(window_options, move |window, cx| {
+            window.on_window_should_close(cx, move |_, cx| {
+                let _ = viewer_for_close.update(cx, |this, cx| {
+                    this.on_encrypt_dialog_window_closed(session_id, cx);
+                });
+                true
+            });
+            let dialog = cx.new(|cx| EncryptWindow::new(viewer, language, input, window, cx));
+            let dialog_focus = dialog.read(cx).focus_handle.clone();
+            let root = cx.new(|cx| Root::new(dialog, window, cx));
+            window.focus(&dialog_focus);
+            root
+        }) {
+            Ok(handle) => {
+                self.encrypt_dialog_window = Some(handle.into());
+                cx.notify();
+            }
+            Err(err) => {
+                crate::debug_log!(@warn, "[encrypt] failed to open encrypt window: {}", err);
+                self.on_encrypt_dialog_window_closed(session_id, cx);
+            }
+        }
+    }
+
+    pub(super) fn close_encrypt_dialog(&mut self, cx: &mut Context<Self>) {
+        let window_handle = self.encrypt_dialog_window.take();
+        if self.encrypt_dialog_open || window_handle.is_some() {
+            self.encrypt_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+        // Defer window removal to avoid borrow conflicts during event handling
+        if let Some(window_handle) = window_handle {
+            cx.defer(move |cx| {
+                let _ = window_handle.update(cx, |_, window, _| {
+                    window.remove_window();
+                });
+            });
+        }
+    }
+
+    fn on_encrypt_dialog_window_closed(&mut self, session_id: u64, cx: &mut Context<Self>) {
+        if self.encrypt_dialog_session == session_id {
+            self.encrypt_dialog_window = None;
+            self.encrypt_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use super::{ExternalTool, PdfViewer};
+use anyhow::{Context as _, Result, bail};
+use gpui::*;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Splits a command template into arguments. Double quotes group words containing spaces;
+/// there are no escapes, so Windows paths with backslashes can be written as-is.
+fn split_command_template(template: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+    for ch in template.chars() {
+        match ch {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+            }
+            ch if ch.is_whitespace() && !in_quotes => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            ch => {
+                current.push(ch);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// Starts `tool` for `file` without waiting for it; `page_index` fills `{page}` (1-based).
+fn launch_external_tool(tool: &ExternalTool, file: &Path, page_index: usize) -> Result<()> {
+    let file = file.to_string_lossy();
+    let page = (page_index + 1).to_string();
+    let args = split_command_template(&tool.command)
+        .into_iter()
+        .map(|arg| arg.replace("{file}", &file).replace("{page}", &page))
+        .collect::<Vec<_>>();
+    let Some((program, args)) = args.split_first() else {
+        bail!("tool \"{}\" has an empty command", tool.name);
+    };
+
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("start {program}"))?;
+    // Reap the tool when it exits so it does not linger as a zombie until kPDF quits.
+    std::thread::Builder::new()
+        .name("kpdf-external-tool".to_string())
+        .spawn(move || {
+            let _ = child.wait();
+        })
+        .context("watch external tool")?;
+    Ok(())
+}
+
+impl PdfViewer {
+    pub(super) fn add_external_tool(
+        &mut self,
+        name: String,
+        command: String,
+        cx: &mut Context<Self>,
+    ) {
+        let (name, command) = (name.trim().to_string(), command.trim().to_string());
+        if name.is_empty() || command.is_empty() {
+            return;
+        }
+        self.external_tools.push(ExternalTool { name, command });
+        self.persist_external_tools();
+        cx.notify();
+    }
+
+    pub(super) fn remove_external_tool(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index >= self.external_tools.len() {
+            return;
+        }
+        self.external_tools.remove(index);
+        self.persist_external_tools();
+        cx.notify();
+    }
+
+    pub(super) fn open_tab_with_external_tool(&self, tab_id: usize, index: usize) {
+        let Some(tool) = self.external_tools.get(index) else {
+            return;
+        };
+        let Some(tab) = self.tab_bar.tabs().iter().find(|tab| tab.id == tab_id) else {
+            return;
+        };
+        let Some(path) = tab.path.as_ref() else {
+            return;
+        };
+        if let Err(err) = launch_external_tool(tool, path, tab.active_page) {
+            crate::debug_log!(@warn, "[external-tool] {} failed: {:#}", tool.name, err);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_command_template_handles_quotes() {
+        assert_eq!(
+            split_command_template(
+                r#""C:\Program Files\Acrobat\Acrobat.exe" /A "page={page}" {file}"#
+            ),
+            vec![
+                r"C:\Program Files\Acrobat\Acrobat.exe",
+                "/A",
+                "page={page}",
+                "{file}"
+            ]
+        );
+        assert_eq!(
+            split_command_template("ocr.sh  \"\" {file}"),
+            vec!["ocr.sh", "", "{file}"]
+        );
+        assert!(split_command_template("   ").is_empty());
+    }
+}
@@ -37,10 +37,61 @@ impl PdfViewer {
         .detach();
     }
 
+    /// Opens files passed on the command line, e.g. by Explorer's "Open with kPDF" verb.
+    /// A file that is already open (say, from the restored session) is switched to.
+    pub fn open_launch_paths(&mut self, paths: Vec<PathBuf>, cx: &mut Context<Self>) {
+        for path in paths {
+            let already_open = self
+                .tab_bar
+                .tabs()
+                .iter()
+                .find(|tab| tab.path.as_ref() == Some(&path))
+                .map(|tab| tab.id);
+            if let Some(tab_id) = already_open {
+                self.switch_to_tab(tab_id, cx);
+            } else if self.active_tab().is_some_and(|tab| tab.path.is_none()) {
+                self.open_pdf_path_in_current_tab(path, cx);
+            } else {
+                self.open_pdf_path_in_new_tab(path, cx);
+            }
+        }
+    }
+
+    /// Opens the files of later kPDF launches, which hand them to this instance, and raises
+    /// the window for each launch.
+    pub fn watch_forwarded_launches(
+        &mut self,
+        launches: crate::single_instance::ForwardedLaunches,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.spawn_in(window, async move |view, cx| {
+            loop {
+                cx.background_executor()
+                    .timer(Duration::from_millis(FORWARDED_LAUNCH_POLL_MS))
+                    .await;
+                let Some(paths) = launches.take() else {
+                    if view.upgrade().is_none() {
+                        return;
+                    }
+                    continue;
+                };
+                let opened = view.update_in(cx, |this, window, cx| {
+                    window.activate_window();
+                    this.open_launch_paths(paths, cx);
+                });
+                if opened.is_err() {
+                    return;
+                }
+            }
+        })
+        .detach();
+    }
+
     fn open_recent_pdf(&mut self, path: PathBuf, cx: &mut Context<Self>) {
         if !path.exists() {
             self.recent_files.retain(|p| p != &path);
-            self.persist_recent_files();
+            self.persist_recent_files(cx);
             cx.notify();
             return;
         }
@@ -110,6 +161,7 @@ impl PdfViewer {
             }
             Ok(exit_status) => {
                 crate::debug_log!(
+                    @warn,
                     "[tab] failed to reveal in file manager: {} | exit={}",
                     path.display(),
                     exit_status
@@ -117,6 +169,7 @@ impl PdfViewer {
             }
             Err(err) => {
                 crate::debug_log!(
+                    @warn,
                     "[tab] failed to reveal in file manager: {} | {}",
                     path.display(),
                     err
@@ -140,6 +193,42 @@ impl PdfViewer {
         self.reveal_path_in_file_manager(path);
     }
 
+    /// Offers the report a crash left behind; that session's tabs were restored as usual.
+    fn offer_pending_crash_report(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(report) = crate::crash_report::take_pending_report() else {
+            return;
+        };
+        crate::debug_log!(@info, "[crash] offering report {}", report.display());
+
+        let i18n = self.i18n();
+        let answer = window.prompt(
+            PromptLevel::Warning,
+            i18n.crash_report_prompt_title,
+            Some(i18n.crash_report_prompt_detail),
+            &[
+                i18n.crash_report_show_button,
+                i18n.crash_report_submit_button,
+                i18n.crash_report_dismiss_button,
+            ],
+            cx,
+        );
+        cx.spawn(async move |this, cx| {
+            let Ok(answer) = answer.await else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| match answer {
+                0 => this.reveal_path_in_file_manager(&report),
+                1 => {
+                    if let Some(url) = crate::crash_report::issue_url(&report) {
+                        cx.open_url(&url);
+                    }
+                }
+                _ => {}
+            });
+        })
+        .detach();
+    }
+
     fn open_logs_directory(&self) {
         let Some(log_file_path) = crate::logger::log_file_path() else {
             crate::debug_log!("[log] cannot open logs directory: unresolved log path");
@@ -176,6 +265,7 @@ impl PdfViewer {
             }
             Ok(exit_status) => {
                 crate::debug_log!(
+                    @warn,
                     "[log] failed to open logs directory: {} | exit={}",
                     log_dir.display(),
                     exit_status
@@ -183,6 +273,7 @@ impl PdfViewer {
             }
             Err(err) => {
                 crate::debug_log!(
+                    @warn,
                     "[log] failed to open logs directory: {} | {}",
                     log_dir.display(),
                     err
@@ -0,0 +1,86 @@
+//! Comic book archives. The images in a `.cbz` (or a `.cbr` that is really a ZIP) become
+//! the pages, in natural file-name order so `page2` comes before `page10`.
+
+use super::IMAGE_PAGE_DPI;
+use crate::i18n::{I18n, Language};
+use anyhow::{Context as _, Result, bail};
+use kpdf_core::archive::{self, ZipEntry};
+use kpdf_core::image_pdf::{ImagePage, ImagePdfWriter};
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+const COMIC_PAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "bmp"];
+
+/// Writes one page per image to `output` and returns the page count.
+pub(super) fn convert_to_pdf(source: &Path, output: &Path, language: Language) -> Result<usize> {
+    let i18n = I18n::new(language);
+    let bytes = std::fs::read(source)?;
+    if !archive::is_zip(&bytes) {
+        bail!(i18n.comic_rar_unsupported);
+    }
+    let mut entries = archive::zip_entries(&bytes)?
+        .into_iter()
+        .filter(is_comic_page)
+        .collect::<Vec<_>>();
+    entries.sort_by(|a, b| natural_cmp(&a.name, &b.name));
+
+    let mut writer = ImagePdfWriter::new(BufWriter::new(File::create(output)?), IMAGE_PAGE_DPI)?;
+    for entry in &entries {
+        let page = archive::read_zip_entry(&bytes, entry)
+            .and_then(|data| ImagePage::from_encoded(&data))
+            .with_context(|| entry.name.clone());
+        match page {
+            Ok(page) => writer.add_page(&page)?,
+            Err(err) => crate::debug_log!(@warn, "[formats][comic] skipped page: {:#}", err),
+        }
+    }
+    let page_count = writer.page_count();
+    if page_count == 0 {
+        bail!(i18n.comic_no_pages);
+    }
+    writer.finish()?;
+    Ok(page_count)
+}
+
+fn is_comic_page(entry: &ZipEntry) -> bool {
+    if entry.is_dir() || entry.name.starts_with("__MACOSX/") {
+        return false;
+    }
+    let file_name = entry.name.rsplit('/').next().unwrap_or_default();
+    !file_name.starts_with('.')
+        && file_name.rsplit_once('.').is_some_and(|(_, extension)| {
+            COMIC_PAGE_EXTENSIONS.contains(&extension.to_ascii_lowercase().as_str())
+        })
+}
+
+/// Compares names case-insensitively, with runs of digits compared by value.
+fn natural_cmp(mut a: &str, mut b: &str) -> Ordering {
+    loop {
+        let (Some(a_first), Some(b_first)) = (a.chars().next(), b.chars().next()) else {
+            return a.len().cmp(&b.len());
+        };
+        let ordering = if a_first.is_ascii_digit() && b_first.is_ascii_digit() {
+            let a_digits = a.len() - a.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            let b_digits = b.len() - b.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+            let a_number = a[..a_digits].trim_start_matches('0');
+            let b_number = b[..b_digits].trim_start_matches('0');
+            let ordering = a_number
+                .len()
+                .cmp(&b_number.len())
+                .then_with(|| a_number.cmp(b_number));
+            a = &a[a_digits..];
+            b = &b[b_digits..];
+            ordering
+        } else {
+            let ordering = a_first.to_lowercase().cmp(b_first.to_lowercase());
+            a = &a[a_first.len_utf8()..];
+            b = &b[b_first.len_utf8()..];
+            ordering
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
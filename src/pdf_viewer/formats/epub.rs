@@ -0,0 +1,34 @@
+//! EPUB books. MuPDF lays the chapters out at a fixed book page size and the pages are
+//! written to a PDF; the viewer's continuous scroll, zoom and reading modes then apply
+//! as for any other document. Only builds with the `mupdf` feature can open them.
+
+use crate::i18n::Language;
+use anyhow::Result;
+use std::path::Path;
+
+/// Page box chapters are laid out at, in points (A5).
+const EPUB_PAGE_WIDTH: f32 = 420.0;
+const EPUB_PAGE_HEIGHT: f32 = 595.0;
+/// Base font size for the book's CSS `em`, in points.
+const EPUB_FONT_SIZE: f32 = 11.0;
+
+/// Writes the laid-out book to `output` and returns its page count.
+pub(super) fn convert_to_pdf(source: &Path, output: &Path, _language: Language) -> Result<usize> {
+    use mupdf::{Document, DocumentWriter, Matrix};
+
+    let mut document = Document::open(&source.to_string_lossy())?;
+    document.layout(EPUB_PAGE_WIDTH, EPUB_PAGE_HEIGHT, EPUB_FONT_SIZE)?;
+    let page_count = document.page_count()?;
+
+    let mut writer = DocumentWriter::new(&output.to_string_lossy(), "pdf", "")?;
+    for page_index in 0..page_count {
+        let page = document.load_page(page_index)?;
+        let device = writer.begin_page(page.bounds()?)?;
+        page.run(&device, &Matrix::IDENTITY)?;
+        writer.end_page(device)?;
+    }
+    // Dropping the writer closes it, which writes the xref and trailer.
+    drop(writer);
+
+    Ok(usize::try_from(page_count)?)
+}
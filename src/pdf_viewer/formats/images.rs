@@ -0,0 +1,46 @@
+//! Standalone images. A TIFF gets one page per image in the file, which suits scans that
+//! have not been turned into a PDF yet; any other image is a single page.
+
+use super::IMAGE_PAGE_DPI;
+use anyhow::{Result, anyhow};
+use kpdf_core::image_pdf::{ImagePage, ImagePdfWriter, TiffPages};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+const TIFF_SIGNATURES: &[&[u8]] = &[b"II*\0", b"MM\0*", b"II+\0", b"MM\0+"];
+
+/// Writes the image's pages to `output` and returns the page count.
+pub(super) fn convert_to_pdf(source: &Path, output: &Path) -> Result<usize> {
+    let bytes = std::fs::read(source)?;
+    let mut writer = ImagePdfWriter::new(BufWriter::new(File::create(output)?), IMAGE_PAGE_DPI)?;
+    if TIFF_SIGNATURES
+        .iter()
+        .any(|signature| bytes.starts_with(signature))
+    {
+        let mut first_error = None;
+        for (index, page) in TiffPages::new(&bytes)?.enumerate() {
+            match page {
+                Ok(page) => writer.add_page(&page)?,
+                Err(err) => {
+                    crate::debug_log!(
+                        @warn,
+                        "[formats][image] skipped TIFF page {}: {:#}",
+                        index + 1,
+                        err
+                    );
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+        if writer.page_count() == 0 {
+            return Err(first_error.unwrap_or_else(|| anyhow!("TIFF has no pages")));
+        }
+    } else {
+        writer.add_page(&ImagePage::from_encoded(&bytes)?)?;
+    }
+
+    let page_count = writer.page_count();
+    writer.finish()?;
+    Ok(page_count)
+}
@@ -0,0 +1,163 @@
+//! Documents other than PDF. Each one is converted once into a PDF under the temp
+//! directory and every Pdfium (or MuPDF) access goes through that copy, so tabs,
+//! thumbnails, search, zoom and the caches treat it like any other PDF.
+
+mod comic;
+#[cfg(feature = "mupdf")]
+mod epub;
+mod images;
+
+use crate::i18n::{I18n, Language};
+use anyhow::{Context as _, Result};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime};
+
+/// Resolution image pages are shown at when the page is viewed at 100%.
+const IMAGE_PAGE_DPI: f32 = 150.0;
+
+const CONVERTED_DIR_NAME: &str = "kpdf-converted";
+const REPAIRED_DIR_NAME: &str = "kpdf-repaired";
+/// Copies older than this are deleted at startup; one that is needed again is recreated.
+const STALE_COPY_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+static CONVERSION_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SourceFormat {
+    #[cfg(feature = "mupdf")]
+    Epub,
+    Comic,
+    Image,
+}
+
+impl SourceFormat {
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            #[cfg(feature = "mupdf")]
+            "epub" => Some(Self::Epub),
+            "cbz" | "cbr" => Some(Self::Comic),
+            "png" | "jpg" | "jpeg" | "gif" | "webp" | "bmp" | "tif" | "tiff" => Some(Self::Image),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `path` names a PDF or a format that can be converted to one.
+pub(super) fn is_supported_document(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"))
+        || SourceFormat::from_path(path).is_some()
+}
+
+/// Whether `path` is a comic book archive, whose pages may be read right to left.
+pub(super) fn is_comic_archive(path: &Path) -> bool {
+    SourceFormat::from_path(path) == Some(SourceFormat::Comic)
+}
+
+/// The PDF to open for `path`: the file itself, the repaired copy of a damaged PDF, or the
+/// converted copy of a supported non-PDF document, converting it on first use.
+pub(super) fn pdf_source(path: &Path, language: Language) -> Result<PathBuf> {
+    let Some(format) = SourceFormat::from_path(path) else {
+        return Ok(repaired_copy(path).unwrap_or_else(|| path.to_path_buf()));
+    };
+    let output = derived_path(path, CONVERTED_DIR_NAME)?;
+    if output.exists() {
+        return Ok(output);
+    }
+    if let Some(directory) = output.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+
+    // Convert to a private file first: renders on other threads may ask for the same
+    // document and must never see a half-written PDF.
+    let partial = output.with_extension(format!(
+        "{}-{}.partial",
+        std::process::id(),
+        CONVERSION_SEQUENCE.fetch_add(1, Ordering::Relaxed)
+    ));
+    let started = std::time::Instant::now();
+    let converted = match format {
+        #[cfg(feature = "mupdf")]
+        SourceFormat::Epub => epub::convert_to_pdf(path, &partial, language),
+        SourceFormat::Comic => comic::convert_to_pdf(path, &partial, language),
+        SourceFormat::Image => images::convert_to_pdf(path, &partial),
+    };
+    let page_count = match converted {
+        Ok(page_count) => page_count,
+        Err(err) => {
+            let _ = std::fs::remove_file(&partial);
+            return Err(err).with_context(|| I18n::new(language).pdfium_cannot_open_file(path));
+        }
+    };
+    std::fs::rename(&partial, &output)?;
+
+    crate::debug_log!(
+        "[formats] converted {:?} {} ({} pages) in {} ms",
+        format,
+        path.display(),
+        page_count,
+        started.elapsed().as_millis()
+    );
+    Ok(output)
+}
+
+/// The repaired copy written for a damaged PDF at `path`, while the file is unchanged.
+/// Reads go there instead, so the tab stays keyed on the original path.
+pub(super) fn repaired_copy(path: &Path) -> Option<PathBuf> {
+    let repaired = repaired_path(path).ok()?;
+    repaired.is_file().then_some(repaired)
+}
+
+/// Where the repaired copy of `path` is written.
+pub(super) fn repaired_path(path: &Path) -> Result<PathBuf> {
+    derived_path(path, REPAIRED_DIR_NAME)
+}
+
+/// Where a copy derived from `path` lives under `directory` in the temp directory. The name
+/// hashes the full path, size and modification time, so same-named files never collide and
+/// a changed file gets a new copy.
+fn derived_path(path: &Path, directory: &str) -> Result<PathBuf> {
+    let canonical_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    let metadata = std::fs::metadata(&canonical_path)?;
+    let mut hasher = DefaultHasher::new();
+    canonical_path.hash(&mut hasher);
+    metadata.len().hash(&mut hasher);
+    metadata.modified().ok().hash(&mut hasher);
+
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    Ok(std::env::temp_dir()
+        .join(directory)
+        .join(format!("{stem}-{:016x}.pdf", hasher.finish())))
+}
+
+/// Deletes converted and repaired copies, and partial files of interrupted conversions,
+/// written more than [`STALE_COPY_AGE`] ago.
+pub(super) fn prune_stale_copies() {
+    let Some(cutoff) = SystemTime::now().checked_sub(STALE_COPY_AGE) else {
+        return;
+    };
+    let mut removed = 0;
+    for directory in [CONVERTED_DIR_NAME, REPAIRED_DIR_NAME] {
+        let Ok(entries) = std::fs::read_dir(std::env::temp_dir().join(directory)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let is_stale = entry
+                .metadata()
+                .and_then(|metadata| metadata.modified())
+                .is_ok_and(|modified| modified < cutoff);
+            if is_stale && std::fs::remove_file(entry.path()).is_ok() {
+                removed += 1;
+            }
+        }
+    }
+    if removed > 0 {
+        crate::debug_log!("[formats] removed {} stale document copies", removed);
+    }
+}
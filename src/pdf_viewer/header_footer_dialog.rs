@@ -0,0 +1,634 @@
+use super::pdf_edit::{
+    HeaderFooter, HeaderFooterAlignment, parse_page_ranges, render_header_footer_preview,
+    stamp_header_footer,
+};
+use super::{
+    HEADER_FOOTER_DEFAULT_FONT_SIZE_INDEX, HEADER_FOOTER_DEFAULT_FOOTER,
+    HEADER_FOOTER_DIALOG_WIDTH, HEADER_FOOTER_DIALOG_WINDOW_HEIGHT,
+    HEADER_FOOTER_FONT_SIZE_OPTIONS, HEADER_FOOTER_PREVIEW_DEBOUNCE_MS,
+    HEADER_FOOTER_PREVIEW_HEIGHT, HEADER_FOOTER_PREVIEW_RENDER_WIDTH,
+    HEADER_FOOTER_PROGRESS_POLL_MS,
+};
+use crate::i18n::{I18n, Language};
+use crate::pdf_viewer::PdfViewer;
+use crate::pdf_viewer::utils::{display_file_name, is_same_file};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::input::{Input, InputEvent, InputState};
+use gpui_component::scroll::ScrollableElement;
+use gpui_component::{button::*, *};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+enum HeaderFooterPreview {
+    Loading,
+    Ready(Arc<RenderImage>),
+    Failed,
+}
+
+pub(super) struct HeaderFooterWindow {
+    viewer: Entity<PdfViewer>,
+    focus_handle: FocusHandle,
+    language: Language,
+    input: PathBuf,
+    page_count: usize,
+    header_input: Entity<InputState>,
+    footer_input: Entity<InputState>,
+    pages_input: Entity<InputState>,
+    alignment: HeaderFooterAlignment,
+    font_size_index: usize,
+    preview: HeaderFooterPreview,
+    /// Bumped on every form change so only the latest preview render lands.
+    preview_epoch: u64,
+    /// (pages stamped, pages selected) while saving; `None` when idle.
+    progress: Option<(usize, usize)>,
+    status_message: Option<String>,
+    saved_output: Option<PathBuf>,
+    _input_subscriptions: Vec<Subscription>,
+}
+
+impl HeaderFooterWindow {
+    pub(super) fn new(
+        viewer: Entity<PdfViewer>,
+        language: Language,
+        input: PathBuf,
+        page_count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let i18n = I18n::new(language);
+        let header_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder(i18n.header_footer_header_label));
+        let footer_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(i18n.header_footer_footer_label)
+                .default_value(HEADER_FOOTER_DEFAULT_FOOTER)
+        });
+        let pages_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n.header_footer_pages_placeholder)
+        });
+        let input_subscriptions = [&header_input, &footer_input, &pages_input]
+            .into_iter()
+            .map(|input| {
+                cx.subscribe(input, |this, _, event: &InputEvent, cx| {
+                    if matches!(event, InputEvent::Change) {
+                        this.schedule_preview(cx);
+                    }
+                })
+            })
+            .collect();
+
+        let mut this = Self {
+            viewer,
+            focus_handle: cx.focus_handle(),
+            language,
+            input,
+            page_count,
+            header_input,
+            footer_input,
+            pages_input,
+            alignment: HeaderFooterAlignment::default(),
+            font_size_index: HEADER_FOOTER_DEFAULT_FONT_SIZE_INDEX,
+            preview: HeaderFooterPreview::Loading,
+            preview_epoch: 0,
+            progress: None,
+            status_message: None,
+            saved_output: None,
+            _input_subscriptions: input_subscriptions,
+        };
+        this.schedule_preview(cx);
+        this
+    }
+
+    fn i18n(&self) -> I18n {
+        I18n::new(self.language)
+    }
+
+    /// The header/footer described by the form, or a message explaining what is wrong.
+    fn header_footer(&self, cx: &App) -> Result<HeaderFooter, String> {
+        let i18n = self.i18n();
+        let header = self.header_input.read(cx).value().trim().to_string();
+        let footer = self.footer_input.read(cx).value().trim().to_string();
+        if header.is_empty() && footer.is_empty() {
+            return Err(i18n.header_footer_missing_content.to_string());
+        }
+        let pages = parse_page_ranges(&self.pages_input.read(cx).value(), self.page_count)
+            .map_err(|part| i18n.header_footer_invalid_pages(&part))?;
+
+        Ok(HeaderFooter {
+            header,
+            footer,
+            alignment: self.alignment,
+            font_size: HEADER_FOOTER_FONT_SIZE_OPTIONS[self.font_size_index],
+            pages,
+        })
+    }
+
+    /// Re-renders the preview once the form has been quiet for a moment.
+    fn schedule_preview(&mut self, cx: &mut Context<Self>) {
+        self.preview_epoch = self.preview_epoch.wrapping_add(1);
+        let epoch = self.preview_epoch;
+        let input = self.input.clone();
+        let language = self.language;
+        // An empty or invalid form still previews the bare page.
+        let header_footer = self.header_footer(cx).unwrap_or_else(|_| HeaderFooter {
+            header: String::new(),
+            footer: String::new(),
+            alignment: self.alignment,
+            font_size: HEADER_FOOTER_FONT_SIZE_OPTIONS[self.font_size_index],
+            pages: Vec::new(),
+        });
+
+        cx.spawn(async move |this, cx| {
+            cx.background_executor()
+                .timer(Duration::from_millis(HEADER_FOOTER_PREVIEW_DEBOUNCE_MS))
+                .await;
+            let current = this
+                .update(cx, |this, _| this.preview_epoch == epoch)
+                .unwrap_or(false);
+            if !current {
+                return;
+            }
+
+            let preview = cx
+                .background_executor()
+                .spawn(async move {
+                    render_header_footer_preview(
+                        &input,
+                        &header_footer,
+                        HEADER_FOOTER_PREVIEW_RENDER_WIDTH,
+                        language,
+                    )
+                })
+                .await;
+            let _ = this.update(cx, |this, cx| {
+                if this.preview_epoch != epoch {
+                    return;
+                }
+                this.preview = match preview {
+                    Ok(image) => HeaderFooterPreview::Ready(image),
+                    Err(err) => {
+                        crate::debug_log!(@warn, "[pdf][edit] header/footer preview failed: {:#}", err);
+                        HeaderFooterPreview::Failed
+                    }
+                };
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn start_save(&mut self, cx: &mut Context<Self>) {
+        if self.progress.is_some() {
+            return;
+        }
+        let header_footer = match self.header_footer(cx) {
+            Ok(header_footer) => header_footer,
+            Err(message) => {
+                self.status_message = Some(message);
+                cx.notify();
+                return;
+            }
+        };
+        let directory = self
+            .input
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let suggested_name = self
+            .input
+            .file_stem()
+            .map(|stem| format!("{}-numbered.pdf", stem.to_string_lossy()))
+            .unwrap_or_else(|| "numbered.pdf".to_string());
+        let picker = cx.prompt_for_new_path(&directory, Some(&suggested_name));
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(output))) = picker.await else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                this.run_stamp(header_footer, output, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn run_stamp(&mut self, header_footer: HeaderFooter, output: PathBuf, cx: &mut Context<Self>) {
+        if self.progress.is_some() {
+            return;
+        }
+        if is_same_file(&self.input, &output) {
+            self.status_message = Some(self.i18n().save_copy_same_file.to_string());
+            cx.notify();
+            return;
+        }
+        let input = self.input.clone();
+        let language = self.language;
+        self.progress = Some((0, header_footer.pages.len()));
+        self.status_message = None;
+        self.saved_output = None;
+        cx.notify();
+
+        let progress = Arc::new(Mutex::new((0, header_footer.pages.len())));
+        let progress_for_task = progress.clone();
+        let finished = Arc::new(AtomicBool::new(false));
+        let finished_for_task = finished.clone();
+        let output_for_task = output.clone();
+        let stamp = cx.background_executor().spawn(async move {
+            let result = stamp_header_footer(
+                &input,
+                &output_for_task,
+                &header_footer,
+                language,
+                |done, total| {
+                    if let Ok(mut progress) = progress_for_task.lock() {
+                        *progress = (done, total);
+                    }
+                },
+            );
+            finished_for_task.store(true, Ordering::Release);
+            result
+        });
+
+        cx.spawn(async move |this, cx| {
+            while !finished.load(Ordering::Acquire) {
+                cx.background_executor()
+                    .timer(Duration::from_millis(HEADER_FOOTER_PROGRESS_POLL_MS))
+                    .await;
+                let snapshot = progress.lock().map(|progress| *progress).ok();
+                let alive = this
+                    .update(cx, |this, cx| {
+                        if this.progress.is_some() {
+                            this.progress = snapshot.or(this.progress);
+                            cx.notify();
+                        }
+                    })
+                    .is_ok();
+                if !alive {
+                    return;
+                }
+            }
+        })
+        .detach();
+
+        cx.spawn(async move |this, cx| {
+            let result = stamp.await;
+            let _ = this.update(cx, |this, cx| {
+                let i18n = this.i18n();
+                this.progress = None;
+                this.status_message = Some(match result {
+                    Ok(count) => {
+                        this.saved_output = Some(output.clone());
+                        i18n.header_footer_done(count, &output)
+                    }
+                    Err(err) => {
+                        crate::debug_log!(@warn, "[pdf][edit] header/footer failed: {:#}", err);
+                        i18n.header_footer_failed(&format!("{err:#}"))
+                    }
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn open_saved_output(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(output) = self.saved_output.clone() else {
+            return;
+        };
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.open_pdf_path_in_new_tab(output, cx);
+        });
+        self.close_dialog(window, cx);
+    }
+
+    fn close_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.close_header_footer_dialog(cx);
+        });
+        window.remove_window();
+    }
+
+    fn alignment_label(i18n: I18n, alignment: HeaderFooterAlignment) -> &'static str {
+        match alignment {
+            HeaderFooterAlignment::Left => i18n.header_footer_align_left,
+            HeaderFooterAlignment::Center => i18n.header_footer_align_center,
+            HeaderFooterAlignment::Right => i18n.header_footer_align_right,
+        }
+    }
+
+    fn render_preview(&self, cx: &App) -> impl IntoElement {
+        let i18n = self.i18n();
+        let placeholder = |label: &'static str| {
+            div()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(label)
+        };
+
+        div()
+            .w_full()
+            .h(px(HEADER_FOOTER_PREVIEW_HEIGHT))
+            .flex()
+            .items_center()
+            .justify_center()
+            .p_2()
+            .rounded_md()
+            .border_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().secondary)
+            .child(match &self.preview {
+                HeaderFooterPreview::Ready(image) => img(image.clone())
+                    .size_full()
+                    .object_fit(ObjectFit::Contain)
+                    .into_any_element(),
+                HeaderFooterPreview::Loading => {
+                    placeholder(i18n.header_footer_preview_loading).into_any_element()
+                }
+                HeaderFooterPreview::Failed => {
+                    placeholder(i18n.header_footer_preview_failed).into_any_element()
+                }
+            })
+    }
+}
+
+impl Render for HeaderFooterWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let i18n = self.i18n();
+        let saving = self.progress.is_some();
+
+        div()
+            .id("header-footer-window")
+            .size_full()
+            .v_flex()
+            .bg(cx.theme().background)
+            .focusable()
+            .track_focus(&self.focus_handle)
+            .capture_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key.as_str() == "escape" {
+                    this.close_dialog(window, cx);
+                    cx.stop_propagation();
+                }
+            }))
+            .child(TitleBar::new())
+            .child(
+                div()
+                    .flex_1()
+                    .min_h(px(0.))
+                    .v_flex()
+                    .gap_3()
+                    .p_4()
+                    .child(
+                        div()
+                            .text_lg()
+                            .text_color(cx.theme().foreground)
+                            .child(i18n.header_footer_dialog_title),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().foreground)
+                            .truncate()
+                            .child(display_file_name(&self.input)),
+                    )
+                    .child(div().h(px(1.)).bg(cx.theme().border))
+                    .child(
+                        div()
+                            .id("header-footer-content")
+                            .flex_1()
+                            .min_h(px(0.))
+                            .overflow_y_scrollbar()
+                            .child(
+                                div()
+                                    .v_flex()
+                                    .gap_3()
+                                    .child(PdfViewer::render_dialog_field(
+                                        i18n.header_footer_header_label,
+                                        Input::new(&self.header_input).small(),
+                                        cx,
+                                    ))
+                                    .child(PdfViewer::render_dialog_field(
+                                        i18n.header_footer_footer_label,
+                                        Input::new(&self.footer_input).small(),
+                                        cx,
+                                    ))
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .child(i18n.header_footer_tokens_hint),
+                                    )
+                                    .child(PdfViewer::render_dialog_field(
+                                        i18n.header_footer_alignment_label,
+                                        PdfViewer::render_choice_group(
+                                            "header-footer-alignment",
+                                            HeaderFooterAlignment::ALL
+                                                .into_iter()
+                                                .map(|alignment| {
+                                                    Self::alignment_label(i18n, alignment)
+                                                        .to_string()
+                                                })
+                                                .collect(),
+                                            HeaderFooterAlignment::ALL
+                                                .iter()
+                                                .position(|alignment| *alignment == self.alignment)
+                                                .unwrap_or(0),
+                                            saving,
+                                            |this, ix, cx| {
+                                                if let Some(alignment) =
+                                                    HeaderFooterAlignment::ALL.get(ix)
+                                                {
+                                                    this.alignment = *alignment;
+                                                }
+                                                this.schedule_preview(cx);
+                                            },
+                                            cx,
+                                        ),
+                                        cx,
+                                    ))
+                                    .child(PdfViewer::render_dialog_field(
+                                        i18n.header_footer_font_size_label,
+                                        PdfViewer::render_choice_group(
+                                            "header-footer-font-size",
+                                            HEADER_FOOTER_FONT_SIZE_OPTIONS
+                                                .into_iter()
+                                                .map(|size| format!("{size:.0} pt"))
+                                                .collect(),
+                                            self.font_size_index,
+                                            saving,
+                                            |this, ix, cx| {
+                                                this.font_size_index = ix
+                                                    .min(HEADER_FOOTER_FONT_SIZE_OPTIONS.len() - 1);
+                                                this.schedule_preview(cx);
+                                            },
+                                            cx,
+                                        ),
+                                        cx,
+                                    ))
+                                    .child(PdfViewer::render_dialog_field(
+                                        i18n.header_footer_pages_label,
+                                        Input::new(&self.pages_input).small(),
+                                        cx,
+                                    ))
+                                    .child(PdfViewer::render_dialog_field(
+                                        i18n.header_footer_preview_label,
+                                        self.render_preview(cx),
+                                        cx,
+                                    )),
+                            ),
+                    )
+                    .when_some(self.progress, |this, (done, total)| {
+                        let fraction = if total == 0 {
+                            0.0
+                        } else {
+                            done as f32 / total as f32
+                        };
+                        this.child(PdfViewer::render_progress_bar(
+                            fraction,
+                            i18n.header_footer_progress((done + 1).min(total), total),
+                            cx,
+                        ))
+                    })
+                    .when_some(self.status_message.clone(), |this, message| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .whitespace_normal()
+                                .child(message),
+                        )
+                    })
+                    .child(
+                        div()
+                            .w_full()
+                            .flex()
+                            .items_center()
+                            .justify_end()
+                            .gap_2()
+                            .when(self.saved_output.is_some(), |this| {
+                                this.child(
+                                    Button::new("header-footer-open-output")
+                                        .small()
+                                        .label(i18n.header_footer_open_button)
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.open_saved_output(window, cx);
+                                        })),
+                                )
+                            })
+                            .child(
+                                Button::new("header-footer-save")
+                                    .small()
+                                    .primary()
+                                    .label(i18n.header_footer_save_button)
+                                    .disabled(saving)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.start_save(cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("header-footer-close")
+                                    .small()
+                                    .ghost()
+                                    .label(i18n.close_button)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.close_dialog(window, cx);
+                                    })),
+                            ),
+                    ),
+            )
+    }
+}
+
+impl PdfViewer {
+    pub(super) fn open_header_footer_dialog(&mut self, cx: &mut Context<Self>) {
+        if self.command_panel_open {
+            self.close_command_panel(cx);
+        }
+
+        if self.header_footer_dialog_open {
+            if let Some(handle) = self.header_footer_dialog_window.as_ref() {
+                let _ = handle.update(cx, |_, window, _| {
+                    window.activate_window();
+                });
+            }
+            return;
+        }
+        let Some(input) = self.active_tab_path().cloned() else {
+            return;
+        };
+        let page_count = self.active_tab().map_or(0, |tab| tab.pages.len());
+
+        self.header_footer_dialog_open = true;
+        self.needs_root_refocus = false;
+        self.header_footer_dialog_session = self.header_footer_dialog_session.wrapping_add(1);
+        let session_id = self.header_footer_dialog_session;
+
+        let language = self.language;
+        let viewer = cx.entity();
+        let viewer_for_close = viewer.clone();
+        let window_options = WindowOptions {
+            titlebar: Some(Self::dialog_titlebar_options()),
+            window_bounds: Some(WindowBounds::centered(
+                size(
+                    px(HEADER_FOOTER_DIALOG_WIDTH),
+                    px(HEADER_FOOTER_DIALOG_WINDOW_HEIGHT),
+                ),
+                cx,
+            )),
+            window_decorations: Some(WindowDecorations::Client),
+            ..WindowOptions::default()
+        };
+
+        match cx.open_window(window_options, move |window, cx| {
+            window.on_window_should_close(cx, move |_, cx| {
+                let _ = viewer_for_close.update(cx, |this, cx| {
+                    this.on_header_footer_dialog_window_closed(session_id, cx);
+                });
+                true
+            });
+            let dialog = cx
+                .new(|cx| HeaderFooterWindow::new(viewer, language, input, page_count, window, cx));
+            let dialog_focus = dialog.read(cx).focus_handle.clone();
+            let root = cx.new(|cx| Root::new(dialog, window, cx));
+            window.focus(&dialog_focus);
+            root
+        }) {
+            Ok(handle) => {
+                self.header_footer_dialog_window = Some(handle.into());
+                cx.notify();
+            }
+            Err(err) => {
+                crate::debug_log!(@warn, "[header_footer] failed to open header/footer window: {}", err);
+                self.on_header_footer_dialog_window_closed(session_id, cx);
+            }
+        }
+    }
+
+    pub(super) fn close_header_footer_dialog(&mut self, cx: &mut Context<Self>) {
+        let window_handle = self.header_footer_dialog_window.take();
+        if self.header_footer_dialog_open || window_handle.is_some() {
+            self.header_footer_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+        // Defer window removal to avoid borrow conflicts during event handling
+        if let Some(window_handle) = window_handle {
+            cx.defer(move |cx| {
+                let _ = window_handle.update(cx, |_, window, _| {
+                    window.remove_window();
+                });
+            });
+        }
+    }
+
+    fn on_header_footer_dialog_window_closed(&mut self, session_id: u64, cx: &mut Context<Self>) {
+        if self.header_footer_dialog_session == session_id {
+            self.header_footer_dialog_window = None;
+            self.header_footer_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+    }
+}
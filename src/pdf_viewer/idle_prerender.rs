@@ -0,0 +1,203 @@
+use super::power;
+use super::utils::{PrerenderPass, PrerenderProgress, prerender_next_to_disk_cache};
+use super::{IDLE_PRERENDER_DELAY_MS, IDLE_PRERENDER_THROTTLE_MS, PdfViewer};
+use crate::i18n::Language;
+use gpui::*;
+use kpdf_core::render::{RenderEngine, RenderOptions};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// One document's worth of idle pre-rendering: the active tab's pages at its current
+/// render width, nearest to the reading position first.
+struct IdlePrerenderJob {
+    marker: IdlePrerenderMarker,
+    path: PathBuf,
+    pages: Vec<usize>,
+    language: Language,
+    disk_cache_limit_bytes: u64,
+}
+
+/// (tab id, render width, engine, options) of a document that needs no more pre-rendering:
+/// every page is cached, or the rest would not fit in the disk cache.
+pub(super) type IdlePrerenderMarker = (usize, u32, RenderEngine, RenderOptions);
+
+enum IdlePrerenderStep {
+    Wait(Duration),
+    Render(IdlePrerenderJob),
+    Stop,
+}
+
+impl PdfViewer {
+    /// Records input; the idle pre-render loop backs off until input stops again.
+    pub(super) fn note_user_activity(&mut self, cx: &mut Context<Self>) {
+        self.last_user_activity_at = Instant::now();
+        if self.idle_prerender_enabled && !self.idle_prerender_scheduled {
+            self.idle_prerender_scheduled = true;
+            self.schedule_idle_prerender(cx);
+        }
+    }
+
+    pub(super) fn set_idle_prerender_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        if self.idle_prerender_enabled == enabled {
+            return;
+        }
+        self.idle_prerender_enabled = enabled;
+        self.persist_render_cache_preferences();
+        self.note_user_activity(cx);
+        cx.notify();
+    }
+
+    /// Pages still worth pre-rendering in the active tab, or `None` when there is nothing
+    /// to do: the feature or disk cache is off, the document is still loading, or it is
+    /// already fully cached at this width.
+    fn idle_prerender_job(&self) -> Option<IdlePrerenderJob> {
+        if !self.idle_prerender_enabled || !self.render_cache_enabled {
+            return None;
+        }
+        let tab = self.active_tab()?;
+        let path = tab.path.clone()?;
+        let target_width = tab.display_render_target_width;
+        if tab.pages.is_empty() || tab.skeleton_only || target_width == 0 {
+            return None;
+        }
+        let marker = (
+            tab.id,
+            target_width,
+            self.render_engine,
+            self.render_options,
+        );
+        if self.idle_prerender_done == Some(marker) {
+            return None;
+        }
+
+        // Tiled pages are never rendered whole, so a full-page cache entry would go unused.
+        let pages = (tab.active_page..tab.pages.len())
+            .chain(0..tab.active_page.min(tab.pages.len()))
+            .filter(|ix| {
+                let page = &tab.pages[*ix];
+                !page.display_failed && !Self::display_uses_tiles(page, target_width)
+            })
+            .collect();
+        Some(IdlePrerenderJob {
+            marker,
+            path,
+            pages,
+            language: self.language,
+            disk_cache_limit_bytes: self.render_cache_limit_mb * 1024 * 1024,
+        })
+    }
+
+    fn next_idle_prerender_step(&mut self) -> IdlePrerenderStep {
+        let idle_delay = Duration::from_millis(IDLE_PRERENDER_DELAY_MS);
+        let idle_for = self.last_user_activity_at.elapsed();
+        if idle_for < idle_delay {
+            return IdlePrerenderStep::Wait(idle_delay - idle_for);
+        }
+        // Visible pages always win; pre-rendering resumes once they have landed.
+        if self
+            .active_tab()
+            .is_some_and(|tab| tab.display_inflight_tasks > 0 || tab.display_tile_inflight)
+        {
+            return IdlePrerenderStep::Wait(Duration::from_millis(IDLE_PRERENDER_THROTTLE_MS));
+        }
+        match self.idle_prerender_job() {
+            Some(job) => IdlePrerenderStep::Render(job),
+            None => {
+                self.idle_prerender_scheduled = false;
+                IdlePrerenderStep::Stop
+            }
+        }
+    }
+
+    /// Once input has been quiet for a while and the machine is on mains power, renders
+    /// the active document one page at a time into the disk cache, pausing between pages.
+    /// Any input puts the loop back to waiting; a page already rendering still finishes.
+    fn schedule_idle_prerender(&mut self, cx: &mut Context<Self>) {
+        cx.spawn(async move |view, cx| {
+            let mut delay = Duration::from_millis(IDLE_PRERENDER_DELAY_MS);
+            // The power state and the position in the page order only hold for one idle
+            // stretch; any wait starts both over.
+            let mut on_ac_power = None;
+            let mut progress: Option<(IdlePrerenderMarker, usize)> = None;
+            // Pages written for the current document stay protected from eviction across
+            // idle stretches, until the document or the cache limit changes.
+            let mut pass: Option<(IdlePrerenderMarker, PrerenderPass)> = None;
+            loop {
+                cx.background_executor().timer(delay).await;
+                let Ok(step) = view.update(cx, |this, _| this.next_idle_prerender_step()) else {
+                    return;
+                };
+                let job = match step {
+                    IdlePrerenderStep::Wait(wait) => {
+                        delay = wait;
+                        on_ac_power = None;
+                        progress = None;
+                        continue;
+                    }
+                    IdlePrerenderStep::Stop => return,
+                    IdlePrerenderStep::Render(job) => job,
+                };
+
+                if on_ac_power.is_none() {
+                    on_ac_power = Some(
+                        cx.background_executor()
+                            .spawn(async move { power::on_ac_power() })
+                            .await,
+                    );
+                }
+                if on_ac_power == Some(false) {
+                    crate::debug_log!("[pdf][prerender] on battery, skipped");
+                    let _ = view.update(cx, |this, _| this.idle_prerender_scheduled = false);
+                    return;
+                }
+
+                let start = progress
+                    .filter(|(marker, _)| *marker == job.marker)
+                    .map(|(_, position)| position)
+                    .unwrap_or(0)
+                    .min(job.pages.len());
+                let marker = job.marker;
+                let mut job_pass = pass
+                    .filter(|(pass_marker, pass)| {
+                        *pass_marker == marker && pass.limit_bytes == job.disk_cache_limit_bytes
+                    })
+                    .map(|(_, pass)| pass)
+                    .unwrap_or_else(|| PrerenderPass::new(job.disk_cache_limit_bytes));
+                let (rendered, job_pass) = cx
+                    .background_executor()
+                    .spawn(async move {
+                        let rendered = prerender_next_to_disk_cache(
+                            &job.path,
+                            &job.pages[start..],
+                            marker.1,
+                            job.language,
+                            marker.2,
+                            marker.3,
+                            &mut job_pass,
+                        );
+                        (rendered, job_pass)
+                    })
+                    .await;
+                pass = Some((marker, job_pass));
+
+                match rendered {
+                    PrerenderProgress::Rendered(offset) => {
+                        progress = Some((marker, start + offset + 1));
+                        delay = Duration::from_millis(IDLE_PRERENDER_THROTTLE_MS);
+                    }
+                    PrerenderProgress::Complete | PrerenderProgress::OverBudget => {
+                        if matches!(rendered, PrerenderProgress::Complete) {
+                            crate::debug_log!("[pdf][prerender] tab {} fully cached", marker.0);
+                        }
+                        let _ = view.update(cx, |this, _| {
+                            this.idle_prerender_done = Some(marker);
+                            this.idle_prerender_scheduled = false;
+                        });
+                        return;
+                    }
+                }
+            }
+        })
+        .detach();
+    }
+}
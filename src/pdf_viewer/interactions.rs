@@ -391,6 +391,7 @@ impl PdfViewer {
                 color: self.text_selection_markup_color,
                 selected_text,
                 rects: normalized_rects,
+                author: self.annotation_defaults.author(),
                 created_at_unix_secs: now,
                 updated_at_unix_secs: now,
             },
@@ -440,7 +441,7 @@ impl PdfViewer {
             if let Some(text) = manager.get_selected_text() {
                 if !text.is_empty() {
                     if let Err(err) = copy_to_clipboard(&text) {
-                        crate::debug_log!("[copy] failed to copy to clipboard: {}", err);
+                        crate::debug_log!(@warn, "[copy] failed to copy to clipboard: {}", err);
                     }
                 }
             }
@@ -542,6 +543,8 @@ impl PdfViewer {
         self.context_menu_note_anchor = None;
         self.context_menu_note_id = None;
         self.context_menu_text_markup_id = None;
+        self.context_menu_stamp_id = None;
+        self.context_menu_shape_id = None;
         cx.notify();
     }
 
@@ -569,6 +572,14 @@ impl PdfViewer {
     }
 
     pub(super) fn text_cursor_style_for_page(&self, page_index: usize) -> gpui::CursorStyle {
+        if self.fullscreen_chrome_hidden {
+            return gpui::CursorStyle::None;
+        }
+
+        if self.shape_tool.is_some() || self.eraser.is_some() {
+            return gpui::CursorStyle::Crosshair;
+        }
+
         if let Some(note_id) = self.hovered_markdown_note_id()
             && self
                 .markdown_note_by_id(note_id)
@@ -630,7 +641,7 @@ impl PdfViewer {
     }
 
     fn show_thumbnail_panel(&self) -> bool {
-        self.active_tab_path().is_some() && self.thumbnail_panel_visible
+        self.thumbnail_panel_visible && !self.zen_mode
     }
 
     pub(super) fn set_thumbnail_panel_visible(&mut self, visible: bool, cx: &mut Context<Self>) {
@@ -645,6 +656,195 @@ impl PdfViewer {
         self.set_thumbnail_panel_visible(!self.thumbnail_panel_visible, cx);
     }
 
+    pub(super) fn set_comic_right_to_left(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        if self.comic_right_to_left != enabled {
+            self.comic_right_to_left = enabled;
+            self.persist_comic_right_to_left();
+            cx.notify();
+        }
+    }
+
+    pub(super) fn set_page_double_click_action(
+        &mut self,
+        action: PageDoubleClickAction,
+        cx: &mut Context<Self>,
+    ) {
+        if self.page_double_click_action != action {
+            self.page_double_click_action = action;
+            self.persist_page_double_click_action();
+            cx.notify();
+        }
+    }
+
+    /// Runs the configured double-click action for a click that missed the page text.
+    pub(super) fn run_page_double_click_action(&mut self, window: &Window, cx: &mut Context<Self>) {
+        match self.page_double_click_action {
+            PageDoubleClickAction::Nothing => {}
+            PageDoubleClickAction::FitWidth => self.zoom_reset(cx),
+            PageDoubleClickAction::ActualSize => self.zoom_to_actual_size(window, cx),
+            PageDoubleClickAction::ToggleZenMode => self.toggle_zen_mode(cx),
+        }
+    }
+
+    pub(super) fn set_shell_open_with_registered(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        let label = self.i18n().shell_open_with_verb;
+        if let Err(err) = shell_integration::set_registered(enabled, label) {
+            crate::debug_log!(@warn, "[shell] failed to update open-with verb: {:#}", err);
+        }
+        self.shell_open_with_registered = shell_integration::is_registered();
+        cx.notify();
+    }
+
+    /// Whether the left arrow keys should turn forward in the active tab.
+    pub(super) fn page_order_right_to_left(&self) -> bool {
+        let Some(tab) = self.active_tab() else {
+            return false;
+        };
+        tab.right_to_left.unwrap_or_else(|| {
+            self.comic_right_to_left
+                && tab
+                    .path
+                    .as_deref()
+                    .is_some_and(|path| formats::is_comic_archive(path))
+        })
+    }
+
+    /// Flips the page order of the active document and remembers it with its position.
+    pub(super) fn toggle_page_order_right_to_left(&mut self, cx: &mut Context<Self>) {
+        let right_to_left = !self.page_order_right_to_left();
+        let Some(tab) = self.active_tab_mut() else {
+            return;
+        };
+        if tab.path.is_none() {
+            return;
+        }
+        tab.right_to_left = Some(right_to_left);
+        self.persist_active_tab_view();
+        cx.notify();
+    }
+
+    pub(super) fn set_zen_mode(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        if self.zen_mode != enabled {
+            self.zen_mode = enabled;
+            self.zen_chrome_revealed = false;
+            self.persist_zen_mode();
+            cx.notify();
+        }
+    }
+
+    pub(super) fn toggle_zen_mode(&mut self, cx: &mut Context<Self>) {
+        self.set_zen_mode(!self.zen_mode, cx);
+    }
+
+    fn update_zen_chrome_reveal(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        if !self.zen_mode {
+            return;
+        }
+
+        let y = f32::from(position.y);
+        let revealed = if self.zen_chrome_revealed {
+            let chrome_height = if self.tab_layout_mode == TabLayoutMode::Horizontal {
+                TITLE_BAR_HEIGHT + TAB_BAR_HEIGHT
+            } else {
+                TITLE_BAR_HEIGHT
+            };
+            // Keep the chrome up while one of its popups is open below it.
+            y <= chrome_height || self.bookmark_popup_open || self.recent_popup_open
+        } else {
+            y <= ZEN_MODE_REVEAL_EDGE
+        };
+        if self.zen_chrome_revealed != revealed {
+            self.zen_chrome_revealed = revealed;
+            cx.notify();
+        }
+    }
+
+    /// Uses the platform's own fullscreen mode; the layout follows from the new viewport size.
+    pub(super) fn toggle_fullscreen(&mut self, window: &mut Window) {
+        window.toggle_fullscreen();
+    }
+
+    fn title_bar_in_layout(&self) -> bool {
+        !self.zen_mode && !self.fullscreen_chrome_hidden
+    }
+
+    /// Fullscreen and presentation mode share one idle timer for the title bar and cursor.
+    fn chrome_hides_when_idle(&self) -> bool {
+        self.window_fullscreen || self.presentation_active
+    }
+
+    fn note_fullscreen_pointer_activity(&mut self, cx: &mut Context<Self>) {
+        if !self.chrome_hides_when_idle() {
+            return;
+        }
+        if self.fullscreen_chrome_hidden {
+            self.fullscreen_chrome_hidden = false;
+            cx.notify();
+        }
+        self.schedule_fullscreen_idle_hide(cx);
+    }
+
+    fn schedule_fullscreen_idle_hide(&mut self, cx: &mut Context<Self>) {
+        self.fullscreen_idle_epoch = self.fullscreen_idle_epoch.wrapping_add(1);
+        if !self.chrome_hides_when_idle() {
+            return;
+        }
+        let idle_epoch = self.fullscreen_idle_epoch;
+
+        cx.spawn(async move |view, cx| {
+            cx.background_executor()
+                .timer(Duration::from_millis(FULLSCREEN_IDLE_HIDE_DELAY_MS))
+                .await;
+
+            let _ = view.update(cx, |this, cx| {
+                if this.fullscreen_idle_epoch != idle_epoch || !this.chrome_hides_when_idle() {
+                    return;
+                }
+                if this.bookmark_popup_open || this.recent_popup_open || this.command_panel_open {
+                    return;
+                }
+                if !this.fullscreen_chrome_hidden {
+                    this.fullscreen_chrome_hidden = true;
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn set_sidebar_width(&mut self, width: f32, cx: &mut Context<Self>) {
+        let width = width.clamp(SIDEBAR_MIN_WIDTH, SIDEBAR_MAX_WIDTH);
+        if (self.sidebar_width - width).abs() > f32::EPSILON {
+            self.sidebar_width = width;
+            cx.notify();
+        }
+    }
+
+    fn start_sidebar_resize(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        self.sidebar_resize_drag = Some((f32::from(position.x), self.sidebar_width));
+        cx.notify();
+    }
+
+    fn update_sidebar_resize(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        let Some((start_x, start_width)) = self.sidebar_resize_drag else {
+            return;
+        };
+        let mut delta = f32::from(position.x) - start_x;
+        if self.language.is_rtl() {
+            // The sidebar sits on the right, so dragging its edge left widens it.
+            delta = -delta;
+        }
+        self.set_sidebar_width(start_width + delta, cx);
+    }
+
+    fn finish_sidebar_resize(&mut self, cx: &mut Context<Self>) {
+        if self.sidebar_resize_drag.take().is_none() {
+            return;
+        }
+        self.persist_sidebar_width();
+        cx.notify();
+    }
+
     fn current_drag_source_tab_id(&self) -> Option<usize> {
         match self.drag_state {
             DragState::Started { source_tab_id } => Some(source_tab_id),
@@ -964,6 +1164,25 @@ impl PdfViewer {
         )
     }
 
+    /// A thin bar along the bottom of a tab while its pages are still loading.
+    fn render_tab_load_progress(fraction: f32, cx: &App) -> impl IntoElement {
+        div()
+            .absolute()
+            .left_1()
+            .right_1()
+            .bottom_0()
+            .h(px(2.))
+            .rounded_full()
+            .bg(cx.theme().border)
+            .child(
+                div()
+                    .h_full()
+                    .rounded_full()
+                    .bg(cx.theme().primary)
+                    .w(relative(fraction)),
+            )
+    }
+
     fn render_tab_items(
         &self,
         tabs_to_show: &[&PdfTab],
@@ -1002,12 +1221,13 @@ impl PdfViewer {
             elements.push(
                 div()
                     .id(("tab", tab_id))
-                    .h(px(28.))
-                    .px_2()
+                    .relative()
+                    .h(self.ui_density.row_height(28.))
+                    .px(self.ui_density.spacing(0.5))
                     .flex_shrink_0()
                     .flex()
                     .items_center()
-                    .gap_2()
+                    .gap(self.ui_density.spacing(0.5))
                     .rounded_md()
                     .bg(cx.theme().secondary)
                     .when(is_active, |this| this.bg(cx.theme().background))
@@ -1120,6 +1340,9 @@ impl PdfViewer {
                                 this.close_tab(tab_id, cx);
                             })),
                     )
+                    .when_some(tab.load_progress(), |this, fraction| {
+                        this.child(Self::render_tab_load_progress(fraction, cx))
+                    })
                     .on_click(cx.listener(move |this, _, _, cx| {
                         if !is_active {
                             this.switch_to_tab(tab_id, cx);
@@ -1184,9 +1407,10 @@ impl PdfViewer {
             elements.push(
                 div()
                     .id(("tab-v", tab_id))
+                    .relative()
                     .w_full()
-                    .h(px(36.))
-                    .px_2()
+                    .h(self.ui_density.row_height(36.))
+                    .px(self.ui_density.spacing(0.5))
                     .flex()
                     .items_center()
                     .justify_between()
@@ -1242,6 +1466,9 @@ impl PdfViewer {
                                 this.close_tab(tab_id, cx);
                             })),
                     )
+                    .when_some(tab.load_progress(), |this, fraction| {
+                        this.child(Self::render_tab_load_progress(fraction, cx))
+                    })
                     .on_click(cx.listener(move |this, _, _, cx| {
                         if !is_active {
                             this.switch_to_tab(tab_id, cx);
@@ -42,6 +42,7 @@ impl KeymapWindow {
                 vec![
                     ("open_file", vec![Keystroke::parse("cmd-o").unwrap()]),
                     ("close_tab", vec![Keystroke::parse("cmd-w").unwrap()]),
+                    ("quick_switch_tab", vec![Keystroke::parse("cmd-p").unwrap()]),
                     ("switch_to_next_tab", vec![Keystroke::parse("cmd-shift-]").unwrap()]),
                     ("switch_to_previous_tab", vec![Keystroke::parse("cmd-shift-[").unwrap()]),
                     ("switch_to_tab_1", vec![Keystroke::parse("cmd-1").unwrap()]),
@@ -60,6 +61,16 @@ impl KeymapWindow {
                 vec![
                     ("toggle_sidebar", vec![Keystroke::parse("cmd-b").unwrap()]),
                     ("toggle_thumbnail_panel", vec![Keystroke::parse("cmd-shift-t").unwrap()]),
+                    ("toggle_page_overview", vec![Keystroke::parse("cmd-shift-o").unwrap()]),
+                    ("toggle_zen_mode", vec![Keystroke::parse("cmd-shift-f").unwrap()]),
+                    (
+                        "toggle_fullscreen",
+                        vec![
+                            Keystroke::parse("f11").unwrap(),
+                            Keystroke::parse("ctrl-cmd-f").unwrap(),
+                        ],
+                    ),
+                    ("toggle_presentation", vec![Keystroke::parse("f5").unwrap()]),
                 ],
             ),
             (
@@ -83,6 +94,11 @@ impl KeymapWindow {
                     ]),
                     ("first_page", vec![Keystroke::parse("home").unwrap()]),
                     ("last_page", vec![Keystroke::parse("end").unwrap()]),
+                    ("navigate_back", vec![Keystroke::parse("alt-left").unwrap()]),
+                    ("navigate_forward", vec![Keystroke::parse("alt-right").unwrap()]),
+                    ("follow_link", vec![Keystroke::parse("f").unwrap()]),
+                    ("next_search_result", vec![Keystroke::parse("f3").unwrap()]),
+                    ("previous_search_result", vec![Keystroke::parse("shift-f3").unwrap()]),
                 ],
             ),
             (
@@ -93,6 +109,18 @@ impl KeymapWindow {
                     ("clear_selection", vec![Keystroke::parse("escape").unwrap()]),
                 ],
             ),
+            (
+                &i18n.keymap_section_annotations,
+                vec![
+                    ("undo_annotation", vec![Keystroke::parse("cmd-z").unwrap()]),
+                    ("redo_annotation", vec![Keystroke::parse("cmd-shift-z").unwrap()]),
+                    ("delete_selected_shape", vec![
+                        Keystroke::parse("delete").unwrap(),
+                        Keystroke::parse("backspace").unwrap(),
+                    ]),
+                    ("cancel_annotation_tool", vec![Keystroke::parse("escape").unwrap()]),
+                ],
+            ),
             (
                 &i18n.keymap_section_panels,
                 vec![
@@ -109,6 +137,7 @@ impl KeymapWindow {
             "show_keymap" => i18n.command_panel_show_keymap.to_string(),
             "open_file" => i18n.action_open_file.to_string(),
             "close_tab" => i18n.action_close_tab.to_string(),
+            "quick_switch_tab" => i18n.action_quick_switch_tab.to_string(),
             "switch_to_next_tab" => i18n.action_switch_to_next_tab.to_string(),
             "switch_to_previous_tab" => i18n.action_switch_to_previous_tab.to_string(),
             "switch_to_tab_1" => i18n.action_switch_to_tab_1.to_string(),
@@ -122,6 +151,10 @@ impl KeymapWindow {
             "switch_to_last_tab" => i18n.action_switch_to_last_tab.to_string(),
             "toggle_sidebar" => i18n.action_toggle_sidebar.to_string(),
             "toggle_thumbnail_panel" => i18n.action_toggle_thumbnail_panel.to_string(),
+            "toggle_page_overview" => i18n.action_toggle_page_overview.to_string(),
+            "toggle_zen_mode" => i18n.action_toggle_zen_mode.to_string(),
+            "toggle_fullscreen" => i18n.action_toggle_fullscreen.to_string(),
+            "toggle_presentation" => i18n.action_toggle_presentation.to_string(),
             "zoom_in" => i18n.action_zoom_in.to_string(),
             "zoom_out" => i18n.action_zoom_out.to_string(),
             "zoom_reset" => i18n.action_zoom_reset.to_string(),
@@ -129,9 +162,18 @@ impl KeymapWindow {
             "next_page" => i18n.action_next_page.to_string(),
             "first_page" => i18n.action_first_page.to_string(),
             "last_page" => i18n.action_last_page.to_string(),
+            "navigate_back" => i18n.action_navigate_back.to_string(),
+            "navigate_forward" => i18n.action_navigate_forward.to_string(),
+            "follow_link" => i18n.action_follow_link.to_string(),
+            "next_search_result" => i18n.action_next_search_result.to_string(),
+            "previous_search_result" => i18n.action_previous_search_result.to_string(),
             "copy" => i18n.action_copy.to_string(),
             "select_all" => i18n.action_select_all.to_string(),
             "clear_selection" => i18n.action_clear_selection.to_string(),
+            "undo_annotation" => i18n.action_undo_annotation.to_string(),
+            "redo_annotation" => i18n.action_redo_annotation.to_string(),
+            "delete_selected_shape" => i18n.action_delete_selected_shape.to_string(),
+            "cancel_annotation_tool" => i18n.action_cancel_annotation_tool.to_string(),
             "toggle_bookmarks" => i18n.action_toggle_bookmarks.to_string(),
             "toggle_recent_files" => i18n.action_toggle_recent_files.to_string(),
             _ => action.to_string(),
@@ -329,7 +371,7 @@ impl PdfViewer {
                 cx.notify();
             }
             Err(err) => {
-                crate::debug_log!("[keymap] failed to open keymap window: {}", err);
+                crate::debug_log!(@warn, "[keymap] failed to open keymap window: {}", err);
                 self.on_keymap_dialog_window_closed(session_id, cx);
             }
         }
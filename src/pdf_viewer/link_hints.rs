@@ -0,0 +1,232 @@
+use super::utils::{PageLink, PageLinkTarget, load_page_links};
+use super::{MarkdownNoteAnchor, PdfViewer};
+use gpui::*;
+
+/// Characters hint labels are built from, home row first as in browser hint extensions.
+const LINK_HINT_CHARACTERS: &str = "sadfjklewcmpgh";
+
+/// Follow-link mode: labels over the links on the pages that were visible when it started.
+pub(super) struct LinkHintMode {
+    epoch: u64,
+    tab_id: usize,
+    loading: bool,
+    hints: Vec<(String, PageLink)>,
+    typed: String,
+}
+
+/// Labels of equal length for `count` hints, so no label is a prefix of another.
+fn link_hint_labels(count: usize) -> Vec<String> {
+    let characters = LINK_HINT_CHARACTERS.chars().collect::<Vec<_>>();
+    let base = characters.len();
+    let mut length = 1;
+    while base.pow(length) < count {
+        length += 1;
+    }
+    (0..count)
+        .map(|mut index| {
+            let mut label = vec![characters[0]; length as usize];
+            for slot in label.iter_mut().rev() {
+                *slot = characters[index % base];
+                index /= base;
+            }
+            label.into_iter().collect()
+        })
+        .collect()
+}
+
+impl PdfViewer {
+    pub(super) fn link_hints_active(&self) -> bool {
+        self.link_hints.is_some()
+    }
+
+    pub(super) fn start_link_hints(&mut self, cx: &mut Context<Self>) {
+        let language = self.language;
+        let Some(tab) = self.active_tab() else {
+            return;
+        };
+        let Some(path) = tab.path.clone() else {
+            return;
+        };
+        if tab.pages.is_empty() {
+            return;
+        }
+        let tab_id = tab.id;
+        let page_indices = tab
+            .last_display_visible_range
+            .clone()
+            .unwrap_or(tab.active_page..tab.active_page + 1)
+            .filter(|index| *index < tab.pages.len())
+            .collect::<Vec<_>>();
+
+        self.link_hint_epoch = self.link_hint_epoch.wrapping_add(1);
+        let epoch = self.link_hint_epoch;
+        self.link_hints = Some(LinkHintMode {
+            epoch,
+            tab_id,
+            loading: true,
+            hints: Vec::new(),
+            typed: String::new(),
+        });
+        cx.notify();
+
+        cx.spawn(async move |view, cx| {
+            let loaded = cx
+                .background_executor()
+                .spawn(async move { load_page_links(&path, &page_indices, language) })
+                .await;
+
+            let _ = view.update(cx, |this, cx| {
+                let Some(mode) = this.link_hints.as_mut() else {
+                    return;
+                };
+                if mode.epoch != epoch {
+                    return;
+                }
+                let mut links = match loaded {
+                    Ok(links) => links,
+                    Err(err) => {
+                        crate::debug_log!(@warn, "[links] load failed: {}", err);
+                        Vec::new()
+                    }
+                };
+                if links.is_empty() {
+                    crate::debug_log!("[links] no links on the visible pages");
+                    this.link_hints = None;
+                    cx.notify();
+                    return;
+                }
+                // Label in reading order: page by page, top to bottom, then left to right.
+                links.sort_by(|a, b| {
+                    a.page_index
+                        .cmp(&b.page_index)
+                        .then_with(|| b.rect[3].total_cmp(&a.rect[3]))
+                        .then_with(|| a.rect[0].total_cmp(&b.rect[0]))
+                });
+                mode.hints = link_hint_labels(links.len())
+                    .into_iter()
+                    .zip(links)
+                    .collect();
+                mode.loading = false;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn cancel_link_hints(&mut self, cx: &mut Context<Self>) {
+        if self.link_hints.take().is_some() {
+            cx.notify();
+        }
+    }
+
+    /// Narrows the hints by one typed key and follows the link once a label is complete.
+    pub(super) fn handle_link_hint_key(&mut self, key: &str, cx: &mut Context<Self>) {
+        if self.active_tab().map(|tab| tab.id) != self.link_hints.as_ref().map(|mode| mode.tab_id)
+            || key == "escape"
+        {
+            self.cancel_link_hints(cx);
+            return;
+        }
+        let Some(mode) = self.link_hints.as_mut() else {
+            return;
+        };
+        if mode.loading {
+            return;
+        }
+        if key == "backspace" {
+            mode.typed.pop();
+            cx.notify();
+            return;
+        }
+
+        let mut characters = key.chars();
+        let (Some(character), None) = (characters.next(), characters.next()) else {
+            return;
+        };
+        if !LINK_HINT_CHARACTERS.contains(character) {
+            return;
+        }
+        let typed = format!("{}{}", mode.typed, character);
+        if let Some((_, link)) = mode.hints.iter().find(|(label, _)| *label == typed) {
+            let link = link.clone();
+            self.link_hints = None;
+            self.follow_page_link(link, cx);
+            return;
+        }
+        // Keys that match no label are ignored rather than clearing what was typed.
+        if mode
+            .hints
+            .iter()
+            .any(|(label, _)| label.starts_with(&typed))
+        {
+            mode.typed = typed;
+            cx.notify();
+        }
+    }
+
+    fn follow_page_link(&mut self, link: PageLink, cx: &mut Context<Self>) {
+        match link.target {
+            PageLinkTarget::Page(page_index) => {
+                crate::debug_log!("[links] follow to page {}", page_index + 1);
+                self.select_page(page_index, cx);
+            }
+            PageLinkTarget::Uri(uri) => {
+                crate::debug_log!("[links] open {}", uri);
+                cx.open_url(&uri);
+            }
+        }
+        cx.notify();
+    }
+
+    pub(super) fn render_link_hints(
+        &self,
+        page_index: usize,
+        page_width_screen: f32,
+        page_height_screen: f32,
+    ) -> Vec<AnyElement> {
+        let Some(mode) = self.link_hints.as_ref() else {
+            return Vec::new();
+        };
+        if self.active_tab().map(|tab| tab.id) != Some(mode.tab_id) {
+            return Vec::new();
+        }
+
+        mode.hints
+            .iter()
+            .filter(|(label, link)| link.page_index == page_index && label.starts_with(&mode.typed))
+            .filter_map(|(label, link)| {
+                let anchor = MarkdownNoteAnchor {
+                    page_index,
+                    x_ratio: link.rect[0],
+                    y_ratio: link.rect[3],
+                };
+                let (left, top) = self.note_anchor_to_page_local_screen(
+                    page_index,
+                    &anchor,
+                    page_width_screen,
+                    page_height_screen,
+                )?;
+                let (typed, remaining) = label.split_at(mode.typed.len());
+                Some(
+                    div()
+                        .absolute()
+                        .left(px(left))
+                        .top(px(top))
+                        .px_1()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(rgb(0xCA8A04))
+                        .bg(rgb(0xFDE047))
+                        .shadow_sm()
+                        .flex()
+                        .text_xs()
+                        .font_weight(FontWeight::BOLD)
+                        .text_color(rgb(0x1C1917))
+                        .child(div().opacity(0.45).child(typed.to_uppercase()))
+                        .child(remaining.to_uppercase())
+                        .into_any_element(),
+                )
+            })
+            .collect()
+    }
+}
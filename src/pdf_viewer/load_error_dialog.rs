@@ -0,0 +1,291 @@
+use super::PdfViewer;
+use super::utils::repair_document_copy;
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::button::*;
+use gpui_component::*;
+use std::path::PathBuf;
+
+const LOAD_ERROR_DIALOG_WIDTH: f32 = 480.0;
+
+impl PdfViewer {
+    pub(super) fn open_load_error_dialog(&mut self, tab_id: usize, cx: &mut Context<Self>) {
+        if self.load_error_dialog_tab_id == Some(tab_id) {
+            return;
+        }
+        self.load_error_dialog_tab_id = Some(tab_id);
+        cx.notify();
+    }
+
+    pub(super) fn close_load_error_dialog(&mut self, cx: &mut Context<Self>) {
+        if self.load_error_dialog_tab_id.take().is_some() {
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+    }
+
+    pub(super) fn load_error_dialog_visible(&self) -> bool {
+        self.load_error_dialog_tab_id.is_some()
+            && self.load_error_dialog_tab_id == self.tab_bar.active_tab_id()
+    }
+
+    fn load_error_tab_path(&self, tab_id: usize) -> Option<PathBuf> {
+        self.tab_bar
+            .tabs()
+            .iter()
+            .find(|tab| tab.id == tab_id)
+            .and_then(|tab| tab.path.clone())
+    }
+
+    pub(super) fn retry_failed_load(&mut self, tab_id: usize, cx: &mut Context<Self>) {
+        let Some(path) = self.load_error_tab_path(tab_id) else {
+            return;
+        };
+        self.load_error_dialog_tab_id = None;
+        self.load_pdf_path_into_tab(tab_id, path, true, cx);
+    }
+
+    fn reveal_failed_load_in_file_manager(&self, tab_id: usize) {
+        let Some(path) = self.load_error_tab_path(tab_id) else {
+            return;
+        };
+        // The file itself may be gone, so fall back to its folder.
+        if path.exists() {
+            self.reveal_path_in_file_manager(&path);
+        } else if let Some(parent) = path.parent() {
+            self.reveal_path_in_file_manager(parent);
+        }
+    }
+
+    fn remove_failed_load_from_recents(&mut self, tab_id: usize, cx: &mut Context<Self>) {
+        let Some(path) = self.load_error_tab_path(tab_id) else {
+            return;
+        };
+        let before = self.recent_files.len();
+        self.recent_files.retain(|p| p != &path);
+        if self.recent_files.len() != before {
+            self.persist_recent_files(cx);
+            cx.notify();
+        }
+    }
+
+    pub(super) fn repair_failed_load(&mut self, tab_id: usize, cx: &mut Context<Self>) {
+        let Some(path) = self.load_error_tab_path(tab_id) else {
+            return;
+        };
+        let language = self.language;
+        if let Some(tab) = self.tab_bar.get_tab_mut(tab_id) {
+            if tab.repairing {
+                return;
+            }
+            tab.repairing = true;
+        }
+        cx.notify();
+
+        cx.spawn(async move |view, cx| {
+            let repaired = cx
+                .background_executor()
+                .spawn({
+                    let path = path.clone();
+                    async move { repair_document_copy(&path, language) }
+                })
+                .await;
+
+            let _ = view.update(cx, |this, cx| {
+                let i18n = this.i18n();
+                let Some(tab) = this.tab_bar.get_tab_mut(tab_id) else {
+                    return;
+                };
+                if tab.path.as_ref() != Some(&path) {
+                    return;
+                }
+                tab.repairing = false;
+
+                match repaired {
+                    Ok(_) => {
+                        // The tab keeps the original path; reads now go to the repaired copy.
+                        this.load_error_dialog_tab_id = None;
+                        this.load_pdf_path_into_tab(tab_id, path.clone(), true, cx);
+                    }
+                    Err(err) => {
+                        crate::debug_log!(
+                            @warn,
+                            "[pdf][repair] failed: {} | {:#}",
+                            path.display(),
+                            err,
+                        );
+                        tab.load_error = Some(i18n.load_error_repair_failed(&format!("{err:#}")));
+                        this.open_load_error_dialog(tab_id, cx);
+                        cx.notify();
+                    }
+                }
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn render_load_error_dialog(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        if !self.load_error_dialog_visible() {
+            return None;
+        }
+        let tab_id = self.load_error_dialog_tab_id?;
+        let tab = self.tab_bar.tabs().iter().find(|tab| tab.id == tab_id)?;
+        let path = tab.path.clone()?;
+        let error = tab.load_error.clone().unwrap_or_default();
+        let repairing = tab.repairing;
+        let in_recents = self.recent_files.contains(&path);
+        let i18n = self.i18n();
+
+        let section = |label: &'static str, value: String| {
+            div()
+                .w_full()
+                .v_flex()
+                .gap_1()
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(label),
+                )
+                .child(
+                    div()
+                        .w_full()
+                        .rounded_md()
+                        .bg(cx.theme().secondary)
+                        .px_2()
+                        .py_1()
+                        .text_xs()
+                        .text_color(cx.theme().foreground)
+                        .child(value),
+                )
+        };
+
+        Some(
+            div()
+                .id("load-error-overlay")
+                .absolute()
+                .top_0()
+                .left_0()
+                .right_0()
+                .bottom_0()
+                .bg(cx.theme().background.opacity(0.45))
+                .on_scroll_wheel(cx.listener(|_, _: &ScrollWheelEvent, _, cx| {
+                    cx.stop_propagation();
+                }))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _, _, cx| {
+                        this.close_load_error_dialog(cx);
+                    }),
+                )
+                .child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .right_0()
+                        .bottom_0()
+                        .v_flex()
+                        .items_center()
+                        .justify_center()
+                        .child(
+                            div()
+                                .id("load-error-dialog")
+                                .w(px(LOAD_ERROR_DIALOG_WIDTH))
+                                .max_w_full()
+                                .v_flex()
+                                .gap_3()
+                                .popover_style(cx)
+                                .p_4()
+                                .on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener(|_, _, _, cx| {
+                                        cx.stop_propagation();
+                                    }),
+                                )
+                                .child(
+                                    h_flex()
+                                        .items_center()
+                                        .gap_2()
+                                        .child(
+                                            Icon::new(crate::icons::IconName::File)
+                                                .size_5()
+                                                .text_color(cx.theme().danger),
+                                        )
+                                        .child(
+                                            div()
+                                                .text_sm()
+                                                .font_medium()
+                                                .text_color(cx.theme().foreground)
+                                                .child(i18n.load_error_dialog_title),
+                                        ),
+                                )
+                                .child(section(
+                                    i18n.load_error_file_label,
+                                    path.to_string_lossy().into_owned(),
+                                ))
+                                .child(section(i18n.load_error_details_label, error))
+                                .child(
+                                    h_flex()
+                                        .w_full()
+                                        .flex_wrap()
+                                        .justify_end()
+                                        .gap_2()
+                                        .child(
+                                            Button::new("load-error-open-folder")
+                                                .small()
+                                                .label(i18n.load_error_open_folder_button)
+                                                .on_click(cx.listener(move |this, _, _, _| {
+                                                    this.reveal_failed_load_in_file_manager(tab_id);
+                                                })),
+                                        )
+                                        .child(
+                                            Button::new("load-error-remove-recent")
+                                                .small()
+                                                .disabled(!in_recents)
+                                                .label(i18n.load_error_remove_recent_button)
+                                                .on_click(cx.listener(move |this, _, _, cx| {
+                                                    this.remove_failed_load_from_recents(tab_id, cx);
+                                                })),
+                                        )
+                                        .child(
+                                            Button::new("load-error-repair")
+                                                .small()
+                                                .loading(repairing)
+                                                .disabled(repairing)
+                                                .label(if repairing {
+                                                    i18n.load_error_repairing
+                                                } else {
+                                                    i18n.load_error_repair_button
+                                                })
+                                                .on_click(cx.listener(move |this, _, _, cx| {
+                                                    this.repair_failed_load(tab_id, cx);
+                                                })),
+                                        )
+                                        .child(
+                                            Button::new("load-error-retry")
+                                                .small()
+                                                .primary()
+                                                .disabled(repairing)
+                                                .label(i18n.load_error_retry_button)
+                                                .on_click(cx.listener(move |this, _, _, cx| {
+                                                    this.retry_failed_load(tab_id, cx);
+                                                })),
+                                        )
+                                        .child(
+                                            Button::new("load-error-close")
+                                                .small()
+                                                .ghost()
+                                                .label(i18n.close_button)
+                                                .when(repairing, |this| this.disabled(true))
+                                                .on_click(cx.listener(|this, _, _, cx| {
+                                                    this.close_load_error_dialog(cx);
+                                                })),
+                                        ),
+                                ),
+                        ),
+                )
+                .into_any_element(),
+        )
+    }
+}
@@ -140,3 +140,21 @@ pub fn show_tab_context_menu(
         _ => None,
     }
 }
+
+/// Empties the system's recent documents for the app (Open Recent in the Dock menu).
+pub fn clear_recent_documents() {
+    if MainThreadMarker::new().is_none() {
+        return;
+    }
+    // SAFETY: both messages are sent on the main thread, and `clearRecentDocuments:`
+    // accepts a nil sender.
+    unsafe {
+        let controller: *mut AnyObject = msg_send![
+            objc2::class!(NSDocumentController),
+            sharedDocumentController
+        ];
+        if let Some(controller) = controller.as_ref() {
+            let _: () = msg_send![controller, clearRecentDocuments: std::ptr::null::<AnyObject>()];
+        }
+    }
+}
@@ -99,7 +99,7 @@ impl PdfViewer {
                 cx.notify();
             }
             Err(err) => {
-                crate::debug_log!("[note] failed to open markdown editor window: {}", err);
+                crate::debug_log!(@warn, "[note] failed to open markdown editor window: {}", err);
                 self.on_markdown_note_editor_window_closed(session_id, cx);
             }
         }
@@ -223,6 +223,7 @@ impl PdfViewer {
             markdown,
             created_at_unix_secs: now,
             updated_at_unix_secs: now,
+            author: self.annotation_defaults.author(),
             selected_text,
             selection_rects,
         };
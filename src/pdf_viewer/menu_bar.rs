@@ -1,6 +1,6 @@
 use crate::icons;
 
-use super::PdfViewer;
+use super::{PdfViewer, ToolbarItem};
 use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 use gpui_component::popover::Popover;
@@ -12,19 +12,44 @@ impl PdfViewer {
         page_count: usize,
         current_page_num: usize,
         zoom_label: SharedString,
-        show_navigation: bool,
-        show_zoom: bool,
         cx: &mut Context<Self>,
     ) -> impl IntoElement {
-        let i18n = self.i18n();
-        let active_page = self.active_tab_active_page();
-        let bookmark_popup_open = self.bookmark_popup_open;
-        let bookmark_scope = self.bookmark_scope;
-        let bookmarks = self.bookmarks_for_scope(bookmark_scope);
-        let bookmark_notes = self.markdown_notes.clone();
-        let bookmark_expanded_notes = self.bookmark_popup_expanded_notes.clone();
-        let bookmark_popup_list_scroll = self.bookmark_popup_list_scroll.clone();
+        let toolbar = self.titlebar_preferences;
+        let navigation_position = toolbar
+            .visible_items()
+            .position(|item| item == ToolbarItem::Navigation);
 
+        let mut items = Vec::new();
+        for item in toolbar.visible_items() {
+            items.push(match item {
+                ToolbarItem::Navigation => self
+                    .render_toolbar_navigation(
+                        page_count,
+                        current_page_num,
+                        navigation_position.is_some_and(|position| position > 0),
+                        cx,
+                    )
+                    .into_any_element(),
+                ToolbarItem::Search => Button::new("toolbar-search")
+                    .ghost()
+                    .small()
+                    .icon(
+                        Icon::new(icons::IconName::Search)
+                            .size_4()
+                            .text_color(cx.theme().foreground),
+                    )
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.open_sidebar_search(window, cx);
+                    }))
+                    .into_any_element(),
+                ToolbarItem::Bookmark => self.render_toolbar_bookmark(cx).into_any_element(),
+                ToolbarItem::Zoom => self
+                    .render_toolbar_zoom(zoom_label.clone(), cx)
+                    .into_any_element(),
+            });
+        }
+
+        // The navigation group stretches to push the items after it to the far edge.
         div()
             .id("title-nav-bar")
             .h_full()
@@ -32,180 +57,188 @@ impl PdfViewer {
             .min_w(px(0.))
             .px_1()
             .flex()
+            .when(self.language.is_rtl(), |this| this.flex_row_reverse())
             .items_center()
-            .justify_between()
-            .when(!show_navigation, |this| this.justify_end())
-            .when(show_navigation, |this| {
+            .gap_2()
+            .when(navigation_position.is_none(), |this| this.justify_end())
+            .children(items)
+    }
+
+    fn render_toolbar_navigation(
+        &self,
+        page_count: usize,
+        current_page_num: usize,
+        align_end: bool,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let active_page = self.active_tab_active_page();
+
+        div()
+            .flex_1()
+            .min_w(px(0.))
+            .flex()
+            .items_center()
+            .gap_2()
+            .when(align_end, |this| this.justify_end())
+            .when(page_count > 0, |this| {
                 this.child(
+                    Button::new("first-page")
+                        .ghost()
+                        .small()
+                        .disabled(active_page == 0)
+                        .icon(
+                            Icon::new(icons::IconName::ChevronFirst)
+                                .text_color(cx.theme().foreground),
+                        )
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.select_page(0, cx);
+                        })),
+                )
+                .child(
+                    Button::new("prev-page")
+                        .ghost()
+                        .small()
+                        .disabled(active_page == 0)
+                        .icon(
+                            Icon::new(icons::IconName::ChevronLeft)
+                                .text_color(cx.theme().foreground),
+                        )
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.prev_page(cx);
+                        })),
+                )
+                .child(
                     div()
-                        .flex()
-                        .items_center()
-                        .gap_2()
-                        .when(page_count > 0, |this| {
-                            this.child(
-                                Button::new("first-page")
-                                    .ghost()
-                                    .small()
-                                    .disabled(active_page == 0)
-                                    .icon(
-                                        Icon::new(icons::IconName::ChevronFirst)
-                                            .text_color(cx.theme().foreground),
-                                    )
-                                    .on_click(cx.listener(|this, _, _, cx| {
-                                        this.select_page(0, cx);
-                                    })),
-                            )
-                            .child(
-                                Button::new("prev-page")
-                                    .ghost()
-                                    .small()
-                                    .disabled(active_page == 0)
-                                    .icon(
-                                        Icon::new(icons::IconName::ChevronLeft)
-                                            .text_color(cx.theme().foreground),
-                                    )
-                                    .on_click(cx.listener(|this, _, _, cx| {
-                                        this.prev_page(cx);
-                                    })),
-                            )
-                            .child(
-                                div()
-                                    .text_sm()
-                                    .text_color(cx.theme().muted_foreground)
-                                    .child(format!("{} / {}", current_page_num, page_count)),
-                            )
-                            .child(
-                                Button::new("next-page")
-                                    .ghost()
-                                    .small()
-                                    .disabled(active_page + 1 >= page_count)
-                                    .icon(
-                                        Icon::new(icons::IconName::ChevronRight)
-                                            .text_color(cx.theme().foreground),
-                                    )
-                                    .on_click(cx.listener(|this, _, _, cx| {
-                                        this.next_page(cx);
-                                    })),
-                            )
-                            .child(
-                                Button::new("last-page")
-                                    .ghost()
-                                    .small()
-                                    .disabled(active_page + 1 >= page_count)
-                                    .icon(
-                                        Icon::new(icons::IconName::ChevronLast)
-                                            .text_color(cx.theme().foreground),
-                                    )
-                                    .on_click(cx.listener(move |this, _, _, cx| {
-                                        this.select_page(page_count.saturating_sub(1), cx);
-                                    })),
-                            )
-                        }),
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(format!("{} / {}", current_page_num, page_count)),
+                )
+                .child(
+                    Button::new("next-page")
+                        .ghost()
+                        .small()
+                        .disabled(active_page + 1 >= page_count)
+                        .icon(
+                            Icon::new(icons::IconName::ChevronRight)
+                                .text_color(cx.theme().foreground),
+                        )
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.next_page(cx);
+                        })),
+                )
+                .child(
+                    Button::new("last-page")
+                        .ghost()
+                        .small()
+                        .disabled(active_page + 1 >= page_count)
+                        .icon(
+                            Icon::new(icons::IconName::ChevronLast)
+                                .text_color(cx.theme().foreground),
+                        )
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.select_page(page_count.saturating_sub(1), cx);
+                        })),
                 )
             })
+    }
+
+    fn render_toolbar_bookmark(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let i18n = self.i18n();
+        let bookmark_popup_open = self.bookmark_popup_open;
+        let bookmark_scope = self.bookmark_scope;
+        let bookmarks = self.bookmarks_for_scope(bookmark_scope);
+        let bookmark_notes = self.markdown_notes.clone();
+        let bookmark_expanded_notes = self.bookmark_popup_expanded_notes.clone();
+        let bookmark_popup_list_scroll = self.bookmark_popup_list_scroll.clone();
+
+        Popover::new("bookmark-popover")
+            .anchor(Corner::TopLeft)
+            .appearance(false)
+            .overlay_closable(false)
+            .open(bookmark_popup_open)
+            .trigger(
+                Button::new("bookmark-add")
+                    .ghost()
+                    .small()
+                    .icon(
+                        Icon::new(icons::IconName::Bookmark)
+                            .size_4()
+                            .text_color(cx.theme().foreground),
+                    )
+                    .on_hover({
+                        let viewer = cx.entity();
+                        move |hovered, _, cx| {
+                            let _ = viewer.update(cx, |this, cx| {
+                                this.set_bookmark_popup_trigger_hovered(*hovered, cx);
+                            });
+                        }
+                    })
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.add_current_page_bookmark_and_open(cx);
+                    })),
+            )
+            .content({
+                let viewer = cx.entity();
+                move |_, _window, cx| {
+                    Self::render_bookmark_popup_panel(
+                        "bookmark-popup",
+                        i18n,
+                        viewer.clone(),
+                        bookmark_scope,
+                        bookmarks.clone(),
+                        bookmark_notes.clone(),
+                        bookmark_expanded_notes.clone(),
+                        &bookmark_popup_list_scroll,
+                        cx,
+                    )
+                }
+            })
+    }
+
+    fn render_toolbar_zoom(
+        &self,
+        zoom_label: SharedString,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let i18n = self.i18n();
+
+        div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .child(
+                Button::new("zoom-out")
+                    .ghost()
+                    .small()
+                    .icon(Icon::new(icons::IconName::Minus).text_color(cx.theme().foreground))
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.zoom_out(cx);
+                    })),
+            )
+            .child(
+                Button::new("zoom-reset")
+                    .ghost()
+                    .small()
+                    .label(i18n.zoom_reset_button)
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.zoom_reset(cx);
+                    })),
+            )
+            .child(
+                Button::new("zoom-in")
+                    .ghost()
+                    .small()
+                    .icon(Icon::new(icons::IconName::Plus).text_color(cx.theme().foreground))
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.zoom_in(cx);
+                    })),
+            )
             .child(
                 div()
-                    .flex()
-                    .items_center()
-                    .gap_2()
-                    .child(
-                        Popover::new("bookmark-popover")
-                            .anchor(Corner::TopLeft)
-                            .appearance(false)
-                            .overlay_closable(false)
-                            .open(bookmark_popup_open)
-                            .trigger(
-                                Button::new("bookmark-add")
-                                    .ghost()
-                                    .small()
-                                    .icon(
-                                        Icon::new(icons::IconName::Bookmark)
-                                            .size_4()
-                                            .text_color(cx.theme().foreground),
-                                    )
-                                    .on_hover({
-                                        let viewer = cx.entity();
-                                        move |hovered, _, cx| {
-                                            let _ = viewer.update(cx, |this, cx| {
-                                                this.set_bookmark_popup_trigger_hovered(
-                                                    *hovered, cx,
-                                                );
-                                            });
-                                        }
-                                    })
-                                    .on_click(cx.listener(|this, _, _, cx| {
-                                        this.add_current_page_bookmark_and_open(cx);
-                                    })),
-                            )
-                            .content({
-                                let viewer = cx.entity();
-                                let i18n = i18n;
-                                let bookmarks = bookmarks.clone();
-                                let bookmark_notes = bookmark_notes.clone();
-                                let bookmark_expanded_notes = bookmark_expanded_notes.clone();
-                                move |_, _window, cx| {
-                                    Self::render_bookmark_popup_panel(
-                                        "bookmark-popup",
-                                        i18n,
-                                        viewer.clone(),
-                                        bookmark_scope,
-                                        bookmarks.clone(),
-                                        bookmark_notes.clone(),
-                                        bookmark_expanded_notes.clone(),
-                                        &bookmark_popup_list_scroll,
-                                        cx,
-                                    )
-                                }
-                            }),
-                    )
-                    .when(show_zoom, |this| {
-                        this.child(
-                            div()
-                                .flex()
-                                .items_center()
-                                .gap_1()
-                                .child(
-                                    Button::new("zoom-out")
-                                        .ghost()
-                                        .small()
-                                        .icon(
-                                            Icon::new(icons::IconName::Minus)
-                                                .text_color(cx.theme().foreground),
-                                        )
-                                        .on_click(cx.listener(|this, _, _, cx| {
-                                            this.zoom_out(cx);
-                                        })),
-                                )
-                                .child(
-                                    Button::new("zoom-reset")
-                                        .ghost()
-                                        .small()
-                                        .label(i18n.zoom_reset_button)
-                                        .on_click(cx.listener(|this, _, _, cx| {
-                                            this.zoom_reset(cx);
-                                        })),
-                                )
-                                .child(
-                                    Button::new("zoom-in")
-                                        .ghost()
-                                        .small()
-                                        .icon(
-                                            Icon::new(icons::IconName::Plus)
-                                                .text_color(cx.theme().foreground),
-                                        )
-                                        .on_click(cx.listener(|this, _, _, cx| {
-                                            this.zoom_in(cx);
-                                        })),
-                                )
-                                .child(
-                                    div()
-                                        .min_w(px(50.))
-                                        .text_sm()
-                                        .text_color(cx.theme().muted_foreground)
-                                        .child(zoom_label),
-                                ),
-                        )
-                    }),
+                    .min_w(px(50.))
+                    .text_sm()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(zoom_label),
             )
     }
 }
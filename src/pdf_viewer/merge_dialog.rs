@@ -0,0 +1,553 @@
+use super::{MERGE_DIALOG_WIDTH, MERGE_DIALOG_WINDOW_HEIGHT, MERGE_PROGRESS_POLL_MS};
+use crate::i18n::{I18n, Language};
+use crate::icons;
+use crate::pdf_viewer::PdfViewer;
+use crate::pdf_viewer::merge_documents;
+use crate::pdf_viewer::utils::{display_file_name, is_same_file};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::scroll::ScrollableElement;
+use gpui_component::{button::*, *};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub(super) struct MergeWindow {
+    viewer: Entity<PdfViewer>,
+    focus_handle: FocusHandle,
+    language: Language,
+    inputs: Vec<PathBuf>,
+    /// (inputs appended, total inputs) while a merge runs; `None` when idle.
+    progress: Option<(usize, usize)>,
+    status_message: Option<String>,
+    merged_output: Option<PathBuf>,
+}
+
+impl MergeWindow {
+    pub(super) fn new(
+        viewer: Entity<PdfViewer>,
+        language: Language,
+        inputs: Vec<PathBuf>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self {
+            viewer,
+            focus_handle: cx.focus_handle(),
+            language,
+            inputs,
+            progress: None,
+            status_message: None,
+            merged_output: None,
+        }
+    }
+
+    fn i18n(&self) -> I18n {
+        I18n::new(self.language)
+    }
+
+    fn is_pdf(path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"))
+    }
+
+    fn add_inputs(&mut self, paths: impl IntoIterator<Item = PathBuf>, cx: &mut Context<Self>) {
+        if self.progress.is_some() {
+            return;
+        }
+        let mut changed = false;
+        for path in paths {
+            if Self::is_pdf(&path) && !self.inputs.contains(&path) {
+                self.inputs.push(path);
+                changed = true;
+            }
+        }
+        if changed {
+            self.merged_output = None;
+            self.status_message = None;
+            cx.notify();
+        }
+    }
+
+    fn prompt_for_inputs(&mut self, cx: &mut Context<Self>) {
+        let picker = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: true,
+            prompt: Some(self.i18n().merge_add_files_prompt.into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = picker.await else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                this.add_inputs(paths, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn move_input(&mut self, index: usize, offset: isize, cx: &mut Context<Self>) {
+        let Some(target) = index.checked_add_signed(offset) else {
+            return;
+        };
+        if self.progress.is_some() || target >= self.inputs.len() {
+            return;
+        }
+        self.inputs.swap(index, target);
+        cx.notify();
+    }
+
+    fn remove_input(&mut self, index: usize, cx: &mut Context<Self>) {
+        if self.progress.is_some() || index >= self.inputs.len() {
+            return;
+        }
+        self.inputs.remove(index);
+        cx.notify();
+    }
+
+    fn start_merge(&mut self, cx: &mut Context<Self>) {
+        if self.progress.is_some() || self.inputs.len() < 2 {
+            return;
+        }
+        let directory = self
+            .inputs
+            .first()
+            .and_then(|path| path.parent())
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let picker = cx.prompt_for_new_path(&directory, Some("merged.pdf"));
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(output))) = picker.await else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                this.run_merge(output, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn run_merge(&mut self, output: PathBuf, cx: &mut Context<Self>) {
+        if self.progress.is_some() {
+            return;
+        }
+        if self.inputs.iter().any(|input| is_same_file(input, &output)) {
+            self.status_message = Some(self.i18n().merge_output_is_input.to_string());
+            cx.notify();
+            return;
+        }
+        let inputs = self.inputs.clone();
+        let language = self.language;
+        self.progress = Some((0, inputs.len()));
+        self.status_message = None;
+        self.merged_output = None;
+        cx.notify();
+
+        let progress = Arc::new(Mutex::new((0, inputs.len())));
+        let progress_for_task = progress.clone();
+        let finished = Arc::new(AtomicBool::new(false));
+        let finished_for_task = finished.clone();
+        let output_for_task = output.clone();
+        let merge = cx.background_executor().spawn(async move {
+            let result = merge_documents(&inputs, &output_for_task, language, |done, total| {
+                if let Ok(mut progress) = progress_for_task.lock() {
+                    *progress = (done, total);
+                }
+            });
+            finished_for_task.store(true, Ordering::Release);
+            result.map(|_| inputs.len())
+        });
+
+        cx.spawn(async move |this, cx| {
+            while !finished.load(Ordering::Acquire) {
+                cx.background_executor()
+                    .timer(Duration::from_millis(MERGE_PROGRESS_POLL_MS))
+                    .await;
+                let snapshot = progress.lock().map(|progress| *progress).ok();
+                let alive = this
+                    .update(cx, |this, cx| {
+                        if this.progress.is_some() {
+                            this.progress = snapshot.or(this.progress);
+                            cx.notify();
+                        }
+                    })
+                    .is_ok();
+                if !alive {
+                    return;
+                }
+            }
+        })
+        .detach();
+
+        cx.spawn(async move |this, cx| {
+            let result = merge.await;
+            let _ = this.update(cx, |this, cx| {
+                let i18n = this.i18n();
+                this.progress = None;
+                this.status_message = Some(match result {
+                    Ok(count) => {
+                        this.merged_output = Some(output.clone());
+                        i18n.merge_done(count, &output)
+                    }
+                    Err(err) => {
+                        crate::debug_log!(@warn, "[pdf][edit] merge failed: {:#}", err);
+                        i18n.merge_failed(&format!("{err:#}"))
+                    }
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn open_merged_output(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(output) = self.merged_output.clone() else {
+            return;
+        };
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.open_pdf_path_in_new_tab(output, cx);
+        });
+        self.close_dialog(window, cx);
+    }
+
+    fn close_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.close_merge_dialog(cx);
+        });
+        window.remove_window();
+    }
+
+    fn render_progress(&self, (done, total): (usize, usize), cx: &App) -> impl IntoElement {
+        let fraction = if total == 0 {
+            0.0
+        } else {
+            done as f32 / total as f32
+        };
+        PdfViewer::render_progress_bar(
+            fraction,
+            self.i18n().merge_progress((done + 1).min(total), total),
+            cx,
+        )
+    }
+
+    fn render_input_row(
+        &self,
+        index: usize,
+        path: &Path,
+        merging: bool,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let last = index + 1 == self.inputs.len();
+        let directory = path
+            .parent()
+            .map(|parent| parent.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        div()
+            .id(("merge-input", index))
+            .w_full()
+            .h_flex()
+            .items_center()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .rounded_md()
+            .hover(|this| this.bg(cx.theme().secondary.opacity(0.35)))
+            .child(
+                div()
+                    .flex_none()
+                    .w(px(20.))
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child((index + 1).to_string()),
+            )
+            .child(
+                Icon::new(icons::IconName::File)
+                    .size_4()
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w(px(0.))
+                    .v_flex()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().foreground)
+                            .truncate()
+                            .child(display_file_name(path)),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .truncate()
+                            .child(directory),
+                    ),
+            )
+            .child(
+                Button::new(("merge-input-up", index))
+                    .ghost()
+                    .xsmall()
+                    .icon(Icon::new(icons::IconName::ChevronUp).size_4())
+                    .disabled(merging || index == 0)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.move_input(index, -1, cx);
+                    })),
+            )
+            .child(
+                Button::new(("merge-input-down", index))
+                    .ghost()
+                    .xsmall()
+                    .icon(Icon::new(icons::IconName::ChevronDown).size_4())
+                    .disabled(merging || last)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.move_input(index, 1, cx);
+                    })),
+            )
+            .child(
+                Button::new(("merge-input-remove", index))
+                    .ghost()
+                    .xsmall()
+                    .icon(Icon::new(icons::IconName::WindowClose).size_4())
+                    .disabled(merging)
+                    .on_click(cx.listener(move |this, _, _, cx| {
+                        this.remove_input(index, cx);
+                    })),
+            )
+    }
+}
+
+impl Render for MergeWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let i18n = self.i18n();
+        let merging = self.progress.is_some();
+        let rows = self
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(index, path)| {
+                self.render_input_row(index, path, merging, cx)
+                    .into_any_element()
+            })
+            .collect::<Vec<_>>();
+
+        div()
+            .id("merge-window")
+            .size_full()
+            .v_flex()
+            .bg(cx.theme().background)
+            .focusable()
+            .track_focus(&self.focus_handle)
+            .capture_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key.as_str() == "escape" {
+                    this.close_dialog(window, cx);
+                    cx.stop_propagation();
+                }
+            }))
+            .drag_over::<ExternalPaths>(|style, _, _, cx| style.bg(cx.theme().secondary))
+            .on_drop(cx.listener(|this, paths: &ExternalPaths, _, cx| {
+                this.add_inputs(paths.paths().to_vec(), cx);
+            }))
+            .child(TitleBar::new())
+            .child(
+                div()
+                    .flex_1()
+                    .min_h(px(0.))
+                    .v_flex()
+                    .gap_3()
+                    .p_4()
+                    .child(
+                        div()
+                            .text_lg()
+                            .text_color(cx.theme().foreground)
+                            .child(i18n.merge_dialog_title),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .whitespace_normal()
+                            .child(i18n.merge_dialog_hint),
+                    )
+                    .child(div().h(px(1.)).bg(cx.theme().border))
+                    .child(
+                        div()
+                            .id("merge-inputs")
+                            .flex_1()
+                            .min_h(px(0.))
+                            .rounded_md()
+                            .border_1()
+                            .border_color(cx.theme().border)
+                            .p_1()
+                            .overflow_y_scrollbar()
+                            .when(rows.is_empty(), |this| {
+                                this.child(
+                                    div()
+                                        .size_full()
+                                        .flex()
+                                        .items_center()
+                                        .justify_center()
+                                        .text_sm()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child(i18n.merge_dialog_empty),
+                                )
+                            })
+                            .child(div().v_flex().gap_1().children(rows)),
+                    )
+                    .when_some(self.progress, |this, progress| {
+                        this.child(self.render_progress(progress, cx))
+                    })
+                    .when_some(self.status_message.clone(), |this, message| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .whitespace_normal()
+                                .child(message),
+                        )
+                    })
+                    .child(
+                        div()
+                            .w_full()
+                            .flex()
+                            .items_center()
+                            .justify_end()
+                            .gap_2()
+                            .child(
+                                Button::new("merge-add-files")
+                                    .small()
+                                    .label(i18n.merge_add_files_button)
+                                    .disabled(merging)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.prompt_for_inputs(cx);
+                                    })),
+                            )
+                            .when(self.merged_output.is_some(), |this| {
+                                this.child(
+                                    Button::new("merge-open-output")
+                                        .small()
+                                        .label(i18n.merge_open_button)
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.open_merged_output(window, cx);
+                                        })),
+                                )
+                            })
+                            .child(
+                                Button::new("merge-start")
+                                    .small()
+                                    .primary()
+                                    .label(i18n.merge_button)
+                                    .disabled(merging || self.inputs.len() < 2)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.start_merge(cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("merge-close")
+                                    .small()
+                                    .ghost()
+                                    .label(i18n.close_button)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.close_dialog(window, cx);
+                                    })),
+                            ),
+                    ),
+            )
+    }
+}
+
+impl PdfViewer {
+    pub(super) fn open_merge_dialog(&mut self, cx: &mut Context<Self>) {
+        if self.command_panel_open {
+            self.close_command_panel(cx);
+        }
+
+        if self.merge_dialog_open {
+            if let Some(handle) = self.merge_dialog_window.as_ref() {
+                let _ = handle.update(cx, |_, window, _| {
+                    window.activate_window();
+                });
+            }
+            return;
+        }
+
+        self.merge_dialog_open = true;
+        self.needs_root_refocus = false;
+        self.merge_dialog_session = self.merge_dialog_session.wrapping_add(1);
+        let session_id = self.merge_dialog_session;
+
+        // Start from the documents already open, in tab order.
+        let inputs = self
+            .tab_bar
+            .tabs()
+            .iter()
+            .filter_map(|tab| tab.path.clone())
+            .collect::<Vec<_>>();
+        let language = self.language;
+        let viewer = cx.entity();
+        let viewer_for_close = viewer.clone();
+        let window_options = WindowOptions {
+            titlebar: Some(Self::dialog_titlebar_options()),
+            window_bounds: Some(WindowBounds::centered(
+                size(px(MERGE_DIALOG_WIDTH), px(MERGE_DIALOG_WINDOW_HEIGHT)),
+                cx,
+            )),
+            window_decorations: Some(WindowDecorations::Client),
+            ..WindowOptions::default()
+        };
+
+        match cx.open_window(window_options, move |window, cx| {
+            window.on_window_should_close(cx, move |_, cx| {
+                let _ = viewer_for_close.update(cx, |this, cx| {
+                    this.on_merge_dialog_window_closed(session_id, cx);
+                });
+                true
+            });
+            let dialog = cx.new(|cx| MergeWindow::new(viewer, language, inputs, cx));
+            let dialog_focus = dialog.read(cx).focus_handle.clone();
+            let root = cx.new(|cx| Root::new(dialog, window, cx));
+            window.focus(&dialog_focus);
+            root
+        }) {
+            Ok(handle) => {
+                self.merge_dialog_window = Some(handle.into());
+                cx.notify();
+            }
+            Err(err) => {
+                crate::debug_log!(@warn, "[merge] failed to open merge window: {}", err);
+                self.on_merge_dialog_window_closed(session_id, cx);
+            }
+        }
+    }
+
+    pub(super) fn close_merge_dialog(&mut self, cx: &mut Context<Self>) {
+        let window_handle = self.merge_dialog_window.take();
+        if self.merge_dialog_open || window_handle.is_some() {
+            self.merge_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+        // Defer window removal to avoid borrow conflicts during event handling
+        if let Some(window_handle) = window_handle {
+            cx.defer(move |cx| {
+                let _ = window_handle.update(cx, |_, window, _| {
+                    window.remove_window();
+                });
+            });
+        }
+    }
+
+    fn on_merge_dialog_window_closed(&mut self, session_id: u64, cx: &mut Context<Self>) {
+        if self.merge_dialog_session == session_id {
+            self.merge_dialog_window = None;
+            self.merge_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+    }
+}
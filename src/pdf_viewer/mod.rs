@@ -1,36 +1,76 @@
+mod annotation_bundle;
 mod command_panel;
+mod diagnostics;
+mod debug_overlay;
 mod display_list;
+mod encrypt_dialog;
+mod external_tools;
+mod formats;
+mod header_footer_dialog;
+mod idle_prerender;
 #[cfg(target_os = "macos")]
 mod macos_context_menu;
 mod keymap;
+mod link_hints;
+mod load_error_dialog;
 mod menu_bar;
+mod merge_dialog;
+mod optimize_dialog;
+mod page_overview;
+mod pdf_edit;
+mod plugins;
+mod power;
+mod presentation;
+mod printing;
+mod render_queue;
+mod renderer;
+mod sessions;
+mod shapes;
+mod shell_integration;
+mod sidebar;
+mod stamp_dialog;
+mod stamps;
+mod status_bar;
 pub mod tab;
+mod tab_switcher;
 mod text_selection;
+mod thumbnail_export;
 mod thumbnail_list;
 mod utils;
+mod watermark_dialog;
 
 use crate::i18n::{I18n, Language};
 use crate::{
-    APP_REPOSITORY_URL, CheckForUpdatesMenu, DisableLoggingMenu, EnableLoggingMenu, OpenLogsMenu,
-    ShowAboutMenu, ShowSettingsMenu, configure_app_menus, updater,
+    APP_REPOSITORY_URL, CheckForUpdatesMenu, ClearRecentFilesMenu, DisableLoggingMenu,
+    EnableLoggingMenu, FirstPageMenu, LastPageMenu, NextPageMenu, OpenLogsMenu, OpenRecentFileMenu,
+    PreviousPageMenu, ShowAboutMenu, ShowSettingsMenu, ToggleFullscreenMenu, ToggleGrayscaleMenu,
+    TogglePageOverviewMenu, TogglePresentationMenu, ToggleStatusBarMenu, ToggleThumbnailPanelMenu,
+    ToggleZenModeMenu, updater,
 };
 use gpui::prelude::FluentBuilder as _;
 use gpui::*;
 use gpui_component::checkbox::Checkbox;
+use gpui_component::color_picker::{ColorPicker, ColorPickerEvent, ColorPickerState};
 use gpui_component::input::{Input, InputEvent, InputState};
 use gpui_component::popover::{Popover, PopoverState};
 use gpui_component::scroll::{ScrollableElement, Scrollbar, ScrollbarShow};
 use gpui_component::select::{SearchableVec, Select, SelectEvent, SelectState};
 use gpui_component::text::TextView;
 use gpui_component::{button::*, *};
+use kpdf_core::annotations;
+use kpdf_core::fetch::DownloadProgress;
+use kpdf_core::render::{RenderEngine, RenderOptions, TextAntialiasing, clamp_render_width};
+use kpdf_core::render_cache;
 #[cfg(target_os = "windows")]
 use raw_window_handle::RawWindowHandle;
 use std::cell::Cell;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 #[cfg(target_os = "windows")]
 use windows::Win32::Foundation::HWND;
 #[cfg(target_os = "windows")]
@@ -43,9 +83,14 @@ include!("window_utils.rs");
 use self::tab::{PdfTab, TabBar};
 use self::text_selection::copy_to_clipboard;
 use self::utils::{
-    display_file_name, ensure_pdfium_ready, load_display_images, load_document_summary,
+    display_file_name, downloaded_pdfium_libraries, ensure_pdfium_ready, is_damaged_document_error,
+    load_display_images, load_display_tiles, load_document_skeleton, load_document_summary,
+    record_pdfium_render_error, set_preferred_pdfium_library,
 };
 
+pub(crate) use self::pdf_edit::{merge_documents, split_document};
+pub(crate) use self::printing::print_document;
+pub(crate) use self::thumbnail_export::export_thumbnails;
 pub use self::utils::PageSummary;
 
 pub struct PdfViewer {
@@ -65,19 +110,98 @@ pub struct PdfViewer {
     bookmarks_store: Option<sled::Tree>,
     notes_store: Option<sled::Tree>,
     text_markups_store: Option<sled::Tree>,
+    stamps_store: Option<sled::Tree>,
+    shapes_store: Option<sled::Tree>,
     tab_layout_mode_store: Option<sled::Tree>,
     vertical_tab_bar_visible_store: Option<sled::Tree>,
     thumbnail_panel_visible_store: Option<sled::Tree>,
+    view_preferences_store: Option<sled::Tree>,
+    sessions_store: Option<sled::Tree>,
     last_window_size: Option<(f32, f32)>,
+    /// Scale factor of the monitor the window was last drawn on.
+    last_window_scale_factor: Option<f32>,
     theme_mode: ThemeMode,
     preferred_light_theme_name: Option<String>,
     preferred_dark_theme_name: Option<String>,
-    titlebar_preferences: TitleBarVisibilityPreferences,
+    titlebar_preferences: TitleBarPreferences,
     tab_layout_mode: TabLayoutMode,
     vertical_tab_bar_visible: bool,
     vertical_tab_bar_hovered: bool,
     thumbnail_panel_visible: bool,
+    sidebar_width: f32,
+    status_bar_visible: bool,
+    /// Keeps every search match highlighted on the pages, not just the current one.
+    search_highlight_all: bool,
+    /// Swaps the left and right page keys in comic archives, for manga.
+    comic_right_to_left: bool,
+    page_double_click_action: PageDoubleClickAction,
+    /// Mirrors the registry so the settings dialog does not query it on every render.
+    shell_open_with_registered: bool,
+    zen_mode: bool,
+    /// Whether the pointer at the top edge has brought the title bar back in zen mode.
+    zen_chrome_revealed: bool,
+    window_fullscreen: bool,
+    /// Set once the pointer has rested long enough in fullscreen to hide the title bar and cursor.
+    fullscreen_chrome_hidden: bool,
+    fullscreen_idle_epoch: u64,
+    presentation_active: bool,
+    /// Whether entering presentation mode switched the window to fullscreen.
+    presentation_owns_fullscreen: bool,
+    presentation_started_at: Option<Instant>,
+    presentation_timer_visible: bool,
+    presentation_auto_advance_secs: u8,
+    /// Bumped on every enter/exit so the timer tick task of an old session stops.
+    presentation_session: u64,
+    /// Bumped on every slide change so a pending auto-advance only fires once.
+    presentation_slide_epoch: u64,
+    presentation_pointer: PresentationPointer,
+    /// Where the left button is held down on a slide, following the mouse while it moves.
+    presentation_press_position: Option<Point<Pixels>>,
+    presentation_press_epoch: u64,
+    /// Set once a press has been held long enough to show the laser dot or spotlight.
+    presentation_pointer_visible: bool,
+    render_worker_count: usize,
+    render_engine: RenderEngine,
+    render_quality_percent: u8,
+    window_opacity_percent: u8,
+    /// The opacity the window background appearance was last switched for.
+    applied_window_opacity_percent: Option<u8>,
+    render_options: RenderOptions,
+    ui_font_size: u8,
+    ui_density: UiDensity,
+    /// Library file chosen in settings; `None` searches the usual locations.
+    pdfium_library_path: Option<PathBuf>,
+    /// Commands contributed by plugin manifests, read once at startup.
+    plugin_commands: Vec<plugins::PluginCommand>,
+    external_tools: Vec<ExternalTool>,
+    annotation_defaults: AnnotationDefaults,
+    /// Commands run from the command panel, most recent first.
+    command_history: Vec<CommandHistoryEntry>,
+    render_cache_enabled: bool,
+    render_cache_limit_mb: u64,
+    render_cache_usage_bytes: u64,
+    /// Render the active document into the disk cache while the app sits idle.
+    idle_prerender_enabled: bool,
+    /// Whether an idle pre-render loop is waiting or running.
+    idle_prerender_scheduled: bool,
+    idle_prerender_done: Option<idle_prerender::IdlePrerenderMarker>,
+    last_user_activity_at: Instant,
+    // (drag start x, width at drag start)
+    sidebar_resize_drag: Option<(f32, f32)>,
+    swipe_gesture: SwipeGesture,
+    presentation_wheel: SwipeGesture,
+    page_overview_open: bool,
+    page_overview_cell_width: f32,
+    page_overview_scroll: VirtualListScrollHandle,
+    page_overview_needs_scroll_to_active: bool,
+    debug_overlay_visible: bool,
+    debug_overlay_epoch: u64,
+    /// Most recent display renders as (page index, time per page, render width).
+    debug_render_timings: VecDeque<(usize, Duration, u32)>,
+    debug_frame_times: VecDeque<Instant>,
     recent_files: Vec<PathBuf>,
+    /// Named sets of open tabs, most recently saved first.
+    sessions: Vec<SavedSession>,
     recent_popup_open: bool,
     recent_popup_trigger_hovered: bool,
     recent_popup_tab_trigger_hovered: bool,
@@ -87,6 +211,17 @@ pub struct PdfViewer {
     bookmarks: Vec<BookmarkEntry>,
     markdown_notes: Vec<MarkdownNoteEntry>,
     text_markups: Vec<TextMarkupEntry>,
+    stamps: Vec<StampEntry>,
+    shapes: Vec<ShapeEntry>,
+    shape_tool: Option<ShapeKind>,
+    eraser: Option<EraserMode>,
+    erasing_page: Option<usize>,
+    shape_style: ShapeStyle,
+    shape_drag: Option<ShapeDrag>,
+    selected_shape_id: Option<u64>,
+    /// The document's shapes before the edit in progress, keyed by its path.
+    shape_edit_snapshot: Option<(PathBuf, Vec<ShapeEntry>)>,
+    shape_history: HashMap<PathBuf, ShapeHistory>,
     bookmark_popup_open: bool,
     bookmark_scope: BookmarkScope,
     bookmark_popup_trigger_hovered: bool,
@@ -107,8 +242,37 @@ pub struct PdfViewer {
     keymap_dialog_open: bool,
     keymap_dialog_window: Option<AnyWindowHandle>,
     keymap_dialog_session: u64,
+    diagnostics_dialog_open: bool,
+    diagnostics_dialog_window: Option<AnyWindowHandle>,
+    diagnostics_dialog_session: u64,
+    merge_dialog_open: bool,
+    merge_dialog_window: Option<AnyWindowHandle>,
+    merge_dialog_session: u64,
+    optimize_dialog_open: bool,
+    optimize_dialog_window: Option<AnyWindowHandle>,
+    optimize_dialog_session: u64,
+    watermark_dialog_open: bool,
+    watermark_dialog_window: Option<AnyWindowHandle>,
+    watermark_dialog_session: u64,
+    header_footer_dialog_open: bool,
+    header_footer_dialog_window: Option<AnyWindowHandle>,
+    header_footer_dialog_session: u64,
+    encrypt_dialog_open: bool,
+    encrypt_dialog_window: Option<AnyWindowHandle>,
+    encrypt_dialog_session: u64,
+    stamp_dialog_open: bool,
+    stamp_dialog_window: Option<AnyWindowHandle>,
+    stamp_dialog_session: u64,
     updater_state: UpdaterUiState,
+    update_channel: updater::UpdateChannel,
+    update_check_frequency: UpdateCheckFrequency,
+    startup_behavior: StartupBehavior,
+    /// The blank tab created at startup by `StartupBehavior::Empty`, which hides the recent
+    /// files list.
+    startup_blank_tab_id: Option<usize>,
+    update_download_cancelled: Arc<AtomicBool>,
     command_panel_open: bool,
+    load_error_dialog_tab_id: Option<usize>,
     command_panel_query: String,
     command_panel_selected_index: usize,
     tab_bar_scroll: ScrollHandle,
@@ -119,15 +283,27 @@ pub struct PdfViewer {
     recent_home_list_scroll: ScrollHandle,
     command_panel_input_state: Entity<InputState>,
     _command_panel_input_subscription: Subscription,
+    sidebar_search_input_state: Entity<InputState>,
+    sidebar_outline_filter_input_state: Entity<InputState>,
+    _sidebar_outline_filter_subscription: Subscription,
+    sidebar_outline_focus_handle: FocusHandle,
+    thumbnail_focus_handle: FocusHandle,
+    link_hints: Option<link_hints::LinkHintMode>,
+    tab_switcher: Option<tab_switcher::TabSwitcher>,
+    link_hint_epoch: u64,
     theme_color_select_state: Entity<SelectState<SearchableVec<SharedString>>>,
+    accent_color: Option<Hsla>,
     _theme_color_select_subscription: Subscription,
     _theme_registry_subscription: Subscription,
+    _window_bounds_subscription: Subscription,
     context_menu_open: bool,
     context_menu_position: Option<Point<Pixels>>,
     context_menu_tab_id: Option<usize>,
     context_menu_note_anchor: Option<MarkdownNoteAnchor>,
     context_menu_note_id: Option<u64>,
     context_menu_text_markup_id: Option<u64>,
+    context_menu_stamp_id: Option<u64>,
+    context_menu_shape_id: Option<u64>,
     text_selection_hover_menu_open: bool,
     text_selection_hover_menu_position: Option<Point<Pixels>>,
     text_selection_hover_menu_anchor: Option<MarkdownNoteAnchor>,
@@ -152,6 +328,11 @@ impl PdfViewer {
             LanguagePreference::System => system_language,
             LanguagePreference::ZhCn => Language::ZhCn,
             LanguagePreference::EnUs => Language::EnUs,
+            LanguagePreference::JaJp => Language::JaJp,
+            LanguagePreference::KoKr => Language::KoKr,
+            LanguagePreference::DeDe => Language::DeDe,
+            LanguagePreference::EsEs => Language::EsEs,
+            LanguagePreference::ArSa => Language::ArSa,
         }
     }
 
@@ -181,9 +362,13 @@ impl PdfViewer {
             bookmarks_store,
             notes_store,
             text_markups_store,
+            stamps_store,
+            shapes_store,
             tab_layout_mode_store,
             vertical_tab_bar_visible_store,
             thumbnail_panel_visible_store,
+            view_preferences_store,
+            sessions_store,
         ) = Self::open_persistent_stores();
         let db_path = Self::local_state_db_path();
         let db_usage_bytes = Self::directory_usage_bytes(&db_path);
@@ -195,6 +380,10 @@ impl PdfViewer {
             .as_ref()
             .map(Self::load_open_tabs_from_store)
             .unwrap_or_else(|| (Vec::new(), None));
+        let sessions = sessions_store
+            .as_ref()
+            .map(Self::load_sessions_from_store)
+            .unwrap_or_default();
         let titlebar_preferences = titlebar_preferences_store
             .as_ref()
             .map(Self::load_titlebar_preferences_from_store)
@@ -209,6 +398,9 @@ impl PdfViewer {
                     )
                 })
                 .unwrap_or_else(|| (ThemeMode::from(window.appearance()), None, None));
+        let accent_color = theme_preferences_store
+            .as_ref()
+            .and_then(Self::load_accent_color_from_store);
         let language_preference = theme_preferences_store
             .as_ref()
             .map(Self::load_language_preference_from_store)
@@ -226,10 +418,178 @@ impl PdfViewer {
             .as_ref()
             .map(Self::load_text_markups_from_store)
             .unwrap_or_default();
+        let stamps = stamps_store
+            .as_ref()
+            .map(Self::load_stamps_from_store)
+            .unwrap_or_default();
+        let shapes = shapes_store
+            .as_ref()
+            .map(Self::load_shapes_from_store)
+            .unwrap_or_default();
         let tab_layout_mode = tab_layout_mode_store
             .as_ref()
             .map(Self::load_tab_layout_mode_from_store)
             .unwrap_or_default();
+        let sidebar_width = view_preferences_store
+            .as_ref()
+            .map(Self::load_sidebar_width_from_store)
+            .unwrap_or(SIDEBAR_WIDTH);
+        let status_bar_visible = view_preferences_store
+            .as_ref()
+            .map(|store| {
+                Self::decode_stored_bool(
+                    store.get(VIEW_PREFERENCES_KEY_STATUS_BAR_VISIBLE).ok().flatten(),
+                    false,
+                )
+            })
+            .unwrap_or(false);
+        let search_highlight_all = view_preferences_store
+            .as_ref()
+            .map(|store| {
+                Self::decode_stored_bool(
+                    store.get(VIEW_PREFERENCES_KEY_SEARCH_HIGHLIGHT_ALL).ok().flatten(),
+                    false,
+                )
+            })
+            .unwrap_or(false);
+        let comic_right_to_left = view_preferences_store
+            .as_ref()
+            .map(|store| {
+                Self::decode_stored_bool(
+                    store.get(VIEW_PREFERENCES_KEY_COMIC_RIGHT_TO_LEFT).ok().flatten(),
+                    false,
+                )
+            })
+            .unwrap_or(false);
+        let page_double_click_action = view_preferences_store
+            .as_ref()
+            .and_then(|store| {
+                store
+                    .get(VIEW_PREFERENCES_KEY_PAGE_DOUBLE_CLICK)
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|raw| PageDoubleClickAction::from_stored(&raw))
+            .unwrap_or_default();
+        let zen_mode = view_preferences_store
+            .as_ref()
+            .map(|store| {
+                Self::decode_stored_bool(
+                    store.get(VIEW_PREFERENCES_KEY_ZEN_MODE).ok().flatten(),
+                    false,
+                )
+            })
+            .unwrap_or(false);
+        let presentation_timer_visible = view_preferences_store
+            .as_ref()
+            .map(|store| {
+                Self::decode_stored_bool(
+                    store.get(VIEW_PREFERENCES_KEY_PRESENTATION_TIMER).ok().flatten(),
+                    true,
+                )
+            })
+            .unwrap_or(true);
+        let presentation_auto_advance_secs = view_preferences_store
+            .as_ref()
+            .map(Self::load_presentation_auto_advance_from_store)
+            .unwrap_or(0);
+        let presentation_pointer = view_preferences_store
+            .as_ref()
+            .and_then(|store| {
+                store
+                    .get(VIEW_PREFERENCES_KEY_PRESENTATION_POINTER)
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|raw| PresentationPointer::from_stored(&raw))
+            .unwrap_or_default();
+        let update_channel = view_preferences_store
+            .as_ref()
+            .and_then(|store| {
+                store
+                    .get(VIEW_PREFERENCES_KEY_UPDATE_CHANNEL)
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|raw| updater::UpdateChannel::from_stored(&raw))
+            .unwrap_or_default();
+        let update_check_frequency = view_preferences_store
+            .as_ref()
+            .and_then(|store| {
+                store
+                    .get(VIEW_PREFERENCES_KEY_UPDATE_CHECK_FREQUENCY)
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|raw| UpdateCheckFrequency::from_stored(&raw))
+            .unwrap_or_default();
+        let startup_behavior = view_preferences_store
+            .as_ref()
+            .and_then(|store| {
+                store
+                    .get(VIEW_PREFERENCES_KEY_STARTUP_BEHAVIOR)
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|raw| StartupBehavior::from_stored(&raw))
+            .unwrap_or_default();
+        let render_worker_count = view_preferences_store
+            .as_ref()
+            .map(Self::load_render_worker_count_from_store)
+            .unwrap_or(RENDER_WORKERS_DEFAULT);
+        let render_quality_percent = view_preferences_store
+            .as_ref()
+            .map(Self::load_render_quality_from_store)
+            .unwrap_or(RENDER_QUALITY_DEFAULT_PERCENT);
+        let window_opacity_percent = window_size_store
+            .as_ref()
+            .map(Self::load_window_opacity_from_store)
+            .unwrap_or(WINDOW_OPACITY_DEFAULT_PERCENT);
+        let render_options = view_preferences_store
+            .as_ref()
+            .map(Self::load_render_options_from_store)
+            .unwrap_or_default();
+        let (ui_font_size, ui_density) = view_preferences_store
+            .as_ref()
+            .map(Self::load_ui_preferences_from_store)
+            .unwrap_or((UI_FONT_SIZE_DEFAULT, UiDensity::default()));
+        let render_engine = view_preferences_store
+            .as_ref()
+            .and_then(|store| store.get(VIEW_PREFERENCES_KEY_RENDER_ENGINE).ok().flatten())
+            .and_then(|raw| RenderEngine::from_stored(&raw))
+            .unwrap_or_default();
+        let pdfium_library_path = view_preferences_store
+            .as_ref()
+            .and_then(|store| store.get(VIEW_PREFERENCES_KEY_PDFIUM_LIBRARY).ok().flatten())
+            .and_then(|raw| String::from_utf8(raw.to_vec()).ok())
+            .filter(|raw| !raw.is_empty())
+            .map(PathBuf::from);
+        set_preferred_pdfium_library(pdfium_library_path.clone());
+        let external_tools = view_preferences_store
+            .as_ref()
+            .and_then(|store| store.get(VIEW_PREFERENCES_KEY_EXTERNAL_TOOLS).ok().flatten())
+            .and_then(|raw| serde_json::from_slice::<Vec<ExternalTool>>(&raw).ok())
+            .unwrap_or_default();
+        let command_history = view_preferences_store
+            .as_ref()
+            .and_then(|store| store.get(VIEW_PREFERENCES_KEY_COMMAND_HISTORY).ok().flatten())
+            .and_then(|raw| serde_json::from_slice::<Vec<CommandHistoryEntry>>(&raw).ok())
+            .unwrap_or_default();
+        let annotation_defaults = view_preferences_store
+            .as_ref()
+            .and_then(|store| {
+                store
+                    .get(VIEW_PREFERENCES_KEY_ANNOTATION_DEFAULTS)
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|raw| serde_json::from_slice::<AnnotationDefaults>(&raw).ok())
+            .unwrap_or_default();
+        let (render_cache_enabled, render_cache_limit_mb, idle_prerender_enabled) =
+            view_preferences_store
+                .as_ref()
+                .map(Self::load_render_cache_preferences_from_store)
+                .unwrap_or((false, RENDER_CACHE_LIMIT_DEFAULT_MB, false));
         let command_panel_input_state = cx.new(|cx| {
             InputState::new(window, cx).placeholder(I18n::new(language).command_panel_search_hint)
         });
@@ -251,6 +611,25 @@ impl PdfViewer {
                 }
             },
         );
+        let sidebar_search_input_state = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(I18n::new(language).sidebar_search_placeholder)
+        });
+        let sidebar_outline_filter_input_state = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(I18n::new(language).sidebar_outline_filter_placeholder)
+        });
+        let sidebar_outline_filter_subscription = cx.subscribe(
+            &sidebar_outline_filter_input_state,
+            |this, _, event: &InputEvent, cx| {
+                if !matches!(event, InputEvent::Change) {
+                    return;
+                }
+                if let Some(tab) = this.active_tab() {
+                    tab.outline_scroll.scroll_to_item(0);
+                }
+                cx.notify();
+            },
+        );
         let theme_color_select_state = cx.new(|cx| {
             SelectState::new(
                 SearchableVec::new(Vec::<SharedString>::new()),
@@ -276,20 +655,29 @@ impl PdfViewer {
                 this.sync_theme_color_select(window, cx);
                 cx.notify();
             });
+        let window_bounds_subscription = cx.observe_window_bounds(window, |this, window, cx| {
+            this.save_window_origin(window, cx);
+        });
 
         let mut tab_bar = TabBar::new();
         let mut tabs_to_restore = Vec::new();
-        for path in saved_open_tab_paths {
-            if !path.exists() {
-                continue;
+        if startup_behavior == StartupBehavior::RestoreTabs {
+            for path in saved_open_tab_paths {
+                if !path.exists() {
+                    continue;
+                }
+                let tab_id = tab_bar.create_tab_with_path(path.clone(), Vec::new());
+                tabs_to_restore.push((tab_id, path));
             }
-            let tab_id = tab_bar.create_tab_with_path(path.clone(), Vec::new());
-            tabs_to_restore.push((tab_id, path));
         }
 
+        let mut startup_blank_tab_id = None;
         if tabs_to_restore.is_empty() {
             // 没有可恢复标签时，创建第一个空标签页
-            tab_bar.create_tab();
+            let tab_id = tab_bar.create_tab();
+            if startup_behavior == StartupBehavior::Empty {
+                startup_blank_tab_id = Some(tab_id);
+            }
         } else {
             let target_active_index = saved_active_open_tab_index
                 .unwrap_or_else(|| tabs_to_restore.len().saturating_sub(1))
@@ -316,10 +704,15 @@ impl PdfViewer {
             bookmarks_store,
             notes_store,
             text_markups_store,
+            stamps_store,
+            shapes_store,
             tab_layout_mode_store,
             vertical_tab_bar_visible_store: vertical_tab_bar_visible_store.clone(),
             thumbnail_panel_visible_store: thumbnail_panel_visible_store.clone(),
+            view_preferences_store,
+            sessions_store,
             last_window_size: None,
+            last_window_scale_factor: None,
             theme_mode,
             preferred_light_theme_name,
             preferred_dark_theme_name,
@@ -334,7 +727,61 @@ impl PdfViewer {
                 .as_ref()
                 .map(|store| PdfViewer::decode_stored_bool(store.get(THUMBNAIL_PANEL_VISIBLE_KEY).ok().flatten(), true))
                 .unwrap_or(true),
+            sidebar_width,
+            status_bar_visible,
+            search_highlight_all,
+            comic_right_to_left,
+            page_double_click_action,
+            shell_open_with_registered: shell_integration::is_registered(),
+            zen_mode,
+            zen_chrome_revealed: false,
+            window_fullscreen: false,
+            fullscreen_chrome_hidden: false,
+            fullscreen_idle_epoch: 0,
+            presentation_active: false,
+            presentation_owns_fullscreen: false,
+            presentation_started_at: None,
+            presentation_timer_visible,
+            presentation_auto_advance_secs,
+            presentation_session: 0,
+            presentation_slide_epoch: 0,
+            presentation_pointer,
+            presentation_press_position: None,
+            presentation_press_epoch: 0,
+            presentation_pointer_visible: false,
+            render_worker_count,
+            render_engine,
+            render_quality_percent,
+            window_opacity_percent,
+            applied_window_opacity_percent: None,
+            render_options,
+            ui_font_size,
+            ui_density,
+            pdfium_library_path,
+            plugin_commands: plugins::load_plugin_commands(),
+            external_tools,
+            annotation_defaults: annotation_defaults.clone(),
+            command_history,
+            render_cache_enabled,
+            render_cache_limit_mb,
+            render_cache_usage_bytes: 0,
+            idle_prerender_enabled,
+            idle_prerender_scheduled: false,
+            idle_prerender_done: None,
+            last_user_activity_at: Instant::now(),
+            sidebar_resize_drag: None,
+            swipe_gesture: SwipeGesture::default(),
+            presentation_wheel: SwipeGesture::default(),
+            page_overview_open: false,
+            page_overview_cell_width: PAGE_OVERVIEW_CELL_WIDTH,
+            page_overview_scroll: VirtualListScrollHandle::new(),
+            page_overview_needs_scroll_to_active: false,
+            debug_overlay_visible: false,
+            debug_overlay_epoch: 0,
+            debug_render_timings: VecDeque::new(),
+            debug_frame_times: VecDeque::new(),
             recent_files,
+            sessions,
             recent_popup_open: false,
             recent_popup_trigger_hovered: false,
             recent_popup_tab_trigger_hovered: false,
@@ -344,6 +791,16 @@ impl PdfViewer {
             bookmarks,
             markdown_notes,
             text_markups,
+            stamps,
+            shapes,
+            shape_tool: None,
+            eraser: None,
+            erasing_page: None,
+            shape_style: ShapeStyle::default(),
+            shape_drag: None,
+            selected_shape_id: None,
+            shape_edit_snapshot: None,
+            shape_history: HashMap::new(),
             bookmark_popup_open: false,
             bookmark_scope: BookmarkScope::CurrentPdf,
             bookmark_popup_trigger_hovered: false,
@@ -364,8 +821,35 @@ impl PdfViewer {
             keymap_dialog_open: false,
             keymap_dialog_window: None,
             keymap_dialog_session: 0,
+            diagnostics_dialog_open: false,
+            diagnostics_dialog_window: None,
+            diagnostics_dialog_session: 0,
+            merge_dialog_open: false,
+            merge_dialog_window: None,
+            merge_dialog_session: 0,
+            optimize_dialog_open: false,
+            optimize_dialog_window: None,
+            optimize_dialog_session: 0,
+            watermark_dialog_open: false,
+            watermark_dialog_window: None,
+            watermark_dialog_session: 0,
+            header_footer_dialog_open: false,
+            header_footer_dialog_window: None,
+            header_footer_dialog_session: 0,
+            encrypt_dialog_open: false,
+            encrypt_dialog_window: None,
+            encrypt_dialog_session: 0,
+            stamp_dialog_open: false,
+            stamp_dialog_window: None,
+            stamp_dialog_session: 0,
             updater_state: UpdaterUiState::Idle,
+            update_channel,
+            update_check_frequency,
+            startup_behavior,
+            startup_blank_tab_id,
+            update_download_cancelled: Arc::new(AtomicBool::new(false)),
             command_panel_open: false,
+            load_error_dialog_tab_id: None,
             command_panel_query: String::new(),
             command_panel_selected_index: 0,
             tab_bar_scroll: ScrollHandle::new(),
@@ -376,19 +860,31 @@ impl PdfViewer {
             recent_home_list_scroll: ScrollHandle::new(),
             command_panel_input_state,
             _command_panel_input_subscription: command_panel_input_subscription,
+            sidebar_search_input_state,
+            sidebar_outline_filter_input_state,
+            _sidebar_outline_filter_subscription: sidebar_outline_filter_subscription,
+            sidebar_outline_focus_handle: cx.focus_handle(),
+            thumbnail_focus_handle: cx.focus_handle(),
+            link_hints: None,
+            tab_switcher: None,
+            link_hint_epoch: 0,
             theme_color_select_state,
+            accent_color,
             _theme_color_select_subscription: theme_color_select_subscription,
             _theme_registry_subscription: theme_registry_subscription,
+            _window_bounds_subscription: window_bounds_subscription,
             context_menu_open: false,
             context_menu_position: None,
             context_menu_tab_id: None,
             context_menu_note_anchor: None,
             context_menu_note_id: None,
             context_menu_text_markup_id: None,
+            context_menu_stamp_id: None,
+            context_menu_shape_id: None,
             text_selection_hover_menu_open: false,
             text_selection_hover_menu_position: None,
             text_selection_hover_menu_anchor: None,
-            text_selection_markup_color: TextMarkupColor::Yellow,
+            text_selection_markup_color: annotation_defaults.highlight_color,
             hovered_markdown_note_id: None,
             hovered_text_markup_id: None,
             hovered_tab_id: None,
@@ -404,13 +900,24 @@ impl PdfViewer {
 
         viewer.apply_theme_preferences(Some(window), cx);
         viewer.sync_theme_color_select(window, cx);
+        viewer.refresh_app_menus(cx);
         viewer.persist_open_tabs();
         if !tabs_to_restore.is_empty()
             && let Err(err) = ensure_pdfium_ready(language)
         {
-            crate::debug_log!("[pdfium] pre-init before restoring tabs failed: {}", err);
+            crate::debug_log!(@warn, "[pdfium] pre-init before restoring tabs failed: {}", err);
         }
         viewer.restore_open_tabs(tabs_to_restore, cx);
+        viewer.schedule_startup_update_check(cx);
+        cx.background_executor()
+            .spawn(async { formats::prune_stale_copies() })
+            .detach();
+        cx.spawn_in(window, async move |this, cx| {
+            let _ = this.update_in(cx, |this, window, cx| {
+                this.offer_pending_crash_report(window, cx);
+            });
+        })
+        .detach();
         viewer
     }
 }
@@ -449,8 +956,10 @@ impl Render for PdfViewer {
             self.needs_root_refocus = false;
             window.focus(&self.focus_handle);
         }
+        self.focus_tab_switcher_input(window, cx);
 
-        window.set_rem_size(cx.theme().font_size);
+        window.set_rem_size(px(self.ui_font_size as f32));
+        self.record_debug_frame();
 
         let bounds = window.bounds();
         let current_size = (f32::from(bounds.size.width), f32::from(bounds.size.height));
@@ -462,6 +971,28 @@ impl Render for PdfViewer {
             }
             window_size_changed = true;
         }
+        if self.applied_window_opacity_percent != Some(self.window_opacity_percent) {
+            self.applied_window_opacity_percent = Some(self.window_opacity_percent);
+            window.set_background_appearance(if self.window_opacity_percent < 100 {
+                WindowBackgroundAppearance::Transparent
+            } else {
+                WindowBackgroundAppearance::Opaque
+            });
+        }
+        let fullscreen = window.is_fullscreen();
+        if self.window_fullscreen != fullscreen {
+            self.window_fullscreen = fullscreen;
+            self.fullscreen_chrome_hidden = false;
+            self.schedule_fullscreen_idle_hide(cx);
+        }
+        let scale_factor = window.scale_factor();
+        if self
+            .last_window_scale_factor
+            .replace(scale_factor)
+            .is_some_and(|previous| previous != scale_factor)
+        {
+            self.on_window_scale_factor_changed(scale_factor);
+        }
 
         let (
             page_count,
@@ -470,7 +1001,7 @@ impl Render for PdfViewer {
             _file_name,
             thumbnail_sizes,
             display_sizes,
-            _display_base_width,
+            display_base_width,
             display_panel_width,
         ) = {
             let active_tab = self.active_tab();
@@ -514,6 +1045,7 @@ impl Render for PdfViewer {
         };
 
         let zoom_label: SharedString = format!("{:.0}%", zoom * 100.0).into();
+        let rtl = self.language.is_rtl();
 
         // 更新当前标签页的显示滚动偏移
         let target_width = if let Some(tab) = self.active_tab() {
@@ -523,7 +1055,18 @@ impl Render for PdfViewer {
         };
         let mut display_layout_changed = false;
         let mut page_to_restore_after_layout_change = None;
+        let mut display_render_width_to_schedule = None;
+        let pending_scroll_top = self.active_tab().and_then(|tab| {
+            let fraction = tab.pending_page_fraction?;
+            Some(self.display_scroll_top_in_page(
+                &tab.pages,
+                tab.active_page,
+                fraction,
+                display_base_width,
+            ))
+        });
         if let Some(tab) = self.active_tab_mut() {
+            tab.last_display_base_width = display_base_width;
             let target_width_changed = tab.last_display_target_width != target_width;
             display_layout_changed = window_size_changed || target_width_changed;
 
@@ -534,6 +1077,12 @@ impl Render for PdfViewer {
 
             if target_width_changed {
                 tab.last_display_target_width = target_width;
+                if tab.display_render_target_width == 0 {
+                    tab.display_render_target_width = target_width;
+                } else {
+                    // Zoom steps often come in bursts; only the final width gets rendered.
+                    display_render_width_to_schedule = Some(target_width);
+                }
             }
 
             if display_layout_changed {
@@ -549,8 +1098,18 @@ impl Render for PdfViewer {
                     tab.last_display_scroll_offset = Some(tab.display_scroll.offset());
                     page_to_restore_after_layout_change = Some(keep_page);
                 }
+            } else if let Some(scroll_top) = pending_scroll_top {
+                tab.pending_page_fraction = None;
+                tab.suppress_display_scroll_sync_once = true;
+                let offset = tab.display_scroll.offset();
+                tab.display_scroll
+                    .set_offset(point(offset.x, px(-scroll_top)));
+                tab.last_display_scroll_offset = Some(tab.display_scroll.offset());
             }
         }
+        if let Some(target_width) = display_render_width_to_schedule {
+            self.schedule_display_render_width_update(target_width, cx);
+        }
         if let Some(keep_page) = page_to_restore_after_layout_change {
             self.schedule_restore_current_page_after_layout_change(keep_page, cx);
         }
@@ -562,9 +1121,256 @@ impl Render for PdfViewer {
         let context_menu = self.render_context_menu(cx);
         let drag_tab_preview = self.render_drag_tab_preview(cx);
         let command_panel = self.render_command_panel(cx);
+        let tab_switcher = self.render_tab_switcher(cx);
+        let load_error_dialog = self.render_load_error_dialog(cx);
+        let debug_overlay = self.render_debug_overlay(cx);
+        let presentation = if self.presentation_active && page_count > 0 {
+            Some(self.render_presentation(window, cx).into_any_element())
+        } else {
+            None
+        };
+        let presenting = presentation.is_some();
+        let page_overview = if self.page_overview_open && page_count > 0 {
+            let available_width = self.display_available_width(window);
+            Some(
+                self.render_page_overview(available_width, cx)
+                    .into_any_element(),
+            )
+        } else {
+            None
+        };
+
+        let title_bar = div()
+            .id("title-bar")
+            .w_full()
+            .v_flex()
+            .bg(cx.theme().title_bar)
+            .child(
+                div()
+                    .id("title-bar-top")
+                    .h(px(TITLE_BAR_HEIGHT))
+                    .w_full()
+                    .relative()
+                    .border_b_1()
+                    .border_color(cx.theme().title_bar_border)
+                    .when(cfg!(target_os = "macos"), |this| {
+                        this.child(
+                            div()
+                                .id("title-drag-area")
+                                .absolute()
+                                .top_0()
+                                .left_0()
+                                .right_0()
+                                .bottom_0()
+                                .on_double_click(|_, window, _| window.titlebar_double_click())
+                                .window_control_area(WindowControlArea::Drag),
+                        )
+                    })
+                    .child(
+                        div()
+                            .id("title-bar-foreground")
+                            .h_full()
+                            .w_full()
+                            .flex()
+                            .when(rtl, |this| this.flex_row_reverse())
+                            .items_center()
+                            .justify_between()
+                            .child(
+                                div()
+                                    .id("title-nav-host")
+                                    .h_full()
+                                    .flex_1()
+                                    .pl(px(TITLE_BAR_CONTENT_LEFT_PADDING))
+                                    .pr_1()
+                                    .flex()
+                                    .when(rtl, |this| this.flex_row_reverse())
+                                    .items_center()
+                                    .gap_2()
+                                    .when(self.tab_layout_mode == TabLayoutMode::Vertical, |this| {
+                                        this.child(
+                                            Button::new("vertical-tab-toggle-button")
+                                                .xsmall()
+                                                .icon(
+                                                    Icon::new(
+                                                        crate::icons::IconName::PanelLeftDashed,
+                                                    )
+                                                    .size_4()
+                                                    .text_color(if self.vertical_tab_bar_visible {
+                                                        cx.theme().primary
+                                                    } else {
+                                                        cx.theme().muted_foreground
+                                                    }),
+                                                )
+                                                .map(|this| {
+                                                    if self.vertical_tab_bar_visible {
+                                                        this.bg(cx.theme().primary.opacity(0.15))
+                                                    } else {
+                                                        this
+                                                    }
+                                                })
+                                                .on_click(cx.listener(|this, _, _, cx| {
+                                                    this.set_vertical_tab_bar_visible(
+                                                        !this.vertical_tab_bar_visible,
+                                                        cx,
+                                                    );
+                                                })),
+                                        )
+                                    })
+                                    .child(
+                                        Button::new("thumbnail-panel-toggle-button")
+                                            .xsmall()
+                                            .icon(
+                                                Icon::new(crate::icons::IconName::ScanEye)
+                                                    .size_4()
+                                                    .text_color(if self.thumbnail_panel_visible {
+                                                        cx.theme().primary
+                                                    } else {
+                                                        cx.theme().muted_foreground
+                                                    }),
+                                            )
+                                            .map(|this| {
+                                                if self.thumbnail_panel_visible {
+                                                    this.bg(cx.theme().primary.opacity(0.15))
+                                                } else {
+                                                    this
+                                                }
+                                            })
+                                            .on_click(cx.listener(|this, _, _, cx| {
+                                                this.toggle_thumbnail_panel(cx);
+                                            })),
+                                    )
+                                    .child(self.render_menu_bar(
+                                        page_count,
+                                        current_page_num,
+                                        zoom_label.clone(),
+                                        cx,
+                                    ))
+                                    .when(self.available_update_version().is_some(), |this| {
+                                        this.child(
+                                            div()
+                                                .id("update-available-badge")
+                                                .flex_shrink_0()
+                                                .px_2()
+                                                .rounded_full()
+                                                .bg(cx.theme().primary.opacity(0.15))
+                                                .text_xs()
+                                                .text_color(cx.theme().primary)
+                                                .cursor_pointer()
+                                                .on_click(cx.listener(|this, _, _, cx| {
+                                                    this.open_about_dialog(cx);
+                                                }))
+                                                .child(self.i18n().update_available_badge),
+                                        )
+                                    }),
+                            )
+                            .when(!cfg!(target_os = "macos"), |this| {
+                                this.child(
+                                    div()
+                                        .id("title-drag-area")
+                                        .h_full()
+                                        .w(px(24.))
+                                        .flex_shrink_0()
+                                        .map(|this| {
+                                            let should_move = Rc::new(Cell::new(false));
+                                            this.on_double_click(|_, window, _| {
+                                                window.zoom_window()
+                                            })
+                                            .on_mouse_down(MouseButton::Left, {
+                                                let should_move = should_move.clone();
+                                                move |_, _, _| {
+                                                    should_move.set(true);
+                                                }
+                                            })
+                                            .on_mouse_down_out({
+                                                let should_move = should_move.clone();
+                                                move |_, _, _| {
+                                                    should_move.set(false);
+                                                }
+                                            })
+                                            .on_mouse_up(MouseButton::Left, {
+                                                let should_move = should_move.clone();
+                                                move |_, _, _| {
+                                                    should_move.set(false);
+                                                }
+                                            })
+                                            .on_mouse_move({
+                                                let should_move = should_move.clone();
+                                                move |_, window, _| {
+                                                    if should_move.get() {
+                                                        should_move.set(false);
+                                                        window.start_window_move();
+                                                    }
+                                                }
+                                            })
+                                            .window_control_area(WindowControlArea::Drag)
+                                        }),
+                                )
+                            })
+                            .when(!cfg!(target_os = "macos"), |this| {
+                                this.child(
+                                    div()
+                                        .h_full()
+                                        .pr_1()
+                                        .flex()
+                                        .items_center()
+                                        .gap_1()
+                                        .child(
+                                            Button::new("window-minimize")
+                                                .ghost()
+                                                .small()
+                                                .icon(
+                                                    Icon::new(
+                                                        crate::icons::IconName::WindowMinimize,
+                                                    )
+                                                    .text_color(cx.theme().foreground),
+                                                )
+                                                .on_click(|_, window, _| window.minimize_window()),
+                                        )
+                                        .child(
+                                            Button::new("window-maximize")
+                                                .ghost()
+                                                .small()
+                                                .icon(
+                                                    Icon::new(if window.is_maximized() {
+                                                        crate::icons::IconName::WindowRestore
+                                                    } else {
+                                                        crate::icons::IconName::WindowMaximize
+                                                    })
+                                                    .text_color(cx.theme().foreground),
+                                                )
+                                                .on_click(|_, window, _| {
+                                                    zoom_or_restore_window(window)
+                                                }),
+                                        )
+                                        .child(
+                                            Button::new("window-close")
+                                                .ghost()
+                                                .small()
+                                                .icon(
+                                                    Icon::new(crate::icons::IconName::WindowClose)
+                                                        .text_color(cx.theme().foreground),
+                                                )
+                                                .on_click(|_, window, _| window.remove_window()),
+                                        ),
+                                )
+                            }),
+                    ),
+            );
+
+        // In zen mode the title bar only shows as an overlay while the pointer is at the top edge.
+        let (title_bar, zen_chrome) = if self.title_bar_in_layout() {
+            (Some(title_bar), None)
+        } else if self.zen_mode && self.zen_chrome_revealed {
+            (None, Some(title_bar))
+        } else {
+            (None, None)
+        };
 
         div()
             .size_full()
+            .when(self.window_opacity_percent < 100, |this| {
+                this.opacity(f32::from(self.window_opacity_percent) / 100.0)
+            })
             .on_action(cx.listener(|this, _: &ShowAboutMenu, _, cx| {
                 this.open_about_dialog(cx);
             }))
@@ -577,31 +1383,76 @@ impl Render for PdfViewer {
             }))
             .on_action(cx.listener(|this, _: &EnableLoggingMenu, _, cx| {
                 if crate::logger::enable_file_logging() {
-                    configure_app_menus(cx, this.i18n());
+                    this.refresh_app_menus(cx);
                 }
             }))
             .on_action(cx.listener(|this, _: &DisableLoggingMenu, _, cx| {
                 crate::logger::disable_file_logging();
-                configure_app_menus(cx, this.i18n());
+                this.refresh_app_menus(cx);
             }))
             .on_action(cx.listener(|this, _: &OpenLogsMenu, _, _| {
                 this.open_logs_directory();
             }))
+            .on_action(cx.listener(|this, action: &OpenRecentFileMenu, _, cx| {
+                this.open_recent_pdf(action.path.clone(), cx);
+            }))
+            .on_action(cx.listener(|this, _: &ClearRecentFilesMenu, _, cx| {
+                this.clear_recent_files(cx);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleThumbnailPanelMenu, _, cx| {
+                this.toggle_thumbnail_panel(cx);
+            }))
+            .on_action(cx.listener(|this, _: &TogglePageOverviewMenu, _, cx| {
+                this.toggle_page_overview(cx);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleStatusBarMenu, _, cx| {
+                this.toggle_status_bar(cx);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleGrayscaleMenu, _, cx| {
+                this.toggle_grayscale(cx);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleZenModeMenu, _, cx| {
+                this.toggle_zen_mode(cx);
+            }))
+            .on_action(cx.listener(|this, _: &ToggleFullscreenMenu, window, _| {
+                this.toggle_fullscreen(window);
+            }))
+            .on_action(cx.listener(|this, _: &TogglePresentationMenu, window, cx| {
+                this.toggle_presentation(window, cx);
+            }))
+            .on_action(cx.listener(|this, _: &FirstPageMenu, _, cx| {
+                this.first_page(cx);
+            }))
+            .on_action(cx.listener(|this, _: &PreviousPageMenu, _, cx| {
+                this.prev_page(cx);
+            }))
+            .on_action(cx.listener(|this, _: &NextPageMenu, _, cx| {
+                this.next_page(cx);
+            }))
+            .on_action(cx.listener(|this, _: &LastPageMenu, _, cx| {
+                this.last_page(cx);
+            }))
             .child(
                 div()
                     .v_flex()
                     .size_full()
                     .bg(cx.theme().background)
                     .relative()
+                    .when(rtl, |this| this.text_right())
+                    .when(self.fullscreen_chrome_hidden, |this| {
+                        this.cursor(CursorStyle::None)
+                    })
                     .track_focus(&self.focus_handle)
                     .capture_key_down(cx.listener(
                         |this, event: &gpui::KeyDownEvent, window, cx| {
+                            this.note_user_activity(cx);
                             this.handle_key_down(event, window, cx);
                         },
                     ))
                     .on_mouse_down(
                         MouseButton::Left,
                         cx.listener(|this, _, window, cx| {
+                            this.note_user_activity(cx);
                             this.close_context_menu(cx);
                             this.close_text_selection_hover_menu(cx);
                             this.close_bookmark_popup(cx);
@@ -609,281 +1460,89 @@ impl Render for PdfViewer {
                         }),
                     )
                     .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, _, cx| {
+                        this.update_sidebar_resize(event.position, cx);
                         this.update_drag_mouse_position(event.position, cx);
+                        this.update_zen_chrome_reveal(event.position, cx);
+                        this.note_fullscreen_pointer_activity(cx);
+                        this.note_user_activity(cx);
                     }))
                     .on_mouse_up(
                         MouseButton::Left,
                         cx.listener(|this, _, _, cx| {
+                            this.finish_sidebar_resize(cx);
                             this.finish_tab_drag(cx);
                         }),
                     )
-                    .child(
-                        div()
-                            .id("title-bar")
-                            .w_full()
-                            .v_flex()
-                            .bg(cx.theme().title_bar)
-                            .child(
-                                div()
-                                    .id("title-bar-top")
-                                    .h(px(TITLE_BAR_HEIGHT))
-                                    .w_full()
-                                    .relative()
-                                    .border_b_1()
-                                    .border_color(cx.theme().title_bar_border)
-                                    .when(cfg!(target_os = "macos"), |this| {
-                                        this.child(
-                                            div()
-                                                .id("title-drag-area")
-                                                .absolute()
-                                                .top_0()
-                                                .left_0()
-                                                .right_0()
-                                                .bottom_0()
-                                                .on_double_click(|_, window, _| {
-                                                    window.titlebar_double_click()
-                                                })
-                                                .window_control_area(WindowControlArea::Drag),
-                                        )
-                                    })
-                                    .child(
-                                        div()
-                                            .id("title-bar-foreground")
-                                            .h_full()
-                                            .w_full()
-                                            .flex()
-                                            .items_center()
-                                            .justify_between()
-                                            .child(
-                                                div()
-                                                    .id("title-nav-host")
-                                                    .h_full()
-                                                    .flex_1()
-                                                    .pl(px(TITLE_BAR_CONTENT_LEFT_PADDING))
-                                                    .pr_1()
-                                                    .flex()
-                                                    .items_center()
-                                                    .gap_2()
-                                                    .when(
-                                                        self.tab_layout_mode == TabLayoutMode::Vertical,
-                                                        |this| {
-                                                            this.child(
-                                                                Button::new("vertical-tab-toggle-button")
-                                                                    .xsmall()
-                                                                    .icon(
-                                                                        Icon::new(
-                                                                            crate::icons::IconName::PanelLeftDashed,
-                                                                        )
-                                                                        .size_4()
-                                                                        .text_color(
-                                                                            if self.vertical_tab_bar_visible {
-                                                                                cx.theme().primary
-                                                                            } else {
-                                                                                cx.theme().muted_foreground
-                                                                            },
-                                                                        ),
-                                                                    )
-                                                                    .map(|this| {
-                                                                        if self.vertical_tab_bar_visible {
-                                                                            this.bg(cx.theme().primary.opacity(0.15))
-                                                                        } else {
-                                                                            this
-                                                                        }
-                                                                    })
-                                                                    .on_click(cx.listener(
-                                                                        |this, _, _, cx| {
-                                                                            this.set_vertical_tab_bar_visible(
-                                                                                !this.vertical_tab_bar_visible,
-                                                                                cx,
-                                                                            );
-                                                                        },
-                                                                    )),
-                                                            )
-                                                        },
-                                                    )
-                                                    .child(
-                                                        Button::new("thumbnail-panel-toggle-button")
-                                                            .xsmall()
-                                                            .icon(
-                                                                Icon::new(
-                                                                    crate::icons::IconName::ScanEye,
-                                                                )
-                                                                .size_4()
-                                                                .text_color(
-                                                                    if self.thumbnail_panel_visible {
-                                                                        cx.theme().primary
-                                                                    } else {
-                                                                        cx.theme().muted_foreground
-                                                                    },
-                                                                ),
-                                                            )
-                                                            .map(|this| {
-                                                                if self.thumbnail_panel_visible {
-                                                                    this.bg(cx.theme().primary.opacity(0.15))
-                                                                } else {
-                                                                    this
-                                                                }
-                                                            })
-                                                            .on_click(cx.listener(
-                                                                |this, _, _, cx| {
-                                                                    this.toggle_thumbnail_panel(cx);
-                                                                },
-                                                            )),
-                                                    )
-                                                    .child(self.render_menu_bar(
-                                                        page_count,
-                                                        current_page_num,
-                                                        zoom_label,
-                                                        self.titlebar_preferences.show_navigation,
-                                                        self.titlebar_preferences.show_zoom,
-                                                        cx,
-                                                    )),
-                                            )
-                                            .when(!cfg!(target_os = "macos"), |this| {
-                                                this.child(
-                                                    div()
-                                                        .id("title-drag-area")
-                                                        .h_full()
-                                                        .w(px(24.))
-                                                        .flex_shrink_0()
-                                                        .map(|this| {
-                                                            let should_move =
-                                                                Rc::new(Cell::new(false));
-                                                            this.on_double_click(
-                                                                |_, window, _| window.zoom_window(),
-                                                            )
-                                                            .on_mouse_down(MouseButton::Left, {
-                                                                let should_move =
-                                                                    should_move.clone();
-                                                                move |_, _, _| {
-                                                                    should_move.set(true);
-                                                                }
-                                                            })
-                                                            .on_mouse_down_out({
-                                                                let should_move =
-                                                                    should_move.clone();
-                                                                move |_, _, _| {
-                                                                    should_move.set(false);
-                                                                }
-                                                            })
-                                                            .on_mouse_up(MouseButton::Left, {
-                                                                let should_move =
-                                                                    should_move.clone();
-                                                                move |_, _, _| {
-                                                                    should_move.set(false);
-                                                                }
-                                                            })
-                                                            .on_mouse_move({
-                                                                let should_move =
-                                                                    should_move.clone();
-                                                                move |_, window, _| {
-                                                                    if should_move.get() {
-                                                                        should_move.set(false);
-                                                                        window.start_window_move();
-                                                                    }
-                                                                }
-                                                            })
-                                                            .window_control_area(
-                                                                WindowControlArea::Drag,
-                                                            )
-                                                        }),
-                                                )
-                                            })
-                                            .when(!cfg!(target_os = "macos"), |this| {
-                                                this.child(
-                                                    div()
-                                                        .h_full()
-                                                        .pr_1()
-                                                        .flex()
-                                                        .items_center()
-                                                        .gap_1()
-                                                        .child(
-                                                            Button::new("window-minimize")
-                                                                .ghost()
-                                                                .small()
-                                                                .icon(
-                                                                    Icon::new(
-                                                                        crate::icons::IconName::WindowMinimize,
-                                                                    )
-                                                                    .text_color(cx.theme().foreground),
-                                                                )
-                                                                .on_click(|_, window, _| {
-                                                                    window.minimize_window()
-                                                                }),
-                                                        )
-                                                        .child(
-                                                            Button::new("window-maximize")
-                                                                .ghost()
-                                                                .small()
-                                                                .icon(
-                                                                    Icon::new(if window.is_maximized() {
-                                                                        crate::icons::IconName::WindowRestore
-                                                                    } else {
-                                                                        crate::icons::IconName::WindowMaximize
-                                                                    })
-                                                                    .text_color(cx.theme().foreground),
-                                                                )
-                                                                .on_click(|_, window, _| {
-                                                                    zoom_or_restore_window(window)
-                                                                }),
-                                                        )
-                                                        .child(
-                                                            Button::new("window-close")
-                                                                .ghost()
-                                                                .small()
-                                                                .icon(
-                                                                    Icon::new(crate::icons::IconName::WindowClose)
-                                                                        .text_color(cx.theme().foreground),
-                                                                )
-                                                                .on_click(|_, window, _| {
-                                                                    window.remove_window()
-                                                                }),
-                                                        ),
-                                                )
-                                            }),
-                                    )
-                            )
+                    .when_some(title_bar, |this, title_bar| this.child(title_bar))
+                    .when(
+                        self.tab_layout_mode == TabLayoutMode::Horizontal && !self.zen_mode,
+                        |this| this.child(self.render_tab_bar(cx)),
                     )
-                    .when(self.tab_layout_mode == TabLayoutMode::Horizontal, |this| {
-                        this.child(self.render_tab_bar(cx))
-                    })
                     .child(
                         div()
                             .h_full()
                             .w_full()
                             .flex()
+                            .when(rtl, |this| this.flex_row_reverse())
                             .overflow_hidden()
-                            .when(self.tab_layout_mode == TabLayoutMode::Vertical && self.vertical_tab_bar_visible, |this| {
-                                this.child(
-                                    div()
-                                        .w(px(VERTICAL_TAB_BAR_WIDTH))
-                                        .h_full()
-                                        .border_r_1()
-                                        .border_color(cx.theme().border)
-                                        .bg(cx.theme().secondary)
-                                        .child(self.render_tab_bar(cx)),
-                                )
-                            })
+                            .when(
+                                self.tab_layout_mode == TabLayoutMode::Vertical
+                                    && self.vertical_tab_bar_visible
+                                    && !self.zen_mode,
+                                |this| {
+                                    this.child(
+                                        div()
+                                            .w(px(VERTICAL_TAB_BAR_WIDTH))
+                                            .h_full()
+                                            .when(rtl, |this| this.border_l_1())
+                                            .when(!rtl, |this| this.border_r_1())
+                                            .border_color(cx.theme().border)
+                                            .bg(cx.theme().secondary)
+                                            .child(self.render_tab_bar(cx)),
+                                    )
+                                },
+                            )
                             .when(self.show_thumbnail_panel(), |this| {
-                                this.child(self.render_thumbnail_panel(
+                                this.child(self.render_sidebar(
                                     page_count,
                                     thumbnail_sizes.clone(),
                                     cx,
                                 ))
                             })
-                            .child(
-                                div()
-                                    .flex_1()
-                                    .h_full()
-                                    .overflow_hidden()
-                                    .child(self.render_display_panel(
+                            .child(div().flex_1().h_full().overflow_hidden().map(|this| {
+                                match page_overview {
+                                    Some(page_overview) => this.child(page_overview),
+                                    None if presenting => this,
+                                    None => this.child(self.render_display_panel(
                                         page_count,
                                         display_sizes,
                                         display_panel_width,
                                         cx,
                                     )),
-                            ),
+                                }
+                            })),
                     )
+                    .when(self.status_bar_visible && !self.zen_mode, |this| {
+                        this.child(self.render_status_bar(zoom_label, cx))
+                    })
+                    .when_some(zen_chrome, |this, title_bar| {
+                        this.child(
+                            div()
+                                .id("zen-chrome")
+                                .absolute()
+                                .top_0()
+                                .left_0()
+                                .right_0()
+                                .v_flex()
+                                .shadow_md()
+                                .occlude()
+                                .child(title_bar)
+                                .when(self.tab_layout_mode == TabLayoutMode::Horizontal, |this| {
+                                    this.child(self.render_tab_bar(cx))
+                                }),
+                        )
+                    })
+                    .when_some(presentation, |this, presentation| this.child(presentation))
                     .when(context_menu.is_some(), |this| {
                         this.child(context_menu.unwrap())
                     })
@@ -893,16 +1552,28 @@ impl Render for PdfViewer {
                     .when(drag_tab_preview.is_some(), |this| {
                         this.child(drag_tab_preview.unwrap())
                     })
+                    .when(load_error_dialog.is_some(), |this| {
+                        this.child(load_error_dialog.unwrap())
+                    })
+                    .when_some(debug_overlay, |this, debug_overlay| {
+                        this.child(debug_overlay)
+                    })
                     .when(command_panel.is_some(), |this| {
                         this.child(command_panel.unwrap())
                     })
-                    .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, _, cx| {
+                    .when_some(tab_switcher, |this, switcher| this.child(switcher))
+                    .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, window, cx| {
                         // 当鼠标在主内容区域移动且垂直标签栏通过 hover 显示时，检查是否应该隐藏
+                        let outside_tab_bar = if this.language.is_rtl() {
+                            event.position.x < window.viewport_size().width - px(VERTICAL_TAB_BAR_WIDTH)
+                        } else {
+                            event.position.x > px(VERTICAL_TAB_BAR_WIDTH)
+                        };
                         if this.tab_layout_mode == TabLayoutMode::Vertical
                             && !this.vertical_tab_bar_visible
                             && this.vertical_tab_bar_hovered
                             && event.position.y > px(TITLE_BAR_HEIGHT)
-                            && event.position.x > px(VERTICAL_TAB_BAR_WIDTH)
+                            && outside_tab_bar
                         {
                             this.set_vertical_tab_bar_hovered(false, cx);
                         }
@@ -910,13 +1581,15 @@ impl Render for PdfViewer {
             )
                     .when(
                         self.tab_layout_mode == TabLayoutMode::Vertical
-                            && !self.vertical_tab_bar_visible,
+                            && !self.vertical_tab_bar_visible
+                            && !self.zen_mode,
                         |this| {
                             this.child(
                                 div()
                                     .id("vertical-tab-hover-area")
                                     .absolute()
-                                    .left_0()
+                                    .when(rtl, |this| this.right_0())
+                                    .when(!rtl, |this| this.left_0())
                                     .top(px(TITLE_BAR_HEIGHT))
                                     .bottom_0()
                                     .w(px(20.0))
@@ -932,15 +1605,18 @@ impl Render for PdfViewer {
                     .when(
                         self.tab_layout_mode == TabLayoutMode::Vertical
                             && !self.vertical_tab_bar_visible
-                            && self.vertical_tab_bar_hovered,
+                            && self.vertical_tab_bar_hovered
+                            && !self.zen_mode,
                         |this| {
                             this.child(
                                 div()
                                     .absolute()
-                                    .left_0()
+                                    .when(rtl, |this| this.right_0())
+                                    .when(!rtl, |this| this.left_0())
                                     .top(px(TITLE_BAR_HEIGHT))
                                     .bottom_0()
-                                    .border_r_1()
+                                    .when(rtl, |this| this.border_l_1())
+                                    .when(!rtl, |this| this.border_r_1())
                                     .border_color(cx.theme().border)
                                     .bg(cx.theme().secondary)
                                     .shadow_md()
@@ -0,0 +1,443 @@
+use super::pdf_edit::{OptimizeReport, optimize_document};
+use super::{
+    OPTIMIZE_DIALOG_WIDTH, OPTIMIZE_DIALOG_WINDOW_HEIGHT, OPTIMIZE_IMAGE_DPI_DEFAULT,
+    OPTIMIZE_IMAGE_DPI_OPTIONS, OPTIMIZE_PROGRESS_POLL_MS,
+};
+use crate::i18n::{I18n, Language};
+use crate::pdf_viewer::PdfViewer;
+use crate::pdf_viewer::utils::{display_file_name, is_same_file};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::{button::*, *};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub(super) struct OptimizeWindow {
+    viewer: Entity<PdfViewer>,
+    focus_handle: FocusHandle,
+    language: Language,
+    input: PathBuf,
+    /// 0 keeps images at their original resolution.
+    max_image_dpi: u32,
+    /// (pages processed, total pages) while optimizing; `None` when idle.
+    progress: Option<(usize, usize)>,
+    status_message: Option<String>,
+    saved_output: Option<PathBuf>,
+}
+
+impl OptimizeWindow {
+    pub(super) fn new(
+        viewer: Entity<PdfViewer>,
+        language: Language,
+        input: PathBuf,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        Self {
+            viewer,
+            focus_handle: cx.focus_handle(),
+            language,
+            input,
+            max_image_dpi: OPTIMIZE_IMAGE_DPI_DEFAULT,
+            progress: None,
+            status_message: None,
+            saved_output: None,
+        }
+    }
+
+    fn i18n(&self) -> I18n {
+        I18n::new(self.language)
+    }
+
+    fn start_save(&mut self, cx: &mut Context<Self>) {
+        if self.progress.is_some() {
+            return;
+        }
+        let directory = self
+            .input
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let suggested_name = self
+            .input
+            .file_stem()
+            .map(|stem| format!("{}-optimized.pdf", stem.to_string_lossy()))
+            .unwrap_or_else(|| "optimized.pdf".to_string());
+        let picker = cx.prompt_for_new_path(&directory, Some(&suggested_name));
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(output))) = picker.await else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                this.run_optimize(output, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn run_optimize(&mut self, output: PathBuf, cx: &mut Context<Self>) {
+        if self.progress.is_some() {
+            return;
+        }
+        if is_same_file(&self.input, &output) {
+            self.status_message = Some(self.i18n().save_copy_same_file.to_string());
+            cx.notify();
+            return;
+        }
+        let input = self.input.clone();
+        let language = self.language;
+        let max_image_dpi = Some(self.max_image_dpi).filter(|dpi| *dpi > 0);
+        self.progress = Some((0, 0));
+        self.status_message = None;
+        self.saved_output = None;
+        cx.notify();
+
+        let progress = Arc::new(Mutex::new((0, 0)));
+        let progress_for_task = progress.clone();
+        let finished = Arc::new(AtomicBool::new(false));
+        let finished_for_task = finished.clone();
+        let output_for_task = output.clone();
+        let optimize = cx.background_executor().spawn(async move {
+            let result = optimize_document(
+                &input,
+                &output_for_task,
+                max_image_dpi,
+                language,
+                |done, total| {
+                    if let Ok(mut progress) = progress_for_task.lock() {
+                        *progress = (done, total);
+                    }
+                },
+            );
+            finished_for_task.store(true, Ordering::Release);
+            result
+        });
+
+        cx.spawn(async move |this, cx| {
+            while !finished.load(Ordering::Acquire) {
+                cx.background_executor()
+                    .timer(Duration::from_millis(OPTIMIZE_PROGRESS_POLL_MS))
+                    .await;
+                let snapshot = progress.lock().map(|progress| *progress).ok();
+                let alive = this
+                    .update(cx, |this, cx| {
+                        if this.progress.is_some() {
+                            this.progress = snapshot.or(this.progress);
+                            cx.notify();
+                        }
+                    })
+                    .is_ok();
+                if !alive {
+                    return;
+                }
+            }
+        })
+        .detach();
+
+        cx.spawn(async move |this, cx| {
+            let result = optimize.await;
+            let _ = this.update(cx, |this, cx| {
+                let i18n = this.i18n();
+                this.progress = None;
+                this.status_message = Some(match result {
+                    Ok(report) => {
+                        this.saved_output = Some(output.clone());
+                        Self::describe_report(i18n, &output, report)
+                    }
+                    Err(err) => {
+                        crate::debug_log!(@warn, "[pdf][edit] optimize failed: {:#}", err);
+                        i18n.optimize_failed(&format!("{err:#}"))
+                    }
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn describe_report(i18n: I18n, output: &Path, report: OptimizeReport) -> String {
+        let change = if report.original_bytes == 0 {
+            "0%".to_string()
+        } else {
+            let ratio = report.optimized_bytes as f64 / report.original_bytes as f64;
+            format!("{:+.1}%", (ratio - 1.0) * 100.0)
+        };
+        i18n.optimize_done(
+            output,
+            &PdfViewer::format_storage_size(report.original_bytes),
+            &PdfViewer::format_storage_size(report.optimized_bytes),
+            &change,
+            report.images_downsampled,
+        )
+    }
+
+    fn open_saved_output(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(output) = self.saved_output.clone() else {
+            return;
+        };
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.open_pdf_path_in_new_tab(output, cx);
+        });
+        self.close_dialog(window, cx);
+    }
+
+    fn close_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.close_optimize_dialog(cx);
+        });
+        window.remove_window();
+    }
+}
+
+impl Render for OptimizeWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let i18n = self.i18n();
+        let optimizing = self.progress.is_some();
+        let max_image_dpi = self.max_image_dpi;
+
+        div()
+            .id("optimize-window")
+            .size_full()
+            .v_flex()
+            .bg(cx.theme().background)
+            .focusable()
+            .track_focus(&self.focus_handle)
+            .capture_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key.as_str() == "escape" {
+                    this.close_dialog(window, cx);
+                    cx.stop_propagation();
+                }
+            }))
+            .child(TitleBar::new())
+            .child(
+                div()
+                    .flex_1()
+                    .min_h(px(0.))
+                    .v_flex()
+                    .gap_3()
+                    .p_4()
+                    .child(
+                        div()
+                            .text_lg()
+                            .text_color(cx.theme().foreground)
+                            .child(i18n.optimize_dialog_title),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().foreground)
+                            .truncate()
+                            .child(display_file_name(&self.input)),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .whitespace_normal()
+                            .child(i18n.optimize_dialog_hint),
+                    )
+                    .child(div().h(px(1.)).bg(cx.theme().border))
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .v_flex()
+                                    .gap_1()
+                                    .child(
+                                        div()
+                                            .text_sm()
+                                            .text_color(cx.theme().foreground)
+                                            .child(i18n.optimize_image_dpi_label),
+                                    )
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .whitespace_normal()
+                                            .child(i18n.optimize_image_dpi_hint),
+                                    ),
+                            )
+                            .child(
+                                OPTIMIZE_IMAGE_DPI_OPTIONS
+                                    .into_iter()
+                                    .enumerate()
+                                    .fold(
+                                        ButtonGroup::new("optimize-image-dpi").small().outline(),
+                                        |group, (ix, dpi)| {
+                                            group.child(
+                                                Button::new(("optimize-image-dpi", ix))
+                                                    .label(if dpi == 0 {
+                                                        i18n.optimize_image_dpi_off.to_string()
+                                                    } else {
+                                                        format!("{dpi} DPI")
+                                                    })
+                                                    .selected(max_image_dpi == dpi)
+                                                    .disabled(optimizing),
+                                            )
+                                        },
+                                    )
+                                    .on_click(cx.listener(|this, selected: &Vec<usize>, _, cx| {
+                                        let Some(dpi) = selected
+                                            .first()
+                                            .and_then(|ix| OPTIMIZE_IMAGE_DPI_OPTIONS.get(*ix))
+                                        else {
+                                            return;
+                                        };
+                                        this.max_image_dpi = *dpi;
+                                        cx.notify();
+                                    })),
+                            ),
+                    )
+                    .child(div().flex_1())
+                    .when_some(self.progress, |this, (done, total)| {
+                        let fraction = if total == 0 {
+                            0.0
+                        } else {
+                            done as f32 / total as f32
+                        };
+                        this.child(PdfViewer::render_progress_bar(
+                            fraction,
+                            i18n.optimize_progress((done + 1).min(total), total),
+                            cx,
+                        ))
+                    })
+                    .when_some(self.status_message.clone(), |this, message| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .whitespace_normal()
+                                .child(message),
+                        )
+                    })
+                    .child(
+                        div()
+                            .w_full()
+                            .flex()
+                            .items_center()
+                            .justify_end()
+                            .gap_2()
+                            .when(self.saved_output.is_some(), |this| {
+                                this.child(
+                                    Button::new("optimize-open-output")
+                                        .small()
+                                        .label(i18n.optimize_open_button)
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.open_saved_output(window, cx);
+                                        })),
+                                )
+                            })
+                            .child(
+                                Button::new("optimize-save")
+                                    .small()
+                                    .primary()
+                                    .label(i18n.optimize_save_button)
+                                    .disabled(optimizing)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.start_save(cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("optimize-close")
+                                    .small()
+                                    .ghost()
+                                    .label(i18n.close_button)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.close_dialog(window, cx);
+                                    })),
+                            ),
+                    ),
+            )
+    }
+}
+
+impl PdfViewer {
+    pub(super) fn open_optimize_dialog(&mut self, cx: &mut Context<Self>) {
+        if self.command_panel_open {
+            self.close_command_panel(cx);
+        }
+
+        if self.optimize_dialog_open {
+            if let Some(handle) = self.optimize_dialog_window.as_ref() {
+                let _ = handle.update(cx, |_, window, _| {
+                    window.activate_window();
+                });
+            }
+            return;
+        }
+        let Some(input) = self.active_tab_path().cloned() else {
+            return;
+        };
+
+        self.optimize_dialog_open = true;
+        self.needs_root_refocus = false;
+        self.optimize_dialog_session = self.optimize_dialog_session.wrapping_add(1);
+        let session_id = self.optimize_dialog_session;
+
+        let language = self.language;
+        let viewer = cx.entity();
+        let viewer_for_close = viewer.clone();
+        let window_options = WindowOptions {
+            titlebar: Some(Self::dialog_titlebar_options()),
+            window_bounds: Some(WindowBounds::centered(
+                size(px(OPTIMIZE_DIALOG_WIDTH), px(OPTIMIZE_DIALOG_WINDOW_HEIGHT)),
+                cx,
+            )),
+            window_decorations: Some(WindowDecorations::Client),
+            ..WindowOptions::default()
+        };
+
+        match cx.open_window(window_options, move |window, cx| {
+            window.on_window_should_close(cx, move |_, cx| {
+                let _ = viewer_for_close.update(cx, |this, cx| {
+                    this.on_optimize_dialog_window_closed(session_id, cx);
+                });
+                true
+            });
+            let dialog = cx.new(|cx| OptimizeWindow::new(viewer, language, input, cx));
+            let dialog_focus = dialog.read(cx).focus_handle.clone();
+            let root = cx.new(|cx| Root::new(dialog, window, cx));
+            window.focus(&dialog_focus);
+            root
+        }) {
+            Ok(handle) => {
+                self.optimize_dialog_window = Some(handle.into());
+                cx.notify();
+            }
+            Err(err) => {
+                crate::debug_log!(@warn, "[optimize] failed to open optimize window: {}", err);
+                self.on_optimize_dialog_window_closed(session_id, cx);
+            }
+        }
+    }
+
+    pub(super) fn close_optimize_dialog(&mut self, cx: &mut Context<Self>) {
+        let window_handle = self.optimize_dialog_window.take();
+        if self.optimize_dialog_open || window_handle.is_some() {
+            self.optimize_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+        // Defer window removal to avoid borrow conflicts during event handling
+        if let Some(window_handle) = window_handle {
+            cx.defer(move |cx| {
+                let _ = window_handle.update(cx, |_, window, _| {
+                    window.remove_window();
+                });
+            });
+        }
+    }
+
+    fn on_optimize_dialog_window_closed(&mut self, session_id: u64, cx: &mut Context<Self>) {
+        if self.optimize_dialog_session == session_id {
+            self.optimize_dialog_window = None;
+            self.optimize_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+    }
+}
@@ -0,0 +1,267 @@
+use super::{
+    PAGE_OVERVIEW_CELL_MAX_WIDTH, PAGE_OVERVIEW_CELL_MIN_WIDTH, PAGE_OVERVIEW_CELL_WIDTH_STEP,
+    PAGE_OVERVIEW_GAP, PAGE_OVERVIEW_LABEL_HEIGHT, PageSummary, PdfViewer,
+};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::scroll::{Scrollbar, ScrollbarShow};
+use gpui_component::*;
+use std::rc::Rc;
+
+impl PdfViewer {
+    pub(super) fn toggle_page_overview(&mut self, cx: &mut Context<Self>) {
+        if self.page_overview_open {
+            self.close_page_overview(cx);
+        } else {
+            self.open_page_overview(cx);
+        }
+    }
+
+    pub(super) fn open_page_overview(&mut self, cx: &mut Context<Self>) {
+        if self.page_overview_open || self.active_tab_pages().is_none_or(|pages| pages.is_empty()) {
+            return;
+        }
+        self.page_overview_open = true;
+        self.page_overview_needs_scroll_to_active = true;
+        cx.notify();
+    }
+
+    pub(super) fn close_page_overview(&mut self, cx: &mut Context<Self>) {
+        if !self.page_overview_open {
+            return;
+        }
+        self.page_overview_open = false;
+        cx.notify();
+    }
+
+    pub(super) fn page_overview_zoom_in(&mut self, cx: &mut Context<Self>) {
+        self.set_page_overview_cell_width(
+            self.page_overview_cell_width + PAGE_OVERVIEW_CELL_WIDTH_STEP,
+            cx,
+        );
+    }
+
+    pub(super) fn page_overview_zoom_out(&mut self, cx: &mut Context<Self>) {
+        self.set_page_overview_cell_width(
+            self.page_overview_cell_width - PAGE_OVERVIEW_CELL_WIDTH_STEP,
+            cx,
+        );
+    }
+
+    fn set_page_overview_cell_width(&mut self, width: f32, cx: &mut Context<Self>) {
+        let width = width.clamp(PAGE_OVERVIEW_CELL_MIN_WIDTH, PAGE_OVERVIEW_CELL_MAX_WIDTH);
+        if (self.page_overview_cell_width - width).abs() > f32::EPSILON {
+            self.page_overview_cell_width = width;
+            // Column count changes, so keep the active page in view.
+            self.page_overview_needs_scroll_to_active = true;
+            cx.notify();
+        }
+    }
+
+    fn page_overview_select_page(&mut self, page_index: usize, cx: &mut Context<Self>) {
+        self.page_overview_open = false;
+        self.select_page(page_index, cx);
+        cx.notify();
+    }
+
+    fn page_overview_columns(&self, available_width: f32) -> usize {
+        let cell = self.page_overview_cell_width + PAGE_OVERVIEW_GAP;
+        (((available_width - PAGE_OVERVIEW_GAP) / cell).floor() as usize).max(1)
+    }
+
+    fn page_overview_cell_height(&self, page: &PageSummary) -> f32 {
+        let aspect_ratio = if page.width_pt > 1.0 {
+            page.height_pt / page.width_pt
+        } else {
+            1.4
+        };
+        self.page_overview_cell_width * aspect_ratio
+    }
+
+    fn page_overview_row_sizes(
+        &self,
+        pages: &[PageSummary],
+        columns: usize,
+        available_width: f32,
+    ) -> Rc<Vec<gpui::Size<Pixels>>> {
+        Rc::new(
+            pages
+                .chunks(columns)
+                .map(|row| {
+                    let height = row
+                        .iter()
+                        .map(|page| self.page_overview_cell_height(page))
+                        .fold(0.0, f32::max);
+                    size(
+                        px(available_width),
+                        px(height + PAGE_OVERVIEW_LABEL_HEIGHT + PAGE_OVERVIEW_GAP),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    fn page_overview_target_width(&self, window: &Window) -> u32 {
+        let width = self.page_overview_cell_width * window.scale_factor();
+        width.clamp(1.0, i32::MAX as f32).round() as u32
+    }
+
+    pub(super) fn render_page_overview(
+        &mut self,
+        available_width: f32,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let columns = self.page_overview_columns(available_width);
+        let row_sizes = self
+            .active_tab_pages()
+            .map(|pages| self.page_overview_row_sizes(pages, columns, available_width))
+            .unwrap_or_else(|| Rc::new(Vec::new()));
+
+        if self.page_overview_needs_scroll_to_active {
+            self.page_overview_needs_scroll_to_active = false;
+            let active_row = self.active_tab_active_page() / columns;
+            self.page_overview_scroll
+                .scroll_to_item(active_row, ScrollStrategy::Center);
+        }
+
+        let cell_width = self.page_overview_cell_width;
+
+        div()
+            .id("page-overview")
+            .size_full()
+            .relative()
+            .bg(cx.theme().muted)
+            .child(
+                v_virtual_list(
+                    cx.entity(),
+                    "page-overview-virtual-list",
+                    row_sizes,
+                    move |viewer, visible_rows, window, cx| {
+                        let page_count = viewer.active_tab_pages().map_or(0, |pages| pages.len());
+                        let visible_pages = (visible_rows.start * columns)
+                            ..(visible_rows.end * columns).min(page_count);
+                        let target_width = viewer.page_overview_target_width(window);
+                        viewer.request_thumbnail_load_for_visible_range(
+                            visible_pages,
+                            target_width,
+                            cx,
+                        );
+
+                        let active_page = viewer.active_tab_active_page();
+                        let right_to_left = viewer.page_order_right_to_left();
+                        visible_rows
+                            .map(|row| {
+                                let Some(pages) = viewer.active_tab_pages() else {
+                                    return div().into_any_element();
+                                };
+                                let start = row * columns;
+                                let end = (start + columns).min(pages.len());
+                                let cells = pages[start..end]
+                                    .iter()
+                                    .map(|page| {
+                                        let ix = page.index;
+                                        let height = viewer.page_overview_cell_height(page);
+                                        let is_active = ix == active_page;
+                                        div()
+                                            .id(("page-overview-cell", ix))
+                                            .w(px(cell_width))
+                                            .flex_none()
+                                            .v_flex()
+                                            .items_center()
+                                            .gap_1()
+                                            .cursor_pointer()
+                                            .child(
+                                                div()
+                                                    .w(px(cell_width))
+                                                    .h(px(height))
+                                                    .overflow_hidden()
+                                                    .rounded_md()
+                                                    .border_2()
+                                                    .border_color(if is_active {
+                                                        cx.theme().primary
+                                                    } else {
+                                                        cx.theme().border
+                                                    })
+                                                    .bg(cx.theme().background)
+                                                    .hover(|this| {
+                                                        this.border_color(
+                                                            cx.theme().primary.opacity(0.6),
+                                                        )
+                                                    })
+                                                    .when_some(
+                                                        page.thumbnail_image.clone(),
+                                                        |this, image| {
+                                                            this.child(
+                                                                img(image)
+                                                                    .size_full()
+                                                                    .object_fit(ObjectFit::Contain),
+                                                            )
+                                                        },
+                                                    )
+                                                    .when(page.thumbnail_image.is_none(), |this| {
+                                                        this.flex().items_center().justify_center().child(
+                                                            if page.thumbnail_failed {
+                                                                Icon::new(crate::icons::IconName::File)
+                                                                    .size_5()
+                                                                    .text_color(cx.theme().muted_foreground)
+                                                                    .into_any_element()
+                                                            } else {
+                                                                spinner::Spinner::new()
+                                                                    .icon(Icon::new(
+                                                                        crate::icons::IconName::LoaderCircle,
+                                                                    ))
+                                                                    .color(cx.theme().muted_foreground)
+                                                                    .into_any_element()
+                                                            },
+                                                        )
+                                                    }),
+                                            )
+                                            .child(
+                                                div()
+                                                    .h(px(PAGE_OVERVIEW_LABEL_HEIGHT))
+                                                    .text_xs()
+                                                    .text_color(if is_active {
+                                                        cx.theme().foreground
+                                                    } else {
+                                                        cx.theme().muted_foreground
+                                                    })
+                                                    .child(format!("{}", ix + 1)),
+                                            )
+                                            .on_click(cx.listener(move |this, _, _, cx| {
+                                                this.page_overview_select_page(ix, cx);
+                                            }))
+                                            .into_any_element()
+                                    })
+                                    .collect::<Vec<_>>();
+
+                                div()
+                                    .w_full()
+                                    .pt(px(PAGE_OVERVIEW_GAP))
+                                    .h_flex()
+                                    .when(right_to_left, |this| this.flex_row_reverse())
+                                    .items_start()
+                                    .justify_center()
+                                    .gap(px(PAGE_OVERVIEW_GAP))
+                                    .children(cells)
+                                    .into_any_element()
+                            })
+                            .collect::<Vec<_>>()
+                    },
+                )
+                .track_scroll(&self.page_overview_scroll)
+                .into_any_element(),
+            )
+            .child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .left_0()
+                    .right_0()
+                    .bottom_0()
+                    .child(
+                        Scrollbar::vertical(&self.page_overview_scroll)
+                            .scrollbar_show(ScrollbarShow::Always),
+                    ),
+            )
+    }
+}
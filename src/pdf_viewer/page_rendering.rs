@@ -2,6 +2,14 @@ impl PdfViewer {
     fn select_page(&mut self, index: usize, cx: &mut Context<Self>) {
         if let Some(tab) = self.active_tab_mut() {
             if index < tab.pages.len() {
+                // Paging to a neighbour is reading on; anything further is a jump worth undoing.
+                if tab.active_page.abs_diff(index) > 1 {
+                    tab.back_history.push(tab.active_page);
+                    if tab.back_history.len() > MAX_NAVIGATION_HISTORY {
+                        tab.back_history.remove(0);
+                    }
+                    tab.forward_history.clear();
+                }
                 tab.selected_page = index;
                 tab.active_page = index;
                 self.sync_scroll_to_selected();
@@ -11,6 +19,73 @@ impl PdfViewer {
         }
     }
 
+    pub(super) fn navigate_back(&mut self, cx: &mut Context<Self>) {
+        self.step_navigation_history(true, cx);
+    }
+
+    pub(super) fn navigate_forward(&mut self, cx: &mut Context<Self>) {
+        self.step_navigation_history(false, cx);
+    }
+
+    fn step_navigation_history(&mut self, back: bool, cx: &mut Context<Self>) {
+        let Some(tab) = self.active_tab_mut() else {
+            return;
+        };
+        let target = if back {
+            tab.back_history.pop()
+        } else {
+            tab.forward_history.pop()
+        };
+        let Some(target) = target.filter(|index| *index < tab.pages.len()) else {
+            return;
+        };
+        if back {
+            tab.forward_history.push(tab.active_page);
+        } else {
+            tab.back_history.push(tab.active_page);
+        }
+        tab.selected_page = target;
+        tab.active_page = target;
+        self.sync_scroll_to_selected();
+        self.persist_current_file_position();
+        cx.notify();
+    }
+
+    /// Turns a horizontal two-finger swipe over the pages into back/forward, like a browser.
+    fn handle_display_swipe(&mut self, event: &ScrollWheelEvent, cx: &mut Context<Self>) {
+        // Mouse wheels report lines; only trackpads report exact pixel deltas.
+        let ScrollDelta::Pixels(delta) = event.delta else {
+            return;
+        };
+        let now = Instant::now();
+        let gesture = &mut self.swipe_gesture;
+        let idle = gesture.last_event_at.is_none_or(|at| {
+            now.duration_since(at) > Duration::from_millis(SWIPE_GESTURE_IDLE_MS)
+        });
+        if idle || event.touch_phase == TouchPhase::Started {
+            *gesture = SwipeGesture::default();
+        }
+        gesture.last_event_at = Some(now);
+        if gesture.handled {
+            return;
+        }
+        gesture.delta_x += f32::from(delta.x);
+        gesture.delta_y += f32::from(delta.y);
+        // Pages never scroll sideways, so only a mostly vertical gesture is a scroll.
+        if gesture.delta_x.abs() < SWIPE_NAVIGATION_THRESHOLD
+            || gesture.delta_x.abs() < gesture.delta_y.abs() * 2.0
+        {
+            return;
+        }
+        gesture.handled = true;
+        // Fingers moving right go back, mirrored for right-to-left interfaces.
+        if (gesture.delta_x > 0.0) != self.language.is_rtl() {
+            self.navigate_back(cx);
+        } else {
+            self.navigate_forward(cx);
+        }
+    }
+
     pub(super) fn prev_page(&mut self, cx: &mut Context<Self>) {
         if let Some(tab) = self.active_tab() {
             if tab.active_page > 0 {
@@ -49,6 +124,7 @@ impl PdfViewer {
     pub(super) fn zoom_in(&mut self, cx: &mut Context<Self>) {
         if let Some(tab) = self.active_tab_mut() {
             tab.zoom = (tab.zoom + ZOOM_STEP).clamp(ZOOM_MIN, ZOOM_MAX);
+            self.persist_active_tab_view();
             cx.notify();
         }
     }
@@ -56,6 +132,7 @@ impl PdfViewer {
     pub(super) fn zoom_out(&mut self, cx: &mut Context<Self>) {
         if let Some(tab) = self.active_tab_mut() {
             tab.zoom = (tab.zoom - ZOOM_STEP).clamp(ZOOM_MIN, ZOOM_MAX);
+            self.persist_active_tab_view();
             cx.notify();
         }
     }
@@ -63,6 +140,19 @@ impl PdfViewer {
     pub(super) fn zoom_reset(&mut self, cx: &mut Context<Self>) {
         if let Some(tab) = self.active_tab_mut() {
             tab.zoom = 1.0;
+            self.persist_active_tab_view();
+            cx.notify();
+        }
+    }
+
+    /// Shows the current page with one PDF point per pixel, or as wide as the panel allows.
+    pub(super) fn zoom_to_actual_size(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let available_width = self.display_available_width(window);
+        if let Some(tab) = self.active_tab_mut()
+            && let Some(page) = tab.pages.get(tab.active_page)
+        {
+            tab.zoom = (page.width_pt / available_width).clamp(ZOOM_MIN, ZOOM_MAX);
+            self.persist_active_tab_view();
             cx.notify();
         }
     }
@@ -113,27 +203,19 @@ impl PdfViewer {
 
                     if page_index_changed {
                         tab.active_page = next_active;
-                        // Save position directly
-                        if let Some(ref path) = tab.path {
-                            if !tab.pages.is_empty() {
-                                let page_index =
-                                    tab.active_page.min(tab.pages.len().saturating_sub(1));
-                                tab.last_saved_position = Some((path.clone(), page_index));
-                                Some((path.clone(), page_index))
-                            } else {
-                                None
-                            }
-                        } else {
-                            None
-                        }
-                    } else {
-                        None
                     }
+                    // The offset into the page changes with every scroll, so the position is
+                    // saved whenever scrolling settles.
+                    tab.path.clone().inspect(|path| {
+                        tab.last_saved_position = Some((path.clone(), tab.active_page));
+                    })
                 };
 
                 // Save file position outside the mutable borrow
-                if let Some((path, page_index)) = path_to_save {
-                    this.save_file_position(&path, page_index);
+                if let Some(path) = path_to_save
+                    && let Some(view) = this.active_tab().map(|tab| this.tab_file_view(tab))
+                {
+                    this.save_file_position(&path, view);
                 }
 
                 // Get tab again for scroll operation
@@ -189,6 +271,17 @@ impl PdfViewer {
                     return;
                 }
 
+                // A position restored on load lands inside the page once the layout settles.
+                let pending_scroll_top = this.active_tab().and_then(|tab| {
+                    let fraction = tab.pending_page_fraction?;
+                    let page_index = keep_page.min(tab.pages.len().saturating_sub(1));
+                    Some(this.display_scroll_top_in_page(
+                        &tab.pages,
+                        page_index,
+                        fraction,
+                        tab.last_display_base_width,
+                    ))
+                });
                 let Some(tab) = this.tab_bar.get_active_tab_mut() else {
                     return;
                 };
@@ -202,8 +295,15 @@ impl PdfViewer {
                 tab.last_display_visible_range =
                     Some(page_index..page_index.saturating_add(1).min(tab.pages.len()));
                 tab.suppress_display_scroll_sync_once = true;
-                tab.display_scroll
-                    .scroll_to_item(page_index, ScrollStrategy::Top);
+                tab.pending_page_fraction = None;
+                if let Some(scroll_top) = pending_scroll_top {
+                    let offset = tab.display_scroll.offset();
+                    tab.display_scroll
+                        .set_offset(point(offset.x, px(-scroll_top)));
+                } else {
+                    tab.display_scroll
+                        .scroll_to_item(page_index, ScrollStrategy::Top);
+                }
                 tab.thumbnail_scroll
                     .scroll_to_item(page_index, ScrollStrategy::Center);
                 tab.last_display_scroll_offset = Some(tab.display_scroll.offset());
@@ -214,7 +314,7 @@ impl PdfViewer {
     }
 
     fn thumbnail_base_width(&self) -> f32 {
-        (SIDEBAR_WIDTH - THUMB_HORIZONTAL_PADDING).max(THUMB_MIN_WIDTH)
+        (self.sidebar_width - THUMB_HORIZONTAL_PADDING).max(THUMB_MIN_WIDTH)
     }
 
     fn thumbnail_card_size(&self, page: &PageSummary) -> (f32, f32) {
@@ -242,22 +342,38 @@ impl PdfViewer {
         )
     }
 
+    /// The window moved to a monitor with a different DPI: render targets are in device
+    /// pixels, so restart every tab's renders at the new density. Current bitmaps stay on
+    /// screen until their replacements arrive.
+    fn on_window_scale_factor_changed(&mut self, scale_factor: f32) {
+        crate::debug_log!("[render] window scale factor changed to {}", scale_factor);
+        for tab in self.tab_bar.tabs_mut() {
+            // Apply the new width right away instead of waiting for zooming to settle.
+            tab.display_render_target_width = 0;
+            tab.reset_thumbnail_render_state();
+            tab.reset_display_render_state();
+        }
+    }
+
+    /// Supersampling factor applied on top of the device-pixel layout width.
+    fn render_quality(&self) -> f32 {
+        self.render_quality_percent as f32 / 100.0
+    }
+
     fn thumbnail_target_width(&self, window: &Window) -> u32 {
-        let width = self.thumbnail_base_width() * window.scale_factor();
+        let width = self.thumbnail_base_width() * window.scale_factor() * self.render_quality();
         width.clamp(1.0, i32::MAX as f32).round() as u32
     }
 
-    fn request_thumbnail_load_from_candidates(
-        &mut self,
-        candidate_order: Vec<usize>,
-        target_width: u32,
-        cx: &mut Context<Self>,
-    ) {
+    fn request_thumbnail_load_from_queue(&mut self, target_width: u32, cx: &mut Context<Self>) {
         let language = self.language;
+        let engine = self.render_engine;
+        let options = self.render_options;
+        let max_workers = self.render_worker_count;
         let Some(tab) = self.active_tab_mut() else {
             return;
         };
-        if candidate_order.is_empty() || tab.pages.is_empty() {
+        if tab.pages.is_empty() {
             return;
         }
 
@@ -265,30 +381,17 @@ impl PdfViewer {
             return;
         };
 
-        if tab.thumbnail_inflight_tasks >= THUMB_MAX_PARALLEL_TASKS {
+        if tab.thumbnail_inflight_tasks >= max_workers {
             return;
         }
 
-        let mut pending = Vec::new();
-        let mut seen = HashSet::new();
-        for ix in candidate_order {
-            if !seen.insert(ix) {
-                continue;
-            }
-
-            let Some(page) = tab.pages.get(ix) else {
-                continue;
-            };
-
-            let needs_render =
-                page.thumbnail_image.is_none() || page.thumbnail_render_width < target_width;
-            if needs_render && !page.thumbnail_failed {
-                pending.push(ix);
-                if pending.len() >= THUMB_BATCH_SIZE {
-                    break;
-                }
-            }
-        }
+        let pending = tab.thumbnail_queue.pop_batch(THUMB_BATCH_SIZE, |ix| {
+            tab.pages.get(ix).is_some_and(|page| {
+                (page.thumbnail_image.is_none() || page.thumbnail_render_width < target_width)
+                    && !page.thumbnail_failed
+                    && !tab.thumbnail_loading.contains(&ix)
+            })
+        });
 
         if pending.is_empty() {
             return;
@@ -305,7 +408,16 @@ impl PdfViewer {
             let load_result = cx
                 .background_executor()
                 .spawn(async move {
-                    let loaded = load_display_images(&path, &pending, target_width, language);
+                    let loaded = load_display_images(
+                        &path,
+                        &pending,
+                        target_width,
+                        language,
+                        engine,
+                        options,
+                        None,
+                        || false,
+                    );
                     (pending, target_width, loaded)
                 })
                 .await;
@@ -320,7 +432,8 @@ impl PdfViewer {
 
                 tab.thumbnail_inflight_tasks = tab.thumbnail_inflight_tasks.saturating_sub(1);
 
-                let (requested_indices, loaded_target_width, loaded_result) = load_result;
+                let (requested_indices, loaded_target_width, loaded_result, render_elapsed) =
+                    load_result;
                 let mut loaded_indices = HashSet::new();
 
                 match loaded_result {
@@ -334,7 +447,7 @@ impl PdfViewer {
                             }
                         }
                     }
-                    Err(_) => {}
+                    Err(err) => record_pdfium_render_error(format!("{err:#}")),
                 }
 
                 for ix in requested_indices {
@@ -360,7 +473,7 @@ impl PdfViewer {
         let Some(tab) = self.active_tab_mut() else {
             return;
         };
-        if visible_range.is_empty() || tab.pages.is_empty() {
+        if visible_range.is_empty() || tab.pages.is_empty() || tab.skeleton_only {
             return;
         }
 
@@ -369,20 +482,36 @@ impl PdfViewer {
         }
 
         tab.last_thumbnail_visible_range = Some(visible_range.clone());
-
-        let mut candidate_order = Vec::with_capacity(visible_range.len());
-        candidate_order.extend(visible_range.clone());
-        self.request_thumbnail_load_from_candidates(candidate_order, target_width, cx);
+        let page_count = tab.pages.len();
+        tab.thumbnail_queue.reprioritize(
+            tab.active_page,
+            visible_range,
+            THUMB_PREFETCH_PAGES,
+            page_count,
+        );
+
+        // Each call starts at most one job, so fill every idle worker.
+        for _ in 0..self.render_worker_count {
+            self.request_thumbnail_load_from_queue(target_width, cx);
+        }
     }
 
     fn display_available_width(&self, window: &Window) -> f32 {
         let viewport_width: f32 = window.viewport_size().width.into();
         let sidebar_width = if self.show_thumbnail_panel() {
-            SIDEBAR_WIDTH
+            self.sidebar_width
         } else {
             0.0
         };
-        (viewport_width - sidebar_width).max(DISPLAY_MIN_WIDTH)
+        let tab_bar_width = if self.tab_layout_mode == TabLayoutMode::Vertical
+            && self.vertical_tab_bar_visible
+            && !self.zen_mode
+        {
+            VERTICAL_TAB_BAR_WIDTH
+        } else {
+            0.0
+        };
+        (viewport_width - sidebar_width - tab_bar_width).max(DISPLAY_MIN_WIDTH)
     }
 
     fn display_panel_width(&self, window: &Window, zoom: f32) -> f32 {
@@ -410,6 +539,26 @@ impl PdfViewer {
         height
     }
 
+    /// Scroll top that puts the viewport `fraction` of the way into `page_index`.
+    fn display_scroll_top_in_page(
+        &self,
+        pages: &[PageSummary],
+        page_index: usize,
+        fraction: f32,
+        base_width: f32,
+    ) -> f32 {
+        let page_top: f32 = pages
+            .iter()
+            .take(page_index)
+            .map(|page| self.display_row_height(page, base_width))
+            .sum();
+        let page_height = pages
+            .get(page_index)
+            .map(|page| self.display_row_height(page, base_width))
+            .unwrap_or(0.0);
+        page_top + page_height * fraction
+    }
+
     fn display_item_sizes(
         &self,
         pages: &[PageSummary],
@@ -424,21 +573,96 @@ impl PdfViewer {
     }
 
     fn display_target_width(&self, window: &Window, zoom: f32) -> u32 {
-        let width = self.display_panel_width(window, zoom) * window.scale_factor();
+        let width =
+            self.display_panel_width(window, zoom) * window.scale_factor() * self.render_quality();
         width.clamp(1.0, i32::MAX as f32).round() as u32
     }
 
-    fn request_display_load_from_candidates(
-        &mut self,
-        candidate_order: Vec<usize>,
-        target_width: u32,
-        cx: &mut Context<Self>,
-    ) {
+    /// Width to render at, which lags behind zoom changes until they settle.
+    fn display_render_target_width(&self, window: &Window, zoom: f32) -> u32 {
+        self.active_tab()
+            .map(|tab| tab.display_render_target_width)
+            .filter(|width| *width > 0)
+            .unwrap_or_else(|| self.display_target_width(window, zoom))
+    }
+
+    /// Re-renders at `target_width` once zooming pauses; until then the current bitmaps
+    /// are drawn scaled to the new layout.
+    fn schedule_display_render_width_update(&mut self, target_width: u32, cx: &mut Context<Self>) {
+        let Some(tab) = self.active_tab_mut() else {
+            return;
+        };
+
+        tab.display_zoom_render_epoch = tab.display_zoom_render_epoch.wrapping_add(1);
+        let zoom_epoch = tab.display_zoom_render_epoch;
+        let tab_id = tab.id;
+
+        cx.spawn(async move |view, cx| {
+            cx.background_executor()
+                .timer(Duration::from_millis(DISPLAY_ZOOM_RENDER_DELAY_MS))
+                .await;
+
+            let _ = view.update(cx, |this, cx| {
+                let Some(tab) = this.tab_bar.get_active_tab_mut() else {
+                    return;
+                };
+                if tab.id != tab_id || tab.display_zoom_render_epoch != zoom_epoch {
+                    return;
+                }
+                if tab.display_render_target_width != target_width {
+                    tab.display_render_target_width = target_width;
+                    // Renders at the old width would be thrown away; stop them early.
+                    tab.cancel_display_renders();
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// Width of the quick first-pass bitmap drawn before the full-resolution render.
+    fn display_preview_width(target_width: u32) -> u32 {
+        ((target_width as f32 * DISPLAY_PREVIEW_SCALE).round() as u32)
+            .max(DISPLAY_PREVIEW_MIN_WIDTH)
+    }
+
+    /// Device-pixel height of a page rendered at `render_width`.
+    fn display_render_height(page: &PageSummary, render_width: u32) -> u32 {
+        let aspect_ratio = if page.width_pt > 1.0 {
+            page.height_pt / page.width_pt
+        } else {
+            1.4
+        };
+        (render_width as f32 * aspect_ratio).round().max(1.0) as u32
+    }
+
+    /// Pages whose full bitmap would exceed the pixel budget are drawn from band tiles instead.
+    fn display_uses_tiles(page: &PageSummary, render_width: u32) -> bool {
+        render_width as u64 * Self::display_render_height(page, render_width) as u64
+            > DISPLAY_TILE_PIXEL_THRESHOLD
+    }
+
+    /// Width a page is actually rendered at, after clamping gigantic pages to the pixel budget.
+    fn display_page_render_width(page: &PageSummary, target_width: u32) -> u32 {
+        clamp_render_width(page.width_pt, page.height_pt, target_width)
+    }
+
+    fn display_render_clamped(page: &PageSummary, target_width: u32) -> bool {
+        Self::display_page_render_width(page, target_width) < target_width
+    }
+
+    fn request_display_load_from_queue(&mut self, target_width: u32, cx: &mut Context<Self>) {
         let language = self.language;
+        let engine = self.render_engine;
+        let options = self.render_options;
+        let max_workers = self.render_worker_count;
+        let disk_cache_limit_bytes = self
+            .render_cache_enabled
+            .then_some(self.render_cache_limit_mb * 1024 * 1024);
         let Some(tab) = self.active_tab_mut() else {
             return;
         };
-        if candidate_order.is_empty() || tab.pages.is_empty() {
+        if tab.pages.is_empty() {
             return;
         }
 
@@ -446,30 +670,38 @@ impl PdfViewer {
             return;
         };
 
-        if tab.display_inflight_tasks >= DISPLAY_MAX_PARALLEL_TASKS {
+        if tab.display_inflight_tasks >= max_workers {
             return;
         }
 
-        let mut pending = Vec::new();
-        let mut seen = HashSet::new();
-        for ix in candidate_order {
-            if !seen.insert(ix) {
-                continue;
-            }
-
-            let Some(page) = tab.pages.get(ix) else {
-                continue;
-            };
-
-            let needs_render =
-                page.display_image.is_none() || page.display_render_width < target_width;
-            if needs_render && !page.display_failed {
-                pending.push(ix);
-                if pending.len() >= DISPLAY_BATCH_SIZE {
-                    break;
-                }
-            }
-        }
+        // Two stages: blank pages first get a cheap low-resolution preview so scrolling
+        // never shows empty cards, then every page is upgraded to the full target width.
+        let preview_width = Self::display_preview_width(target_width);
+        let preview_stage = preview_width < target_width
+            && tab.display_queue.pages().any(|ix| {
+                tab.pages
+                    .get(ix)
+                    .is_some_and(|page| page.display_image.is_none() && !page.display_failed)
+            });
+        let render_width = if preview_stage {
+            preview_width
+        } else {
+            target_width
+        };
+        // Previews are cheap to redo; only full-resolution renders are worth keeping on disk.
+        let disk_cache_limit_bytes = disk_cache_limit_bytes.filter(|_| !preview_stage);
+
+        let pending = tab.display_queue.pop_batch(DISPLAY_BATCH_SIZE, |ix| {
+            tab.pages.get(ix).is_some_and(|page| {
+                let needs_render = if preview_stage {
+                    page.display_image.is_none()
+                } else {
+                    (page.display_image.is_none() || page.display_render_width < target_width)
+                        && !Self::display_uses_tiles(page, target_width)
+                };
+                needs_render && !page.display_failed && !tab.display_loading.contains(&ix)
+            })
+        });
 
         if pending.is_empty() {
             return;
@@ -480,27 +712,39 @@ impl PdfViewer {
         }
         tab.display_inflight_tasks = tab.display_inflight_tasks.saturating_add(1);
         let epoch = tab.display_epoch;
+        let cancel_epoch = tab.display_cancel_epoch.clone();
         let tab_id = tab.id;
 
         cx.spawn(async move |view, cx| {
             let load_result = cx
                 .background_executor()
                 .spawn(async move {
-                    let loaded = load_display_images(&path, &pending, target_width, language);
-                    (pending, target_width, loaded)
+                    let started = Instant::now();
+                    let loaded = load_display_images(
+                        &path,
+                        &pending,
+                        render_width,
+                        language,
+                        engine,
+                        options,
+                        disk_cache_limit_bytes,
+                        || cancel_epoch.load(Ordering::Relaxed) != epoch,
+                    );
+                    (pending, render_width, loaded, started.elapsed())
                 })
                 .await;
 
             let _ = view.update(cx, |this, cx| {
-                let Some(tab) = this.tab_bar.get_active_tab_mut() else {
+                let is_active = this.tab_bar.active_tab_id() == Some(tab_id);
+                let Some(tab) = this.tab_bar.get_tab_mut(tab_id) else {
                     return;
                 };
-                if tab.id != tab_id || tab.display_epoch != epoch {
+                // A cancelled job still counts as a worker until it has stopped.
+                tab.display_inflight_tasks = tab.display_inflight_tasks.saturating_sub(1);
+                if !is_active || tab.display_epoch != epoch {
                     return;
                 }
 
-                tab.display_inflight_tasks = tab.display_inflight_tasks.saturating_sub(1);
-
                 let (requested_indices, loaded_target_width, loaded_result) = load_result;
                 let mut loaded_indices = HashSet::new();
 
@@ -508,14 +752,20 @@ impl PdfViewer {
                     Ok(images) => {
                         for (ix, image) in images {
                             if let Some(page) = tab.pages.get_mut(ix) {
-                                page.display_image = Some(image);
-                                page.display_render_width = loaded_target_width;
+                                // Workers finish out of order; never let a preview replace a
+                                // sharper render that already landed.
+                                if page.display_image.is_none()
+                                    || loaded_target_width >= page.display_render_width
+                                {
+                                    page.display_image = Some(image);
+                                    page.display_render_width = loaded_target_width;
+                                }
                                 page.display_failed = false;
                                 loaded_indices.insert(ix);
                             }
                         }
                     }
-                    Err(_) => {}
+                    Err(err) => record_pdfium_render_error(format!("{err:#}")),
                 }
 
                 for ix in requested_indices {
@@ -526,6 +776,15 @@ impl PdfViewer {
                         page.display_failed = true;
                     }
                 }
+                // Landed or failed, the first pages are settled and loading is over.
+                tab.first_render_pending = false;
+                let mut rendered_pages: Vec<usize> = loaded_indices.into_iter().collect();
+                rendered_pages.sort_unstable();
+                this.record_display_render_timing(
+                    &rendered_pages,
+                    render_elapsed,
+                    loaded_target_width,
+                );
                 cx.notify();
             });
         })
@@ -541,7 +800,7 @@ impl PdfViewer {
         let Some(tab) = self.active_tab_mut() else {
             return;
         };
-        if visible_range.is_empty() || tab.pages.is_empty() {
+        if visible_range.is_empty() || tab.pages.is_empty() || tab.skeleton_only {
             return;
         }
 
@@ -550,10 +809,216 @@ impl PdfViewer {
         }
 
         tab.last_display_visible_range = Some(visible_range.clone());
+        let page_count = tab.pages.len();
+        tab.display_queue.reprioritize(
+            tab.active_page,
+            visible_range,
+            DISPLAY_PREFETCH_PAGES,
+            page_count,
+        );
+        // Pages that scrolled out of the render window are not worth finishing.
+        if tab
+            .display_loading
+            .iter()
+            .any(|ix| !tab.display_queue.contains(*ix))
+        {
+            tab.cancel_display_renders();
+        }
 
-        let mut candidate_order = Vec::with_capacity(visible_range.len());
-        candidate_order.extend(visible_range.clone());
+        for _ in 0..self.render_worker_count {
+            self.request_display_load_from_queue(target_width, cx);
+        }
+    }
 
-        self.request_display_load_from_candidates(candidate_order, target_width, cx);
+    fn request_display_tiles_for_visible_range(
+        &mut self,
+        visible_range: std::ops::Range<usize>,
+        target_width: u32,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        let language = self.language;
+        let options = self.render_options;
+        let viewport_height: f32 = window.viewport_size().height.into();
+        let Some(tab) = self.active_tab() else {
+            return;
+        };
+        if visible_range.is_empty() || tab.pages.is_empty() || tab.skeleton_only {
+            return;
+        }
+
+        // Work out which tile rows of each oversized page intersect the viewport.
+        let base_width = self.display_base_width(window, tab.zoom);
+        let scroll_top = -f32::from(tab.display_scroll.offset().y);
+        let mut page_top: f32 = tab.pages[..visible_range.start.min(tab.pages.len())]
+            .iter()
+            .map(|page| self.display_row_height(page, base_width))
+            .sum();
+        let mut wanted = Vec::new();
+        for ix in visible_range.clone() {
+            let Some(page) = tab.pages.get(ix) else {
+                break;
+            };
+            let (page_width, page_height) = self.display_card_size(page, base_width);
+            if Self::display_uses_tiles(page, target_width) && !page.display_failed {
+                let visible_top = (scroll_top - page_top).max(0.0);
+                let visible_bottom = (scroll_top + viewport_height - page_top).min(page_height);
+                if visible_bottom > visible_top {
+                    let render_width = Self::display_page_render_width(page, target_width);
+                    let device_per_logical = render_width as f32 / page_width;
+                    let tile_height = DISPLAY_TILE_HEIGHT as f32;
+                    let first_row = (visible_top * device_per_logical / tile_height) as u32;
+                    let last_row =
+                        ((visible_bottom * device_per_logical - 1.0).max(0.0) / tile_height) as u32;
+                    wanted.push((ix, render_width, first_row..=last_row));
+                }
+            }
+            page_top += page_height;
+        }
+
+        let Some(tab) = self.active_tab_mut() else {
+            return;
+        };
+        // Only pages on screen keep their tiles, which bounds tile memory to roughly a viewport.
+        tab.display_tiles.retain(|ix, _| visible_range.contains(ix));
+        for (ix, tiles) in tab.display_tiles.iter_mut() {
+            let render_width = tab
+                .pages
+                .get(*ix)
+                .map(|page| Self::display_page_render_width(page, target_width))
+                .unwrap_or(target_width);
+            if tiles.render_width != render_width {
+                let previous = std::mem::take(&mut tiles.tiles);
+                if !previous.is_empty() {
+                    tiles.stale = Some((tiles.render_width, previous));
+                }
+                tiles.render_width = render_width;
+                tiles.loading.clear();
+            }
+        }
+        for (ix, _, rows) in &wanted {
+            if let Some(tiles) = tab.display_tiles.get_mut(ix)
+                && rows.clone().all(|row| tiles.tiles.contains_key(&row))
+            {
+                tiles.stale = None;
+            }
+        }
+        if tab.display_tile_inflight {
+            return;
+        }
+
+        let Some(path) = tab.path.clone() else {
+            return;
+        };
+        let Some((page_index, render_width, rows)) =
+            wanted.into_iter().find_map(|(ix, render_width, rows)| {
+                let tiles = tab.display_tiles.get(&ix);
+                let pending = rows
+                    .filter(|row| {
+                        tiles.is_none_or(|tiles| {
+                            !tiles.tiles.contains_key(row) && !tiles.loading.contains(row)
+                        })
+                    })
+                    .take(DISPLAY_TILE_BATCH_SIZE)
+                    .collect::<Vec<_>>();
+                (!pending.is_empty()).then_some((ix, render_width, pending))
+            })
+        else {
+            return;
+        };
+
+        let tiles = tab.display_tiles.entry(page_index).or_default();
+        tiles.render_width = render_width;
+        tiles.loading.extend(rows.iter().copied());
+        tab.display_tile_inflight = true;
+        let epoch = tab.display_epoch;
+        let cancel_epoch = tab.display_cancel_epoch.clone();
+        let tab_id = tab.id;
+
+        cx.spawn(async move |view, cx| {
+            let loaded = cx
+                .background_executor()
+                .spawn(async move {
+                    load_display_tiles(
+                        &path,
+                        page_index,
+                        render_width,
+                        &rows,
+                        DISPLAY_TILE_HEIGHT,
+                        options,
+                        language,
+                        || cancel_epoch.load(Ordering::Relaxed) != epoch,
+                    )
+                    .map(|tiles| (rows, tiles))
+                })
+                .await;
+
+            let _ = view.update(cx, |this, cx| {
+                let is_active = this.tab_bar.active_tab_id() == Some(tab_id);
+                let Some(tab) = this.tab_bar.get_tab_mut(tab_id) else {
+                    return;
+                };
+                // Only one tile job runs at a time, so the next may start once this one stops.
+                tab.display_tile_inflight = false;
+                if !is_active || tab.display_epoch != epoch {
+                    return;
+                }
+
+                let mut failed = false;
+                if let Some(tiles) = tab.display_tiles.get_mut(&page_index) {
+                    match loaded {
+                        Ok((requested_rows, images)) => {
+                            failed = images.len() < requested_rows.len();
+                            for row in requested_rows {
+                                tiles.loading.remove(&row);
+                            }
+                            if tiles.render_width == render_width {
+                                tiles.tiles.extend(images);
+                            }
+                        }
+                        Err(err) => {
+                            record_pdfium_render_error(format!("{err:#}"));
+                            failed = true;
+                        }
+                    }
+                }
+                // A band that cannot render would otherwise be requested again every frame.
+                if failed {
+                    tab.display_tiles.remove(&page_index);
+                    if let Some(page) = tab.pages.get_mut(page_index) {
+                        page.display_failed = true;
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn retry_thumbnail_render(&mut self, page_index: usize, cx: &mut Context<Self>) {
+        let Some(page) = self
+            .active_tab_mut()
+            .and_then(|tab| tab.pages.get_mut(page_index))
+        else {
+            return;
+        };
+        if page.thumbnail_failed {
+            // The visible-range request on the next frame picks the page up again.
+            page.thumbnail_failed = false;
+            cx.notify();
+        }
+    }
+
+    fn retry_display_render(&mut self, page_index: usize, cx: &mut Context<Self>) {
+        let Some(page) = self
+            .active_tab_mut()
+            .and_then(|tab| tab.pages.get_mut(page_index))
+        else {
+            return;
+        };
+        if page.display_failed {
+            page.display_failed = false;
+            cx.notify();
+        }
     }
 }
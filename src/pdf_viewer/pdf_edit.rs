@@ -0,0 +1,133 @@
+use super::formats;
+use super::utils::{bgra_to_gpui_render_image, ensure_pdfium_ready, with_pdf_source};
+use crate::i18n::{I18n, Language};
+use anyhow::{Result, bail};
+use gpui::RenderImage;
+use kpdf_core::document::OpenError;
+use kpdf_core::edit;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+pub(crate) use kpdf_core::edit::{
+    EncryptPermissions, HeaderFooter, HeaderFooterAlignment, OptimizeReport, Watermark,
+    WatermarkContent, WatermarkPosition, parse_page_ranges,
+};
+
+/// Runs a [`kpdf_core::edit`] call on several documents, explaining a missing Pdfium library
+/// and a file Pdfium cannot open in the UI language. Single-document edits go through
+/// [`with_pdf_source`] instead.
+fn localized<T>(language: Language, f: impl FnOnce() -> Result<T>) -> Result<T> {
+    ensure_pdfium_ready(language)?;
+    f().map_err(|err| {
+        let message = err
+            .downcast_ref::<OpenError>()
+            .map(|open| I18n::new(language).pdfium_cannot_open_file(&open.path));
+        match message {
+            Some(message) => err.context(message),
+            None => err,
+        }
+    })
+}
+
+pub(crate) fn merge_documents(
+    inputs: &[PathBuf],
+    output: &Path,
+    language: Language,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<()> {
+    // Other formats are converted before the Pdfium lock is taken.
+    let sources = inputs
+        .iter()
+        .map(|input| formats::pdf_source(input, language))
+        .collect::<Result<Vec<_>>>()?;
+    localized(language, || {
+        edit::merge_documents(&sources, output, on_progress)
+    })
+}
+
+pub(crate) fn split_document(
+    input: &Path,
+    every: usize,
+    output_dir: &Path,
+    overwrite: bool,
+    language: Language,
+) -> Result<Vec<PathBuf>> {
+    with_pdf_source(input, language, |source| {
+        edit::split_document(source, every, output_dir, overwrite)
+    })
+}
+
+pub(crate) fn optimize_document(
+    input: &Path,
+    output: &Path,
+    max_image_dpi: Option<u32>,
+    language: Language,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<OptimizeReport> {
+    with_pdf_source(input, language, |source| {
+        edit::optimize_document(source, output, max_image_dpi, on_progress)
+    })
+}
+
+pub(crate) fn stamp_watermark(
+    input: &Path,
+    output: &Path,
+    watermark: &Watermark,
+    language: Language,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<usize> {
+    with_pdf_source(input, language, |source| {
+        edit::stamp_watermark(source, output, watermark, on_progress)
+    })
+}
+
+pub(crate) fn stamp_header_footer(
+    input: &Path,
+    output: &Path,
+    header_footer: &HeaderFooter,
+    language: Language,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<usize> {
+    with_pdf_source(input, language, |source| {
+        edit::stamp_header_footer(source, output, header_footer, on_progress)
+    })
+}
+
+pub(crate) fn render_header_footer_preview(
+    input: &Path,
+    header_footer: &HeaderFooter,
+    target_width: u32,
+    language: Language,
+) -> Result<Arc<RenderImage>> {
+    let (width, height, bytes) = with_pdf_source(input, language, |source| {
+        edit::render_header_footer_preview(source, header_footer, target_width)
+    })?;
+    bgra_to_gpui_render_image(width, height, bytes, language)
+}
+
+pub(crate) fn save_encrypted_copy(
+    input: &Path,
+    output: &Path,
+    user_password: &str,
+    owner_password: &str,
+    permissions: EncryptPermissions,
+    language: Language,
+) -> Result<()> {
+    if user_password.is_empty() && owner_password.is_empty() {
+        bail!(I18n::new(language).encrypt_missing_password);
+    }
+    with_pdf_source(input, language, |source| {
+        edit::save_encrypted_copy(source, output, user_password, owner_password, permissions)
+    })
+}
+
+pub(crate) fn save_copy_with_bookmark_outline(
+    input: &Path,
+    output: &Path,
+    bookmarks: &[(usize, String)],
+    language: Language,
+) -> Result<usize> {
+    with_pdf_source(input, language, |source| {
+        edit::save_copy_with_bookmark_outline(source, output, bookmarks)
+    })
+}
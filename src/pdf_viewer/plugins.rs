@@ -0,0 +1,287 @@
+use super::PdfViewer;
+use super::utils::load_page_text_for_selection;
+use anyhow::{Context as _, Result, bail};
+use gpui::*;
+use serde::Deserialize;
+use std::io::Write as _;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+const PLUGIN_MANIFEST_FILE_NAME: &str = "plugin.json";
+
+/// One command contributed by a plugin manifest.
+///
+/// Plugins are directories under [`plugins_dir`] holding a `plugin.json`:
+///
+/// ```json
+/// {
+///   "name": "OCR",
+///   "commands": [
+///     {
+///       "title": "OCR current page",
+///       "subtitle": "Runs tesseract on the page",
+///       "command": ["python3", "ocr.py", "{file}", "{page}"],
+///       "context_menu": true
+///     }
+///   ]
+/// }
+/// ```
+///
+/// `{file}`, `{page}` (1-based) and `{page_count}` are substituted in every argument and
+/// also exported as `KPDF_FILE`, `KPDF_PAGE` and `KPDF_PAGE_COUNT`; the selected text is in
+/// `KPDF_SELECTED_TEXT` and the text of the current page is written to stdin. A stdout line
+/// `goto <page>` navigates the active tab to that 1-based page.
+#[derive(Clone, Debug)]
+pub(super) struct PluginCommand {
+    pub(super) plugin_name: String,
+    pub(super) title: String,
+    pub(super) subtitle: String,
+    pub(super) in_context_menu: bool,
+    program: String,
+    args: Vec<String>,
+    working_dir: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct RawPluginManifest {
+    name: String,
+    #[serde(default)]
+    commands: Vec<RawPluginCommand>,
+}
+
+#[derive(Deserialize)]
+struct RawPluginCommand {
+    title: String,
+    #[serde(default)]
+    subtitle: String,
+    command: Vec<String>,
+    #[serde(default)]
+    context_menu: bool,
+}
+
+/// Document state handed to a running plugin command.
+#[derive(Clone, Debug)]
+pub(super) struct PluginContext {
+    pub(super) file: PathBuf,
+    pub(super) page_index: usize,
+    pub(super) page_count: usize,
+    pub(super) selected_text: String,
+}
+
+pub(super) fn plugins_dir() -> Option<PathBuf> {
+    kpdf_core::paths::data_dir().map(|dir| dir.join("plugins"))
+}
+
+/// Reads every plugin manifest; broken manifests are logged and skipped.
+pub(super) fn load_plugin_commands() -> Vec<PluginCommand> {
+    let Some(dir) = plugins_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut plugin_dirs = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.join(PLUGIN_MANIFEST_FILE_NAME).is_file())
+        .collect::<Vec<_>>();
+    plugin_dirs.sort();
+
+    let mut commands = Vec::new();
+    for plugin_dir in plugin_dirs {
+        match load_plugin_manifest(&plugin_dir) {
+            Ok(plugin_commands) => {
+                crate::debug_log!(
+                    "[plugins] loaded {} commands from {}",
+                    plugin_commands.len(),
+                    plugin_dir.display()
+                );
+                commands.extend(plugin_commands);
+            }
+            Err(err) => {
+                crate::debug_log!("[plugins] skipped {}: {:#}", plugin_dir.display(), err);
+            }
+        }
+    }
+    commands
+}
+
+fn load_plugin_manifest(plugin_dir: &Path) -> Result<Vec<PluginCommand>> {
+    let manifest_path = plugin_dir.join(PLUGIN_MANIFEST_FILE_NAME);
+    let raw = std::fs::read_to_string(&manifest_path)
+        .with_context(|| format!("read {}", manifest_path.display()))?;
+    let manifest = serde_json::from_str::<RawPluginManifest>(&raw)
+        .with_context(|| format!("parse {}", manifest_path.display()))?;
+
+    manifest
+        .commands
+        .into_iter()
+        .map(|command| {
+            let Some((program, args)) = command.command.split_first() else {
+                bail!("command \"{}\" has no program", command.title);
+            };
+            Ok(PluginCommand {
+                plugin_name: manifest.name.clone(),
+                title: command.title,
+                subtitle: command.subtitle,
+                in_context_menu: command.context_menu,
+                program: program.clone(),
+                args: args.to_vec(),
+                working_dir: plugin_dir.to_path_buf(),
+            })
+        })
+        .collect()
+}
+
+fn expand_placeholders(arg: &str, context: &PluginContext) -> String {
+    arg.replace("{file}", &context.file.to_string_lossy())
+        .replace("{page}", &(context.page_index + 1).to_string())
+        .replace("{page_count}", &context.page_count.to_string())
+}
+
+/// Returns the 1-based page from the last `goto <page>` line, if any.
+fn parse_goto_page(stdout: &str) -> Option<usize> {
+    stdout
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("goto "))
+        .filter_map(|page| page.trim().parse::<usize>().ok())
+        .filter(|page| *page > 0)
+        .last()
+}
+
+/// Runs `command` to completion and returns the page index it asked to navigate to.
+fn run_plugin_command(command: &PluginCommand, context: &PluginContext) -> Result<Option<usize>> {
+    let page_text = load_page_text_for_selection(&context.file, context.page_index)
+        .ok()
+        .flatten()
+        .map(|(_, _, _, chars)| chars.into_iter().map(|info| info.text).collect::<String>())
+        .unwrap_or_default();
+
+    let program = if Path::new(&command.program).is_relative()
+        && command.working_dir.join(&command.program).is_file()
+    {
+        command.working_dir.join(&command.program)
+    } else {
+        PathBuf::from(&command.program)
+    };
+    let mut child = Command::new(&program)
+        .args(
+            command
+                .args
+                .iter()
+                .map(|arg| expand_placeholders(arg, context)),
+        )
+        .current_dir(&command.working_dir)
+        .env("KPDF_FILE", &context.file)
+        .env("KPDF_PAGE", (context.page_index + 1).to_string())
+        .env("KPDF_PAGE_COUNT", context.page_count.to_string())
+        .env("KPDF_SELECTED_TEXT", &context.selected_text)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("start {}", program.display()))?;
+
+    // Feed stdin from its own thread while `wait_with_output` drains stdout and stderr, so a
+    // plugin that prints before it finishes reading cannot fill a pipe and deadlock.
+    let stdin_writer = child
+        .stdin
+        .take()
+        .map(|mut stdin| {
+            std::thread::Builder::new()
+                .name("kpdf-plugin-stdin".to_string())
+                .spawn(move || {
+                    // A plugin that never reads stdin closes the pipe early; that is not an error.
+                    let _ = stdin.write_all(page_text.as_bytes());
+                })
+        })
+        .transpose()
+        .context("feed plugin stdin")?;
+    let output = child.wait_with_output()?;
+    if let Some(writer) = stdin_writer {
+        let _ = writer.join();
+    }
+    if !output.status.success() {
+        bail!(
+            "{} exited with {}: {}",
+            program.display(),
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(parse_goto_page(&String::from_utf8_lossy(&output.stdout)).map(|page| page - 1))
+}
+
+impl PdfViewer {
+    fn plugin_context(&self) -> Option<PluginContext> {
+        let tab = self.active_tab()?;
+        let file = tab.path.clone()?;
+        let selected_text = tab
+            .text_selection_manager
+            .borrow()
+            .get_selected_text()
+            .unwrap_or_default();
+        Some(PluginContext {
+            file,
+            page_index: tab.active_page,
+            page_count: tab.pages.len(),
+            selected_text,
+        })
+    }
+
+    pub(super) fn run_plugin_command(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(command) = self.plugin_commands.get(index).cloned() else {
+            return;
+        };
+        let Some(context) = self.plugin_context() else {
+            crate::debug_log!("[plugins] {} needs an open document", command.title);
+            return;
+        };
+        let Some(tab_id) = self.tab_bar.active_tab_id() else {
+            return;
+        };
+
+        cx.spawn(async move |view, cx| {
+            let title = command.title.clone();
+            let result = cx
+                .background_executor()
+                .spawn(async move { run_plugin_command(&command, &context) })
+                .await;
+            match result {
+                Ok(Some(page_index)) => {
+                    let _ = view.update(cx, |this, cx| {
+                        if this.tab_bar.active_tab_id() == Some(tab_id) {
+                            this.select_page(page_index, cx);
+                        }
+                    });
+                }
+                Ok(None) => {}
+                Err(err) => crate::debug_log!(@warn, "[plugins] {} failed: {:#}", title, err),
+            }
+        })
+        .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_and_goto() {
+        let context = PluginContext {
+            file: PathBuf::from("/tmp/a.pdf"),
+            page_index: 2,
+            page_count: 10,
+            selected_text: String::new(),
+        };
+        assert_eq!(
+            expand_placeholders("{file}:{page}/{page_count}", &context),
+            "/tmp/a.pdf:3/10"
+        );
+        assert_eq!(parse_goto_page("log\ngoto 4\ngoto 7\n"), Some(7));
+        assert_eq!(parse_goto_page("goto 0\ngoto x"), None);
+    }
+}
@@ -0,0 +1,70 @@
+//! Whether the machine is running on mains power, so background work can hold off on
+//! battery. Anything that cannot be determined counts as mains power, which is what
+//! desktops without a battery report anyway.
+
+/// `false` only when the platform says the machine is running on battery.
+pub(super) fn on_ac_power() -> bool {
+    imp::on_ac_power()
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    /// `ACLineStatus` value for "offline"; 1 is online and 255 unknown.
+    const AC_LINE_OFFLINE: u8 = 0;
+
+    pub(super) fn on_ac_power() -> bool {
+        let mut status = SYSTEM_POWER_STATUS::default();
+        if unsafe { GetSystemPowerStatus(&mut status) }.is_err() {
+            return true;
+        }
+        status.ACLineStatus != AC_LINE_OFFLINE
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod imp {
+    use std::process::Command;
+
+    pub(super) fn on_ac_power() -> bool {
+        // First line reads "Now drawing from 'AC Power'" or "... 'Battery Power'".
+        let Ok(output) = Command::new("pmset").args(["-g", "batt"]).output() else {
+            return true;
+        };
+        !String::from_utf8_lossy(&output.stdout).contains("'Battery Power'")
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod imp {
+    use std::path::Path;
+
+    const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+    pub(super) fn on_ac_power() -> bool {
+        let Ok(entries) = std::fs::read_dir(POWER_SUPPLY_DIR) else {
+            return true;
+        };
+        // A discharging battery is the one reliable signal; mains adapters are not always
+        // exposed, and desktops have no battery at all.
+        !entries.filter_map(Result::ok).any(|entry| {
+            let supply = entry.path();
+            read_attribute(&supply, "type").as_deref() == Some("Battery")
+                && read_attribute(&supply, "status").as_deref() == Some("Discharging")
+        })
+    }
+
+    fn read_attribute(supply: &Path, name: &str) -> Option<String> {
+        std::fs::read_to_string(supply.join(name))
+            .ok()
+            .map(|value| value.trim().to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+mod imp {
+    pub(super) fn on_ac_power() -> bool {
+        true
+    }
+}
@@ -0,0 +1,459 @@
+use super::{
+    PRESENTATION_LASER_DOT_SIZE, PRESENTATION_POINTER_HOLD_MS, PRESENTATION_SPOTLIGHT_RADIUS,
+    PRESENTATION_WHEEL_THRESHOLD, PdfViewer, PresentationPointer, SWIPE_GESTURE_IDLE_MS,
+    SwipeGesture,
+};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::*;
+use std::time::{Duration, Instant};
+
+impl PdfViewer {
+    pub(super) fn toggle_presentation(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.presentation_active {
+            self.exit_presentation(window, cx);
+        } else {
+            self.enter_presentation(window, cx);
+        }
+    }
+
+    pub(super) fn enter_presentation(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.presentation_active || self.active_tab_pages().is_none_or(|pages| pages.is_empty())
+        {
+            return;
+        }
+        self.presentation_active = true;
+        self.presentation_started_at = Some(Instant::now());
+        self.presentation_session = self.presentation_session.wrapping_add(1);
+        self.presentation_owns_fullscreen = !window.is_fullscreen();
+        if self.presentation_owns_fullscreen {
+            window.toggle_fullscreen();
+        }
+        self.close_context_menu(cx);
+        self.close_text_selection_hover_menu(cx);
+        window.focus(&self.focus_handle);
+        self.schedule_presentation_timer_tick(cx);
+        self.schedule_presentation_auto_advance(cx);
+        self.schedule_fullscreen_idle_hide(cx);
+        cx.notify();
+    }
+
+    pub(super) fn exit_presentation(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if !self.presentation_active {
+            return;
+        }
+        self.presentation_active = false;
+        self.presentation_started_at = None;
+        self.presentation_session = self.presentation_session.wrapping_add(1);
+        self.presentation_slide_epoch = self.presentation_slide_epoch.wrapping_add(1);
+        if self.presentation_owns_fullscreen && window.is_fullscreen() {
+            window.toggle_fullscreen();
+        }
+        self.presentation_owns_fullscreen = false;
+        self.presentation_press_position = None;
+        self.presentation_pointer_visible = false;
+        self.fullscreen_chrome_hidden = false;
+        self.schedule_fullscreen_idle_hide(cx);
+        cx.notify();
+    }
+
+    fn presentation_next_slide(&mut self, cx: &mut Context<Self>) {
+        self.next_page(cx);
+        self.schedule_presentation_auto_advance(cx);
+    }
+
+    fn presentation_prev_slide(&mut self, cx: &mut Context<Self>) {
+        self.prev_page(cx);
+        self.schedule_presentation_auto_advance(cx);
+    }
+
+    /// Flips slides from the scroll wheel, since a slide always fits the screen. Every wheel
+    /// tick turns one slide; a trackpad gesture turns one once it travels past
+    /// `PRESENTATION_WHEEL_THRESHOLD`, so its momentum does not race through the deck.
+    fn presentation_scroll_wheel(&mut self, event: &ScrollWheelEvent, cx: &mut Context<Self>) {
+        let now = Instant::now();
+        let gesture = &mut self.presentation_wheel;
+        let idle = gesture
+            .last_event_at
+            .is_none_or(|at| now.duration_since(at) > Duration::from_millis(SWIPE_GESTURE_IDLE_MS));
+        if idle || event.touch_phase == TouchPhase::Started {
+            *gesture = SwipeGesture::default();
+        }
+        gesture.last_event_at = Some(now);
+        if gesture.handled {
+            return;
+        }
+        let (delta, threshold, per_gesture) = match event.delta {
+            ScrollDelta::Lines(delta) => (delta.y, 1.0, false),
+            ScrollDelta::Pixels(delta) => (f32::from(delta.y), PRESENTATION_WHEEL_THRESHOLD, true),
+        };
+        gesture.delta_y += delta;
+        if gesture.delta_y.abs() < threshold {
+            return;
+        }
+        let forward = gesture.delta_y < 0.0;
+        gesture.delta_y = 0.0;
+        gesture.handled = per_gesture;
+        if forward {
+            self.presentation_next_slide(cx);
+        } else {
+            self.presentation_prev_slide(cx);
+        }
+    }
+
+    /// Slide keys while presenting; returns false for keys left to the regular shortcuts.
+    pub(super) fn handle_presentation_key(
+        &mut self,
+        key: &str,
+        is_primary_modifier: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if is_primary_modifier {
+            return false;
+        }
+        let right_to_left = self.page_order_right_to_left();
+        match key {
+            "escape" | "f5" => self.exit_presentation(window, cx),
+            "left" if right_to_left => self.presentation_next_slide(cx),
+            "right" if right_to_left => self.presentation_prev_slide(cx),
+            "right" | "down" | "pagedown" | "space" | "enter" => self.presentation_next_slide(cx),
+            "left" | "up" | "pageup" | "backspace" => self.presentation_prev_slide(cx),
+            "home" => {
+                self.first_page(cx);
+                self.schedule_presentation_auto_advance(cx);
+            }
+            "end" => {
+                self.last_page(cx);
+                self.schedule_presentation_auto_advance(cx);
+            }
+            "t" => self.toggle_presentation_timer(cx),
+            "l" => {
+                let ix = PresentationPointer::ALL
+                    .iter()
+                    .position(|pointer| *pointer == self.presentation_pointer)
+                    .unwrap_or(0);
+                let next = PresentationPointer::ALL[(ix + 1) % PresentationPointer::ALL.len()];
+                self.set_presentation_pointer(next, cx);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn toggle_presentation_timer(&mut self, cx: &mut Context<Self>) {
+        self.set_presentation_timer_visible(!self.presentation_timer_visible, cx);
+    }
+
+    pub(super) fn set_presentation_timer_visible(&mut self, visible: bool, cx: &mut Context<Self>) {
+        if self.presentation_timer_visible != visible {
+            self.presentation_timer_visible = visible;
+            self.persist_presentation_preferences();
+            cx.notify();
+        }
+    }
+
+    pub(super) fn set_presentation_auto_advance_secs(&mut self, secs: u8, cx: &mut Context<Self>) {
+        if self.presentation_auto_advance_secs != secs {
+            self.presentation_auto_advance_secs = secs;
+            self.persist_presentation_preferences();
+            self.schedule_presentation_auto_advance(cx);
+            cx.notify();
+        }
+    }
+
+    pub(super) fn set_presentation_pointer(
+        &mut self,
+        pointer: PresentationPointer,
+        cx: &mut Context<Self>,
+    ) {
+        if self.presentation_pointer != pointer {
+            self.presentation_pointer = pointer;
+            self.persist_presentation_preferences();
+            cx.notify();
+        }
+    }
+
+    /// A press becomes the pointer overlay once it is held; a quick click still advances.
+    fn presentation_press(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        self.presentation_press_position = Some(position);
+        self.presentation_pointer_visible = false;
+        self.presentation_press_epoch = self.presentation_press_epoch.wrapping_add(1);
+        if self.presentation_pointer == PresentationPointer::Off {
+            return;
+        }
+
+        let press_epoch = self.presentation_press_epoch;
+        cx.spawn(async move |view, cx| {
+            cx.background_executor()
+                .timer(Duration::from_millis(PRESENTATION_POINTER_HOLD_MS))
+                .await;
+
+            let _ = view.update(cx, |this, cx| {
+                if this.presentation_press_epoch == press_epoch
+                    && this.presentation_press_position.is_some()
+                {
+                    this.presentation_pointer_visible = true;
+                    // Holding the pointer on a slide should not flip it away underneath.
+                    this.presentation_slide_epoch = this.presentation_slide_epoch.wrapping_add(1);
+                    cx.notify();
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn presentation_release(&mut self, cx: &mut Context<Self>) {
+        if self.presentation_press_position.take().is_none() {
+            return;
+        }
+        if self.presentation_pointer_visible {
+            self.presentation_pointer_visible = false;
+            self.schedule_presentation_auto_advance(cx);
+            cx.notify();
+        } else {
+            self.presentation_next_slide(cx);
+        }
+    }
+
+    fn presentation_pointer_moved(&mut self, position: Point<Pixels>, cx: &mut Context<Self>) {
+        if self.presentation_press_position.is_some() {
+            self.presentation_press_position = Some(position);
+            if self.presentation_pointer_visible {
+                cx.notify();
+            }
+        }
+    }
+
+    fn render_presentation_pointer(
+        &self,
+        position: Point<Pixels>,
+        viewport_width: f32,
+        viewport_height: f32,
+    ) -> AnyElement {
+        match self.presentation_pointer {
+            PresentationPointer::Off => div().into_any_element(),
+            PresentationPointer::Laser => div()
+                .absolute()
+                .left(position.x - px(PRESENTATION_LASER_DOT_SIZE / 2.0))
+                .top(position.y - px(PRESENTATION_LASER_DOT_SIZE / 2.0))
+                .size(px(PRESENTATION_LASER_DOT_SIZE))
+                .rounded_full()
+                .bg(rgb(0xff2020))
+                .shadow_lg()
+                .into_any_element(),
+            PresentationPointer::Spotlight => {
+                // A ring whose border reaches past every window edge from any cursor
+                // position, leaving a clear circle of the spotlight radius in the middle.
+                let reach = viewport_width.hypot(viewport_height);
+                let outer = PRESENTATION_SPOTLIGHT_RADIUS + reach;
+                let mut ring = div()
+                    .absolute()
+                    .left(position.x - px(outer))
+                    .top(position.y - px(outer))
+                    .size(px(outer * 2.0))
+                    .border_color(black().opacity(0.7));
+                let style = ring.style();
+                style.border_widths = EdgesRefinement {
+                    top: Some(px(reach).into()),
+                    right: Some(px(reach).into()),
+                    bottom: Some(px(reach).into()),
+                    left: Some(px(reach).into()),
+                };
+                style.corner_radii = CornersRefinement {
+                    top_left: Some(px(outer).into()),
+                    top_right: Some(px(outer).into()),
+                    bottom_right: Some(px(outer).into()),
+                    bottom_left: Some(px(outer).into()),
+                };
+                ring.into_any_element()
+            }
+        }
+    }
+
+    /// Repaints once a second so the elapsed timer keeps counting; stops with the session.
+    fn schedule_presentation_timer_tick(&mut self, cx: &mut Context<Self>) {
+        let session = self.presentation_session;
+        cx.spawn(async move |view, cx| {
+            loop {
+                cx.background_executor().timer(Duration::from_secs(1)).await;
+
+                let running = view
+                    .update(cx, |this, cx| {
+                        if !this.presentation_active || this.presentation_session != session {
+                            return false;
+                        }
+                        if this.presentation_timer_visible {
+                            cx.notify();
+                        }
+                        true
+                    })
+                    .unwrap_or(false);
+                if !running {
+                    break;
+                }
+            }
+        })
+        .detach();
+    }
+
+    /// Restarts the auto-advance countdown; any manual slide change pushes it back.
+    fn schedule_presentation_auto_advance(&mut self, cx: &mut Context<Self>) {
+        self.presentation_slide_epoch = self.presentation_slide_epoch.wrapping_add(1);
+        let on_last_slide = self
+            .active_tab_pages()
+            .is_none_or(|pages| self.active_tab_active_page() + 1 >= pages.len());
+        if !self.presentation_active || self.presentation_auto_advance_secs == 0 || on_last_slide {
+            return;
+        }
+
+        let slide_epoch = self.presentation_slide_epoch;
+        let delay = Duration::from_secs(u64::from(self.presentation_auto_advance_secs));
+        cx.spawn(async move |view, cx| {
+            cx.background_executor().timer(delay).await;
+
+            let _ = view.update(cx, |this, cx| {
+                if this.presentation_active && this.presentation_slide_epoch == slide_epoch {
+                    this.presentation_next_slide(cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    fn presentation_elapsed_label(&self) -> SharedString {
+        let elapsed = self
+            .presentation_started_at
+            .map(|started_at| started_at.elapsed().as_secs())
+            .unwrap_or(0);
+        let (hours, minutes, seconds) = (elapsed / 3600, elapsed / 60 % 60, elapsed % 60);
+        if hours > 0 {
+            format!("{hours}:{minutes:02}:{seconds:02}").into()
+        } else {
+            format!("{minutes:02}:{seconds:02}").into()
+        }
+    }
+
+    pub(super) fn render_presentation(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let viewport = window.viewport_size();
+        let (viewport_width, viewport_height) =
+            (f32::from(viewport.width), f32::from(viewport.height));
+        let active_page = self.active_tab_active_page();
+        let page_count = self.active_tab_pages().map_or(0, |pages| pages.len());
+        let slide = self
+            .active_tab_pages()
+            .and_then(|pages| pages.get(active_page))
+            .map(|page| {
+                let aspect_ratio = if page.width_pt > 1.0 {
+                    page.height_pt / page.width_pt
+                } else {
+                    1.4
+                };
+                // Fit the whole page on screen, letterboxed on the longer side.
+                let width = viewport_width.min(viewport_height / aspect_ratio);
+                let image = page
+                    .display_image
+                    .clone()
+                    .or_else(|| page.thumbnail_image.clone());
+                (width, width * aspect_ratio, image)
+            });
+
+        if let Some((width, _, _)) = slide {
+            let target_width = (width * window.scale_factor()).clamp(1.0, i32::MAX as f32);
+            self.request_display_load_for_visible_range(
+                active_page..(active_page + 1).min(page_count),
+                target_width.round() as u32,
+                cx,
+            );
+        }
+
+        let pointer_position = self
+            .presentation_press_position
+            .filter(|_| self.presentation_pointer_visible);
+
+        div()
+            .id("presentation")
+            .absolute()
+            .top_0()
+            .left_0()
+            .right_0()
+            .bottom_0()
+            .flex()
+            .items_center()
+            .justify_center()
+            .bg(black())
+            .occlude()
+            .when_some(slide, |this, (width, height, image)| {
+                this.child(
+                    div()
+                        .w(px(width))
+                        .h(px(height))
+                        .bg(white())
+                        .when_some(image, |this, image| {
+                            this.child(img(image).size_full().object_fit(ObjectFit::Contain))
+                        }),
+                )
+            })
+            .when(self.presentation_timer_visible, |this| {
+                this.child(
+                    div()
+                        .absolute()
+                        .bottom_4()
+                        .right_4()
+                        .px_3()
+                        .py_1()
+                        .rounded_md()
+                        .bg(black().opacity(0.6))
+                        .text_color(white())
+                        .text_sm()
+                        .child(self.presentation_elapsed_label()),
+                )
+            })
+            .when(self.fullscreen_chrome_hidden, |this| {
+                this.cursor(CursorStyle::None)
+            })
+            .when_some(pointer_position, |this, position| {
+                this.cursor(CursorStyle::None)
+                    .child(self.render_presentation_pointer(
+                        position,
+                        viewport_width,
+                        viewport_height,
+                    ))
+            })
+            .on_mouse_down(
+                MouseButton::Left,
+                cx.listener(|this, event: &MouseDownEvent, window, cx| {
+                    window.focus(&this.focus_handle);
+                    this.presentation_press(event.position, cx);
+                    cx.stop_propagation();
+                }),
+            )
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, _, _, cx| {
+                    this.presentation_release(cx);
+                    cx.stop_propagation();
+                }),
+            )
+            .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, _, cx| {
+                this.presentation_pointer_moved(event.position, cx);
+                this.note_fullscreen_pointer_activity(cx);
+            }))
+            .on_scroll_wheel(cx.listener(|this, event: &ScrollWheelEvent, _, cx| {
+                this.presentation_scroll_wheel(event, cx);
+                cx.stop_propagation();
+            }))
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(|this, _, window, cx| {
+                    window.focus(&this.focus_handle);
+                    this.presentation_prev_slide(cx);
+                    cx.stop_propagation();
+                }),
+            )
+    }
+}
@@ -0,0 +1,193 @@
+//! Sends documents to a printer without the viewer: CUPS `lp` on macOS and Linux, the
+//! GDI spooler on Windows (pages are rasterized by Pdfium at the printer's resolution).
+
+use crate::i18n::Language;
+use anyhow::Result;
+use kpdf_core::edit::PageSpan;
+use std::path::Path;
+
+/// Prints `pages` (1-based, all pages when empty) of `path` on `printer`, or on the
+/// system default printer.
+pub(crate) fn print_document(
+    path: &Path,
+    printer: Option<&str>,
+    pages: &[PageSpan],
+    language: Language,
+) -> Result<()> {
+    crate::debug_log!(
+        "[print] {} on {} (pages: {:?})",
+        path.display(),
+        printer.unwrap_or("default printer"),
+        pages
+    );
+    spool(path, printer, pages, language)
+}
+
+#[cfg(not(target_os = "windows"))]
+fn spool(path: &Path, printer: Option<&str>, pages: &[PageSpan], language: Language) -> Result<()> {
+    use super::formats;
+    use anyhow::{Context as _, bail};
+
+    let source = formats::pdf_source(path, language)?;
+    let mut command = std::process::Command::new("lp");
+    if let Some(printer) = printer {
+        command.arg("-d").arg(printer);
+    }
+    if !pages.is_empty() {
+        let page_list = pages
+            .iter()
+            .map(|span| match span.last {
+                Some(last) => format!("{}-{}", span.first, last),
+                None => format!("{}-", span.first),
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        command.arg("-P").arg(page_list);
+    }
+    command
+        .arg("-t")
+        .arg(super::utils::display_file_name(path))
+        .arg("--")
+        .arg(&source);
+
+    let output = command.output().context("run lp (is CUPS installed?)")?;
+    if !output.status.success() {
+        bail!(
+            "lp failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    crate::debug_log!("[print] {}", String::from_utf8_lossy(&output.stdout).trim());
+    Ok(())
+}
+
+#[cfg(target_os = "windows")]
+fn spool(path: &Path, printer: Option<&str>, pages: &[PageSpan], language: Language) -> Result<()> {
+    use super::renderer::renderer_for;
+    use super::utils::{display_file_name, load_document_summary};
+    use anyhow::{anyhow, bail};
+    use kpdf_core::render::{RenderEngine, RenderOptions};
+    use windows::Win32::Graphics::Gdi::{
+        BI_RGB, BITMAPINFO, BITMAPINFOHEADER, CreateDCW, DIB_RGB_COLORS, DeleteDC, GetDeviceCaps,
+        HALFTONE, HORZRES, SRCCOPY, SetStretchBltMode, StretchDIBits, VERTRES,
+    };
+    use windows::Win32::Graphics::Printing::GetDefaultPrinterW;
+    use windows::Win32::Storage::Xps::{AbortDoc, DOCINFOW, EndDoc, EndPage, StartDocW, StartPage};
+    use windows::core::{HSTRING, PCWSTR, PWSTR};
+
+    let (page_count, _) = load_document_summary(path, language, 0, 0, |_, _| {})?;
+    let page_indices = if pages.is_empty() {
+        (0..page_count).collect::<Vec<_>>()
+    } else {
+        let mut indices = Vec::new();
+        for span in pages {
+            indices.extend(span.indices(page_count).map_err(|page| {
+                anyhow!("page {page} is out of range (the document has {page_count} pages)")
+            })?);
+        }
+        indices
+    };
+
+    let printer = match printer {
+        Some(printer) => printer.to_owned(),
+        None => {
+            let mut len = 0u32;
+            unsafe {
+                let _ = GetDefaultPrinterW(None, &mut len);
+            }
+            let mut buffer = vec![0u16; len as usize];
+            if len == 0
+                || !unsafe { GetDefaultPrinterW(Some(PWSTR(buffer.as_mut_ptr())), &mut len) }
+                    .as_bool()
+            {
+                bail!("no default printer is configured");
+            }
+            String::from_utf16_lossy(&buffer[..len.saturating_sub(1) as usize])
+        }
+    };
+
+    let device = HSTRING::from(printer.as_str());
+    let hdc = unsafe { CreateDCW(PCWSTR::null(), &device, PCWSTR::null(), None) };
+    if hdc.is_invalid() {
+        bail!("cannot open printer {printer}");
+    }
+    let document_name = HSTRING::from(display_file_name(path));
+    let document_info = DOCINFOW {
+        cbSize: std::mem::size_of::<DOCINFOW>() as i32,
+        lpszDocName: PCWSTR(document_name.as_ptr()),
+        ..Default::default()
+    };
+
+    let printed = (|| -> Result<()> {
+        if unsafe { StartDocW(hdc, &document_info) } <= 0 {
+            bail!("printer {printer} refused the job");
+        }
+        let (area_width, area_height) = unsafe {
+            (
+                GetDeviceCaps(Some(hdc), HORZRES),
+                GetDeviceCaps(Some(hdc), VERTRES),
+            )
+        };
+        for page_index in page_indices {
+            let (width, height, bgra) = renderer_for(RenderEngine::Pdfium).render_page(
+                path,
+                page_index,
+                area_width.max(1) as u32,
+                RenderOptions::default(),
+                language,
+            )?;
+            // Fit the page into the printable area, centered.
+            let scale = (area_width as f32 / width as f32).min(area_height as f32 / height as f32);
+            let (dest_width, dest_height) = (
+                (width as f32 * scale) as i32,
+                (height as f32 * scale) as i32,
+            );
+            let bitmap_info = BITMAPINFO {
+                bmiHeader: BITMAPINFOHEADER {
+                    biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                    biWidth: width as i32,
+                    biHeight: -(height as i32),
+                    biPlanes: 1,
+                    biBitCount: 32,
+                    biCompression: BI_RGB.0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            unsafe {
+                if StartPage(hdc) <= 0 {
+                    return Err(anyhow!("printer {printer} refused page {}", page_index + 1));
+                }
+                SetStretchBltMode(hdc, HALFTONE);
+                StretchDIBits(
+                    hdc,
+                    (area_width - dest_width) / 2,
+                    (area_height - dest_height) / 2,
+                    dest_width,
+                    dest_height,
+                    0,
+                    0,
+                    width as i32,
+                    height as i32,
+                    Some(bgra.as_ptr().cast()),
+                    &bitmap_info,
+                    DIB_RGB_COLORS,
+                    SRCCOPY,
+                );
+                EndPage(hdc);
+            }
+        }
+        unsafe {
+            EndDoc(hdc);
+        }
+        Ok(())
+    })();
+
+    unsafe {
+        if printed.is_err() {
+            AbortDoc(hdc);
+        }
+        let _ = DeleteDC(hdc);
+    }
+    printed
+}
@@ -341,6 +341,96 @@ impl PdfViewer {
             .collect()
     }
 
+    /// Saves a copy of the active document with its bookmarks added to the PDF outline, so
+    /// other viewers show them too.
+    pub(super) fn save_copy_with_bookmark_outline(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(path) = self.active_tab_path().cloned() else {
+            return;
+        };
+        let i18n = self.i18n();
+        let mut bookmarks = self.bookmarks_for_scope(BookmarkScope::CurrentPdf);
+        if bookmarks.is_empty() {
+            crate::debug_log!("[bookmark] no bookmarks to save for {}", path.display());
+            let _ = window.prompt(
+                PromptLevel::Info,
+                i18n.bookmark_outline_none,
+                None,
+                &[i18n.close_button],
+                cx,
+            );
+            return;
+        }
+        bookmarks.sort_by_key(|bookmark| bookmark.page_index);
+        let entries = bookmarks
+            .into_iter()
+            .map(|bookmark| {
+                (
+                    bookmark.page_index,
+                    i18n.bookmark_page_label(bookmark.page_index + 1),
+                )
+            })
+            .collect::<Vec<_>>();
+        let language = self.language;
+
+        let directory = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let suggested_name = path
+            .file_stem()
+            .map(|stem| format!("{}-bookmarks.pdf", stem.to_string_lossy()))
+            .unwrap_or_else(|| "bookmarks.pdf".to_string());
+        let picker = cx.prompt_for_new_path(&directory, Some(&suggested_name));
+
+        cx.spawn_in(window, async move |_, cx| {
+            let Ok(Ok(Some(output))) = picker.await else {
+                return;
+            };
+            // The open tab keeps reading the source, so never write over it.
+            if utils::is_same_file(&path, &output) {
+                crate::debug_log!(@warn, "[bookmark] refusing to overwrite {}", path.display());
+                let _ = cx.prompt(
+                    PromptLevel::Warning,
+                    i18n.save_copy_same_file,
+                    None,
+                    &[i18n.close_button],
+                );
+                return;
+            }
+            let output_for_task = output.clone();
+            let result = cx
+                .background_executor()
+                .spawn(async move {
+                    pdf_edit::save_copy_with_bookmark_outline(
+                        &path,
+                        &output_for_task,
+                        &entries,
+                        language,
+                    )
+                })
+                .await;
+            let _ = match result {
+                Ok(count) => cx.prompt(
+                    PromptLevel::Info,
+                    &i18n.bookmark_outline_saved(count, &output),
+                    None,
+                    &[i18n.close_button],
+                ),
+                Err(err) => {
+                    crate::debug_log!(@warn, "[bookmark] failed to save outline copy: {:#}", err);
+                    cx.prompt(
+                        PromptLevel::Warning,
+                        i18n.bookmark_outline_failed,
+                        Some(&format!("{err:#}")),
+                        &[i18n.close_button],
+                    )
+                }
+            };
+        })
+        .detach();
+    }
+
     pub(super) fn active_tab_page_bookmarked(&self, page_index: usize) -> bool {
         let Some(path) = self.active_tab_path() else {
             return false;
@@ -361,7 +451,15 @@ impl PdfViewer {
             return;
         }
 
-        self.save_file_position(&bookmark.path, bookmark.page_index);
+        self.save_file_position(
+            &bookmark.path,
+            SavedFileView {
+                page_index: bookmark.page_index,
+                zoom: None,
+                page_fraction: 0.0,
+                right_to_left: None,
+            },
+        );
 
         let existing_tab_id = self
             .tab_bar
@@ -528,6 +626,8 @@ impl PdfViewer {
         self.context_menu_note_anchor = note_anchor;
         self.context_menu_note_id = note_id;
         self.context_menu_text_markup_id = None;
+        self.context_menu_stamp_id = None;
+        self.context_menu_shape_id = None;
         cx.notify();
     }
 
@@ -544,6 +644,8 @@ impl PdfViewer {
         self.context_menu_note_anchor = None;
         self.context_menu_note_id = None;
         self.context_menu_text_markup_id = Some(markup_id);
+        self.context_menu_stamp_id = None;
+        self.context_menu_shape_id = None;
         self.context_menu_position = Some(window.mouse_position());
         cx.notify();
     }
@@ -712,6 +814,7 @@ impl PdfViewer {
         cx: &mut Context<PopoverState>,
     ) -> AnyElement {
         let is_from_notes = bookmarks.iter().any(|b| b.created_at_unix_secs == 0);
+        let density = viewer.read(cx).ui_density;
 
         div()
             .id("bookmark-list-scroll-wrap")
@@ -754,7 +857,7 @@ impl PdfViewer {
                                     .w_full()
                                     .rounded_md()
                                     .px_2()
-                                    .py_1()
+                                    .py(density.spacing(0.25))
                                     .cursor_pointer()
                                     .hover(|this| this.bg(cx.theme().secondary.opacity(0.6)))
                                     .active(|this| this.bg(cx.theme().secondary.opacity(0.9)))
@@ -911,6 +1014,7 @@ impl PdfViewer {
         show_choose_file_button: bool,
         cx: &App,
     ) -> AnyElement {
+        let density = viewer.read(cx).ui_density;
         div()
             .w_full()
             .v_flex()
@@ -983,7 +1087,7 @@ impl PdfViewer {
                                                 .w_full()
                                                 .rounded_md()
                                                 .px_2()
-                                                .py_1()
+                                                .py(density.spacing(0.25))
                                                 .cursor_pointer()
                                                 .hover(|this| {
                                                     this.bg(cx.theme().secondary.opacity(0.6))
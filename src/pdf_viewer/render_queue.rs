@@ -0,0 +1,131 @@
+use std::collections::HashSet;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
+pub enum RenderPriority {
+    Active,
+    Visible,
+    Prefetch,
+}
+
+/// Pages waiting to be rendered for one list of a tab, ordered by priority.
+///
+/// The queue is rebuilt from the current viewport on every scroll, so pages that scrolled
+/// away simply drop out instead of being rendered late.
+#[derive(Debug, Clone, Default)]
+pub struct RenderQueue {
+    entries: Vec<(RenderPriority, usize)>,
+}
+
+impl RenderQueue {
+    pub fn reprioritize(
+        &mut self,
+        active_page: usize,
+        visible_range: Range<usize>,
+        prefetch_pages: usize,
+        page_count: usize,
+    ) {
+        self.entries.clear();
+        if page_count == 0 {
+            return;
+        }
+
+        let visible_range = visible_range.start.min(page_count)..visible_range.end.min(page_count);
+        if visible_range.contains(&active_page) {
+            self.entries.push((RenderPriority::Active, active_page));
+        }
+        self.entries.extend(
+            visible_range
+                .clone()
+                .filter(|ix| *ix != active_page)
+                .map(|ix| (RenderPriority::Visible, ix)),
+        );
+
+        // Prefetch alternates after/before the viewport, favouring the reading direction.
+        let after = visible_range.end
+            ..visible_range
+                .end
+                .saturating_add(prefetch_pages)
+                .min(page_count);
+        let before = visible_range.start.saturating_sub(prefetch_pages)..visible_range.start;
+        let mut after = after.into_iter();
+        let mut before = before.rev();
+        loop {
+            let next_after = after.next();
+            let next_before = before.next();
+            if next_after.is_none() && next_before.is_none() {
+                break;
+            }
+            self.entries.extend(
+                next_after
+                    .into_iter()
+                    .chain(next_before)
+                    .map(|ix| (RenderPriority::Prefetch, ix)),
+            );
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn contains(&self, page_index: usize) -> bool {
+        self.entries.iter().any(|(_, ix)| *ix == page_index)
+    }
+
+    /// Page indices in priority order.
+    pub fn pages(&self) -> impl Iterator<Item = usize> + '_ {
+        self.entries.iter().map(|(_, ix)| *ix)
+    }
+
+    /// Removes and returns up to `max` of the highest-priority pages accepted by `needs_render`.
+    /// Rejected pages are dropped, since they are either loaded already or in flight.
+    pub fn pop_batch(
+        &mut self,
+        max: usize,
+        mut needs_render: impl FnMut(usize) -> bool,
+    ) -> Vec<usize> {
+        let mut batch = Vec::new();
+        let mut seen = HashSet::new();
+        let mut taken = 0;
+        for (_, ix) in &self.entries {
+            if batch.len() >= max {
+                break;
+            }
+            taken += 1;
+            if seen.insert(*ix) && needs_render(*ix) {
+                batch.push(*ix);
+            }
+        }
+        self.entries.drain(..taken);
+        batch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn active_then_visible_then_prefetch() {
+        let mut queue = RenderQueue::default();
+        queue.reprioritize(5, 4..7, 2, 20);
+        assert_eq!(queue.pages().collect::<Vec<_>>(), vec![5, 4, 6, 7, 3, 8, 2]);
+    }
+
+    #[test]
+    fn reprioritize_drops_stale_pages() {
+        let mut queue = RenderQueue::default();
+        queue.reprioritize(0, 0..3, 1, 10);
+        queue.reprioritize(8, 8..10, 1, 10);
+        assert_eq!(queue.pages().collect::<Vec<_>>(), vec![8, 9, 7]);
+    }
+
+    #[test]
+    fn pop_batch_skips_rejected_pages() {
+        let mut queue = RenderQueue::default();
+        queue.reprioritize(1, 0..4, 0, 4);
+        assert_eq!(queue.pop_batch(2, |ix| ix != 1), vec![0, 2]);
+        assert_eq!(queue.pages().collect::<Vec<_>>(), vec![3]);
+    }
+}
@@ -0,0 +1,93 @@
+use super::RenderEngine;
+use super::utils::PdfiumRenderer;
+use crate::i18n::Language;
+use anyhow::Result;
+use kpdf_core::render::RenderOptions;
+use std::path::Path;
+
+/// A page rasterizer. Implementations return BGRA pixels at (at most) `target_width`.
+///
+/// Band tiles, text and outlines always go through Pdfium; only full-page bitmaps are
+/// routed through the selected engine. `options` are Pdfium flags that other engines may
+/// ignore.
+pub(super) trait Renderer: Send + Sync {
+    fn render_page(
+        &self,
+        path: &Path,
+        page_index: usize,
+        target_width: u32,
+        options: RenderOptions,
+        language: Language,
+    ) -> Result<(u32, u32, Vec<u8>)>;
+}
+
+/// Falls back to Pdfium when the selected engine was not compiled in.
+pub(super) fn renderer_for(engine: RenderEngine) -> &'static dyn Renderer {
+    match engine {
+        RenderEngine::Pdfium => &PdfiumRenderer,
+        #[cfg(feature = "mupdf")]
+        RenderEngine::MuPdf => &mupdf_backend::MupdfRenderer,
+        #[cfg(not(feature = "mupdf"))]
+        RenderEngine::MuPdf => &PdfiumRenderer,
+    }
+}
+
+#[cfg(feature = "mupdf")]
+mod mupdf_backend {
+    use super::super::formats;
+    use super::Renderer;
+    use crate::i18n::{I18n, Language};
+    use anyhow::{Result, anyhow};
+    use kpdf_core::render::RenderOptions;
+    use mupdf::{Colorspace, Document, Matrix};
+    use std::path::Path;
+
+    pub(super) struct MupdfRenderer;
+
+    impl Renderer for MupdfRenderer {
+        fn render_page(
+            &self,
+            path: &Path,
+            page_index: usize,
+            target_width: u32,
+            options: RenderOptions,
+            language: Language,
+        ) -> Result<(u32, u32, Vec<u8>)> {
+            let i18n = I18n::new(language);
+            let source = formats::pdf_source(path, language)?;
+            // MuPDF contexts are per thread, so each render opens its own document handle.
+            let document = Document::open(&source.to_string_lossy())
+                .map_err(|err| anyhow!("{}: {err}", i18n.pdfium_cannot_open_file(path)))?;
+            let page = document.load_page(i32::try_from(page_index)?)?;
+            let bounds = page.bounds()?;
+            let (width_pt, height_pt) = (bounds.x1 - bounds.x0, bounds.y1 - bounds.y0);
+            let render_width =
+                kpdf_core::render::clamp_render_width(width_pt, height_pt, target_width);
+            let scale = render_width as f32 / width_pt.max(1.0);
+            let pixmap = page.to_pixmap(
+                &Matrix::new_scale(scale, scale),
+                &Colorspace::device_rgb(),
+                false,
+                true,
+            )?;
+
+            let (width, height) = (pixmap.width(), pixmap.height());
+            let components = pixmap.n() as usize;
+            let stride = pixmap.stride() as usize;
+            let samples = pixmap.samples();
+            let mut bgra = Vec::with_capacity(width as usize * height as usize * 4);
+            for row in samples.chunks(stride).take(height as usize) {
+                for pixel in row[..width as usize * components].chunks_exact(components) {
+                    if options.grayscale {
+                        let luma = kpdf_core::render::luma(pixel[0], pixel[1], pixel[2]);
+                        bgra.extend_from_slice(&[luma, luma, luma, 255]);
+                    } else {
+                        bgra.extend_from_slice(&[pixel[2], pixel[1], pixel[0], 255]);
+                    }
+                }
+            }
+            options.post_process(&mut bgra);
+            Ok((width, height, bgra))
+        }
+    }
+}
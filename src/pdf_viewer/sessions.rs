@@ -0,0 +1,167 @@
+use super::{PdfViewer, SavedFileView, SavedSession, SessionTab};
+use gpui::*;
+
+impl PdfViewer {
+    pub(super) fn load_sessions_from_store(store: &sled::Tree) -> Vec<SavedSession> {
+        let mut sessions = store
+            .iter()
+            .filter_map(Result::ok)
+            .filter_map(|(_, value)| serde_json::from_slice::<SavedSession>(&value).ok())
+            .filter(|session| !session.name.is_empty())
+            .collect::<Vec<_>>();
+        sessions.sort_by(|a, b| b.saved_at_unix_secs.cmp(&a.saved_at_unix_secs));
+        sessions
+    }
+
+    fn persist_session(&self, session: &SavedSession) {
+        let Some(store) = self.sessions_store.as_ref() else {
+            return;
+        };
+        let Ok(value) = serde_json::to_vec(session) else {
+            return;
+        };
+        let _ = store.insert(session.name.as_bytes(), value);
+        let _ = store.flush();
+    }
+
+    pub(super) fn session_named(&self, name: &str) -> Option<&SavedSession> {
+        self.sessions.iter().find(|session| session.name == name)
+    }
+
+    /// "Session N" with the first N not already taken.
+    pub(super) fn default_session_name(&self) -> String {
+        let i18n = self.i18n();
+        (self.sessions.len() + 1..)
+            .map(|number| i18n.session_default_name(number))
+            .find(|name| self.session_named(name).is_none())
+            .unwrap_or_default()
+    }
+
+    /// Records the tabs that have a file, with each tab's page and zoom, under `name`,
+    /// replacing any session already saved with that name.
+    pub(super) fn save_session(&mut self, name: &str, cx: &mut Context<Self>) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+
+        let active_tab_id = self.tab_bar.active_tab_id();
+        let mut active_index = 0;
+        let mut tabs = Vec::new();
+        for tab in self.tab_bar.tabs() {
+            let Some(path) = tab.path.as_ref() else {
+                continue;
+            };
+            if active_tab_id == Some(tab.id) {
+                active_index = tabs.len();
+            }
+            let view = if tab.pages.is_empty() {
+                self.load_saved_file_view(path)
+            } else {
+                Some(self.tab_file_view(tab))
+            };
+            tabs.push(SessionTab {
+                path: path.clone(),
+                page_index: view.map(|view| view.page_index).unwrap_or(0),
+                zoom: view.and_then(|view| view.zoom),
+            });
+        }
+        if tabs.is_empty() {
+            return;
+        }
+
+        let session = SavedSession {
+            name: name.to_string(),
+            tabs,
+            active_index,
+            saved_at_unix_secs: Self::now_unix_secs(),
+        };
+        crate::debug_log!(
+            "[session] saved '{}' with {} tabs",
+            session.name,
+            session.tabs.len()
+        );
+        self.persist_session(&session);
+        self.sessions
+            .retain(|existing| existing.name != session.name);
+        self.sessions.insert(0, session);
+        cx.notify();
+    }
+
+    /// Replaces the open tabs with the session's files, reopening each at its saved page
+    /// and zoom. Files that no longer exist are skipped.
+    pub(super) fn restore_session(&mut self, name: &str, cx: &mut Context<Self>) {
+        let Some(session) = self.session_named(name).cloned() else {
+            return;
+        };
+        let tabs = session
+            .tabs
+            .iter()
+            .filter(|tab| tab.path.exists())
+            .collect::<Vec<_>>();
+        if tabs.is_empty() {
+            return;
+        }
+
+        let _ = self.set_markdown_note_hover_id(None);
+        self.clear_text_selection_hover_menu_state();
+        let previous_tab_ids = self
+            .tab_bar
+            .tabs()
+            .iter()
+            .map(|tab| tab.id)
+            .collect::<Vec<_>>();
+        for tab_id in &previous_tab_ids {
+            self.save_tab_position_if_needed(*tab_id);
+        }
+
+        let mut restored_tab_ids = Vec::with_capacity(tabs.len());
+        for tab in tabs {
+            self.save_file_position(
+                &tab.path,
+                SavedFileView {
+                    page_index: tab.page_index,
+                    zoom: tab.zoom,
+                    page_fraction: 0.0,
+                    right_to_left: None,
+                },
+            );
+            restored_tab_ids.push(
+                self.tab_bar
+                    .create_tab_with_path(tab.path.clone(), Vec::new()),
+            );
+        }
+        for tab_id in previous_tab_ids {
+            self.tab_bar.close_tab(tab_id);
+        }
+
+        let active_index = session.active_index.min(restored_tab_ids.len() - 1);
+        let active_tab_id = restored_tab_ids[active_index];
+        let _ = self.tab_bar.switch_to_tab(active_tab_id);
+        crate::debug_log!(
+            "[session] restored '{}' with {} tabs",
+            session.name,
+            restored_tab_ids.len()
+        );
+
+        self.persist_open_tabs();
+        self.scroll_tab_bar_to_active_tab();
+        if self.load_tab_if_needed(active_tab_id, cx) {
+            return;
+        }
+        cx.notify();
+    }
+
+    pub(super) fn delete_session(&mut self, name: &str, cx: &mut Context<Self>) {
+        let before = self.sessions.len();
+        self.sessions.retain(|session| session.name != name);
+        if self.sessions.len() == before {
+            return;
+        }
+        if let Some(store) = self.sessions_store.as_ref() {
+            let _ = store.remove(name.as_bytes());
+            let _ = store.flush();
+        }
+        cx.notify();
+    }
+}
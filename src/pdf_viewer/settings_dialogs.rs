@@ -25,13 +25,19 @@ impl PdfViewer {
         cx.notify();
 
         cx.spawn(async move |view, cx| {
-            let usage_bytes = cx
+            let (usage_bytes, render_cache_usage_bytes) = cx
                 .background_executor()
-                .spawn(async move { Self::directory_usage_bytes(&db_path) })
+                .spawn(async move {
+                    (
+                        Self::directory_usage_bytes(&db_path),
+                        render_cache::usage_bytes(),
+                    )
+                })
                 .await;
 
             let _ = view.update(cx, |this, cx| {
                 this.db_usage_bytes = usage_bytes;
+                this.render_cache_usage_bytes = render_cache_usage_bytes;
                 this.db_usage_refreshing = false;
                 cx.notify();
             });
@@ -65,25 +71,74 @@ impl PdfViewer {
             UpdaterUiState::UpToDate { latest_version } => {
                 i18n.update_status_up_to_date(latest_version)
             }
-            UpdaterUiState::Available { latest_version, .. } => {
-                i18n.update_status_available(latest_version)
-            }
+            UpdaterUiState::Available {
+                latest_version,
+                download,
+                ..
+            } => match download {
+                UpdateDownload::NotStarted | UpdateDownload::InProgress(_) => {
+                    i18n.update_status_available(latest_version)
+                }
+                UpdateDownload::Failed(message) => i18n.diagnostics_download_failed(message),
+                UpdateDownload::Done(path) => i18n.update_status_downloaded(latest_version, path),
+            },
             UpdaterUiState::Error { message } => i18n.update_status_failed(message),
         }
     }
 
     fn check_for_updates(&mut self, cx: &mut Context<Self>) {
+        self.run_update_check(false, cx);
+    }
+
+    /// Checks once per launch when the configured interval has passed since the last check.
+    fn schedule_startup_update_check(&mut self, cx: &mut Context<Self>) {
+        let Some(interval_secs) = self.update_check_frequency.interval_secs() else {
+            return;
+        };
+        let last_checked = self
+            .view_preferences_store
+            .as_ref()
+            .and_then(|store| {
+                store
+                    .get(VIEW_PREFERENCES_KEY_LAST_UPDATE_CHECK)
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|raw| <[u8; 8]>::try_from(raw.as_ref()).ok())
+            .map(u64::from_be_bytes)
+            .unwrap_or(0);
+        if Self::now_unix_secs().saturating_sub(last_checked) < interval_secs {
+            return;
+        }
+
+        cx.spawn(async move |view, cx| {
+            cx.background_executor()
+                .timer(Duration::from_secs(UPDATE_STARTUP_CHECK_DELAY_SECS))
+                .await;
+            let _ = view.update(cx, |this, cx| {
+                if matches!(this.updater_state, UpdaterUiState::Idle) {
+                    this.run_update_check(true, cx);
+                }
+            });
+        })
+        .detach();
+    }
+
+    /// A `background` check records when it ran and stays quiet about failures.
+    fn run_update_check(&mut self, background: bool, cx: &mut Context<Self>) {
         if matches!(self.updater_state, UpdaterUiState::Checking) {
             return;
         }
 
         self.updater_state = UpdaterUiState::Checking;
+        let channel = self.update_channel;
+        let options = self.updater_options();
         cx.notify();
 
         cx.spawn(async move |view, cx| {
             let update_result = cx
                 .background_executor()
-                .spawn(async move { updater::check_for_updates(env!("CARGO_PKG_VERSION")) })
+                .spawn(async move { updater::check_for_updates(env!("CARGO_PKG_VERSION"), channel, &options) })
                 .await;
 
             let _ = view.update(cx, |this, cx| {
@@ -95,21 +150,249 @@ impl PdfViewer {
                         this.updater_state = UpdaterUiState::Available {
                             latest_version: info.latest_version,
                             download_url: info.download_url,
+                            asset: info.asset,
+                            download: UpdateDownload::NotStarted,
                         };
                     }
+                    Err(err) if background => {
+                        crate::debug_log!(@warn, "[updater] background check failed: {}", err);
+                        this.updater_state = UpdaterUiState::Idle;
+                    }
                     Err(err) => {
-                        crate::debug_log!("[updater] check failed: {}", err);
+                        crate::debug_log!(@warn, "[updater] check failed: {}", err);
                         this.updater_state = UpdaterUiState::Error {
                             message: Self::summarize_updater_error(&err.to_string()),
                         };
                     }
                 }
+                if background && !matches!(this.updater_state, UpdaterUiState::Idle) {
+                    this.persist_last_update_check(Self::now_unix_secs());
+                }
+                this.refresh_app_menus(cx);
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Downloads the available update in-app when its checksum is published, otherwise
+    /// hands the download page to the browser.
+    fn download_update(&mut self, cx: &mut Context<Self>) {
+        let UpdaterUiState::Available {
+            download_url,
+            asset,
+            download,
+            ..
+        } = &mut self.updater_state
+        else {
+            return;
+        };
+        if matches!(download, UpdateDownload::InProgress(_)) {
+            return;
+        }
+        let Some(asset) = asset.clone() else {
+            cx.open_url(download_url);
+            return;
+        };
+
+        *download = UpdateDownload::InProgress(DownloadProgress::default());
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.update_download_cancelled = cancelled.clone();
+        cx.notify();
+
+        let progress = Arc::new(Mutex::new(DownloadProgress::default()));
+        let progress_for_task = progress.clone();
+        let finished = Arc::new(AtomicBool::new(false));
+        let finished_for_task = finished.clone();
+        let options = self.updater_options();
+        let target_dir = updater::download_directory();
+        let download = cx.background_executor().spawn(async move {
+            let result = updater::download_update(
+                &asset,
+                &options,
+                &target_dir,
+                |update| {
+                    if let Ok(mut progress) = progress_for_task.lock() {
+                        *progress = update;
+                    }
+                },
+                || cancelled.load(Ordering::Relaxed),
+            );
+            finished_for_task.store(true, Ordering::Release);
+            result
+        });
+
+        // Progress is polled rather than pushed per chunk to keep redraws cheap.
+        cx.spawn(async move |view, cx| {
+            while !finished.load(Ordering::Acquire) {
+                cx.background_executor()
+                    .timer(Duration::from_millis(UPDATE_DOWNLOAD_PROGRESS_POLL_MS))
+                    .await;
+                let snapshot = progress.lock().map(|progress| *progress).ok();
+                let alive = view
+                    .update(cx, |this, cx| {
+                        if let (
+                            UpdaterUiState::Available {
+                                download: UpdateDownload::InProgress(current),
+                                ..
+                            },
+                            Some(snapshot),
+                        ) = (&mut this.updater_state, snapshot)
+                        {
+                            *current = snapshot;
+                            cx.notify();
+                        }
+                    })
+                    .is_ok();
+                if !alive {
+                    return;
+                }
+            }
+        })
+        .detach();
+
+        cx.spawn(async move |view, cx| {
+            let result = download.await;
+            let _ = view.update(cx, |this, cx| {
+                let UpdaterUiState::Available { download, .. } = &mut this.updater_state else {
+                    return;
+                };
+                *download = match result {
+                    Ok(path) => {
+                        cx.reveal_path(&path);
+                        UpdateDownload::Done(path)
+                    }
+                    Err(err) => {
+                        crate::debug_log!(@warn, "[updater] download failed: {:#}", err);
+                        UpdateDownload::Failed(Self::summarize_updater_error(&format!("{err:#}")))
+                    }
+                };
                 cx.notify();
             });
         })
         .detach();
     }
 
+    fn set_startup_behavior(&mut self, behavior: StartupBehavior, cx: &mut Context<Self>) {
+        if self.startup_behavior != behavior {
+            self.startup_behavior = behavior;
+            self.persist_startup_behavior();
+            cx.notify();
+        }
+    }
+
+    fn set_update_check_frequency(
+        &mut self,
+        frequency: UpdateCheckFrequency,
+        cx: &mut Context<Self>,
+    ) {
+        if self.update_check_frequency != frequency {
+            self.update_check_frequency = frequency;
+            self.persist_update_check_frequency();
+            cx.notify();
+        }
+    }
+
+    /// The version of an update found by the last check, if any.
+    fn available_update_version(&self) -> Option<&str> {
+        match &self.updater_state {
+            UpdaterUiState::Available { latest_version, .. } => Some(latest_version),
+            _ => None,
+        }
+    }
+
+    fn refresh_app_menus(&self, cx: &mut App) {
+        crate::configure_app_menus(
+            cx,
+            self.i18n(),
+            self.available_update_version(),
+            &self.recent_files,
+        );
+    }
+
+    fn updater_options(&self) -> updater::UpdaterOptions {
+        updater::UpdaterOptions {
+            endpoint: self.stored_preference_string(VIEW_PREFERENCES_KEY_UPDATE_ENDPOINT),
+            proxy: self.stored_preference_string(VIEW_PREFERENCES_KEY_DOWNLOAD_PROXY),
+        }
+    }
+
+    /// The proxy applies to every download; the endpoint and mirror each replace GitHub for
+    /// the updater and pdfium respectively.
+    fn set_network_options(
+        &mut self,
+        proxy: Option<String>,
+        update_endpoint: Option<String>,
+        pdfium_mirror: Option<String>,
+    ) {
+        self.persist_preference_strings(&[
+            (VIEW_PREFERENCES_KEY_DOWNLOAD_PROXY, &proxy),
+            (VIEW_PREFERENCES_KEY_UPDATE_ENDPOINT, &update_endpoint),
+            (VIEW_PREFERENCES_KEY_PDFIUM_MIRROR, &pdfium_mirror),
+        ]);
+    }
+
+    fn set_annotation_defaults(&mut self, defaults: AnnotationDefaults, cx: &mut Context<Self>) {
+        if self.annotation_defaults == defaults {
+            return;
+        }
+        if self.annotation_defaults.highlight_color != defaults.highlight_color {
+            self.text_selection_markup_color = defaults.highlight_color;
+        }
+        self.annotation_defaults = defaults;
+        self.persist_annotation_defaults();
+        cx.notify();
+    }
+
+    fn set_update_channel(&mut self, channel: updater::UpdateChannel, cx: &mut Context<Self>) {
+        if self.update_channel == channel {
+            return;
+        }
+        self.update_channel = channel;
+        self.persist_update_channel();
+        // A result from the other channel no longer applies; an in-flight download keeps going.
+        if !matches!(
+            self.updater_state,
+            UpdaterUiState::Checking
+                | UpdaterUiState::Available {
+                    download: UpdateDownload::InProgress(_),
+                    ..
+                }
+        ) {
+            self.updater_state = UpdaterUiState::Idle;
+        }
+        cx.notify();
+    }
+
+    fn cancel_update_download(&self) {
+        self.update_download_cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn install_update_and_relaunch(&mut self, cx: &mut Context<Self>) {
+        let UpdaterUiState::Available {
+            download: UpdateDownload::Done(path),
+            ..
+        } = &self.updater_state
+        else {
+            return;
+        };
+        let path = path.clone();
+
+        // Open tabs are persisted as they change; write them once more so the relaunched
+        // instance restores exactly this session.
+        self.persist_open_tabs();
+        if let Err(err) = updater::install_and_relaunch(&path) {
+            crate::debug_log!(@warn, "[updater] install failed: {:#}", err);
+            if let UpdaterUiState::Available { download, .. } = &mut self.updater_state {
+                *download =
+                    UpdateDownload::Failed(Self::summarize_updater_error(&format!("{err:#}")));
+            }
+            cx.notify();
+            return;
+        }
+        cx.quit();
+    }
+
     fn open_settings_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if self.command_panel_open {
             self.close_command_panel(cx);
@@ -187,7 +470,7 @@ impl PdfViewer {
                 cx.notify();
             }
             Err(err) => {
-                crate::debug_log!("[settings] failed to open settings window: {}", err);
+                crate::debug_log!(@warn, "[settings] failed to open settings window: {}", err);
                 self.on_settings_dialog_window_closed(session_id, cx);
             }
         }
@@ -232,11 +515,23 @@ impl PdfViewer {
         }
     }
 
-    fn set_titlebar_navigation_visible(&mut self, visible: bool, cx: &mut Context<Self>) {
-        if self.titlebar_preferences.show_navigation == visible {
+    fn set_toolbar_item_visible(
+        &mut self,
+        item: ToolbarItem,
+        visible: bool,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.titlebar_preferences.set_visible(item, visible) {
+            return;
+        }
+        self.persist_titlebar_preferences();
+        cx.notify();
+    }
+
+    fn move_toolbar_entry(&mut self, index: usize, offset: isize, cx: &mut Context<Self>) {
+        if !self.titlebar_preferences.move_entry(index, offset) {
             return;
         }
-        self.titlebar_preferences.show_navigation = visible;
         self.persist_titlebar_preferences();
         cx.notify();
     }
@@ -259,7 +554,13 @@ impl PdfViewer {
         self.command_panel_input_state.update(cx, |input, cx| {
             input.set_placeholder(i18n.command_panel_search_hint, window, cx)
         });
-        crate::configure_app_menus(cx, i18n);
+        self.sidebar_search_input_state.update(cx, |input, cx| {
+            input.set_placeholder(i18n.sidebar_search_placeholder, window, cx)
+        });
+        self.sidebar_outline_filter_input_state.update(cx, |input, cx| {
+            input.set_placeholder(i18n.sidebar_outline_filter_placeholder, window, cx)
+        });
+        self.refresh_app_menus(cx);
         cx.notify();
     }
 
@@ -300,6 +601,16 @@ impl PdfViewer {
                 .unwrap_or_else(|| registry.default_dark_theme().clone());
             (selected_light_theme, selected_dark_theme)
         };
+        let active_theme_name = if self.theme_mode == ThemeMode::Dark {
+            selected_dark_theme.name.clone()
+        } else {
+            selected_light_theme.name.clone()
+        };
+        let high_contrast = HIGH_CONTRAST_THEME_NAMES.contains(&active_theme_name.as_ref());
+        if self.render_options.high_contrast != high_contrast {
+            self.render_options.high_contrast = high_contrast;
+            self.discard_rendered_pages();
+        }
 
         {
             let theme = Theme::global_mut(cx);
@@ -313,6 +624,58 @@ impl PdfViewer {
             Theme::change(self.theme_mode, None, cx);
             cx.refresh_windows();
         }
+
+        // Theme::change reloads the theme's own colors, so the override goes on top.
+        if let Some(accent_color) = self.accent_color {
+            let colors = &mut Theme::global_mut(cx).colors;
+            colors.primary = accent_color;
+            colors.primary_hover = accent_color.opacity(0.9);
+            colors.primary_active = accent_color.darken(0.1);
+            colors.primary_foreground = if accent_color.l > 0.6 {
+                black()
+            } else {
+                white()
+            };
+            colors.ring = accent_color;
+            cx.refresh_windows();
+        }
+        // The root view paints the theme background under the viewer, so it has to be
+        // see-through as well for a translucent window.
+        if self.window_opacity_percent < 100 {
+            let colors = &mut Theme::global_mut(cx).colors;
+            colors.background = colors
+                .background
+                .opacity(f32::from(self.window_opacity_percent) / 100.0);
+            cx.refresh_windows();
+        }
+    }
+
+    /// Copies a theme file into the watched themes directory; the registry picks it up from
+    /// there and the theme select refreshes through the registry subscription.
+    fn import_theme_file(path: &Path) -> anyhow::Result<PathBuf> {
+        let raw = std::fs::read_to_string(path)?;
+        let value = serde_json::from_str::<serde_json::Value>(&raw)?;
+        if !value.get("themes").is_some_and(|themes| themes.is_array()) {
+            anyhow::bail!("{} has no \"themes\" list", path.display());
+        }
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("{} has no file name", path.display()))?;
+        let target_dir = crate::user_themes_dir();
+        std::fs::create_dir_all(&target_dir)?;
+        let target = target_dir.join(file_name);
+        std::fs::write(&target, raw)?;
+        Ok(target)
+    }
+
+    fn set_accent_color(&mut self, color: Option<Hsla>, cx: &mut Context<Self>) {
+        if self.accent_color == color {
+            return;
+        }
+        self.accent_color = color;
+        self.persist_theme_preferences();
+        self.apply_theme_preferences(None, cx);
+        cx.notify();
     }
 
     fn sync_theme_color_select(&mut self, window: &mut Window, cx: &mut Context<Self>) {
@@ -366,15 +729,6 @@ impl PdfViewer {
         cx.notify();
     }
 
-    fn set_titlebar_zoom_visible(&mut self, visible: bool, cx: &mut Context<Self>) {
-        if self.titlebar_preferences.show_zoom == visible {
-            return;
-        }
-        self.titlebar_preferences.show_zoom = visible;
-        self.persist_titlebar_preferences();
-        cx.notify();
-    }
-
     fn set_tab_layout_mode(&mut self, mode: TabLayoutMode, cx: &mut Context<Self>) {
         if self.tab_layout_mode == mode {
             return;
@@ -397,6 +751,147 @@ impl PdfViewer {
         self.set_vertical_tab_bar_visible(!self.vertical_tab_bar_visible, cx);
     }
 
+    fn set_render_worker_count(&mut self, count: usize, cx: &mut Context<Self>) {
+        let count = count.clamp(1, RENDER_WORKERS_MAX);
+        if self.render_worker_count == count {
+            return;
+        }
+        self.render_worker_count = count;
+        self.persist_render_worker_count();
+        cx.notify();
+    }
+
+    fn set_render_engine(&mut self, engine: RenderEngine, cx: &mut Context<Self>) {
+        if self.render_engine == engine {
+            return;
+        }
+        self.render_engine = engine;
+        self.persist_render_engine();
+        // Drop bitmaps from the previous engine so every page re-renders with the new one.
+        self.discard_rendered_pages();
+        cx.notify();
+    }
+
+    fn set_render_quality_percent(&mut self, percent: u8, cx: &mut Context<Self>) {
+        if self.render_quality_percent == percent {
+            return;
+        }
+        self.render_quality_percent = percent;
+        self.persist_render_quality();
+        // Lowering the quality should also free the larger bitmaps, not just stop growing them.
+        self.discard_rendered_pages();
+        cx.notify();
+    }
+
+    fn set_window_opacity_percent(&mut self, percent: u8, cx: &mut Context<Self>) {
+        if self.window_opacity_percent == percent {
+            return;
+        }
+        self.window_opacity_percent = percent;
+        self.persist_window_opacity();
+        self.apply_theme_preferences(None, cx);
+        cx.notify();
+    }
+
+    fn set_ui_font_size(&mut self, font_size: u8, cx: &mut Context<Self>) {
+        if self.ui_font_size == font_size {
+            return;
+        }
+        self.ui_font_size = font_size;
+        self.persist_ui_preferences();
+        cx.notify();
+    }
+
+    fn set_ui_density(&mut self, density: UiDensity, cx: &mut Context<Self>) {
+        if self.ui_density == density {
+            return;
+        }
+        self.ui_density = density;
+        self.persist_ui_preferences();
+        cx.notify();
+    }
+
+    fn set_render_options(&mut self, options: RenderOptions, cx: &mut Context<Self>) {
+        if self.render_options == options {
+            return;
+        }
+        self.render_options = options;
+        self.persist_render_options();
+        self.discard_rendered_pages();
+        cx.notify();
+    }
+
+    pub(super) fn toggle_grayscale(&mut self, cx: &mut Context<Self>) {
+        self.set_render_options(
+            RenderOptions {
+                grayscale: !self.render_options.grayscale,
+                ..self.render_options
+            },
+            cx,
+        );
+    }
+
+    fn discard_rendered_pages(&mut self) {
+        for tab in self.tab_bar.tabs_mut() {
+            for page in &mut tab.pages {
+                page.thumbnail_image = None;
+                page.thumbnail_render_width = 0;
+                page.thumbnail_failed = false;
+                page.display_image = None;
+                page.display_render_width = 0;
+                page.display_failed = false;
+            }
+            tab.reset_thumbnail_render_state();
+            tab.reset_display_render_state();
+        }
+    }
+
+    /// Pdfium cannot be unloaded, so a new library is only bound on the next launch unless
+    /// none has loaded yet.
+    pub(super) fn set_pdfium_library_path(
+        &mut self,
+        path: Option<PathBuf>,
+        cx: &mut Context<Self>,
+    ) {
+        if self.pdfium_library_path == path {
+            return;
+        }
+        self.pdfium_library_path = path;
+        self.persist_pdfium_library_path();
+        set_preferred_pdfium_library(self.pdfium_library_path.clone());
+        cx.notify();
+    }
+
+    fn set_render_cache_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        if self.render_cache_enabled == enabled {
+            return;
+        }
+        self.render_cache_enabled = enabled;
+        self.persist_render_cache_preferences();
+        cx.notify();
+    }
+
+    fn set_render_cache_limit_mb(&mut self, limit_mb: u64, cx: &mut Context<Self>) {
+        if self.render_cache_limit_mb == limit_mb {
+            return;
+        }
+        self.render_cache_limit_mb = limit_mb;
+        self.persist_render_cache_preferences();
+        // A larger cache may now fit the pages idle pre-rendering gave up on.
+        self.idle_prerender_done = None;
+        self.note_user_activity(cx);
+        cx.notify();
+    }
+
+    fn clear_render_cache(&mut self, cx: &mut Context<Self>) {
+        cx.background_executor()
+            .spawn(async move { render_cache::clear() })
+            .detach();
+        self.render_cache_usage_bytes = 0;
+        self.idle_prerender_done = None;
+        cx.notify();
+    }
+
     fn set_vertical_tab_bar_hovered(&mut self, hovered: bool, cx: &mut Context<Self>) {
         if self.vertical_tab_bar_hovered == hovered {
             return;
@@ -411,8 +906,32 @@ struct SettingsDialogSnapshot {
     language: Language,
     language_preference: LanguagePreference,
     theme_mode: ThemeMode,
-    titlebar_preferences: TitleBarVisibilityPreferences,
+    titlebar_preferences: TitleBarPreferences,
     tab_layout_mode: TabLayoutMode,
+    thumbnail_panel_visible: bool,
+    comic_right_to_left: bool,
+    page_double_click_action: PageDoubleClickAction,
+    shell_open_with_registered: bool,
+    render_worker_count: usize,
+    render_engine: RenderEngine,
+    render_quality_percent: u8,
+    window_opacity_percent: u8,
+    presentation_timer_visible: bool,
+    presentation_auto_advance_secs: u8,
+    presentation_pointer: PresentationPointer,
+    update_check_frequency: UpdateCheckFrequency,
+    startup_behavior: StartupBehavior,
+    render_options: RenderOptions,
+    accent_color: Option<Hsla>,
+    ui_font_size: u8,
+    ui_density: UiDensity,
+    pdfium_library_path: Option<PathBuf>,
+    render_cache_enabled: bool,
+    render_cache_limit_mb: u64,
+    idle_prerender_enabled: bool,
+    render_cache_usage_bytes: u64,
+    external_tools: Vec<ExternalTool>,
+    annotation_defaults: AnnotationDefaults,
     db_usage_refreshing: bool,
     db_usage_bytes: u64,
     db_path_text: String,
@@ -426,6 +945,30 @@ impl SettingsDialogSnapshot {
             theme_mode: viewer.theme_mode,
             titlebar_preferences: viewer.titlebar_preferences,
             tab_layout_mode: viewer.tab_layout_mode,
+            thumbnail_panel_visible: viewer.thumbnail_panel_visible,
+            comic_right_to_left: viewer.comic_right_to_left,
+            page_double_click_action: viewer.page_double_click_action,
+            shell_open_with_registered: viewer.shell_open_with_registered,
+            render_worker_count: viewer.render_worker_count,
+            render_engine: viewer.render_engine,
+            render_quality_percent: viewer.render_quality_percent,
+            window_opacity_percent: viewer.window_opacity_percent,
+            presentation_timer_visible: viewer.presentation_timer_visible,
+            presentation_auto_advance_secs: viewer.presentation_auto_advance_secs,
+            presentation_pointer: viewer.presentation_pointer,
+            update_check_frequency: viewer.update_check_frequency,
+            startup_behavior: viewer.startup_behavior,
+            render_options: viewer.render_options,
+            accent_color: viewer.accent_color,
+            ui_font_size: viewer.ui_font_size,
+            ui_density: viewer.ui_density,
+            pdfium_library_path: viewer.pdfium_library_path.clone(),
+            render_cache_enabled: viewer.render_cache_enabled,
+            render_cache_limit_mb: viewer.render_cache_limit_mb,
+            idle_prerender_enabled: viewer.idle_prerender_enabled,
+            render_cache_usage_bytes: viewer.render_cache_usage_bytes,
+            external_tools: viewer.external_tools.clone(),
+            annotation_defaults: viewer.annotation_defaults.clone(),
             db_usage_refreshing: viewer.db_usage_refreshing,
             db_usage_bytes: viewer.db_usage_bytes,
             db_path_text: viewer.db_path.to_string_lossy().to_string(),
@@ -437,7 +980,19 @@ struct SettingsDialogWindow {
     viewer: Entity<PdfViewer>,
     theme_color_select_state: Entity<SelectState<SearchableVec<SharedString>>>,
     snapshot: SettingsDialogSnapshot,
+    downloaded_pdfium_libraries: Vec<(String, PathBuf)>,
+    external_tool_name_input: Entity<InputState>,
+    external_tool_command_input: Entity<InputState>,
+    accent_color_picker: Entity<ColorPickerState>,
+    accent_color_input: Entity<InputState>,
+    proxy_input: Entity<InputState>,
+    update_endpoint_input: Entity<InputState>,
+    pdfium_mirror_input: Entity<InputState>,
+    annotation_author_input: Entity<InputState>,
+    log_modules_input: Entity<InputState>,
+    log_filter_error: Option<String>,
     _viewer_observation: Subscription,
+    _accent_color_subscription: Subscription,
     focus_handle: FocusHandle,
 }
 
@@ -446,9 +1001,74 @@ impl SettingsDialogWindow {
         viewer: Entity<PdfViewer>,
         theme_color_select_state: Entity<SelectState<SearchableVec<SharedString>>>,
         snapshot: SettingsDialogSnapshot,
-        _window: &mut Window,
+        window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
+        let i18n = I18n::new(snapshot.language);
+        let external_tool_name_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n.settings_external_tool_name_placeholder)
+        });
+        let external_tool_command_input = cx.new(|cx| {
+            InputState::new(window, cx).placeholder(i18n.settings_external_tool_command_placeholder)
+        });
+        let accent_color_picker = cx.new(|cx| {
+            let picker = ColorPickerState::new(window, cx);
+            match snapshot.accent_color {
+                Some(color) => picker.default_value(color),
+                None => picker,
+            }
+        });
+        let accent_color_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("#3B82F6")
+                .default_value(
+                    snapshot
+                        .accent_color
+                        .map(PdfViewer::format_hex_color)
+                        .unwrap_or_default(),
+                )
+        });
+        let (proxy, update_endpoint, pdfium_mirror) = {
+            let viewer = viewer.read(cx);
+            (
+                viewer.stored_preference_string(VIEW_PREFERENCES_KEY_DOWNLOAD_PROXY),
+                viewer.stored_preference_string(VIEW_PREFERENCES_KEY_UPDATE_ENDPOINT),
+                viewer.stored_preference_string(VIEW_PREFERENCES_KEY_PDFIUM_MIRROR),
+            )
+        };
+        let proxy_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(i18n.diagnostics_proxy_placeholder)
+                .default_value(proxy.unwrap_or_default())
+        });
+        let update_endpoint_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(i18n.settings_update_endpoint_placeholder)
+                .default_value(update_endpoint.unwrap_or_default())
+        });
+        let pdfium_mirror_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(i18n.diagnostics_mirror_placeholder)
+                .default_value(pdfium_mirror.unwrap_or_default())
+        });
+        let annotation_author_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(i18n.settings_annotation_author_placeholder)
+                .default_value(snapshot.annotation_defaults.author.clone())
+        });
+        let log_modules_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder("pdfium=trace,store=warn")
+                .default_value(crate::logger::log_filter().modules_spec())
+        });
+        let accent_color_subscription = cx.subscribe_in(
+            &accent_color_picker,
+            window,
+            |this, _, event: &ColorPickerEvent, window, cx| {
+                let ColorPickerEvent::Change(color) = event;
+                this.set_accent_color(*color, window, cx);
+            },
+        );
         let viewer_for_observe = viewer.clone();
         let viewer_observation = cx.observe(&viewer_for_observe, |this, viewer, cx| {
             this.snapshot = {
@@ -461,42 +1081,290 @@ impl SettingsDialogWindow {
             viewer,
             theme_color_select_state,
             snapshot,
+            downloaded_pdfium_libraries: downloaded_pdfium_libraries(),
+            external_tool_name_input,
+            external_tool_command_input,
+            accent_color_picker,
+            accent_color_input,
+            proxy_input,
+            update_endpoint_input,
+            pdfium_mirror_input,
+            annotation_author_input,
+            log_modules_input,
+            log_filter_error: None,
             _viewer_observation: viewer_observation,
+            _accent_color_subscription: accent_color_subscription,
             focus_handle: cx.focus_handle(),
         }
     }
 
-    fn close_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+    fn choose_pdfium_library(&mut self, cx: &mut Context<Self>) {
+        let i18n = I18n::new(self.snapshot.language);
+        let picker = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some(i18n.settings_pdfium_library_prompt.into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = picker.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                let _ = this.viewer.update(cx, |viewer, cx| {
+                    viewer.set_pdfium_library_path(Some(path), cx);
+                });
+            });
+        })
+        .detach();
+    }
+
+    fn add_external_tool(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let name = self.external_tool_name_input.read(cx).value().to_string();
+        let command = self
+            .external_tool_command_input
+            .read(cx)
+            .value()
+            .to_string();
+        if name.trim().is_empty() || command.trim().is_empty() {
+            return;
+        }
         let _ = self.viewer.update(cx, |viewer, cx| {
-            viewer.close_settings_dialog(cx);
+            viewer.add_external_tool(name, command, cx);
         });
-        window.remove_window();
+        for input in [
+            &self.external_tool_name_input,
+            &self.external_tool_command_input,
+        ] {
+            input.update(cx, |input, cx| input.set_value("", window, cx));
+        }
     }
-}
 
-impl Render for SettingsDialogWindow {
-    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn choose_theme_file(&mut self, cx: &mut Context<Self>) {
         let i18n = I18n::new(self.snapshot.language);
-        let theme_mode = self.snapshot.theme_mode;
-        let language_preference = self.snapshot.language_preference;
-        let titlebar_preferences = self.snapshot.titlebar_preferences;
-        let tab_layout_mode = self.snapshot.tab_layout_mode;
-        let theme_color_select_state = self.theme_color_select_state.clone();
-        let db_usage_refreshing = self.snapshot.db_usage_refreshing;
-        let db_usage_bytes = self.snapshot.db_usage_bytes;
-        let db_path_text = self.snapshot.db_path_text.clone();
-        let has_theme_color_options = ThemeRegistry::global(cx)
-            .sorted_themes()
-            .into_iter()
-            .any(|theme| theme.mode == theme_mode);
-        let db_usage_text = PdfViewer::format_storage_size(db_usage_bytes);
-        let refresh_db_label: SharedString = if db_usage_refreshing {
-            "…".into()
-        } else {
-            i18n.settings_db_refresh_button.into()
-        };
+        let picker = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: true,
+            prompt: Some(i18n.settings_theme_import_prompt.into()),
+        });
 
-        window.set_window_title(i18n.settings_dialog_title);
+        cx.spawn(async move |_, _| {
+            let Ok(Ok(Some(paths))) = picker.await else {
+                return;
+            };
+            for path in paths {
+                match PdfViewer::import_theme_file(&path) {
+                    Ok(target) => crate::debug_log!("[theme] imported {}", target.display()),
+                    Err(err) => {
+                        crate::debug_log!(@warn, "[theme] failed to import {}: {:#}", path.display(), err)
+                    }
+                }
+            }
+        })
+        .detach();
+    }
+
+    fn set_accent_color(
+        &mut self,
+        color: Option<Hsla>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.set_accent_color(color, cx);
+        });
+        let hex = color.map(PdfViewer::format_hex_color).unwrap_or_default();
+        self.accent_color_input
+            .update(cx, |input, cx| input.set_value(hex, window, cx));
+    }
+
+    fn apply_accent_color_input(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let text = self.accent_color_input.read(cx).value().to_string();
+        let Some(color) = PdfViewer::parse_hex_color(&text) else {
+            return;
+        };
+        self.accent_color_picker
+            .update(cx, |picker, cx| picker.set_value(color, window, cx));
+        self.set_accent_color(Some(color), window, cx);
+    }
+
+    fn save_network_options(&mut self, cx: &mut Context<Self>) {
+        let input_value = |input: &Entity<InputState>| {
+            Some(input.read(cx).value().trim().to_string()).filter(|value| !value.is_empty())
+        };
+        let proxy = input_value(&self.proxy_input);
+        let update_endpoint = input_value(&self.update_endpoint_input);
+        let pdfium_mirror = input_value(&self.pdfium_mirror_input);
+        let _ = self.viewer.update(cx, |viewer, _| {
+            viewer.set_network_options(proxy, update_endpoint, pdfium_mirror);
+        });
+    }
+
+    fn update_annotation_defaults(
+        &mut self,
+        update: impl FnOnce(&mut AnnotationDefaults),
+        cx: &mut Context<Self>,
+    ) {
+        let mut defaults = self.snapshot.annotation_defaults.clone();
+        update(&mut defaults);
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.set_annotation_defaults(defaults, cx);
+        });
+    }
+
+    fn save_annotation_author(&mut self, cx: &mut Context<Self>) {
+        let author = self
+            .annotation_author_input
+            .read(cx)
+            .value()
+            .trim()
+            .to_string();
+        self.update_annotation_defaults(|defaults| defaults.author = author, cx);
+    }
+
+    fn render_annotation_color_row(
+        &self,
+        id: &'static str,
+        label: &'static str,
+        swatches: Vec<(Rgba, bool)>,
+        select: fn(&mut AnnotationDefaults, usize),
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .w_full()
+            .flex()
+            .items_center()
+            .justify_between()
+            .gap_3()
+            .child(
+                div()
+                    .text_sm()
+                    .text_color(cx.theme().foreground)
+                    .child(label),
+            )
+            .child(
+                div()
+                    .h_flex()
+                    .gap_1()
+                    .children(
+                        swatches
+                            .into_iter()
+                            .enumerate()
+                            .map(|(ix, (color, selected))| {
+                                div()
+                                    .id((id, ix))
+                                    .size(px(16.))
+                                    .rounded_full()
+                                    .cursor_pointer()
+                                    .bg(color)
+                                    .border_2()
+                                    .border_color(if selected {
+                                        cx.theme().foreground
+                                    } else {
+                                        cx.theme().secondary
+                                    })
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.update_annotation_defaults(
+                                            |defaults| select(defaults, ix),
+                                            cx,
+                                        );
+                                    }))
+                            }),
+                    ),
+            )
+    }
+
+    fn set_log_level(&mut self, level: crate::logger::LogLevel, cx: &mut Context<Self>) {
+        let mut filter = crate::logger::log_filter();
+        filter.level = level;
+        crate::logger::set_log_filter(filter);
+        cx.notify();
+    }
+
+    fn apply_log_modules_input(&mut self, cx: &mut Context<Self>) {
+        let level = crate::logger::log_filter().level;
+        let modules = self.log_modules_input.read(cx).value().to_string();
+        match crate::logger::LogFilter::parse(&format!("{},{modules}", level.as_str())) {
+            Ok(filter) => {
+                crate::logger::set_log_filter(filter);
+                self.log_filter_error = None;
+            }
+            Err(directive) => {
+                let i18n = I18n::new(self.snapshot.language);
+                self.log_filter_error = Some(i18n.settings_log_filter_invalid(&directive));
+            }
+        }
+        cx.notify();
+    }
+
+    fn close_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.close_settings_dialog(cx);
+        });
+        window.remove_window();
+    }
+}
+
+impl Render for SettingsDialogWindow {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let i18n = I18n::new(self.snapshot.language);
+        let theme_mode = self.snapshot.theme_mode;
+        let language_preference = self.snapshot.language_preference;
+        let titlebar_preferences = self.snapshot.titlebar_preferences;
+        let tab_layout_mode = self.snapshot.tab_layout_mode;
+        let thumbnail_panel_visible = self.snapshot.thumbnail_panel_visible;
+        let comic_right_to_left = self.snapshot.comic_right_to_left;
+        let page_double_click_action = self.snapshot.page_double_click_action;
+        let shell_open_with_registered = self.snapshot.shell_open_with_registered;
+        let render_worker_count = self.snapshot.render_worker_count;
+        let render_engine = self.snapshot.render_engine;
+        let render_quality_percent = self.snapshot.render_quality_percent;
+        let window_opacity_percent = self.snapshot.window_opacity_percent;
+        let presentation_timer_visible = self.snapshot.presentation_timer_visible;
+        let presentation_auto_advance_secs = self.snapshot.presentation_auto_advance_secs;
+        let presentation_pointer = self.snapshot.presentation_pointer;
+        let update_check_frequency = self.snapshot.update_check_frequency;
+        let startup_behavior = self.snapshot.startup_behavior;
+        let log_level = crate::logger::log_filter().level;
+        let log_filter_error = self.log_filter_error.clone();
+        let render_options = self.snapshot.render_options;
+        let ui_font_size = self.snapshot.ui_font_size;
+        let accent_color = self.snapshot.accent_color;
+        let ui_density = self.snapshot.ui_density;
+        let pdfium_library_path = self.snapshot.pdfium_library_path.clone();
+        let pdfium_library_text = pdfium_library_path
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_else(|| i18n.settings_pdfium_library_automatic_hint.to_string());
+        let downloaded_pdfium_libraries = self.downloaded_pdfium_libraries.clone();
+        let render_cache_enabled = self.snapshot.render_cache_enabled;
+        let render_cache_limit_mb = self.snapshot.render_cache_limit_mb;
+        let idle_prerender_enabled = self.snapshot.idle_prerender_enabled;
+        let render_cache_usage_text =
+            PdfViewer::format_storage_size(self.snapshot.render_cache_usage_bytes);
+        let external_tools = self.snapshot.external_tools.clone();
+        let annotation_defaults = self.snapshot.annotation_defaults.clone();
+        let theme_color_select_state = self.theme_color_select_state.clone();
+        let db_usage_refreshing = self.snapshot.db_usage_refreshing;
+        let db_usage_bytes = self.snapshot.db_usage_bytes;
+        let db_path_text = self.snapshot.db_path_text.clone();
+        let has_theme_color_options = ThemeRegistry::global(cx)
+            .sorted_themes()
+            .into_iter()
+            .any(|theme| theme.mode == theme_mode);
+        let db_usage_text = PdfViewer::format_storage_size(db_usage_bytes);
+        let refresh_db_label: SharedString = if db_usage_refreshing {
+            "…".into()
+        } else {
+            i18n.settings_db_refresh_button.into()
+        };
+
+        window.set_window_title(i18n.settings_dialog_title);
 
         div()
             .id("settings-dialog-window")
@@ -553,13 +1421,12 @@ impl Render for SettingsDialogWindow {
                                     .child(
                                         div()
                                             .w_full()
-                                            .flex()
+                                            .v_flex()
                                             .items_start()
-                                            .justify_between()
                                             .gap_3()
                                             .child(
                                                 div()
-                                                    .flex_1()
+                                                    .w_full()
                                                     .v_flex()
                                                     .items_start()
                                                     .gap_1()
@@ -578,41 +1445,55 @@ impl Render for SettingsDialogWindow {
                                                     ),
                                             )
                                             .child(
-                                                ButtonGroup::new("settings-language-preference-window")
-                                                    .small()
-                                                    .outline()
-                                                    .child(
-                                                        Button::new("settings-language-system-window")
-                                                            .label(i18n.settings_language_system)
-                                                            .selected(
-                                                                language_preference
-                                                                    == LanguagePreference::System,
-                                                            ),
-                                                    )
-                                                    .child(
-                                                        Button::new("settings-language-zh-cn-window")
-                                                            .label(i18n.settings_language_zh_cn)
-                                                            .selected(
-                                                                language_preference
-                                                                    == LanguagePreference::ZhCn,
-                                                            ),
-                                                    )
-                                                    .child(
-                                                        Button::new("settings-language-en-us-window")
-                                                            .label(i18n.settings_language_en_us)
-                                                            .selected(
-                                                                language_preference
-                                                                    == LanguagePreference::EnUs,
-                                                            ),
+                                                LanguagePreference::ALL
+                                                    .into_iter()
+                                                    .enumerate()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-language-preference-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, (ix, preference)| {
+                                                            group.child(
+                                                                Button::new(("settings-language", ix))
+                                                                    .label(match preference {
+                                                                        LanguagePreference::System => {
+                                                                            i18n.settings_language_system
+                                                                        }
+                                                                        LanguagePreference::ZhCn => {
+                                                                            i18n.settings_language_zh_cn
+                                                                        }
+                                                                        LanguagePreference::EnUs => {
+                                                                            i18n.settings_language_en_us
+                                                                        }
+                                                                        LanguagePreference::JaJp => {
+                                                                            i18n.settings_language_ja_jp
+                                                                        }
+                                                                        LanguagePreference::KoKr => {
+                                                                            i18n.settings_language_ko_kr
+                                                                        }
+                                                                        LanguagePreference::DeDe => {
+                                                                            i18n.settings_language_de_de
+                                                                        }
+                                                                        LanguagePreference::EsEs => {
+                                                                            i18n.settings_language_es_es
+                                                                        }
+                                                                        LanguagePreference::ArSa => {
+                                                                            i18n.settings_language_ar_sa
+                                                                        }
+                                                                    })
+                                                                    .selected(language_preference == preference),
+                                                            )
+                                                        },
                                                     )
                                                     .on_click(cx.listener(
                                                         |this, selected: &Vec<usize>, window, cx| {
-                                                            let preference =
-                                                                match selected.first().copied() {
-                                                                    Some(1) => LanguagePreference::ZhCn,
-                                                                    Some(2) => LanguagePreference::EnUs,
-                                                                    _ => LanguagePreference::System,
-                                                                };
+                                                            let Some(preference) = selected
+                                                                .first()
+                                                                .and_then(|ix| LanguagePreference::ALL.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
                                                             let _ =
                                                                 this.viewer.update(cx, |viewer, cx| {
                                                                     viewer.set_language_preference(
@@ -738,38 +1619,32 @@ impl Render for SettingsDialogWindow {
                                                     ),
                                             )
                                             .child(
-                                                div().w(px(200.)).child(
-                                                    Select::new(&theme_color_select_state)
-                                                        .small()
-                                                        .disabled(!has_theme_color_options)
-                                                        .placeholder(
-                                                            i18n.settings_theme_color_placeholder,
+                                                div()
+                                                    .flex()
+                                                    .items_center()
+                                                    .gap_2()
+                                                    .child(
+                                                        div().w(px(200.)).child(
+                                                            Select::new(&theme_color_select_state)
+                                                                .small()
+                                                                .disabled(!has_theme_color_options)
+                                                                .placeholder(
+                                                                    i18n.settings_theme_color_placeholder,
+                                                                ),
                                                         ),
-                                                ),
+                                                    )
+                                                    .child(
+                                                        Button::new("settings-theme-import")
+                                                            .small()
+                                                            .outline()
+                                                            .label(i18n.settings_theme_import_button)
+                                                            .on_click(cx.listener(|this, _, _, cx| {
+                                                                this.choose_theme_file(cx);
+                                                            })),
+                                                    ),
                                             ),
-                                    ),
-                            ),
-                    )
-                    .child(
-                        div()
-                            .v_flex()
-                            .gap_2()
-                            .child(
-                                div()
-                                    .mt_2()
-                                    .text_sm()
-                                    .text_color(cx.theme().muted_foreground)
-                                    .child(i18n.settings_titlebar_section),
-                            )
-                            .child(
-                                div()
-                                    .w_full()
-                                    .rounded_md()
-                                    .border_1()
-                                    .border_color(cx.theme().border)
-                                    .p_3()
-                                    .v_flex()
-                                    .gap_3()
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
                                     .child(
                                         div()
                                             .w_full()
@@ -787,30 +1662,46 @@ impl Render for SettingsDialogWindow {
                                                         div()
                                                             .text_sm()
                                                             .text_color(cx.theme().foreground)
-                                                            .child(i18n.settings_titlebar_navigation_label),
+                                                            .child(i18n.settings_accent_color_label),
                                                     )
                                                     .child(
                                                         div()
                                                             .text_xs()
                                                             .text_color(cx.theme().muted_foreground)
                                                             .whitespace_normal()
-                                                            .child(i18n.settings_titlebar_navigation_hint),
+                                                            .child(i18n.settings_accent_color_hint),
                                                     ),
                                             )
                                             .child(
-                                                Checkbox::new(
-                                                    "settings-show-titlebar-navigation-window",
-                                                )
-                                                .checked(titlebar_preferences.show_navigation)
-                                                .on_click(cx.listener(
-                                                    |this, checked: &bool, _, cx| {
-                                                        let _ = this.viewer.update(cx, |viewer, cx| {
-                                                            viewer.set_titlebar_navigation_visible(
-                                                                *checked, cx,
-                                                            );
-                                                        });
-                                                    },
-                                                )),
+                                                div()
+                                                    .flex()
+                                                    .items_center()
+                                                    .gap_2()
+                                                    .child(ColorPicker::new(&self.accent_color_picker).small())
+                                                    .child(
+                                                        div()
+                                                            .w(px(96.))
+                                                            .child(Input::new(&self.accent_color_input).small()),
+                                                    )
+                                                    .child(
+                                                        Button::new("settings-accent-color-apply")
+                                                            .small()
+                                                            .outline()
+                                                            .label(i18n.settings_accent_color_apply_button)
+                                                            .on_click(cx.listener(|this, _, window, cx| {
+                                                                this.apply_accent_color_input(window, cx);
+                                                            })),
+                                                    )
+                                                    .child(
+                                                        Button::new("settings-accent-color-reset")
+                                                            .small()
+                                                            .ghost()
+                                                            .disabled(accent_color.is_none())
+                                                            .label(i18n.settings_accent_color_reset_button)
+                                                            .on_click(cx.listener(|this, _, window, cx| {
+                                                                this.set_accent_color(None, window, cx);
+                                                            })),
+                                                    ),
                                             ),
                                     )
                                     .child(div().h(px(1.)).bg(cx.theme().border))
@@ -831,50 +1722,49 @@ impl Render for SettingsDialogWindow {
                                                         div()
                                                             .text_sm()
                                                             .text_color(cx.theme().foreground)
-                                                            .child(i18n.settings_titlebar_zoom_label),
+                                                            .child(i18n.settings_ui_font_size_label),
                                                     )
                                                     .child(
                                                         div()
                                                             .text_xs()
                                                             .text_color(cx.theme().muted_foreground)
                                                             .whitespace_normal()
-                                                            .child(i18n.settings_titlebar_zoom_hint),
+                                                            .child(i18n.settings_ui_font_size_hint),
                                                     ),
                                             )
                                             .child(
-                                                Checkbox::new("settings-show-titlebar-zoom-window")
-                                                    .checked(titlebar_preferences.show_zoom)
+                                                UI_FONT_SIZE_OPTIONS
+                                                    .into_iter()
+                                                    .enumerate()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-ui-font-size-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, (ix, font_size)| {
+                                                            group.child(
+                                                                Button::new(("settings-ui-font-size", ix))
+                                                                    .label(font_size.to_string())
+                                                                    .selected(ui_font_size == font_size),
+                                                            )
+                                                        },
+                                                    )
                                                     .on_click(cx.listener(
-                                                        |this, checked: &bool, _, cx| {
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(font_size) = selected
+                                                                .first()
+                                                                .and_then(|ix| UI_FONT_SIZE_OPTIONS.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
                                                             let _ = this.viewer.update(cx, |viewer, cx| {
-                                                                viewer.set_titlebar_zoom_visible(*checked, cx);
+                                                                viewer.set_ui_font_size(font_size, cx);
                                                             });
                                                         },
                                                     )),
                                             ),
-                                    ),
-                            ),
-                    )
-                    .child(
-                        div()
-                            .v_flex()
-                            .gap_2()
-                            .child(
-                                div()
-                                    .mt_2()
-                                    .text_sm()
-                                    .text_color(cx.theme().muted_foreground)
-                                    .child(i18n.settings_tab_layout_section),
-                            )
-                            .child(
-                                div()
-                                    .w_full()
-                                    .rounded_md()
-                                    .border_1()
-                                    .border_color(cx.theme().border)
-                                    .p_3()
-                                    .v_flex()
-                                    .gap_3()
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
                                     .child(
                                         div()
                                             .w_full()
@@ -892,43 +1782,115 @@ impl Render for SettingsDialogWindow {
                                                         div()
                                                             .text_sm()
                                                             .text_color(cx.theme().foreground)
-                                                            .child(i18n.settings_tab_layout_label),
+                                                            .child(i18n.settings_ui_density_label),
                                                     )
                                                     .child(
                                                         div()
                                                             .text_xs()
                                                             .text_color(cx.theme().muted_foreground)
                                                             .whitespace_normal()
-                                                            .child(i18n.settings_tab_layout_hint),
+                                                            .child(i18n.settings_ui_density_hint),
                                                     ),
                                             )
                                             .child(
-                                                ButtonGroup::new("settings-tab-layout-mode-window")
-                                                    .small()
-                                                    .outline()
+                                                UiDensity::ALL
+                                                    .into_iter()
+                                                    .enumerate()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-ui-density-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, (ix, density)| {
+                                                            group.child(
+                                                                Button::new(("settings-ui-density", ix))
+                                                                    .label(match density {
+                                                                        UiDensity::Comfortable => {
+                                                                            i18n.settings_ui_density_comfortable
+                                                                        }
+                                                                        UiDensity::Compact => {
+                                                                            i18n.settings_ui_density_compact
+                                                                        }
+                                                                    })
+                                                                    .selected(ui_density == density),
+                                                            )
+                                                        },
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(density) = selected
+                                                                .first()
+                                                                .and_then(|ix| UiDensity::ALL.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_ui_density(density, cx);
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
                                                     .child(
-                                                        Button::new("settings-tab-layout-horizontal-window")
-                                                            .label(i18n.settings_tab_layout_horizontal)
-                                                            .selected(tab_layout_mode == TabLayoutMode::Horizontal),
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_window_opacity_label),
                                                     )
                                                     .child(
-                                                        Button::new("settings-tab-layout-vertical-window")
-                                                            .label(i18n.settings_tab_layout_vertical)
-                                                            .selected(tab_layout_mode == TabLayoutMode::Vertical),
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_window_opacity_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                WINDOW_OPACITY_OPTIONS_PERCENT
+                                                    .iter()
+                                                    .copied()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-window-opacity-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, percent| {
+                                                            group.child(
+                                                                Button::new((
+                                                                    "settings-window-opacity",
+                                                                    percent as usize,
+                                                                ))
+                                                                .label(format!("{percent}%"))
+                                                                .selected(window_opacity_percent == percent),
+                                                            )
+                                                        },
                                                     )
                                                     .on_click(cx.listener(
                                                         |this, selected: &Vec<usize>, _, cx| {
-                                                            let mode = if selected.first().copied()
-                                                                == Some(1)
-                                                            {
-                                                                TabLayoutMode::Vertical
-                                                            } else {
-                                                                TabLayoutMode::Horizontal
+                                                            let Some(percent) = selected
+                                                                .first()
+                                                                .and_then(|ix| WINDOW_OPACITY_OPTIONS_PERCENT.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
                                                             };
                                                             let _ =
                                                                 this.viewer.update(cx, |viewer, cx| {
-                                                                    viewer.set_tab_layout_mode(
-                                                                        mode,
+                                                                    viewer.set_window_opacity_percent(
+                                                                        percent,
                                                                         cx,
                                                                     );
                                                                 });
@@ -938,6 +1900,1736 @@ impl Render for SettingsDialogWindow {
                                     ),
                             ),
                     )
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .mt_2()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(i18n.settings_titlebar_section),
+                            )
+                            .child(
+                                div()
+                                    .w_full()
+                                    .rounded_md()
+                                    .border_1()
+                                    .border_color(cx.theme().border)
+                                    .p_3()
+                                    .v_flex()
+                                    .gap_3()
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .whitespace_normal()
+                                            .child(i18n.settings_toolbar_hint),
+                                    )
+                                    .children(titlebar_preferences.toolbar.into_iter().enumerate().map(
+                                        |(index, entry)| {
+                                            let (label, hint) = match entry.item {
+                                                ToolbarItem::Navigation => (
+                                                    i18n.settings_titlebar_navigation_label,
+                                                    i18n.settings_titlebar_navigation_hint,
+                                                ),
+                                                ToolbarItem::Search => {
+                                                    (i18n.settings_toolbar_search_label, i18n.settings_toolbar_search_hint)
+                                                }
+                                                ToolbarItem::Bookmark => (
+                                                    i18n.settings_toolbar_bookmark_label,
+                                                    i18n.settings_toolbar_bookmark_hint,
+                                                ),
+                                                ToolbarItem::Zoom => {
+                                                    (i18n.settings_titlebar_zoom_label, i18n.settings_titlebar_zoom_hint)
+                                                }
+                                            };
+                                            let item = entry.item;
+                                            div()
+                                                .w_full()
+                                                .flex()
+                                                .items_center()
+                                                .justify_between()
+                                                .gap_2()
+                                                .when(index > 0, |this| {
+                                                    this.pt_3().border_t_1().border_color(cx.theme().border)
+                                                })
+                                                .child(
+                                                    div()
+                                                        .flex_1()
+                                                        .v_flex()
+                                                        .items_start()
+                                                        .gap_1()
+                                                        .child(
+                                                            div()
+                                                                .text_sm()
+                                                                .text_color(cx.theme().foreground)
+                                                                .child(label),
+                                                        )
+                                                        .child(
+                                                            div()
+                                                                .text_xs()
+                                                                .text_color(cx.theme().muted_foreground)
+                                                                .whitespace_normal()
+                                                                .child(hint),
+                                                        ),
+                                                )
+                                                .child(
+                                                    Button::new(("settings-toolbar-move-up", index))
+                                                        .xsmall()
+                                                        .ghost()
+                                                        .icon(Icon::new(crate::icons::IconName::ChevronUp).size_4())
+                                                        .disabled(index == 0)
+                                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.move_toolbar_entry(index, -1, cx);
+                                                            });
+                                                        })),
+                                                )
+                                                .child(
+                                                    Button::new(("settings-toolbar-move-down", index))
+                                                        .xsmall()
+                                                        .ghost()
+                                                        .icon(Icon::new(crate::icons::IconName::ChevronDown).size_4())
+                                                        .disabled(index + 1 == ToolbarItem::ALL.len())
+                                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.move_toolbar_entry(index, 1, cx);
+                                                            });
+                                                        })),
+                                                )
+                                                .child(
+                                                    Checkbox::new(("settings-toolbar-item", index))
+                                                        .checked(entry.visible)
+                                                        .on_click(cx.listener(
+                                                            move |this, checked: &bool, _, cx| {
+                                                                let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                    viewer.set_toolbar_item_visible(item, *checked, cx);
+                                                                });
+                                                            },
+                                                        )),
+                                                )
+                                        },
+                                    )),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .mt_2()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(i18n.settings_tab_layout_section),
+                            )
+                            .child(
+                                div()
+                                    .w_full()
+                                    .rounded_md()
+                                    .border_1()
+                                    .border_color(cx.theme().border)
+                                    .p_3()
+                                    .v_flex()
+                                    .gap_3()
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_tab_layout_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_tab_layout_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                ButtonGroup::new("settings-tab-layout-mode-window")
+                                                    .small()
+                                                    .outline()
+                                                    .child(
+                                                        Button::new("settings-tab-layout-horizontal-window")
+                                                            .label(i18n.settings_tab_layout_horizontal)
+                                                            .selected(tab_layout_mode == TabLayoutMode::Horizontal),
+                                                    )
+                                                    .child(
+                                                        Button::new("settings-tab-layout-vertical-window")
+                                                            .label(i18n.settings_tab_layout_vertical)
+                                                            .selected(tab_layout_mode == TabLayoutMode::Vertical),
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let mode = if selected.first().copied()
+                                                                == Some(1)
+                                                            {
+                                                                TabLayoutMode::Vertical
+                                                            } else {
+                                                                TabLayoutMode::Horizontal
+                                                            };
+                                                            let _ =
+                                                                this.viewer.update(cx, |viewer, cx| {
+                                                                    viewer.set_tab_layout_mode(
+                                                                        mode,
+                                                                        cx,
+                                                                    );
+                                                                });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_startup_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_startup_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                StartupBehavior::ALL
+                                                    .into_iter()
+                                                    .enumerate()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-startup-behavior-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, (ix, behavior)| {
+                                                            group.child(
+                                                                Button::new(("settings-startup-behavior", ix))
+                                                                    .label(match behavior {
+                                                                        StartupBehavior::RestoreTabs => {
+                                                                            i18n.settings_startup_restore_tabs
+                                                                        }
+                                                                        StartupBehavior::Empty => {
+                                                                            i18n.settings_startup_empty
+                                                                        }
+                                                                        StartupBehavior::Home => {
+                                                                            i18n.settings_startup_home
+                                                                        }
+                                                                    })
+                                                                    .selected(startup_behavior == behavior),
+                                                            )
+                                                        },
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(behavior) = selected
+                                                                .first()
+                                                                .and_then(|ix| StartupBehavior::ALL.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_startup_behavior(behavior, cx);
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_thumbnail_panel_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_thumbnail_panel_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                Checkbox::new("settings-show-thumbnail-panel-window")
+                                                    .checked(thumbnail_panel_visible)
+                                                    .on_click(cx.listener(
+                                                        |this, checked: &bool, _, cx| {
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_thumbnail_panel_visible(*checked, cx);
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_comic_right_to_left_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_comic_right_to_left_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                Checkbox::new("settings-comic-right-to-left-window")
+                                                    .checked(comic_right_to_left)
+                                                    .on_click(cx.listener(
+                                                        |this, checked: &bool, _, cx| {
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_comic_right_to_left(*checked, cx);
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_page_double_click_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_page_double_click_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                PageDoubleClickAction::ALL
+                                                    .into_iter()
+                                                    .enumerate()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-page-double-click-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, (ix, action)| {
+                                                            group.child(
+                                                                Button::new(("settings-page-double-click", ix))
+                                                                    .label(match action {
+                                                                        PageDoubleClickAction::Nothing => {
+                                                                            i18n.settings_page_double_click_nothing
+                                                                        }
+                                                                        PageDoubleClickAction::FitWidth => {
+                                                                            i18n.settings_page_double_click_fit_width
+                                                                        }
+                                                                        PageDoubleClickAction::ActualSize => {
+                                                                            i18n.settings_page_double_click_actual_size
+                                                                        }
+                                                                        PageDoubleClickAction::ToggleZenMode => {
+                                                                            i18n.settings_page_double_click_zen_mode
+                                                                        }
+                                                                    })
+                                                                    .selected(page_double_click_action == action),
+                                                            )
+                                                        },
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(action) = selected
+                                                                .first()
+                                                                .and_then(|ix| PageDoubleClickAction::ALL.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_page_double_click_action(action, cx);
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_presentation_timer_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_presentation_timer_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                Checkbox::new("settings-presentation-timer-window")
+                                                    .checked(presentation_timer_visible)
+                                                    .on_click(cx.listener(
+                                                        |this, checked: &bool, _, cx| {
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_presentation_timer_visible(*checked, cx);
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_presentation_auto_advance_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_presentation_auto_advance_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                PRESENTATION_AUTO_ADVANCE_OPTIONS_SECS
+                                                    .iter()
+                                                    .copied()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-presentation-auto-advance-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, secs| {
+                                                            group.child(
+                                                                Button::new((
+                                                                    "settings-presentation-auto-advance",
+                                                                    secs as usize,
+                                                                ))
+                                                                .label(if secs == 0 {
+                                                                    i18n.settings_presentation_auto_advance_off.to_string()
+                                                                } else {
+                                                                    format!("{secs}s")
+                                                                })
+                                                                .selected(presentation_auto_advance_secs == secs),
+                                                            )
+                                                        },
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(secs) = selected
+                                                                .first()
+                                                                .and_then(|ix| PRESENTATION_AUTO_ADVANCE_OPTIONS_SECS.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
+                                                            let _ =
+                                                                this.viewer.update(cx, |viewer, cx| {
+                                                                    viewer.set_presentation_auto_advance_secs(
+                                                                        secs,
+                                                                        cx,
+                                                                    );
+                                                                });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_presentation_pointer_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_presentation_pointer_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                PresentationPointer::ALL
+                                                    .into_iter()
+                                                    .enumerate()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-presentation-pointer-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, (ix, pointer)| {
+                                                            group.child(
+                                                                Button::new(("settings-presentation-pointer", ix))
+                                                                    .label(match pointer {
+                                                                        PresentationPointer::Off => {
+                                                                            i18n.settings_presentation_pointer_off
+                                                                        }
+                                                                        PresentationPointer::Laser => {
+                                                                            i18n.settings_presentation_pointer_laser
+                                                                        }
+                                                                        PresentationPointer::Spotlight => {
+                                                                            i18n.settings_presentation_pointer_spotlight
+                                                                        }
+                                                                    })
+                                                                    .selected(presentation_pointer == pointer),
+                                                            )
+                                                        },
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(pointer) = selected
+                                                                .first()
+                                                                .and_then(|ix| PresentationPointer::ALL.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_presentation_pointer(pointer, cx);
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
+                                    ),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .mt_2()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(i18n.settings_rendering_section),
+                            )
+                            .child(
+                                div()
+                                    .w_full()
+                                    .rounded_md()
+                                    .border_1()
+                                    .border_color(cx.theme().border)
+                                    .p_3()
+                                    .v_flex()
+                                    .gap_3()
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_render_workers_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_render_workers_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                (1..=RENDER_WORKERS_MAX)
+                                                    .fold(
+                                                        ButtonGroup::new("settings-render-workers-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, count| {
+                                                            group.child(
+                                                                Button::new((
+                                                                    "settings-render-workers",
+                                                                    count,
+                                                                ))
+                                                                .label(count.to_string())
+                                                                .selected(render_worker_count == count),
+                                                            )
+                                                        },
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(index) = selected.first().copied()
+                                                            else {
+                                                                return;
+                                                            };
+                                                            let _ =
+                                                                this.viewer.update(cx, |viewer, cx| {
+                                                                    viewer.set_render_worker_count(
+                                                                        index + 1,
+                                                                        cx,
+                                                                    );
+                                                                });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_render_quality_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_render_quality_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                RENDER_QUALITY_OPTIONS_PERCENT
+                                                    .iter()
+                                                    .copied()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-render-quality-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, percent| {
+                                                            group.child(
+                                                                Button::new((
+                                                                    "settings-render-quality",
+                                                                    percent as usize,
+                                                                ))
+                                                                .label(format!(
+                                                                    "{}×",
+                                                                    percent as f32 / 100.0
+                                                                ))
+                                                                .selected(render_quality_percent == percent),
+                                                            )
+                                                        },
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(percent) = selected
+                                                                .first()
+                                                                .and_then(|ix| RENDER_QUALITY_OPTIONS_PERCENT.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
+                                                            let _ =
+                                                                this.viewer.update(cx, |viewer, cx| {
+                                                                    viewer.set_render_quality_percent(
+                                                                        percent,
+                                                                        cx,
+                                                                    );
+                                                                });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_text_antialiasing_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_text_antialiasing_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                TextAntialiasing::ALL
+                                                    .into_iter()
+                                                    .enumerate()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-text-antialiasing-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, (ix, mode)| {
+                                                            group.child(
+                                                                Button::new(("settings-text-antialiasing", ix))
+                                                                    .label(match mode {
+                                                                        TextAntialiasing::Grayscale => {
+                                                                            i18n.settings_text_antialiasing_grayscale
+                                                                        }
+                                                                        TextAntialiasing::Lcd => {
+                                                                            i18n.settings_text_antialiasing_lcd
+                                                                        }
+                                                                        TextAntialiasing::Off => {
+                                                                            i18n.settings_text_antialiasing_off
+                                                                        }
+                                                                    })
+                                                                    .selected(render_options.text_antialiasing == mode),
+                                                            )
+                                                        },
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(mode) = selected
+                                                                .first()
+                                                                .and_then(|ix| TextAntialiasing::ALL.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_render_options(
+                                                                    RenderOptions {
+                                                                        text_antialiasing: mode,
+                                                                        ..viewer.render_options
+                                                                    },
+                                                                    cx,
+                                                                );
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_native_text_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_native_text_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                Checkbox::new("settings-native-text-window")
+                                                    .checked(render_options.native_text)
+                                                    .on_click(cx.listener(
+                                                        |this, checked: &bool, _, cx| {
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_render_options(
+                                                                    RenderOptions {
+                                                                        native_text: *checked,
+                                                                        ..viewer.render_options
+                                                                    },
+                                                                    cx,
+                                                                );
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .min_w(px(0.))
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_pdfium_library_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_pdfium_library_hint),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(pdfium_library_text),
+                                                    ),
+                                            )
+                                            .child(
+                                                div()
+                                                    .v_flex()
+                                                    .items_end()
+                                                    .gap_2()
+                                                    .child(
+                                                        downloaded_pdfium_libraries
+                                                            .iter()
+                                                            .enumerate()
+                                                            .fold(
+                                                                ButtonGroup::new("settings-pdfium-library-window")
+                                                                    .small()
+                                                                    .outline()
+                                                                    .child(
+                                                                        Button::new("settings-pdfium-library-auto")
+                                                                            .label(i18n.settings_pdfium_library_automatic)
+                                                                            .selected(pdfium_library_path.is_none()),
+                                                                    ),
+                                                                |group, (ix, (label, path))| {
+                                                                    group.child(
+                                                                        Button::new(("settings-pdfium-library", ix))
+                                                                            .label(label.clone())
+                                                                            .selected(
+                                                                                pdfium_library_path.as_ref() == Some(path),
+                                                                            ),
+                                                                    )
+                                                                },
+                                                            )
+                                                            .on_click(cx.listener(
+                                                                |this, selected: &Vec<usize>, _, cx| {
+                                                                    let Some(ix) = selected.first().copied() else {
+                                                                        return;
+                                                                    };
+                                                                    // Index 0 is "Automatic"; downloads follow it.
+                                                                    let path = ix.checked_sub(1).and_then(|ix| {
+                                                                        this.downloaded_pdfium_libraries
+                                                                            .get(ix)
+                                                                            .map(|(_, path)| path.clone())
+                                                                    });
+                                                                    let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                        viewer.set_pdfium_library_path(path, cx);
+                                                                    });
+                                                                },
+                                                            )),
+                                                    )
+                                                    .child(
+                                                        Button::new("settings-pdfium-library-choose-window")
+                                                            .small()
+                                                            .ghost()
+                                                            .label(i18n.settings_pdfium_library_choose_button)
+                                                            .on_click(cx.listener(|this, _, _, cx| {
+                                                                this.choose_pdfium_library(cx);
+                                                            })),
+                                                    ),
+                                            ),
+                                    )
+                                    .when(cfg!(feature = "mupdf"), |this| {
+                                        this.child(div().h(px(1.)).bg(cx.theme().border)).child(
+                                            div()
+                                                .w_full()
+                                                .flex()
+                                                .items_start()
+                                                .justify_between()
+                                                .gap_3()
+                                                .child(
+                                                    div()
+                                                        .flex_1()
+                                                        .v_flex()
+                                                        .items_start()
+                                                        .gap_1()
+                                                        .child(
+                                                            div()
+                                                                .text_sm()
+                                                                .text_color(cx.theme().foreground)
+                                                                .child(i18n.settings_render_engine_label),
+                                                        )
+                                                        .child(
+                                                            div()
+                                                                .text_xs()
+                                                                .text_color(cx.theme().muted_foreground)
+                                                                .whitespace_normal()
+                                                                .child(i18n.settings_render_engine_hint),
+                                                        ),
+                                                )
+                                                .child(
+                                                    RenderEngine::ALL
+                                                        .into_iter()
+                                                        .fold(
+                                                            ButtonGroup::new("settings-render-engine-window")
+                                                                .small()
+                                                                .outline(),
+                                                            |group, engine| {
+                                                                group.child(
+                                                                    Button::new((
+                                                                        "settings-render-engine",
+                                                                        engine as usize,
+                                                                    ))
+                                                                    .label(engine.label())
+                                                                    .selected(render_engine == engine),
+                                                                )
+                                                            },
+                                                        )
+                                                        .on_click(cx.listener(
+                                                            |this, selected: &Vec<usize>, _, cx| {
+                                                                let Some(engine) = selected
+                                                                    .first()
+                                                                    .and_then(|ix| RenderEngine::ALL.get(*ix))
+                                                                    .copied()
+                                                                else {
+                                                                    return;
+                                                                };
+                                                                let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                    viewer.set_render_engine(engine, cx);
+                                                                });
+                                                            },
+                                                        )),
+                                                ),
+                                        )
+                                    })
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_render_cache_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_render_cache_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                Checkbox::new("settings-render-cache-window")
+                                                    .checked(render_cache_enabled)
+                                                    .on_click(cx.listener(
+                                                        |this, checked: &bool, _, cx| {
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_render_cache_enabled(*checked, cx);
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_idle_prerender_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_idle_prerender_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                Checkbox::new("settings-idle-prerender-window")
+                                                    .checked(idle_prerender_enabled)
+                                                    .on_click(cx.listener(
+                                                        |this, checked: &bool, _, cx| {
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_idle_prerender_enabled(*checked, cx);
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_center()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .text_xs()
+                                                    .text_color(cx.theme().muted_foreground)
+                                                    .child(format!(
+                                                        "{}: {}",
+                                                        i18n.settings_render_cache_usage_label,
+                                                        render_cache_usage_text
+                                                    )),
+                                            )
+                                            .child(
+                                                RENDER_CACHE_LIMIT_OPTIONS_MB
+                                                    .into_iter()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-render-cache-limit-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, limit_mb| {
+                                                            group.child(
+                                                                Button::new((
+                                                                    "settings-render-cache-limit",
+                                                                    limit_mb as usize,
+                                                                ))
+                                                                .label(PdfViewer::format_storage_size(
+                                                                    limit_mb * 1024 * 1024,
+                                                                ))
+                                                                .selected(render_cache_limit_mb == limit_mb),
+                                                            )
+                                                        },
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(limit_mb) = selected
+                                                                .first()
+                                                                .and_then(|ix| {
+                                                                    RENDER_CACHE_LIMIT_OPTIONS_MB.get(*ix)
+                                                                })
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
+                                                            let _ =
+                                                                this.viewer.update(cx, |viewer, cx| {
+                                                                    viewer.set_render_cache_limit_mb(
+                                                                        limit_mb,
+                                                                        cx,
+                                                                    );
+                                                                });
+                                                        },
+                                                    )),
+                                            )
+                                            .child(
+                                                Button::new("settings-render-cache-clear-window")
+                                                    .small()
+                                                    .ghost()
+                                                    .label(i18n.settings_render_cache_clear_button)
+                                                    .on_click(cx.listener(|this, _, _, cx| {
+                                                        let _ = this.viewer.update(cx, |viewer, cx| {
+                                                            viewer.clear_render_cache(cx);
+                                                        });
+                                                    })),
+                                            ),
+                                    ),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .mt_2()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(i18n.settings_external_tools_section),
+                            )
+                            .child(
+                                div()
+                                    .w_full()
+                                    .rounded_md()
+                                    .border_1()
+                                    .border_color(cx.theme().border)
+                                    .p_3()
+                                    .v_flex()
+                                    .gap_3()
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .whitespace_normal()
+                                            .child(i18n.settings_external_tools_hint),
+                                    )
+                                    .children(external_tools.into_iter().enumerate().map(
+                                        |(index, tool)| {
+                                            div()
+                                                .w_full()
+                                                .flex()
+                                                .items_center()
+                                                .justify_between()
+                                                .gap_3()
+                                                .child(
+                                                    div()
+                                                        .flex_1()
+                                                        .min_w(px(0.))
+                                                        .v_flex()
+                                                        .items_start()
+                                                        .gap_1()
+                                                        .child(
+                                                            div()
+                                                                .text_sm()
+                                                                .text_color(cx.theme().foreground)
+                                                                .child(tool.name),
+                                                        )
+                                                        .child(
+                                                            div()
+                                                                .w_full()
+                                                                .truncate()
+                                                                .text_xs()
+                                                                .text_color(cx.theme().muted_foreground)
+                                                                .child(tool.command),
+                                                        ),
+                                                )
+                                                .child(
+                                                    Button::new(("settings-external-tool-remove", index))
+                                                        .small()
+                                                        .ghost()
+                                                        .label(i18n.settings_external_tool_remove_button)
+                                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.remove_external_tool(index, cx);
+                                                            });
+                                                        })),
+                                                )
+                                        },
+                                    ))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_center()
+                                            .gap_2()
+                                            .child(
+                                                div()
+                                                    .w(px(140.))
+                                                    .child(Input::new(&self.external_tool_name_input).small()),
+                                            )
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .min_w(px(0.))
+                                                    .child(Input::new(&self.external_tool_command_input).small()),
+                                            )
+                                            .child(
+                                                Button::new("settings-external-tool-add")
+                                                    .small()
+                                                    .outline()
+                                                    .label(i18n.settings_external_tool_add_button)
+                                                    .on_click(cx.listener(|this, _, window, cx| {
+                                                        this.add_external_tool(window, cx);
+                                                    })),
+                                            ),
+                                    ),
+                            ),
+                    )
+                    .when(cfg!(target_os = "windows"), |this| {
+                        this.child(
+                            div()
+                                .v_flex()
+                                .gap_2()
+                                .child(
+                                    div()
+                                        .mt_2()
+                                        .text_sm()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child(i18n.settings_shell_integration_section),
+                                )
+                                .child(
+                                    div()
+                                        .w_full()
+                                        .rounded_md()
+                                        .border_1()
+                                        .border_color(cx.theme().border)
+                                        .p_3()
+                                        .flex()
+                                        .items_start()
+                                        .justify_between()
+                                        .gap_3()
+                                        .child(
+                                            div()
+                                                .flex_1()
+                                                .v_flex()
+                                                .items_start()
+                                                .gap_1()
+                                                .child(
+                                                    div()
+                                                        .text_sm()
+                                                        .text_color(cx.theme().foreground)
+                                                        .child(i18n.settings_shell_open_with_label),
+                                                )
+                                                .child(
+                                                    div()
+                                                        .text_xs()
+                                                        .text_color(cx.theme().muted_foreground)
+                                                        .whitespace_normal()
+                                                        .child(i18n.settings_shell_open_with_hint),
+                                                ),
+                                        )
+                                        .child(
+                                            Checkbox::new("settings-shell-open-with-window")
+                                                .checked(shell_open_with_registered)
+                                                .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                                    let _ = this.viewer.update(cx, |viewer, cx| {
+                                                        viewer.set_shell_open_with_registered(*checked, cx);
+                                                    });
+                                                })),
+                                        ),
+                                ),
+                        )
+                    })
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .mt_2()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(i18n.settings_updates_section),
+                            )
+                            .child(
+                                div()
+                                    .w_full()
+                                    .rounded_md()
+                                    .border_1()
+                                    .border_color(cx.theme().border)
+                                    .p_3()
+                                    .v_flex()
+                                    .gap_3()
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_update_check_frequency_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_update_check_frequency_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                UpdateCheckFrequency::ALL
+                                                    .into_iter()
+                                                    .enumerate()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-update-check-frequency-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, (ix, frequency)| {
+                                                            group.child(
+                                                                Button::new(("settings-update-check-frequency", ix))
+                                                                    .label(match frequency {
+                                                                        UpdateCheckFrequency::Never => {
+                                                                            i18n.settings_update_check_never
+                                                                        }
+                                                                        UpdateCheckFrequency::Daily => {
+                                                                            i18n.settings_update_check_daily
+                                                                        }
+                                                                        UpdateCheckFrequency::Weekly => {
+                                                                            i18n.settings_update_check_weekly
+                                                                        }
+                                                                    })
+                                                                    .selected(update_check_frequency == frequency),
+                                                            )
+                                                        },
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(frequency) = selected
+                                                                .first()
+                                                                .and_then(|ix| UpdateCheckFrequency::ALL.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
+                                                            let _ = this.viewer.update(cx, |viewer, cx| {
+                                                                viewer.set_update_check_frequency(frequency, cx);
+                                                            });
+                                                        },
+                                                    )),
+                                            ),
+                                    ),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .mt_2()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(i18n.settings_annotations_section),
+                            )
+                            .child(
+                                div()
+                                    .w_full()
+                                    .rounded_md()
+                                    .border_1()
+                                    .border_color(cx.theme().border)
+                                    .p_3()
+                                    .v_flex()
+                                    .gap_3()
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .v_flex()
+                                            .gap_1()
+                                            .child(
+                                                div()
+                                                    .text_sm()
+                                                    .text_color(cx.theme().foreground)
+                                                    .child(i18n.settings_annotation_author_label),
+                                            )
+                                            .child(
+                                                div()
+                                                    .w_full()
+                                                    .flex()
+                                                    .items_center()
+                                                    .gap_2()
+                                                    .child(div().flex_1().child(Input::new(&self.annotation_author_input).small()))
+                                                    .child(
+                                                        Button::new("settings-annotation-author-save")
+                                                            .small()
+                                                            .outline()
+                                                            .label(i18n.settings_annotation_author_save_button)
+                                                            .on_click(cx.listener(|this, _, _, cx| {
+                                                                this.save_annotation_author(cx);
+                                                            })),
+                                                    ),
+                                            ),
+                                    )
+                                    .child(self.render_annotation_color_row(
+                                        "settings-annotation-highlight-color",
+                                        i18n.settings_annotation_highlight_color_label,
+                                        TextMarkupColor::ALL
+                                            .into_iter()
+                                            .map(|color| {
+                                                (
+                                                    PdfViewer::text_markup_color_rgb(color),
+                                                    color == annotation_defaults.highlight_color,
+                                                )
+                                            })
+                                            .collect(),
+                                        |defaults, ix| defaults.highlight_color = TextMarkupColor::ALL[ix],
+                                        cx,
+                                    ))
+                                    .child(self.render_annotation_color_row(
+                                        "settings-annotation-shape-color",
+                                        i18n.settings_annotation_shape_color_label,
+                                        ShapeColor::ALL
+                                            .into_iter()
+                                            .map(|color| {
+                                                (
+                                                    PdfViewer::shape_color_rgb(color),
+                                                    color == annotation_defaults.shape_color,
+                                                )
+                                            })
+                                            .collect(),
+                                        |defaults, ix| defaults.shape_color = ShapeColor::ALL[ix],
+                                        cx,
+                                    ))
+                                    .child(self.render_annotation_color_row(
+                                        "settings-annotation-ink-color",
+                                        i18n.settings_annotation_ink_color_label,
+                                        ShapeColor::ALL
+                                            .into_iter()
+                                            .map(|color| {
+                                                (
+                                                    PdfViewer::shape_color_rgb(color),
+                                                    color == annotation_defaults.ink_color,
+                                                )
+                                            })
+                                            .collect(),
+                                        |defaults, ix| defaults.ink_color = ShapeColor::ALL[ix],
+                                        cx,
+                                    )),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .mt_2()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(i18n.settings_network_section),
+                            )
+                            .child(
+                                div()
+                                    .w_full()
+                                    .rounded_md()
+                                    .border_1()
+                                    .border_color(cx.theme().border)
+                                    .p_3()
+                                    .v_flex()
+                                    .gap_3()
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(cx.theme().muted_foreground)
+                                            .whitespace_normal()
+                                            .child(i18n.settings_network_hint),
+                                    )
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .v_flex()
+                                            .gap_1()
+                                            .child(
+                                                div()
+                                                    .text_sm()
+                                                    .text_color(cx.theme().foreground)
+                                                    .child(i18n.settings_proxy_label),
+                                            )
+                                            .child(Input::new(&self.proxy_input).small()),
+                                    )
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .v_flex()
+                                            .gap_1()
+                                            .child(
+                                                div()
+                                                    .text_sm()
+                                                    .text_color(cx.theme().foreground)
+                                                    .child(i18n.settings_update_endpoint_label),
+                                            )
+                                            .child(Input::new(&self.update_endpoint_input).small()),
+                                    )
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .v_flex()
+                                            .gap_1()
+                                            .child(
+                                                div()
+                                                    .text_sm()
+                                                    .text_color(cx.theme().foreground)
+                                                    .child(i18n.settings_pdfium_mirror_label),
+                                            )
+                                            .child(Input::new(&self.pdfium_mirror_input).small()),
+                                    )
+                                    .child(
+                                        div().w_full().flex().justify_end().child(
+                                            Button::new("settings-network-save")
+                                                .small()
+                                                .outline()
+                                                .label(i18n.settings_network_save_button)
+                                                .on_click(cx.listener(|this, _, _, cx| {
+                                                    this.save_network_options(cx);
+                                                })),
+                                        ),
+                                    ),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .v_flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .mt_2()
+                                    .text_sm()
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(i18n.settings_logging_section),
+                            )
+                            .child(
+                                div()
+                                    .w_full()
+                                    .rounded_md()
+                                    .border_1()
+                                    .border_color(cx.theme().border)
+                                    .p_3()
+                                    .v_flex()
+                                    .gap_3()
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_start()
+                                            .justify_between()
+                                            .gap_3()
+                                            .child(
+                                                div()
+                                                    .flex_1()
+                                                    .v_flex()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(i18n.settings_log_level_label),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .whitespace_normal()
+                                                            .child(i18n.settings_log_level_hint),
+                                                    ),
+                                            )
+                                            .child(
+                                                crate::logger::LogLevel::ALL
+                                                    .into_iter()
+                                                    .enumerate()
+                                                    .fold(
+                                                        ButtonGroup::new("settings-log-level-window")
+                                                            .small()
+                                                            .outline(),
+                                                        |group, (ix, level)| {
+                                                            group.child(
+                                                                Button::new(("settings-log-level", ix))
+                                                                    .label(level.as_str())
+                                                                    .selected(log_level == level),
+                                                            )
+                                                        },
+                                                    )
+                                                    .on_click(cx.listener(
+                                                        |this, selected: &Vec<usize>, _, cx| {
+                                                            let Some(level) = selected
+                                                                .first()
+                                                                .and_then(|ix| crate::logger::LogLevel::ALL.get(*ix))
+                                                                .copied()
+                                                            else {
+                                                                return;
+                                                            };
+                                                            this.set_log_level(level, cx);
+                                                        },
+                                                    )),
+                                            ),
+                                    )
+                                    .child(div().h(px(1.)).bg(cx.theme().border))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .v_flex()
+                                            .gap_1()
+                                            .child(
+                                                div()
+                                                    .text_sm()
+                                                    .text_color(cx.theme().foreground)
+                                                    .child(i18n.settings_log_modules_label),
+                                            )
+                                            .child(
+                                                div()
+                                                    .text_xs()
+                                                    .text_color(cx.theme().muted_foreground)
+                                                    .whitespace_normal()
+                                                    .child(i18n.settings_log_modules_hint),
+                                            )
+                                            .child(
+                                                div()
+                                                    .w_full()
+                                                    .flex()
+                                                    .items_center()
+                                                    .gap_2()
+                                                    .child(
+                                                        div()
+                                                            .flex_1()
+                                                            .min_w(px(0.))
+                                                            .child(Input::new(&self.log_modules_input).small()),
+                                                    )
+                                                    .child(
+                                                        Button::new("settings-log-modules-apply")
+                                                            .small()
+                                                            .outline()
+                                                            .label(i18n.settings_log_modules_apply_button)
+                                                            .on_click(cx.listener(|this, _, _, cx| {
+                                                                this.apply_log_modules_input(cx);
+                                                            })),
+                                                    ),
+                                            )
+                                            .when_some(log_filter_error, |this, error| {
+                                                this.child(
+                                                    div()
+                                                        .text_xs()
+                                                        .text_color(cx.theme().danger)
+                                                        .child(error),
+                                                )
+                                            }),
+                                    ),
+                            ),
+                    )
                     .child(
                         div()
                             .v_flex()
@@ -0,0 +1,1061 @@
+use super::{
+    ERASER_RADIUS, EraserMode, INK_MIN_POINT_DISTANCE, MarkdownNoteAnchor, PdfViewer,
+    SHAPE_HANDLE_SIZE, SHAPE_HIT_TOLERANCE, SHAPE_MIN_DRAG_DISTANCE, SHAPE_STROKE_WIDTH_OPTIONS,
+    SHAPE_UNDO_LIMIT, ShapeColor, ShapeDrag, ShapeDragTarget, ShapeEntry, ShapeKind, ShapeStyle,
+};
+use crate::i18n::I18n;
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::*;
+use kpdf_core::annotations;
+use std::path::Path;
+
+const SHAPE_FILL_OPACITY: f32 = 0.25;
+const SHAPE_ARROW_HEAD_ANGLE: f32 = 0.45;
+
+struct ShapeOverlay {
+    kind: ShapeKind,
+    start: (f32, f32),
+    end: (f32, f32),
+    points: Vec<(f32, f32)>,
+    color: Hsla,
+    stroke_width: f32,
+    filled: bool,
+    selected: bool,
+}
+
+impl PdfViewer {
+    pub(super) fn load_shapes_from_store(store: &sled::Tree) -> Vec<ShapeEntry> {
+        annotations::load_entries(store)
+    }
+
+    pub(super) fn persist_shapes(&self) {
+        if let Some(store) = self.shapes_store.as_ref() {
+            annotations::store_entries(store, &self.shapes);
+        }
+    }
+
+    fn next_shape_id(&self) -> u64 {
+        let mut candidate = Self::now_unix_millis().saturating_mul(1000);
+        while self.shapes.iter().any(|shape| shape.id == candidate) {
+            candidate = candidate.saturating_add(1);
+        }
+        candidate
+    }
+
+    fn shape_by_id_mut(&mut self, shape_id: u64) -> Option<&mut ShapeEntry> {
+        self.shapes.iter_mut().find(|shape| shape.id == shape_id)
+    }
+
+    /// Arms `kind` so the next drag on a page draws it. Ink stays armed for further strokes.
+    pub(super) fn start_shape_tool(&mut self, kind: ShapeKind, cx: &mut Context<Self>) {
+        if self.active_tab_path().is_none() {
+            return;
+        }
+        self.shape_tool = Some(kind);
+        self.eraser = None;
+        self.selected_shape_id = None;
+        self.clear_text_selection(cx);
+        cx.notify();
+    }
+
+    pub(super) fn start_eraser(&mut self, mode: EraserMode, cx: &mut Context<Self>) {
+        if self.active_tab_path().is_none() {
+            return;
+        }
+        self.eraser = Some(mode);
+        self.shape_tool = None;
+        self.selected_shape_id = None;
+        self.clear_text_selection(cx);
+        cx.notify();
+    }
+
+    pub(super) fn shape_interaction_active(&self) -> bool {
+        self.shape_tool.is_some() || self.eraser.is_some() || self.selected_shape_id.is_some()
+    }
+
+    pub(super) fn cancel_shape_interaction(&mut self, cx: &mut Context<Self>) {
+        self.shape_tool = None;
+        self.eraser = None;
+        self.selected_shape_id = None;
+        self.shape_drag = None;
+        self.erasing_page = None;
+        // Abandon a half-finished drag or erase gesture.
+        if let Some((path, snapshot)) = self.shape_edit_snapshot.take() {
+            self.replace_document_shapes(&path, snapshot);
+        }
+        cx.notify();
+    }
+
+    pub(super) fn delete_shape_by_id(&mut self, shape_id: u64, cx: &mut Context<Self>) {
+        if self.selected_shape_id == Some(shape_id) {
+            self.selected_shape_id = None;
+        }
+        let Some(path) = self
+            .shapes
+            .iter()
+            .find(|shape| shape.id == shape_id)
+            .map(|shape| shape.path.clone())
+        else {
+            return;
+        };
+        self.begin_shape_edit(&path);
+        self.shapes.retain(|shape| shape.id != shape_id);
+        self.commit_shape_edit();
+        self.persist_shapes();
+        cx.notify();
+    }
+
+    pub(super) fn delete_selected_shape(&mut self, cx: &mut Context<Self>) {
+        if let Some(shape_id) = self.selected_shape_id {
+            self.delete_shape_by_id(shape_id, cx);
+        }
+    }
+
+    /// Restyles a shape and carries its width and fill over to the next one drawn; stroke colors
+    /// come from the annotation defaults.
+    fn update_shape_style(
+        &mut self,
+        shape_id: u64,
+        update: impl FnOnce(&mut ShapeStyle),
+        cx: &mut Context<Self>,
+    ) {
+        let now = Self::now_unix_secs();
+        let Some(path) = self
+            .shapes
+            .iter()
+            .find(|shape| shape.id == shape_id)
+            .map(|shape| shape.path.clone())
+        else {
+            return;
+        };
+        self.begin_shape_edit(&path);
+        let Some(shape) = self.shape_by_id_mut(shape_id) else {
+            self.shape_edit_snapshot = None;
+            return;
+        };
+        update(&mut shape.style);
+        shape.updated_at_unix_secs = now;
+        self.shape_style = shape.style;
+        self.commit_shape_edit();
+        self.persist_shapes();
+        cx.notify();
+    }
+
+    fn document_shapes(&self, path: &Path) -> Vec<ShapeEntry> {
+        self.shapes
+            .iter()
+            .filter(|shape| shape.path == path)
+            .cloned()
+            .collect()
+    }
+
+    /// Swaps in `shapes` as the document's shapes, leaving other documents untouched, and
+    /// returns the ones it replaced.
+    fn replace_document_shapes(&mut self, path: &Path, shapes: Vec<ShapeEntry>) -> Vec<ShapeEntry> {
+        let (replaced, kept) = std::mem::take(&mut self.shapes)
+            .into_iter()
+            .partition(|shape| shape.path == path);
+        self.shapes = kept;
+        self.shapes.extend(shapes);
+        replaced
+    }
+
+    /// Snapshots the shapes of the document at `path` before an edit to them.
+    pub(super) fn begin_shape_edit(&mut self, path: &Path) {
+        if self.shape_edit_snapshot.is_none() {
+            self.shape_edit_snapshot = Some((path.to_path_buf(), self.document_shapes(path)));
+        }
+    }
+
+    /// Turns the state saved by `begin_shape_edit` into one undo step of that document, if
+    /// anything changed.
+    pub(super) fn commit_shape_edit(&mut self) {
+        let Some((path, snapshot)) = self.shape_edit_snapshot.take() else {
+            return;
+        };
+        if snapshot == self.document_shapes(&path) {
+            return;
+        }
+        let history = self.shape_history.entry(path).or_default();
+        if history.undo.len() >= SHAPE_UNDO_LIMIT {
+            history.undo.remove(0);
+        }
+        history.undo.push(snapshot);
+        history.redo.clear();
+    }
+
+    /// Steps the active document's shapes and ink back one edit. Returns `false` when there
+    /// is nothing to undo.
+    pub(super) fn undo_shape_edit(&mut self, cx: &mut Context<Self>) -> bool {
+        self.step_shape_history(false, cx)
+    }
+
+    pub(super) fn redo_shape_edit(&mut self, cx: &mut Context<Self>) -> bool {
+        self.step_shape_history(true, cx)
+    }
+
+    fn step_shape_history(&mut self, redo: bool, cx: &mut Context<Self>) -> bool {
+        if self.shape_edit_snapshot.is_some() {
+            return false;
+        }
+        let Some(path) = self.active_tab_path().cloned() else {
+            return false;
+        };
+        let restored = match self.shape_history.get_mut(&path) {
+            Some(history) if redo => history.redo.pop(),
+            Some(history) => history.undo.pop(),
+            None => None,
+        };
+        let Some(restored) = restored else {
+            return false;
+        };
+        let current = self.replace_document_shapes(&path, restored);
+        let history = self.shape_history.entry(path).or_default();
+        if redo {
+            history.undo.push(current);
+        } else {
+            history.redo.push(current);
+        }
+        self.selected_shape_id = None;
+        self.persist_shapes();
+        cx.notify();
+        true
+    }
+
+    fn active_tab_shapes_for_page(&self, page_index: usize) -> Vec<ShapeEntry> {
+        let Some(path) = self.active_tab_path() else {
+            return Vec::new();
+        };
+
+        self.shapes
+            .iter()
+            .filter(|shape| shape.path == *path && shape.page_index == page_index)
+            .cloned()
+            .collect::<Vec<_>>()
+    }
+
+    fn shape_endpoints(shape: &ShapeEntry) -> [f32; 4] {
+        [
+            shape.start_x_ratio,
+            shape.start_y_ratio,
+            shape.end_x_ratio,
+            shape.end_y_ratio,
+        ]
+    }
+
+    fn set_shape_endpoints(shape: &mut ShapeEntry, endpoints: [f32; 4]) {
+        // Ink points travel with their bounds; ink is only ever moved, never resized.
+        let dx = endpoints[0] - shape.start_x_ratio;
+        let dy = endpoints[1] - shape.start_y_ratio;
+        for point in &mut shape.points {
+            point[0] += dx;
+            point[1] += dy;
+        }
+        shape.start_x_ratio = endpoints[0];
+        shape.start_y_ratio = endpoints[1];
+        shape.end_x_ratio = endpoints[2];
+        shape.end_y_ratio = endpoints[3];
+    }
+
+    fn fit_ink_bounds(shape: &mut ShapeEntry) {
+        let Some(&[first_x, first_y]) = shape.points.first() else {
+            return;
+        };
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (first_x, first_y, first_x, first_y);
+        for &[x, y] in &shape.points {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        shape.start_x_ratio = min_x;
+        shape.start_y_ratio = min_y;
+        shape.end_x_ratio = max_x;
+        shape.end_y_ratio = max_y;
+    }
+
+    fn shape_screen_endpoints(
+        &self,
+        shape: &ShapeEntry,
+        page_width_screen: f32,
+        page_height_screen: f32,
+    ) -> Option<((f32, f32), (f32, f32))> {
+        let endpoint = |x_ratio: f32, y_ratio: f32| {
+            self.note_anchor_to_page_local_screen(
+                shape.page_index,
+                &MarkdownNoteAnchor {
+                    page_index: shape.page_index,
+                    x_ratio,
+                    y_ratio,
+                },
+                page_width_screen,
+                page_height_screen,
+            )
+        };
+        Some((
+            endpoint(shape.start_x_ratio, shape.start_y_ratio)?,
+            endpoint(shape.end_x_ratio, shape.end_y_ratio)?,
+        ))
+    }
+
+    fn shape_screen_points(
+        &self,
+        shape: &ShapeEntry,
+        page_width_screen: f32,
+        page_height_screen: f32,
+    ) -> Vec<(f32, f32)> {
+        shape
+            .points
+            .iter()
+            .filter_map(|&[x_ratio, y_ratio]| {
+                self.note_anchor_to_page_local_screen(
+                    shape.page_index,
+                    &MarkdownNoteAnchor {
+                        page_index: shape.page_index,
+                        x_ratio,
+                        y_ratio,
+                    },
+                    page_width_screen,
+                    page_height_screen,
+                )
+            })
+            .collect()
+    }
+
+    fn shape_stroke_scale(&self, page_index: usize, page_width_screen: f32) -> Option<f32> {
+        self.active_tab_pages()
+            .and_then(|pages| pages.get(page_index))
+            .filter(|page| page.width_pt > 0.0)
+            .map(|page| page_width_screen / page.width_pt)
+    }
+
+    /// Topmost shape under the pointer; the selected shape's endpoint handles win over bodies.
+    pub(super) fn hit_test_shape_on_page(
+        &self,
+        page_index: usize,
+        local_x: f32,
+        local_y: f32,
+        page_width_screen: f32,
+        page_height_screen: f32,
+    ) -> Option<(u64, ShapeDragTarget)> {
+        let scale = self.shape_stroke_scale(page_index, page_width_screen)?;
+        let shapes = self.active_tab_shapes_for_page(page_index);
+        let near = |(x, y): (f32, f32), radius: f32| (local_x - x).hypot(local_y - y) <= radius;
+
+        if let Some(selected) = self
+            .selected_shape_id
+            .and_then(|id| shapes.iter().find(|shape| shape.id == id))
+            && selected.kind != ShapeKind::Ink
+            && let Some((start, end)) =
+                self.shape_screen_endpoints(selected, page_width_screen, page_height_screen)
+        {
+            let radius = SHAPE_HANDLE_SIZE / 2.0 + SHAPE_HIT_TOLERANCE;
+            if near(end, radius) {
+                return Some((selected.id, ShapeDragTarget::End));
+            }
+            if near(start, radius) {
+                return Some((selected.id, ShapeDragTarget::Start));
+            }
+        }
+
+        shapes.iter().rev().find_map(|shape| {
+            let (start, end) =
+                self.shape_screen_endpoints(shape, page_width_screen, page_height_screen)?;
+            let tolerance = shape.style.stroke_width_pt * scale / 2.0 + SHAPE_HIT_TOLERANCE;
+            let hit = match shape.kind {
+                ShapeKind::Rectangle => {
+                    local_x >= start.0.min(end.0) - tolerance
+                        && local_x <= start.0.max(end.0) + tolerance
+                        && local_y >= start.1.min(end.1) - tolerance
+                        && local_y <= start.1.max(end.1) + tolerance
+                }
+                ShapeKind::Ellipse => {
+                    let radius_x = (end.0 - start.0).abs() / 2.0 + tolerance;
+                    let radius_y = (end.1 - start.1).abs() / 2.0 + tolerance;
+                    let dx = (local_x - (start.0 + end.0) / 2.0) / radius_x;
+                    let dy = (local_y - (start.1 + end.1) / 2.0) / radius_y;
+                    dx * dx + dy * dy <= 1.0
+                }
+                ShapeKind::Line | ShapeKind::Arrow => {
+                    Self::distance_to_segment((local_x, local_y), start, end) <= tolerance
+                }
+                ShapeKind::Ink => self
+                    .shape_screen_points(shape, page_width_screen, page_height_screen)
+                    .windows(2)
+                    .any(|segment| {
+                        Self::distance_to_segment((local_x, local_y), segment[0], segment[1])
+                            <= tolerance
+                    }),
+            };
+            hit.then_some((shape.id, ShapeDragTarget::Body))
+        })
+    }
+
+    fn distance_to_segment(point: (f32, f32), start: (f32, f32), end: (f32, f32)) -> f32 {
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let length_sq = dx * dx + dy * dy;
+        let t = if length_sq <= f32::EPSILON {
+            0.0
+        } else {
+            (((point.0 - start.0) * dx + (point.1 - start.1) * dy) / length_sq).clamp(0.0, 1.0)
+        };
+        (point.0 - (start.0 + t * dx)).hypot(point.1 - (start.1 + t * dy))
+    }
+
+    /// Starts drawing, moving or resizing a shape. Returns `false` when the click should fall
+    /// through to text selection.
+    pub(super) fn handle_shape_mouse_down(
+        &mut self,
+        page_index: usize,
+        local_x: f32,
+        local_y: f32,
+        page_width_screen: f32,
+        page_height_screen: f32,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(anchor) = self.page_local_screen_to_note_anchor(
+            page_index,
+            local_x,
+            local_y,
+            page_width_screen,
+            page_height_screen,
+        ) else {
+            return false;
+        };
+        let pointer = (anchor.x_ratio, anchor.y_ratio);
+
+        if let Some(mode) = self.eraser {
+            let Some(path) = self.active_tab_path().cloned() else {
+                return false;
+            };
+            self.begin_shape_edit(&path);
+            self.erasing_page = Some(page_index);
+            self.erase_ink_at(
+                page_index,
+                local_x,
+                local_y,
+                page_width_screen,
+                page_height_screen,
+                mode,
+            );
+            cx.notify();
+            return true;
+        }
+
+        if let Some(kind) = self.shape_tool {
+            let Some(path) = self.active_tab_path().cloned() else {
+                return false;
+            };
+            let now = Self::now_unix_secs();
+            let shape_id = self.next_shape_id();
+            self.begin_shape_edit(&path);
+            self.shapes.push(ShapeEntry {
+                id: shape_id,
+                path,
+                page_index,
+                kind,
+                start_x_ratio: pointer.0,
+                start_y_ratio: pointer.1,
+                end_x_ratio: pointer.0,
+                end_y_ratio: pointer.1,
+                points: if kind == ShapeKind::Ink {
+                    vec![[pointer.0, pointer.1]]
+                } else {
+                    Vec::new()
+                },
+                style: ShapeStyle {
+                    stroke: self.annotation_defaults.shape_color(kind),
+                    ..self.shape_style
+                },
+                author: self.annotation_defaults.author(),
+                created_at_unix_secs: now,
+                updated_at_unix_secs: now,
+            });
+            self.selected_shape_id = (kind != ShapeKind::Ink).then_some(shape_id);
+            self.shape_drag = Some(ShapeDrag {
+                shape_id,
+                page_index,
+                target: ShapeDragTarget::End,
+                pointer_origin: pointer,
+                original: [pointer.0, pointer.1, pointer.0, pointer.1],
+                creating: true,
+            });
+            cx.notify();
+            return true;
+        }
+
+        let Some((shape_id, target)) = self.hit_test_shape_on_page(
+            page_index,
+            local_x,
+            local_y,
+            page_width_screen,
+            page_height_screen,
+        ) else {
+            if self.selected_shape_id.take().is_some() {
+                cx.notify();
+            }
+            return false;
+        };
+        let Some((path, original)) = self
+            .shapes
+            .iter()
+            .find(|shape| shape.id == shape_id)
+            .map(|shape| (shape.path.clone(), Self::shape_endpoints(shape)))
+        else {
+            return false;
+        };
+        self.clear_text_selection(cx);
+        self.begin_shape_edit(&path);
+        self.selected_shape_id = Some(shape_id);
+        self.shape_drag = Some(ShapeDrag {
+            shape_id,
+            page_index,
+            target,
+            pointer_origin: pointer,
+            original,
+            creating: false,
+        });
+        cx.notify();
+        true
+    }
+
+    pub(super) fn handle_shape_mouse_move(
+        &mut self,
+        page_index: usize,
+        local_x: f32,
+        local_y: f32,
+        page_width_screen: f32,
+        page_height_screen: f32,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if let Some(erasing_page) = self.erasing_page {
+            if erasing_page == page_index
+                && let Some(mode) = self.eraser
+            {
+                self.erase_ink_at(
+                    page_index,
+                    local_x,
+                    local_y,
+                    page_width_screen,
+                    page_height_screen,
+                    mode,
+                );
+                cx.notify();
+            }
+            return true;
+        }
+        let Some(drag) = self.shape_drag else {
+            return false;
+        };
+        if drag.page_index != page_index {
+            return true;
+        }
+        let Some(anchor) = self.page_local_screen_to_note_anchor(
+            page_index,
+            local_x,
+            local_y,
+            page_width_screen,
+            page_height_screen,
+        ) else {
+            return true;
+        };
+
+        let Some(kind) = self
+            .shapes
+            .iter()
+            .find(|shape| shape.id == drag.shape_id)
+            .map(|shape| shape.kind)
+        else {
+            return true;
+        };
+        if kind == ShapeKind::Ink && drag.creating {
+            let last_point = self
+                .shapes
+                .iter()
+                .find(|shape| shape.id == drag.shape_id)
+                .and_then(|shape| shape.points.last().copied())
+                .and_then(|[x_ratio, y_ratio]| {
+                    self.note_anchor_to_page_local_screen(
+                        page_index,
+                        &MarkdownNoteAnchor {
+                            page_index,
+                            x_ratio,
+                            y_ratio,
+                        },
+                        page_width_screen,
+                        page_height_screen,
+                    )
+                });
+            let far_enough = last_point
+                .is_none_or(|(x, y)| (local_x - x).hypot(local_y - y) >= INK_MIN_POINT_DISTANCE);
+            if far_enough && let Some(shape) = self.shape_by_id_mut(drag.shape_id) {
+                shape.points.push([anchor.x_ratio, anchor.y_ratio]);
+                Self::fit_ink_bounds(shape);
+                cx.notify();
+            }
+            return true;
+        }
+
+        let [start_x, start_y, end_x, end_y] = drag.original;
+        let endpoints = match drag.target {
+            ShapeDragTarget::Start => [anchor.x_ratio, anchor.y_ratio, end_x, end_y],
+            ShapeDragTarget::End => [start_x, start_y, anchor.x_ratio, anchor.y_ratio],
+            ShapeDragTarget::Body => {
+                // Keep the whole shape on the page while it moves.
+                let dx = (anchor.x_ratio - drag.pointer_origin.0)
+                    .clamp(-start_x.min(end_x), 1.0 - start_x.max(end_x));
+                let dy = (anchor.y_ratio - drag.pointer_origin.1)
+                    .clamp(-start_y.min(end_y), 1.0 - start_y.max(end_y));
+                [start_x + dx, start_y + dy, end_x + dx, end_y + dy]
+            }
+        };
+        if let Some(shape) = self.shape_by_id_mut(drag.shape_id) {
+            Self::set_shape_endpoints(shape, endpoints);
+            cx.notify();
+        }
+        true
+    }
+
+    pub(super) fn handle_shape_mouse_up(&mut self, cx: &mut Context<Self>) -> bool {
+        if self.erasing_page.take().is_some() {
+            self.commit_shape_edit();
+            self.persist_shapes();
+            cx.notify();
+            return true;
+        }
+        let Some(drag) = self.shape_drag.take() else {
+            return false;
+        };
+        let page_size = self
+            .active_tab_pages()
+            .and_then(|pages| pages.get(drag.page_index))
+            .map(|page| (page.width_pt, page.height_pt))
+            .unwrap_or((1.0, 1.0));
+        let now = Self::now_unix_secs();
+        let mut discard = false;
+        if let Some(shape) = self.shape_by_id_mut(drag.shape_id) {
+            let extent = ((shape.end_x_ratio - shape.start_x_ratio) * page_size.0)
+                .hypot((shape.end_y_ratio - shape.start_y_ratio) * page_size.1);
+            let too_few_points = shape.kind == ShapeKind::Ink && shape.points.len() < 2;
+            discard = drag.creating && (extent < SHAPE_MIN_DRAG_DISTANCE || too_few_points);
+            shape.updated_at_unix_secs = now;
+        }
+        if discard {
+            self.shapes.retain(|shape| shape.id != drag.shape_id);
+            self.selected_shape_id = None;
+        }
+        if drag.creating && self.shape_tool != Some(ShapeKind::Ink) {
+            self.shape_tool = None;
+        }
+        self.commit_shape_edit();
+        self.persist_shapes();
+        cx.notify();
+        true
+    }
+
+    /// Removes ink under the pointer on the active tab's page: whole strokes, or just the
+    /// samples inside the eraser, splitting a stroke where it was cut.
+    fn erase_ink_at(
+        &mut self,
+        page_index: usize,
+        local_x: f32,
+        local_y: f32,
+        page_width_screen: f32,
+        page_height_screen: f32,
+        mode: EraserMode,
+    ) {
+        let Some(path) = self.active_tab_path().cloned() else {
+            return;
+        };
+        let mut next_id = self.next_shape_id();
+        let shapes = std::mem::take(&mut self.shapes);
+        let mut kept = Vec::with_capacity(shapes.len());
+        for shape in shapes {
+            if shape.kind != ShapeKind::Ink || shape.path != path || shape.page_index != page_index
+            {
+                kept.push(shape);
+                continue;
+            }
+            let screen_points =
+                self.shape_screen_points(&shape, page_width_screen, page_height_screen);
+            let erased = screen_points
+                .iter()
+                .map(|&(x, y)| (local_x - x).hypot(local_y - y) <= ERASER_RADIUS)
+                .collect::<Vec<_>>();
+            match mode {
+                EraserMode::WholeStroke => {
+                    let touched = screen_points.windows(2).any(|segment| {
+                        Self::distance_to_segment((local_x, local_y), segment[0], segment[1])
+                            <= ERASER_RADIUS
+                    });
+                    if !touched && !erased.contains(&true) {
+                        kept.push(shape);
+                    }
+                }
+                EraserMode::Partial => {
+                    if !erased.contains(&true) {
+                        kept.push(shape);
+                        continue;
+                    }
+                    let mut keeps_id = true;
+                    let runs = shape.points.iter().zip(&erased).collect::<Vec<_>>();
+                    for run in runs.split(|(_, erased)| **erased) {
+                        if run.len() < 2 {
+                            continue;
+                        }
+                        let mut piece = shape.clone();
+                        piece.points = run.iter().map(|(point, _)| **point).collect();
+                        if !keeps_id {
+                            piece.id = next_id;
+                            next_id = next_id.saturating_add(1);
+                        }
+                        keeps_id = false;
+                        Self::fit_ink_bounds(&mut piece);
+                        kept.push(piece);
+                    }
+                }
+            }
+        }
+        self.shapes = kept;
+    }
+
+    pub(super) fn open_shape_context_menu(
+        &mut self,
+        shape_id: u64,
+        position: Point<Pixels>,
+        cx: &mut Context<Self>,
+    ) {
+        self.clear_text_selection_hover_menu_state();
+        self.selected_shape_id = Some(shape_id);
+        self.context_menu_open = true;
+        self.context_menu_position = Some(position);
+        self.context_menu_tab_id = None;
+        self.context_menu_note_anchor = None;
+        self.context_menu_note_id = None;
+        self.context_menu_text_markup_id = None;
+        self.context_menu_stamp_id = None;
+        self.context_menu_shape_id = Some(shape_id);
+        cx.notify();
+    }
+
+    pub(super) fn shape_tool_label(i18n: I18n, kind: ShapeKind) -> &'static str {
+        match kind {
+            ShapeKind::Rectangle => i18n.command_panel_draw_rectangle,
+            ShapeKind::Ellipse => i18n.command_panel_draw_ellipse,
+            ShapeKind::Line => i18n.command_panel_draw_line,
+            ShapeKind::Arrow => i18n.command_panel_draw_arrow,
+            ShapeKind::Ink => i18n.command_panel_draw_ink,
+        }
+    }
+
+    pub(super) fn eraser_label(i18n: I18n, mode: EraserMode) -> &'static str {
+        match mode {
+            EraserMode::Partial => i18n.command_panel_eraser,
+            EraserMode::WholeStroke => i18n.command_panel_stroke_eraser,
+        }
+    }
+
+    pub(super) fn shape_color_rgb(color: ShapeColor) -> Rgba {
+        match color {
+            ShapeColor::Red => rgb(0xDC2626),
+            ShapeColor::Blue => rgb(0x2563EB),
+            ShapeColor::Green => rgb(0x16A34A),
+            ShapeColor::Black => rgb(0x111827),
+        }
+    }
+
+    pub(super) fn render_shape_overlay(
+        &self,
+        page_index: usize,
+        page_width_screen: f32,
+        page_height_screen: f32,
+        cx: &App,
+    ) -> Option<AnyElement> {
+        let scale = self.shape_stroke_scale(page_index, page_width_screen)?;
+        let overlays = self
+            .active_tab_shapes_for_page(page_index)
+            .iter()
+            .filter_map(|shape| {
+                let (start, end) =
+                    self.shape_screen_endpoints(shape, page_width_screen, page_height_screen)?;
+                Some(ShapeOverlay {
+                    kind: shape.kind,
+                    start,
+                    end,
+                    points: self.shape_screen_points(shape, page_width_screen, page_height_screen),
+                    color: Self::shape_color_rgb(shape.style.stroke).into(),
+                    stroke_width: (shape.style.stroke_width_pt * scale).max(1.0),
+                    filled: shape.style.filled && shape.kind.is_closed(),
+                    selected: self.selected_shape_id == Some(shape.id),
+                })
+            })
+            .collect::<Vec<_>>();
+        if overlays.is_empty() {
+            return None;
+        }
+        let handle_color = cx.theme().primary;
+
+        Some(
+            canvas(
+                |_, _, _| {},
+                move |bounds, _, window, _| {
+                    for overlay in &overlays {
+                        Self::paint_shape(bounds.origin, overlay, handle_color, window);
+                    }
+                },
+            )
+            .absolute()
+            .top_0()
+            .left_0()
+            .size_full()
+            .into_any_element(),
+        )
+    }
+
+    fn paint_shape(
+        origin: Point<Pixels>,
+        overlay: &ShapeOverlay,
+        handle_color: Hsla,
+        window: &mut Window,
+    ) {
+        let start = origin + point(px(overlay.start.0), px(overlay.start.1));
+        let end = origin + point(px(overlay.end.0), px(overlay.end.1));
+
+        if overlay.filled {
+            let mut fill_path = PathBuilder::fill();
+            Self::trace_shape(&mut fill_path, origin, overlay, start, end);
+            if let Ok(path) = fill_path.build() {
+                window.paint_path(path, overlay.color.opacity(SHAPE_FILL_OPACITY));
+            }
+        }
+        let mut stroke_path = PathBuilder::stroke(px(overlay.stroke_width));
+        Self::trace_shape(&mut stroke_path, origin, overlay, start, end);
+        if let Ok(path) = stroke_path.build() {
+            window.paint_path(path, overlay.color);
+        }
+
+        if overlay.selected && overlay.kind == ShapeKind::Ink {
+            let mut outline = PathBuilder::stroke(px(1.)).dash_array(&[px(4.), px(3.)]);
+            outline.add_polygon(
+                &[start, point(end.x, start.y), end, point(start.x, end.y)],
+                true,
+            );
+            if let Ok(path) = outline.build() {
+                window.paint_path(path, handle_color);
+            }
+        } else if overlay.selected {
+            for handle in [start, end] {
+                let half = px(SHAPE_HANDLE_SIZE / 2.0);
+                window.paint_quad(fill(
+                    Bounds::new(
+                        point(handle.x - half, handle.y - half),
+                        size(px(SHAPE_HANDLE_SIZE), px(SHAPE_HANDLE_SIZE)),
+                    ),
+                    handle_color,
+                ));
+            }
+        }
+    }
+
+    fn trace_shape(
+        builder: &mut PathBuilder,
+        origin: Point<Pixels>,
+        overlay: &ShapeOverlay,
+        start: Point<Pixels>,
+        end: Point<Pixels>,
+    ) {
+        match overlay.kind {
+            ShapeKind::Rectangle => {
+                builder.add_polygon(
+                    &[start, point(end.x, start.y), end, point(start.x, end.y)],
+                    true,
+                );
+            }
+            ShapeKind::Ellipse => {
+                let radii = point((end.x - start.x).abs() / 2.0, (end.y - start.y).abs() / 2.0);
+                if radii.x < px(0.5) || radii.y < px(0.5) {
+                    return;
+                }
+                let center_y = (start.y + end.y) / 2.0;
+                let left = point(start.x.min(end.x), center_y);
+                let right = point(start.x.max(end.x), center_y);
+                builder.move_to(left);
+                builder.arc_to(radii, px(0.), false, true, right);
+                builder.arc_to(radii, px(0.), false, true, left);
+                builder.close();
+            }
+            ShapeKind::Line => {
+                builder.move_to(start);
+                builder.line_to(end);
+            }
+            ShapeKind::Ink => {
+                let mut points = overlay
+                    .points
+                    .iter()
+                    .map(|&(x, y)| origin + point(px(x), px(y)));
+                let Some(first) = points.next() else {
+                    return;
+                };
+                builder.move_to(first);
+                for next in points {
+                    builder.line_to(next);
+                }
+            }
+            ShapeKind::Arrow => {
+                builder.move_to(start);
+                builder.line_to(end);
+                let angle = f32::from(end.y - start.y).atan2(f32::from(end.x - start.x));
+                let head = (overlay.stroke_width * 4.0).max(10.0);
+                for side in [-SHAPE_ARROW_HEAD_ANGLE, SHAPE_ARROW_HEAD_ANGLE] {
+                    builder.move_to(end);
+                    builder.line_to(point(
+                        end.x - px(head * (angle + side).cos()),
+                        end.y - px(head * (angle + side).sin()),
+                    ));
+                }
+            }
+        }
+    }
+
+    pub(super) fn render_shape_context_menu(
+        &self,
+        shape_id: u64,
+        x: f32,
+        y: f32,
+        cx: &mut Context<Self>,
+    ) -> Option<AnyElement> {
+        let i18n = self.i18n();
+        let shape = self.shapes.iter().find(|shape| shape.id == shape_id)?;
+        let style = shape.style;
+        let closed = shape.kind.is_closed();
+        let separator = || div().h(px(16.)).w_px().bg(cx.theme().border);
+
+        Some(
+            div()
+                .id(("context-menu-shape", shape_id))
+                .absolute()
+                .left(px(x))
+                .top(px(y))
+                .h(px(38.))
+                .rounded_lg()
+                .border_1()
+                .border_color(cx.theme().border.opacity(0.88))
+                .bg(cx.theme().secondary.opacity(0.94))
+                .shadow_md()
+                .px_1()
+                .flex()
+                .items_center()
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|_, _: &MouseDownEvent, _, cx| {
+                        cx.stop_propagation();
+                    }),
+                )
+                .child(
+                    div()
+                        .w_full()
+                        .h_full()
+                        .h_flex()
+                        .items_center()
+                        .gap_1()
+                        .children(ShapeColor::ALL.into_iter().enumerate().map(|(ix, color)| {
+                            div()
+                                .id(("shape-color", ix))
+                                .size(px(16.))
+                                .rounded_full()
+                                .cursor_pointer()
+                                .bg(Self::shape_color_rgb(color))
+                                .border_2()
+                                .border_color(if style.stroke == color {
+                                    cx.theme().foreground
+                                } else {
+                                    cx.theme().secondary
+                                })
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    this.update_shape_style(
+                                        shape_id,
+                                        |style| style.stroke = color,
+                                        cx,
+                                    );
+                                }))
+                        }))
+                        .child(separator())
+                        .children(SHAPE_STROKE_WIDTH_OPTIONS.into_iter().enumerate().map(
+                            |(ix, width)| {
+                                div()
+                                    .id(("shape-width", ix))
+                                    .px_1()
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .hover(|this| this.bg(cx.theme().secondary))
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.update_shape_style(
+                                            shape_id,
+                                            |style| style.stroke_width_pt = width,
+                                            cx,
+                                        );
+                                    }))
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(if style.stroke_width_pt == width {
+                                                cx.theme().foreground
+                                            } else {
+                                                cx.theme().muted_foreground
+                                            })
+                                            .child(format!("{width} pt")),
+                                    )
+                            },
+                        ))
+                        .when(closed, |this| {
+                            this.child(separator()).child(
+                                div()
+                                    .id(("shape-fill", shape_id))
+                                    .px_1()
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .hover(|this| this.bg(cx.theme().secondary))
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.update_shape_style(
+                                            shape_id,
+                                            |style| style.filled = !style.filled,
+                                            cx,
+                                        );
+                                    }))
+                                    .child(
+                                        div()
+                                            .text_xs()
+                                            .text_color(if style.filled {
+                                                cx.theme().foreground
+                                            } else {
+                                                cx.theme().muted_foreground
+                                            })
+                                            .child(i18n.shape_fill_button),
+                                    ),
+                            )
+                        })
+                        .child(separator())
+                        .child(
+                            div()
+                                .id(("shape-delete", shape_id))
+                                .px_1()
+                                .rounded_md()
+                                .cursor_pointer()
+                                .hover(|this| this.bg(cx.theme().secondary))
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    this.delete_shape_by_id(shape_id, cx);
+                                    this.close_context_menu(cx);
+                                }))
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().foreground)
+                                        .child(i18n.delete_shape_button),
+                                ),
+                        ),
+                )
+                .into_any_element(),
+        )
+    }
+}
@@ -0,0 +1,137 @@
+//! Explorer's "Open with kPDF" context-menu verb for PDF files. The verb is registered for
+//! the current user under `HKCU\Software\Classes`, so it needs no elevation and does not
+//! change the default PDF handler.
+
+use anyhow::Result;
+
+/// Whether the verb is registered (always `false` outside Windows).
+pub(super) fn is_registered() -> bool {
+    imp::is_registered()
+}
+
+/// Adds the verb, labelled `label`, pointing at the running executable; or removes it.
+pub(super) fn set_registered(enabled: bool, label: &str) -> Result<()> {
+    crate::debug_log!("[shell] open-with verb registered: {}", enabled);
+    if enabled {
+        imp::register(label)
+    } else {
+        imp::unregister()
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod imp {
+    use anyhow::{Context as _, Result};
+    use windows::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+    use windows::Win32::System::Registry::{
+        HKEY, HKEY_CURRENT_USER, KEY_READ, KEY_WRITE, REG_OPTION_NON_VOLATILE, REG_SZ, RegCloseKey,
+        RegCreateKeyExW, RegDeleteTreeW, RegOpenKeyExW, RegSetValueExW,
+    };
+    use windows::Win32::UI::Shell::{SHCNE_ASSOCCHANGED, SHCNF_IDLIST, SHChangeNotify};
+    use windows::core::{HSTRING, PCWSTR};
+
+    const VERB_KEY: &str = r"Software\Classes\SystemFileAssociations\.pdf\shell\kpdf.open";
+
+    pub(super) fn is_registered() -> bool {
+        let mut key = HKEY::default();
+        let command_key = HSTRING::from(format!(r"{VERB_KEY}\command"));
+        let opened =
+            unsafe { RegOpenKeyExW(HKEY_CURRENT_USER, &command_key, None, KEY_READ, &mut key) };
+        if opened.is_err() {
+            return false;
+        }
+        unsafe {
+            let _ = RegCloseKey(key);
+        }
+        true
+    }
+
+    pub(super) fn register(label: &str) -> Result<()> {
+        let exe = std::env::current_exe().context("locate kpdf executable")?;
+        let exe = exe.to_string_lossy();
+        set_string(VERB_KEY, None, label)?;
+        set_string(VERB_KEY, Some("Icon"), &format!("\"{exe}\",0"))?;
+        set_string(
+            &format!(r"{VERB_KEY}\command"),
+            None,
+            &format!("\"{exe}\" \"%1\""),
+        )?;
+        notify_shell();
+        Ok(())
+    }
+
+    pub(super) fn unregister() -> Result<()> {
+        let deleted = unsafe { RegDeleteTreeW(HKEY_CURRENT_USER, &HSTRING::from(VERB_KEY)) };
+        if deleted != ERROR_FILE_NOT_FOUND {
+            deleted
+                .ok()
+                .with_context(|| format!(r"delete HKCU\{VERB_KEY}"))?;
+        }
+        notify_shell();
+        Ok(())
+    }
+
+    fn set_string(subkey: &str, name: Option<&str>, value: &str) -> Result<()> {
+        let mut key = HKEY::default();
+        unsafe {
+            RegCreateKeyExW(
+                HKEY_CURRENT_USER,
+                &HSTRING::from(subkey),
+                None,
+                PCWSTR::null(),
+                REG_OPTION_NON_VOLATILE,
+                KEY_WRITE,
+                None,
+                &mut key,
+                None,
+            )
+        }
+        .ok()
+        .with_context(|| format!(r"create HKCU\{subkey}"))?;
+
+        let data = value
+            .encode_utf16()
+            .chain(Some(0))
+            .flat_map(u16::to_le_bytes)
+            .collect::<Vec<_>>();
+        let name = name.map(HSTRING::from);
+        let written = unsafe {
+            RegSetValueExW(
+                key,
+                name.as_ref()
+                    .map_or(PCWSTR::null(), |name| PCWSTR(name.as_ptr())),
+                None,
+                REG_SZ,
+                Some(&data),
+            )
+        };
+        unsafe {
+            let _ = RegCloseKey(key);
+        }
+        written
+            .ok()
+            .with_context(|| format!(r"write HKCU\{subkey}"))
+    }
+
+    /// Lets Explorer pick up the change without a restart.
+    fn notify_shell() {
+        unsafe { SHChangeNotify(SHCNE_ASSOCCHANGED, SHCNF_IDLIST, None, None) };
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+mod imp {
+    use anyhow::{Result, bail};
+
+    pub(super) fn is_registered() -> bool {
+        false
+    }
+
+    pub(super) fn register(_label: &str) -> Result<()> {
+        bail!("Explorer integration is only available on Windows")
+    }
+
+    pub(super) fn unregister() -> Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,1064 @@
+use super::utils::{OutlineEntry, load_document_outline, search_document_text};
+use super::{
+    PdfViewer, SIDEBAR_RESIZE_HANDLE_WIDTH, SIDEBAR_TAB_BAR_HEIGHT, SIDEBAR_WIDTH, SidebarTab,
+    TextMarkupKind, VIEW_PREFERENCES_KEY_SIDEBAR_TAB_PREFIX,
+};
+use crate::icons;
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::checkbox::Checkbox;
+use gpui_component::input::Input;
+use gpui_component::scroll::{ScrollableElement, Scrollbar, ScrollbarShow};
+use gpui_component::{button::*, *};
+use std::collections::HashSet;
+use std::path::Path;
+use std::rc::Rc;
+
+const SIDEBAR_OUTLINE_INDENT: f32 = 12.0;
+const SIDEBAR_OUTLINE_CHEVRON_WIDTH: f32 = 14.0;
+
+fn outline_has_children(outline: &[OutlineEntry], ix: usize) -> bool {
+    outline
+        .get(ix + 1)
+        .is_some_and(|next| next.depth > outline[ix].depth)
+}
+
+fn outline_parent(outline: &[OutlineEntry], ix: usize) -> Option<usize> {
+    let depth = outline[ix].depth;
+    (0..ix).rev().find(|&parent| outline[parent].depth < depth)
+}
+
+/// Indices of the outline rows left to show. A filter keeps every matching heading plus its
+/// ancestors and ignores collapsed state; without one, descendants of collapsed entries are hidden.
+fn visible_outline_rows(
+    outline: &[OutlineEntry],
+    collapsed: &HashSet<usize>,
+    filter: &str,
+) -> Vec<usize> {
+    let filter = filter.trim().to_lowercase();
+    if filter.is_empty() {
+        let mut rows = Vec::with_capacity(outline.len());
+        let mut collapsed_depth = None;
+        for (ix, entry) in outline.iter().enumerate() {
+            if let Some(depth) = collapsed_depth {
+                if entry.depth > depth {
+                    continue;
+                }
+                collapsed_depth = None;
+            }
+            rows.push(ix);
+            if collapsed.contains(&ix) {
+                collapsed_depth = Some(entry.depth);
+            }
+        }
+        return rows;
+    }
+
+    let mut keep = vec![false; outline.len()];
+    let mut ancestors: Vec<usize> = Vec::new();
+    for (ix, entry) in outline.iter().enumerate() {
+        while ancestors
+            .last()
+            .is_some_and(|&parent| outline[parent].depth >= entry.depth)
+        {
+            ancestors.pop();
+        }
+        if entry.title.to_lowercase().contains(&filter) {
+            keep[ix] = true;
+            for &parent in &ancestors {
+                keep[parent] = true;
+            }
+        }
+        ancestors.push(ix);
+    }
+    keep.into_iter()
+        .enumerate()
+        .filter_map(|(ix, keep)| keep.then_some(ix))
+        .collect()
+}
+
+impl PdfViewer {
+    pub(super) fn active_sidebar_tab(&self) -> SidebarTab {
+        self.active_tab()
+            .map(|tab| tab.sidebar_tab)
+            .unwrap_or_default()
+    }
+
+    pub(super) fn set_sidebar_tab(&mut self, sidebar_tab: SidebarTab, cx: &mut Context<Self>) {
+        let Some(tab) = self.active_tab_mut() else {
+            return;
+        };
+        if tab.sidebar_tab == sidebar_tab {
+            return;
+        }
+        tab.sidebar_tab = sidebar_tab;
+        let tab_id = tab.id;
+        let path = tab.path.clone();
+
+        if let Some(path) = path.as_ref() {
+            self.persist_sidebar_tab(path, sidebar_tab);
+        }
+        if sidebar_tab == SidebarTab::Outline {
+            self.request_outline_load(tab_id, cx);
+        }
+        cx.notify();
+    }
+
+    /// Shows the sidebar on its search tab and focuses the query input.
+    pub(super) fn open_sidebar_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.set_thumbnail_panel_visible(true, cx);
+        self.set_sidebar_tab(SidebarTab::Search, cx);
+        self.sidebar_search_input_state
+            .update(cx, |input, cx| input.focus(window, cx));
+    }
+
+    fn sidebar_tab_key(path: &Path) -> Vec<u8> {
+        let mut key = VIEW_PREFERENCES_KEY_SIDEBAR_TAB_PREFIX.as_bytes().to_vec();
+        key.extend(Self::file_position_key(path));
+        key
+    }
+
+    pub(super) fn load_saved_sidebar_tab(&self, path: &Path) -> Option<SidebarTab> {
+        let store = self.view_preferences_store.as_ref()?;
+        let value = store.get(Self::sidebar_tab_key(path)).ok().flatten()?;
+        SidebarTab::from_stored(value.as_ref())
+    }
+
+    fn persist_sidebar_tab(&self, path: &Path, sidebar_tab: SidebarTab) {
+        let Some(store) = self.view_preferences_store.as_ref() else {
+            return;
+        };
+
+        if store
+            .insert(Self::sidebar_tab_key(path), sidebar_tab.as_stored())
+            .is_err()
+        {
+            return;
+        }
+
+        let _ = store.flush();
+    }
+
+    pub(super) fn request_outline_load(&mut self, tab_id: usize, cx: &mut Context<Self>) {
+        let language = self.language;
+        let Some(tab) = self.tab_bar.get_tab_mut(tab_id) else {
+            return;
+        };
+        if tab.outline_loading || tab.outline_loaded || !tab.summary_loaded {
+            return;
+        }
+        let Some(path) = tab.path.clone() else {
+            return;
+        };
+        tab.outline_loading = true;
+
+        cx.spawn(async move |view, cx| {
+            let loaded = cx
+                .background_executor()
+                .spawn({
+                    let path = path.clone();
+                    async move { load_document_outline(&path, language) }
+                })
+                .await;
+
+            let _ = view.update(cx, |this, cx| {
+                let Some(tab) = this.tab_bar.get_tab_mut(tab_id) else {
+                    return;
+                };
+                if tab.path.as_ref() != Some(&path) || !tab.outline_loading {
+                    return;
+                }
+                tab.outline_loading = false;
+                tab.outline_loaded = true;
+                match loaded {
+                    Ok(outline) => tab.outline = outline,
+                    Err(err) => {
+                        crate::debug_log!(
+                            @warn,
+                            "[outline] load failed: {} | {}",
+                            path.display(),
+                            err
+                        );
+                        tab.outline.clear();
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    pub(super) fn start_sidebar_search(&mut self, query: String, cx: &mut Context<Self>) {
+        let language = self.language;
+        let Some(tab) = self.active_tab_mut() else {
+            return;
+        };
+        let Some(path) = tab.path.clone() else {
+            return;
+        };
+        let tab_id = tab.id;
+        tab.search_epoch = tab.search_epoch.wrapping_add(1);
+        tab.search_query = query.trim().to_string();
+        tab.search_results.clear();
+        tab.search_cursor = None;
+        tab.search_wrapped = false;
+        tab.search_highlights_visible = false;
+        tab.search_match_rects.borrow_mut().clear();
+        tab.sidebar_tab = SidebarTab::Search;
+        if tab.search_query.is_empty() {
+            tab.search_loading = false;
+            cx.notify();
+            return;
+        }
+        tab.search_loading = true;
+        let epoch = tab.search_epoch;
+        let query = tab.search_query.clone();
+        self.persist_sidebar_tab(&path, SidebarTab::Search);
+        cx.notify();
+
+        cx.spawn(async move |view, cx| {
+            let results = cx
+                .background_executor()
+                .spawn({
+                    let path = path.clone();
+                    async move { search_document_text(&path, &query, language) }
+                })
+                .await;
+
+            let _ = view.update(cx, |this, cx| {
+                let Some(tab) = this.tab_bar.get_tab_mut(tab_id) else {
+                    return;
+                };
+                if tab.search_epoch != epoch || tab.path.as_ref() != Some(&path) {
+                    return;
+                }
+                tab.search_loading = false;
+                tab.search_highlights_visible = true;
+                match results {
+                    Ok(results) => tab.search_results = results,
+                    Err(err) => {
+                        crate::debug_log!(@warn, "[search] failed: {} | {}", path.display(), err);
+                        tab.search_results.clear();
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Moves to the next or previous search result, starting from the current page when no
+    /// result has been visited yet, and selects the match on its page.
+    pub(super) fn step_search_result(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let Some(tab) = self.active_tab_mut() else {
+            return;
+        };
+        let count = tab.search_results.len();
+        if count == 0 {
+            return;
+        }
+        let (target, wrapped) = match tab.search_cursor {
+            Some(cursor) if forward => ((cursor + 1) % count, cursor + 1 >= count),
+            Some(cursor) => (cursor.checked_sub(1).unwrap_or(count - 1), cursor == 0),
+            None if forward => tab
+                .search_results
+                .iter()
+                .position(|hit| hit.page_index >= tab.active_page)
+                .map_or((0, true), |ix| (ix, false)),
+            None => tab
+                .search_results
+                .iter()
+                .rposition(|hit| hit.page_index <= tab.active_page)
+                .map_or((count - 1, true), |ix| (ix, false)),
+        };
+        tab.search_cursor = Some(target);
+        tab.search_wrapped = wrapped;
+        let hit = tab.search_results[target].clone();
+
+        {
+            let mut manager = tab.text_selection_manager.borrow_mut();
+            manager.start_selection(hit.page_index, hit.char_start);
+            manager.update_selection(hit.page_index, hit.char_start + hit.char_len);
+            manager.end_selection();
+        }
+        self.select_page(hit.page_index, cx);
+        cx.notify();
+    }
+
+    pub(super) fn set_search_highlight_all(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        if self.search_highlight_all == enabled {
+            return;
+        }
+        self.search_highlight_all = enabled;
+        self.persist_search_highlight_all();
+        if enabled && let Some(tab) = self.active_tab_mut() {
+            tab.search_highlights_visible = true;
+        }
+        cx.notify();
+    }
+
+    pub(super) fn search_highlights_shown(&self) -> bool {
+        self.search_highlight_all
+            && self
+                .active_tab()
+                .is_some_and(|tab| tab.search_highlights_visible && !tab.search_results.is_empty())
+    }
+
+    pub(super) fn hide_search_highlights(&mut self, cx: &mut Context<Self>) {
+        if let Some(tab) = self.active_tab_mut() {
+            tab.search_highlights_visible = false;
+            cx.notify();
+        }
+    }
+
+    /// Tells which end the last F3 step wrapped around to, while that is still the result shown.
+    pub(super) fn search_wrapped_label(&self) -> Option<&'static str> {
+        let i18n = self.i18n();
+        let tab = self.active_tab().filter(|tab| tab.search_wrapped)?;
+        Some(if tab.search_cursor == Some(0) {
+            i18n.search_wrapped_to_first
+        } else {
+            i18n.search_wrapped_to_last
+        })
+    }
+
+    pub(super) fn sidebar_outline_filter_focused(&self, window: &Window, cx: &App) -> bool {
+        self.sidebar_outline_filter_input_state
+            .read(cx)
+            .focus_handle(cx)
+            .is_focused(window)
+    }
+
+    fn active_outline_rows(&self, cx: &App) -> Vec<usize> {
+        let Some(tab) = self.active_tab() else {
+            return Vec::new();
+        };
+        let filter = self.sidebar_outline_filter_input_state.read(cx).value();
+        visible_outline_rows(&tab.outline, &tab.outline_collapsed, &filter)
+    }
+
+    fn select_outline_row(&mut self, ix: usize, rows: &[usize], cx: &mut Context<Self>) {
+        let Some(tab) = self.active_tab_mut() else {
+            return;
+        };
+        tab.outline_selected = Some(ix);
+        if let Some(position) = rows.iter().position(|row| *row == ix) {
+            tab.outline_scroll.scroll_to_item(position);
+        }
+        cx.notify();
+    }
+
+    /// Moves the keyboard selection through the visible outline rows, stopping at either end.
+    pub(super) fn move_outline_selection(&mut self, delta: isize, cx: &mut Context<Self>) {
+        let rows = self.active_outline_rows(cx);
+        let Some(last) = rows.len().checked_sub(1) else {
+            return;
+        };
+        let current = self
+            .active_tab()
+            .and_then(|tab| tab.outline_selected)
+            .and_then(|selected| rows.iter().position(|row| *row == selected));
+        let next = match current {
+            Some(position) => position.saturating_add_signed(delta).min(last),
+            None if delta < 0 => last,
+            None => 0,
+        };
+        self.select_outline_row(rows[next], &rows, cx);
+    }
+
+    fn set_outline_collapsed(&mut self, ix: usize, collapsed: bool, cx: &mut Context<Self>) {
+        let Some(tab) = self.active_tab_mut() else {
+            return;
+        };
+        let changed = if collapsed {
+            tab.outline_collapsed.insert(ix)
+        } else {
+            tab.outline_collapsed.remove(&ix)
+        };
+        if !changed {
+            return;
+        }
+        // Keep the selection on screen when it disappears into the collapsed entry.
+        if collapsed
+            && tab.outline_selected.is_some_and(|selected| {
+                selected > ix
+                    && tab.outline[ix + 1..=selected]
+                        .iter()
+                        .all(|entry| entry.depth > tab.outline[ix].depth)
+            })
+        {
+            tab.outline_selected = Some(ix);
+        }
+        cx.notify();
+    }
+
+    /// Right arrow: expands the selected entry, or steps into its first child when already open.
+    pub(super) fn expand_outline_selection(&mut self, cx: &mut Context<Self>) {
+        let rows = self.active_outline_rows(cx);
+        let Some(tab) = self.active_tab() else {
+            return;
+        };
+        let Some(selected) = tab.outline_selected else {
+            self.move_outline_selection(1, cx);
+            return;
+        };
+        if !outline_has_children(&tab.outline, selected) {
+            return;
+        }
+        if tab.outline_collapsed.contains(&selected) {
+            self.set_outline_collapsed(selected, false, cx);
+        } else if rows.contains(&(selected + 1)) {
+            self.select_outline_row(selected + 1, &rows, cx);
+        }
+    }
+
+    /// Left arrow: collapses the selected entry, or steps out to its parent when already closed.
+    pub(super) fn collapse_outline_selection(&mut self, cx: &mut Context<Self>) {
+        let rows = self.active_outline_rows(cx);
+        let filtering = !self
+            .sidebar_outline_filter_input_state
+            .read(cx)
+            .value()
+            .trim()
+            .is_empty();
+        let Some(tab) = self.active_tab() else {
+            return;
+        };
+        let Some(selected) = tab.outline_selected else {
+            return;
+        };
+        if !filtering
+            && outline_has_children(&tab.outline, selected)
+            && !tab.outline_collapsed.contains(&selected)
+        {
+            self.set_outline_collapsed(selected, true, cx);
+        } else if let Some(parent) = outline_parent(&tab.outline, selected) {
+            self.select_outline_row(parent, &rows, cx);
+        }
+    }
+
+    pub(super) fn activate_outline_selection(&mut self, cx: &mut Context<Self>) {
+        let page_index = self.active_tab().and_then(|tab| {
+            tab.outline_selected
+                .and_then(|selected| tab.outline.get(selected))
+                .and_then(|entry| entry.page_index)
+        });
+        if let Some(page_index) = page_index {
+            self.select_page(page_index, cx);
+        }
+    }
+
+    /// Handles a key press while the outline list has focus. Returns `false` for keys it ignores.
+    pub(super) fn handle_outline_key(
+        &mut self,
+        key: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        match key {
+            "down" => self.move_outline_selection(1, cx),
+            "up" => self.move_outline_selection(-1, cx),
+            "right" => self.expand_outline_selection(cx),
+            "left" => self.collapse_outline_selection(cx),
+            "enter" => self.activate_outline_selection(cx),
+            "escape" => window.focus(&self.focus_handle),
+            _ => return false,
+        }
+        true
+    }
+
+    pub(super) fn sidebar_search_input_focused(&self, window: &Window, cx: &App) -> bool {
+        self.sidebar_search_input_state
+            .read(cx)
+            .focus_handle(cx)
+            .is_focused(window)
+    }
+
+    fn sidebar_tab_icon(sidebar_tab: SidebarTab) -> icons::IconName {
+        match sidebar_tab {
+            SidebarTab::Thumbnails => icons::IconName::LayoutGrid,
+            SidebarTab::Outline => icons::IconName::ListTree,
+            SidebarTab::Bookmarks => icons::IconName::Bookmark,
+            SidebarTab::Annotations => icons::IconName::MessageSquareText,
+            SidebarTab::Search => icons::IconName::Search,
+        }
+    }
+
+    fn sidebar_tab_label(&self, sidebar_tab: SidebarTab) -> &'static str {
+        let i18n = self.i18n();
+        match sidebar_tab {
+            SidebarTab::Thumbnails => i18n.sidebar_tab_thumbnails,
+            SidebarTab::Outline => i18n.sidebar_tab_outline,
+            SidebarTab::Bookmarks => i18n.sidebar_tab_bookmarks,
+            SidebarTab::Annotations => i18n.sidebar_tab_annotations,
+            SidebarTab::Search => i18n.sidebar_tab_search,
+        }
+    }
+
+    pub(super) fn render_sidebar(
+        &self,
+        page_count: usize,
+        thumbnail_sizes: Rc<Vec<gpui::Size<Pixels>>>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let active_sidebar_tab = self.active_sidebar_tab();
+        let sidebar_resizing = self.sidebar_resize_drag.is_some();
+        let rtl = self.language.is_rtl();
+
+        let body = match active_sidebar_tab {
+            SidebarTab::Thumbnails => self
+                .render_thumbnail_panel(page_count, thumbnail_sizes, cx)
+                .into_any_element(),
+            SidebarTab::Outline => self.render_sidebar_outline(cx),
+            SidebarTab::Bookmarks => self.render_sidebar_bookmarks(cx),
+            SidebarTab::Annotations => self.render_sidebar_annotations(cx),
+            SidebarTab::Search => self.render_sidebar_search(cx),
+        };
+
+        div()
+            .h_full()
+            .w(px(self.sidebar_width))
+            .flex_none()
+            .relative()
+            .v_flex()
+            .when(rtl, |this| this.border_l_1())
+            .when(!rtl, |this| this.border_r_1())
+            .border_color(cx.theme().sidebar_border)
+            .bg(cx.theme().sidebar)
+            .overflow_hidden()
+            .child(
+                div()
+                    .id("sidebar-tab-bar")
+                    .h(px(SIDEBAR_TAB_BAR_HEIGHT))
+                    .w_full()
+                    .flex_none()
+                    .px_1()
+                    .h_flex()
+                    .items_center()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().sidebar_border)
+                    .children(SidebarTab::ALL.into_iter().enumerate().map(|(ix, sidebar_tab)| {
+                        let is_active = sidebar_tab == active_sidebar_tab;
+                        Button::new(("sidebar-tab", ix))
+                            .ghost()
+                            .xsmall()
+                            .tooltip(self.sidebar_tab_label(sidebar_tab))
+                            .icon(
+                                Icon::new(Self::sidebar_tab_icon(sidebar_tab))
+                                    .size_4()
+                                    .text_color(if is_active {
+                                        cx.theme().primary
+                                    } else {
+                                        cx.theme().muted_foreground
+                                    }),
+                            )
+                            .map(|this| {
+                                if is_active {
+                                    this.bg(cx.theme().primary.opacity(0.15))
+                                } else {
+                                    this
+                                }
+                            })
+                            .on_click(cx.listener(move |this, _, _, cx| {
+                                this.set_sidebar_tab(sidebar_tab, cx);
+                            }))
+                    })),
+            )
+            .child(div().flex_1().min_h(px(0.)).w_full().child(body))
+            .child(
+                div()
+                    .id("sidebar-resize-handle")
+                    .absolute()
+                    .top_0()
+                    .bottom_0()
+                    .when(rtl, |this| this.left_0())
+                    .when(!rtl, |this| this.right_0())
+                    .w(px(SIDEBAR_RESIZE_HANDLE_WIDTH))
+                    .cursor(CursorStyle::ResizeLeftRight)
+                    .when(sidebar_resizing, |this| this.bg(cx.theme().primary.opacity(0.4)))
+                    .hover(|this| this.bg(cx.theme().primary.opacity(0.25)))
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, event: &MouseDownEvent, _, cx| {
+                            this.start_sidebar_resize(event.position, cx);
+                            cx.stop_propagation();
+                        }),
+                    )
+                    .on_double_click(cx.listener(|this, _, _, cx| {
+                        this.set_sidebar_width(SIDEBAR_WIDTH, cx);
+                        this.persist_sidebar_width();
+                    })),
+            )
+    }
+
+    fn render_sidebar_placeholder(&self, text: impl Into<SharedString>, cx: &App) -> AnyElement {
+        div()
+            .size_full()
+            .p_3()
+            .flex()
+            .items_center()
+            .justify_center()
+            .text_xs()
+            .text_center()
+            .text_color(cx.theme().muted_foreground)
+            .child(text.into())
+            .into_any_element()
+    }
+
+    fn render_sidebar_row(
+        &self,
+        id: impl Into<ElementId>,
+        page_index: usize,
+        title: SharedString,
+        subtitle: Option<SharedString>,
+        indent: f32,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let is_active = page_index == self.active_tab_active_page();
+        div()
+            .id(id)
+            .w_full()
+            .pl(px(8. + indent))
+            .pr_2()
+            .py(self.ui_density.spacing(0.25))
+            .rounded_md()
+            .v_flex()
+            .gap_0p5()
+            .cursor_pointer()
+            .when(is_active, |this| this.bg(cx.theme().secondary.opacity(0.55)))
+            .hover(|this| this.bg(cx.theme().secondary.opacity(0.35)))
+            .child(
+                div()
+                    .w_full()
+                    .h_flex()
+                    .items_center()
+                    .justify_between()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex_1()
+                            .min_w(px(0.))
+                            .text_xs()
+                            .text_color(cx.theme().foreground)
+                            .truncate()
+                            .child(title),
+                    )
+                    .child(
+                        div()
+                            .flex_none()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("{}", page_index + 1)),
+                    ),
+            )
+            .when_some(subtitle, |this, subtitle| {
+                this.child(
+                    div()
+                        .w_full()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .line_clamp(2)
+                        .child(subtitle),
+                )
+            })
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.select_page(page_index, cx);
+            }))
+            .into_any_element()
+    }
+
+    fn render_sidebar_list(&self, id: &'static str, rows: Vec<AnyElement>) -> AnyElement {
+        div()
+            .id(id)
+            .size_full()
+            .p(self.ui_density.spacing(0.25))
+            .v_flex()
+            .gap_0p5()
+            .overflow_y_scrollbar()
+            .children(rows)
+            .into_any_element()
+    }
+
+    fn render_sidebar_outline(&self, cx: &mut Context<Self>) -> AnyElement {
+        let i18n = self.i18n();
+        let Some(tab) = self.active_tab() else {
+            return self.render_sidebar_placeholder(i18n.no_pages, cx);
+        };
+        if tab.outline_loading || (!tab.outline_loaded && tab.summary_loading) {
+            return self.render_sidebar_placeholder(i18n.sidebar_outline_loading, cx);
+        }
+        if tab.outline.is_empty() {
+            return self.render_sidebar_placeholder(i18n.sidebar_outline_empty, cx);
+        }
+
+        let filter = self
+            .sidebar_outline_filter_input_state
+            .read(cx)
+            .value()
+            .to_string();
+        let filtering = !filter.trim().is_empty();
+        let rows = visible_outline_rows(&tab.outline, &tab.outline_collapsed, &filter);
+        let body = if rows.is_empty() {
+            self.render_sidebar_placeholder(i18n.sidebar_outline_no_matches, cx)
+        } else {
+            self.render_sidebar_outline_list(&rows, filtering, cx)
+        };
+
+        div()
+            .size_full()
+            .v_flex()
+            .child(
+                div()
+                    .w_full()
+                    .flex_none()
+                    .p_2()
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|_, _: &MouseDownEvent, _, cx| {
+                            cx.stop_propagation();
+                        }),
+                    )
+                    .child(
+                        Input::new(&self.sidebar_outline_filter_input_state)
+                            .small()
+                            .prefix(
+                                Icon::new(icons::IconName::Search)
+                                    .size_3()
+                                    .text_color(cx.theme().muted_foreground),
+                            )
+                            .cleanable(true),
+                    ),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_h(px(0.))
+                    .w_full()
+                    .track_focus(&self.sidebar_outline_focus_handle)
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|this, _: &MouseDownEvent, window, cx| {
+                            window.focus(&this.sidebar_outline_focus_handle);
+                            cx.stop_propagation();
+                        }),
+                    )
+                    .child(body),
+            )
+            .into_any_element()
+    }
+
+    fn render_sidebar_outline_list(
+        &self,
+        rows: &[usize],
+        filtering: bool,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let Some(scroll) = self.active_tab().map(|tab| tab.outline_scroll.clone()) else {
+            return div().into_any_element();
+        };
+        let row_elements = rows
+            .iter()
+            .map(|&ix| self.render_sidebar_outline_row(ix, filtering, cx))
+            .collect::<Vec<_>>();
+
+        div()
+            .relative()
+            .size_full()
+            .child(
+                div()
+                    .id("sidebar-outline-list")
+                    .size_full()
+                    .p(self.ui_density.spacing(0.25))
+                    .v_flex()
+                    .gap_0p5()
+                    .overflow_y_scroll()
+                    .track_scroll(&scroll)
+                    .children(row_elements),
+            )
+            .child(
+                div()
+                    .absolute()
+                    .top_0()
+                    .right_0()
+                    .bottom_0()
+                    .child(Scrollbar::vertical(&scroll).scrollbar_show(ScrollbarShow::Always)),
+            )
+            .into_any_element()
+    }
+
+    fn render_sidebar_outline_row(
+        &self,
+        ix: usize,
+        filtering: bool,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let i18n = self.i18n();
+        let Some(tab) = self.active_tab() else {
+            return div().into_any_element();
+        };
+        let entry = &tab.outline[ix];
+        let page_index = entry.page_index;
+        let title = match page_index {
+            Some(page_index) if entry.title.is_empty() => i18n.bookmark_page_label(page_index + 1),
+            _ => entry.title.clone(),
+        };
+        let has_children = outline_has_children(&tab.outline, ix);
+        let collapsed = !filtering && tab.outline_collapsed.contains(&ix);
+        let is_selected = tab.outline_selected == Some(ix);
+        let is_active = page_index == Some(self.active_tab_active_page());
+
+        div()
+            .id(("sidebar-outline-row", ix))
+            .w_full()
+            .pl(px(4. + entry.depth as f32 * SIDEBAR_OUTLINE_INDENT))
+            .pr_2()
+            .py(self.ui_density.spacing(0.25))
+            .rounded_md()
+            .h_flex()
+            .items_center()
+            .gap_1()
+            .when(page_index.is_some(), |this| this.cursor_pointer())
+            .when(is_active, |this| {
+                this.bg(cx.theme().secondary.opacity(0.55))
+            })
+            .border_1()
+            .border_color(if is_selected {
+                cx.theme().primary.opacity(0.6)
+            } else {
+                transparent_black()
+            })
+            .hover(|this| this.bg(cx.theme().secondary.opacity(0.35)))
+            .child(
+                div()
+                    .id(("sidebar-outline-chevron", ix))
+                    .w(px(SIDEBAR_OUTLINE_CHEVRON_WIDTH))
+                    .flex_none()
+                    .when(has_children && !filtering, |this| {
+                        this.child(
+                            Icon::new(if collapsed {
+                                icons::IconName::ChevronRight
+                            } else {
+                                icons::IconName::ChevronDown
+                            })
+                            .size_3()
+                            .text_color(cx.theme().muted_foreground),
+                        )
+                        .on_click(cx.listener(move |this, _, _, cx| {
+                            this.set_outline_collapsed(ix, !collapsed, cx);
+                            cx.stop_propagation();
+                        }))
+                    }),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .min_w(px(0.))
+                    .text_xs()
+                    .text_color(cx.theme().foreground)
+                    .truncate()
+                    .child(title),
+            )
+            .when_some(page_index, |this, page_index| {
+                this.child(
+                    div()
+                        .flex_none()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(format!("{}", page_index + 1)),
+                )
+            })
+            .on_click(cx.listener(move |this, _, _, cx| {
+                let rows = this.active_outline_rows(cx);
+                this.select_outline_row(ix, &rows, cx);
+                this.activate_outline_selection(cx);
+            }))
+            .into_any_element()
+    }
+
+    fn render_sidebar_bookmarks(&self, cx: &mut Context<Self>) -> AnyElement {
+        let i18n = self.i18n();
+        let mut bookmarks = self.bookmarks_for_scope(super::BookmarkScope::CurrentPdf);
+        if bookmarks.is_empty() {
+            return self.render_sidebar_placeholder(i18n.no_bookmarks, cx);
+        }
+        bookmarks.sort_by_key(|bookmark| bookmark.page_index);
+
+        let rows = bookmarks
+            .into_iter()
+            .enumerate()
+            .map(|(ix, bookmark)| {
+                self.render_sidebar_row(
+                    ("sidebar-bookmark-row", ix),
+                    bookmark.page_index,
+                    i18n.bookmark_page_label(bookmark.page_index + 1).into(),
+                    None,
+                    0.,
+                    cx,
+                )
+            })
+            .collect::<Vec<_>>();
+        self.render_sidebar_list("sidebar-bookmark-list", rows)
+    }
+
+    fn render_sidebar_annotations(&self, cx: &mut Context<Self>) -> AnyElement {
+        let i18n = self.i18n();
+        let Some(path) = self.active_tab_path() else {
+            return self.render_sidebar_placeholder(i18n.sidebar_annotations_empty, cx);
+        };
+
+        // (page_index, created_at, title, preview)
+        let mut entries = self
+            .text_markups
+            .iter()
+            .filter(|markup| markup.path == *path)
+            .map(|markup| {
+                let title = match markup.kind {
+                    TextMarkupKind::Highlight => i18n.text_markup_highlight_button,
+                    TextMarkupKind::Underline => i18n.text_markup_underline_button,
+                };
+                (
+                    markup.page_index,
+                    markup.created_at_unix_secs,
+                    title.to_string(),
+                    markup.selected_text.trim().to_string(),
+                )
+            })
+            .chain(
+                self.markdown_notes
+                    .iter()
+                    .filter(|note| note.path == *path)
+                    .map(|note| {
+                        (
+                            note.page_index,
+                            note.created_at_unix_secs,
+                            i18n.text_markup_add_note_button.to_string(),
+                            note.markdown.trim().to_string(),
+                        )
+                    }),
+            )
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return self.render_sidebar_placeholder(i18n.sidebar_annotations_empty, cx);
+        }
+        entries.sort_by_key(|(page_index, created_at, _, _)| (*page_index, *created_at));
+
+        let rows = entries
+            .into_iter()
+            .enumerate()
+            .map(|(ix, (page_index, _, title, preview))| {
+                self.render_sidebar_row(
+                    ("sidebar-annotation-row", ix),
+                    page_index,
+                    title.into(),
+                    (!preview.is_empty()).then(|| preview.into()),
+                    0.,
+                    cx,
+                )
+            })
+            .collect::<Vec<_>>();
+        self.render_sidebar_list("sidebar-annotation-list", rows)
+    }
+
+    fn render_sidebar_search(&self, cx: &mut Context<Self>) -> AnyElement {
+        let i18n = self.i18n();
+        let (query, results, loading) = self
+            .active_tab()
+            .map(|tab| {
+                (
+                    tab.search_query.clone(),
+                    tab.search_results.clone(),
+                    tab.search_loading,
+                )
+            })
+            .unwrap_or_default();
+
+        let body = if loading {
+            self.render_sidebar_placeholder(i18n.sidebar_search_searching, cx)
+        } else if query.is_empty() {
+            div().into_any_element()
+        } else if results.is_empty() {
+            self.render_sidebar_placeholder(i18n.sidebar_search_no_results, cx)
+        } else {
+            let rows = results
+                .into_iter()
+                .enumerate()
+                .map(|(ix, hit)| {
+                    self.render_sidebar_row(
+                        ("sidebar-search-row", ix),
+                        hit.page_index,
+                        hit.snippet.into(),
+                        None,
+                        0.,
+                        cx,
+                    )
+                })
+                .collect::<Vec<_>>();
+            self.render_sidebar_list("sidebar-search-list", rows)
+        };
+        let result_count = self
+            .active_tab()
+            .filter(|tab| !tab.search_loading && !tab.search_query.is_empty())
+            .map(|tab| match tab.search_cursor {
+                Some(cursor) => {
+                    i18n.sidebar_search_result_position(cursor + 1, tab.search_results.len())
+                }
+                None => i18n.sidebar_search_result_count(tab.search_results.len()),
+            });
+        let wrapped = self.search_wrapped_label();
+
+        div()
+            .size_full()
+            .v_flex()
+            .child(
+                div()
+                    .w_full()
+                    .flex_none()
+                    .p_2()
+                    .v_flex()
+                    .gap_1()
+                    .on_mouse_down(
+                        MouseButton::Left,
+                        cx.listener(|_, _: &MouseDownEvent, _, cx| {
+                            cx.stop_propagation();
+                        }),
+                    )
+                    .child(
+                        Input::new(&self.sidebar_search_input_state)
+                            .small()
+                            .prefix(
+                                Icon::new(icons::IconName::Search)
+                                    .size_3()
+                                    .text_color(cx.theme().muted_foreground),
+                            )
+                            .cleanable(true),
+                    )
+                    .when_some(result_count, |this, result_count| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(result_count),
+                        )
+                    })
+                    .child(
+                        Checkbox::new("sidebar-search-highlight-all")
+                            .label(i18n.sidebar_search_highlight_all)
+                            .checked(self.search_highlight_all)
+                            .on_click(cx.listener(|this, checked: &bool, _, cx| {
+                                this.set_search_highlight_all(*checked, cx);
+                            })),
+                    )
+                    .when_some(wrapped, |this, wrapped| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().warning)
+                                .child(wrapped),
+                        )
+                    }),
+            )
+            .child(div().flex_1().min_h(px(0.)).w_full().child(body))
+            .into_any_element()
+    }
+}
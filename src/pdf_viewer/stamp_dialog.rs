@@ -0,0 +1,278 @@
+use super::{
+    MarkdownNoteAnchor, STAMP_DIALOG_WIDTH, STAMP_DIALOG_WINDOW_HEIGHT, StampContent, StampPreset,
+};
+use crate::i18n::{I18n, Language};
+use crate::pdf_viewer::PdfViewer;
+use gpui::*;
+use gpui_component::input::{Input, InputState};
+use gpui_component::{button::*, *};
+
+pub(super) struct StampWindow {
+    viewer: Entity<PdfViewer>,
+    focus_handle: FocusHandle,
+    language: Language,
+    anchor: MarkdownNoteAnchor,
+    text_input: Entity<InputState>,
+}
+
+impl StampWindow {
+    pub(super) fn new(
+        viewer: Entity<PdfViewer>,
+        language: Language,
+        anchor: MarkdownNoteAnchor,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let i18n = I18n::new(language);
+        let text_input = cx
+            .new(|cx| InputState::new(window, cx).placeholder(i18n.stamp_custom_text_placeholder));
+
+        Self {
+            viewer,
+            focus_handle: cx.focus_handle(),
+            language,
+            anchor,
+            text_input,
+        }
+    }
+
+    fn i18n(&self) -> I18n {
+        I18n::new(self.language)
+    }
+
+    fn place(&mut self, content: StampContent, cx: &mut Context<Self>) {
+        let anchor = self.anchor;
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.add_stamp(anchor, content, cx);
+            viewer.close_stamp_dialog(cx);
+        });
+    }
+
+    fn place_text(&mut self, cx: &mut Context<Self>) {
+        let text = self.text_input.read(cx).value().trim().to_string();
+        if text.is_empty() {
+            return;
+        }
+        self.place(StampContent::Text(text), cx);
+    }
+
+    fn choose_image(&mut self, cx: &mut Context<Self>) {
+        let picker = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some(self.i18n().stamp_image_prompt.into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = picker.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                this.place(StampContent::Image(path), cx);
+            });
+        })
+        .detach();
+    }
+
+    fn close_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.close_stamp_dialog(cx);
+        });
+        window.remove_window();
+    }
+}
+
+impl Render for StampWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let i18n = self.i18n();
+
+        div()
+            .id("stamp-window")
+            .size_full()
+            .v_flex()
+            .bg(cx.theme().background)
+            .focusable()
+            .track_focus(&self.focus_handle)
+            .capture_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key.as_str() == "escape" {
+                    this.close_dialog(window, cx);
+                    cx.stop_propagation();
+                }
+            }))
+            .child(TitleBar::new())
+            .child(
+                div()
+                    .flex_1()
+                    .min_h(px(0.))
+                    .v_flex()
+                    .gap_3()
+                    .p_4()
+                    .child(
+                        div()
+                            .text_lg()
+                            .text_color(cx.theme().foreground)
+                            .child(i18n.stamp_dialog_title),
+                    )
+                    .child(div().h(px(1.)).bg(cx.theme().border))
+                    .child(PdfViewer::render_dialog_field(
+                        i18n.stamp_presets_label,
+                        div().w_full().flex().flex_wrap().gap_2().children(
+                            StampPreset::ALL
+                                .into_iter()
+                                .enumerate()
+                                .map(|(ix, preset)| {
+                                    let content = StampContent::Preset(preset);
+                                    let color = PdfViewer::stamp_color(&content);
+                                    div()
+                                        .id(("stamp-preset", ix))
+                                        .px_3()
+                                        .py_1()
+                                        .rounded_md()
+                                        .border_2()
+                                        .border_color(color)
+                                        .text_color(color)
+                                        .text_sm()
+                                        .font_weight(FontWeight::BOLD)
+                                        .cursor_pointer()
+                                        .hover(|this| this.bg(cx.theme().secondary))
+                                        .on_click(cx.listener(move |this, _, _, cx| {
+                                            this.place(StampContent::Preset(preset), cx);
+                                        }))
+                                        .child(
+                                            PdfViewer::stamp_preset_label(i18n, preset)
+                                                .to_uppercase(),
+                                        )
+                                }),
+                        ),
+                        cx,
+                    ))
+                    .child(PdfViewer::render_dialog_field(
+                        i18n.stamp_custom_text_label,
+                        div()
+                            .w_full()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(div().flex_1().child(Input::new(&self.text_input).small()))
+                            .child(
+                                Button::new("stamp-place-text")
+                                    .small()
+                                    .label(i18n.stamp_place_text_button)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.place_text(cx);
+                                    })),
+                            ),
+                        cx,
+                    ))
+                    .child(div().flex_1())
+                    .child(
+                        div()
+                            .w_full()
+                            .flex()
+                            .items_center()
+                            .justify_end()
+                            .gap_2()
+                            .child(
+                                Button::new("stamp-choose-image")
+                                    .small()
+                                    .label(i18n.stamp_choose_image_button)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.choose_image(cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("stamp-close")
+                                    .small()
+                                    .ghost()
+                                    .label(i18n.close_button)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.close_dialog(window, cx);
+                                    })),
+                            ),
+                    ),
+            )
+    }
+}
+
+impl PdfViewer {
+    pub(super) fn open_stamp_dialog(&mut self, anchor: MarkdownNoteAnchor, cx: &mut Context<Self>) {
+        if self.stamp_dialog_open {
+            // The anchor belongs to the window, so a new placement replaces the old picker.
+            self.close_stamp_dialog(cx);
+        }
+        if self.active_tab_path().is_none() {
+            return;
+        }
+
+        self.stamp_dialog_open = true;
+        self.needs_root_refocus = false;
+        self.stamp_dialog_session = self.stamp_dialog_session.wrapping_add(1);
+        let session_id = self.stamp_dialog_session;
+
+        let language = self.language;
+        let viewer = cx.entity();
+        let viewer_for_close = viewer.clone();
+        let window_options = WindowOptions {
+            titlebar: Some(Self::dialog_titlebar_options()),
+            window_bounds: Some(WindowBounds::centered(
+                size(px(STAMP_DIALOG_WIDTH), px(STAMP_DIALOG_WINDOW_HEIGHT)),
+                cx,
+            )),
+            window_decorations: Some(WindowDecorations::Client),
+            ..WindowOptions::default()
+        };
+
+        match cx.open_window(window_options, move |window, cx| {
+            window.on_window_should_close(cx, move |_, cx| {
+                let _ = viewer_for_close.update(cx, |this, cx| {
+                    this.on_stamp_dialog_window_closed(session_id, cx);
+                });
+                true
+            });
+            let dialog = cx.new(|cx| StampWindow::new(viewer, language, anchor, window, cx));
+            let dialog_focus = dialog.read(cx).focus_handle.clone();
+            let root = cx.new(|cx| Root::new(dialog, window, cx));
+            window.focus(&dialog_focus);
+            root
+        }) {
+            Ok(handle) => {
+                self.stamp_dialog_window = Some(handle.into());
+                cx.notify();
+            }
+            Err(err) => {
+                crate::debug_log!(@warn, "[stamp] failed to open stamp window: {}", err);
+                self.on_stamp_dialog_window_closed(session_id, cx);
+            }
+        }
+    }
+
+    pub(super) fn close_stamp_dialog(&mut self, cx: &mut Context<Self>) {
+        let window_handle = self.stamp_dialog_window.take();
+        if self.stamp_dialog_open || window_handle.is_some() {
+            self.stamp_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+        // Defer window removal to avoid borrow conflicts during event handling
+        if let Some(window_handle) = window_handle {
+            cx.defer(move |cx| {
+                let _ = window_handle.update(cx, |_, window, _| {
+                    window.remove_window();
+                });
+            });
+        }
+    }
+
+    fn on_stamp_dialog_window_closed(&mut self, session_id: u64, cx: &mut Context<Self>) {
+        if self.stamp_dialog_session == session_id {
+            self.stamp_dialog_window = None;
+            self.stamp_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use super::{
+    MarkdownNoteAnchor, PdfViewer, STAMP_DEFAULT_WIDTH_RATIO, STAMP_TEXT_ASPECT, StampContent,
+    StampEntry, StampPreset,
+};
+use crate::i18n::I18n;
+use gpui::*;
+use kpdf_core::annotations;
+
+impl PdfViewer {
+    pub(super) fn load_stamps_from_store(store: &sled::Tree) -> Vec<StampEntry> {
+        annotations::load_entries(store)
+    }
+
+    pub(super) fn persist_stamps(&self) {
+        if let Some(store) = self.stamps_store.as_ref() {
+            annotations::store_entries(store, &self.stamps);
+        }
+    }
+
+    fn next_stamp_id(&self) -> u64 {
+        let mut candidate = Self::now_unix_millis().saturating_mul(1000);
+        while self.stamps.iter().any(|stamp| stamp.id == candidate) {
+            candidate = candidate.saturating_add(1);
+        }
+        candidate
+    }
+
+    /// Places `content` centered on `anchor` in the active tab, keeping image stamps at their
+    /// own aspect ratio.
+    pub(super) fn add_stamp(
+        &mut self,
+        anchor: MarkdownNoteAnchor,
+        content: StampContent,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(path) = self.active_tab_path().cloned() else {
+            return;
+        };
+        let Some((page_width_pt, page_height_pt)) = self
+            .active_tab_pages()
+            .and_then(|pages| pages.get(anchor.page_index))
+            .map(|page| (page.width_pt, page.height_pt))
+            .filter(|(width, height)| *width > 0.0 && *height > 0.0)
+        else {
+            return;
+        };
+
+        let aspect = match &content {
+            StampContent::Image(image_path) => image::image_dimensions(image_path)
+                .ok()
+                .filter(|(width, height)| *width > 0 && *height > 0)
+                .map(|(width, height)| height as f32 / width as f32)
+                .unwrap_or(1.0),
+            StampContent::Preset(_) | StampContent::Text(_) => STAMP_TEXT_ASPECT,
+        };
+        let width_ratio = STAMP_DEFAULT_WIDTH_RATIO;
+        let height_ratio = (width_ratio * page_width_pt * aspect / page_height_pt).min(1.0);
+        let now = Self::now_unix_secs();
+        self.stamps.insert(
+            0,
+            StampEntry {
+                id: self.next_stamp_id(),
+                path,
+                page_index: anchor.page_index,
+                content,
+                x_ratio: anchor.x_ratio,
+                y_ratio: anchor.y_ratio,
+                width_ratio,
+                height_ratio,
+                author: self.annotation_defaults.author(),
+                created_at_unix_secs: now,
+                updated_at_unix_secs: now,
+            },
+        );
+        self.persist_stamps();
+        cx.notify();
+    }
+
+    pub(super) fn delete_stamp_by_id(&mut self, stamp_id: u64, cx: &mut Context<Self>) {
+        let original_len = self.stamps.len();
+        self.stamps.retain(|stamp| stamp.id != stamp_id);
+        if self.stamps.len() != original_len {
+            self.persist_stamps();
+            cx.notify();
+        }
+    }
+
+    pub(super) fn active_tab_stamps_for_page(&self, page_index: usize) -> Vec<StampEntry> {
+        let Some(path) = self.active_tab_path() else {
+            return Vec::new();
+        };
+
+        self.stamps
+            .iter()
+            .filter(|stamp| stamp.path == *path && stamp.page_index == page_index)
+            .cloned()
+            .collect::<Vec<_>>()
+    }
+
+    pub(super) fn open_stamp_context_menu(
+        &mut self,
+        stamp_id: u64,
+        position: Point<Pixels>,
+        cx: &mut Context<Self>,
+    ) {
+        self.clear_text_selection_hover_menu_state();
+        self.context_menu_open = true;
+        self.context_menu_position = Some(position);
+        self.context_menu_tab_id = None;
+        self.context_menu_note_anchor = None;
+        self.context_menu_note_id = None;
+        self.context_menu_text_markup_id = None;
+        self.context_menu_stamp_id = Some(stamp_id);
+        self.context_menu_shape_id = None;
+        cx.notify();
+    }
+
+    pub(super) fn stamp_preset_label(i18n: I18n, preset: StampPreset) -> &'static str {
+        match preset {
+            StampPreset::Approved => i18n.stamp_preset_approved,
+            StampPreset::Draft => i18n.stamp_preset_draft,
+            StampPreset::Confidential => i18n.stamp_preset_confidential,
+            StampPreset::Rejected => i18n.stamp_preset_rejected,
+        }
+    }
+
+    pub(super) fn stamp_color(content: &StampContent) -> Rgba {
+        match content {
+            StampContent::Preset(StampPreset::Approved) => rgb(0x16A34A),
+            StampContent::Preset(StampPreset::Draft) => rgb(0x6B7280),
+            StampContent::Preset(StampPreset::Confidential) => rgb(0xDC2626),
+            StampContent::Preset(StampPreset::Rejected) => rgb(0xB91C1C),
+            StampContent::Text(_) | StampContent::Image(_) => rgb(0x2563EB),
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use super::{PdfViewer, STATUS_BAR_HEIGHT};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::*;
+
+impl PdfViewer {
+    pub(super) fn set_status_bar_visible(&mut self, visible: bool, cx: &mut Context<Self>) {
+        if self.status_bar_visible != visible {
+            self.status_bar_visible = visible;
+            self.persist_status_bar_visible();
+            cx.notify();
+        }
+    }
+
+    pub(super) fn toggle_status_bar(&mut self, cx: &mut Context<Self>) {
+        self.set_status_bar_visible(!self.status_bar_visible, cx);
+    }
+
+    fn status_bar_page_text(&self) -> Option<String> {
+        let i18n = self.i18n();
+        let pages = self.active_tab_pages()?;
+        if pages.is_empty() {
+            return None;
+        }
+        let active_page = self.active_tab_active_page().min(pages.len() - 1);
+        let page_num = active_page + 1;
+        Some(match pages[active_page].label.as_deref() {
+            Some(label) => i18n.status_bar_page_with_label(label, page_num, pages.len()),
+            None => i18n.status_bar_page(page_num, pages.len()),
+        })
+    }
+
+    fn status_bar_selected_char_count(&self) -> usize {
+        self.active_tab_text_selection_manager()
+            .and_then(|manager| manager.borrow().get_selected_text())
+            .map(|text| text.chars().count())
+            .unwrap_or(0)
+    }
+
+    fn status_bar_renders_in_flight(&self) -> usize {
+        self.active_tab()
+            .map(|tab| tab.thumbnail_inflight_tasks + tab.display_inflight_tasks)
+            .unwrap_or(0)
+    }
+
+    pub(super) fn render_status_bar(
+        &self,
+        zoom_label: SharedString,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let i18n = self.i18n();
+        let has_document = self.active_tab_path().is_some();
+        let page_text = self.status_bar_page_text();
+        let selected_chars = self.status_bar_selected_char_count();
+        let search_wrapped = self.search_wrapped_label();
+        let file_size = self
+            .active_tab()
+            .and_then(|tab| tab.file_size_bytes)
+            .map(Self::format_storage_size);
+        let renders_in_flight = self.status_bar_renders_in_flight();
+        let load_progress = self
+            .active_tab()
+            .and_then(|tab| Some((tab.load_progress()?, tab.summary_progress)));
+        let repaired = self.active_tab().is_some_and(|tab| tab.repaired);
+
+        let separator = || {
+            div()
+                .w(px(1.))
+                .h(px(12.))
+                .bg(cx.theme().border)
+        };
+
+        div()
+            .id("status-bar")
+            .w_full()
+            .h(px(STATUS_BAR_HEIGHT))
+            .flex_none()
+            .h_flex()
+            .items_center()
+            .justify_between()
+            .px_3()
+            .gap_3()
+            .border_t_1()
+            .border_color(cx.theme().border)
+            .bg(cx.theme().secondary)
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+            .child(
+                h_flex()
+                    .items_center()
+                    .gap_3()
+                    .min_w_0()
+                    .when_some(page_text, |this, page_text| {
+                        this.child(div().whitespace_nowrap().child(page_text))
+                    })
+                    .when(has_document, |this| {
+                        this.child(separator())
+                            .child(div().whitespace_nowrap().child(zoom_label))
+                    })
+                    .when(selected_chars > 0, |this| {
+                        this.child(separator()).child(
+                            div()
+                                .whitespace_nowrap()
+                                .child(i18n.status_bar_selection_chars(selected_chars)),
+                        )
+                    })
+                    .when_some(search_wrapped, |this, search_wrapped| {
+                        this.child(separator()).child(
+                            div()
+                                .whitespace_nowrap()
+                                .text_color(cx.theme().warning)
+                                .child(search_wrapped),
+                        )
+                    }),
+            )
+            .child(
+                h_flex()
+                    .items_center()
+                    .gap_3()
+                    .flex_none()
+                    .when(repaired, |this| {
+                        this.child(
+                            div()
+                                .whitespace_nowrap()
+                                .text_color(cx.theme().warning)
+                                .child(i18n.status_bar_repaired),
+                        )
+                        .child(separator())
+                    })
+                    .when_some(load_progress, |this, (fraction, summary_progress)| {
+                        this.child(
+                            h_flex()
+                                .items_center()
+                                .gap_2()
+                                .child(
+                                    div()
+                                        .w(px(60.))
+                                        .h(px(4.))
+                                        .rounded_full()
+                                        .bg(cx.theme().border)
+                                        .child(
+                                            div()
+                                                .h_full()
+                                                .rounded_full()
+                                                .bg(cx.theme().primary)
+                                                .w(relative(fraction)),
+                                        ),
+                                )
+                                .child(div().whitespace_nowrap().child(match summary_progress {
+                                    Some((_, 0)) => i18n.status_bar_opening.to_string(),
+                                    Some((done, total)) => {
+                                        i18n.status_bar_loading_pages(done, total)
+                                    }
+                                    None => i18n.status_bar_rendering_first_pages.to_string(),
+                                })),
+                        )
+                        .child(separator())
+                    })
+                    .when_some(file_size, |this, file_size| {
+                        this.child(div().whitespace_nowrap().child(file_size))
+                            .child(separator())
+                    })
+                    .child(div().whitespace_nowrap().child(if renders_in_flight > 0 {
+                        i18n.status_bar_renders_in_flight(renders_in_flight)
+                    } else {
+                        i18n.status_bar_idle.to_string()
+                    })),
+            )
+    }
+}
@@ -1,10 +1,24 @@
-use crate::pdf_viewer::PageSummary;
+use crate::pdf_viewer::render_queue::RenderQueue;
 use crate::pdf_viewer::text_selection::TextSelectionManager;
+use crate::pdf_viewer::utils::{OutlineEntry, TextSearchHit};
+use crate::pdf_viewer::{PageSummary, SidebarTab};
 use gpui::*;
 use gpui_component::VirtualListScrollHandle;
 use std::cell::RefCell;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Band tiles of a page too large to render as one bitmap, keyed by tile row.
+#[derive(Clone, Default)]
+pub struct DisplayPageTiles {
+    pub render_width: u32,
+    pub tiles: BTreeMap<u32, Arc<RenderImage>>,
+    pub loading: HashSet<u32>,
+    /// Tiles from the previous zoom level, drawn scaled until the new ones cover the viewport.
+    pub stale: Option<(u32, BTreeMap<u32, Arc<RenderImage>>)>,
+}
 
 #[derive(Clone)]
 pub struct PdfTab {
@@ -14,28 +28,96 @@ pub struct PdfTab {
     pub summary_loaded: bool,
     pub summary_loading: bool,
     pub summary_failed: bool,
+    /// Pages whose real size and label are known; the rest are placeholders still streaming in.
+    pub summary_loaded_pages: usize,
+    /// Pages read and the total while summaries are still loading; `None` once they are all
+    /// in or loading stopped.
+    pub summary_progress: Option<(usize, usize)>,
+    /// Set when a document finishes loading and cleared once its first display batch lands,
+    /// so the load indicator also covers the initial render.
+    pub first_render_pending: bool,
+    pub summary_epoch: u64,
+    pub load_error: Option<String>,
+    pub repairing: bool,
+    /// Whether the tab shows the repaired copy of its damaged file.
+    pub repaired: bool,
+    /// Pdfium is unavailable; pages are sized placeholders read by the pure-Rust fallback.
+    pub skeleton_only: bool,
     pub selected_page: usize,
     pub active_page: usize,
     pub zoom: f32,
     pub thumbnail_scroll: VirtualListScrollHandle,
+    /// Thumbnail picked with the keyboard; Enter turns it into the active page.
+    pub thumbnail_cursor: Option<usize>,
     pub display_scroll: VirtualListScrollHandle,
     pub thumbnail_loading: HashSet<usize>,
     pub thumbnail_inflight_tasks: usize,
     pub thumbnail_epoch: u64,
+    pub thumbnail_queue: RenderQueue,
     pub last_thumbnail_visible_range: Option<std::ops::Range<usize>>,
     pub display_loading: HashSet<usize>,
     pub display_inflight_tasks: usize,
     pub display_epoch: u64,
+    /// Mirror of `display_epoch` readable from background render jobs.
+    pub display_cancel_epoch: Arc<AtomicU64>,
+    pub display_queue: RenderQueue,
     pub last_display_visible_range: Option<std::ops::Range<usize>>,
     pub last_display_target_width: u32,
+    /// Width pages are actually rendered at; trails `last_display_target_width` while zooming.
+    pub display_render_target_width: u32,
+    pub display_zoom_render_epoch: u64,
+    pub display_tiles: HashMap<usize, DisplayPageTiles>,
+    pub display_tile_inflight: bool,
     pub display_scroll_sync_epoch: u64,
     pub last_display_scroll_offset: Option<Point<Pixels>>,
     pub suppress_display_scroll_sync_once: bool,
+    /// Width the display list was last laid out at, for converting scroll offsets.
+    pub last_display_base_width: f32,
+    /// Offset into the active page still to be restored once the display list has a layout.
+    pub pending_page_fraction: Option<f32>,
     pub last_saved_position: Option<(PathBuf, usize)>,
+    /// Per-file page order; `None` follows the comic archive setting.
+    pub right_to_left: Option<bool>,
+    /// Pages left by jumps (links, outline, go to page), most recent last.
+    pub back_history: Vec<usize>,
+    /// Pages stepped back from, revisited by going forward.
+    pub forward_history: Vec<usize>,
+    pub file_size_bytes: Option<u64>,
     pub text_selection_manager: RefCell<TextSelectionManager>,
+    pub sidebar_tab: SidebarTab,
+    pub outline: Vec<OutlineEntry>,
+    pub outline_loading: bool,
+    pub outline_loaded: bool,
+    /// Outline row picked with the keyboard, as an index into `outline`.
+    pub outline_selected: Option<usize>,
+    /// Outline entries whose children are hidden.
+    pub outline_collapsed: HashSet<usize>,
+    pub outline_scroll: ScrollHandle,
+    pub search_query: String,
+    pub search_results: Vec<TextSearchHit>,
+    pub search_loading: bool,
+    pub search_epoch: u64,
+    /// Result last reached with F3 / Shift+F3.
+    pub search_cursor: Option<usize>,
+    /// Set when the last step went past either end of the results.
+    pub search_wrapped: bool,
+    /// Whether "highlight all" still draws the matches; Escape hides them until the next search.
+    pub search_highlights_visible: bool,
+    /// Match rectangles in page points, filled per page once its text has been extracted.
+    pub search_match_rects: RefCell<HashMap<usize, Vec<(f32, f32, f32, f32)>>>,
 }
 
 impl PdfTab {
+    /// How far along loading the page summaries is, from 0.0 to 1.0, while it runs. Stays
+    /// full until the first pages are on screen.
+    pub fn load_progress(&self) -> Option<f32> {
+        match self.summary_progress {
+            Some((_, 0)) => Some(0.0),
+            Some((done, total)) => Some((done as f32 / total as f32).clamp(0.0, 1.0)),
+            None => self.first_render_pending.then_some(1.0),
+        }
+    }
+
     pub fn new(id: usize) -> Self {
         Self {
             id,
@@ -44,25 +126,62 @@ impl PdfTab {
             summary_loaded: false,
             summary_loading: false,
             summary_failed: false,
+            summary_loaded_pages: 0,
+            summary_progress: None,
+            first_render_pending: false,
+            summary_epoch: 0,
+            load_error: None,
+            repairing: false,
+            repaired: false,
+            skeleton_only: false,
             selected_page: 0,
             active_page: 0,
             zoom: 1.0,
             thumbnail_scroll: VirtualListScrollHandle::new(),
+            thumbnail_cursor: None,
             display_scroll: VirtualListScrollHandle::new(),
             thumbnail_loading: HashSet::new(),
             thumbnail_inflight_tasks: 0,
             thumbnail_epoch: 0,
+            thumbnail_queue: RenderQueue::default(),
             last_thumbnail_visible_range: None,
             display_loading: HashSet::new(),
             display_inflight_tasks: 0,
             display_epoch: 0,
+            display_cancel_epoch: Arc::new(AtomicU64::new(0)),
+            display_queue: RenderQueue::default(),
             last_display_visible_range: None,
             last_display_target_width: 220,
+            display_render_target_width: 0,
+            display_zoom_render_epoch: 0,
+            display_tiles: HashMap::new(),
+            display_tile_inflight: false,
             display_scroll_sync_epoch: 0,
             last_display_scroll_offset: None,
             suppress_display_scroll_sync_once: false,
+            last_display_base_width: 0.0,
+            pending_page_fraction: None,
             last_saved_position: None,
+            right_to_left: None,
+            back_history: Vec::new(),
+            forward_history: Vec::new(),
+            file_size_bytes: None,
             text_selection_manager: RefCell::new(TextSelectionManager::new()),
+            sidebar_tab: SidebarTab::default(),
+            outline: Vec::new(),
+            outline_loading: false,
+            outline_loaded: false,
+            outline_selected: None,
+            outline_collapsed: HashSet::new(),
+            outline_scroll: ScrollHandle::new(),
+            search_query: String::new(),
+            search_results: Vec::new(),
+            search_loading: false,
+            search_epoch: 0,
+            search_cursor: None,
+            search_wrapped: false,
+            search_highlights_visible: false,
+            search_match_rects: RefCell::new(HashMap::new()),
         }
     }
 
@@ -70,14 +189,27 @@ impl PdfTab {
         self.thumbnail_loading.clear();
         self.thumbnail_inflight_tasks = 0;
         self.thumbnail_epoch = self.thumbnail_epoch.wrapping_add(1);
+        self.thumbnail_queue.clear();
         self.last_thumbnail_visible_range = None;
     }
 
     pub fn reset_display_render_state(&mut self) {
+        self.cancel_display_renders();
+        self.display_queue.clear();
+        self.last_display_visible_range = None;
+        self.display_tiles.clear();
+    }
+
+    /// Invalidates in-flight display renders; running jobs stop before their next page and
+    /// release their worker slot when they return.
+    pub fn cancel_display_renders(&mut self) {
         self.display_loading.clear();
-        self.display_inflight_tasks = 0;
+        for tiles in self.display_tiles.values_mut() {
+            tiles.loading.clear();
+        }
         self.display_epoch = self.display_epoch.wrapping_add(1);
-        self.last_display_visible_range = None;
+        self.display_cancel_epoch
+            .store(self.display_epoch, Ordering::Relaxed);
     }
 
     pub fn reset_page_render_state(&mut self) {
@@ -87,6 +219,22 @@ impl PdfTab {
         self.text_selection_manager.borrow_mut().clear_selection();
     }
 
+    pub fn reset_document_side_state(&mut self) {
+        self.outline.clear();
+        self.outline_loading = false;
+        self.outline_loaded = false;
+        self.outline_selected = None;
+        self.outline_collapsed.clear();
+        self.search_query.clear();
+        self.search_results.clear();
+        self.search_loading = false;
+        self.search_epoch = self.search_epoch.wrapping_add(1);
+        self.search_cursor = None;
+        self.search_wrapped = false;
+        self.search_highlights_visible = false;
+        self.search_match_rects.borrow_mut().clear();
+    }
+
     pub fn file_name(&self) -> String {
         self.path
             .as_ref()
@@ -130,6 +278,7 @@ impl TabBar {
         tab.path = Some(path);
         tab.pages = pages;
         tab.summary_loaded = !tab.pages.is_empty();
+        tab.summary_loaded_pages = tab.pages.len();
         self.tabs.push(tab);
         self.active_tab_id = Some(id);
         id
@@ -183,6 +332,10 @@ impl TabBar {
         &self.tabs
     }
 
+    pub fn tabs_mut(&mut self) -> &mut [PdfTab] {
+        &mut self.tabs
+    }
+
     pub fn active_tab_id(&self) -> Option<usize> {
         self.active_tab_id
     }
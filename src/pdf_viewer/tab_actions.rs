@@ -4,8 +4,7 @@ impl PdfViewer {
             && let Some(path) = tab.path.as_ref()
             && !tab.pages.is_empty()
         {
-            let page_index = tab.active_page.min(tab.pages.len().saturating_sub(1));
-            self.save_file_position(path, page_index);
+            self.save_file_position(path, self.tab_file_view(tab));
         }
     }
 
@@ -136,6 +135,15 @@ impl PdfViewer {
             return;
         }
 
+        if self.tab_switcher_open() {
+            let primary_only = is_primary_modifier && !event.keystroke.modifiers.shift;
+            if self.handle_tab_switcher_key(key, primary_only, cx) {
+                cx.stop_propagation();
+            }
+            // Other keys edit the query.
+            return;
+        }
+
         if self.command_panel_open {
             if key == "escape" {
                 self.close_command_panel(cx);
@@ -166,6 +174,92 @@ impl PdfViewer {
             return;
         }
 
+        if self.presentation_active
+            && self.handle_presentation_key(key, is_primary_modifier, window, cx)
+        {
+            cx.stop_propagation();
+            return;
+        }
+
+        // F3 / Shift+F3 (Cmd+G / Cmd+Shift+G on macOS) step through search results anywhere.
+        if key == "f3"
+            || (cfg!(target_os = "macos") && key == "g" && event.keystroke.modifiers.platform)
+        {
+            self.step_search_result(!event.keystroke.modifiers.shift, cx);
+            cx.stop_propagation();
+            return;
+        }
+
+        if self.sidebar_search_input_focused(window, cx) {
+            if key == "enter" {
+                let query = self.sidebar_search_input_state.read(cx).value().to_string();
+                self.start_sidebar_search(query, cx);
+                cx.stop_propagation();
+                return;
+            }
+            if key == "escape" {
+                window.focus(&self.focus_handle);
+                cx.stop_propagation();
+                return;
+            }
+            // Keep typing in the search field from triggering page shortcuts.
+            return;
+        }
+
+        if self.sidebar_outline_filter_focused(window, cx) {
+            match key {
+                "down" => self.move_outline_selection(1, cx),
+                "up" => self.move_outline_selection(-1, cx),
+                "enter" => self.activate_outline_selection(cx),
+                "escape" => window.focus(&self.sidebar_outline_focus_handle),
+                // Keep typing in the filter field from triggering page shortcuts.
+                _ => return,
+            }
+            cx.stop_propagation();
+            return;
+        }
+
+        // Follow-link mode takes plain keys as hint labels; a shortcut leaves it and runs.
+        if self.link_hints_active() {
+            if !event.keystroke.modifiers.modified() {
+                self.handle_link_hint_key(key, cx);
+                cx.stop_propagation();
+                return;
+            }
+            self.cancel_link_hints(cx);
+        }
+
+        if self.sidebar_outline_focus_handle.is_focused(window)
+            && !event.keystroke.modifiers.modified()
+            && self.handle_outline_key(key, window, cx)
+        {
+            cx.stop_propagation();
+            return;
+        }
+
+        if self.thumbnail_focus_handle.is_focused(window)
+            && !event.keystroke.modifiers.modified()
+            && self.handle_thumbnail_key(key, window, cx)
+        {
+            cx.stop_propagation();
+            return;
+        }
+
+        if self.load_error_dialog_visible() {
+            if key == "escape" {
+                self.close_load_error_dialog(cx);
+                cx.stop_propagation();
+                return;
+            }
+            if key == "enter" {
+                if let Some(tab_id) = self.load_error_dialog_tab_id {
+                    self.retry_failed_load(tab_id, cx);
+                }
+                cx.stop_propagation();
+                return;
+            }
+        }
+
         // Handle ESC to close bookmark popup
         if self.bookmark_popup_open {
             if key == "escape" {
@@ -196,8 +290,42 @@ impl PdfViewer {
             self.select_all_text(cx);
             cx.stop_propagation();
         }
-        // Handle Escape to clear selection
+        // Handle Cmd/Ctrl+Z and Cmd/Ctrl+Shift+Z to undo/redo shape and ink edits
+        else if key == "z" && is_primary_modifier {
+            let handled = if event.keystroke.modifiers.shift {
+                self.redo_shape_edit(cx)
+            } else {
+                self.undo_shape_edit(cx)
+            };
+            if handled {
+                cx.stop_propagation();
+            }
+        }
+        // Handle Escape to disarm the shape tool or deselect a shape
+        else if key == "escape" && self.shape_interaction_active() {
+            self.cancel_shape_interaction(cx);
+            cx.stop_propagation();
+        }
+        // Handle Delete/Backspace to remove the selected shape
+        else if (key == "delete" || key == "backspace") && self.selected_shape_id.is_some() {
+            self.delete_selected_shape(cx);
+            cx.stop_propagation();
+        }
+        // Handle Escape to leave page overview
+        else if key == "escape" && self.page_overview_open {
+            self.close_page_overview(cx);
+            cx.stop_propagation();
+        }
+        // Handle Escape to leave zen mode
+        else if key == "escape" && self.zen_mode {
+            self.set_zen_mode(false, cx);
+            cx.stop_propagation();
+        }
+        // Handle Escape to clear selection and any "highlight all" search matches
         else if key == "escape" {
+            if self.search_highlights_shown() {
+                self.hide_search_highlights(cx);
+            }
             self.clear_text_selection(cx);
             cx.stop_propagation();
         }
@@ -211,6 +339,16 @@ impl PdfViewer {
             self.toggle_command_panel(window, cx);
             cx.stop_propagation();
         }
+        // Handle Cmd/Ctrl+P to quick-switch between open tabs
+        else if key == "p" && is_primary_modifier && !event.keystroke.modifiers.shift {
+            self.toggle_tab_switcher(window, cx);
+            cx.stop_propagation();
+        }
+        // Handle Cmd/Ctrl+Shift+O to toggle page overview
+        else if key == "o" && is_primary_modifier && event.keystroke.modifiers.shift {
+            self.toggle_page_overview(cx);
+            cx.stop_propagation();
+        }
         // Handle Cmd/Ctrl+O to open PDF
         else if key == "o" && is_primary_modifier {
             self.open_pdf_dialog(window, cx);
@@ -236,11 +374,42 @@ impl PdfViewer {
             self.set_vertical_tab_bar_visible(!self.vertical_tab_bar_visible, cx);
             cx.stop_propagation();
         }
+        // Handle F11 / Ctrl+Cmd+F to toggle fullscreen
+        else if key == "f11"
+            || (key == "f"
+                && event.keystroke.modifiers.control
+                && event.keystroke.modifiers.platform)
+        {
+            self.toggle_fullscreen(window);
+            cx.stop_propagation();
+        }
+        // Handle Cmd/Ctrl+Shift+F to toggle zen mode
+        else if key == "f" && is_primary_modifier && event.keystroke.modifiers.shift {
+            self.toggle_zen_mode(cx);
+            cx.stop_propagation();
+        }
         // Handle Cmd/Ctrl+Shift+T to toggle thumbnail panel
         else if key == "t" && is_primary_modifier && event.keystroke.modifiers.shift {
             self.toggle_thumbnail_panel(cx);
             cx.stop_propagation();
         }
+        // Handle F to label visible links for keyboard following
+        else if key == "f"
+            && !event.keystroke.modifiers.modified()
+            && !self.page_overview_open
+            && !self.presentation_active
+        {
+            self.start_link_hints(cx);
+            cx.stop_propagation();
+        }
+        // Handle Cmd/Ctrl+Plus/Minus to resize page overview cells
+        else if self.page_overview_open && key == "=" && is_primary_modifier {
+            self.page_overview_zoom_in(cx);
+            cx.stop_propagation();
+        } else if self.page_overview_open && key == "-" && is_primary_modifier {
+            self.page_overview_zoom_out(cx);
+            cx.stop_propagation();
+        }
         // Handle Cmd/Ctrl+Plus to zoom in
         else if key == "=" && is_primary_modifier {
             self.zoom_in(cx);
@@ -276,14 +445,30 @@ impl PdfViewer {
             self.last_page(cx);
             cx.stop_propagation();
         }
-        // Handle Cmd/Ctrl+Left Arrow to go to previous page
+        // Handle Alt+Left / Alt+Right to go back / forward in the tab's page history
+        else if key == "left" && event.keystroke.modifiers.alt && !is_primary_modifier {
+            self.navigate_back(cx);
+            cx.stop_propagation();
+        } else if key == "right" && event.keystroke.modifiers.alt && !is_primary_modifier {
+            self.navigate_forward(cx);
+            cx.stop_propagation();
+        }
+        // Handle Cmd/Ctrl+Left Arrow to go to previous page (next page when reading right to left)
         else if key == "left" && is_primary_modifier {
-            self.prev_page(cx);
+            if self.page_order_right_to_left() {
+                self.next_page(cx);
+            } else {
+                self.prev_page(cx);
+            }
             cx.stop_propagation();
         }
-        // Handle Cmd/Ctrl+Right Arrow to go to next page
+        // Handle Cmd/Ctrl+Right Arrow to go to next page (previous page when reading right to left)
         else if key == "right" && is_primary_modifier {
-            self.next_page(cx);
+            if self.page_order_right_to_left() {
+                self.prev_page(cx);
+            } else {
+                self.next_page(cx);
+            }
             cx.stop_propagation();
         }
         // Handle Cmd/Ctrl+1..9 to switch tabs
@@ -354,14 +539,26 @@ impl PdfViewer {
         }
     }
 
-    fn remember_recent_file(&mut self, path: &PathBuf) {
+    fn remember_recent_file(&mut self, path: &PathBuf, cx: &mut App) {
         self.recent_files.retain(|p| p != path);
         self.recent_files.insert(0, path.clone());
         self.recent_files.truncate(MAX_RECENT_FILES);
-        self.persist_recent_files();
+        self.persist_recent_files(cx);
+        // Also feeds the Dock menu and Finder's recent items on macOS.
+        cx.add_recent_document(path);
+    }
+
+    fn clear_recent_files(&mut self, cx: &mut Context<Self>) {
+        self.recent_files.clear();
+        self.persist_recent_files(cx);
+        #[cfg(target_os = "macos")]
+        self::macos_context_menu::clear_recent_documents();
+        cx.notify();
     }
 
-    fn persist_recent_files(&self) {
+    /// Saves the recent files and rebuilds the Open Recent menu from them.
+    fn persist_recent_files(&self, cx: &mut App) {
+        self.refresh_app_menus(cx);
         let Some(store) = self.recent_store.as_ref() else {
             return;
         };
@@ -389,28 +586,96 @@ impl PdfViewer {
             .into_bytes()
     }
 
-    fn load_saved_file_position(&self, path: &Path) -> Option<usize> {
-        let store = self.position_store.as_ref()?;
-        let value = store.get(Self::file_position_key(path)).ok().flatten()?;
-        if value.len() != 8 {
+    /// Entries hold the page index as a big-endian u64, then the zoom and the offset into the
+    /// page as big-endian f32s, then a right-to-left flag byte; older entries stop earlier.
+    /// There is no rotation or zoom mode to store: pages are always shown upright, and zoom is
+    /// a factor of the fit-to-width size, so `1.0` is the fit-width mode.
+    fn decode_file_position(value: &[u8]) -> Option<SavedFileView> {
+        if !matches!(value.len(), 8 | 12 | 16 | 17) {
             return None;
         }
+        let read_f32 = |range: std::ops::Range<usize>| {
+            value
+                .get(range)
+                .and_then(|raw| raw.try_into().ok())
+                .map(f32::from_be_bytes)
+                .filter(|value| value.is_finite())
+        };
+        Some(SavedFileView {
+            page_index: usize::try_from(u64::from_be_bytes(value[..8].try_into().ok()?)).ok()?,
+            zoom: read_f32(8..12).map(|zoom| zoom.clamp(ZOOM_MIN, ZOOM_MAX)),
+            page_fraction: read_f32(12..16).unwrap_or(0.0).clamp(0.0, 1.0),
+            right_to_left: value.get(16).map(|flag| *flag != 0),
+        })
+    }
 
-        let mut bytes = [0u8; 8];
-        bytes.copy_from_slice(value.as_ref());
-        usize::try_from(u64::from_be_bytes(bytes)).ok()
+    fn load_saved_file_view(&self, path: &Path) -> Option<SavedFileView> {
+        let store = self.position_store.as_ref()?;
+        let value = store.get(Self::file_position_key(path)).ok().flatten()?;
+        Self::decode_file_position(&value)
     }
 
-    fn save_file_position(&self, path: &Path, page_index: usize) {
+    fn load_saved_file_position(&self, path: &Path) -> Option<usize> {
+        self.load_saved_file_view(path).map(|view| view.page_index)
+    }
+
+    /// A `None` zoom or page order keeps whatever was saved for the file before.
+    fn save_file_position(&self, path: &Path, view: SavedFileView) {
         let Some(store) = self.position_store.as_ref() else {
             return;
         };
 
-        let page_bytes = (page_index as u64).to_be_bytes();
-        let _ = store.insert(Self::file_position_key(path), page_bytes.as_slice());
+        let saved = self.load_saved_file_view(path);
+        let zoom = view.zoom.or_else(|| saved.and_then(|saved| saved.zoom));
+        let right_to_left = view
+            .right_to_left
+            .or_else(|| saved.and_then(|saved| saved.right_to_left));
+        let mut value = (view.page_index as u64).to_be_bytes().to_vec();
+        if zoom.is_some() || view.page_fraction > 0.0 || right_to_left.is_some() {
+            value.extend_from_slice(&zoom.unwrap_or(1.0).to_be_bytes());
+        }
+        if view.page_fraction > 0.0 || right_to_left.is_some() {
+            value.extend_from_slice(&view.page_fraction.to_be_bytes());
+        }
+        if let Some(right_to_left) = right_to_left {
+            value.push(u8::from(right_to_left));
+        }
+        let _ = store.insert(Self::file_position_key(path), value);
         let _ = store.flush();
     }
 
+    /// The tab's current page, zoom and offset into the page, measured against the last
+    /// rendered layout.
+    fn tab_file_view(&self, tab: &PdfTab) -> SavedFileView {
+        let page_index = tab.active_page.min(tab.pages.len().saturating_sub(1));
+        let base_width = tab.last_display_base_width;
+        let page_fraction = match tab.pages.get(page_index) {
+            Some(page) if base_width > 0.0 => {
+                let page_top =
+                    self.display_scroll_top_in_page(&tab.pages, page_index, 0.0, base_width);
+                let page_height = self.display_row_height(page, base_width).max(1.0);
+                let scroll_top = -f32::from(tab.display_scroll.offset().y);
+                ((scroll_top - page_top) / page_height).clamp(0.0, 1.0)
+            }
+            _ => 0.0,
+        };
+        SavedFileView {
+            page_index,
+            zoom: Some(tab.zoom),
+            page_fraction,
+            right_to_left: tab.right_to_left,
+        }
+    }
+
+    fn persist_active_tab_view(&self) {
+        if let Some(tab) = self.active_tab()
+            && let Some(path) = tab.path.as_ref()
+            && !tab.pages.is_empty()
+        {
+            self.save_file_position(path, self.tab_file_view(tab));
+        }
+    }
+
     fn persist_current_file_position(&mut self) {
         if let Some(tab) = self.active_tab() {
             if tab.pages.is_empty() {
@@ -431,7 +696,16 @@ impl PdfViewer {
                 return;
             }
 
-            self.save_file_position(path, page_index);
+            // Selecting a page scrolls to its top.
+            self.save_file_position(
+                path,
+                SavedFileView {
+                    page_index,
+                    zoom: Some(tab.zoom),
+                    page_fraction: 0.0,
+                    right_to_left: tab.right_to_left,
+                },
+            );
         }
     }
 
@@ -445,13 +719,39 @@ impl PdfViewer {
             .insert(WINDOW_SIZE_KEY_WIDTH, width_bytes.as_slice())
             .is_err()
         {
-            crate::debug_log!("[window_size] save width failed");
+            crate::debug_log!(@warn, "[window_size] save width failed");
         }
         if store
             .insert(WINDOW_SIZE_KEY_HEIGHT, height_bytes.as_slice())
             .is_err()
         {
-            crate::debug_log!("[window_size] save height failed");
+            crate::debug_log!(@warn, "[window_size] save height failed");
+        }
+        let _ = store.flush();
+    }
+
+    /// Records where a normal (not maximized or fullscreen) window sits and on which display.
+    fn save_window_origin(&self, window: &Window, cx: &App) {
+        if window.is_maximized() || window.is_fullscreen() {
+            return;
+        }
+        let Some(store) = self.window_size_store.as_ref() else {
+            return;
+        };
+        let origin = window.bounds().origin;
+        let x_bytes = f32::from(origin.x).to_be_bytes();
+        let y_bytes = f32::from(origin.y).to_be_bytes();
+        if store.insert(WINDOW_SIZE_KEY_X, x_bytes.as_slice()).is_err()
+            || store.insert(WINDOW_SIZE_KEY_Y, y_bytes.as_slice()).is_err()
+        {
+            crate::debug_log!(@warn, "[window_size] save origin failed");
+        }
+        if let Some(uuid) = window.display(cx).and_then(|display| display.uuid().ok())
+            && store
+                .insert(WINDOW_SIZE_KEY_DISPLAY, uuid.as_bytes().as_slice())
+                .is_err()
+        {
+            crate::debug_log!(@warn, "[window_size] save display failed");
         }
         let _ = store.flush();
     }
@@ -0,0 +1,447 @@
+use super::{PdfViewer, display_file_name};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::input::{Input, InputEvent, InputState};
+use gpui_component::scroll::{Scrollbar, ScrollbarShow};
+use gpui_component::*;
+use std::path::PathBuf;
+
+const TAB_SWITCHER_WIDTH: f32 = 480.0;
+const TAB_SWITCHER_MAX_HEIGHT: f32 = 360.0;
+const TAB_SWITCHER_SCROLLBAR_GUTTER: f32 = 20.0;
+
+/// The Cmd/Ctrl+P overlay: open tabs filtered by a fuzzy match on their file names.
+pub(super) struct TabSwitcher {
+    input_state: Entity<InputState>,
+    _input_subscription: Subscription,
+    query: String,
+    selected_index: usize,
+    list_scroll: ScrollHandle,
+    needs_focus: bool,
+}
+
+/// Scores `candidate` as a case-insensitive subsequence of `query`, favouring characters
+/// that continue a run or start a word. `None` when some query character is missing.
+fn fuzzy_score(query: &str, candidate: &str) -> Option<i32> {
+    let candidate = candidate
+        .chars()
+        .flat_map(char::to_lowercase)
+        .collect::<Vec<_>>();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match = None;
+    for query_char in query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+    {
+        let index = position
+            + candidate[position..]
+                .iter()
+                .position(|c| *c == query_char)?;
+        score += 1;
+        if index > 0 && previous_match == Some(index - 1) {
+            score += 5;
+        }
+        if index == 0 || !candidate[index - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous_match = Some(index);
+        position = index + 1;
+    }
+    Some(score)
+}
+
+/// Position of the entry after the active tab in `matches`, wrapping around; the first entry
+/// when the active tab isn't listed.
+fn index_after_active(matches: &[(usize, PathBuf)], active_tab_id: Option<usize>) -> usize {
+    matches
+        .iter()
+        .position(|(tab_id, _)| Some(*tab_id) == active_tab_id)
+        .map_or(0, |index| (index + 1) % matches.len())
+}
+
+impl PdfViewer {
+    pub(super) fn tab_switcher_open(&self) -> bool {
+        self.tab_switcher.is_some()
+    }
+
+    pub(super) fn toggle_tab_switcher(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.tab_switcher_open() {
+            self.close_tab_switcher(cx);
+        } else {
+            self.open_tab_switcher(window, cx);
+        }
+    }
+
+    fn open_tab_switcher(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.close_command_panel(cx);
+        if self.recent_popup_open {
+            self.close_recent_popup(cx);
+        }
+        if self.bookmark_popup_open {
+            self.close_bookmark_popup(cx);
+        }
+        if self.note_editor_open {
+            self.close_markdown_note_editor(cx);
+        }
+        if self.keymap_dialog_open {
+            self.close_keymap_dialog(cx);
+        }
+        self.needs_root_refocus = false;
+
+        let placeholder = self.i18n().tab_switcher_placeholder;
+        let input_state = cx.new(|cx| InputState::new(window, cx).placeholder(placeholder));
+        let input_subscription = cx.subscribe(&input_state, |this, input, event, cx| {
+            if !matches!(event, InputEvent::Change) {
+                return;
+            }
+            let query = input.read(cx).value().to_string();
+            let Some(switcher) = this.tab_switcher.as_mut() else {
+                return;
+            };
+            if switcher.query != query {
+                switcher.query = query;
+                switcher.selected_index = 0;
+                switcher.list_scroll.scroll_to_item(0);
+                cx.notify();
+            }
+        });
+        self.tab_switcher = Some(TabSwitcher {
+            input_state,
+            _input_subscription: input_subscription,
+            query: String::new(),
+            // Start on the tab after the active one so Cmd/Ctrl+P then Enter moves on.
+            selected_index: index_after_active(
+                &self.tab_switcher_matches(""),
+                self.tab_bar.active_tab_id(),
+            ),
+            list_scroll: ScrollHandle::new(),
+            needs_focus: true,
+        });
+        cx.notify();
+    }
+
+    pub(super) fn close_tab_switcher(&mut self, cx: &mut Context<Self>) {
+        if self.tab_switcher.take().is_some() {
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+    }
+
+    /// Focuses the query field the first time the switcher renders.
+    pub(super) fn focus_tab_switcher_input(&mut self, window: &mut Window, cx: &mut App) {
+        if let Some(switcher) = self.tab_switcher.as_mut()
+            && switcher.needs_focus
+        {
+            switcher.needs_focus = false;
+            switcher
+                .input_state
+                .update(cx, |input, cx| input.focus(window, cx));
+        }
+    }
+
+    /// Open tabs whose file names match `query`, best match first; tab order when empty.
+    fn tab_switcher_matches(&self, query: &str) -> Vec<(usize, PathBuf)> {
+        let mut matches = self
+            .tab_bar
+            .tabs()
+            .iter()
+            .enumerate()
+            .filter_map(|(order, tab)| {
+                let path = tab.path.clone()?;
+                let name = display_file_name(&path);
+                let score = fuzzy_score(query, &name)?;
+                Some((score, name.chars().count(), order, tab.id, path))
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+        if query.trim().is_empty() {
+            matches.sort_by_key(|(_, _, order, _, _)| *order);
+        }
+        matches
+            .into_iter()
+            .map(|(_, _, _, tab_id, path)| (tab_id, path))
+            .collect()
+    }
+
+    /// Handles navigation keys while the switcher is open; other keys go to the query field.
+    pub(super) fn handle_tab_switcher_key(
+        &mut self,
+        key: &str,
+        is_primary_modifier: bool,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        match key {
+            "escape" => self.close_tab_switcher(cx),
+            "down" => self.move_tab_switcher_selection(1, cx),
+            "up" => self.move_tab_switcher_selection(-1, cx),
+            "p" if is_primary_modifier => self.move_tab_switcher_selection(1, cx),
+            "enter" => {
+                let Some(switcher) = self.tab_switcher.as_ref() else {
+                    return false;
+                };
+                let matches = self.tab_switcher_matches(&switcher.query);
+                let selected = matches
+                    .get(switcher.selected_index.min(matches.len().saturating_sub(1)))
+                    .map(|(tab_id, _)| *tab_id);
+                if let Some(tab_id) = selected {
+                    self.activate_tab_switcher_item(tab_id, cx);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    fn move_tab_switcher_selection(&mut self, delta: isize, cx: &mut Context<Self>) {
+        let Some(query) = self.tab_switcher.as_ref().map(|s| s.query.clone()) else {
+            return;
+        };
+        let len = self.tab_switcher_matches(&query).len();
+        let Some(switcher) = self.tab_switcher.as_mut() else {
+            return;
+        };
+        if len == 0 {
+            return;
+        }
+        let current = switcher.selected_index.min(len - 1) as isize;
+        let next = (current + delta).rem_euclid(len as isize) as usize;
+        if next != switcher.selected_index {
+            switcher.selected_index = next;
+            switcher.list_scroll.scroll_to_item(next);
+            cx.notify();
+        }
+    }
+
+    fn activate_tab_switcher_item(&mut self, tab_id: usize, cx: &mut Context<Self>) {
+        self.close_tab_switcher(cx);
+        if self.tab_bar.active_tab_id() != Some(tab_id) {
+            self.switch_to_tab(tab_id, cx);
+        }
+    }
+
+    pub(super) fn render_tab_switcher(&self, cx: &mut Context<Self>) -> Option<AnyElement> {
+        let switcher = self.tab_switcher.as_ref()?;
+        let i18n = self.i18n();
+        let density = self.ui_density;
+        let active_tab_id = self.tab_bar.active_tab_id();
+        let matches = self.tab_switcher_matches(&switcher.query);
+        let selected_index = switcher.selected_index.min(matches.len().saturating_sub(1));
+        let list_scroll = switcher.list_scroll.clone();
+        let list_height = TAB_SWITCHER_MAX_HEIGHT - 44.0;
+
+        let list_content = if matches.is_empty() {
+            div()
+                .px_2()
+                .py_1()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(i18n.tab_switcher_no_matches)
+                .into_any_element()
+        } else {
+            div()
+                .relative()
+                .w_full()
+                .child(
+                    div()
+                        .id("tab-switcher-list")
+                        .w_full()
+                        .h(px(list_height))
+                        .overflow_y_scroll()
+                        .track_scroll(&list_scroll)
+                        .pr(px(TAB_SWITCHER_SCROLLBAR_GUTTER))
+                        .v_flex()
+                        .gap_1()
+                        .children(matches.into_iter().enumerate().map(
+                            |(index, (tab_id, path))| {
+                                let is_selected = index == selected_index;
+                                div()
+                                    .id(("tab-switcher-item", index))
+                                    .w_full()
+                                    .rounded_md()
+                                    .px_2()
+                                    .py(density.spacing(0.25))
+                                    .cursor_pointer()
+                                    .when(is_selected, |this| {
+                                        this.border_1()
+                                            .border_color(cx.theme().primary.opacity(0.65))
+                                            .bg(cx.theme().secondary.opacity(0.85))
+                                    })
+                                    .when(!is_selected, |this| {
+                                        this.hover(|this| {
+                                            this.bg(cx.theme().secondary.opacity(0.6))
+                                        })
+                                    })
+                                    .on_mouse_move(cx.listener(
+                                        move |this, _: &MouseMoveEvent, _, cx| {
+                                            if let Some(switcher) = this.tab_switcher.as_mut()
+                                                && switcher.selected_index != index
+                                            {
+                                                switcher.selected_index = index;
+                                                cx.notify();
+                                            }
+                                        },
+                                    ))
+                                    .on_click(cx.listener(move |this, _, _, cx| {
+                                        this.activate_tab_switcher_item(tab_id, cx);
+                                    }))
+                                    .child(
+                                        div()
+                                            .w_full()
+                                            .flex()
+                                            .items_center()
+                                            .justify_between()
+                                            .child(
+                                                div()
+                                                    .v_flex()
+                                                    .flex_1()
+                                                    .overflow_x_hidden()
+                                                    .items_start()
+                                                    .gap_1()
+                                                    .child(
+                                                        div()
+                                                            .w_full()
+                                                            .truncate()
+                                                            .text_sm()
+                                                            .text_color(cx.theme().foreground)
+                                                            .child(display_file_name(&path)),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .w_full()
+                                                            .truncate()
+                                                            .text_xs()
+                                                            .text_color(cx.theme().muted_foreground)
+                                                            .child(path.display().to_string()),
+                                                    ),
+                                            )
+                                            .when(active_tab_id == Some(tab_id), |this| {
+                                                this.child(
+                                                    div()
+                                                        .text_xs()
+                                                        .text_color(cx.theme().primary)
+                                                        .child(i18n.command_panel_current_badge),
+                                                )
+                                            }),
+                                    )
+                            },
+                        )),
+                )
+                .child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .right_0()
+                        .bottom_0()
+                        .child(
+                            Scrollbar::vertical(&list_scroll).scrollbar_show(ScrollbarShow::Always),
+                        ),
+                )
+                .into_any_element()
+        };
+
+        Some(
+            div()
+                .id("tab-switcher-overlay")
+                .absolute()
+                .top_0()
+                .left_0()
+                .right_0()
+                .bottom_0()
+                .bg(cx.theme().background.opacity(0.45))
+                .on_scroll_wheel(cx.listener(|_, _: &ScrollWheelEvent, _, cx| {
+                    cx.stop_propagation();
+                }))
+                .on_mouse_down(
+                    MouseButton::Left,
+                    cx.listener(|this, _, _, cx| {
+                        this.close_tab_switcher(cx);
+                    }),
+                )
+                .child(
+                    div()
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .right_0()
+                        .bottom_0()
+                        .v_flex()
+                        .items_center()
+                        .justify_center()
+                        .child(
+                            div()
+                                .id("tab-switcher")
+                                .w(px(TAB_SWITCHER_WIDTH))
+                                .h(px(TAB_SWITCHER_MAX_HEIGHT))
+                                .v_flex()
+                                .gap_2()
+                                .popover_style(cx)
+                                .p_2()
+                                .on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener(|_, _, _, cx| {
+                                        cx.stop_propagation();
+                                    }),
+                                )
+                                .child(
+                                    div()
+                                        .w_full()
+                                        .v_flex()
+                                        .gap_1()
+                                        .child(
+                                            Input::new(&switcher.input_state)
+                                                .small()
+                                                .appearance(false)
+                                                .bordered(false)
+                                                .focus_bordered(false)
+                                                .cleanable(true),
+                                        )
+                                        .child(div().h(px(1.)).bg(cx.theme().border)),
+                                )
+                                .child(list_content),
+                        ),
+                )
+                .into_any_element(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fuzzy_score, index_after_active};
+    use std::path::PathBuf;
+
+    #[test]
+    fn fuzzy_score_matches_subsequences() {
+        assert!(fuzzy_score("", "report.pdf").is_some());
+        assert!(fuzzy_score("rpt", "Report.pdf").is_some());
+        assert!(fuzzy_score("q3 rep", "Q3-report.pdf").is_some());
+        assert_eq!(fuzzy_score("xyz", "report.pdf"), None);
+        assert_eq!(fuzzy_score("tr", "report.pdf"), None);
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_runs_and_word_starts() {
+        let run = fuzzy_score("man", "manual.pdf").unwrap();
+        let scattered = fuzzy_score("man", "my-annual.pdf").unwrap();
+        assert!(run > scattered);
+
+        let word_start = fuzzy_score("r", "tax-return.pdf").unwrap();
+        let inner = fuzzy_score("r", "statement.pdf").unwrap_or(0);
+        assert!(word_start > inner);
+    }
+
+    #[test]
+    fn selection_starts_after_the_active_tab() {
+        let tabs = [3, 7, 9]
+            .map(|id| (id, PathBuf::from(format!("{id}.pdf"))))
+            .to_vec();
+        assert_eq!(index_after_active(&tabs, Some(3)), 1);
+        assert_eq!(index_after_active(&tabs, Some(7)), 2);
+        assert_eq!(index_after_active(&tabs, Some(9)), 0);
+        assert_eq!(index_after_active(&tabs, None), 0);
+        assert_eq!(index_after_active(&tabs[..1], Some(3)), 0);
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, Weak};
 
 /// Represents a text selection range on a specific page
 #[derive(Clone, Debug, PartialEq)]
@@ -376,9 +376,13 @@ impl TextSelectionManager {
     }
 
     pub fn clear_cache(&mut self) {
-        if let Ok(mut caches) = self.text_caches.lock() {
-            caches.clear();
-        }
+        // Swap in a fresh map so background extraction for the old document writes nowhere.
+        self.text_caches = Arc::new(Mutex::new(HashMap::new()));
+    }
+
+    /// Handle for background text extraction; it stops upgrading once the cache is reset.
+    pub fn text_cache_handle(&self) -> Weak<Mutex<HashMap<usize, PageTextCache>>> {
+        Arc::downgrade(&self.text_caches)
     }
 
     /// Load cached text data directly (used by async loading)
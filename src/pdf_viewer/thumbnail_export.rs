@@ -0,0 +1,136 @@
+//! Batch thumbnail export for `kpdf thumbs`: renders the first (or every) page of many
+//! documents to PNG files, on several threads.
+
+use super::formats;
+use super::renderer::renderer_for;
+use super::utils::{display_file_name, load_document_summary};
+use crate::i18n::Language;
+use anyhow::{Context as _, Result, anyhow};
+use kpdf_core::render::{RenderEngine, RenderOptions};
+use kpdf_core::render_cache::{self, RenderCacheKey};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Renders thumbnails `width` pixels wide into `output_dir` and returns one result per
+/// page: the PNG written, or why that page failed.
+///
+/// Directories in `inputs` contribute the documents directly inside them. Pages already in
+/// the viewer's render cache at this width are copied from there instead of re-rendered.
+pub(crate) fn export_thumbnails(
+    inputs: &[PathBuf],
+    width: u32,
+    all_pages: bool,
+    output_dir: &Path,
+    language: Language,
+) -> Result<Vec<Result<PathBuf>>> {
+    let mut documents = Vec::new();
+    for input in inputs {
+        if input.is_dir() {
+            let mut entries = std::fs::read_dir(input)?
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.is_file() && formats::is_supported_document(path))
+                .collect::<Vec<_>>();
+            entries.sort();
+            documents.extend(entries);
+        } else {
+            documents.push(input.clone());
+        }
+    }
+    std::fs::create_dir_all(output_dir)?;
+
+    let mut results = Vec::new();
+    let mut jobs = Vec::new();
+    for document in documents {
+        if !all_pages {
+            jobs.push((document, 0, false));
+            continue;
+        }
+        match load_document_summary(&document, language, 0, 0, |_, _| {}) {
+            Ok((page_count, _)) => {
+                jobs.extend((0..page_count).map(|page_index| (document.clone(), page_index, true)))
+            }
+            Err(err) => results.push(Err(err.context(document.display().to_string()))),
+        }
+    }
+
+    // Pdfium rasterizes one page at a time, but hashing, cache reads and PNG encoding run
+    // on all workers.
+    let next_job = AtomicUsize::new(0);
+    let finished = Mutex::new(Vec::with_capacity(jobs.len()));
+    let workers = std::thread::available_parallelism()
+        .map(usize::from)
+        .unwrap_or(1)
+        .min(jobs.len());
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                while let Some((document, page_index, numbered)) =
+                    jobs.get(next_job.fetch_add(1, Ordering::Relaxed))
+                {
+                    let result = export_page(
+                        document,
+                        *page_index,
+                        *numbered,
+                        width,
+                        output_dir,
+                        language,
+                    )
+                    .with_context(|| format!("{} page {}", document.display(), page_index + 1));
+                    if let Ok(mut finished) = finished.lock() {
+                        finished.push((document, *page_index, result));
+                    }
+                }
+            });
+        }
+    });
+
+    let mut finished = finished
+        .into_inner()
+        .map_err(|_| anyhow!("thumbnail results lock is poisoned"))?;
+    finished.sort_by(|a, b| a.0.cmp(b.0).then(a.1.cmp(&b.1)));
+    results.extend(finished.into_iter().map(|(_, _, result)| result));
+    Ok(results)
+}
+
+fn export_page(
+    document: &Path,
+    page_index: usize,
+    numbered: bool,
+    width: u32,
+    output_dir: &Path,
+    language: Language,
+) -> Result<PathBuf> {
+    let stem = document
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| display_file_name(document));
+    let output = if numbered {
+        output_dir.join(format!("{stem}-p{}.png", page_index + 1))
+    } else {
+        output_dir.join(format!("{stem}.png"))
+    };
+
+    let cache_key = render_cache::file_hash(document).map(|file_hash| RenderCacheKey {
+        file_hash,
+        page_index,
+        width,
+        rotation: 0,
+        engine: RenderEngine::Pdfium,
+        options: RenderOptions::default(),
+    });
+    let (bitmap_width, bitmap_height, bgra) = match cache_key.as_ref().and_then(render_cache::load)
+    {
+        Some(cached) => cached,
+        None => renderer_for(RenderEngine::Pdfium).render_page(
+            document,
+            page_index,
+            width,
+            RenderOptions::default(),
+            language,
+        )?,
+    };
+    render_cache::save_png(&output, bitmap_width, bitmap_height, &bgra)?;
+    Ok(output)
+}
@@ -1,11 +1,76 @@
-use super::{PdfViewer, SIDEBAR_WIDTH};
+use super::PdfViewer;
 use gpui::prelude::FluentBuilder as _;
 use gpui::*;
+use gpui_component::button::*;
 use gpui_component::scroll::{Scrollbar, ScrollbarShow};
 use gpui_component::*;
 use std::rc::Rc;
 
 impl PdfViewer {
+    fn set_thumbnail_cursor(&mut self, page_index: usize, cx: &mut Context<Self>) {
+        let Some(tab) = self.active_tab_mut() else {
+            return;
+        };
+        let Some(last) = tab.pages.len().checked_sub(1) else {
+            return;
+        };
+        let page_index = page_index.min(last);
+        tab.thumbnail_cursor = Some(page_index);
+        tab.thumbnail_scroll.scroll_to_item(page_index, ScrollStrategy::Center);
+        cx.notify();
+    }
+
+    fn move_thumbnail_cursor(&mut self, delta: isize, cx: &mut Context<Self>) {
+        let Some(tab) = self.active_tab() else {
+            return;
+        };
+        let current = tab.thumbnail_cursor.unwrap_or(tab.active_page);
+        self.set_thumbnail_cursor(current.saturating_add_signed(delta), cx);
+    }
+
+    /// How far Page Up/Down move the cursor: one screenful of thumbnails, keeping one in view.
+    fn thumbnail_page_step(&self) -> isize {
+        self.active_tab()
+            .and_then(|tab| tab.last_thumbnail_visible_range.clone())
+            .map(|range| range.len().saturating_sub(1).max(1) as isize)
+            .unwrap_or(1)
+    }
+
+    /// Handles a key press while the thumbnail panel has focus. Returns `false` for keys it
+    /// ignores.
+    pub(super) fn handle_thumbnail_key(
+        &mut self,
+        key: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let right_to_left = self.page_order_right_to_left();
+        match key {
+            "left" if right_to_left => self.move_thumbnail_cursor(1, cx),
+            "right" if right_to_left => self.move_thumbnail_cursor(-1, cx),
+            "down" | "right" => self.move_thumbnail_cursor(1, cx),
+            "up" | "left" => self.move_thumbnail_cursor(-1, cx),
+            "pagedown" => self.move_thumbnail_cursor(self.thumbnail_page_step(), cx),
+            "pageup" => self.move_thumbnail_cursor(-self.thumbnail_page_step(), cx),
+            "home" => self.set_thumbnail_cursor(0, cx),
+            "end" => self.set_thumbnail_cursor(usize::MAX, cx),
+            "enter" => {
+                if let Some(page_index) = self.active_tab().and_then(|tab| tab.thumbnail_cursor) {
+                    self.select_page(page_index, cx);
+                }
+            }
+            "escape" => {
+                if let Some(tab) = self.active_tab_mut() {
+                    tab.thumbnail_cursor = None;
+                }
+                window.focus(&self.focus_handle);
+                cx.notify();
+            }
+            _ => return false,
+        }
+        true
+    }
+
     pub(super) fn render_thumbnail_panel(
         &self,
         page_count: usize,
@@ -16,13 +81,9 @@ impl PdfViewer {
         let _active_page = self.active_tab_active_page();
 
         div()
-            .h_full()
-            .w(px(SIDEBAR_WIDTH))
-            .flex_none()
-            .border_r_1()
-            .border_color(cx.theme().sidebar_border)
-            .bg(cx.theme().sidebar)
+            .size_full()
             .overflow_hidden()
+            .track_focus(&self.thumbnail_focus_handle)
             .when(page_count == 0, |this| {
                 this.child(
                     div()
@@ -46,8 +107,8 @@ impl PdfViewer {
                                 cx.entity(),
                                 "thumb-virtual-list",
                                 thumbnail_sizes.clone(),
-                                move |viewer, visible_range, _window, cx| {
-                                    let target_width = viewer.thumbnail_target_width(_window);
+                                move |viewer, visible_range, window, cx| {
+                                    let target_width = viewer.thumbnail_target_width(window);
                                     viewer.request_thumbnail_load_for_visible_range(
                                         visible_range.clone(),
                                         target_width,
@@ -55,6 +116,12 @@ impl PdfViewer {
                                     );
 
                                     let active_page = viewer.active_tab_active_page();
+                                    let cursor = viewer
+                                        .active_tab()
+                                        .and_then(|tab| tab.thumbnail_cursor)
+                                        .filter(|_| viewer.thumbnail_focus_handle.is_focused(window));
+                                    let skeleton_only =
+                                        viewer.active_tab().is_some_and(|tab| tab.skeleton_only);
 
                                     visible_range
                                         .map(|ix| {
@@ -66,6 +133,7 @@ impl PdfViewer {
                                             };
                                             let (_, thumb_height) = viewer.thumbnail_card_size(page);
                                             let is_selected = ix == active_page;
+                                            let is_cursor = cursor == Some(ix);
                                             let is_bookmarked = viewer.active_tab_page_bookmarked(ix);
                                             div()
                                                 .id(("thumb-row", ix))
@@ -89,7 +157,9 @@ impl PdfViewer {
                                                         .overflow_hidden()
                                                         .rounded_md()
                                                         .border_1()
-                                                        .border_color(if is_selected {
+                                                        .border_color(if is_cursor {
+                                                            cx.theme().primary
+                                                        } else if is_selected {
                                                             cx.theme().foreground
                                                         } else {
                                                             cx.theme().sidebar_border
@@ -134,8 +204,29 @@ impl PdfViewer {
                                                                                     i18n.thumbnail_render_failed,
                                                                                 ),
                                                                         )
+                                                                        .child(
+                                                                            Button::new(("thumb-render-retry", ix))
+                                                                                .xsmall()
+                                                                                .label(i18n.page_render_retry_button)
+                                                                                .on_click(cx.listener(
+                                                                                    move |this, _, _, cx| {
+                                                                                        cx.stop_propagation();
+                                                                                        this.retry_thumbnail_render(ix, cx);
+                                                                                    },
+                                                                                )),
+                                                                        )
+                                                                    })
+                                                                    .when(skeleton_only, |this| {
+                                                                        this.child(
+                                                                            Icon::new(crate::icons::IconName::File)
+                                                                                .size_5()
+                                                                                .text_color(
+                                                                                    cx.theme()
+                                                                                        .muted_foreground,
+                                                                                ),
+                                                                        )
                                                                     })
-                                                                    .when(!page.thumbnail_failed, |this| {
+                                                                    .when(!page.thumbnail_failed && !skeleton_only, |this| {
                                                                         this.child(
                                                                             spinner::Spinner::new()
                                                                                 .large()
@@ -186,8 +277,14 @@ impl PdfViewer {
                                                 )
                                                 .cursor_pointer()
                                                 .on_click(cx.listener(
-                                                    move |this, _, _, cx| {
+                                                    move |this, _, window, cx| {
                                                         this.select_page(ix, cx);
+                                                        if let Some(tab) = this.active_tab_mut() {
+                                                            tab.thumbnail_cursor = Some(ix);
+                                                        }
+                                                        // Clicks focus the panel on mouse up, after the root has taken
+                                                        // focus on mouse down.
+                                                        window.focus(&this.thumbnail_focus_handle);
                                                     },
                                                 ))
                                                 .into_any_element()
@@ -1,3 +1,7 @@
+use kpdf_core::annotations::{
+    MarkdownNoteEntry, ShapeColor, ShapeEntry, ShapeKind, ShapeStyle, StampContent, StampEntry,
+    StampPreset, TextMarkupColor, TextMarkupEntry, TextMarkupKind, TextMarkupRect,
+};
 use serde::{Deserialize, Serialize};
 
 // 定义拖放状态
@@ -19,6 +23,219 @@ pub enum RecentPopupAnchor {
     TabAddButton,
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub enum SidebarTab {
+    #[default]
+    Thumbnails,
+    Outline,
+    Bookmarks,
+    Annotations,
+    Search,
+}
+
+impl SidebarTab {
+    pub(super) const ALL: [SidebarTab; 5] = [
+        SidebarTab::Thumbnails,
+        SidebarTab::Outline,
+        SidebarTab::Bookmarks,
+        SidebarTab::Annotations,
+        SidebarTab::Search,
+    ];
+
+    pub(super) fn as_stored(self) -> &'static [u8] {
+        match self {
+            SidebarTab::Thumbnails => b"thumbnails",
+            SidebarTab::Outline => b"outline",
+            SidebarTab::Bookmarks => b"bookmarks",
+            SidebarTab::Annotations => b"annotations",
+            SidebarTab::Search => b"search",
+        }
+    }
+
+    pub(super) fn from_stored(raw: &[u8]) -> Option<Self> {
+        Self::ALL.into_iter().find(|tab| tab.as_stored() == raw)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub(super) enum UiDensity {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+impl UiDensity {
+    pub(super) const ALL: [UiDensity; 2] = [UiDensity::Comfortable, UiDensity::Compact];
+
+    pub(super) fn as_stored(self) -> &'static [u8] {
+        match self {
+            UiDensity::Comfortable => b"comfortable",
+            UiDensity::Compact => b"compact",
+        }
+    }
+
+    pub(super) fn from_stored(raw: &[u8]) -> Option<Self> {
+        Self::ALL.into_iter().find(|density| density.as_stored() == raw)
+    }
+
+    /// Padding for list rows and tabs; `comfortable` is in rems.
+    pub(super) fn spacing(self, comfortable: f32) -> Rems {
+        match self {
+            UiDensity::Comfortable => rems(comfortable),
+            UiDensity::Compact => rems(comfortable * 0.5),
+        }
+    }
+
+    /// Fixed row heights shrink by a quarter in the compact layout.
+    pub(super) fn row_height(self, comfortable: f32) -> Pixels {
+        match self {
+            UiDensity::Comfortable => px(comfortable),
+            UiDensity::Compact => px((comfortable * 0.75).round()),
+        }
+    }
+}
+
+/// What holding the mouse button shows over a slide in presentation mode.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub(super) enum PresentationPointer {
+    #[default]
+    Off,
+    Laser,
+    Spotlight,
+}
+
+impl PresentationPointer {
+    pub(super) const ALL: [PresentationPointer; 3] = [
+        PresentationPointer::Off,
+        PresentationPointer::Laser,
+        PresentationPointer::Spotlight,
+    ];
+
+    pub(super) fn as_stored(self) -> &'static [u8] {
+        match self {
+            PresentationPointer::Off => b"off",
+            PresentationPointer::Laser => b"laser",
+            PresentationPointer::Spotlight => b"spotlight",
+        }
+    }
+
+    pub(super) fn from_stored(raw: &[u8]) -> Option<Self> {
+        Self::ALL.into_iter().find(|pointer| pointer.as_stored() == raw)
+    }
+}
+
+/// How often kpdf looks for updates on its own when it starts.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub(super) enum UpdateCheckFrequency {
+    #[default]
+    Never,
+    Daily,
+    Weekly,
+}
+
+impl UpdateCheckFrequency {
+    pub(super) const ALL: [UpdateCheckFrequency; 3] = [
+        UpdateCheckFrequency::Never,
+        UpdateCheckFrequency::Daily,
+        UpdateCheckFrequency::Weekly,
+    ];
+
+    pub(super) fn as_stored(self) -> &'static [u8] {
+        match self {
+            UpdateCheckFrequency::Never => b"never",
+            UpdateCheckFrequency::Daily => b"daily",
+            UpdateCheckFrequency::Weekly => b"weekly",
+        }
+    }
+
+    pub(super) fn from_stored(raw: &[u8]) -> Option<Self> {
+        Self::ALL.into_iter().find(|frequency| frequency.as_stored() == raw)
+    }
+
+    pub(super) fn interval_secs(self) -> Option<u64> {
+        match self {
+            UpdateCheckFrequency::Never => None,
+            UpdateCheckFrequency::Daily => Some(24 * 60 * 60),
+            UpdateCheckFrequency::Weekly => Some(7 * 24 * 60 * 60),
+        }
+    }
+}
+
+/// What the window shows when kpdf starts.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub(super) enum StartupBehavior {
+    /// Reopen the tabs that were open when kpdf last quit.
+    #[default]
+    RestoreTabs,
+    /// A single blank tab.
+    Empty,
+    /// A single tab showing the recent files list.
+    Home,
+}
+
+impl StartupBehavior {
+    pub(super) const ALL: [StartupBehavior; 3] = [
+        StartupBehavior::RestoreTabs,
+        StartupBehavior::Empty,
+        StartupBehavior::Home,
+    ];
+
+    pub(super) fn as_stored(self) -> &'static [u8] {
+        match self {
+            StartupBehavior::RestoreTabs => b"restore_tabs",
+            StartupBehavior::Empty => b"empty",
+            StartupBehavior::Home => b"home",
+        }
+    }
+
+    pub(super) fn from_stored(raw: &[u8]) -> Option<Self> {
+        Self::ALL.into_iter().find(|behavior| behavior.as_stored() == raw)
+    }
+}
+
+/// What double-clicking a page away from its text does.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+pub(super) enum PageDoubleClickAction {
+    #[default]
+    Nothing,
+    FitWidth,
+    ActualSize,
+    ToggleZenMode,
+}
+
+impl PageDoubleClickAction {
+    pub(super) const ALL: [PageDoubleClickAction; 4] = [
+        PageDoubleClickAction::Nothing,
+        PageDoubleClickAction::FitWidth,
+        PageDoubleClickAction::ActualSize,
+        PageDoubleClickAction::ToggleZenMode,
+    ];
+
+    pub(super) fn as_stored(self) -> &'static [u8] {
+        match self {
+            PageDoubleClickAction::Nothing => b"nothing",
+            PageDoubleClickAction::FitWidth => b"fit_width",
+            PageDoubleClickAction::ActualSize => b"actual_size",
+            PageDoubleClickAction::ToggleZenMode => b"toggle_zen_mode",
+        }
+    }
+
+    pub(super) fn from_stored(raw: &[u8]) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.as_stored() == raw)
+    }
+}
+
+/// Where a file was left, as kept in the file-positions tree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct SavedFileView {
+    pub(super) page_index: usize,
+    pub(super) zoom: Option<f32>,
+    /// How far the viewport top was into the page, from 0.0 (top edge) to 1.0.
+    pub(super) page_fraction: f32,
+    /// Page order picked for the file; `None` leaves it to the comic archive setting.
+    pub(super) right_to_left: Option<bool>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub(super) struct BookmarkEntry {
     pub(super) path: PathBuf,
@@ -37,6 +254,24 @@ pub(super) enum LanguagePreference {
     System,
     ZhCn,
     EnUs,
+    JaJp,
+    KoKr,
+    DeDe,
+    EsEs,
+    ArSa,
+}
+
+impl LanguagePreference {
+    pub(super) const ALL: [LanguagePreference; 8] = [
+        LanguagePreference::System,
+        LanguagePreference::ZhCn,
+        LanguagePreference::EnUs,
+        LanguagePreference::JaJp,
+        LanguagePreference::KoKr,
+        LanguagePreference::DeDe,
+        LanguagePreference::EsEs,
+        LanguagePreference::ArSa,
+    ];
 }
 
 impl Default for LanguagePreference {
@@ -45,60 +280,99 @@ impl Default for LanguagePreference {
     }
 }
 
+/// A user-defined "Open with…" entry; `command` may contain `{file}` and `{page}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct ExternalTool {
+    pub(super) name: String,
+    pub(super) command: String,
+}
+
+/// How often and how recently a command panel command was run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct CommandHistoryEntry {
+    pub(super) key: String,
+    pub(super) count: u32,
+    pub(super) last_used_unix_secs: u64,
+}
+
+/// A named snapshot of the open tabs that can be reopened from the command panel.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(super) struct SavedSession {
+    pub(super) name: String,
+    pub(super) tabs: Vec<SessionTab>,
+    #[serde(default)]
+    pub(super) active_index: usize,
+    pub(super) saved_at_unix_secs: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub(super) struct MarkdownNoteEntry {
-    pub(super) id: u64,
+pub(super) struct SessionTab {
     pub(super) path: PathBuf,
     pub(super) page_index: usize,
-    pub(super) x_ratio: f32,
-    pub(super) y_ratio: f32,
-    pub(super) markdown: String,
-    pub(super) created_at_unix_secs: u64,
-    pub(super) updated_at_unix_secs: u64,
-    /// 如果是从文本选区创建的，存储选中的文本和矩形区域
-    #[serde(default)]
-    pub(super) selected_text: String,
-    #[serde(default)]
-    pub(super) selection_rects: Vec<TextMarkupRect>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(super) zoom: Option<f32>,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "snake_case")]
-pub(super) enum TextMarkupKind {
-    Highlight,
-    Underline,
+/// Author and per-tool colors applied to newly created annotations, saved as one JSON
+/// preference.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub(super) struct AnnotationDefaults {
+    pub(super) author: String,
+    pub(super) highlight_color: TextMarkupColor,
+    pub(super) shape_color: ShapeColor,
+    pub(super) ink_color: ShapeColor,
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Default)]
-#[serde(rename_all = "snake_case")]
-pub(super) enum TextMarkupColor {
-    #[default]
-    Yellow,
-    Green,
-    Blue,
-    Pink,
+impl AnnotationDefaults {
+    pub(super) fn author(&self) -> Option<String> {
+        let author = self.author.trim();
+        (!author.is_empty()).then(|| author.to_string())
+    }
+
+    pub(super) fn shape_color(&self, kind: ShapeKind) -> ShapeColor {
+        if kind == ShapeKind::Ink {
+            self.ink_color
+        } else {
+            self.shape_color
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub(super) struct TextMarkupRect {
-    pub(super) left_ratio: f32,
-    pub(super) top_ratio: f32,
-    pub(super) right_ratio: f32,
-    pub(super) bottom_ratio: f32,
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum EraserMode {
+    /// Cuts the strokes under the pointer, keeping the pieces on either side.
+    Partial,
+    WholeStroke,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub(super) struct TextMarkupEntry {
-    pub(super) id: u64,
-    pub(super) path: PathBuf,
+impl EraserMode {
+    pub(super) const ALL: [EraserMode; 2] = [EraserMode::Partial, EraserMode::WholeStroke];
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum ShapeDragTarget {
+    Start,
+    End,
+    Body,
+}
+
+/// Undo and redo snapshots of one document's shapes and ink.
+#[derive(Debug, Default)]
+pub(super) struct ShapeHistory {
+    pub(super) undo: Vec<Vec<ShapeEntry>>,
+    pub(super) redo: Vec<Vec<ShapeEntry>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ShapeDrag {
+    pub(super) shape_id: u64,
     pub(super) page_index: usize,
-    pub(super) kind: TextMarkupKind,
-    #[serde(default)]
-    pub(super) color: TextMarkupColor,
-    pub(super) selected_text: String,
-    pub(super) rects: Vec<TextMarkupRect>,
-    pub(super) created_at_unix_secs: u64,
-    pub(super) updated_at_unix_secs: u64,
+    pub(super) target: ShapeDragTarget,
+    pub(super) pointer_origin: (f32, f32),
+    /// Endpoints when the drag started, as `[start_x, start_y, end_x, end_y]` ratios.
+    pub(super) original: [f32; 4],
+    pub(super) creating: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -118,27 +392,122 @@ pub(super) enum UpdaterUiState {
     Available {
         latest_version: String,
         download_url: String,
+        /// Present when the installer can be downloaded and verified in-app.
+        asset: Option<updater::UpdateAsset>,
+        download: UpdateDownload,
     },
     Error {
         message: String,
     },
 }
 
-#[derive(Debug, Clone, Copy)]
-pub(super) struct TitleBarVisibilityPreferences {
-    pub(super) show_navigation: bool,
-    pub(super) show_zoom: bool,
+/// Progress of downloading an available update inside the about dialog.
+#[derive(Debug, Clone, Default)]
+pub(super) enum UpdateDownload {
+    #[default]
+    NotStarted,
+    InProgress(DownloadProgress),
+    Failed(String),
+    Done(PathBuf),
+}
+
+/// A button group that can be placed in the title-bar toolbar.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum ToolbarItem {
+    Navigation,
+    Search,
+    Bookmark,
+    Zoom,
+}
+
+impl ToolbarItem {
+    pub(super) const ALL: [ToolbarItem; 4] = [
+        ToolbarItem::Navigation,
+        ToolbarItem::Search,
+        ToolbarItem::Bookmark,
+        ToolbarItem::Zoom,
+    ];
+
+    fn visible_by_default(self) -> bool {
+        self != ToolbarItem::Search
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub(super) struct ToolbarEntry {
+    pub(super) item: ToolbarItem,
+    pub(super) visible: bool,
+}
+
+/// Order and visibility of the title-bar toolbar; every item appears exactly once.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) struct TitleBarPreferences {
+    pub(super) toolbar: [ToolbarEntry; ToolbarItem::ALL.len()],
 }
 
-impl Default for TitleBarVisibilityPreferences {
+impl Default for TitleBarPreferences {
     fn default() -> Self {
         Self {
-            show_navigation: true,
-            show_zoom: true,
+            toolbar: ToolbarItem::ALL.map(|item| ToolbarEntry {
+                item,
+                visible: item.visible_by_default(),
+            }),
         }
     }
 }
 
+impl TitleBarPreferences {
+    /// Keeps the stored order, dropping duplicates and appending items the store predates.
+    pub(super) fn from_entries(entries: &[ToolbarEntry]) -> Self {
+        let mut ordered: Vec<ToolbarEntry> = Vec::with_capacity(ToolbarItem::ALL.len());
+        for entry in entries.iter().copied().chain(Self::default().toolbar) {
+            if !ordered.iter().any(|known| known.item == entry.item) {
+                ordered.push(entry);
+            }
+        }
+
+        let mut preferences = Self::default();
+        preferences.toolbar.copy_from_slice(&ordered);
+        preferences
+    }
+
+    pub(super) fn is_visible(&self, item: ToolbarItem) -> bool {
+        self.toolbar
+            .iter()
+            .any(|entry| entry.item == item && entry.visible)
+    }
+
+    pub(super) fn set_visible(&mut self, item: ToolbarItem, visible: bool) -> bool {
+        match self.toolbar.iter_mut().find(|entry| entry.item == item) {
+            Some(entry) if entry.visible != visible => {
+                entry.visible = visible;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Swaps the entry at `index` with its neighbour `offset` steps away.
+    pub(super) fn move_entry(&mut self, index: usize, offset: isize) -> bool {
+        let Some(target) = index.checked_add_signed(offset) else {
+            return false;
+        };
+        if index >= self.toolbar.len() || target >= self.toolbar.len() || target == index {
+            return false;
+        }
+        self.toolbar.swap(index, target);
+        true
+    }
+
+    pub(super) fn visible_items(&self) -> impl Iterator<Item = ToolbarItem> + '_ {
+        self.toolbar
+            .iter()
+            .filter(|entry| entry.visible)
+            .map(|entry| entry.item)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub(super) enum TabLayoutMode {
@@ -151,3 +520,13 @@ impl Default for TabLayoutMode {
         Self::Horizontal
     }
 }
+
+/// Scroll travel within the current gesture, for swipe navigation and slide flips.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct SwipeGesture {
+    pub(super) delta_x: f32,
+    pub(super) delta_y: f32,
+    pub(super) last_event_at: Option<Instant>,
+    /// Set once the gesture has navigated, so its momentum does not navigate again.
+    pub(super) handled: bool,
+}
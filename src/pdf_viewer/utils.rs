@@ -1,68 +1,69 @@
+use super::formats;
+use super::renderer::{Renderer, renderer_for};
+use super::text_selection::TextCharInfo;
+use super::{RenderEngine, SKELETON_PAGE_TEXT_MAX_CHARS};
 use crate::i18n::{I18n, Language};
 use anyhow::{Context as _, Result, anyhow};
-use gpui::RenderImage as GpuiRenderImage;
+use gpui::{RenderImage as GpuiRenderImage, SharedString};
 use image::{Frame as RasterFrame, RgbaImage};
-use pdfium_render::prelude::*;
-use std::collections::HashSet;
+use kpdf_core::document;
+use kpdf_core::pdfium;
+use kpdf_core::render::RenderOptions;
+use kpdf_core::render_cache::{self, RenderCacheKey};
+use kpdf_core::{repair, skeleton};
+use pdfium_render::prelude::Pdfium;
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::sync::Arc;
 use std::time::Instant;
 use std::time::SystemTime;
 
+pub(super) use kpdf_core::document::{
+    OutlineEntry, PageLink, PageLinkTarget, TextSearchHit, is_damaged_document_error,
+};
+pub(super) use kpdf_core::pdfium::{
+    PdfiumDiagnostics, downloaded_pdfium_libraries, install_user_pdfium_library,
+    pdfium_access_guard, pdfium_versions_dir, record_pdfium_render_error,
+    set_preferred_pdfium_library,
+};
+
 #[derive(Clone)]
 pub struct PageSummary {
     pub index: usize,
     pub width_pt: f32,
     pub height_pt: f32,
+    /// Page label from the document (e.g. "iv"), when it differs from the page number.
+    pub label: Option<String>,
     pub thumbnail_image: Option<Arc<GpuiRenderImage>>,
     pub thumbnail_render_width: u32,
     pub thumbnail_failed: bool,
     pub display_image: Option<Arc<GpuiRenderImage>>,
     pub display_render_width: u32,
     pub display_failed: bool,
+    /// Text read without Pdfium, shown in place of the page when the library is missing.
+    pub skeleton_text: Option<SharedString>,
 }
 
-static PDFIUM_INSTANCE: OnceLock<Pdfium> = OnceLock::new();
-static PDFIUM_INIT_LOCK: Mutex<()> = Mutex::new(());
-static PDFIUM_ACCESS_LOCK: Mutex<()> = Mutex::new(());
-static PDFIUM_DOCUMENT_CACHE: OnceLock<Mutex<Option<CachedPdfDocument>>> = OnceLock::new();
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct CachedPdfDocumentKey {
-    canonical_path: PathBuf,
-    file_len: Option<u64>,
-    modified: Option<SystemTime>,
-}
-
-struct CachedPdfDocument {
-    key: CachedPdfDocumentKey,
-    document: PdfDocument<'static>,
-}
-
-fn shared_pdfium(language: Language) -> Result<&'static Pdfium> {
-    if let Some(pdfium) = PDFIUM_INSTANCE.get() {
-        return Ok(pdfium);
-    }
-
-    let _init_guard = PDFIUM_INIT_LOCK
-        .lock()
-        .map_err(|_| anyhow!("Pdfium init lock is poisoned"))?;
-
-    if let Some(pdfium) = PDFIUM_INSTANCE.get() {
-        return Ok(pdfium);
+impl PageSummary {
+    fn new(index: usize, width_pt: f32, height_pt: f32, label: Option<String>) -> Self {
+        Self {
+            index,
+            width_pt,
+            height_pt,
+            label,
+            thumbnail_image: None,
+            thumbnail_render_width: 0,
+            thumbnail_failed: false,
+            display_image: None,
+            display_render_width: 0,
+            display_failed: false,
+            skeleton_text: None,
+        }
     }
-
-    let pdfium = init_pdfium(language)?;
-    let _ = PDFIUM_INSTANCE.set(pdfium);
-    PDFIUM_INSTANCE
-        .get()
-        .ok_or_else(|| anyhow!("Pdfium initialized but instance is unavailable"))
 }
 
-fn pdfium_access_guard() -> Result<MutexGuard<'static, ()>> {
-    PDFIUM_ACCESS_LOCK
-        .lock()
-        .map_err(|_| anyhow!("Pdfium global access lock is poisoned"))
+/// Binds Pdfium on first use, explaining a missing library in the UI language.
+pub(super) fn shared_pdfium(language: Language) -> Result<&'static Pdfium> {
+    pdfium::shared_pdfium().context(I18n::new(language).pdfium_not_found)
 }
 
 pub(super) fn ensure_pdfium_ready(language: Language) -> Result<()> {
@@ -70,205 +71,202 @@ pub(super) fn ensure_pdfium_ready(language: Language) -> Result<()> {
     shared_pdfium(language).map(|_| ())
 }
 
-fn app_resources_lib_dir(current_exe: &Path) -> Option<PathBuf> {
-    let macos_dir = current_exe.parent()?;
-    if macos_dir.file_name()?.to_string_lossy() != "MacOS" {
-        return None;
-    }
-    let contents_dir = macos_dir.parent()?;
-    if contents_dir.file_name()?.to_string_lossy() != "Contents" {
-        return None;
+pub(super) fn pdfium_diagnostics(language: Language) -> PdfiumDiagnostics {
+    let i18n = I18n::new(language);
+    let diagnostics = pdfium::pdfium_diagnostics();
+    PdfiumDiagnostics {
+        load_error: diagnostics
+            .load_error
+            .as_ref()
+            .map(|error| format!("{}: {error}", i18n.pdfium_not_found)),
+        ..diagnostics
     }
-
-    Some(contents_dir.join("Resources").join("lib"))
 }
 
-#[cfg(target_os = "linux")]
-fn linux_packaged_lib_dir(current_exe: &Path) -> Option<PathBuf> {
-    let exe_dir = current_exe.parent()?;
-    if exe_dir.file_name()?.to_string_lossy() != "bin" {
-        return None;
-    }
-    let prefix_dir = exe_dir.parent()?;
-    Some(prefix_dir.join("lib").join("kpdf").join("lib"))
+/// Runs a [`document`] call on the PDF behind `path`, converting other formats first and
+/// without holding the Pdfium lock. A missing Pdfium library and a file Pdfium cannot open
+/// are explained in the UI language.
+pub(super) fn with_pdf_source<T>(
+    path: &Path,
+    language: Language,
+    f: impl FnOnce(&Path) -> Result<T>,
+) -> Result<T> {
+    let source = formats::pdf_source(path, language)?;
+    ensure_pdfium_ready(language)?;
+    f(&source).map_err(|err| {
+        if err.is::<document::OpenError>() {
+            err.context(I18n::new(language).pdfium_cannot_open_file(path))
+        } else {
+            err
+        }
+    })
 }
 
-fn push_library_dir(
-    candidates: &mut Vec<PathBuf>,
-    seen: &mut HashSet<PathBuf>,
-    candidate: PathBuf,
-) {
-    if candidate.as_os_str().is_empty() {
-        return;
-    }
-
-    let normalized = if candidate.exists() {
-        candidate.canonicalize().unwrap_or(candidate)
-    } else if candidate.is_relative() {
-        std::env::current_dir()
-            .map(|cwd| cwd.join(&candidate))
-            .unwrap_or(candidate)
-    } else {
-        candidate
-    };
-
-    if seen.insert(normalized.clone()) {
-        candidates.push(normalized);
+/// Whether a save to `output` would land on `input`. Edits read the source while writing the
+/// copy, so saving over it would truncate it first; paths are compared canonicalized so
+/// another spelling of the same file is caught too.
+pub(super) fn is_same_file(input: &Path, output: &Path) -> bool {
+    match (input.canonicalize(), output.canonicalize()) {
+        (Ok(input), Ok(output)) => input == output,
+        _ => input == output,
     }
 }
 
-fn collect_library_dirs() -> Vec<PathBuf> {
-    let mut candidates = Vec::new();
-    let mut seen = HashSet::new();
-
-    if let Ok(override_dir) = std::env::var("KPDF_PDFIUM_LIB_DIR") {
-        let override_dir = override_dir.trim();
-        if !override_dir.is_empty() {
-            push_library_dir(&mut candidates, &mut seen, PathBuf::from(override_dir));
-        }
-    }
-
-    if let Ok(current_exe) = std::env::current_exe() {
-        if let Some(resources_lib_dir) = app_resources_lib_dir(&current_exe) {
-            push_library_dir(&mut candidates, &mut seen, resources_lib_dir);
-        }
-        #[cfg(target_os = "linux")]
-        if let Some(packaged_lib_dir) = linux_packaged_lib_dir(&current_exe) {
-            push_library_dir(&mut candidates, &mut seen, packaged_lib_dir);
-        }
-
-        if let Some(exe_dir) = current_exe.parent() {
-            push_library_dir(&mut candidates, &mut seen, exe_dir.join("lib"));
-            push_library_dir(&mut candidates, &mut seen, exe_dir.to_path_buf());
+pub(super) fn display_file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+}
 
-            for ancestor in exe_dir.ancestors().take(6) {
-                push_library_dir(&mut candidates, &mut seen, ancestor.join("lib"));
-            }
-        }
+/// Reads up to `max_pages` page summaries starting at `start` and returns them with the
+/// document's total page count. `on_progress` receives the number of pages read from the
+/// start of the document and the total after each page.
+///
+/// Huge documents are walked in chunks so the tab becomes usable after the first one; the
+/// Pdfium lock is released between chunks so renders can interleave.
+pub(super) fn load_document_summary(
+    path: &Path,
+    language: Language,
+    start: usize,
+    max_pages: usize,
+    on_progress: impl FnMut(usize, usize),
+) -> Result<(usize, Vec<PageSummary>)> {
+    if start == 0 {
+        crate::debug_log!("[pdf][load] opening: {}", path.display());
     }
-
-    #[cfg(target_os = "linux")]
-    {
-        push_library_dir(
-            &mut candidates,
-            &mut seen,
-            PathBuf::from("/usr/lib/kpdf/lib"),
-        );
-        push_library_dir(
-            &mut candidates,
-            &mut seen,
-            PathBuf::from("/usr/local/lib/kpdf/lib"),
-        );
+    let (total_pages, pages) = with_pdf_source(path, language, |source| {
+        document::page_infos(source, start, max_pages, on_progress)
+    })?;
+    if start == 0 {
+        crate::debug_log!("[pdf][load] document loaded, pages: {}", total_pages);
     }
 
-    if let Ok(current_dir) = std::env::current_dir() {
-        push_library_dir(&mut candidates, &mut seen, current_dir.join("lib"));
-        push_library_dir(&mut candidates, &mut seen, current_dir);
-    }
+    crate::debug_log!(
+        "[pdf][load] summary loaded, pages {}..{} of {}",
+        start,
+        start + pages.len(),
+        total_pages
+    );
+    let pages = pages
+        .into_iter()
+        .map(|page| PageSummary::new(page.index, page.width_pt, page.height_pt, page.label))
+        .collect();
+    Ok((total_pages, pages))
+}
 
-    push_library_dir(&mut candidates, &mut seen, PathBuf::from("./lib"));
-    push_library_dir(&mut candidates, &mut seen, PathBuf::from("./"));
+/// Page summaries read without Pdfium, used when the library cannot be loaded so the tab
+/// still shows the document's page layout and whatever text the pages carry.
+pub(super) fn load_document_skeleton(path: &Path, language: Language) -> Result<Vec<PageSummary>> {
+    let i18n = I18n::new(language);
+    let bytes = std::fs::read(path).with_context(|| i18n.pdfium_cannot_open_file(path))?;
+    let sizes =
+        skeleton::page_sizes(&bytes).ok_or_else(|| anyhow!(i18n.pdfium_cannot_open_file(path)))?;
+    crate::debug_log!(
+        "[pdf][load] skeleton loaded without pdfium, {} pages",
+        sizes.len()
+    );
 
-    candidates
+    let mut texts = skeleton::page_texts(&bytes).into_iter();
+    Ok(sizes
+        .into_iter()
+        .enumerate()
+        .map(|(index, (width_pt, height_pt))| {
+            let mut page = PageSummary::new(index, width_pt, height_pt, None);
+            page.skeleton_text = texts
+                .next()
+                .map(|text| {
+                    text.trim()
+                        .chars()
+                        .take(SKELETON_PAGE_TEXT_MAX_CHARS)
+                        .collect::<String>()
+                })
+                .filter(|text| !text.is_empty())
+                .map(SharedString::from);
+            page
+        })
+        .collect())
 }
 
-fn init_pdfium(language: Language) -> Result<Pdfium> {
+/// Writes a cleaned-up copy of a damaged PDF and returns its path. Later reads of `path` open
+/// the copy instead, see [`formats::repaired_copy`].
+///
+/// Bytes before the `%PDF-` header and after the last `%%EOF` marker are dropped, then
+/// Pdfium re-saves the document so its cross-reference table is rebuilt. When Pdfium's own
+/// reconstruction gives up, the object table is scavenged from raw `obj` headers instead.
+pub(super) fn repair_document_copy(path: &Path, language: Language) -> Result<PathBuf> {
+    let _access_guard = pdfium_access_guard()?;
     let i18n = I18n::new(language);
+    crate::debug_log!("[pdf][repair] repairing: {}", path.display());
 
-    crate::debug_log!("[pdfium] starting init...");
+    let bytes = std::fs::read(path).with_context(|| i18n.pdfium_cannot_open_file(path))?;
+    let start = find_subslice(&bytes, b"%PDF-").unwrap_or(0);
+    let end = rfind_subslice(&bytes, b"%%EOF")
+        .map(|ix| ix + b"%%EOF".len())
+        .filter(|end| *end > start)
+        .unwrap_or(bytes.len());
 
-    for lib_dir in collect_library_dirs() {
-        let lib_path = Pdfium::pdfium_platform_library_name_at_path(&lib_dir);
-        let display = lib_path.to_string_lossy().into_owned();
-        crate::debug_log!("[pdfium] trying path: {}", display);
-
-        if !lib_path.exists() {
-            crate::debug_log!("[pdfium] {} skipped: not found", display);
-            continue;
+    let pdfium = shared_pdfium(language)?;
+    let trimmed = &bytes[start..end];
+    let document = match pdfium.load_pdf_from_byte_slice(trimmed, None) {
+        Ok(document) => document,
+        Err(err) => {
+            crate::debug_log!(
+                @warn,
+                "[pdf][repair] pdfium rebuild failed, scanning objects: {}",
+                err
+            );
+            let rebuilt = repair::rebuild_xref(trimmed)
+                .ok_or(err)
+                .with_context(|| i18n.pdfium_cannot_open_file(path))?;
+            pdfium
+                .load_pdf_from_byte_vec(rebuilt, None)
+                .with_context(|| i18n.pdfium_cannot_open_file(path))?
         }
+    };
 
-        match Pdfium::bind_to_library(lib_path) {
-            Ok(bindings) => {
-                crate::debug_log!("[pdfium] loaded from {}", display);
-                crate::debug_log!("[pdfium] init success!");
-                return Ok(Pdfium::new(bindings));
-            }
-            Err(e) => crate::debug_log!("[pdfium] {} failed: {}", display, e),
-        }
+    let repaired_path = formats::repaired_path(path)?;
+    if let Some(directory) = repaired_path.parent() {
+        std::fs::create_dir_all(directory)?;
     }
-
-    crate::debug_log!("[pdfium] trying system library");
-    let bindings = Pdfium::bind_to_system_library();
-    match &bindings {
-        Ok(_) => crate::debug_log!("[pdfium] loaded from system"),
-        Err(e) => crate::debug_log!("[pdfium] system failed: {}", e),
+    // Readers switch to the copy as soon as it exists, so it must never be half-written.
+    let partial = repaired_path.with_extension(format!("{}.partial", std::process::id()));
+    if let Err(err) = document.save_to_file(&partial) {
+        let _ = std::fs::remove_file(&partial);
+        return Err(err.into());
     }
+    std::fs::rename(&partial, &repaired_path)?;
 
-    let bindings = bindings.context(i18n.pdfium_not_found)?;
-    crate::debug_log!("[pdfium] init success!");
-    Ok(Pdfium::new(bindings))
-}
-
-fn document_cache() -> &'static Mutex<Option<CachedPdfDocument>> {
-    PDFIUM_DOCUMENT_CACHE.get_or_init(|| Mutex::new(None))
+    crate::debug_log!("[pdf][repair] wrote repaired copy: {}", repaired_path.display());
+    Ok(repaired_path)
 }
 
-fn document_cache_key(path: &Path) -> CachedPdfDocumentKey {
-    let canonical_path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
-    let metadata = std::fs::metadata(&canonical_path).ok();
-
-    CachedPdfDocumentKey {
-        canonical_path,
-        file_len: metadata.as_ref().map(|meta| meta.len()),
-        modified: metadata.and_then(|meta| meta.modified().ok()),
-    }
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
 }
 
-pub(super) fn display_file_name(path: &Path) -> String {
-    path.file_name()
-        .map(|name| name.to_string_lossy().into_owned())
-        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+fn rfind_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .rposition(|window| window == needle)
 }
 
-pub(super) fn load_document_summary(path: &Path, language: Language) -> Result<Vec<PageSummary>> {
-    let _access_guard = pdfium_access_guard()?;
-    let i18n = I18n::new(language);
-    crate::debug_log!("[pdf][load] opening: {}", path.display());
-
-    let pdfium = shared_pdfium(language)?;
-    crate::debug_log!("[pdf][load] pdfium loaded");
-
-    let document = pdfium
-        .load_pdf_from_file(path, None)
-        .with_context(|| i18n.pdfium_cannot_open_file(path))?;
-    crate::debug_log!(
-        "[pdf][load] document loaded, pages: {}",
-        document.pages().len()
-    );
-
-    let total_pages = document.pages().len() as usize;
-    let mut pages = Vec::with_capacity(total_pages);
-
-    for ix in 0..total_pages {
-        let page = document.pages().get(ix as u16)?;
-        let width_pt = page.width().value as f32;
-        let height_pt = page.height().value as f32;
-
-        pages.push(PageSummary {
-            index: ix,
-            width_pt,
-            height_pt,
-            thumbnail_image: None,
-            thumbnail_render_width: 0,
-            thumbnail_failed: false,
-            display_image: None,
-            display_render_width: 0,
-            display_failed: false,
-        });
+pub(super) struct PdfiumRenderer;
+
+impl Renderer for PdfiumRenderer {
+    fn render_page(
+        &self,
+        path: &Path,
+        page_index: usize,
+        target_width: u32,
+        options: RenderOptions,
+        language: Language,
+    ) -> Result<(u32, u32, Vec<u8>)> {
+        with_pdf_source(path, language, |source| {
+            document::render_page(source, page_index, target_width, options)
+        })
     }
-
-    crate::debug_log!("[pdf][load] summary loaded, {} pages", pages.len());
-    Ok(pages)
 }
 
 pub(super) fn load_display_images(
@@ -276,44 +274,20 @@ pub(super) fn load_display_images(
     page_indices: &[usize],
     target_width: u32,
     language: Language,
+    engine: RenderEngine,
+    options: RenderOptions,
+    disk_cache_limit_bytes: Option<u64>,
+    is_cancelled: impl Fn() -> bool,
 ) -> Result<Vec<(usize, Arc<GpuiRenderImage>)>> {
-    let _access_guard = pdfium_access_guard()?;
     if page_indices.is_empty() {
         return Ok(Vec::new());
     }
+    let file_hash = disk_cache_limit_bytes.and_then(|_| render_cache::file_hash(path));
 
-    let render_config = PdfRenderConfig::new().set_target_width(target_width as i32);
     let mut display_images = Vec::new();
     let mut seen = std::collections::HashSet::new();
     let file_name = display_file_name(path);
-    let cache_key = document_cache_key(path);
-    let i18n = I18n::new(language);
-    let mut cached_document_guard = document_cache()
-        .lock()
-        .map_err(|_| anyhow!(i18n.pdfium_cache_lock_poisoned))?;
-
-    let cache_hit = cached_document_guard
-        .as_ref()
-        .map(|cached| cached.key == cache_key)
-        .unwrap_or(false);
-
-    if !cache_hit {
-        let pdfium = shared_pdfium(language)?;
-        let document = pdfium
-            .load_pdf_from_file(&cache_key.canonical_path, None)
-            .with_context(|| i18n.pdfium_cannot_open_file(path))?;
-
-        *cached_document_guard = Some(CachedPdfDocument {
-            key: cache_key,
-            document,
-        });
-    }
-
-    let document = &cached_document_guard
-        .as_ref()
-        .expect("Pdfium document cache should be initialized")
-        .document;
-    let total_pages = document.pages().len() as usize;
+    let renderer = renderer_for(engine);
     let requested: Vec<usize> = page_indices
         .iter()
         .copied()
@@ -321,58 +295,70 @@ pub(super) fn load_display_images(
         .collect();
 
     for ix in requested {
+        // Checked per page so a superseded batch stops holding the Pdfium lock.
+        if is_cancelled() {
+            crate::debug_log!("[pdf][render] {} p{} cancelled", file_name, ix + 1);
+            break;
+        }
+
         let started_at = Instant::now();
         let page_num = ix + 1;
-
-        if ix >= total_pages || ix > u16::MAX as usize {
+        let cache_key = file_hash.map(|file_hash| RenderCacheKey {
+            file_hash,
+            page_index: ix,
+            width: target_width,
+            rotation: 0,
+            engine,
+            options,
+        });
+        if let Some(key) = cache_key.as_ref()
+            && let Some((width, height, bytes)) = render_cache::load(key)
+            && let Ok(image) = bgra_to_gpui_render_image(width, height, bytes, language)
+        {
             crate::debug_log!(
-                "[pdf][render] {} p{} skipped: out of range (total_pages={}) | {}ms",
+                "[pdf][render] {} p{} disk cache hit | {}ms",
                 file_name,
                 page_num,
-                total_pages,
                 started_at.elapsed().as_millis()
             );
+            display_images.push((ix, image));
             continue;
         }
 
-        let page = match document.pages().get(ix as u16) {
-            Ok(page) => page,
-            Err(err) => {
-                crate::debug_log!(
-                    "[pdf][render] {} p{} failed: get_page error: {} | {}ms",
-                    file_name,
-                    page_num,
-                    err,
-                    started_at.elapsed().as_millis()
-                );
-                continue;
-            }
-        };
-
         let render_started_at = Instant::now();
-        let bitmap = match page.render_with_config(&render_config) {
-            Ok(bitmap) => bitmap,
-            Err(err) => {
-                crate::debug_log!(
-                    "[pdf][render] {} p{} failed: render error: {} | total={}ms render={}ms",
-                    file_name,
-                    page_num,
-                    err,
-                    started_at.elapsed().as_millis(),
-                    render_started_at.elapsed().as_millis()
-                );
-                continue;
-            }
-        };
+        let raw_bitmap = renderer.render_page(path, ix, target_width, options, language);
         let render_elapsed_ms = render_started_at.elapsed().as_millis();
+        if let Err(err) = raw_bitmap.as_ref() {
+            crate::debug_log!(
+                @warn,
+                "[pdf][render] {} p{} failed: {:#} | total={}ms render={}ms",
+                file_name,
+                page_num,
+                err,
+                started_at.elapsed().as_millis(),
+                render_elapsed_ms
+            );
+            continue;
+        }
+
+        if let (Ok((width, height, bytes)), Some(key), Some(limit_bytes)) = (
+            raw_bitmap.as_ref(),
+            cache_key.as_ref(),
+            disk_cache_limit_bytes,
+        ) {
+            render_cache::store(key, *width, *height, bytes, limit_bytes);
+        }
 
         let convert_started_at = Instant::now();
-        match bitmap_to_gpui_render_image(&bitmap, language) {
+        match raw_bitmap.and_then(|(width, height, bytes)| {
+            bgra_to_gpui_render_image(width, height, bytes, language)
+        }) {
             Ok(image) => {
                 display_images.push((ix, image));
             }
             Err(err) => {
                 crate::debug_log!(
+                    @warn,
                     "[pdf][render] {} p{} failed: upload error: {} | total={}ms render={}ms upload={}ms",
                     file_name,
                     page_num,
@@ -388,164 +374,270 @@ pub(super) fn load_display_images(
     Ok(display_images)
 }
 
-#[allow(deprecated)]
-fn bitmap_to_gpui_render_image(
-    bitmap: &PdfBitmap,
-    language: Language,
-) -> Result<Arc<GpuiRenderImage>> {
-    let i18n = I18n::new(language);
-    let width = bitmap.width() as u32;
-    let height = bitmap.height() as u32;
-    if width == 0 || height == 0 {
-        return Err(anyhow!(i18n.invalid_bitmap_size(width, height)));
-    }
-
-    let format = bitmap.format().unwrap_or(PdfBitmapFormat::BGRA);
-    let mut bytes = match format {
-        PdfBitmapFormat::BGRA | PdfBitmapFormat::BGRx | PdfBitmapFormat::BRGx => {
-            bitmap.as_raw_bytes()
-        }
-        _ => rgba_to_bgra(bitmap.as_rgba_bytes()),
-    };
-
-    let expected_len = width as usize * height as usize * 4;
-    if bytes.len() != expected_len {
-        bytes = rgba_to_bgra(bitmap.as_rgba_bytes());
-        if bytes.len() != expected_len {
-            return Err(anyhow!(i18n.bitmap_len_mismatch(bytes.len(), expected_len)));
-        }
-    }
+/// Disk cache bookkeeping for one idle pre-render pass over a document.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct PrerenderPass {
+    /// Entries written since this moment belong to the pass and are never evicted by it.
+    pub started_at: SystemTime,
+    pub limit_bytes: u64,
+    pub written_bytes: u64,
+    pub written_pages: u64,
+}
 
-    if matches!(format, PdfBitmapFormat::BGRx | PdfBitmapFormat::BRGx) {
-        for pixel in bytes.chunks_exact_mut(4) {
-            pixel[3] = 255;
+impl PrerenderPass {
+    pub fn new(limit_bytes: u64) -> Self {
+        Self {
+            started_at: SystemTime::now(),
+            limit_bytes,
+            written_bytes: 0,
+            written_pages: 0,
         }
     }
-
-    let buffer = RgbaImage::from_raw(width, height, bytes)
-        .ok_or_else(|| anyhow!(i18n.cannot_create_image_buffer(width, height)))?;
-    let frame = RasterFrame::new(buffer);
-
-    Ok(Arc::new(GpuiRenderImage::new([frame])))
 }
 
-fn rgba_to_bgra(mut rgba: Vec<u8>) -> Vec<u8> {
-    for pixel in rgba.chunks_exact_mut(4) {
-        pixel.swap(0, 2);
-    }
-    rgba
+pub(super) enum PrerenderProgress {
+    /// The page at this position in `page_indices` was rendered (or failed to render).
+    Rendered(usize),
+    /// Every page is in the disk cache.
+    Complete,
+    /// The pages still missing are not expected to fit in the free part of the cache.
+    OverBudget,
 }
 
-/// Load text information from a page for text selection using pdfium-render high-level API
-pub fn load_page_text_for_selection(
+/// Renders the first of `page_indices` that is not in the disk cache yet and stores it.
+/// Stops once the remaining pages, estimated from the pages this pass has written so far,
+/// would not fit in what is left of the cache limit, so the pass never evicts its
+/// own pages to make room for more.
+pub(super) fn prerender_next_to_disk_cache(
     path: &Path,
-    page_index: usize,
-) -> Result<Option<(usize, f32, f32, Vec<super::text_selection::TextCharInfo>)>> {
-    let _access_guard = pdfium_access_guard()?;
-    let cache_key = document_cache_key(path);
-
-    let mut cached_document_guard = match document_cache().lock() {
-        Ok(guard) => guard,
-        Err(_) => return Ok(None),
+    page_indices: &[usize],
+    target_width: u32,
+    language: Language,
+    engine: RenderEngine,
+    options: RenderOptions,
+    pass: &mut PrerenderPass,
+) -> PrerenderProgress {
+    let Some(file_hash) = render_cache::file_hash(path) else {
+        return PrerenderProgress::Complete;
     };
-
-    let cache_hit = cached_document_guard
-        .as_ref()
-        .map(|cached| cached.key == cache_key)
-        .unwrap_or(false);
-
-    if !cache_hit {
-        let pdfium = match shared_pdfium(Language::EnUs) {
-            Ok(p) => p,
-            Err(_) => return Ok(None),
-        };
-
-        let document = match pdfium.load_pdf_from_file(&cache_key.canonical_path, None) {
-            Ok(doc) => doc,
-            Err(err) => {
-                crate::debug_log!(
-                    "[text] Failed to load document for text extraction: {} | {:?}",
-                    path.display(),
-                    err
-                );
-                return Ok(None);
-            }
-        };
-
-        *cached_document_guard = Some(CachedPdfDocument {
-            key: cache_key,
-            document,
-        });
-    }
-
-    let document = match cached_document_guard.as_ref() {
-        Some(cached) => &cached.document,
-        None => return Ok(None),
+    let key_for = |page_index| RenderCacheKey {
+        file_hash,
+        page_index,
+        width: target_width,
+        rotation: 0,
+        engine,
+        options,
+    };
+    let missing = page_indices
+        .iter()
+        .enumerate()
+        .filter(|(_, ix)| !render_cache::contains(&key_for(**ix)))
+        .map(|(position, ix)| (position, *ix))
+        .collect::<Vec<_>>();
+    let Some(&(position, ix)) = missing.first() else {
+        return PrerenderProgress::Complete;
     };
 
-    let total_pages = document.pages().len() as usize;
-    if page_index >= total_pages {
+    let free_bytes = pass.limit_bytes.saturating_sub(render_cache::usage_bytes());
+    let estimated_bytes = if pass.written_pages == 0 {
+        0
+    } else {
+        pass.written_bytes / pass.written_pages * missing.len() as u64
+    };
+    if free_bytes == 0 || estimated_bytes > free_bytes {
         crate::debug_log!(
-            "[text] Page index {} out of range (total: {})",
-            page_index,
-            total_pages
+            "[pdf][prerender] {} stopped: {} pages need ~{} bytes, {} free",
+            display_file_name(path),
+            missing.len(),
+            estimated_bytes,
+            free_bytes
         );
-        return Ok(None);
+        return PrerenderProgress::OverBudget;
     }
 
-    let page = match document.pages().get(page_index as u16) {
-        Ok(p) => p,
+    let renderer = renderer_for(engine);
+    let started_at = Instant::now();
+    match renderer.render_page(path, ix, target_width, options, language) {
+        Ok((width, height, bytes)) => {
+            if let Some(len) = render_cache::store_keeping_since(
+                &key_for(ix),
+                width,
+                height,
+                &bytes,
+                pass.limit_bytes,
+                Some(pass.started_at),
+            ) {
+                pass.written_bytes += len;
+                pass.written_pages += 1;
+            }
+            crate::debug_log!(
+                "[pdf][prerender] {} p{} cached | {}ms",
+                display_file_name(path),
+                ix + 1,
+                started_at.elapsed().as_millis()
+            );
+        }
         Err(err) => {
             crate::debug_log!(
-                "[text] Failed to get page {} from document: {:?}",
-                page_index,
+                @warn,
+                "[pdf][prerender] {} p{} failed: {:#}",
+                display_file_name(path),
+                ix + 1,
                 err
             );
-            return Ok(None);
         }
-    };
+    }
+    PrerenderProgress::Rendered(position)
+}
+
+/// Renders horizontal bands of one page at `target_width` so huge pages never need a full
+/// bitmap. Each band is `tile_height` device pixels tall (the last one may be shorter).
+pub(super) fn load_display_tiles(
+    path: &Path,
+    page_index: usize,
+    target_width: u32,
+    tile_rows: &[u32],
+    tile_height: u32,
+    options: RenderOptions,
+    language: Language,
+    is_cancelled: impl Fn() -> bool,
+) -> Result<Vec<(u32, Arc<GpuiRenderImage>)>> {
+    if is_cancelled() {
+        return Ok(Vec::new());
+    }
+    let file_name = display_file_name(path);
+    let mut tiles = Vec::new();
+    let mut started_at = Instant::now();
+    with_pdf_source(path, language, |source| {
+        document::render_page_tiles(
+            source,
+            page_index,
+            target_width,
+            tile_rows,
+            tile_height,
+            options,
+            |row, tile| {
+                match tile.and_then(|(width, height, bytes)| {
+                    bgra_to_gpui_render_image(width, height, bytes, language)
+                }) {
+                    Ok(image) => tiles.push((row, image)),
+                    Err(err) => {
+                        crate::debug_log!(
+                            @warn,
+                            "[pdf][tile] {} p{} row{} failed: {} | {}ms",
+                            file_name,
+                            page_index + 1,
+                            row,
+                            err,
+                            started_at.elapsed().as_millis()
+                        );
+                    }
+                }
+                started_at = Instant::now();
+                !is_cancelled()
+            },
+        )
+    })?;
+
+    Ok(tiles)
+}
+
+pub(super) fn bgra_to_gpui_render_image(
+    width: u32,
+    height: u32,
+    bytes: Vec<u8>,
+    language: Language,
+) -> Result<Arc<GpuiRenderImage>> {
+    let i18n = I18n::new(language);
+    let buffer = RgbaImage::from_raw(width, height, bytes)
+        .ok_or_else(|| anyhow!(i18n.cannot_create_image_buffer(width, height)))?;
+    let frame = RasterFrame::new(buffer);
 
-    let page_width = page.width().value as f32;
-    let page_height = page.height().value as f32;
+    Ok(Arc::new(GpuiRenderImage::new([frame])))
+}
 
-    let page_text = match page.text() {
-        Ok(text) => text,
+/// Load text information from a page for text selection using pdfium-render high-level API
+pub fn load_page_text_for_selection(
+    path: &Path,
+    page_index: usize,
+) -> Result<Option<(usize, f32, f32, Vec<TextCharInfo>)>> {
+    let page_text = with_pdf_source(path, Language::EnUs, |source| {
+        document::page_text(source, page_index)
+    });
+    let page_text = match page_text {
+        Ok(Some(page_text)) => page_text,
+        Ok(None) => {
+            crate::debug_log!("[text] Page index {} out of range", page_index);
+            return Ok(None);
+        }
         Err(err) => {
             crate::debug_log!(
-                "[text] Failed to get text from page {}: {:?}",
+                "[text] Failed to load text of page {}: {} | {:#}",
                 page_index,
+                path.display(),
                 err
             );
             return Ok(None);
         }
     };
 
-    let chars_collection = page_text.chars();
+    let chars = page_text
+        .chars
+        .into_iter()
+        .map(|char| TextCharInfo {
+            text: char.text,
+            left: char.left,
+            top: char.top,
+            right: char.right,
+            bottom: char.bottom,
+        })
+        .collect();
+    Ok(Some((
+        page_text.page_index,
+        page_text.width_pt,
+        page_text.height_pt,
+        chars,
+    )))
+}
 
-    if chars_collection.is_empty() {
-        return Ok(Some((page_index, page_width, page_height, Vec::new())));
-    }
+pub(super) fn load_document_outline(path: &Path, language: Language) -> Result<Vec<OutlineEntry>> {
+    let entries = with_pdf_source(path, language, document::outline)?;
+    crate::debug_log!(
+        "[pdf][outline] {} loaded, {} entries",
+        display_file_name(path),
+        entries.len()
+    );
+    Ok(entries)
+}
 
-    let mut chars = Vec::new();
-
-    for char in chars_collection.iter() {
-        let bounds = match char.tight_bounds() {
-            Ok(b) => b,
-            Err(_) => continue,
-        };
-        let text = char
-            .unicode_char()
-            .map(|c| c.to_string())
-            .unwrap_or_default();
-
-        chars.push(super::text_selection::TextCharInfo {
-            text,
-            left: bounds.left().value as f32,
-            top: bounds.top().value as f32,
-            right: bounds.right().value as f32,
-            bottom: bounds.bottom().value as f32,
-        });
-    }
+/// Reads the internal and web links on `page_indices`; links to other files or launch actions
+/// are skipped.
+pub(super) fn load_page_links(
+    path: &Path,
+    page_indices: &[usize],
+    language: Language,
+) -> Result<Vec<PageLink>> {
+    let links = with_pdf_source(path, language, |source| {
+        document::page_links(source, page_indices)
+    })?;
+    crate::debug_log!(
+        "[pdf][links] {} pages {:?}: {} links",
+        display_file_name(path),
+        page_indices,
+        links.len()
+    );
+    Ok(links)
+}
 
-    Ok(Some((page_index, page_width, page_height, chars)))
+pub(super) fn search_document_text(
+    path: &Path,
+    query: &str,
+    language: Language,
+) -> Result<Vec<TextSearchHit>> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    with_pdf_source(path, language, |source| {
+        document::search_text(source, query)
+    })
 }
@@ -0,0 +1,657 @@
+use super::pdf_edit::{
+    Watermark, WatermarkContent, WatermarkPosition, parse_page_ranges, stamp_watermark,
+};
+use super::{
+    WATERMARK_DEFAULT_TEXT, WATERMARK_DIALOG_WIDTH, WATERMARK_DIALOG_WINDOW_HEIGHT,
+    WATERMARK_OPACITY_OPTIONS, WATERMARK_PROGRESS_POLL_MS, WATERMARK_ROTATION_OPTIONS,
+    WATERMARK_SIZE_OPTIONS, WATERMARK_TEXT_COLORS,
+};
+use crate::i18n::{I18n, Language};
+use crate::pdf_viewer::PdfViewer;
+use crate::pdf_viewer::utils::{display_file_name, is_same_file};
+use gpui::prelude::FluentBuilder as _;
+use gpui::*;
+use gpui_component::input::{Input, InputState};
+use gpui_component::scroll::ScrollableElement;
+use gpui_component::{button::*, *};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WatermarkMode {
+    Text,
+    Image,
+}
+
+pub(super) struct WatermarkWindow {
+    viewer: Entity<PdfViewer>,
+    focus_handle: FocusHandle,
+    language: Language,
+    input: PathBuf,
+    page_count: usize,
+    mode: WatermarkMode,
+    text_input: Entity<InputState>,
+    image_path: Option<PathBuf>,
+    color_index: usize,
+    position: WatermarkPosition,
+    opacity_index: usize,
+    rotation_index: usize,
+    size_index: usize,
+    pages_input: Entity<InputState>,
+    /// (pages stamped, pages selected) while saving; `None` when idle.
+    progress: Option<(usize, usize)>,
+    status_message: Option<String>,
+    saved_output: Option<PathBuf>,
+}
+
+impl WatermarkWindow {
+    pub(super) fn new(
+        viewer: Entity<PdfViewer>,
+        language: Language,
+        input: PathBuf,
+        page_count: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let i18n = I18n::new(language);
+        let text_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder(i18n.watermark_text_placeholder));
+        text_input.update(cx, |input, cx| {
+            input.set_value(WATERMARK_DEFAULT_TEXT, window, cx);
+        });
+        let pages_input =
+            cx.new(|cx| InputState::new(window, cx).placeholder(i18n.watermark_pages_placeholder));
+
+        Self {
+            viewer,
+            focus_handle: cx.focus_handle(),
+            language,
+            input,
+            page_count,
+            mode: WatermarkMode::Text,
+            text_input,
+            image_path: None,
+            color_index: 0,
+            position: WatermarkPosition::default(),
+            opacity_index: 1,
+            rotation_index: 2,
+            size_index: 1,
+            pages_input,
+            progress: None,
+            status_message: None,
+            saved_output: None,
+        }
+    }
+
+    fn i18n(&self) -> I18n {
+        I18n::new(self.language)
+    }
+
+    fn choose_image(&mut self, cx: &mut Context<Self>) {
+        let picker = cx.prompt_for_paths(PathPromptOptions {
+            files: true,
+            directories: false,
+            multiple: false,
+            prompt: Some(self.i18n().watermark_image_prompt.into()),
+        });
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(paths))) = picker.await else {
+                return;
+            };
+            let Some(path) = paths.into_iter().next() else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                this.image_path = Some(path);
+                this.mode = WatermarkMode::Image;
+                this.status_message = None;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// The watermark described by the form, or a message explaining what is missing.
+    fn watermark(&self, cx: &App) -> Result<Watermark, String> {
+        let i18n = self.i18n();
+        let content = match self.mode {
+            WatermarkMode::Text => {
+                let text = self.text_input.read(cx).value().trim().to_string();
+                if text.is_empty() {
+                    return Err(i18n.watermark_missing_content.to_string());
+                }
+                WatermarkContent::Text {
+                    text,
+                    color: WATERMARK_TEXT_COLORS[self.color_index],
+                }
+            }
+            WatermarkMode::Image => match &self.image_path {
+                Some(path) => WatermarkContent::Image(path.clone()),
+                None => return Err(i18n.watermark_missing_content.to_string()),
+            },
+        };
+        let pages = parse_page_ranges(&self.pages_input.read(cx).value(), self.page_count)
+            .map_err(|part| i18n.watermark_invalid_pages(&part))?;
+
+        Ok(Watermark {
+            content,
+            position: self.position,
+            opacity: WATERMARK_OPACITY_OPTIONS[self.opacity_index],
+            rotation_degrees: WATERMARK_ROTATION_OPTIONS[self.rotation_index],
+            relative_width: WATERMARK_SIZE_OPTIONS[self.size_index],
+            pages,
+        })
+    }
+
+    fn start_save(&mut self, cx: &mut Context<Self>) {
+        if self.progress.is_some() {
+            return;
+        }
+        let watermark = match self.watermark(cx) {
+            Ok(watermark) => watermark,
+            Err(message) => {
+                self.status_message = Some(message);
+                cx.notify();
+                return;
+            }
+        };
+        let directory = self
+            .input
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let suggested_name = self
+            .input
+            .file_stem()
+            .map(|stem| format!("{}-watermarked.pdf", stem.to_string_lossy()))
+            .unwrap_or_else(|| "watermarked.pdf".to_string());
+        let picker = cx.prompt_for_new_path(&directory, Some(&suggested_name));
+
+        cx.spawn(async move |this, cx| {
+            let Ok(Ok(Some(output))) = picker.await else {
+                return;
+            };
+            let _ = this.update(cx, |this, cx| {
+                this.run_stamp(watermark, output, cx);
+            });
+        })
+        .detach();
+    }
+
+    fn run_stamp(&mut self, watermark: Watermark, output: PathBuf, cx: &mut Context<Self>) {
+        if self.progress.is_some() {
+            return;
+        }
+        if is_same_file(&self.input, &output) {
+            self.status_message = Some(self.i18n().save_copy_same_file.to_string());
+            cx.notify();
+            return;
+        }
+        let input = self.input.clone();
+        let language = self.language;
+        self.progress = Some((0, watermark.pages.len()));
+        self.status_message = None;
+        self.saved_output = None;
+        cx.notify();
+
+        let progress = Arc::new(Mutex::new((0, watermark.pages.len())));
+        let progress_for_task = progress.clone();
+        let finished = Arc::new(AtomicBool::new(false));
+        let finished_for_task = finished.clone();
+        let output_for_task = output.clone();
+        let stamp = cx.background_executor().spawn(async move {
+            let result = stamp_watermark(
+                &input,
+                &output_for_task,
+                &watermark,
+                language,
+                |done, total| {
+                    if let Ok(mut progress) = progress_for_task.lock() {
+                        *progress = (done, total);
+                    }
+                },
+            );
+            finished_for_task.store(true, Ordering::Release);
+            result
+        });
+
+        cx.spawn(async move |this, cx| {
+            while !finished.load(Ordering::Acquire) {
+                cx.background_executor()
+                    .timer(Duration::from_millis(WATERMARK_PROGRESS_POLL_MS))
+                    .await;
+                let snapshot = progress.lock().map(|progress| *progress).ok();
+                let alive = this
+                    .update(cx, |this, cx| {
+                        if this.progress.is_some() {
+                            this.progress = snapshot.or(this.progress);
+                            cx.notify();
+                        }
+                    })
+                    .is_ok();
+                if !alive {
+                    return;
+                }
+            }
+        })
+        .detach();
+
+        cx.spawn(async move |this, cx| {
+            let result = stamp.await;
+            let _ = this.update(cx, |this, cx| {
+                let i18n = this.i18n();
+                this.progress = None;
+                this.status_message = Some(match result {
+                    Ok(count) => {
+                        this.saved_output = Some(output.clone());
+                        i18n.watermark_done(count, &output)
+                    }
+                    Err(err) => {
+                        crate::debug_log!(@warn, "[pdf][edit] watermark failed: {:#}", err);
+                        i18n.watermark_failed(&format!("{err:#}"))
+                    }
+                });
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    fn open_saved_output(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(output) = self.saved_output.clone() else {
+            return;
+        };
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.open_pdf_path_in_new_tab(output, cx);
+        });
+        self.close_dialog(window, cx);
+    }
+
+    fn close_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let _ = self.viewer.update(cx, |viewer, cx| {
+            viewer.close_watermark_dialog(cx);
+        });
+        window.remove_window();
+    }
+
+    fn position_label(i18n: I18n, position: WatermarkPosition) -> &'static str {
+        match position {
+            WatermarkPosition::Center => i18n.watermark_position_center,
+            WatermarkPosition::TopLeft => i18n.watermark_position_top_left,
+            WatermarkPosition::TopRight => i18n.watermark_position_top_right,
+            WatermarkPosition::BottomLeft => i18n.watermark_position_bottom_left,
+            WatermarkPosition::BottomRight => i18n.watermark_position_bottom_right,
+        }
+    }
+}
+
+impl Render for WatermarkWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let i18n = self.i18n();
+        let saving = self.progress.is_some();
+        let percent = |value: f32| format!("{:.0}%", value * 100.0);
+
+        let content = match self.mode {
+            WatermarkMode::Text => div()
+                .v_flex()
+                .gap_2()
+                .child(Input::new(&self.text_input).small())
+                .child(PdfViewer::render_dialog_field(
+                    i18n.watermark_color_label,
+                    PdfViewer::render_choice_group(
+                        "watermark-color",
+                        vec![
+                            i18n.watermark_color_gray.to_string(),
+                            i18n.watermark_color_red.to_string(),
+                        ],
+                        self.color_index,
+                        saving,
+                        |this, ix, _| this.color_index = ix.min(WATERMARK_TEXT_COLORS.len() - 1),
+                        cx,
+                    ),
+                    cx,
+                ))
+                .into_any_element(),
+            WatermarkMode::Image => div()
+                .h_flex()
+                .items_center()
+                .gap_2()
+                .child(
+                    Button::new("watermark-choose-image")
+                        .small()
+                        .label(i18n.watermark_choose_image_button)
+                        .disabled(saving)
+                        .on_click(cx.listener(|this, _, _, cx| {
+                            this.choose_image(cx);
+                        })),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .min_w(px(0.))
+                        .text_sm()
+                        .text_color(cx.theme().muted_foreground)
+                        .truncate()
+                        .child(
+                            self.image_path
+                                .as_deref()
+                                .map(display_file_name)
+                                .unwrap_or_else(|| i18n.watermark_no_image.to_string()),
+                        ),
+                )
+                .into_any_element(),
+        };
+
+        div()
+            .id("watermark-window")
+            .size_full()
+            .v_flex()
+            .bg(cx.theme().background)
+            .focusable()
+            .track_focus(&self.focus_handle)
+            .capture_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                if event.keystroke.key.as_str() == "escape" {
+                    this.close_dialog(window, cx);
+                    cx.stop_propagation();
+                }
+            }))
+            .child(TitleBar::new())
+            .child(
+                div()
+                    .flex_1()
+                    .min_h(px(0.))
+                    .v_flex()
+                    .gap_3()
+                    .p_4()
+                    .child(
+                        div()
+                            .text_lg()
+                            .text_color(cx.theme().foreground)
+                            .child(i18n.watermark_dialog_title),
+                    )
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(cx.theme().foreground)
+                            .truncate()
+                            .child(display_file_name(&self.input)),
+                    )
+                    .child(div().h(px(1.)).bg(cx.theme().border))
+                    .child(
+                        div()
+                            .id("watermark-content")
+                            .flex_1()
+                            .min_h(px(0.))
+                            .overflow_y_scrollbar()
+                            .child(
+                                div()
+                                    .v_flex()
+                                    .gap_3()
+                                    .child(PdfViewer::render_dialog_field(
+                                        i18n.watermark_type_label,
+                                        PdfViewer::render_choice_group(
+                                            "watermark-type",
+                                            vec![
+                                                i18n.watermark_type_text.to_string(),
+                                                i18n.watermark_type_image.to_string(),
+                                            ],
+                                            match self.mode {
+                                                WatermarkMode::Text => 0,
+                                                WatermarkMode::Image => 1,
+                                            },
+                                            saving,
+                                            |this, ix, _| {
+                                                this.mode = if ix == 0 {
+                                                    WatermarkMode::Text
+                                                } else {
+                                                    WatermarkMode::Image
+                                                };
+                                            },
+                                            cx,
+                                        ),
+                                        cx,
+                                    ))
+                                    .child(content)
+                                    .child(PdfViewer::render_dialog_field(
+                                        i18n.watermark_position_label,
+                                        PdfViewer::render_choice_group(
+                                            "watermark-position",
+                                            WatermarkPosition::ALL
+                                                .into_iter()
+                                                .map(|position| {
+                                                    Self::position_label(i18n, position).to_string()
+                                                })
+                                                .collect(),
+                                            WatermarkPosition::ALL
+                                                .iter()
+                                                .position(|position| *position == self.position)
+                                                .unwrap_or(0),
+                                            saving,
+                                            |this, ix, _| {
+                                                if let Some(position) =
+                                                    WatermarkPosition::ALL.get(ix)
+                                                {
+                                                    this.position = *position;
+                                                }
+                                            },
+                                            cx,
+                                        ),
+                                        cx,
+                                    ))
+                                    .child(PdfViewer::render_dialog_field(
+                                        i18n.watermark_opacity_label,
+                                        PdfViewer::render_choice_group(
+                                            "watermark-opacity",
+                                            WATERMARK_OPACITY_OPTIONS
+                                                .into_iter()
+                                                .map(percent)
+                                                .collect(),
+                                            self.opacity_index,
+                                            saving,
+                                            |this, ix, _| {
+                                                this.opacity_index =
+                                                    ix.min(WATERMARK_OPACITY_OPTIONS.len() - 1)
+                                            },
+                                            cx,
+                                        ),
+                                        cx,
+                                    ))
+                                    .child(PdfViewer::render_dialog_field(
+                                        i18n.watermark_rotation_label,
+                                        PdfViewer::render_choice_group(
+                                            "watermark-rotation",
+                                            WATERMARK_ROTATION_OPTIONS
+                                                .into_iter()
+                                                .map(|degrees| format!("{degrees:.0}°"))
+                                                .collect(),
+                                            self.rotation_index,
+                                            saving,
+                                            |this, ix, _| {
+                                                this.rotation_index =
+                                                    ix.min(WATERMARK_ROTATION_OPTIONS.len() - 1)
+                                            },
+                                            cx,
+                                        ),
+                                        cx,
+                                    ))
+                                    .child(PdfViewer::render_dialog_field(
+                                        i18n.watermark_size_label,
+                                        PdfViewer::render_choice_group(
+                                            "watermark-size",
+                                            WATERMARK_SIZE_OPTIONS
+                                                .into_iter()
+                                                .map(percent)
+                                                .collect(),
+                                            self.size_index,
+                                            saving,
+                                            |this, ix, _| {
+                                                this.size_index =
+                                                    ix.min(WATERMARK_SIZE_OPTIONS.len() - 1)
+                                            },
+                                            cx,
+                                        ),
+                                        cx,
+                                    ))
+                                    .child(PdfViewer::render_dialog_field(
+                                        i18n.watermark_pages_label,
+                                        Input::new(&self.pages_input).small(),
+                                        cx,
+                                    )),
+                            ),
+                    )
+                    .when_some(self.progress, |this, (done, total)| {
+                        let fraction = if total == 0 {
+                            0.0
+                        } else {
+                            done as f32 / total as f32
+                        };
+                        this.child(PdfViewer::render_progress_bar(
+                            fraction,
+                            i18n.watermark_progress((done + 1).min(total), total),
+                            cx,
+                        ))
+                    })
+                    .when_some(self.status_message.clone(), |this, message| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .whitespace_normal()
+                                .child(message),
+                        )
+                    })
+                    .child(
+                        div()
+                            .w_full()
+                            .flex()
+                            .items_center()
+                            .justify_end()
+                            .gap_2()
+                            .when(self.saved_output.is_some(), |this| {
+                                this.child(
+                                    Button::new("watermark-open-output")
+                                        .small()
+                                        .label(i18n.watermark_open_button)
+                                        .on_click(cx.listener(|this, _, window, cx| {
+                                            this.open_saved_output(window, cx);
+                                        })),
+                                )
+                            })
+                            .child(
+                                Button::new("watermark-save")
+                                    .small()
+                                    .primary()
+                                    .label(i18n.watermark_save_button)
+                                    .disabled(saving)
+                                    .on_click(cx.listener(|this, _, _, cx| {
+                                        this.start_save(cx);
+                                    })),
+                            )
+                            .child(
+                                Button::new("watermark-close")
+                                    .small()
+                                    .ghost()
+                                    .label(i18n.close_button)
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.close_dialog(window, cx);
+                                    })),
+                            ),
+                    ),
+            )
+    }
+}
+
+impl PdfViewer {
+    pub(super) fn open_watermark_dialog(&mut self, cx: &mut Context<Self>) {
+        if self.command_panel_open {
+            self.close_command_panel(cx);
+        }
+
+        if self.watermark_dialog_open {
+            if let Some(handle) = self.watermark_dialog_window.as_ref() {
+                let _ = handle.update(cx, |_, window, _| {
+                    window.activate_window();
+                });
+            }
+            return;
+        }
+        let Some(input) = self.active_tab_path().cloned() else {
+            return;
+        };
+        let page_count = self.active_tab().map_or(0, |tab| tab.pages.len());
+
+        self.watermark_dialog_open = true;
+        self.needs_root_refocus = false;
+        self.watermark_dialog_session = self.watermark_dialog_session.wrapping_add(1);
+        let session_id = self.watermark_dialog_session;
+
+        let language = self.language;
+        let viewer = cx.entity();
+        let viewer_for_close = viewer.clone();
+        let window_options = WindowOptions {
+            titlebar: Some(Self::dialog_titlebar_options()),
+            window_bounds: Some(WindowBounds::centered(
+                size(
+                    px(WATERMARK_DIALOG_WIDTH),
+                    px(WATERMARK_DIALOG_WINDOW_HEIGHT),
+                ),
+                cx,
+            )),
+            window_decorations: Some(WindowDecorations::Client),
+            ..WindowOptions::default()
+        };
+
+        match cx.open_window(window_options, move |window, cx| {
+            window.on_window_should_close(cx, move |_, cx| {
+                let _ = viewer_for_close.update(cx, |this, cx| {
+                    this.on_watermark_dialog_window_closed(session_id, cx);
+                });
+                true
+            });
+            let dialog =
+                cx.new(|cx| WatermarkWindow::new(viewer, language, input, page_count, window, cx));
+            let dialog_focus = dialog.read(cx).focus_handle.clone();
+            let root = cx.new(|cx| Root::new(dialog, window, cx));
+            window.focus(&dialog_focus);
+            root
+        }) {
+            Ok(handle) => {
+                self.watermark_dialog_window = Some(handle.into());
+                cx.notify();
+            }
+            Err(err) => {
+                crate::debug_log!(@warn, "[watermark] failed to open watermark window: {}", err);
+                self.on_watermark_dialog_window_closed(session_id, cx);
+            }
+        }
+    }
+
+    pub(super) fn close_watermark_dialog(&mut self, cx: &mut Context<Self>) {
+        let window_handle = self.watermark_dialog_window.take();
+        if self.watermark_dialog_open || window_handle.is_some() {
+            self.watermark_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+        // Defer window removal to avoid borrow conflicts during event handling
+        if let Some(window_handle) = window_handle {
+            cx.defer(move |cx| {
+                let _ = window_handle.update(cx, |_, window, _| {
+                    window.remove_window();
+                });
+            });
+        }
+    }
+
+    fn on_watermark_dialog_window_closed(&mut self, session_id: u64, cx: &mut Context<Self>) {
+        if self.watermark_dialog_session == session_id {
+            self.watermark_dialog_window = None;
+            self.watermark_dialog_open = false;
+            self.needs_root_refocus = true;
+            cx.notify();
+        }
+    }
+}
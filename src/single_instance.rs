@@ -0,0 +1,188 @@
+//! Keeps one kPDF process per user. Later launches, such as Explorer's "Open with kPDF" on
+//! several files at once, hand their documents to the running instance instead of starting a
+//! second process that cannot open the locked state database.
+//!
+//! The running instance holds an exclusive lock on `instance.lock` in the data directory and
+//! listens on a loopback port, which it records with a random token in `instance.endpoint`.
+//! A later launch connects, sends the token and then one path per line, and waits for `ok`.
+
+use std::fs::{File, TryLockError};
+use std::hash::{BuildHasher, RandomState};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+const LOCK_FILE_NAME: &str = "instance.lock";
+const ENDPOINT_FILE_NAME: &str = "instance.endpoint";
+/// How long a later launch keeps trying while the running instance is still starting up.
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(5);
+const FORWARD_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+/// A connection that sends nothing for this long is dropped.
+const READ_TIMEOUT: Duration = Duration::from_secs(2);
+const ACK: &str = "ok";
+
+/// Held for the life of the running instance; the OS releases it when the process exits.
+static INSTANCE_LOCK: OnceLock<File> = OnceLock::new();
+
+/// Launches forwarded to this instance that the UI has not handled yet.
+#[derive(Clone, Default)]
+pub struct ForwardedLaunches(Arc<Mutex<Option<Vec<PathBuf>>>>);
+
+impl ForwardedLaunches {
+    /// Paths from every launch since the last call, or `None` when there was no launch. A
+    /// launch without files still yields an empty list, so the window can be raised.
+    pub fn take(&self) -> Option<Vec<PathBuf>> {
+        self.0.lock().ok()?.take()
+    }
+
+    fn push(&self, paths: Vec<PathBuf>) {
+        if let Ok(mut pending) = self.0.lock() {
+            pending.get_or_insert_with(Vec::new).extend(paths);
+        }
+    }
+}
+
+pub enum Instance {
+    /// This process is the running instance; later launches arrive through the inbox.
+    Primary(ForwardedLaunches),
+    /// The paths were handed to the running instance, so this process should exit.
+    Forwarded,
+}
+
+/// Becomes the running instance, or forwards `paths` to the one already running. When the
+/// running instance cannot be reached, this process carries on by itself.
+pub fn claim_or_forward(paths: &[PathBuf]) -> Instance {
+    let Some(dir) = kpdf_core::paths::data_dir() else {
+        return Instance::Primary(ForwardedLaunches::default());
+    };
+    match claim(&dir) {
+        Ok(Some(launches)) => Instance::Primary(launches),
+        Ok(None) => match forward(&dir, paths) {
+            Ok(()) => {
+                crate::debug_log!("[instance] forwarded {} paths", paths.len());
+                Instance::Forwarded
+            }
+            Err(err) => {
+                crate::debug_log!(@warn, "[instance] forwarding failed: {}", err);
+                Instance::Primary(ForwardedLaunches::default())
+            }
+        },
+        Err(err) => {
+            crate::debug_log!(@warn, "[instance] cannot listen for launches: {}", err);
+            Instance::Primary(ForwardedLaunches::default())
+        }
+    }
+}
+
+/// Takes the instance lock and starts listening, or returns `None` when another process
+/// already holds the lock.
+fn claim(dir: &Path) -> io::Result<Option<ForwardedLaunches>> {
+    std::fs::create_dir_all(dir)?;
+    let lock = File::options()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(LOCK_FILE_NAME))?;
+    match lock.try_lock() {
+        Ok(()) => {}
+        Err(TryLockError::WouldBlock) => return Ok(None),
+        Err(TryLockError::Error(err)) => return Err(err),
+    }
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let token = random_token();
+    // Renamed into place so a later launch never reads half an endpoint.
+    let endpoint = dir.join(ENDPOINT_FILE_NAME);
+    let partial = endpoint.with_extension("partial");
+    std::fs::write(
+        &partial,
+        format!("{} {}\n", listener.local_addr()?.port(), token),
+    )?;
+    std::fs::rename(&partial, &endpoint)?;
+    let _ = INSTANCE_LOCK.set(lock);
+
+    let launches = ForwardedLaunches::default();
+    let inbox = launches.clone();
+    std::thread::Builder::new()
+        .name("kpdf-instance".to_string())
+        .spawn(move || {
+            for stream in listener.incoming().flatten() {
+                match receive(stream, &token) {
+                    Ok(paths) => inbox.push(paths),
+                    Err(err) => crate::debug_log!("[instance] rejected a launch: {}", err),
+                }
+            }
+        })?;
+    Ok(Some(launches))
+}
+
+fn random_token() -> String {
+    let state = RandomState::new();
+    format!(
+        "{:016x}{:016x}",
+        state.hash_one(Instant::now()),
+        state.hash_one(std::process::id())
+    )
+}
+
+/// Reads one forwarded launch and acknowledges it.
+fn receive(mut stream: TcpStream, token: &str) -> io::Result<Vec<PathBuf>> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut lines = BufReader::new(stream.try_clone()?).lines();
+    if lines.next().transpose()?.as_deref() != Some(token) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "wrong token",
+        ));
+    }
+    let paths = lines
+        .collect::<io::Result<Vec<_>>>()?
+        .into_iter()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect();
+    writeln!(stream, "{ACK}")?;
+    Ok(paths)
+}
+
+/// Sends `paths` to the running instance, retrying while it is still starting up.
+fn forward(dir: &Path, paths: &[PathBuf]) -> io::Result<()> {
+    let deadline = Instant::now() + FORWARD_TIMEOUT;
+    loop {
+        match try_forward(dir, paths) {
+            Ok(()) => return Ok(()),
+            Err(err) if Instant::now() >= deadline => return Err(err),
+            Err(_) => std::thread::sleep(FORWARD_RETRY_INTERVAL),
+        }
+    }
+}
+
+fn try_forward(dir: &Path, paths: &[PathBuf]) -> io::Result<()> {
+    let endpoint = std::fs::read_to_string(dir.join(ENDPOINT_FILE_NAME))?;
+    let malformed = || io::Error::new(io::ErrorKind::InvalidData, "malformed endpoint file");
+    let (port, token) = endpoint.trim().split_once(' ').ok_or_else(malformed)?;
+    let port = port.parse::<u16>().map_err(|_| malformed())?;
+
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let mut stream = TcpStream::connect_timeout(&address, READ_TIMEOUT)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let mut message = format!("{token}\n");
+    for path in paths {
+        message.push_str(&path.to_string_lossy());
+        message.push('\n');
+    }
+    stream.write_all(message.as_bytes())?;
+    stream.shutdown(Shutdown::Write)?;
+
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    if reply.trim() != ACK {
+        return Err(io::Error::new(
+            io::ErrorKind::ConnectionRefused,
+            "no acknowledgement",
+        ));
+    }
+    Ok(())
+}
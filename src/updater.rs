@@ -1,19 +1,73 @@
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, anyhow, bail};
+use kpdf_core::fetch::{DownloadProgress, FetchOptions, fetch_resumable, sha256_file};
 use reqwest::blocking::Client;
 use semver::Version;
 use serde::Deserialize;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-const DEFAULT_LATEST_RELEASE_API_URL: &str =
-    "https://api.github.com/repos/KusStar/kpdf/releases/latest";
+const DEFAULT_RELEASES_API_URL: &str = "https://api.github.com/repos/KusStar/kpdf/releases";
+const RELEASES_PAGE_SIZE: u32 = 30;
 const HTTP_ACCEPT_HEADER: &str = "application/vnd.github+json";
 const HTTP_USER_AGENT: &str = concat!("kPDF-Updater/", env!("CARGO_PKG_VERSION"));
 const HTTP_TIMEOUT: Duration = Duration::from_secs(15);
 
+/// Names of release assets that list the SHA-256 of every other asset.
+const CHECKSUM_LIST_ASSET_NAMES: [&str; 4] = [
+    "sha256sums",
+    "sha256sums.txt",
+    "checksums.txt",
+    "checksums.sha256",
+];
+
+/// Which releases count as updates: stable ones only, or pre-releases as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    pub const ALL: [UpdateChannel; 2] = [UpdateChannel::Stable, UpdateChannel::Beta];
+
+    pub fn as_stored(self) -> &'static [u8] {
+        match self {
+            UpdateChannel::Stable => b"stable",
+            UpdateChannel::Beta => b"beta",
+        }
+    }
+
+    pub fn from_stored(raw: &[u8]) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|channel| channel.as_stored() == raw)
+    }
+}
+
+/// Network settings shared by the update check and update downloads.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UpdaterOptions {
+    /// Replaces the GitHub releases API with a compatible mirror; `/latest` is appended for
+    /// the stable channel.
+    pub endpoint: Option<String>,
+    pub proxy: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub struct UpdateInfo {
     pub latest_version: String,
     pub download_url: String,
+    /// Set when the release publishes a checksum for the platform installer, which is what
+    /// allows downloading it in-app instead of through the browser.
+    pub asset: Option<UpdateAsset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateAsset {
+    pub name: String,
+    pub url: String,
+    pub checksum_url: String,
 }
 
 #[derive(Debug, Clone)]
@@ -27,6 +81,8 @@ struct GithubRelease {
     tag_name: String,
     html_url: String,
     #[serde(default)]
+    draft: bool,
+    #[serde(default)]
     assets: Vec<GithubAsset>,
 }
 
@@ -36,8 +92,16 @@ struct GithubAsset {
     browser_download_url: String,
 }
 
-pub fn check_for_updates(current_version: &str) -> Result<UpdateCheck> {
-    let release = fetch_latest_release()?;
+pub fn check_for_updates(
+    current_version: &str,
+    channel: UpdateChannel,
+    options: &UpdaterOptions,
+) -> Result<UpdateCheck> {
+    let release = match channel {
+        UpdateChannel::Stable => fetch_latest_release(options)?,
+        UpdateChannel::Beta => newest_release(fetch_releases(options)?)
+            .ok_or_else(|| anyhow!("no published releases"))?,
+    };
     let latest_version = normalize_version_label(&release.tag_name);
     let latest_semver = parse_semver(&latest_version)?;
     let current_semver = parse_semver(&normalize_version_label(current_version))?;
@@ -46,29 +110,275 @@ pub fn check_for_updates(current_version: &str) -> Result<UpdateCheck> {
         return Ok(UpdateCheck::UpToDate { latest_version });
     }
 
-    let download_url = select_asset_for_current_platform(&release.assets)
+    let platform_asset = select_asset_for_current_platform(&release.assets);
+    let download_url = platform_asset
         .map(|asset| asset.browser_download_url.clone())
         .unwrap_or_else(|| release.html_url.clone());
+    let asset = platform_asset.and_then(|asset| {
+        let checksum = select_checksum_asset(&release.assets, &asset.name)?;
+        Some(UpdateAsset {
+            name: asset.name.clone(),
+            url: asset.browser_download_url.clone(),
+            checksum_url: checksum.browser_download_url.clone(),
+        })
+    });
 
     Ok(UpdateCheck::UpdateAvailable(UpdateInfo {
         latest_version,
         download_url,
+        asset,
     }))
 }
 
-fn fetch_latest_release() -> Result<GithubRelease> {
-    let client = Client::builder()
-        .timeout(HTTP_TIMEOUT)
+/// Downloads `asset` into `target_dir` and verifies it against the published SHA-256.
+///
+/// The file stays a `.part` until verified, so a retry resumes the transfer. Returns the
+/// path of the verified installer.
+pub fn download_update(
+    asset: &UpdateAsset,
+    options: &UpdaterOptions,
+    target_dir: &Path,
+    mut on_progress: impl FnMut(DownloadProgress),
+    is_cancelled: impl Fn() -> bool,
+) -> Result<PathBuf> {
+    // Asset names come from the network; never let one point outside `target_dir`.
+    let file_name = Path::new(&asset.name)
+        .file_name()
+        .ok_or_else(|| anyhow!("invalid asset name: {}", asset.name))?;
+    let expected = fetch_published_checksum(asset, options)?;
+
+    std::fs::create_dir_all(target_dir)
+        .with_context(|| format!("create {}", target_dir.display()))?;
+    let target = target_dir.join(file_name);
+    let part_path = target.with_file_name(format!("{}.part", file_name.to_string_lossy()));
+    let fetch_options = FetchOptions {
+        mirror: None,
+        proxy: options.proxy.clone(),
+    };
+    fetch_resumable(
+        &fetch_options,
+        &asset.url,
+        &part_path,
+        &mut on_progress,
+        &is_cancelled,
+    )?;
+
+    let actual = sha256_file(&part_path)?;
+    if !actual.eq_ignore_ascii_case(&expected) {
+        let _ = std::fs::remove_file(&part_path);
+        bail!(
+            "checksum mismatch for {}: expected {expected}, got {actual}",
+            asset.name
+        );
+    }
+
+    std::fs::rename(&part_path, &target)
+        .with_context(|| format!("{} -> {}", part_path.display(), target.display()))?;
+    Ok(target)
+}
+
+/// Where downloaded installers are saved: the user's Downloads folder when there is one.
+pub fn download_directory() -> PathBuf {
+    std::env::var_os("USERPROFILE")
+        .or_else(|| std::env::var_os("HOME"))
+        .map(|home| PathBuf::from(home).join("Downloads"))
+        .filter(|dir| dir.is_dir())
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+/// Whether `install_and_relaunch` knows how to apply `installer` on this platform.
+pub fn can_install(installer: &Path) -> bool {
+    install_target(installer).is_ok()
+}
+
+/// Applies a verified installer once this process exits and then starts kpdf again.
+///
+/// The work happens in a detached helper that waits for our pid, because the running
+/// binary and its store are locked until then. The caller is expected to quit right after
+/// this returns `Ok`.
+pub fn install_and_relaunch(installer: &Path) -> Result<()> {
+    let target = install_target(installer)?;
+    let pid = std::process::id().to_string();
+    let mut command = install_command(installer, &target, &pid);
+    command
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null());
+    command
+        .spawn()
+        .with_context(|| format!("failed to start installer for {}", installer.display()))?;
+    Ok(())
+}
+
+/// What gets replaced or relaunched: the `.app` bundle on macOS, the executable elsewhere
+/// (the AppImage file itself when running from one).
+fn install_target(installer: &Path) -> Result<PathBuf> {
+    let name = installer
+        .file_name()
+        .map(|name| name.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let current_exe = std::env::current_exe().context("failed to locate running executable")?;
+
+    #[cfg(target_os = "macos")]
+    {
+        if !(name.ends_with(".dmg") || name.ends_with(".app.zip")) {
+            bail!("unsupported installer: {name}");
+        }
+        return current_exe
+            .ancestors()
+            .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+            .map(Path::to_path_buf)
+            .ok_or_else(|| anyhow!("kpdf is not running from an app bundle"));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        if !name.ends_with(".exe") {
+            bail!("unsupported installer: {name}");
+        }
+        return Ok(current_exe);
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        if name.ends_with(".appimage") {
+            return std::env::var_os("APPIMAGE")
+                .map(PathBuf::from)
+                .ok_or_else(|| anyhow!("kpdf is not running from an AppImage"));
+        }
+        if name.ends_with(".deb") || name.ends_with(".rpm") {
+            return Ok(current_exe);
+        }
+        bail!("unsupported installer: {name}");
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (name, current_exe);
+        bail!("updates cannot be installed on this platform")
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn install_command(installer: &Path, target: &Path, pid: &str) -> std::process::Command {
+    use std::os::windows::process::CommandExt;
+    const DETACHED_PROCESS: u32 = 0x0000_0008;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    // The NSIS setup installs silently with `/S`; paths are passed through the
+    // environment so quoting never reaches the script.
+    let mut command = std::process::Command::new("powershell");
+    command
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Wait-Process -Id $env:KPDF_UPDATE_PID -ErrorAction SilentlyContinue; \
+             Start-Process -Wait -FilePath $env:KPDF_UPDATE_INSTALLER -ArgumentList '/S'; \
+             Start-Process -FilePath $env:KPDF_UPDATE_TARGET",
+        ])
+        .env("KPDF_UPDATE_PID", pid)
+        .env("KPDF_UPDATE_INSTALLER", installer)
+        .env("KPDF_UPDATE_TARGET", target)
+        .creation_flags(DETACHED_PROCESS | CREATE_NO_WINDOW);
+    command
+}
+
+#[cfg(unix)]
+fn install_command(installer: &Path, target: &Path, pid: &str) -> std::process::Command {
+    #[cfg(target_os = "macos")]
+    const SCRIPT: &str = r#"
+while kill -0 "$1" 2>/dev/null; do sleep 0.2; done
+work=$(mktemp -d) || exit 1
+case "$2" in
+  *.dmg|*.DMG)
+    hdiutil attach -nobrowse -readonly -mountpoint "$work/mnt" "$2" || exit 1
+    app=$(find "$work/mnt" -maxdepth 1 -name '*.app' | head -n 1)
+    rm -rf "$3.new" && ditto "$app" "$3.new"
+    hdiutil detach "$work/mnt" -quiet ;;
+  *)
+    ditto -x -k "$2" "$work/unzipped" || exit 1
+    app=$(find "$work/unzipped" -maxdepth 1 -name '*.app' | head -n 1)
+    rm -rf "$3.new" && ditto "$app" "$3.new" ;;
+esac
+if [ -d "$3.new" ]; then rm -rf "$3" && mv "$3.new" "$3"; fi
+rm -rf "$work"
+open "$3"
+"#;
+
+    #[cfg(not(target_os = "macos"))]
+    const SCRIPT: &str = r#"
+while kill -0 "$1" 2>/dev/null; do sleep 0.2; done
+case "$2" in
+  *.deb|*.DEB) pkexec dpkg -i "$2" ;;
+  *.rpm|*.RPM) pkexec rpm -U --replacepkgs "$2" ;;
+  *) cp -f "$2" "$3.new" && chmod +x "$3.new" && mv -f "$3.new" "$3" ;;
+esac
+exec "$3"
+"#;
+
+    // Arguments are passed positionally so paths never need shell quoting.
+    let mut command = std::process::Command::new("/bin/sh");
+    command
+        .arg("-c")
+        .arg(SCRIPT)
+        .arg("kpdf-update")
+        .arg(pid)
+        .arg(installer)
+        .arg(target);
+    command
+}
+
+fn fetch_published_checksum(asset: &UpdateAsset, options: &UpdaterOptions) -> Result<String> {
+    let body = http_client(options)?
+        .get(&asset.checksum_url)
+        .header(reqwest::header::USER_AGENT, HTTP_USER_AGENT)
+        .send()
+        .context("failed to request update checksum")?
+        .error_for_status()
+        .context("update checksum request failed")?
+        .text()
+        .context("failed to read update checksum")?;
+
+    published_checksum_in(&body, &asset.name)
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("no published checksum for {}", asset.name))
+}
+
+fn http_client(options: &UpdaterOptions) -> Result<Client> {
+    let mut client = Client::builder().timeout(HTTP_TIMEOUT);
+    if let Some(proxy) = options.proxy.as_deref() {
+        client = client.proxy(reqwest::Proxy::all(proxy).context("invalid proxy")?);
+    }
+    client
         .build()
-        .context("failed to create update http client")?;
+        .context("failed to create update http client")
+}
 
-    let endpoint = std::env::var("KPDF_UPDATER_LATEST_URL")
+/// An endpoint set through a `KPDF_UPDATER_*` variable, which wins over the settings.
+fn endpoint_from_env(name: &str) -> Option<String> {
+    std::env::var(name)
         .ok()
         .map(|v| v.trim().to_string())
         .filter(|v| !v.is_empty())
-        .unwrap_or_else(|| DEFAULT_LATEST_RELEASE_API_URL.to_string());
+}
 
-    client
+fn releases_base_url(options: &UpdaterOptions) -> String {
+    options
+        .endpoint
+        .as_deref()
+        .map(str::trim)
+        .filter(|endpoint| !endpoint.is_empty())
+        .unwrap_or(DEFAULT_RELEASES_API_URL)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn fetch_latest_release(options: &UpdaterOptions) -> Result<GithubRelease> {
+    let endpoint = endpoint_from_env("KPDF_UPDATER_LATEST_URL")
+        .unwrap_or_else(|| format!("{}/latest", releases_base_url(options)));
+
+    http_client(options)?
         .get(&endpoint)
         .header(reqwest::header::ACCEPT, HTTP_ACCEPT_HEADER)
         .header(reqwest::header::USER_AGENT, HTTP_USER_AGENT)
@@ -80,6 +390,40 @@ fn fetch_latest_release() -> Result<GithubRelease> {
         .context("failed to parse latest release response")
 }
 
+/// Recent releases including pre-releases, which `releases/latest` leaves out.
+fn fetch_releases(options: &UpdaterOptions) -> Result<Vec<GithubRelease>> {
+    let endpoint = endpoint_from_env("KPDF_UPDATER_RELEASES_URL").unwrap_or_else(|| {
+        format!(
+            "{}?per_page={RELEASES_PAGE_SIZE}",
+            releases_base_url(options)
+        )
+    });
+
+    http_client(options)?
+        .get(&endpoint)
+        .header(reqwest::header::ACCEPT, HTTP_ACCEPT_HEADER)
+        .header(reqwest::header::USER_AGENT, HTTP_USER_AGENT)
+        .send()
+        .context("failed to request releases")?
+        .error_for_status()
+        .context("releases request failed")?
+        .json::<Vec<GithubRelease>>()
+        .context("failed to parse releases response")
+}
+
+/// The highest published version; drafts and unparsable tags are skipped.
+fn newest_release(releases: Vec<GithubRelease>) -> Option<GithubRelease> {
+    releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .filter_map(|release| {
+            let version = parse_semver(&normalize_version_label(&release.tag_name)).ok()?;
+            Some((version, release))
+        })
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, release)| release)
+}
+
 fn parse_semver(raw: &str) -> Result<Version> {
     Version::parse(raw).with_context(|| format!("invalid version: {raw}"))
 }
@@ -92,6 +436,36 @@ fn normalize_version_label(raw: &str) -> String {
         .to_string()
 }
 
+/// A `<asset>.sha256` file next to the installer, or a shared checksum list.
+fn select_checksum_asset<'a>(
+    assets: &'a [GithubAsset],
+    asset_name: &str,
+) -> Option<&'a GithubAsset> {
+    let sidecar = format!("{asset_name}.sha256").to_ascii_lowercase();
+    assets
+        .iter()
+        .find(|asset| asset.name.to_ascii_lowercase() == sidecar)
+        .or_else(|| {
+            assets.iter().find(|asset| {
+                CHECKSUM_LIST_ASSET_NAMES.contains(&asset.name.to_ascii_lowercase().as_str())
+            })
+        })
+}
+
+/// Finds the checksum of `asset_name` in `sha256sum` output; a lone hash (as in a sidecar
+/// file) matches any name.
+fn published_checksum_in<'a>(checksums: &'a str, asset_name: &str) -> Option<&'a str> {
+    checksums.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let checksum = parts.next()?;
+        let name_matches = parts
+            .next()
+            .is_none_or(|name| name.trim_start_matches('*') == asset_name);
+        (name_matches && checksum.len() == 64 && checksum.chars().all(|ch| ch.is_ascii_hexdigit()))
+            .then_some(checksum)
+    })
+}
+
 fn select_asset_for_current_platform(assets: &[GithubAsset]) -> Option<&GithubAsset> {
     #[cfg(target_os = "macos")]
     {
@@ -153,10 +527,7 @@ fn is_windows_installer(name: &str) -> bool {
 #[cfg(any(test, all(unix, not(target_os = "macos"))))]
 fn is_linux_installer(name: &str) -> bool {
     let name = name.to_ascii_lowercase();
-    name.ends_with(".appimage")
-        || name.ends_with(".deb")
-        || name.ends_with(".rpm")
-        || name.ends_with(".tar.gz")
+    name.ends_with(".appimage") || name.ends_with(".deb") || name.ends_with(".rpm")
 }
 
 #[cfg(test)]
@@ -191,5 +562,92 @@ mod tests {
         assert!(select_macos_asset(&assets).is_some());
         assert!(select_windows_asset(&assets).is_some());
         assert!(select_linux_asset(&assets).is_some());
+        // Tarballs have no in-app install path, so they must not be offered.
+        assert!(!is_linux_installer("kpdf_0.3.1_x86_64.tar.gz"));
+    }
+
+    #[test]
+    fn releases_base_url_uses_endpoint_when_set() {
+        assert_eq!(
+            releases_base_url(&UpdaterOptions::default()),
+            "https://api.github.com/repos/KusStar/kpdf/releases"
+        );
+        let options = UpdaterOptions {
+            endpoint: Some(" https://mirror.example.com/kpdf/releases/ ".into()),
+            proxy: None,
+        };
+        assert_eq!(
+            releases_base_url(&options),
+            "https://mirror.example.com/kpdf/releases"
+        );
+    }
+
+    #[test]
+    fn newest_release_includes_prereleases() {
+        let release = |tag: &str, draft: bool| GithubRelease {
+            tag_name: tag.into(),
+            html_url: format!("https://example.com/{tag}"),
+            draft,
+            assets: Vec::new(),
+        };
+        let releases = vec![
+            release("v0.3.1", false),
+            release("v0.4.0-beta.2", false),
+            release("v0.4.0", true),
+            release("nightly", false),
+            release("v0.4.0-beta.1", false),
+        ];
+
+        assert_eq!(
+            newest_release(releases).map(|release| release.tag_name),
+            Some("v0.4.0-beta.2".to_string())
+        );
+        assert!(newest_release(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn select_checksum_sidecar_before_list() {
+        let assets = vec![
+            GithubAsset {
+                name: "SHA256SUMS".into(),
+                browser_download_url: "https://example.com/SHA256SUMS".into(),
+            },
+            GithubAsset {
+                name: "kpdf_0.3.1_amd64.deb.sha256".into(),
+                browser_download_url: "https://example.com/linux.deb.sha256".into(),
+            },
+        ];
+
+        assert_eq!(
+            select_checksum_asset(&assets, "kpdf_0.3.1_amd64.deb").map(|asset| asset.name.as_str()),
+            Some("kpdf_0.3.1_amd64.deb.sha256")
+        );
+        assert_eq!(
+            select_checksum_asset(&assets, "windows-kpdf_0.3.1_x64-setup.exe")
+                .map(|asset| asset.name.as_str()),
+            Some("SHA256SUMS")
+        );
+        assert!(select_checksum_asset(&assets[..0], "kpdf_0.3.1_amd64.deb").is_none());
+    }
+
+    #[test]
+    fn parse_published_checksums() {
+        let hash = "3f0a9e1b6c2d4e5f708192a3b4c5d6e7f8091a2b3c4d5e6f708192a3b4c5d6e7";
+        let list = format!("{hash}  kpdf_0.3.1_amd64.deb\n{hash} *kpdf.dmg\n");
+
+        assert_eq!(
+            published_checksum_in(&list, "kpdf_0.3.1_amd64.deb"),
+            Some(hash)
+        );
+        assert_eq!(published_checksum_in(&list, "kpdf.dmg"), Some(hash));
+        assert_eq!(published_checksum_in(&list, "kpdf.exe"), None);
+        assert_eq!(
+            published_checksum_in(&format!("{hash}\n"), "kpdf.exe"),
+            Some(hash)
+        );
+        assert_eq!(
+            published_checksum_in("not-a-hash  kpdf.exe", "kpdf.exe"),
+            None
+        );
     }
 }